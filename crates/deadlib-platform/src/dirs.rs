@@ -38,6 +38,10 @@ impl AppDirs {
         self.data_dir.join("save").join("current_screen.txt")
     }
 
+    pub fn duplicate_songs_path(&self) -> PathBuf {
+        self.data_dir.join("save").join("duplicate_songs.txt")
+    }
+
     pub fn default_player_options_path(&self) -> PathBuf {
         self.data_dir
             .join("save")
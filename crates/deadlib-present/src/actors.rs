@@ -1,6 +1,6 @@
 use crate::anim;
 use deadlib_render_core::{
-    BlendMode, MeshVertex, TMeshCacheKey, TextureHandle, TexturedMeshVertex,
+    BlendMode, MeshVertex, ShaderEffect, TMeshCacheKey, TextureHandle, TexturedMeshVertex,
 };
 use glam::Mat4 as Matrix4;
 use std::cell::{Cell, Ref, RefCell};
@@ -493,6 +493,27 @@ pub enum Actor {
     /// End the most recent flat camera scope.
     CameraPop,
 
+    /// Offscreen pass: renders `children` into render-target `slot` at `size`
+    /// pixels ahead of the screen. Draws nothing in place; sprites sample the
+    /// result through `render_target_handle(slot)`.
+    RenderTarget {
+        slot: u32,
+        size: [u32; 2],
+        clear: bool,
+        children: Arc<[Self]>,
+    },
+
+    /// Composites render-target `slot` over the whole pass through a built-in
+    /// fragment effect. Pair it with an `Actor::RenderTarget` filling the slot;
+    /// backends without shader effects draw nothing.
+    ShaderEffect {
+        slot: u32,
+        effect: ShaderEffect,
+        tint: [f32; 4],
+        blend: BlendMode,
+        z: i16,
+    },
+
     /// Shadow wrapper: draws child's objects once more with an offset and tint,
     /// matching `StepMania`'s `shadowlength*` and `shadowcolor` behavior.
     Shadow {
@@ -746,6 +767,7 @@ impl Actor {
             | Self::RetainedFrame { .. }
             | Self::CameraPush { .. }
             | Self::CameraPop => true,
+            Self::RenderTarget { .. } | Self::ShaderEffect { .. } => false,
         }
     }
 
@@ -796,13 +818,15 @@ impl Actor {
                 }
                 tint[3] *= alpha;
             }
-            Self::RetainedFrame { tint, .. } => tint[3] *= alpha,
+            Self::RetainedFrame { tint, .. } | Self::ShaderEffect { tint, .. } => {
+                tint[3] *= alpha;
+            }
             Self::Camera { children, .. } => {
                 for child in children {
                     child.mul_alpha(alpha);
                 }
             }
-            Self::CameraPush { .. } | Self::CameraPop => {}
+            Self::CameraPush { .. } | Self::CameraPop | Self::RenderTarget { .. } => {}
             Self::Shadow { color, child, .. } => {
                 color[3] *= alpha;
                 child.mul_alpha(alpha);
@@ -880,6 +904,15 @@ pub fn actor_tree_stats(actors: &[Actor]) -> ActorTreeStats {
                 stats.cameras = stats.cameras.saturating_add(1);
             }
             Actor::CameraPop => {}
+            Actor::ShaderEffect { .. } => {
+                stats.textured_meshes = stats.textured_meshes.saturating_add(1);
+            }
+            Actor::RenderTarget { children, .. } => {
+                stats.frames = stats.frames.saturating_add(1);
+                for child in children.iter() {
+                    visit(stats, child);
+                }
+            }
             Actor::Shadow { child, .. } => {
                 stats.shadows = stats.shadows.saturating_add(1);
                 visit(stats, child);
@@ -83,6 +83,7 @@ enum DrawKind {
     Sprite,
     Mesh,
     TexturedMesh,
+    Effect,
 }
 
 #[derive(Clone)]
@@ -100,11 +101,20 @@ struct TexturedMeshPayload {
     depth_test: bool,
 }
 
+/// Full-pass composite of a render target; the item's texture handle names
+/// the target.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct EffectPayload {
+    effect: renderer::ShaderEffect,
+    tint: [f32; 4],
+}
+
 #[derive(Default)]
 struct FrameBuilder {
     items: Vec<DrawItem>,
     meshes: Vec<Option<MeshPayload>>,
     textured_meshes: Vec<Option<TexturedMeshPayload>>,
+    effects: Vec<EffectPayload>,
 }
 
 impl FrameBuilder {
@@ -117,6 +127,7 @@ impl FrameBuilder {
         self.items.clear();
         self.meshes.clear();
         self.textured_meshes.clear();
+        self.effects.clear();
     }
 
     fn reserve(&mut self, additional: usize) {
@@ -126,12 +137,14 @@ impl FrameBuilder {
     fn append_retained(&mut self, cached: &Self, sprite_start: u32, order_counter: &mut u32) {
         let mesh_start = saturating_u32(self.meshes.len());
         let textured_mesh_start = saturating_u32(self.textured_meshes.len());
+        let effect_start = saturating_u32(self.effects.len());
         self.items.reserve(cached.items.len());
         self.meshes.reserve(cached.meshes.len());
         self.textured_meshes.reserve(cached.textured_meshes.len());
         self.meshes.extend(cached.meshes.iter().cloned());
         self.textured_meshes
             .extend(cached.textured_meshes.iter().cloned());
+        self.effects.extend_from_slice(&cached.effects);
 
         self.items.extend(cached.items.iter().map(|item| {
             let mut item = *item;
@@ -139,6 +152,7 @@ impl FrameBuilder {
                 DrawKind::Sprite => item.payload_index.saturating_add(sprite_start),
                 DrawKind::Mesh => item.payload_index.saturating_add(mesh_start),
                 DrawKind::TexturedMesh => item.payload_index.saturating_add(textured_mesh_start),
+                DrawKind::Effect => item.payload_index.saturating_add(effect_start),
             };
             item.order = *order_counter;
             *order_counter = order_counter.saturating_add(1);
@@ -221,6 +235,29 @@ impl FrameBuilder {
         });
     }
 
+    #[inline(always)]
+    fn push_effect(
+        &mut self,
+        texture_handle: renderer::TextureHandle,
+        order: u32,
+        z: i16,
+        blend: BlendMode,
+        camera: u8,
+        payload: EffectPayload,
+    ) {
+        let payload_index = saturating_u32(self.effects.len());
+        self.effects.push(payload);
+        self.items.push(DrawItem {
+            texture_handle,
+            order,
+            payload_index,
+            z,
+            blend,
+            camera,
+            kind: DrawKind::Effect,
+        });
+    }
+
     #[inline(always)]
     fn push(&mut self, object: EditableDraw) {
         let EditableDraw {
@@ -269,6 +306,9 @@ impl FrameBuilder {
                     depth_test,
                 },
             ),
+            EditablePayload::Effect(payload) => {
+                self.push_effect(texture_handle, order, z, blend, camera, payload)
+            }
         }
     }
 
@@ -336,6 +376,9 @@ impl FrameBuilder {
                     depth_test: payload.depth_test,
                 }
             }
+            DrawKind::Effect => {
+                EditablePayload::Effect(*self.effects.get(item.payload_index as usize)?)
+            }
         };
         Some(EditableDraw {
             texture_handle: item.texture_handle,
@@ -371,6 +414,7 @@ impl FrameBuilder {
                     depth_test: payload.depth_test,
                 }
             }
+            DrawKind::Effect => EditablePayload::Effect(self.effects[item.payload_index as usize]),
         }
     }
 }
@@ -389,6 +433,7 @@ enum EditablePayload {
         geom_cache_key: renderer::TMeshCacheKey,
         depth_test: bool,
     },
+    Effect(EffectPayload),
 }
 
 #[derive(Clone)]
@@ -1283,6 +1328,25 @@ where
             scratch.frame_stats.sprite_runs_after = gather.runs_after;
         }
     }
+    if !scratch.render_targets.is_empty() {
+        compose_render_targets(
+            m,
+            fonts,
+            scratch,
+            &mut builder,
+            &mut cameras,
+            &mut sprite_instances,
+            &mut mesh_vertices,
+            &mut tmesh_instances,
+            &mut tmesh_geometries,
+            &mut ops,
+            &mut tmesh_geom_map,
+            text_cache,
+            texture_ctx,
+            actor_textures.as_deref(),
+            total_elapsed,
+        );
+    }
     scratch.frame_builder = builder;
     scratch.tmesh_geom_map = tmesh_geom_map;
 
@@ -1297,6 +1361,117 @@ where
     }
 }
 
+/// Composes the offscreen passes queued by `Actor::RenderTarget` and prepends
+/// them to `ops`. Each slot renders once per frame (the first actor wins)
+/// through an uncentered full-screen camera. Targets nested inside a target
+/// are composed in a later round whose passes run first, so every pass sees
+/// the targets it samples already drawn.
+#[allow(clippy::too_many_arguments)]
+fn compose_render_targets<T: TextureContext + ?Sized>(
+    m: &Metrics,
+    fonts: &font::FontMap,
+    scratch: &mut ComposeScratch,
+    builder: &mut FrameBuilder,
+    cameras: &mut Vec<Matrix4>,
+    sprite_instances: &mut Vec<renderer::SpriteInstanceRaw>,
+    mesh_vertices: &mut Vec<renderer::MeshVertex>,
+    tmesh_instances: &mut Vec<renderer::TexturedMeshInstanceRaw>,
+    tmesh_geometries: &mut Vec<renderer::TexturedMeshGeometry>,
+    ops: &mut Vec<renderer::DrawOp>,
+    geom_map: &mut HashMap<TMeshGeomKey, u32, rustc_hash::FxBuildHasher>,
+    text_cache: &mut TextLayoutCache,
+    texture_ctx: &T,
+    actor_textures: Option<&[Arc<str>]>,
+    total_elapsed: f32,
+) {
+    let mut pending = std::mem::take(&mut scratch.render_targets);
+    let Ok(camera) = u8::try_from(cameras.len()) else {
+        pending.clear();
+        scratch.render_targets = pending;
+        return;
+    };
+    cameras.push(glam::camera::rh::proj::opengl::orthographic(
+        m.left, m.right, m.bottom, m.top, -1.0, 1.0,
+    ));
+    let root_rect = SmRect {
+        x: 0.0,
+        y: 0.0,
+        w: m.right - m.left,
+        h: m.top - m.bottom,
+    };
+    let style = ComposeStyle {
+        tint: [1.0; 4],
+        blend: None,
+    };
+    let mut target_ops = std::mem::take(&mut scratch.render_target_ops);
+    let mut masks = std::mem::take(&mut scratch.masks);
+    let mut texture_cache = std::mem::take(&mut scratch.texture_cache);
+    target_ops.clear();
+    let mut rendered: SmallVec<[u32; 8]> = SmallVec::new();
+    while !pending.is_empty() {
+        let round_start = target_ops.len();
+        for target in &pending {
+            if rendered.contains(&target.pass.slot) {
+                continue;
+            }
+            rendered.push(target.pass.slot);
+            masks.clear();
+            let mut order_counter = 0u32;
+            build_actor_list(
+                &target.children,
+                root_rect,
+                m,
+                fonts,
+                scratch,
+                0,
+                camera,
+                style,
+                None,
+                cameras,
+                &mut masks,
+                &mut order_counter,
+                builder,
+                sprite_instances,
+                text_cache,
+                &mut texture_cache,
+                texture_ctx,
+                actor_textures,
+                total_elapsed,
+            );
+            if !builder
+                .items
+                .windows(2)
+                .all(|pair| pair[0].sort_key() <= pair[1].sort_key())
+            {
+                sort_composed_draw_items(&mut builder.items, scratch);
+            }
+            target_ops.push(renderer::DrawOp::BeginTarget(target.pass));
+            finish_frame::<false>(
+                builder,
+                mesh_vertices,
+                tmesh_instances,
+                tmesh_geometries,
+                &mut target_ops,
+                geom_map,
+            );
+            target_ops.push(renderer::DrawOp::EndTarget);
+        }
+        // Targets queued while composing this round feed it, so their
+        // passes move in front of it.
+        let round_len = target_ops.len() - round_start;
+        target_ops.rotate_right(round_len);
+        pending.clear();
+        std::mem::swap(&mut pending, &mut scratch.render_targets);
+    }
+    scratch.masks = masks;
+    scratch.texture_cache = texture_cache;
+    scratch.render_targets = pending;
+    target_ops.extend_from_slice(ops);
+    std::mem::swap(ops, &mut target_ops);
+    target_ops.clear();
+    scratch.render_target_ops = target_ops;
+}
+
 #[derive(Default)]
 pub struct ComposeScratch {
     frame_builder: FrameBuilder,
@@ -1317,10 +1492,18 @@ pub struct ComposeScratch {
     transient_text_mesh_builders: Vec<TextMeshBatchBuilder>,
     recycled_text_mesh_vertices: Vec<Vec<renderer::TexturedMeshVertex>>,
     retained_frames: RetainedFrameCache,
+    render_targets: Vec<PendingRenderTarget>,
+    render_target_ops: Vec<renderer::DrawOp>,
     collect_frame_stats: bool,
     frame_stats: ComposeFrameStats,
 }
 
+/// An `Actor::RenderTarget` met while composing, drawn after the screen pass.
+struct PendingRenderTarget {
+    pass: renderer::RenderTargetPass,
+    children: Arc<[actors::Actor]>,
+}
+
 pub const COMPOSE_STORAGE_SLOTS: usize = 23;
 pub const COMPOSE_STORAGE_NAMES: [&str; COMPOSE_STORAGE_SLOTS] = [
     "draw_items",
@@ -1816,6 +1999,19 @@ fn finish_frame<const TRACK_SPRITE_RUNS: bool>(
                 }
                 cursor += object_count;
             }
+            DrawKind::Effect => {
+                let EffectPayload { effect, tint } = builder.effects[item.payload_index as usize];
+                ops.push(renderer::DrawOp::Effect(renderer::EffectRun {
+                    texture_handle,
+                    effect,
+                    tint,
+                    blend,
+                }));
+                if TRACK_SPRITE_RUNS {
+                    previous_sprite = None;
+                }
+                cursor += 1;
+            }
         }
     }
     builder.clear();
@@ -5166,7 +5362,10 @@ fn sprite_source_handle(
             handle,
             generation: handle_generation,
             ..
-        } if *handle != renderer::INVALID_TEXTURE_HANDLE && *handle_generation == generation => {
+        } if *handle != renderer::INVALID_TEXTURE_HANDLE
+            && (*handle_generation == generation
+                || renderer::render_target_slot(*handle).is_some()) =>
+        {
             Some(*handle)
         }
         _ => None,
@@ -5268,6 +5467,8 @@ fn push_shadow_objects_for_range(
                     },
                 );
             }
+            // A full-pass composite has no outline to cast a shadow from.
+            DrawKind::Effect => {}
         }
     }
 }
@@ -6766,6 +6967,51 @@ fn build_actor_recursive<'a, T: TextureContext + ?Sized>(
 
         actors::Actor::CameraPush { .. } | actors::Actor::CameraPop => {}
 
+        actors::Actor::RenderTarget {
+            slot,
+            size,
+            clear,
+            children,
+        } => {
+            if size[0] > 0 && size[1] > 0 {
+                scratch.render_targets.push(PendingRenderTarget {
+                    pass: renderer::RenderTargetPass {
+                        slot: *slot,
+                        width: size[0],
+                        height: size[1],
+                        clear: *clear,
+                    },
+                    children: Arc::clone(children),
+                });
+            }
+        }
+
+        actors::Actor::ShaderEffect {
+            slot,
+            effect,
+            tint,
+            blend,
+            z,
+        } => {
+            let tint = mul_rgba(style.tint, *tint);
+            if tint[3] <= 0.0 {
+                return;
+            }
+            let order = *order_counter;
+            *order_counter = order.saturating_add(1);
+            out.push_effect(
+                renderer::render_target_handle(*slot),
+                order,
+                base_z.saturating_add(*z),
+                style.blend.unwrap_or(*blend),
+                camera,
+                EffectPayload {
+                    effect: *effect,
+                    tint,
+                },
+            );
+        }
+
         actors::Actor::Text {
             align,
             offset,
@@ -8131,7 +8377,7 @@ fn object_world_area(
             }
             area
         }
-        EditablePayload::Mesh { .. } => 0.0,
+        EditablePayload::Mesh { .. } | EditablePayload::Effect(_) => 0.0,
     }
 }
 
@@ -8283,7 +8529,7 @@ fn clip_sprite_object_to_world_rect_with_recycled(
     }
     let mut textured_mesh_bounds = None;
     match &obj.object_type {
-        EditablePayload::Mesh { .. } => return true,
+        EditablePayload::Mesh { .. } | EditablePayload::Effect(_) => return true,
         EditablePayload::TexturedMesh {
             instance, vertices, ..
         } => {
@@ -8525,7 +8771,7 @@ fn clipped_sprite_object_to_world_rect_impl<const ACCEPT_CONTAINED_SPRITE: bool>
                 recycled_vertices,
             )
         }
-        EditablePayload::Mesh { .. } => Some(ClippedSpriteObject {
+        EditablePayload::Mesh { .. } | EditablePayload::Effect(_) => Some(ClippedSpriteObject {
             object_type: obj.object_type.clone(),
             sprite: None,
         }),
@@ -9474,6 +9720,58 @@ mod tests {
         assert_eq!(composed[0].color, [0.4, 0.15, 0.4, 0.375]);
     }
 
+    #[test]
+    fn nested_render_targets_render_before_the_targets_sampling_them() {
+        let mesh = |z| Actor::ReusableMesh {
+            align: [0.0, 0.0],
+            offset: [0.0, 0.0],
+            size: [SizeSpec::Px(1.0), SizeSpec::Px(1.0)],
+            tint: [1.0; 4],
+            vertices: Arc::new(vec![MeshVertex {
+                pos: [1.0, 1.0],
+                color: [1.0; 4],
+            }]),
+            visible: true,
+            blend: BlendMode::Alpha,
+            z,
+        };
+        let target = |slot, children: Vec<Actor>| Actor::RenderTarget {
+            slot,
+            size: [64, 32],
+            clear: true,
+            children: children.into(),
+        };
+        let actors = [
+            target(0, vec![mesh(0), target(1, vec![mesh(1)])]),
+            target(1, vec![mesh(2)]),
+            mesh(3),
+        ];
+        let metrics = Metrics {
+            left: 0.0,
+            right: 100.0,
+            top: 100.0,
+            bottom: 0.0,
+        };
+
+        let render = build_screen(&actors, [0.0; 4], &metrics, &font::FontMap::default(), 0.0);
+        let passes: Vec<u32> = render
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                DrawOp::BeginTarget(pass) => Some(pass.slot),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(passes, [1, 0], "each slot renders once, inner first");
+        let ends = render
+            .ops
+            .iter()
+            .filter(|op| matches!(op, DrawOp::EndTarget))
+            .count();
+        assert_eq!(ends, 2);
+        assert!(matches!(render.ops.last(), Some(DrawOp::Mesh(_))));
+    }
+
     #[test]
     fn transient_textured_mesh_buffers_do_not_coalesce() {
         let instance = TexturedMeshInstanceRaw::new(
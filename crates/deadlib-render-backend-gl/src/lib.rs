@@ -1,8 +1,9 @@
 use deadlib_render_core::{
-    BlendMode, CameraUploadCache, DrawOp, DrawStats, FastU64Map, RenderFrame, SamplerDesc,
-    SamplerFilter, SamplerWrap, SpriteInstanceRaw, TMeshCacheKey, TextureHandle,
-    TexturedMeshBufferCache, TexturedMeshInstanceRaw, TexturedMeshUploads, TexturedMeshVertex,
-    draw_storage_stats, resolve_textured_meshes,
+    BlendMode, CameraUploadCache, DrawOp, DrawStats, EffectUniforms, FastU64Map, RenderFrame,
+    RenderTargetPass, SamplerDesc, SamplerFilter, SamplerWrap, ShaderProgramId, SpriteInstanceRaw,
+    TMeshCacheKey, TextureHandle, TexturedMeshBufferCache, TexturedMeshInstanceRaw,
+    TexturedMeshUploads, TexturedMeshVertex, draw_storage_stats, render_target_slot,
+    resolve_textured_meshes,
 };
 use glam::Mat4 as Matrix4;
 use glow::{HasContext, PixelPackData, PixelUnpackData, UniformLocation};
@@ -16,7 +17,7 @@ use glutin::{
 use image::RgbaImage;
 use log::{debug, info, warn};
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle, RawWindowHandle};
-use std::{error::Error, ffi::CStr, mem, num::NonZeroU32, sync::Arc, time::Instant};
+use std::{borrow::Cow, error::Error, ffi::CStr, mem, num::NonZeroU32, sync::Arc, time::Instant};
 use winit::window::Window;
 
 #[cfg(all(unix, not(target_os = "macos")))]
@@ -125,6 +126,7 @@ impl GlApi {
                 mesh_frag: include_str!("shaders/opengl_mesh_legacy.frag"),
                 tmesh_vert: include_str!("shaders/opengl_tmesh_legacy.vert"),
                 tmesh_frag: include_str!("shaders/opengl_tmesh_legacy.frag"),
+                effect: None,
             },
             (Self::Desktop, GlPath::Modern) => ShaderSet {
                 sprite_vert: include_str!("shaders/opengl_shader.vert"),
@@ -133,6 +135,11 @@ impl GlApi {
                 mesh_frag: include_str!("shaders/opengl_mesh.frag"),
                 tmesh_vert: include_str!("shaders/opengl_tmesh.vert"),
                 tmesh_frag: include_str!("shaders/opengl_tmesh.frag"),
+                effect: Some(EffectShaders {
+                    vert: include_str!("shaders/opengl_effect.vert"),
+                    frag: include_str!("shaders/opengl_effect.frag"),
+                    custom_prelude: include_str!("shaders/opengl_effect_custom.frag"),
+                }),
             },
             #[cfg(all(unix, not(target_os = "macos")))]
             (Self::Gles, GlPath::Modern) => ShaderSet {
@@ -142,6 +149,11 @@ impl GlApi {
                 mesh_frag: include_str!("shaders/opengl_mesh_gles.frag"),
                 tmesh_vert: include_str!("shaders/opengl_tmesh_gles.vert"),
                 tmesh_frag: include_str!("shaders/opengl_tmesh_gles.frag"),
+                effect: Some(EffectShaders {
+                    vert: include_str!("shaders/opengl_effect_gles.vert"),
+                    frag: include_str!("shaders/opengl_effect_gles.frag"),
+                    custom_prelude: include_str!("shaders/opengl_effect_custom_gles.frag"),
+                }),
             },
         }
     }
@@ -155,6 +167,17 @@ struct ShaderSet {
     mesh_frag: &'static str,
     tmesh_vert: &'static str,
     tmesh_frag: &'static str,
+    // Effects sample render targets, which only the modern path provides.
+    effect: Option<EffectShaders>,
+}
+
+#[derive(Clone, Copy)]
+struct EffectShaders {
+    vert: &'static str,
+    /// The built-in programs.
+    frag: &'static str,
+    /// Declarations a user program's GLSL is appended to.
+    custom_prelude: &'static str,
 }
 
 const SPRITE_ATTRIBS: [(u32, &str); 12] = [
@@ -174,6 +197,8 @@ const SPRITE_ATTRIBS: [(u32, &str); 12] = [
 
 const MESH_ATTRIBS: [(u32, &str); 2] = [(0, "a_pos"), (1, "a_color")];
 
+const EFFECT_ATTRIBS: [(u32, &str); 2] = [(0, "a_pos"), (1, "a_tex_coord")];
+
 const TMESH_ATTRIBS: [(u32, &str); 13] = [
     (0, "a_pos"),
     (1, "a_uv"),
@@ -203,6 +228,13 @@ struct CachedTMeshGeom {
     vertex_count: u32,
 }
 
+struct GlRenderTarget {
+    framebuffer: glow::Framebuffer,
+    texture: glow::Texture,
+    depth: glow::Renderbuffer,
+    size: (u32, u32),
+}
+
 #[derive(Clone, Copy)]
 struct LegacySpriteUniforms {
     center: UniformLocation,
@@ -217,6 +249,19 @@ struct LegacySpriteUniforms {
     texture_mask: UniformLocation,
 }
 
+/// Uniforms a user program never reads are optimized out, so only the ones
+/// the wrapper itself uses are required.
+#[derive(Clone, Copy)]
+struct EffectProgram {
+    program: glow::Program,
+    texture: Option<UniformLocation>,
+    tint: UniformLocation,
+    params: Option<UniformLocation>,
+    texel_size: Option<UniformLocation>,
+    program_index: Option<UniformLocation>,
+    flip_y: UniformLocation,
+}
+
 #[derive(Clone, Copy)]
 struct LegacyTMeshUniforms {
     model: UniformLocation,
@@ -236,6 +281,11 @@ pub struct State {
     program: glow::Program,
     mesh_program: glow::Program,
     tmesh_program: glow::Program,
+    effect_shaders: Option<EffectShaders>,
+    effect_program: Option<EffectProgram>,
+    /// User programs by id; `None` for programs without GLSL or that failed
+    /// to compile, which draw through [`deadlib_render_core::ShaderEffect::PASSTHROUGH`].
+    custom_effects: FastU64Map<Option<EffectProgram>>,
     mvp_location: UniformLocation,
     mesh_mvp_location: UniformLocation,
     tmesh_mvp_location: UniformLocation,
@@ -243,6 +293,7 @@ pub struct State {
     texture_location: UniformLocation,
     legacy_sprite_uniforms: Option<LegacySpriteUniforms>,
     legacy_tmesh_uniforms: Option<LegacyTMeshUniforms>,
+    legacy_targets_warned: bool,
     projection: Matrix4,
     window_size: (u32, u32),
     // A single, shared set of buffers for a unit quad.
@@ -259,6 +310,7 @@ pub struct State {
     uploads: TexturedMeshUploads,
     cached_tmesh: FastU64Map<CachedTMeshGeom>,
     cached_tmesh_bytes: usize,
+    render_targets: FastU64Map<GlRenderTarget>,
     vsync_enabled: bool,
     screenshot_requested: bool,
     captured_frame: Option<RgbaImage>,
@@ -295,6 +347,11 @@ pub fn init(
         create_mesh_program(&gl, shaders.mesh_vert, shaders.mesh_frag)?;
     let (tmesh_program, tmesh_mvp_location, tmesh_texture_location) =
         create_tmesh_program(&gl, shaders.tmesh_vert, shaders.tmesh_frag)?;
+    let effect_program = shaders.effect.and_then(|effect| {
+        create_effect_program(&gl, effect.vert, effect.frag)
+            .inspect_err(|err| warn!("OpenGL shader effects unavailable: {err}"))
+            .ok()
+    });
     let legacy_sprite_uniforms = if path == GlPath::Legacy {
         Some(legacy_sprite_uniforms(&gl, program)?)
     } else {
@@ -648,6 +705,9 @@ pub fn init(
         program,
         mesh_program,
         tmesh_program,
+        effect_shaders: shaders.effect,
+        effect_program,
+        custom_effects: FastU64Map::default(),
         mvp_location,
         mesh_mvp_location,
         tmesh_mvp_location,
@@ -655,6 +715,7 @@ pub fn init(
         texture_location,
         legacy_sprite_uniforms,
         legacy_tmesh_uniforms,
+        legacy_targets_warned: false,
         projection,
         window_size: (initial_width, initial_height),
         shared_vao,
@@ -670,6 +731,7 @@ pub fn init(
         uploads: TexturedMeshUploads::with_capacity(1024, 64),
         cached_tmesh: FastU64Map::default(),
         cached_tmesh_bytes: 0,
+        render_targets: FastU64Map::default(),
         vsync_enabled,
         screenshot_requested: false,
         captured_frame: None,
@@ -925,6 +987,262 @@ fn ensure_cached_tmesh(
     true
 }
 
+fn create_render_target(
+    gl: &glow::Context,
+    width: u32,
+    height: u32,
+) -> Result<GlRenderTarget, String> {
+    let w = i32::try_from(width).map_err(|_| "render target width overflow".to_string())?;
+    let h = i32::try_from(height).map_err(|_| "render target height overflow".to_string())?;
+    // SAFETY: the OpenGL context is current on this thread, every object bound
+    // here was just created on it, and the texture allocation uploads no data.
+    unsafe {
+        let texture = gl.create_texture()?;
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_WRAP_S,
+            glow::CLAMP_TO_EDGE as i32,
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_WRAP_T,
+            glow::CLAMP_TO_EDGE as i32,
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_MIN_FILTER,
+            glow::LINEAR as i32,
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_MAG_FILTER,
+            glow::LINEAR as i32,
+        );
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_BASE_LEVEL, 0);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAX_LEVEL, 0);
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA8 as i32,
+            w,
+            h,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            PixelUnpackData::Slice(None),
+        );
+        gl.bind_texture(glow::TEXTURE_2D, None);
+
+        let depth = match gl.create_renderbuffer() {
+            Ok(depth) => depth,
+            Err(err) => {
+                gl.delete_texture(texture);
+                return Err(err);
+            }
+        };
+        gl.bind_renderbuffer(glow::RENDERBUFFER, Some(depth));
+        gl.renderbuffer_storage(glow::RENDERBUFFER, glow::DEPTH_COMPONENT24, w, h);
+        gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+
+        let framebuffer = match gl.create_framebuffer() {
+            Ok(framebuffer) => framebuffer,
+            Err(err) => {
+                gl.delete_renderbuffer(depth);
+                gl.delete_texture(texture);
+                return Err(err);
+            }
+        };
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+        gl.framebuffer_texture_2d(
+            glow::FRAMEBUFFER,
+            glow::COLOR_ATTACHMENT0,
+            glow::TEXTURE_2D,
+            Some(texture),
+            0,
+        );
+        gl.framebuffer_renderbuffer(
+            glow::FRAMEBUFFER,
+            glow::DEPTH_ATTACHMENT,
+            glow::RENDERBUFFER,
+            Some(depth),
+        );
+        let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+        if status == glow::FRAMEBUFFER_COMPLETE {
+            // New targets start transparent even when the first pass loads them.
+            gl.color_mask(true, true, true, true);
+            gl.clear_color(0.0, 0.0, 0.0, 0.0);
+            gl.clear_depth(1.0);
+            gl.depth_mask(true);
+            gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
+        }
+        gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+        let target = GlRenderTarget {
+            framebuffer,
+            texture,
+            depth,
+            size: (width, height),
+        };
+        if status != glow::FRAMEBUFFER_COMPLETE {
+            delete_render_target(gl, &target);
+            return Err(format!("framebuffer incomplete (status {status:#x})"));
+        }
+        Ok(target)
+    }
+}
+
+fn delete_render_target(gl: &glow::Context, target: &GlRenderTarget) {
+    // SAFETY: the target's objects were created by this backend on the current
+    // context and are deleted exactly once by their owner.
+    unsafe {
+        gl.delete_framebuffer(target.framebuffer);
+        gl.delete_renderbuffer(target.depth);
+        gl.delete_texture(target.texture);
+    }
+}
+
+/// Creates or resizes the framebuffers for this frame's offscreen passes.
+///
+/// Only the modern path renders targets; legacy GL2 contexts may lack
+/// framebuffer objects, so they draw through `legacy_screen_ops` instead.
+fn ensure_render_targets(state: &mut State, frame: &RenderFrame) {
+    if state.path != GlPath::Modern {
+        if !state.legacy_targets_warned && frame.render_target_passes().next().is_some() {
+            state.legacy_targets_warned = true;
+            warn!(
+                "Legacy OpenGL has no render targets: shader effects draw their content unfiltered and render-target sprites are skipped"
+            );
+        }
+        return;
+    }
+    for (pass, _) in frame.render_target_passes() {
+        let key = u64::from(pass.slot);
+        if pass.width == 0
+            || pass.height == 0
+            || state
+                .render_targets
+                .get(&key)
+                .is_some_and(|target| target.size == (pass.width, pass.height))
+        {
+            continue;
+        }
+        if let Some(target) = state.render_targets.remove(&key) {
+            delete_render_target(&state.gl, &target);
+        }
+        match create_render_target(&state.gl, pass.width, pass.height) {
+            Ok(target) => {
+                state.render_targets.insert(key, target);
+            }
+            Err(err) => debug!(
+                "OpenGL render target {} ({}x{}) unavailable: {err}",
+                pass.slot, pass.width, pass.height
+            ),
+        }
+    }
+}
+
+/// The screen ops a legacy context draws. Each effect composite is replaced
+/// by the ops of the pass it samples, so its content stays on screen without
+/// the filter, tint or blend.
+fn legacy_screen_ops(frame: &RenderFrame) -> Cow<'_, [DrawOp]> {
+    let screen = frame.screen_ops();
+    if !screen.iter().any(|op| matches!(op, DrawOp::Effect(_))) {
+        return Cow::Borrowed(screen);
+    }
+    let passes: Vec<_> = frame.render_target_passes().collect();
+    let mut ops = Vec::with_capacity(screen.len());
+    push_legacy_ops(screen, &passes, passes.len(), &mut ops);
+    Cow::Owned(ops)
+}
+
+fn push_legacy_ops(
+    source: &[DrawOp],
+    passes: &[(RenderTargetPass, &[DrawOp])],
+    depth: usize,
+    out: &mut Vec<DrawOp>,
+) {
+    for &op in source {
+        let DrawOp::Effect(run) = op else {
+            out.push(op);
+            continue;
+        };
+        // Passes can only nest as deep as there are passes; anything deeper
+        // is a cycle.
+        let Some(depth) = depth.checked_sub(1) else {
+            continue;
+        };
+        if let Some((_, body)) = render_target_slot(run.texture_handle)
+            .and_then(|slot| passes.iter().find(|(pass, _)| pass.slot == slot))
+        {
+            push_legacy_ops(body, passes, depth, out);
+        }
+    }
+}
+
+/// Binds a pass's framebuffer, returning its slot when the target exists.
+fn begin_render_target(
+    gl: &glow::Context,
+    targets: &FastU64Map<GlRenderTarget>,
+    pass: RenderTargetPass,
+) -> Option<u32> {
+    let target = targets.get(&u64::from(pass.slot))?;
+    // SAFETY: the framebuffer is a live object owned by this backend, and the
+    // state calls below do not retain Rust pointers.
+    unsafe {
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(target.framebuffer));
+        gl.viewport(0, 0, target.size.0 as i32, target.size.1 as i32);
+        // Targets keep alpha so a later composite can blend them.
+        gl.color_mask(true, true, true, true);
+        if pass.clear {
+            gl.clear_color(0.0, 0.0, 0.0, 0.0);
+            gl.clear_depth(1.0);
+            gl.depth_mask(true);
+            gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
+        }
+    }
+    Some(pass.slot)
+}
+
+fn end_render_target(gl: &glow::Context, window_size: (u32, u32)) {
+    // SAFETY: rebinding the window framebuffer and restoring its viewport and
+    // color mask only mutates state on the current context.
+    unsafe {
+        gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+        gl.viewport(0, 0, window_size.0 as i32, window_size.1 as i32);
+        gl.color_mask(true, true, true, false);
+    }
+}
+
+/// Selects a run's camera. Target passes flip Y so row 0 of the target
+/// texture holds the top of the pass, matching uploaded images.
+#[inline(always)]
+fn gl_pass_camera(frame: &RenderFrame, camera: u8, fallback: Matrix4, in_target: bool) -> Matrix4 {
+    let cam = frame
+        .cameras
+        .get(camera as usize)
+        .copied()
+        .unwrap_or(fallback);
+    if in_target {
+        Matrix4::from_scale(glam::Vec3::new(1.0, -1.0, 1.0)) * cam
+    } else {
+        cam
+    }
+}
+
+#[inline(always)]
+fn resolve_gl_texture(
+    textures: &impl TextureLookup,
+    targets: &FastU64Map<GlRenderTarget>,
+    handle: TextureHandle,
+    active_target: Option<u32>,
+) -> Option<glow::Texture> {
+    match render_target_slot(handle) {
+        Some(slot) if active_target == Some(slot) => None,
+        Some(slot) => targets.get(&u64::from(slot)).map(|target| target.texture),
+        None => textures.opengl_texture(handle).map(|texture| texture.0),
+    }
+}
+
 #[inline(always)]
 pub fn request_screenshot(state: &mut State) {
     state.screenshot_requested = true;
@@ -961,13 +1279,20 @@ pub fn draw(
         unsafe {
             gl.enable(glow::BLEND);
             match want {
+                // Alpha factors only matter in render targets, where coverage
+                // must accumulate the way the software backend composites it.
                 BlendMode::Alpha => {
                     gl.blend_equation(glow::FUNC_ADD);
-                    gl.blend_func(glow::SRC_ALPHA, glow::ONE_MINUS_SRC_ALPHA);
+                    gl.blend_func_separate(
+                        glow::SRC_ALPHA,
+                        glow::ONE_MINUS_SRC_ALPHA,
+                        glow::ONE,
+                        glow::ONE_MINUS_SRC_ALPHA,
+                    );
                 }
                 BlendMode::Add => {
                     gl.blend_equation(glow::FUNC_ADD);
-                    gl.blend_func(glow::SRC_ALPHA, glow::ONE);
+                    gl.blend_func_separate(glow::SRC_ALPHA, glow::ONE, glow::ONE, glow::ONE);
                 }
                 BlendMode::Multiply => {
                     gl.blend_equation(glow::FUNC_ADD);
@@ -1013,6 +1338,8 @@ pub fn draw(
                 .then_some(cache_key)
         });
     }
+    ensure_render_targets(state, frame);
+    ensure_custom_effects(state, frame);
     let mut stats = DrawStats {
         backend_prepare_us: elapsed_us_since(backend_prepare_started),
         storage: draw_storage_stats(frame, Some(&state.uploads)),
@@ -1049,12 +1376,15 @@ pub fn draw(
 
         let mut last_bound_tex: Option<glow::Texture> = None;
         let mut last_blend = Some(BlendMode::Alpha);
-        let mut last_prog: Option<u8> = None; // 0=sprite, 1=mesh, 2=textured mesh
+        let mut last_prog: Option<u8> = None; // 0=sprite, 1=mesh, 2=textured mesh, 3=effect
+        let mut last_effect_program: Option<glow::Program> = None;
         let mut last_cameras = [CameraUploadCache::default(); 3];
         let mut last_sprite_instance_start: Option<u32> = None;
         let mut last_tmesh_instance_start: Option<u32> = None;
         let mut tmesh_buffer_cache = TexturedMeshBufferCache::default();
         let mut last_depth_test = Some(false);
+        let mut active_target: Option<u32> = None;
+        let mut skip_target = false;
 
         let backend_upload_started = Instant::now();
         if state.path == GlPath::Modern && !frame.sprite_instances.is_empty() {
@@ -1115,7 +1445,23 @@ pub fn draw(
                 .expect("modern OpenGL path creates a textured mesh instance VBO");
 
             for op in frame.ops.iter().copied() {
+                if skip_target && !matches!(op, DrawOp::BeginTarget(_) | DrawOp::EndTarget) {
+                    continue;
+                }
                 match op {
+                    DrawOp::BeginTarget(pass) => {
+                        active_target = begin_render_target(gl, &state.render_targets, pass);
+                        skip_target = active_target.is_none();
+                        last_cameras = [CameraUploadCache::default(); 3];
+                        last_depth_test = None;
+                        apply_depth_test(gl, false, &mut last_depth_test);
+                    }
+                    DrawOp::EndTarget => {
+                        end_render_target(gl, state.window_size);
+                        active_target = None;
+                        skip_target = false;
+                        last_cameras = [CameraUploadCache::default(); 3];
+                    }
                     DrawOp::Sprite(run) => {
                         apply_blend(gl, run.blend, &mut last_blend);
                         apply_depth_test(gl, false, &mut last_depth_test);
@@ -1221,11 +1567,12 @@ pub fn draw(
                         }
 
                         if last_cameras[0].update_required(run.camera) {
-                            let cam = frame
-                                .cameras
-                                .get(run.camera as usize)
-                                .copied()
-                                .unwrap_or(state.projection);
+                            let cam = gl_pass_camera(
+                                frame,
+                                run.camera,
+                                state.projection,
+                                active_target.is_some(),
+                            );
                             let mvp_array = cam.to_cols_array_2d();
                             gl.uniform_matrix_4_f32_slice(
                                 Some(&state.mvp_location),
@@ -1234,10 +1581,12 @@ pub fn draw(
                             );
                         }
 
-                        let Some(texture) = textures
-                            .opengl_texture(run.texture_handle)
-                            .map(|texture| texture.0)
-                        else {
+                        let Some(texture) = resolve_gl_texture(
+                            textures,
+                            &state.render_targets,
+                            run.texture_handle,
+                            active_target,
+                        ) else {
                             continue;
                         };
 
@@ -1283,11 +1632,12 @@ pub fn draw(
                         }
 
                         if last_cameras[1].update_required(run.camera) {
-                            let cam = frame
-                                .cameras
-                                .get(run.camera as usize)
-                                .copied()
-                                .unwrap_or(state.projection);
+                            let cam = gl_pass_camera(
+                                frame,
+                                run.camera,
+                                state.projection,
+                                active_target.is_some(),
+                            );
                             let mvp_array = cam.to_cols_array_2d();
                             gl.uniform_matrix_4_f32_slice(
                                 Some(&state.mesh_mvp_location),
@@ -1442,11 +1792,12 @@ pub fn draw(
                         }
 
                         if last_cameras[2].update_required(run.camera) {
-                            let cam = frame
-                                .cameras
-                                .get(run.camera as usize)
-                                .copied()
-                                .unwrap_or(state.projection);
+                            let cam = gl_pass_camera(
+                                frame,
+                                run.camera,
+                                state.projection,
+                                active_target.is_some(),
+                            );
                             let mvp_array = cam.to_cols_array_2d();
                             gl.uniform_matrix_4_f32_slice(
                                 Some(&state.tmesh_mvp_location),
@@ -1455,10 +1806,12 @@ pub fn draw(
                             );
                         }
 
-                        let Some(texture) = textures
-                            .opengl_texture(run.texture_handle)
-                            .map(|texture| texture.0)
-                        else {
+                        let Some(texture) = resolve_gl_texture(
+                            textures,
+                            &state.render_targets,
+                            run.texture_handle,
+                            active_target,
+                        ) else {
                             continue;
                         };
 
@@ -1489,6 +1842,58 @@ pub fn draw(
                         vertices =
                             vertices.saturating_add(tri_count.saturating_mul(run.instance_count));
                     }
+                    DrawOp::Effect(mut run) => {
+                        let custom = state
+                            .custom_effects
+                            .get(&u64::from(run.effect.program()))
+                            .copied()
+                            .flatten();
+                        if custom.is_none() {
+                            run = run.builtin_only();
+                        }
+                        let Some(effect) = custom.or(state.effect_program) else {
+                            continue;
+                        };
+                        let Some(target) = render_target_slot(run.texture_handle)
+                            .filter(|slot| active_target != Some(*slot))
+                            .and_then(|slot| state.render_targets.get(&u64::from(slot)))
+                        else {
+                            continue;
+                        };
+                        apply_blend(gl, run.blend, &mut last_blend);
+                        apply_depth_test(gl, false, &mut last_depth_test);
+
+                        if last_prog != Some(3) || last_effect_program != Some(effect.program) {
+                            gl.use_program(Some(effect.program));
+                            gl.bind_vertex_array(Some(shared_vao));
+                            gl.uniform_1_i32(effect.texture.as_ref(), 0);
+                            last_prog = Some(3);
+                            last_effect_program = Some(effect.program);
+                            last_sprite_instance_start = None;
+                            tmesh_buffer_cache.reset();
+                        }
+                        let uniforms = EffectUniforms::new(run, [target.size.0, target.size.1]);
+                        gl.uniform_4_f32_slice(Some(&effect.tint), &uniforms.tint);
+                        gl.uniform_4_f32_slice(effect.params.as_ref(), &uniforms.params);
+                        gl.uniform_2_f32_slice(effect.texel_size.as_ref(), &uniforms.texel_size);
+                        gl.uniform_1_i32(effect.program_index.as_ref(), uniforms.program as i32);
+                        gl.uniform_1_f32(
+                            Some(&effect.flip_y),
+                            if active_target.is_some() { -1.0 } else { 1.0 },
+                        );
+
+                        if last_bound_tex != Some(target.texture) {
+                            gl.bind_texture(glow::TEXTURE_2D, Some(target.texture));
+                            last_bound_tex = Some(target.texture);
+                        }
+                        gl.draw_elements(
+                            glow::TRIANGLES,
+                            state.index_count,
+                            glow::UNSIGNED_SHORT,
+                            0,
+                        );
+                        vertices = vertices.saturating_add(4);
+                    }
                 }
             }
         } else {
@@ -1499,7 +1904,7 @@ pub fn draw(
                 .legacy_tmesh_uniforms
                 .expect("legacy OpenGL path creates textured mesh uniforms");
 
-            for op in frame.ops.iter().copied() {
+            for op in legacy_screen_ops(frame).iter().copied() {
                 match op {
                     DrawOp::Sprite(run) => {
                         apply_blend(gl, run.blend, &mut last_blend);
@@ -1529,11 +1934,12 @@ pub fn draw(
                         }
 
                         if last_cameras[0].update_required(run.camera) {
-                            let cam = frame
-                                .cameras
-                                .get(run.camera as usize)
-                                .copied()
-                                .unwrap_or(state.projection);
+                            let cam = gl_pass_camera(
+                                frame,
+                                run.camera,
+                                state.projection,
+                                active_target.is_some(),
+                            );
                             let mvp_array = cam.to_cols_array_2d();
                             gl.uniform_matrix_4_f32_slice(
                                 Some(&state.mvp_location),
@@ -1542,10 +1948,12 @@ pub fn draw(
                             );
                         }
 
-                        let Some(texture) = textures
-                            .opengl_texture(run.texture_handle)
-                            .map(|texture| texture.0)
-                        else {
+                        let Some(texture) = resolve_gl_texture(
+                            textures,
+                            &state.render_targets,
+                            run.texture_handle,
+                            active_target,
+                        ) else {
                             continue;
                         };
 
@@ -1654,11 +2062,12 @@ pub fn draw(
                         }
 
                         if last_cameras[1].update_required(run.camera) {
-                            let cam = frame
-                                .cameras
-                                .get(run.camera as usize)
-                                .copied()
-                                .unwrap_or(state.projection);
+                            let cam = gl_pass_camera(
+                                frame,
+                                run.camera,
+                                state.projection,
+                                active_target.is_some(),
+                            );
                             let mvp_array = cam.to_cols_array_2d();
                             gl.uniform_matrix_4_f32_slice(
                                 Some(&state.mesh_mvp_location),
@@ -1732,11 +2141,12 @@ pub fn draw(
                         }
 
                         if last_cameras[2].update_required(run.camera) {
-                            let cam = frame
-                                .cameras
-                                .get(run.camera as usize)
-                                .copied()
-                                .unwrap_or(state.projection);
+                            let cam = gl_pass_camera(
+                                frame,
+                                run.camera,
+                                state.projection,
+                                active_target.is_some(),
+                            );
                             let mvp_array = cam.to_cols_array_2d();
                             gl.uniform_matrix_4_f32_slice(
                                 Some(&state.tmesh_mvp_location),
@@ -1745,10 +2155,12 @@ pub fn draw(
                             );
                         }
 
-                        let Some(texture) = textures
-                            .opengl_texture(run.texture_handle)
-                            .map(|texture| texture.0)
-                        else {
+                        let Some(texture) = resolve_gl_texture(
+                            textures,
+                            &state.render_targets,
+                            run.texture_handle,
+                            active_target,
+                        ) else {
                             continue;
                         };
 
@@ -1818,9 +2230,14 @@ pub fn draw(
                             vertices = vertices.saturating_add(tri_count);
                         }
                     }
+                    // `legacy_screen_ops` leaves no passes or composites.
+                    DrawOp::BeginTarget(_) | DrawOp::EndTarget | DrawOp::Effect(_) => {}
                 }
             }
         }
+        if active_target.is_some() || skip_target {
+            end_render_target(gl, state.window_size);
+        }
         apply_depth_test(gl, false, &mut last_depth_test);
         if state.path == GlPath::Modern {
             gl.bind_vertex_array(None);
@@ -1935,14 +2352,31 @@ pub fn set_default_projection(state: &mut State, projection: Matrix4) {
     state.projection = projection;
 }
 
+pub fn release_render_targets(state: &mut State) {
+    for (_, target) in state.render_targets.drain() {
+        delete_render_target(&state.gl, &target);
+    }
+}
+
 pub fn cleanup(state: &mut State) {
     info!("Cleaning up OpenGL resources...");
+    release_render_targets(state);
     // SAFETY: all GL object handles below were created by this backend and are
     // still owned by `state`, so deleting them once during cleanup is valid.
     unsafe {
         state.gl.delete_program(state.program);
         state.gl.delete_program(state.mesh_program);
         state.gl.delete_program(state.tmesh_program);
+        if let Some(effect) = state.effect_program {
+            state.gl.delete_program(effect.program);
+        }
+        for effect in state
+            .custom_effects
+            .drain()
+            .filter_map(|(_, effect)| effect)
+        {
+            state.gl.delete_program(effect.program);
+        }
         if let Some(vao) = state.shared_vao {
             state.gl.delete_vertex_array(vao);
         }
@@ -2363,6 +2797,128 @@ fn create_tmesh_program(
     }
 }
 
+fn create_effect_program(
+    gl: &glow::Context,
+    vert_src: &str,
+    frag_src: &str,
+) -> Result<EffectProgram, String> {
+    // SAFETY: shader/program creation and linkage only touch the current OpenGL
+    // context, and all temporary shader/program handles are cleaned up on every
+    // exit path below.
+    let program = unsafe {
+        let program = gl.create_program()?;
+        for (index, name) in EFFECT_ATTRIBS {
+            gl.bind_attrib_location(program, index, name);
+        }
+        let compile = |ty, src: &str| -> Result<glow::Shader, String> {
+            let sh = gl.create_shader(ty)?;
+            gl.shader_source(sh, src);
+            gl.compile_shader(sh);
+            if !gl.get_shader_compile_status(sh) {
+                let log = gl.get_shader_info_log(sh);
+                gl.delete_shader(sh);
+                return Err(log);
+            }
+            Ok(sh)
+        };
+
+        let vert = compile(glow::VERTEX_SHADER, vert_src)?;
+        let frag = match compile(glow::FRAGMENT_SHADER, frag_src) {
+            Ok(frag) => frag,
+            Err(log) => {
+                gl.delete_shader(vert);
+                gl.delete_program(program);
+                return Err(log);
+            }
+        };
+
+        gl.attach_shader(program, vert);
+        gl.attach_shader(program, frag);
+        gl.link_program(program);
+        let linked = gl.get_program_link_status(program);
+        let log = if linked {
+            String::new()
+        } else {
+            gl.get_program_info_log(program)
+        };
+        gl.detach_shader(program, vert);
+        gl.detach_shader(program, frag);
+        gl.delete_shader(vert);
+        gl.delete_shader(frag);
+        if !linked {
+            gl.delete_program(program);
+            return Err(log);
+        }
+        program
+    };
+    let locations = (|| {
+        Ok(EffectProgram {
+            program,
+            texture: uniform_location(gl, program, "u_texture").ok(),
+            tint: uniform_location(gl, program, "u_tint")?,
+            params: uniform_location(gl, program, "u_params").ok(),
+            texel_size: uniform_location(gl, program, "u_texel_size").ok(),
+            program_index: uniform_location(gl, program, "u_program").ok(),
+            flip_y: uniform_location(gl, program, "u_flip_y")?,
+        })
+    })();
+    if locations.is_err() {
+        // SAFETY: the program was created above and is not referenced elsewhere.
+        unsafe { gl.delete_program(program) };
+    }
+    locations
+}
+
+/// Compiles the user programs the frame runs for the first time. Needs the
+/// context current, like the rest of `draw`.
+fn ensure_custom_effects(state: &mut State, frame: &RenderFrame) {
+    let Some(shaders) = state.effect_shaders else {
+        return;
+    };
+    for op in &frame.ops {
+        let DrawOp::Effect(run) = op else {
+            continue;
+        };
+        let id = run.effect.program();
+        if run.effect.is_builtin() || state.custom_effects.contains_key(&u64::from(id)) {
+            continue;
+        }
+        let compiled = compile_custom_effect(&state.gl, shaders, id);
+        state.custom_effects.insert(u64::from(id), compiled);
+    }
+}
+
+fn compile_custom_effect(
+    gl: &glow::Context,
+    shaders: EffectShaders,
+    id: ShaderProgramId,
+) -> Option<EffectProgram> {
+    let Some(program) = deadlib_render_core::shader_program(id) else {
+        warn!("OpenGL: shader program {id} is not registered; drawing it unchanged.");
+        return None;
+    };
+    let Some(glsl) = program.sources.glsl.as_deref() else {
+        warn!(
+            "OpenGL: shader program '{}' has no GLSL source; drawing it unchanged.",
+            program.name
+        );
+        return None;
+    };
+    let frag = format!(
+        "{}\n{glsl}\n{}",
+        shaders.custom_prelude,
+        include_str!("shaders/opengl_effect_custom_main.frag")
+    );
+    create_effect_program(gl, shaders.vert, &frag)
+        .inspect_err(|err| {
+            warn!(
+                "OpenGL: shader program '{}' failed to compile; drawing it unchanged: {err}",
+                program.name
+            );
+        })
+        .ok()
+}
+
 fn uniform_location(
     gl: &glow::Context,
     program: glow::Program,
@@ -2463,7 +3019,12 @@ fn surface_extent(width: u32, height: u32) -> (NonZeroU32, NonZeroU32) {
 #[cfg(test)]
 mod tests {
     use super::{
-        GlApi, GlPath, GlVersion, base_instance_capability, parse_gl_version, surface_extent,
+        GlApi, GlPath, GlVersion, base_instance_capability, legacy_screen_ops, parse_gl_version,
+        surface_extent,
+    };
+    use deadlib_render_core::{
+        BlendMode, DrawOp, EffectRun, MeshRun, RenderFrame, RenderTargetPass, ShaderEffect,
+        render_target_handle,
     };
 
     #[test]
@@ -2532,4 +3093,60 @@ mod tests {
             true
         ));
     }
+
+    fn mesh(vertex_start: u32) -> DrawOp {
+        DrawOp::Mesh(MeshRun {
+            vertex_start,
+            vertex_count: 3,
+            blend: BlendMode::Alpha,
+            camera: 0,
+        })
+    }
+
+    fn effect(slot: u32) -> DrawOp {
+        DrawOp::Effect(EffectRun {
+            texture_handle: render_target_handle(slot),
+            effect: ShaderEffect::Blur { radius: 2.0 },
+            tint: [1.0; 4],
+            blend: BlendMode::Alpha,
+        })
+    }
+
+    fn pass(slot: u32) -> DrawOp {
+        DrawOp::BeginTarget(RenderTargetPass {
+            slot,
+            width: 64,
+            height: 64,
+            clear: true,
+        })
+    }
+
+    #[test]
+    fn legacy_screen_ops_draw_effect_passes_in_place() {
+        let frame = RenderFrame {
+            clear_color: [0.0; 4],
+            cameras: Vec::new(),
+            sprite_instances: Vec::new(),
+            mesh_vertices: Vec::new(),
+            tmesh_instances: Vec::new(),
+            tmesh_geometries: Vec::new(),
+            ops: vec![
+                pass(1),
+                mesh(30),
+                DrawOp::EndTarget,
+                pass(0),
+                mesh(10),
+                effect(1),
+                DrawOp::EndTarget,
+                mesh(0),
+                effect(0),
+                mesh(20),
+            ],
+        };
+
+        assert_eq!(
+            legacy_screen_ops(&frame).as_ref(),
+            [mesh(0), mesh(10), mesh(30), mesh(20)]
+        );
+    }
 }
@@ -0,0 +1,13 @@
+#version 330 core
+layout (location = 0) in vec2 a_pos;
+layout (location = 1) in vec2 a_tex_coord;
+
+out vec2 v_uv;
+
+// -1.0 inside render targets so row 0 of the target holds the top of the pass.
+uniform float u_flip_y;
+
+void main() {
+    v_uv = a_tex_coord;
+    gl_Position = vec4(a_pos.x * 2.0, a_pos.y * 2.0 * u_flip_y, 0.0, 1.0);
+}
@@ -0,0 +1,15 @@
+#version 330 core
+in vec2 v_uv;
+out vec4 FragColor;
+
+uniform sampler2D u_texture;
+uniform vec4 u_tint;
+uniform vec4 u_params;
+uniform vec2 u_texel_size;
+
+// A user program follows and defines `vec4 effect(vec2 uv)` returning the
+// untinted colour; it may read `u_params` and `u_texel_size` or sample
+// through `tap`.
+vec4 tap(vec2 uv) {
+    return texture(u_texture, uv);
+}
@@ -0,0 +1,3 @@
+void main() {
+    FragColor = effect(v_uv) * u_tint;
+}
@@ -0,0 +1,54 @@
+#version 300 es
+precision highp float;
+precision highp int;
+
+in vec2 v_uv;
+out vec4 FragColor;
+
+uniform sampler2D u_texture;
+uniform vec4 u_tint;
+uniform vec4 u_params;
+uniform vec2 u_texel_size;
+uniform int u_program;
+
+// Mirrors the software backend's shade_effect tap for tap.
+const float BLUR_WEIGHTS[5] = float[5](1.0, 4.0, 6.0, 4.0, 1.0);
+const vec3 LUMA = vec3(0.2126, 0.7152, 0.0722);
+const float TAU = 6.28318530718;
+
+vec4 shade(vec2 uv) {
+    if (u_program == 0) {
+        float step_px = u_params.x * 0.5;
+        vec4 sum = vec4(0.0);
+        for (int j = 0; j < 5; ++j) {
+            for (int i = 0; i < 5; ++i) {
+                vec2 offset = vec2(float(i - 2), float(j - 2)) * step_px * u_texel_size;
+                sum += texture(u_texture, uv + offset) * (BLUR_WEIGHTS[i] * BLUR_WEIGHTS[j] / 256.0);
+            }
+        }
+        return sum;
+    }
+    if (u_program == 1) {
+        vec2 offset = u_params.xy * u_texel_size;
+        vec4 r = texture(u_texture, uv + offset);
+        vec4 g = texture(u_texture, uv);
+        vec4 b = texture(u_texture, uv - offset);
+        return vec4(r.r, g.g, b.b, max(max(r.a, g.a), b.a));
+    }
+    if (u_program == 2) {
+        vec4 c = texture(u_texture, uv);
+        float luma = dot(c.rgb, LUMA);
+        vec3 graded = (mix(vec3(luma), c.rgb, u_params.x) - 0.5) * u_params.y + 0.5 + u_params.z;
+        return vec4(clamp(graded, 0.0, 1.0), c.a);
+    }
+    if (u_params.y <= 0.0) {
+        return texture(u_texture, uv);
+    }
+    float y = uv.y / u_texel_size.y;
+    float shift = u_params.x * sin(TAU * y / u_params.y + u_params.z);
+    return texture(u_texture, vec2(uv.x + shift * u_texel_size.x, uv.y));
+}
+
+void main() {
+    FragColor = shade(v_uv) * u_tint;
+}
@@ -3,6 +3,7 @@ pub(crate) enum DrawKind {
     Sprite,
     Mesh,
     TexturedMesh,
+    Effect,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
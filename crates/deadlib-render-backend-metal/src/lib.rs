@@ -4,11 +4,11 @@ mod encoder_cache;
 
 use core_graphics_types::geometry::CGSize;
 use deadlib_render_core::{
-    BlendMode, ClockDomainTrace, DrawOp, DrawStats, FastU64Map, MeshVertex, PresentModePolicy,
-    PresentModeTrace, PresentStats, RenderFrame, SamplerDesc, SamplerFilter, SamplerWrap,
-    SpriteInstanceRaw, TMeshCacheKey, TextureHandle, TexturedMeshBufferCache,
+    BlendMode, ClockDomainTrace, DrawOp, DrawStats, EffectUniforms, FastU64Map, MeshVertex,
+    PresentModePolicy, PresentModeTrace, PresentStats, RenderFrame, SamplerDesc, SamplerFilter,
+    SamplerWrap, SpriteInstanceRaw, TMeshCacheKey, TextureHandle, TexturedMeshBufferCache,
     TexturedMeshInstanceRaw, TexturedMeshUploads, TexturedMeshVertex, draw_storage_stats,
-    resolve_textured_meshes,
+    render_target_slot, resolve_textured_meshes,
 };
 use foreign_types::ForeignType;
 use glam::Mat4 as Matrix4;
@@ -37,6 +37,9 @@ const LOGICAL_HEIGHT: f32 = 480.0;
 const DESIGN_WIDTH_16_9: f32 = 854.0;
 const COLOR_FORMAT: MTLPixelFormat = MTLPixelFormat::BGRA8Unorm;
 const DEPTH_FORMAT: MTLPixelFormat = MTLPixelFormat::Depth32Float;
+const SCREEN_WRITE_MASK: MTLColorWriteMask = MTLColorWriteMask::Red
+    .union(MTLColorWriteMask::Green)
+    .union(MTLColorWriteMask::Blue);
 const SHADER: &str = include_str!("shaders/renderer.metal");
 
 const _: () = assert!(mem::size_of::<SpriteInstanceRaw>() == 100);
@@ -75,6 +78,22 @@ impl PipelineSet {
     }
 }
 
+/// Pipelines for one kind of pass. Screen passes leave alpha untouched; target
+/// passes write it so a later composite can blend them.
+struct PassPipelines {
+    sprite: PipelineSet,
+    mesh: PipelineSet,
+    tmesh: PipelineSet,
+    effect: PipelineSet,
+}
+
+/// Offscreen color and depth for one render-target slot.
+struct MetalRenderTarget {
+    texture: Texture,
+    depth: metal::Texture,
+    size: (u32, u32),
+}
+
 struct DynamicBuffer {
     raw: Buffer,
     capacity: usize,
@@ -158,9 +177,8 @@ pub struct State {
     device: Device,
     queue: CommandQueue,
     layer: MetalLayer,
-    sprite_pipelines: PipelineSet,
-    mesh_pipelines: PipelineSet,
-    tmesh_pipelines: PipelineSet,
+    screen_pipelines: PassPipelines,
+    target_pipelines: PassPipelines,
     depth_disabled: DepthStencilState,
     depth_enabled: DepthStencilState,
     depth: metal::Texture,
@@ -174,6 +192,7 @@ pub struct State {
     cached_tmeshes: Vec<CachedTMesh>,
     cached_tmesh_bytes: usize,
     cache_stats: CacheStats,
+    render_targets: FastU64Map<MetalRenderTarget>,
     next_texture_id: u64,
     next_present_id: u32,
     completed_present_id: u32,
@@ -216,15 +235,8 @@ pub fn init(
     let library = device
         .new_library_with_source(SHADER, &options)
         .map_err(std::io::Error::other)?;
-    let sprite_pipelines =
-        build_pipeline_set(&device, &library, "sprite_vertex", "sprite_fragment")?;
-    let mesh_pipelines = build_pipeline_set(&device, &library, "mesh_vertex", "mesh_fragment")?;
-    let tmesh_pipelines = build_pipeline_set(
-        &device,
-        &library,
-        "textured_mesh_vertex",
-        "textured_mesh_fragment",
-    )?;
+    let screen_pipelines = build_pass_pipelines(&device, &library, SCREEN_WRITE_MASK)?;
+    let target_pipelines = build_pass_pipelines(&device, &library, MTLColorWriteMask::all())?;
     let (depth_disabled, depth_enabled) = build_depth_states(&device);
     let depth = create_depth_target(&device, size.width, size.height);
     let render_pass = create_render_pass(&depth);
@@ -237,9 +249,8 @@ pub fn init(
         device,
         queue,
         layer,
-        sprite_pipelines,
-        mesh_pipelines,
-        tmesh_pipelines,
+        screen_pipelines,
+        target_pipelines,
         depth_disabled,
         depth_enabled,
         depth,
@@ -253,6 +264,7 @@ pub fn init(
         cached_tmeshes: Vec::with_capacity(256),
         cached_tmesh_bytes: 0,
         cache_stats: CacheStats::default(),
+        render_targets: FastU64Map::default(),
         next_texture_id: 1,
         next_present_id: 1,
         completed_present_id: 0,
@@ -366,6 +378,7 @@ fn draw_inner(
         });
         stats.storage = draw_storage_stats(frame, Some(&state.uploads));
     }
+    ensure_render_targets(state, frame);
     stats.backend_prepare_us = elapsed_us(prepare_started);
 
     let slot_index = state.frame_index;
@@ -398,6 +411,9 @@ fn draw_inner(
     );
     let command = state.queue.new_command_buffer();
     command.set_label("DeadSync native Metal frame");
+    let targets_started = Instant::now();
+    let target_vertices = encode_render_targets(state, slot_index, command, frame, textures);
+    let target_record_us = elapsed_us(targets_started);
     let encoder = command.new_render_command_encoder(&state.render_pass.descriptor);
     clear_render_target(&state.render_pass.color);
     encoder.set_label("DeadSync native Metal render pass");
@@ -413,13 +429,166 @@ fn draw_inner(
     stats.backend_setup_us = elapsed_us(setup_started);
 
     let record_started = Instant::now();
+    let vertices_drawn = target_vertices.saturating_add(encode_ops(
+        state,
+        slot_index,
+        encoder,
+        frame,
+        frame.screen_ops(),
+        textures,
+        None,
+    ));
+    encoder.end_encoding();
+
+    let screenshot = if state.screenshot_requested {
+        state.screenshot_requested = false;
+        Some(encode_screenshot(
+            command,
+            drawable.texture(),
+            width,
+            height,
+        ))
+    } else {
+        None
+    };
+    stats.backend_record_us = elapsed_us(record_started).saturating_add(target_record_us);
+
+    let present_started = Instant::now();
+    command.present_drawable(&drawable);
+    stats.present_us = elapsed_us(present_started);
+    let owned_command = command.to_owned();
+    let submit_started = Instant::now();
+    command.commit();
+    stats.submit_us = elapsed_us(submit_started);
+    let mut applied_back_pressure = false;
+    let mut queue_idle_waited = false;
+    if apply_present_back_pressure || screenshot.is_some() {
+        let wait_started = Instant::now();
+        owned_command.wait_until_completed();
+        let waited = elapsed_us(wait_started);
+        stats.gpu_wait_us = stats.gpu_wait_us.saturating_add(waited);
+        applied_back_pressure = apply_present_back_pressure && waited >= BACK_PRESSURE_THRESHOLD_US;
+        queue_idle_waited = screenshot.is_some() && waited != 0;
+        mark_completed(state, submitted_id);
+    }
+    if let Some((buffer, row_bytes)) = screenshot {
+        state.captured_frame = read_screenshot(&buffer, width, height, row_bytes);
+    }
+    state.frames[slot_index].command = Some(owned_command);
+    state.frames[slot_index].submitted_id = submitted_id;
+    state.frame_index = (slot_index + 1) % FRAMES_IN_FLIGHT;
+    poll_completions(state);
+
+    let in_flight_images = submitted_id
+        .saturating_sub(state.completed_present_id)
+        .min(u32::from(u8::MAX)) as u8;
+    stats.present_stats = PresentStats {
+        mode: present_mode(state.vsync_enabled, state.present_mode_policy),
+        display_clock: ClockDomainTrace::Unknown,
+        host_clock: ClockDomainTrace::Monotonic,
+        in_flight_images,
+        waited_for_image,
+        applied_back_pressure,
+        queue_idle_waited,
+        suboptimal: false,
+        submitted_present_id: submitted_id,
+        completed_present_id: state.completed_present_id,
+        refresh_ns: 0,
+        actual_interval_ns: 0,
+        present_margin_ns: 0,
+        host_present_ns: state.completed_host_ns,
+        calibration_error_ns: 0,
+    };
+    stats.vertices = vertices_drawn;
+    Ok(stats)
+}
+
+/// Offscreen passes, each in its own encoder ahead of the screen pass.
+/// Metal's texture origin is the top-left, so targets need no flip to sample
+/// like uploaded images.
+fn encode_render_targets(
+    state: &State,
+    slot_index: usize,
+    command: &CommandBufferRef,
+    frame: &RenderFrame,
+    textures: &impl TextureLookup,
+) -> u32 {
+    let mut vertices_drawn = 0u32;
+    for (pass, ops) in frame.render_target_passes() {
+        let Some(target) = state.render_targets.get(&u64::from(pass.slot)) else {
+            continue;
+        };
+        let descriptor = RenderPassDescriptor::new();
+        let color = descriptor
+            .color_attachments()
+            .object_at(0)
+            .expect("attachment 0");
+        color.set_texture(Some(&target.texture.raw));
+        color.set_load_action(if pass.clear {
+            MTLLoadAction::Clear
+        } else {
+            MTLLoadAction::Load
+        });
+        color.set_clear_color(MTLClearColor::new(0.0, 0.0, 0.0, 0.0));
+        color.set_store_action(MTLStoreAction::Store);
+        let depth = descriptor.depth_attachment().expect("depth attachment");
+        depth.set_texture(Some(&target.depth));
+        depth.set_load_action(MTLLoadAction::Clear);
+        depth.set_store_action(MTLStoreAction::DontCare);
+        depth.set_clear_depth(1.0);
+        let encoder = command.new_render_command_encoder(descriptor);
+        encoder.set_label("DeadSync native Metal target pass");
+        encoder.set_viewport(MTLViewport {
+            originX: 0.0,
+            originY: 0.0,
+            width: f64::from(target.size.0),
+            height: f64::from(target.size.1),
+            znear: 0.0,
+            zfar: 1.0,
+        });
+        encoder.set_front_facing_winding(MTLWinding::CounterClockwise);
+        vertices_drawn = vertices_drawn.saturating_add(encode_ops(
+            state,
+            slot_index,
+            encoder,
+            frame,
+            ops,
+            textures,
+            Some(pass.slot),
+        ));
+        encoder.end_encoding();
+    }
+    vertices_drawn
+}
+
+/// Records one pass's ops, returning the vertices drawn. A target pass skips
+/// draws sampling its own slot.
+fn encode_ops(
+    state: &State,
+    slot_index: usize,
+    encoder: &RenderCommandEncoderRef,
+    frame: &RenderFrame,
+    ops: &[DrawOp],
+    textures: &impl TextureLookup,
+    active_target: Option<u32>,
+) -> u32 {
+    let pipelines = if active_target.is_some() {
+        &state.target_pipelines
+    } else {
+        &state.screen_pipelines
+    };
     let mut vertices_drawn = 0u32;
     let mut cache = EncoderCache::default();
     let mut tmesh_buffer_cache = TexturedMeshBufferCache::default();
-    for op in &frame.ops {
+    for op in ops {
         match op {
             DrawOp::Sprite(run) => {
-                let Some(texture) = textures.metal_texture(run.texture_handle) else {
+                let Some(texture) = resolve_texture(
+                    textures,
+                    &state.render_targets,
+                    run.texture_handle,
+                    active_target,
+                ) else {
                     continue;
                 };
                 let sprite_offset =
@@ -442,7 +611,7 @@ fn draw_inner(
                     encoder.set_depth_stencil_state(&state.depth_disabled);
                 }
                 if cache.pipeline_changed(DrawKind::Sprite, blend_key(run.blend)) {
-                    encoder.set_render_pipeline_state(state.sprite_pipelines.get(run.blend));
+                    encoder.set_render_pipeline_state(pipelines.sprite.get(run.blend));
                 }
                 set_camera(
                     encoder,
@@ -482,7 +651,7 @@ fn draw_inner(
                     encoder.set_depth_stencil_state(&state.depth_disabled);
                 }
                 if cache.pipeline_changed(DrawKind::Mesh, blend_key(run.blend)) {
-                    encoder.set_render_pipeline_state(state.mesh_pipelines.get(run.blend));
+                    encoder.set_render_pipeline_state(pipelines.mesh.get(run.blend));
                 }
                 set_camera(
                     encoder,
@@ -507,7 +676,12 @@ fn draw_inner(
                 if source.vertex_count() == 0 || run.instance_count == 0 {
                     continue;
                 }
-                let Some(texture) = textures.metal_texture(run.texture_handle) else {
+                let Some(texture) = resolve_texture(
+                    textures,
+                    &state.render_targets,
+                    run.texture_handle,
+                    active_target,
+                ) else {
                     continue;
                 };
                 let instance_offset =
@@ -526,7 +700,7 @@ fn draw_inner(
                     }
                 }
                 if cache.pipeline_changed(DrawKind::TexturedMesh, blend_key(run.blend)) {
-                    encoder.set_render_pipeline_state(state.tmesh_pipelines.get(run.blend));
+                    encoder.set_render_pipeline_state(pipelines.tmesh.get(run.blend));
                 }
                 if cache.depth_changed(run.depth_test) {
                     encoder.set_depth_stencil_state(if run.depth_test {
@@ -590,71 +764,121 @@ fn draw_inner(
                 vertices_drawn = vertices_drawn
                     .saturating_add((source.vertex_count() / 3).saturating_mul(run.instance_count));
             }
+            DrawOp::Effect(run) => {
+                let Some(target) = render_target_slot(run.texture_handle)
+                    .filter(|slot| active_target != Some(*slot))
+                    .and_then(|slot| state.render_targets.get(&u64::from(slot)))
+                else {
+                    continue;
+                };
+                if cache.kind_changed(DrawKind::Effect) {
+                    tmesh_buffer_cache.reset();
+                }
+                if cache.cull_changed(CullMode::None) {
+                    encoder.set_cull_mode(MTLCullMode::None);
+                }
+                if cache.depth_changed(false) {
+                    encoder.set_depth_stencil_state(&state.depth_disabled);
+                }
+                if cache.pipeline_changed(DrawKind::Effect, blend_key(run.blend)) {
+                    encoder.set_render_pipeline_state(pipelines.effect.get(run.blend));
+                }
+                if !run.effect.is_builtin() {
+                    static WARNED: std::sync::Once = std::sync::Once::new();
+                    WARNED.call_once(|| {
+                        warn!("Metal cannot run user shader programs; drawing them unchanged.");
+                    });
+                }
+                let uniforms =
+                    EffectUniforms::new(run.builtin_only(), [target.size.0, target.size.1]);
+                encoder.set_fragment_bytes(
+                    0,
+                    mem::size_of::<EffectUniforms>() as u64,
+                    ptr::from_ref(&uniforms).cast(),
+                );
+                if cache.texture_changed(target.texture.id) {
+                    encoder.set_fragment_texture(0, Some(&target.texture.raw));
+                }
+                if cache.sampler_changed(target.texture.id, false) {
+                    encoder.set_fragment_sampler_state(0, Some(&target.texture.sampler));
+                }
+                encoder.draw_primitives(MTLPrimitiveType::Triangle, 0, 6);
+                vertices_drawn = vertices_drawn.saturating_add(4);
+            }
+            // Target spans are split off before encoding.
+            DrawOp::BeginTarget(_) | DrawOp::EndTarget => {}
         }
     }
-    encoder.end_encoding();
-
-    let screenshot = if state.screenshot_requested {
-        state.screenshot_requested = false;
-        Some(encode_screenshot(
-            command,
-            drawable.texture(),
-            width,
-            height,
-        ))
-    } else {
-        None
-    };
-    stats.backend_record_us = elapsed_us(record_started);
+    vertices_drawn
+}
 
-    let present_started = Instant::now();
-    command.present_drawable(&drawable);
-    stats.present_us = elapsed_us(present_started);
-    let owned_command = command.to_owned();
-    let submit_started = Instant::now();
-    command.commit();
-    stats.submit_us = elapsed_us(submit_started);
-    let mut applied_back_pressure = false;
-    let mut queue_idle_waited = false;
-    if apply_present_back_pressure || screenshot.is_some() {
-        let wait_started = Instant::now();
-        owned_command.wait_until_completed();
-        let waited = elapsed_us(wait_started);
-        stats.gpu_wait_us = stats.gpu_wait_us.saturating_add(waited);
-        applied_back_pressure = apply_present_back_pressure && waited >= BACK_PRESSURE_THRESHOLD_US;
-        queue_idle_waited = screenshot.is_some() && waited != 0;
-        mark_completed(state, submitted_id);
+#[inline(always)]
+fn resolve_texture<'a>(
+    textures: &'a impl TextureLookup,
+    targets: &'a FastU64Map<MetalRenderTarget>,
+    handle: TextureHandle,
+    active_target: Option<u32>,
+) -> Option<&'a Texture> {
+    match render_target_slot(handle) {
+        Some(slot) if active_target == Some(slot) => None,
+        Some(slot) => targets.get(&u64::from(slot)).map(|target| &target.texture),
+        None => textures.metal_texture(handle),
     }
-    if let Some((buffer, row_bytes)) = screenshot {
-        state.captured_frame = read_screenshot(&buffer, width, height, row_bytes);
+}
+
+/// Creates or resizes the target for every pass in `frame`.
+fn ensure_render_targets(state: &mut State, frame: &RenderFrame) {
+    for (pass, _) in frame.render_target_passes() {
+        let key = u64::from(pass.slot);
+        if pass.width == 0
+            || pass.height == 0
+            || state
+                .render_targets
+                .get(&key)
+                .is_some_and(|target| target.size == (pass.width, pass.height))
+        {
+            continue;
+        }
+        let target = create_render_target(state, pass.width, pass.height);
+        state.render_targets.insert(key, target);
     }
-    state.frames[slot_index].command = Some(owned_command);
-    state.frames[slot_index].submitted_id = submitted_id;
-    state.frame_index = (slot_index + 1) % FRAMES_IN_FLIGHT;
-    poll_completions(state);
+}
 
-    let in_flight_images = submitted_id
-        .saturating_sub(state.completed_present_id)
-        .min(u32::from(u8::MAX)) as u8;
-    stats.present_stats = PresentStats {
-        mode: present_mode(state.vsync_enabled, state.present_mode_policy),
-        display_clock: ClockDomainTrace::Unknown,
-        host_clock: ClockDomainTrace::Monotonic,
-        in_flight_images,
-        waited_for_image,
-        applied_back_pressure,
-        queue_idle_waited,
-        suboptimal: false,
-        submitted_present_id: submitted_id,
-        completed_present_id: state.completed_present_id,
-        refresh_ns: 0,
-        actual_interval_ns: 0,
-        present_margin_ns: 0,
-        host_present_ns: state.completed_host_ns,
-        calibration_error_ns: 0,
+fn create_render_target(state: &mut State, width: u32, height: u32) -> MetalRenderTarget {
+    let desc = TextureDescriptor::new();
+    desc.set_texture_type(MTLTextureType::D2);
+    desc.set_pixel_format(COLOR_FORMAT);
+    desc.set_width(u64::from(width));
+    desc.set_height(u64::from(height));
+    desc.set_storage_mode(MTLStorageMode::Private);
+    desc.set_usage(MTLTextureUsage::RenderTarget | MTLTextureUsage::ShaderRead);
+    let raw = state.device.new_texture(&desc);
+    let sampler_desc = SamplerDesc {
+        filter: SamplerFilter::Linear,
+        wrap: SamplerWrap::Clamp,
+        mipmaps: false,
     };
-    stats.vertices = vertices_drawn;
-    Ok(stats)
+    let sampler = create_sampler(&state.device, sampler_desc);
+    let repeat_sampler = create_sampler(
+        &state.device,
+        SamplerDesc {
+            wrap: SamplerWrap::Repeat,
+            ..sampler_desc
+        },
+    );
+    let id = state.next_texture_id;
+    state.next_texture_id = state.next_texture_id.wrapping_add(1).max(1);
+    MetalRenderTarget {
+        texture: Texture {
+            id,
+            raw,
+            sampler,
+            repeat_sampler,
+            mipmaps: false,
+        },
+        depth: create_depth_target(&state.device, width, height),
+        size: (width, height),
+    }
 }
 
 pub fn resize(state: &mut State, width: u32, height: u32) {
@@ -670,6 +894,12 @@ pub fn set_default_projection(state: &mut State, projection: Matrix4) {
     state.projection = projection;
 }
 
+/// Drops every offscreen target; in-flight command buffers keep their own
+/// references until they complete.
+pub fn release_render_targets(state: &mut State) {
+    state.render_targets.clear();
+}
+
 pub fn set_present_config(
     state: &mut State,
     vsync_enabled: bool,
@@ -701,6 +931,7 @@ pub fn cleanup(state: &mut State) {
     state.cached_tmesh_slots.clear();
     state.cached_tmeshes.clear();
     state.cached_tmesh_bytes = 0;
+    release_render_targets(state);
     if let Err(error) = detach_layer(&state.window) {
         warn!("Failed to detach native Metal layer: {error}");
     }
@@ -744,11 +975,43 @@ fn set_layer_present_config(layer: &MetalLayerRef, vsync_enabled: bool) {
     layer.set_display_sync_enabled(vsync_enabled);
 }
 
+fn build_pass_pipelines(
+    device: &DeviceRef,
+    library: &LibraryRef,
+    write_mask: MTLColorWriteMask,
+) -> Result<PassPipelines, Box<dyn Error>> {
+    Ok(PassPipelines {
+        sprite: build_pipeline_set(
+            device,
+            library,
+            "sprite_vertex",
+            "sprite_fragment",
+            write_mask,
+        )?,
+        mesh: build_pipeline_set(device, library, "mesh_vertex", "mesh_fragment", write_mask)?,
+        tmesh: build_pipeline_set(
+            device,
+            library,
+            "textured_mesh_vertex",
+            "textured_mesh_fragment",
+            write_mask,
+        )?,
+        effect: build_pipeline_set(
+            device,
+            library,
+            "effect_vertex",
+            "effect_fragment",
+            write_mask,
+        )?,
+    })
+}
+
 fn build_pipeline_set(
     device: &DeviceRef,
     library: &LibraryRef,
     vertex_name: &str,
     fragment_name: &str,
+    write_mask: MTLColorWriteMask,
 ) -> Result<PipelineSet, Box<dyn Error>> {
     let vertex = library
         .get_function(vertex_name, None)
@@ -757,10 +1020,10 @@ fn build_pipeline_set(
         .get_function(fragment_name, None)
         .map_err(std::io::Error::other)?;
     Ok(PipelineSet {
-        alpha: build_pipeline(device, &vertex, &fragment, BlendMode::Alpha)?,
-        add: build_pipeline(device, &vertex, &fragment, BlendMode::Add)?,
-        multiply: build_pipeline(device, &vertex, &fragment, BlendMode::Multiply)?,
-        subtract: build_pipeline(device, &vertex, &fragment, BlendMode::Subtract)?,
+        alpha: build_pipeline(device, &vertex, &fragment, BlendMode::Alpha, write_mask)?,
+        add: build_pipeline(device, &vertex, &fragment, BlendMode::Add, write_mask)?,
+        multiply: build_pipeline(device, &vertex, &fragment, BlendMode::Multiply, write_mask)?,
+        subtract: build_pipeline(device, &vertex, &fragment, BlendMode::Subtract, write_mask)?,
     })
 }
 
@@ -769,6 +1032,7 @@ fn build_pipeline(
     vertex: &FunctionRef,
     fragment: &FunctionRef,
     blend: BlendMode,
+    write_mask: MTLColorWriteMask,
 ) -> Result<RenderPipelineState, Box<dyn Error>> {
     let desc = RenderPipelineDescriptor::new();
     desc.set_vertex_function(Some(vertex));
@@ -779,9 +1043,7 @@ fn build_pipeline(
         .object_at(0)
         .ok_or_else(|| std::io::Error::other("Metal pipeline has no color attachment"))?;
     attachment.set_pixel_format(COLOR_FORMAT);
-    attachment.set_write_mask(
-        MTLColorWriteMask::Red | MTLColorWriteMask::Green | MTLColorWriteMask::Blue,
-    );
+    attachment.set_write_mask(write_mask);
     configure_blend(attachment, blend);
     device
         .new_render_pipeline_state(&desc)
@@ -173,3 +173,87 @@ fragment float4 textured_mesh_fragment(
     }
     return color;
 }
+
+struct EffectUniforms {
+    float4 tint;
+    float4 params;
+    float2 texel_size;
+    uint program;
+    uint _pad;
+};
+
+struct EffectOut {
+    float4 pos [[position]];
+    float2 uv;
+};
+
+// Metal targets share the top-left origin, so composites need no flip.
+vertex EffectOut effect_vertex(uint vertex_id [[vertex_id]])
+{
+    constexpr float2 positions[6] = {
+        float2(-0.5, -0.5), float2(0.5, -0.5), float2(0.5, 0.5),
+        float2(0.5, 0.5), float2(-0.5, 0.5), float2(-0.5, -0.5),
+    };
+    constexpr float2 uvs[6] = {
+        float2(0.0, 1.0), float2(1.0, 1.0), float2(1.0, 0.0),
+        float2(1.0, 0.0), float2(0.0, 0.0), float2(0.0, 1.0),
+    };
+    float2 pos = positions[vertex_id];
+    EffectOut out;
+    out.pos = float4(pos.x * 2.0, pos.y * 2.0, 0.0, 1.0);
+    out.uv = uvs[vertex_id];
+    return out;
+}
+
+// Mirrors the software backend's shade_effect tap for tap.
+constant float BLUR_WEIGHTS[5] = {1.0, 4.0, 6.0, 4.0, 1.0};
+constant float3 LUMA = float3(0.2126, 0.7152, 0.0722);
+constant float TAU = 6.28318530718;
+
+static float4 shade_effect(
+    texture2d<float> tex,
+    sampler tex_sampler,
+    constant EffectUniforms &u,
+    float2 uv)
+{
+    if (u.program == 0) {
+        float step_px = u.params.x * 0.5;
+        float4 sum = float4(0.0);
+        for (int j = 0; j < 5; ++j) {
+            for (int i = 0; i < 5; ++i) {
+                float2 offset = float2(float(i - 2), float(j - 2)) * step_px * u.texel_size;
+                sum += tex.sample(tex_sampler, uv + offset)
+                    * (BLUR_WEIGHTS[i] * BLUR_WEIGHTS[j] / 256.0);
+            }
+        }
+        return sum;
+    }
+    if (u.program == 1) {
+        float2 offset = u.params.xy * u.texel_size;
+        float4 r = tex.sample(tex_sampler, uv + offset);
+        float4 g = tex.sample(tex_sampler, uv);
+        float4 b = tex.sample(tex_sampler, uv - offset);
+        return float4(r.r, g.g, b.b, max(max(r.a, g.a), b.a));
+    }
+    if (u.program == 2) {
+        float4 c = tex.sample(tex_sampler, uv);
+        float luma = dot(c.rgb, LUMA);
+        float3 graded = (mix(float3(luma), c.rgb, u.params.x) - 0.5) * u.params.y + 0.5 + u.params.z;
+        return float4(clamp(graded, 0.0, 1.0), c.a);
+    }
+    if (u.params.y <= 0.0) {
+        return tex.sample(tex_sampler, uv);
+    }
+    float y = uv.y / u.texel_size.y;
+    float shift = u.params.x * sin(TAU * y / u.params.y + u.params.z);
+    return tex.sample(tex_sampler, float2(uv.x + shift * u.texel_size.x, uv.y));
+}
+
+fragment float4 effect_fragment(
+    EffectOut in [[stage_in]],
+    texture2d<float> tex [[texture(0)]],
+    sampler tex_sampler [[sampler(0)]],
+    constant EffectUniforms &uniforms [[buffer(0)]])
+{
+    return shade_effect(tex, tex_sampler, uniforms, in.uv) * uniforms.tint;
+}
@@ -1,13 +1,14 @@
 use deadlib_render_core::{
-    BlendMode, DrawOp, DrawStats, RenderFrame, SOFTWARE_MESH_STORAGE_SLOT,
-    SOFTWARE_OBJECTS_STORAGE_SLOT, SOFTWARE_TMESH_STORAGE_SLOT, SamplerDesc, SamplerFilter,
-    SamplerWrap, TextureHandle, draw_storage_stats,
+    BlendMode, DrawOp, DrawStats, EffectRun, RenderFrame, RenderTargetPass,
+    SOFTWARE_MESH_STORAGE_SLOT, SOFTWARE_OBJECTS_STORAGE_SLOT, SOFTWARE_TMESH_STORAGE_SLOT,
+    SamplerDesc, SamplerFilter, SamplerWrap, ShaderEffect, TextureHandle, draw_storage_stats,
+    render_target_slot,
 };
 use glam::{Mat4 as Matrix4, Vec4 as Vector4};
 use image::RgbaImage;
-use log::info;
+use log::{info, warn};
 use rayon::prelude::*;
-use std::{error::Error, num::NonZeroU32, sync::Arc, time::Instant};
+use std::{collections::HashMap, error::Error, num::NonZeroU32, sync::Arc, time::Instant};
 use winit::{dpi::PhysicalSize, window::Window};
 
 const SOFTWARE_ROW_CHUNK: usize = 32;
@@ -20,6 +21,11 @@ const MESH_STAGE_VERTEX_CAP: usize = 36 * 1024;
 const U8_TO_F32: f32 = 1.0 / 255.0;
 const LOGICAL_HEIGHT: f32 = 480.0;
 const DESIGN_WIDTH_16_9: f32 = 854.0;
+const RENDER_TARGET_SAMPLER: SamplerDesc = SamplerDesc {
+    filter: SamplerFilter::Linear,
+    wrap: SamplerWrap::Clamp,
+    mipmaps: false,
+};
 
 pub struct Texture {
     pub image: RgbaImage,
@@ -30,6 +36,24 @@ pub trait TextureLookup {
     fn software_texture(&self, handle: TextureHandle) -> Option<&Texture>;
 }
 
+/// Resolves render-target handles to finished offscreen passes.
+///
+/// The target being drawn is taken out of the map for its pass, so a run that
+/// samples it finds no texture and is skipped like any other missing one.
+struct TargetTextures<'a, T> {
+    textures: &'a T,
+    targets: &'a HashMap<u32, Texture>,
+}
+
+impl<T: TextureLookup> TextureLookup for TargetTextures<'_, T> {
+    fn software_texture(&self, handle: TextureHandle) -> Option<&Texture> {
+        match render_target_slot(handle) {
+            Some(slot) => self.targets.get(&slot),
+            None => self.textures.software_texture(handle),
+        }
+    }
+}
+
 pub struct State {
     _context: softbuffer::Context<Arc<Window>>,
     surface: softbuffer::Surface<Arc<Window>, Arc<Window>>,
@@ -42,6 +66,10 @@ pub struct State {
     prepared_objects: Vec<PreparedObject>,
     prepared_mesh_vertices: Vec<ScreenVertexColor>,
     prepared_tmesh_vertices: Vec<ScreenVertexTexColor>,
+    render_targets: HashMap<u32, Texture>,
+    target_pixels: Vec<u32>,
+    screenshot_requested: bool,
+    captured_frame: Option<RgbaImage>,
 }
 
 struct WorkerPool {
@@ -82,6 +110,7 @@ enum PreparedObject {
         blend: BlendMode,
         texture_handle: TextureHandle,
     },
+    Effect(EffectRun),
 }
 
 pub fn init(window: Arc<Window>, _vsync_enabled: bool) -> Result<State, Box<dyn Error>> {
@@ -109,6 +138,10 @@ pub fn init(window: Arc<Window>, _vsync_enabled: bool) -> Result<State, Box<dyn
         prepared_objects: Vec::with_capacity(1024),
         prepared_mesh_vertices: Vec::with_capacity(MESH_STAGE_VERTEX_CAP),
         prepared_tmesh_vertices: Vec::with_capacity(MESH_STAGE_VERTEX_CAP),
+        render_targets: HashMap::new(),
+        target_pixels: Vec::new(),
+        screenshot_requested: false,
+        captured_frame: None,
     })
 }
 
@@ -138,8 +171,26 @@ fn ensure_worker_pool(state: &mut State, threads: usize) -> Result<(), Box<dyn E
     Ok(())
 }
 
+/// The next drawn frame is copied out before it is presented.
 #[inline(always)]
-pub const fn request_screenshot(_state: &mut State) {}
+pub const fn request_screenshot(state: &mut State) {
+    state.screenshot_requested = true;
+}
+
+pub fn capture_frame(state: &mut State) -> Result<RgbaImage, Box<dyn Error>> {
+    state
+        .captured_frame
+        .take()
+        .ok_or_else(|| std::io::Error::other("No captured screenshot frame available").into())
+}
+
+fn screenshot_image(pixels: &[u32], width: u32, height: u32) -> RgbaImage {
+    let mut image = RgbaImage::new(width, height);
+    for (out, pixel) in image.pixels_mut().zip(pixels) {
+        *out = image::Rgba([(pixel >> 16) as u8, (pixel >> 8) as u8, *pixel as u8, 255]);
+    }
+    image
+}
 
 pub fn create_texture(image: &RgbaImage, sampler: SamplerDesc) -> Result<Texture, Box<dyn Error>> {
     Ok(Texture {
@@ -185,12 +236,23 @@ pub fn draw(
         Some(threads) if threads >= 1 => threads.min(state.available_threads),
         _ => state.available_threads,
     };
-    let use_parallel = threads > 1 && h >= SOFTWARE_ROW_CHUNK * 2 && !frame.ops.is_empty();
-    let stage_meshes = use_parallel && h.div_ceil(SOFTWARE_ROW_CHUNK) >= MIN_STAGE_MESH_STRIPES;
     let backend_prepare_started = Instant::now();
     ensure_worker_pool(state, threads)?;
+    let mut target_vertices = 0u32;
+    for (pass, ops) in frame.render_target_passes() {
+        target_vertices = target_vertices
+            .saturating_add(draw_target_pass(state, frame, pass, ops, textures, threads));
+    }
+    let textures = &TargetTextures {
+        textures,
+        targets: &state.render_targets,
+    };
+    let screen_ops = frame.screen_ops();
+    let use_parallel = threads > 1 && h >= SOFTWARE_ROW_CHUNK * 2 && !screen_ops.is_empty();
+    let stage_meshes = use_parallel && h.div_ceil(SOFTWARE_ROW_CHUNK) >= MIN_STAGE_MESH_STRIPES;
     prepare_objects(
         frame,
+        screen_ops,
         default_proj,
         textures,
         w,
@@ -223,49 +285,25 @@ pub fn draw(
         *pixel = clear;
     }
 
-    let prepared_objects = state.prepared_objects.as_slice();
-    let prepared_mesh_vertices = state.prepared_mesh_vertices.as_slice();
-    let prepared_tmesh_vertices = state.prepared_tmesh_vertices.as_slice();
-    let vertices = if let Some(worker_pool) = worker_pool {
-        let pixels: &mut [u32] = &mut buffer;
-        worker_pool.install(|| {
-            pixels
-                .par_chunks_mut(w * SOFTWARE_ROW_CHUNK)
-                .enumerate()
-                .map(|(chunk_index, stripe)| {
-                    let y_start = chunk_index * SOFTWARE_ROW_CHUNK;
-                    let y_end = y_start + stripe.len() / w;
-                    draw_rows(
-                        frame,
-                        prepared_objects,
-                        prepared_mesh_vertices,
-                        prepared_tmesh_vertices,
-                        textures,
-                        w,
-                        h,
-                        y_start,
-                        y_end,
-                        stripe,
-                    )
-                })
-                .reduce(|| 0, u32::saturating_add)
-        })
-    } else {
-        draw_rows(
-            frame,
-            prepared_objects,
-            prepared_mesh_vertices,
-            prepared_tmesh_vertices,
-            textures,
-            w,
-            h,
-            0,
-            h,
-            &mut buffer,
-        )
-    };
+    let vertices = render_prepared(
+        worker_pool,
+        frame,
+        &state.prepared_objects,
+        &state.prepared_mesh_vertices,
+        &state.prepared_tmesh_vertices,
+        textures,
+        w,
+        h,
+        &mut buffer,
+    )
+    .saturating_add(target_vertices);
     let backend_record_us = elapsed_us_since(backend_record_started);
 
+    if state.screenshot_requested {
+        state.screenshot_requested = false;
+        state.captured_frame = Some(screenshot_image(&buffer, width, height));
+    }
+
     let present_started = Instant::now();
     buffer.present()?;
 
@@ -292,9 +330,202 @@ pub fn draw(
     })
 }
 
+#[allow(clippy::too_many_arguments)]
+fn render_prepared(
+    worker_pool: Option<&rayon::ThreadPool>,
+    frame: &RenderFrame,
+    prepared_objects: &[PreparedObject],
+    mesh_vertices: &[ScreenVertexColor],
+    tmesh_vertices: &[ScreenVertexTexColor],
+    textures: &(impl TextureLookup + Sync),
+    width: usize,
+    height: usize,
+    pixels: &mut [u32],
+) -> u32 {
+    let Some(worker_pool) = worker_pool else {
+        return draw_rows(
+            frame,
+            prepared_objects,
+            mesh_vertices,
+            tmesh_vertices,
+            textures,
+            width,
+            height,
+            0,
+            height,
+            pixels,
+        );
+    };
+    worker_pool.install(|| {
+        pixels
+            .par_chunks_mut(width * SOFTWARE_ROW_CHUNK)
+            .enumerate()
+            .map(|(chunk_index, stripe)| {
+                let y_start = chunk_index * SOFTWARE_ROW_CHUNK;
+                let y_end = y_start + stripe.len() / width;
+                draw_rows(
+                    frame,
+                    prepared_objects,
+                    mesh_vertices,
+                    tmesh_vertices,
+                    textures,
+                    width,
+                    height,
+                    y_start,
+                    y_end,
+                    stripe,
+                )
+            })
+            .reduce(|| 0, u32::saturating_add)
+    })
+}
+
+/// Renders one offscreen pass into its target, creating or resizing it first.
+fn draw_target_pass(
+    state: &mut State,
+    frame: &RenderFrame,
+    pass: RenderTargetPass,
+    ops: &[DrawOp],
+    textures: &(impl TextureLookup + Sync),
+    threads: usize,
+) -> u32 {
+    let w = pass.width as usize;
+    let h = pass.height as usize;
+    if w == 0 || h == 0 {
+        return 0;
+    }
+    let mut target = state
+        .render_targets
+        .remove(&pass.slot)
+        .filter(|target| target.image.dimensions() == (pass.width, pass.height))
+        .unwrap_or_else(|| Texture {
+            image: RgbaImage::new(pass.width, pass.height),
+            sampler: RENDER_TARGET_SAMPLER,
+        });
+    let pixels = &mut state.target_pixels;
+    load_target_pixels(&target.image, pass.clear, pixels);
+
+    let use_parallel = threads > 1 && h >= SOFTWARE_ROW_CHUNK * 2 && !ops.is_empty();
+    let stage_meshes = use_parallel && h.div_ceil(SOFTWARE_ROW_CHUNK) >= MIN_STAGE_MESH_STRIPES;
+    let textures = &TargetTextures {
+        textures,
+        targets: &state.render_targets,
+    };
+    prepare_objects(
+        frame,
+        ops,
+        state.projection,
+        textures,
+        w,
+        h,
+        &mut state.prepared_objects,
+        &mut state.prepared_mesh_vertices,
+        &mut state.prepared_tmesh_vertices,
+        stage_meshes,
+    );
+    let worker_pool = if use_parallel {
+        state.worker_pool.as_ref().map(|worker| &worker.pool)
+    } else {
+        None
+    };
+    let vertices = render_prepared(
+        worker_pool,
+        frame,
+        &state.prepared_objects,
+        &state.prepared_mesh_vertices,
+        &state.prepared_tmesh_vertices,
+        textures,
+        w,
+        h,
+        pixels,
+    );
+    store_target_pixels(pixels, &mut target.image);
+    state.render_targets.insert(pass.slot, target);
+    vertices
+}
+
+/// Draws the frame's offscreen passes into `targets` on the calling thread,
+/// without a surface. Parity tests diff the results against the GPU backends.
+#[cfg(any(test, feature = "test-util"))]
+pub fn draw_render_targets(
+    frame: &RenderFrame,
+    textures: &(impl TextureLookup + Sync),
+    projection: Matrix4,
+    targets: &mut HashMap<u32, Texture>,
+) {
+    let mut prepared = Vec::new();
+    let mut prepared_mesh = Vec::new();
+    let mut prepared_tmesh = Vec::new();
+    let mut pixels = Vec::new();
+    for (pass, ops) in frame.render_target_passes() {
+        let (w, h) = (pass.width as usize, pass.height as usize);
+        if w == 0 || h == 0 {
+            continue;
+        }
+        let mut target = targets
+            .remove(&pass.slot)
+            .filter(|target| target.image.dimensions() == (pass.width, pass.height))
+            .unwrap_or_else(|| Texture {
+                image: RgbaImage::new(pass.width, pass.height),
+                sampler: RENDER_TARGET_SAMPLER,
+            });
+        load_target_pixels(&target.image, pass.clear, &mut pixels);
+        let lookup = TargetTextures { textures, targets };
+        prepare_objects(
+            frame,
+            ops,
+            projection,
+            &lookup,
+            w,
+            h,
+            &mut prepared,
+            &mut prepared_mesh,
+            &mut prepared_tmesh,
+            false,
+        );
+        draw_rows(
+            frame,
+            &prepared,
+            &prepared_mesh,
+            &prepared_tmesh,
+            &lookup,
+            w,
+            h,
+            0,
+            h,
+            &mut pixels,
+        );
+        store_target_pixels(&pixels, &mut target.image);
+        targets.insert(pass.slot, target);
+    }
+}
+
+/// Seeds the pass buffer in the surface's packed ARGB layout.
+fn load_target_pixels(image: &RgbaImage, clear: bool, pixels: &mut Vec<u32>) {
+    pixels.clear();
+    if clear {
+        pixels.resize(image.as_raw().len() / 4, 0);
+        return;
+    }
+    pixels.extend(
+        image
+            .as_raw()
+            .chunks_exact(4)
+            .map(|c| u32::from_be_bytes([c[3], c[0], c[1], c[2]])),
+    );
+}
+
+fn store_target_pixels(pixels: &[u32], image: &mut RgbaImage) {
+    for (dst, pixel) in image.chunks_exact_mut(4).zip(pixels.iter().copied()) {
+        let [a, r, g, b] = pixel.to_be_bytes();
+        dst.copy_from_slice(&[r, g, b, a]);
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn prepare_objects(
     frame: &RenderFrame,
+    ops: &[DrawOp],
     default_proj: Matrix4,
     textures: &(impl TextureLookup + Sync),
     width: usize,
@@ -314,7 +545,7 @@ fn prepare_objects(
     mesh_vertices.clear();
     tmesh_vertices.clear();
 
-    for op in &frame.ops {
+    for op in ops {
         match *op {
             DrawOp::Sprite(run) => {
                 let projection = frame
@@ -446,6 +677,15 @@ fn prepare_objects(
                     });
                 }
             }
+            DrawOp::Effect(run) => {
+                if run.tint[3] > 0.0
+                    && render_target_slot(run.texture_handle).is_some()
+                    && textures.software_texture(run.texture_handle).is_some()
+                {
+                    prepared.push(PreparedObject::Effect(run));
+                }
+            }
+            DrawOp::BeginTarget(_) | DrawOp::EndTarget => {}
         }
     }
 }
@@ -592,6 +832,20 @@ fn draw_rows(
                     buffer,
                 )
             }
+            PreparedObject::Effect(run) => {
+                let Some(tex) = textures.software_texture(run.texture_handle) else {
+                    continue;
+                };
+                rasterize_effect(
+                    *run,
+                    tex,
+                    width,
+                    height,
+                    stripe_y_start,
+                    stripe_y_end,
+                    buffer,
+                )
+            }
         };
         vertices_drawn = vertices_drawn.saturating_add(drawn);
     }
@@ -613,6 +867,11 @@ pub fn set_default_projection(state: &mut State, projection: Matrix4) {
     state.projection = projection;
 }
 
+pub fn release_render_targets(state: &mut State) {
+    state.render_targets = HashMap::new();
+    state.target_pixels = Vec::new();
+}
+
 pub fn cleanup(_state: &mut State) {
     info!("Software renderer backend cleanup.");
 }
@@ -1296,6 +1555,125 @@ fn rasterize_triangle_color(
     }
 }
 
+/// Composites `tex` over the whole pass through `run.effect`.
+fn rasterize_effect(
+    run: EffectRun,
+    tex: &Texture,
+    width: usize,
+    height: usize,
+    stripe_y_start: usize,
+    stripe_y_end: usize,
+    buffer: &mut [u32],
+) -> u32 {
+    if width == 0 || height == 0 || stripe_y_start >= stripe_y_end {
+        return 0;
+    }
+    if !run.effect.is_builtin() {
+        static WARNED: std::sync::Once = std::sync::Once::new();
+        WARNED.call_once(|| {
+            warn!("Software renderer cannot run user shader programs; drawing them unchanged.");
+        });
+    }
+    let add = matches!(run.blend, BlendMode::Add);
+    let tint = run.tint;
+    for y in stripe_y_start..stripe_y_end.min(height) {
+        let v = (y as f32 + 0.5) / height as f32;
+        let row = &mut buffer[(y - stripe_y_start) * width..][..width];
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let u = (x as f32 + 0.5) / width as f32;
+            let shaded = shade_effect(run.effect, &tex.image, tex.sampler, u, v);
+            let sa = clamp01(shaded[3] * tint[3]);
+            if sa <= 0.0 {
+                continue;
+            }
+            let sr = clamp01(shaded[0] * tint[0]);
+            let sg = clamp01(shaded[1] * tint[1]);
+            let sb = clamp01(shaded[2] * tint[2]);
+            *pixel = if add {
+                blend_add(*pixel, sr, sg, sb, sa)
+            } else {
+                blend_src_over(*pixel, sr, sg, sb, sa)
+            };
+        }
+    }
+    4
+}
+
+/// Reference implementation of the built-in effect programs. The GPU shaders
+/// mirror it tap for tap; `u`/`v` address the source with row 0 at the top.
+/// User programs have no reference and sample the source unchanged.
+fn shade_effect(
+    effect: ShaderEffect,
+    image: &RgbaImage,
+    sampler: SamplerDesc,
+    u: f32,
+    v: f32,
+) -> [f32; 4] {
+    const BLUR_WEIGHTS: [f32; 5] = [1.0, 4.0, 6.0, 4.0, 1.0];
+    const LUMA: [f32; 3] = [0.2126, 0.7152, 0.0722];
+
+    let tex_w = image.width().max(1) as usize;
+    let tex_h = image.height().max(1) as usize;
+    let texel = [1.0 / tex_w as f32, 1.0 / tex_h as f32];
+    let sample = |u: f32, v: f32| {
+        sample_tex_linear(image.as_raw(), tex_w, tex_h, u, v, sampler).unwrap_or([0.0; 4])
+    };
+    match effect {
+        ShaderEffect::Blur { radius } => {
+            let step = radius * 0.5;
+            let mut sum = [0.0; 4];
+            for (j, weight_y) in BLUR_WEIGHTS.iter().enumerate() {
+                let dv = (j as f32 - 2.0) * step * texel[1];
+                for (i, weight_x) in BLUR_WEIGHTS.iter().enumerate() {
+                    let du = (i as f32 - 2.0) * step * texel[0];
+                    let weight = weight_x * weight_y / 256.0;
+                    let color = sample(u + du, v + dv);
+                    for (total, channel) in sum.iter_mut().zip(color) {
+                        *total = channel.mul_add(weight, *total);
+                    }
+                }
+            }
+            sum
+        }
+        ShaderEffect::ChromaticAberration { offset } => {
+            let du = offset[0] * texel[0];
+            let dv = offset[1] * texel[1];
+            let r = sample(u + du, v + dv);
+            let g = sample(u, v);
+            let b = sample(u - du, v - dv);
+            [r[0], g[1], b[2], r[3].max(g[3]).max(b[3])]
+        }
+        ShaderEffect::ColorGrade {
+            saturation,
+            contrast,
+            brightness,
+        } => {
+            let color = sample(u, v);
+            let luma = LUMA[2].mul_add(color[2], LUMA[0].mul_add(color[0], LUMA[1] * color[1]));
+            let grade = |channel: f32| {
+                clamp01(
+                    ((channel - luma).mul_add(saturation, luma) - 0.5).mul_add(contrast, 0.5)
+                        + brightness,
+                )
+            };
+            [grade(color[0]), grade(color[1]), grade(color[2]), color[3]]
+        }
+        ShaderEffect::Wave {
+            amplitude,
+            wavelength,
+            phase,
+        } => {
+            if wavelength <= 0.0 {
+                return sample(u, v);
+            }
+            let y = v * tex_h as f32;
+            let shift = amplitude * (std::f32::consts::TAU * y / wavelength + phase).sin();
+            sample(shift.mul_add(texel[0], u), v)
+        }
+        ShaderEffect::Custom { .. } => sample(u, v),
+    }
+}
+
 #[inline(always)]
 fn wrap_uv(u: f32, wrap: SamplerWrap) -> f32 {
     match wrap {
@@ -1740,7 +2118,7 @@ mod tests {
     use deadlib_render_core::{
         INVALID_TMESH_CACHE_KEY, MeshRun, MeshVertex, SpriteInstanceRaw, SpriteRun,
         TexturedMeshGeometry, TexturedMeshInstanceRaw, TexturedMeshRun, TexturedMeshVertex,
-        TexturedMeshVertices,
+        TexturedMeshVertices, render_target_handle,
     };
     use glam::Vec3;
     use image::Rgba;
@@ -1778,6 +2156,7 @@ mod tests {
         let mut prepared_tmesh = Vec::with_capacity(MESH_STAGE_VERTEX_CAP);
         prepare_objects(
             &frame,
+            &frame.ops,
             fallback,
             &textures,
             WIDTH,
@@ -1799,6 +2178,7 @@ mod tests {
         );
         prepare_objects(
             &frame,
+            &frame.ops,
             fallback,
             &textures,
             WIDTH,
@@ -1847,6 +2227,7 @@ mod tests {
 
         prepare_objects(
             &frame,
+            &frame.ops,
             Matrix4::IDENTITY,
             &textures,
             WIDTH,
@@ -1873,6 +2254,368 @@ mod tests {
         );
     }
 
+    #[test]
+    fn render_target_composite_matches_direct_drawing() {
+        let mut textures = test_textures();
+        for pixel in textures.texture.image.pixels_mut() {
+            pixel.0[3] = 255;
+        }
+        let camera = ortho_for_window(WIDTH as u32, HEIGHT as u32);
+        let content = DrawOp::Sprite(SpriteRun {
+            instance_start: 0,
+            instance_count: 1,
+            blend: BlendMode::Alpha,
+            texture_handle: TEXTURE_HANDLE,
+            camera: 0,
+        });
+        let direct = RenderFrame {
+            clear_color: [0.025, 0.05, 0.075, 1.0],
+            cameras: vec![camera],
+            sprite_instances: vec![sprite([-20.0, 10.0], 0.21, 1.0)],
+            mesh_vertices: Vec::new(),
+            tmesh_instances: Vec::new(),
+            tmesh_geometries: Vec::new(),
+            ops: vec![content],
+        };
+        let pass = RenderTargetPass {
+            slot: 2,
+            width: WIDTH as u32,
+            height: HEIGHT as u32,
+            clear: true,
+        };
+        let mut composited = direct.clone();
+        composited.sprite_instances.push(SpriteInstanceRaw {
+            center: [0.0, 0.0, 0.0, 1.0],
+            size: [
+                LOGICAL_HEIGHT * WIDTH as f32 / HEIGHT as f32,
+                LOGICAL_HEIGHT,
+            ],
+            rot_sin_cos: [0.0, 1.0],
+            tint: [1.0; 4],
+            uv_scale: [1.0; 2],
+            uv_offset: [0.0; 2],
+            local_offset: [0.0; 2],
+            local_offset_rot_sin_cos: [0.0, 1.0],
+            edge_fade: [0.0; 4],
+            texture_mask: 0.0,
+        });
+        composited.ops = vec![
+            DrawOp::BeginTarget(pass),
+            content,
+            DrawOp::EndTarget,
+            DrawOp::Sprite(SpriteRun {
+                instance_start: 1,
+                instance_count: 1,
+                blend: BlendMode::Alpha,
+                texture_handle: render_target_handle(pass.slot),
+                camera: 0,
+            }),
+        ];
+
+        let direct_pixels = render_software_frame(&direct, &textures, &mut HashMap::new());
+        let mut targets = HashMap::new();
+        let composited_pixels = render_software_frame(&composited, &textures, &mut targets);
+
+        assert_eq!(
+            targets[&pass.slot].image.dimensions(),
+            (pass.width, pass.height)
+        );
+        let clear = pack_rgba(direct.clear_color);
+        assert!(direct_pixels.iter().any(|pixel| *pixel != clear));
+        for (direct, composited) in direct_pixels.iter().zip(&composited_pixels) {
+            for (a, b) in direct
+                .to_be_bytes()
+                .into_iter()
+                .zip(composited.to_be_bytes())
+            {
+                assert!(a.abs_diff(b) <= 1, "{direct:08x} != {composited:08x}");
+            }
+        }
+    }
+
+    #[test]
+    fn render_target_pass_skips_sampling_its_own_target() {
+        let textures = test_textures();
+        let pass = RenderTargetPass {
+            slot: 0,
+            width: 16,
+            height: 16,
+            clear: false,
+        };
+        let mut frame = mixed_frame();
+        frame.ops = vec![
+            DrawOp::BeginTarget(pass),
+            DrawOp::Sprite(SpriteRun {
+                instance_start: 0,
+                instance_count: 1,
+                blend: BlendMode::Alpha,
+                texture_handle: render_target_handle(pass.slot),
+                camera: 0,
+            }),
+            DrawOp::EndTarget,
+        ];
+        let mut targets = HashMap::new();
+        targets.insert(
+            pass.slot,
+            Texture {
+                image: RgbaImage::from_pixel(16, 16, Rgba([10, 20, 30, 40])),
+                sampler: RENDER_TARGET_SAMPLER,
+            },
+        );
+
+        render_software_frame(&frame, &textures, &mut targets);
+
+        assert!(
+            targets[&pass.slot]
+                .image
+                .pixels()
+                .all(|pixel| pixel.0 == [10, 20, 30, 40])
+        );
+    }
+
+    #[test]
+    fn render_target_trail_accumulates_over_two_frames() {
+        let textures = TestTextures {
+            texture: Texture {
+                image: RgbaImage::from_pixel(8, 8, Rgba([200, 100, 50, 255])),
+                sampler: RENDER_TARGET_SAMPLER,
+            },
+        };
+        let logical_width = LOGICAL_HEIGHT * WIDTH as f32 / HEIGHT as f32;
+        let instance = |center: [f32; 2], size: [f32; 2]| SpriteInstanceRaw {
+            center: [center[0], center[1], 0.0, 1.0],
+            size,
+            rot_sin_cos: [0.0, 1.0],
+            tint: [1.0; 4],
+            uv_scale: [1.0; 2],
+            uv_offset: [0.0; 2],
+            local_offset: [0.0; 2],
+            local_offset_rot_sin_cos: [0.0, 1.0],
+            edge_fade: [0.0; 4],
+            texture_mask: 0.0,
+        };
+        let run = |instance_start, texture_handle| {
+            DrawOp::Sprite(SpriteRun {
+                instance_start,
+                instance_count: 1,
+                blend: BlendMode::Alpha,
+                texture_handle,
+                camera: 0,
+            })
+        };
+        let pass = |slot, clear| RenderTargetPass {
+            slot,
+            width: WIDTH as u32,
+            height: HEIGHT as u32,
+            clear,
+        };
+        let (trail, copy) = (0, 1);
+        let left = (WIDTH as u32 / 2 - 17, HEIGHT as u32 / 2);
+        let right = (WIDTH as u32 / 2 + 17, HEIGHT as u32 / 2);
+
+        // A preserved target draws over itself; a feedback target redraws
+        // last frame from a copy taken before its pass.
+        for feedback in [false, true] {
+            let mut targets = HashMap::new();
+            for (frame_index, x) in [-100.0, 100.0].into_iter().enumerate() {
+                let ops = if feedback {
+                    vec![
+                        DrawOp::BeginTarget(pass(copy, true)),
+                        run(0, render_target_handle(trail)),
+                        DrawOp::EndTarget,
+                        DrawOp::BeginTarget(pass(trail, true)),
+                        run(0, render_target_handle(copy)),
+                        run(1, TEXTURE_HANDLE),
+                        DrawOp::EndTarget,
+                    ]
+                } else {
+                    vec![
+                        DrawOp::BeginTarget(pass(trail, false)),
+                        run(1, TEXTURE_HANDLE),
+                        DrawOp::EndTarget,
+                    ]
+                };
+                let frame = RenderFrame {
+                    clear_color: [0.0, 0.0, 0.0, 1.0],
+                    cameras: vec![ortho_for_window(WIDTH as u32, HEIGHT as u32)],
+                    sprite_instances: vec![
+                        instance([0.0, 0.0], [logical_width, LOGICAL_HEIGHT]),
+                        instance([x, 0.0], [60.0, 60.0]),
+                    ],
+                    mesh_vertices: Vec::new(),
+                    tmesh_instances: Vec::new(),
+                    tmesh_geometries: Vec::new(),
+                    ops,
+                };
+
+                render_software_frame(&frame, &textures, &mut targets);
+
+                let image = &targets[&trail].image;
+                assert_eq!(image.get_pixel(left.0, left.1).0, [200, 100, 50, 255]);
+                let right_pixel = image.get_pixel(right.0, right.1).0;
+                if frame_index == 0 {
+                    assert_eq!(right_pixel[3], 0, "feedback: {feedback}");
+                } else {
+                    assert_eq!(right_pixel, [200, 100, 50, 255], "feedback: {feedback}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shader_effects_are_identity_at_neutral_params() {
+        let image = RgbaImage::from_fn(8, 6, |x, y| {
+            Rgba([(x * 30) as u8, (y * 40) as u8, (x * 7 + y * 11) as u8, 200])
+        });
+        let neutral = [
+            ShaderEffect::Blur { radius: 0.0 },
+            ShaderEffect::ChromaticAberration { offset: [0.0; 2] },
+            ShaderEffect::ColorGrade {
+                saturation: 1.0,
+                contrast: 1.0,
+                brightness: 0.0,
+            },
+            ShaderEffect::Wave {
+                amplitude: 0.0,
+                wavelength: 16.0,
+                phase: 0.0,
+            },
+            ShaderEffect::Custom {
+                program: ShaderEffect::NAMES.len() as u32,
+                params: [1.0; 4],
+            },
+        ];
+        for y in 0..6 {
+            for x in 0..8 {
+                let u = (x as f32 + 0.5) / 8.0;
+                let v = (y as f32 + 0.5) / 6.0;
+                let expected = image.get_pixel(x, y).0.map(|c| f32::from(c) * U8_TO_F32);
+                for effect in neutral {
+                    let shaded = shade_effect(effect, &image, RENDER_TARGET_SAMPLER, u, v);
+                    for (a, b) in expected.iter().zip(shaded) {
+                        assert!((a - b).abs() < 1e-4, "{effect:?} at ({x}, {y})");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shader_effects_move_channels_and_grade_colors() {
+        let image = RgbaImage::from_fn(3, 1, |x, _| match x {
+            0 => Rgba([255, 0, 0, 255]),
+            1 => Rgba([0, 255, 0, 255]),
+            _ => Rgba([0, 0, 255, 255]),
+        });
+        let u = 0.5;
+        let split = shade_effect(
+            ShaderEffect::ChromaticAberration { offset: [1.0, 0.0] },
+            &image,
+            RENDER_TARGET_SAMPLER,
+            u,
+            0.5,
+        );
+        let close = |a: [f32; 4], b: [f32; 4]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-4);
+        assert!(close(split, [0.0, 1.0, 0.0, 1.0]), "{split:?}");
+        let swapped = shade_effect(
+            ShaderEffect::ChromaticAberration {
+                offset: [-1.0, 0.0],
+            },
+            &image,
+            RENDER_TARGET_SAMPLER,
+            u,
+            0.5,
+        );
+        assert!(close(swapped, [1.0; 4]), "{swapped:?}");
+
+        let grey = shade_effect(
+            ShaderEffect::ColorGrade {
+                saturation: 0.0,
+                contrast: 1.0,
+                brightness: 0.0,
+            },
+            &image,
+            RENDER_TARGET_SAMPLER,
+            u,
+            0.5,
+        );
+        assert!(grey[..3].iter().all(|c| (c - 0.7152).abs() < 1e-4));
+
+        let blurred = shade_effect(
+            ShaderEffect::Blur { radius: 2.0 },
+            &image,
+            RENDER_TARGET_SAMPLER,
+            u,
+            0.5,
+        );
+        assert!((blurred[0] - 5.0 / 16.0).abs() < 1e-4);
+        assert!((blurred[1] - 6.0 / 16.0).abs() < 1e-4);
+        assert!((blurred[2] - 5.0 / 16.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn effect_composite_blends_over_the_whole_pass() {
+        let tex = Texture {
+            image: RgbaImage::from_pixel(4, 4, Rgba([100, 150, 200, 255])),
+            sampler: RENDER_TARGET_SAMPLER,
+        };
+        let run = EffectRun {
+            texture_handle: render_target_handle(0),
+            effect: ShaderEffect::ColorGrade {
+                saturation: 1.0,
+                contrast: 1.0,
+                brightness: 0.5,
+            },
+            tint: [1.0, 1.0, 1.0, 0.5],
+            blend: BlendMode::Alpha,
+        };
+        let mut pixels = vec![pack_rgba([0.0, 0.0, 0.0, 1.0]); 8 * 4];
+
+        assert_eq!(rasterize_effect(run, &tex, 8, 4, 0, 4, &mut pixels), 4);
+
+        let expected = pack_rgba([(100.0 / 255.0 + 0.5) * 0.5, 0.5, 0.5, 1.0]);
+        assert!(pixels.iter().all(|pixel| *pixel == expected));
+    }
+
+    /// Mirrors `draw` without a surface: target passes, then the screen ops.
+    fn render_software_frame(
+        frame: &RenderFrame,
+        textures: &(impl TextureLookup + Sync),
+        targets: &mut HashMap<u32, Texture>,
+    ) -> Vec<u32> {
+        let mut prepared = Vec::new();
+        let mut prepared_mesh = Vec::new();
+        let mut prepared_tmesh = Vec::new();
+        draw_render_targets(frame, textures, frame.cameras[0], targets);
+        let lookup = TargetTextures { textures, targets };
+        let mut screen = vec![pack_rgba(frame.clear_color); WIDTH * HEIGHT];
+        prepare_objects(
+            frame,
+            frame.screen_ops(),
+            frame.cameras[0],
+            &lookup,
+            WIDTH,
+            HEIGHT,
+            &mut prepared,
+            &mut prepared_mesh,
+            &mut prepared_tmesh,
+            false,
+        );
+        draw_rows(
+            frame,
+            &prepared,
+            &prepared_mesh,
+            &prepared_tmesh,
+            &lookup,
+            WIDTH,
+            HEIGHT,
+            0,
+            HEIGHT,
+            &mut screen,
+        );
+        screen
+    }
+
     fn render_prepared_stripes(
         frame: &RenderFrame,
         prepared: &[PreparedObject],
@@ -5,11 +5,12 @@ use ash::{
     vk,
 };
 use deadlib_render_core::{
-    BlendMode, CameraUploadCache, ClockDomainTrace, DrawOp, DrawStats, FastU64Map, MeshVertex,
-    PresentModePolicy, PresentModeTrace, PresentStats, RenderFrame, SamplerDesc, SamplerFilter,
-    SamplerWrap, SpriteInstanceRaw as InstanceData, TMeshCacheKey, TextureHandle,
+    BlendMode, CameraUploadCache, ClockDomainTrace, DrawOp, DrawStats, EffectUniforms, FastU64Map,
+    MeshVertex, PresentModePolicy, PresentModeTrace, PresentStats, RenderFrame, SamplerDesc,
+    SamplerFilter, SamplerWrap, SpriteInstanceRaw as InstanceData, TMeshCacheKey, TextureHandle,
     TexturedMeshBufferCache, TexturedMeshInstanceRaw as TexturedMeshInstanceGpu,
-    TexturedMeshUploads, TexturedMeshVertex, draw_storage_stats, resolve_textured_meshes,
+    TexturedMeshUploads, TexturedMeshVertex, draw_storage_stats, render_target_slot,
+    resolve_textured_meshes,
 };
 use glam::Mat4 as Matrix4;
 use image::RgbaImage;
@@ -32,6 +33,11 @@ const VULKAN_PRESENT_DISPLAY_TIMING_TELEMETRY: bool = false;
 const VULKAN_TMESH_CACHE_MAX_BYTES: usize = 16 * 1024 * 1024;
 const LOGICAL_HEIGHT: f32 = 480.0;
 const DESIGN_WIDTH_16_9: f32 = 854.0;
+const RENDER_TARGET_SAMPLER: SamplerDesc = SamplerDesc {
+    filter: SamplerFilter::Linear,
+    wrap: SamplerWrap::Clamp,
+    mipmaps: false,
+};
 #[cfg(windows)]
 static QPC_FREQ_HZ: std::sync::LazyLock<Option<u64>> = std::sync::LazyLock::new(qpc_freq_hz);
 
@@ -52,6 +58,13 @@ fn projection_push_constant_range() -> vk::PushConstantRange {
         .size(std::mem::size_of::<ProjPush>() as u32)
 }
 
+fn effect_push_constant_range() -> vk::PushConstantRange {
+    vk::PushConstantRange::default()
+        .stage_flags(vk::ShaderStageFlags::FRAGMENT)
+        .offset(0)
+        .size(std::mem::size_of::<EffectUniforms>() as u32)
+}
+
 struct PipelinePair {
     layout: vk::PipelineLayout,
     pipe: vk::Pipeline,
@@ -88,6 +101,41 @@ impl Drop for Texture {
     }
 }
 
+// An offscreen color target in the swapchain format, so the surface pipelines
+// stay render-pass compatible. It rests in SHADER_READ_ONLY_OPTIMAL between passes.
+struct RenderTarget {
+    texture: Texture,
+    framebuffer: vk::Framebuffer,
+    size: (u32, u32),
+    initialized: bool,
+}
+
+impl Drop for RenderTarget {
+    fn drop(&mut self) {
+        // SAFETY: The framebuffer was created for this target on `texture.device`, and callers
+        // wait for the device to go idle before dropping targets.
+        unsafe {
+            self.texture
+                .device
+                .destroy_framebuffer(self.framebuffer, None);
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct TargetRenderPasses {
+    clear: vk::RenderPass,
+    load: vk::RenderPass,
+}
+
+#[derive(Clone, Copy)]
+struct RingBases {
+    instance: u32,
+    vertex: u32,
+    tmesh_vertex: u32,
+    tmesh_instance: u32,
+}
+
 struct BufferResource {
     buffer: vk::Buffer,
     memory: vk::DeviceMemory,
@@ -189,12 +237,16 @@ pub struct State {
     command_pool: vk::CommandPool,
     swapchain_resources: SwapchainResources,
     render_pass: vk::RenderPass,
+    target_render_passes: Option<TargetRenderPasses>,
+    render_targets: HashMap<u32, RenderTarget>,
     sprite_pipeline_layout: vk::PipelineLayout,
     sprite_pipeline: vk::Pipeline,
     mesh_pipeline_layout: vk::PipelineLayout,
     mesh_pipeline: vk::Pipeline,
     textured_mesh_pipeline_layout: vk::PipelineLayout,
     textured_mesh_pipeline: vk::Pipeline,
+    effect_pipeline_layout: vk::PipelineLayout,
+    effect_pipeline: vk::Pipeline,
     vertex_buffer: Option<BufferResource>,
     index_buffer: Option<BufferResource>,
     descriptor_set_layout: vk::DescriptorSetLayout,
@@ -308,6 +360,15 @@ pub fn init(
         descriptor_set_layout,
         BlendMode::Alpha,
     )?;
+    let PipelinePair {
+        layout: effect_pipeline_layout,
+        pipe: effect_pipeline,
+    } = create_effect_pipeline(
+        device.as_ref().unwrap(),
+        render_pass,
+        descriptor_set_layout,
+        BlendMode::Alpha,
+    )?;
 
     let command_buffers =
         create_command_buffers(device.as_ref().unwrap(), command_pool, MAX_FRAMES_IN_FLIGHT)?;
@@ -338,12 +399,16 @@ pub fn init(
         command_pool,
         swapchain_resources,
         render_pass,
+        target_render_passes: None,
+        render_targets: HashMap::new(),
         sprite_pipeline_layout,
         sprite_pipeline,
         mesh_pipeline_layout,
         mesh_pipeline,
         textured_mesh_pipeline_layout,
         textured_mesh_pipeline,
+        effect_pipeline_layout,
+        effect_pipeline,
         vertex_buffer: None,
         index_buffer: None,
         descriptor_set_layout,
@@ -697,6 +762,101 @@ fn create_mesh_pipeline(
     Ok(PipelinePair { layout, pipe })
 }
 
+fn create_effect_pipeline(
+    device: &Device,
+    render_pass: vk::RenderPass,
+    set_layout: vk::DescriptorSetLayout,
+    mode: BlendMode,
+) -> Result<PipelinePair, Box<dyn Error>> {
+    let vert_shader_code = include_bytes!(concat!(env!("OUT_DIR"), "/vulkan_effect.vert.spv"));
+    let frag_shader_code = include_bytes!(concat!(env!("OUT_DIR"), "/vulkan_effect.frag.spv"));
+    let vert_module = create_shader_module(device, vert_shader_code)?;
+    let frag_module = create_shader_module(device, frag_shader_code)?;
+    let main_name = c"main";
+
+    let shader_stages = [
+        vk::PipelineShaderStageCreateInfo::default()
+            .stage(vk::ShaderStageFlags::VERTEX)
+            .module(vert_module)
+            .name(main_name),
+        vk::PipelineShaderStageCreateInfo::default()
+            .stage(vk::ShaderStageFlags::FRAGMENT)
+            .module(frag_module)
+            .name(main_name),
+    ];
+
+    // Vertex inputs: binding 0 (unit quad) only
+    let (binding_descriptions, attribute_descriptions) =
+        vertex_input_descriptions_textured_instanced();
+    let vertex_input_info = vk::PipelineVertexInputStateCreateInfo::default()
+        .vertex_binding_descriptions(&binding_descriptions[..1])
+        .vertex_attribute_descriptions(&attribute_descriptions[..2]);
+
+    let input_assembly = vk::PipelineInputAssemblyStateCreateInfo::default()
+        .topology(vk::PrimitiveTopology::TRIANGLE_LIST);
+
+    let viewport_state = vk::PipelineViewportStateCreateInfo::default()
+        .viewport_count(1)
+        .scissor_count(1);
+
+    let rasterizer = vk::PipelineRasterizationStateCreateInfo::default()
+        .polygon_mode(vk::PolygonMode::FILL)
+        .line_width(1.0)
+        .cull_mode(vk::CullModeFlags::NONE)
+        .front_face(vk::FrontFace::COUNTER_CLOCKWISE);
+
+    let multisampling = vk::PipelineMultisampleStateCreateInfo::default()
+        .rasterization_samples(vk::SampleCountFlags::TYPE_1);
+
+    let color_blend_attachment = color_blend_for(mode);
+    let color_blending = vk::PipelineColorBlendStateCreateInfo::default()
+        .attachments(std::slice::from_ref(&color_blend_attachment));
+
+    let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+    let dynamic_state =
+        vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dynamic_states);
+
+    let push_constant_range = effect_push_constant_range();
+
+    let pipeline_layout_info = vk::PipelineLayoutCreateInfo::default()
+        .set_layouts(std::slice::from_ref(&set_layout))
+        .push_constant_ranges(std::slice::from_ref(&push_constant_range));
+
+    // SAFETY: The descriptor set layout and push-constant range are valid for this pipeline, and
+    // the create info borrows only stack data for the duration of the call.
+    let layout = unsafe { device.create_pipeline_layout(&pipeline_layout_info, None)? };
+
+    let pipeline_info = vk::GraphicsPipelineCreateInfo::default()
+        .stages(&shader_stages)
+        .vertex_input_state(&vertex_input_info)
+        .input_assembly_state(&input_assembly)
+        .viewport_state(&viewport_state)
+        .rasterization_state(&rasterizer)
+        .multisample_state(&multisampling)
+        .color_blend_state(&color_blending)
+        .dynamic_state(&dynamic_state)
+        .layout(layout)
+        .render_pass(render_pass)
+        .subpass(0);
+
+    // SAFETY: All referenced shader modules, pipeline layout, and render pass are live and owned
+    // by the caller; Vulkan copies the provided create info before returning.
+    let pipe = unsafe {
+        device
+            .create_graphics_pipelines(vk::PipelineCache::null(), &[pipeline_info], None)
+            .map_err(|e| e.1)?[0]
+    };
+
+    // SAFETY: The pipeline has already been created and no longer borrows the temporary shader
+    // modules, so they can be destroyed immediately on the same device.
+    unsafe {
+        device.destroy_shader_module(vert_module, None);
+        device.destroy_shader_module(frag_module, None);
+    }
+
+    Ok(PipelinePair { layout, pipe })
+}
+
 fn create_textured_mesh_pipeline(
     device: &Device,
     render_pass: vk::RenderPass,
@@ -1547,6 +1707,7 @@ pub fn draw(
     } else {
         None
     };
+    ensure_render_targets(state, frame)?;
     stats.backend_upload_us = elapsed_us_since(backend_upload_started);
 
     // SAFETY: We wait on the current frame fence before reusing its command buffer or writing into
@@ -1663,6 +1824,14 @@ pub fn draw(
             .saturating_add(elapsed_us_since(backend_upload_started));
 
         let backend_record_started = Instant::now();
+        let bases = RingBases {
+            instance: base_first_instance.unwrap_or(0),
+            vertex: base_first_vertex.unwrap_or(0),
+            tmesh_vertex: base_first_tmesh_vertex.unwrap_or(0),
+            tmesh_instance: base_first_tmesh_instance.unwrap_or(0),
+        };
+        let mut vertices_drawn =
+            record_render_target_passes(&device, cmd, state, frame, textures, bases);
         let c = frame.clear_color;
         let clear_value = vk::ClearValue {
             color: vk::ClearColorValue {
@@ -1694,11 +1863,266 @@ pub fn draw(
         };
         device.cmd_set_scissor(cmd, 0, &[sc]);
 
+        vertices_drawn = vertices_drawn.saturating_add(record_ops(
+            &device,
+            cmd,
+            state,
+            frame,
+            frame.screen_ops(),
+            textures,
+            bases,
+            None,
+        ));
+
+        device.cmd_end_render_pass(cmd);
+        let screenshot_staging = if state.screenshot_requested {
+            state.screenshot_requested = false;
+            state.captured_frame = None;
+            if state.swapchain_resources.supports_transfer_src {
+                let width = state.swapchain_resources.extent.width;
+                let height = state.swapchain_resources.extent.height;
+                let bytes_per_row = width as usize * 4;
+                let copy_size = (bytes_per_row * height as usize) as vk::DeviceSize;
+                let (staging_buffer, staging_memory) = create_gpu_buffer(
+                    &state.instance,
+                    &device,
+                    state.pdevice,
+                    copy_size,
+                    vk::BufferUsageFlags::TRANSFER_DST,
+                    vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+                )?;
+                let swap_image = state.swapchain_resources._images[image_index as usize];
+                let subresource = vk::ImageSubresourceRange::default()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .base_mip_level(0)
+                    .level_count(1)
+                    .base_array_layer(0)
+                    .layer_count(1);
+                let to_transfer = vk::ImageMemoryBarrier::default()
+                    .src_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+                    .dst_access_mask(vk::AccessFlags::TRANSFER_READ)
+                    .old_layout(vk::ImageLayout::PRESENT_SRC_KHR)
+                    .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+                    .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                    .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                    .image(swap_image)
+                    .subresource_range(subresource);
+                device.cmd_pipeline_barrier(
+                    cmd,
+                    vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                    vk::PipelineStageFlags::TRANSFER,
+                    vk::DependencyFlags::empty(),
+                    &[],
+                    &[],
+                    std::slice::from_ref(&to_transfer),
+                );
+
+                let copy_region = vk::BufferImageCopy::default()
+                    .buffer_offset(0)
+                    .buffer_row_length(0)
+                    .buffer_image_height(0)
+                    .image_subresource(vk::ImageSubresourceLayers {
+                        aspect_mask: vk::ImageAspectFlags::COLOR,
+                        mip_level: 0,
+                        base_array_layer: 0,
+                        layer_count: 1,
+                    })
+                    .image_extent(vk::Extent3D {
+                        width,
+                        height,
+                        depth: 1,
+                    });
+                device.cmd_copy_image_to_buffer(
+                    cmd,
+                    swap_image,
+                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    staging_buffer,
+                    std::slice::from_ref(&copy_region),
+                );
+
+                let to_present = vk::ImageMemoryBarrier::default()
+                    .src_access_mask(vk::AccessFlags::TRANSFER_READ)
+                    .dst_access_mask(vk::AccessFlags::MEMORY_READ)
+                    .old_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+                    .new_layout(vk::ImageLayout::PRESENT_SRC_KHR)
+                    .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                    .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                    .image(swap_image)
+                    .subresource_range(subresource);
+                device.cmd_pipeline_barrier(
+                    cmd,
+                    vk::PipelineStageFlags::TRANSFER,
+                    vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                    vk::DependencyFlags::empty(),
+                    &[],
+                    &[],
+                    std::slice::from_ref(&to_present),
+                );
+
+                Some((
+                    BufferResource {
+                        buffer: staging_buffer,
+                        memory: staging_memory,
+                    },
+                    width,
+                    height,
+                    state.swapchain_resources.format.format,
+                ))
+            } else {
+                warn!(
+                    "Vulkan swapchain does not support transfer-src usage; screenshot unavailable"
+                );
+                None
+            }
+        } else {
+            None
+        };
+        device.end_command_buffer(cmd)?;
+        stats.backend_record_us = elapsed_us_since(backend_record_started);
+
+        let wait = [state.image_available_semaphores[state.current_frame]];
+        let sig = [state.render_finished_semaphores[state.current_frame]];
+        let stages = [vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT];
+        let submit = vk::SubmitInfo::default()
+            .wait_semaphores(&wait)
+            .wait_dst_stage_mask(&stages)
+            .command_buffers(std::slice::from_ref(&cmd))
+            .signal_semaphores(&sig);
+        let submit_started = Instant::now();
+        device.queue_submit(state.queue, &[submit], fence)?;
+        stats.submit_us = elapsed_us_since(submit_started);
+
+        let submitted_present_id = next_present_id(state);
+        let present_times = [vk::PresentTimeGOOGLE::default().present_id(submitted_present_id)];
+        let mut present_times_info = vk::PresentTimesInfoGOOGLE::default().times(&present_times);
+        let mut present_info = vk::PresentInfoKHR::default()
+            .wait_semaphores(&sig)
+            .swapchains(std::slice::from_ref(&state.swapchain_resources.swapchain))
+            .image_indices(std::slice::from_ref(&image_index));
+        if state.present_telemetry.display_timing.is_some() {
+            present_info = present_info.push_next(&mut present_times_info);
+        }
+        let present_started = Instant::now();
+        let present_result = state
+            .swapchain_resources
+            .swapchain_loader
+            .queue_present(state.queue, &present_info);
+        stats.present_us = elapsed_us_since(present_started);
+        let present_suboptimal = match present_result {
+            Ok(suboptimal) => suboptimal || acquired_suboptimal,
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR | vk::Result::SUBOPTIMAL_KHR) => true,
+            Err(e) => return Err(e.into()),
+        };
+        if let Some(slot) = state
+            .present_telemetry
+            .cpu_image_present_ids
+            .get_mut(image_index as usize)
+        {
+            *slot = submitted_present_id;
+        }
+        calibrate_present_clock(state);
+        poll_past_presentation_timing(state);
+        state.last_submitted_present_id = submitted_present_id;
+        retire_completed_textures(state);
+        stats.present_stats = snapshot_present_stats(
+            state,
+            waited_for_image,
+            false,
+            false,
+            present_suboptimal,
+            submitted_present_id,
+        );
+        if present_suboptimal {
+            recreate_swapchain_and_dependents(state)?;
+        }
+        if apply_present_back_pressure && screenshot_staging.is_none() {
+            // Match the wgpu Vulkan pacing path: when the app is running
+            // uncapped, wait for this frame's GPU work to retire so the CPU
+            // cannot build a long queue of stale Mailbox presents.
+            let wait_started = Instant::now();
+            device.wait_for_fences(&[fence], true, u64::MAX)?;
+            let wait_us = elapsed_us_since(wait_started);
+            stats.gpu_wait_us = stats.gpu_wait_us.saturating_add(wait_us);
+            back_pressure_waited = wait_us >= VULKAN_BACK_PRESSURE_THRESHOLD_US;
+        }
+        if let Some((staging, width, height, format)) = screenshot_staging {
+            let wait_started = Instant::now();
+            device.wait_for_fences(&[fence], true, u64::MAX)?;
+            stats.gpu_wait_us = stats
+                .gpu_wait_us
+                .saturating_add(elapsed_us_since(wait_started));
+            queue_idle_waited = true;
+            let map_size = (width as usize * height as usize * 4) as vk::DeviceSize;
+            let mapped =
+                match device.map_memory(staging.memory, 0, map_size, vk::MemoryMapFlags::empty()) {
+                    Ok(ptr) => ptr,
+                    Err(e) => {
+                        destroy_buffer(&device, &staging);
+                        return Err(e.into());
+                    }
+                };
+            let src = std::slice::from_raw_parts(mapped.cast::<u8>(), map_size as usize);
+            let row_bytes = width as usize * 4;
+            let mut rgba = vec![0u8; row_bytes * height as usize];
+            let swap_rb = matches!(
+                format,
+                vk::Format::B8G8R8A8_UNORM | vk::Format::B8G8R8A8_SRGB
+            );
+            for y in 0..height as usize {
+                let src_row = y * row_bytes;
+                // Vulkan image copy rows are already top-to-bottom for this swapchain path.
+                let dst_row = y * row_bytes;
+                if swap_rb {
+                    let mut x = 0usize;
+                    while x < width as usize {
+                        let s = src_row + x * 4;
+                        let d = dst_row + x * 4;
+                        rgba[d] = src[s + 2];
+                        rgba[d + 1] = src[s + 1];
+                        rgba[d + 2] = src[s];
+                        rgba[d + 3] = src[s + 3];
+                        x += 1;
+                    }
+                } else {
+                    rgba[dst_row..dst_row + row_bytes]
+                        .copy_from_slice(&src[src_row..src_row + row_bytes]);
+                }
+            }
+            device.unmap_memory(staging.memory);
+            destroy_buffer(&device, &staging);
+            state.captured_frame = RgbaImage::from_raw(width, height, rgba);
+        }
+
+        stats.present_stats.applied_back_pressure = back_pressure_waited;
+        stats.present_stats.queue_idle_waited = queue_idle_waited;
+        state.current_frame = (state.current_frame + 1) % MAX_FRAMES_IN_FLIGHT;
+        stats.vertices = vertices_drawn;
+        Ok(stats)
+    }
+}
+
+/// Records one op span into the active render pass and returns the vertices drawn.
+#[allow(clippy::too_many_arguments)]
+fn record_ops(
+    device: &Device,
+    cmd: vk::CommandBuffer,
+    state: &State,
+    frame: &RenderFrame,
+    ops: &[DrawOp],
+    textures: &impl TextureLookup,
+    bases: RingBases,
+    active_target: Option<u32>,
+) -> u32 {
+    // SAFETY: `cmd` is recording inside a render pass compatible with the surface pipelines, and
+    // every buffer, pipeline, and descriptor set bound below is owned by `state` or `textures`
+    // and outlives the submission of this command buffer.
+    unsafe {
         enum Bound {
             None,
             Sprite,
             Mesh,
             TexturedMesh,
+            Effect,
         }
         let mut bound = Bound::None;
         let mut last_set = vk::DescriptorSet::null();
@@ -1707,13 +2131,16 @@ pub fn draw(
         let mut last_camera = CameraUploadCache::default();
         let mut tmesh_buffer_cache = TexturedMeshBufferCache::default();
         let mut vertices_drawn: u32 = 0;
-        for op in &frame.ops {
+        for op in ops {
             match op {
                 DrawOp::Sprite(run) => {
-                    let Some(set) = textures
-                        .vulkan_texture(run.texture_handle)
-                        .map(|texture| texture.descriptor_set)
-                    else {
+                    let Some(set) = resolve_texture(
+                        textures,
+                        &state.render_targets,
+                        run.texture_handle,
+                        active_target,
+                    )
+                    .map(|texture| texture.descriptor_set) else {
                         continue;
                     };
                     if !matches!(bound, Bound::Sprite) {
@@ -1762,7 +2189,7 @@ pub fn draw(
                         last_set = set;
                     }
 
-                    let first_instance = base_first_instance.unwrap_or(0) + run.instance_start;
+                    let first_instance = bases.instance + run.instance_start;
                     device.cmd_draw_indexed(cmd, 6, run.instance_count, 0, 0, first_instance);
                     vertices_drawn = vertices_drawn.saturating_add(4 * run.instance_count);
                 }
@@ -1797,7 +2224,7 @@ pub fn draw(
                         );
                     }
 
-                    let first_vertex = base_first_vertex.unwrap_or(0) + draw.vertex_start;
+                    let first_vertex = bases.vertex + draw.vertex_start;
                     device.cmd_draw(cmd, draw.vertex_count, 1, first_vertex, 0);
                     vertices_drawn = vertices_drawn.saturating_add(draw.vertex_count);
                 }
@@ -1805,10 +2232,13 @@ pub fn draw(
                     let Some(source) = state.uploads.source(draw.geometry) else {
                         continue;
                     };
-                    let Some(set) = textures
-                        .vulkan_texture(draw.texture_handle)
-                        .map(|texture| texture.descriptor_set_repeat)
-                    else {
+                    let Some(set) = resolve_texture(
+                        textures,
+                        &state.render_targets,
+                        draw.texture_handle,
+                        active_target,
+                    )
+                    .map(|texture| texture.descriptor_set_repeat) else {
                         continue;
                     };
                     if !matches!(bound, Bound::TexturedMesh) {
@@ -1873,10 +2303,9 @@ pub fn draw(
                     let first_vertex = if source.buffer_key().is_some() {
                         0
                     } else {
-                        base_first_tmesh_vertex.unwrap_or(0) + source.vertex_start()
+                        bases.tmesh_vertex + source.vertex_start()
                     };
-                    let first_instance =
-                        base_first_tmesh_instance.unwrap_or(0) + draw.instance_start;
+                    let first_instance = bases.tmesh_instance + draw.instance_start;
                     device.cmd_draw(
                         cmd,
                         source.vertex_count(),
@@ -1888,234 +2317,268 @@ pub fn draw(
                     vertices_drawn = vertices_drawn
                         .saturating_add(tri_count.saturating_mul(draw.instance_count));
                 }
+                DrawOp::Effect(run) => {
+                    let Some(set) = resolve_texture(
+                        textures,
+                        &state.render_targets,
+                        run.texture_handle,
+                        active_target,
+                    )
+                    .map(|texture| texture.descriptor_set) else {
+                        continue;
+                    };
+                    let source_size = render_target_slot(run.texture_handle)
+                        .and_then(|slot| state.render_targets.get(&slot))
+                        .map_or([1, 1], |target| [target.size.0, target.size.1]);
+                    if !matches!(bound, Bound::Effect) {
+                        device.cmd_bind_pipeline(
+                            cmd,
+                            vk::PipelineBindPoint::GRAPHICS,
+                            state.effect_pipeline,
+                        );
+                        let vb0 = state.vertex_buffer.as_ref().unwrap().buffer;
+                        device.cmd_bind_vertex_buffers(cmd, 0, &[vb0], &[0]);
+                        let ib = state.index_buffer.as_ref().unwrap().buffer;
+                        device.cmd_bind_index_buffer(cmd, ib, 0, vk::IndexType::UINT16);
+                        bound = Bound::Effect;
+                        last_set = vk::DescriptorSet::null();
+                        // The effect layout has no projection range, so the
+                        // next camera push has to be re-sent.
+                        last_camera = CameraUploadCache::default();
+                        tmesh_buffer_cache.reset();
+                    }
+                    if !run.effect.is_builtin() {
+                        static WARNED: std::sync::Once = std::sync::Once::new();
+                        WARNED.call_once(|| {
+                            warn!(
+                                "Vulkan shaders are built ahead of time; user shader programs draw unchanged."
+                            );
+                        });
+                    }
+                    device.cmd_push_constants(
+                        cmd,
+                        state.effect_pipeline_layout,
+                        vk::ShaderStageFlags::FRAGMENT,
+                        0,
+                        bytemuck::bytes_of(&EffectUniforms::new(run.builtin_only(), source_size)),
+                    );
+                    if last_set != set {
+                        device.cmd_bind_descriptor_sets(
+                            cmd,
+                            vk::PipelineBindPoint::GRAPHICS,
+                            state.effect_pipeline_layout,
+                            0,
+                            &[set],
+                            &[],
+                        );
+                        last_set = set;
+                    }
+                    device.cmd_draw_indexed(cmd, 6, 1, 0, 0, 0);
+                    vertices_drawn = vertices_drawn.saturating_add(4);
+                }
+                DrawOp::BeginTarget(_) | DrawOp::EndTarget => {}
             }
         }
-
-        device.cmd_end_render_pass(cmd);
-        let screenshot_staging = if state.screenshot_requested {
-            state.screenshot_requested = false;
-            state.captured_frame = None;
-            if state.swapchain_resources.supports_transfer_src {
-                let width = state.swapchain_resources.extent.width;
-                let height = state.swapchain_resources.extent.height;
-                let bytes_per_row = width as usize * 4;
-                let copy_size = (bytes_per_row * height as usize) as vk::DeviceSize;
-                let (staging_buffer, staging_memory) = create_gpu_buffer(
-                    &state.instance,
-                    &device,
-                    state.pdevice,
-                    copy_size,
-                    vk::BufferUsageFlags::TRANSFER_DST,
-                    vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
-                )?;
-                let swap_image = state.swapchain_resources._images[image_index as usize];
-                let subresource = vk::ImageSubresourceRange::default()
-                    .aspect_mask(vk::ImageAspectFlags::COLOR)
-                    .base_mip_level(0)
-                    .level_count(1)
-                    .base_array_layer(0)
-                    .layer_count(1);
-                let to_transfer = vk::ImageMemoryBarrier::default()
-                    .src_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
-                    .dst_access_mask(vk::AccessFlags::TRANSFER_READ)
-                    .old_layout(vk::ImageLayout::PRESENT_SRC_KHR)
-                    .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
-                    .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-                    .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-                    .image(swap_image)
-                    .subresource_range(subresource);
-                device.cmd_pipeline_barrier(
-                    cmd,
-                    vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
-                    vk::PipelineStageFlags::TRANSFER,
-                    vk::DependencyFlags::empty(),
-                    &[],
-                    &[],
-                    std::slice::from_ref(&to_transfer),
-                );
-
-                let copy_region = vk::BufferImageCopy::default()
-                    .buffer_offset(0)
-                    .buffer_row_length(0)
-                    .buffer_image_height(0)
-                    .image_subresource(vk::ImageSubresourceLayers {
-                        aspect_mask: vk::ImageAspectFlags::COLOR,
-                        mip_level: 0,
-                        base_array_layer: 0,
-                        layer_count: 1,
-                    })
-                    .image_extent(vk::Extent3D {
-                        width,
-                        height,
-                        depth: 1,
-                    });
-                device.cmd_copy_image_to_buffer(
-                    cmd,
-                    swap_image,
-                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
-                    staging_buffer,
-                    std::slice::from_ref(&copy_region),
-                );
-
-                let to_present = vk::ImageMemoryBarrier::default()
-                    .src_access_mask(vk::AccessFlags::TRANSFER_READ)
-                    .dst_access_mask(vk::AccessFlags::MEMORY_READ)
-                    .old_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
-                    .new_layout(vk::ImageLayout::PRESENT_SRC_KHR)
-                    .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-                    .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-                    .image(swap_image)
-                    .subresource_range(subresource);
-                device.cmd_pipeline_barrier(
-                    cmd,
-                    vk::PipelineStageFlags::TRANSFER,
-                    vk::PipelineStageFlags::BOTTOM_OF_PIPE,
-                    vk::DependencyFlags::empty(),
-                    &[],
-                    &[],
-                    std::slice::from_ref(&to_present),
-                );
-
-                Some((
-                    BufferResource {
-                        buffer: staging_buffer,
-                        memory: staging_memory,
-                    },
-                    width,
-                    height,
-                    state.swapchain_resources.format.format,
-                ))
-            } else {
-                warn!(
-                    "Vulkan swapchain does not support transfer-src usage; screenshot unavailable"
-                );
-                None
-            }
-        } else {
-            None
-        };
-        device.end_command_buffer(cmd)?;
-        stats.backend_record_us = elapsed_us_since(backend_record_started);
-
-        let wait = [state.image_available_semaphores[state.current_frame]];
-        let sig = [state.render_finished_semaphores[state.current_frame]];
-        let stages = [vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT];
-        let submit = vk::SubmitInfo::default()
-            .wait_semaphores(&wait)
-            .wait_dst_stage_mask(&stages)
-            .command_buffers(std::slice::from_ref(&cmd))
-            .signal_semaphores(&sig);
-        let submit_started = Instant::now();
-        device.queue_submit(state.queue, &[submit], fence)?;
-        stats.submit_us = elapsed_us_since(submit_started);
-
-        let submitted_present_id = next_present_id(state);
-        let present_times = [vk::PresentTimeGOOGLE::default().present_id(submitted_present_id)];
-        let mut present_times_info = vk::PresentTimesInfoGOOGLE::default().times(&present_times);
-        let mut present_info = vk::PresentInfoKHR::default()
-            .wait_semaphores(&sig)
-            .swapchains(std::slice::from_ref(&state.swapchain_resources.swapchain))
-            .image_indices(std::slice::from_ref(&image_index));
-        if state.present_telemetry.display_timing.is_some() {
-            present_info = present_info.push_next(&mut present_times_info);
-        }
-        let present_started = Instant::now();
-        let present_result = state
-            .swapchain_resources
-            .swapchain_loader
-            .queue_present(state.queue, &present_info);
-        stats.present_us = elapsed_us_since(present_started);
-        let present_suboptimal = match present_result {
-            Ok(suboptimal) => suboptimal || acquired_suboptimal,
-            Err(vk::Result::ERROR_OUT_OF_DATE_KHR | vk::Result::SUBOPTIMAL_KHR) => true,
-            Err(e) => return Err(e.into()),
+        vertices_drawn
+    }
+}
+
+/// Records this frame's offscreen passes ahead of the surface render pass.
+fn record_render_target_passes(
+    device: &Device,
+    cmd: vk::CommandBuffer,
+    state: &mut State,
+    frame: &RenderFrame,
+    textures: &impl TextureLookup,
+    bases: RingBases,
+) -> u32 {
+    let Some(passes) = state.target_render_passes else {
+        return 0;
+    };
+    let mut vertices_drawn = 0u32;
+    for (target_pass, ops) in frame.render_target_passes() {
+        let Some(target) = state.render_targets.get_mut(&target_pass.slot) else {
+            continue;
         };
-        if let Some(slot) = state
-            .present_telemetry
-            .cpu_image_present_ids
-            .get_mut(image_index as usize)
-        {
-            *slot = submitted_present_id;
+        // The load pass expects the resting shader-read layout, so a fresh
+        // target always clears on its first pass.
+        let clear = target_pass.clear || !target.initialized;
+        target.initialized = true;
+        let (width, height) = target.size;
+        let framebuffer = target.framebuffer;
+        let clear_value = vk::ClearValue {
+            color: vk::ClearColorValue { float32: [0.0; 4] },
+        };
+        let extent = vk::Extent2D { width, height };
+        let rp_info = vk::RenderPassBeginInfo::default()
+            .render_pass(if clear { passes.clear } else { passes.load })
+            .framebuffer(framebuffer)
+            .render_area(vk::Rect2D {
+                offset: vk::Offset2D::default(),
+                extent,
+            })
+            .clear_values(std::slice::from_ref(&clear_value));
+        // Same negative-height viewport as the surface pass, so row 0 of the
+        // target holds the top of the pass like an uploaded image.
+        let vp = vk::Viewport {
+            x: 0.0,
+            y: height as f32,
+            width: width as f32,
+            height: -(height as f32),
+            min_depth: 0.0,
+            max_depth: 1.0,
+        };
+        let sc = vk::Rect2D {
+            offset: vk::Offset2D::default(),
+            extent,
+        };
+        // SAFETY: `cmd` is recording outside any render pass, and the target's framebuffer was
+        // created against a render pass compatible with both target pass variants.
+        unsafe {
+            device.cmd_begin_render_pass(cmd, &rp_info, vk::SubpassContents::INLINE);
+            device.cmd_set_viewport(cmd, 0, &[vp]);
+            device.cmd_set_scissor(cmd, 0, &[sc]);
         }
-        calibrate_present_clock(state);
-        poll_past_presentation_timing(state);
-        state.last_submitted_present_id = submitted_present_id;
-        retire_completed_textures(state);
-        stats.present_stats = snapshot_present_stats(
+        vertices_drawn = vertices_drawn.saturating_add(record_ops(
+            device,
+            cmd,
             state,
-            waited_for_image,
-            false,
-            false,
-            present_suboptimal,
-            submitted_present_id,
-        );
-        if present_suboptimal {
-            recreate_swapchain_and_dependents(state)?;
+            frame,
+            ops,
+            textures,
+            bases,
+            Some(target_pass.slot),
+        ));
+        // SAFETY: Ends the target render pass begun above on the same command buffer.
+        unsafe {
+            device.cmd_end_render_pass(cmd);
         }
-        if apply_present_back_pressure && screenshot_staging.is_none() {
-            // Match the wgpu Vulkan pacing path: when the app is running
-            // uncapped, wait for this frame's GPU work to retire so the CPU
-            // cannot build a long queue of stale Mailbox presents.
-            let wait_started = Instant::now();
-            device.wait_for_fences(&[fence], true, u64::MAX)?;
-            let wait_us = elapsed_us_since(wait_started);
-            stats.gpu_wait_us = stats.gpu_wait_us.saturating_add(wait_us);
-            back_pressure_waited = wait_us >= VULKAN_BACK_PRESSURE_THRESHOLD_US;
+    }
+    vertices_drawn
+}
+
+#[inline(always)]
+fn resolve_texture<'a>(
+    textures: &'a impl TextureLookup,
+    targets: &'a HashMap<u32, RenderTarget>,
+    handle: TextureHandle,
+    active_target: Option<u32>,
+) -> Option<&'a Texture> {
+    match render_target_slot(handle) {
+        Some(slot) if active_target == Some(slot) => None,
+        Some(slot) => targets.get(&slot).map(|target| &target.texture),
+        None => textures.vulkan_texture(handle),
+    }
+}
+
+/// Creates or resizes the targets for this frame's offscreen passes.
+fn ensure_render_targets(state: &mut State, frame: &RenderFrame) -> Result<(), Box<dyn Error>> {
+    let mut waited_idle = false;
+    for (pass, _) in frame.render_target_passes() {
+        if pass.width == 0
+            || pass.height == 0
+            || state
+                .render_targets
+                .get(&pass.slot)
+                .is_some_and(|target| target.size == (pass.width, pass.height))
+        {
+            continue;
         }
-        if let Some((staging, width, height, format)) = screenshot_staging {
-            let wait_started = Instant::now();
-            device.wait_for_fences(&[fence], true, u64::MAX)?;
-            stats.gpu_wait_us = stats
-                .gpu_wait_us
-                .saturating_add(elapsed_us_since(wait_started));
-            queue_idle_waited = true;
-            let map_size = (width as usize * height as usize * 4) as vk::DeviceSize;
-            let mapped =
-                match device.map_memory(staging.memory, 0, map_size, vk::MemoryMapFlags::empty()) {
-                    Ok(ptr) => ptr,
-                    Err(e) => {
-                        destroy_buffer(&device, &staging);
-                        return Err(e.into());
-                    }
-                };
-            let src = std::slice::from_raw_parts(mapped.cast::<u8>(), map_size as usize);
-            let row_bytes = width as usize * 4;
-            let mut rgba = vec![0u8; row_bytes * height as usize];
-            let swap_rb = matches!(
-                format,
-                vk::Format::B8G8R8A8_UNORM | vk::Format::B8G8R8A8_SRGB
-            );
-            for y in 0..height as usize {
-                let src_row = y * row_bytes;
-                // Vulkan image copy rows are already top-to-bottom for this swapchain path.
-                let dst_row = y * row_bytes;
-                if swap_rb {
-                    let mut x = 0usize;
-                    while x < width as usize {
-                        let s = src_row + x * 4;
-                        let d = dst_row + x * 4;
-                        rgba[d] = src[s + 2];
-                        rgba[d + 1] = src[s + 1];
-                        rgba[d + 2] = src[s];
-                        rgba[d + 3] = src[s + 3];
-                        x += 1;
-                    }
-                } else {
-                    rgba[dst_row..dst_row + row_bytes]
-                        .copy_from_slice(&src[src_row..src_row + row_bytes]);
-                }
+        if state.render_targets.contains_key(&pass.slot) && !waited_idle {
+            // Earlier frames may still sample the old image.
+            // SAFETY: Waiting for idle only blocks until queued work retires.
+            unsafe {
+                state.device.as_ref().unwrap().device_wait_idle()?;
             }
-            device.unmap_memory(staging.memory);
-            destroy_buffer(&device, &staging);
-            state.captured_frame = RgbaImage::from_raw(width, height, rgba);
+            waited_idle = true;
         }
+        state.render_targets.remove(&pass.slot);
+        let passes = match state.target_render_passes {
+            Some(passes) => passes,
+            None => {
+                let device = state.device.as_ref().unwrap();
+                let format = state.swapchain_resources.format.format;
+                let passes = TargetRenderPasses {
+                    clear: create_target_render_pass(device, format, true)?,
+                    load: create_target_render_pass(device, format, false)?,
+                };
+                state.target_render_passes = Some(passes);
+                passes
+            }
+        };
+        let target = create_render_target(state, passes.clear, pass.width, pass.height)?;
+        state.render_targets.insert(pass.slot, target);
+    }
+    Ok(())
+}
 
-        stats.present_stats.applied_back_pressure = back_pressure_waited;
-        stats.present_stats.queue_idle_waited = queue_idle_waited;
-        state.current_frame = (state.current_frame + 1) % MAX_FRAMES_IN_FLIGHT;
-        stats.vertices = vertices_drawn;
-        Ok(stats)
+fn create_render_target(
+    state: &mut State,
+    render_pass: vk::RenderPass,
+    width: u32,
+    height: u32,
+) -> Result<RenderTarget, Box<dyn Error>> {
+    let device = state.device.as_ref().unwrap().clone();
+    let format = state.swapchain_resources.format.format;
+    let (image, memory) = create_image(
+        state,
+        width,
+        height,
+        format,
+        vk::ImageTiling::OPTIMAL,
+        vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::SAMPLED,
+        vk::MemoryPropertyFlags::DEVICE_LOCAL,
+    )?;
+    let view = create_image_view(&device, image, format)?;
+    let sampler_default = get_sampler(state, RENDER_TARGET_SAMPLER)?;
+    let sampler_repeat = get_sampler(
+        state,
+        SamplerDesc {
+            wrap: SamplerWrap::Repeat,
+            ..RENDER_TARGET_SAMPLER
+        },
+    )?;
+    let (set, set_repeat, pool) =
+        create_texture_descriptor_sets(state, view, sampler_default, sampler_repeat)?;
+    let texture = Texture {
+        device: device.clone(),
+        image,
+        memory,
+        view,
+        descriptor_set: set,
+        descriptor_set_repeat: set_repeat,
+        pool,
+    };
+    let attachments = [view];
+    let create_info = vk::FramebufferCreateInfo::default()
+        .render_pass(render_pass)
+        .attachments(&attachments)
+        .width(width)
+        .height(height)
+        .layers(1);
+    // SAFETY: The framebuffer create info references the live target view and a render pass
+    // created on the same device; `texture` drops the view if creation fails.
+    let framebuffer = unsafe { device.create_framebuffer(&create_info, None)? };
+    Ok(RenderTarget {
+        texture,
+        framebuffer,
+        size: (width, height),
+        initialized: false,
+    })
+}
+
+/// Drops every offscreen target, e.g. when a screen that used them exits.
+pub fn release_render_targets(state: &mut State) {
+    if state.render_targets.is_empty() {
+        return;
+    }
+    // SAFETY: Waiting for idle guarantees no in-flight work still samples the targets.
+    unsafe {
+        if let Some(device) = &state.device {
+            let _ = device.device_wait_idle();
+        }
     }
+    state.render_targets.clear();
 }
 
 pub fn cleanup(state: &mut State) {
@@ -2132,6 +2595,7 @@ pub fn cleanup(state: &mut State) {
     }
     retire_all_submitted_texture_uploads(state);
     retire_all_textures(state);
+    state.render_targets.clear();
 
     // SAFETY: The device is idle, so it is valid to tear down swapchain resources, mapped rings,
     // pipelines, descriptor pools/layouts, the device, and finally the instance-owned objects.
@@ -2249,11 +2713,26 @@ pub fn cleanup(state: &mut State) {
             .as_ref()
             .unwrap()
             .destroy_pipeline_layout(state.textured_mesh_pipeline_layout, None);
+        state
+            .device
+            .as_ref()
+            .unwrap()
+            .destroy_pipeline(state.effect_pipeline, None);
+        state
+            .device
+            .as_ref()
+            .unwrap()
+            .destroy_pipeline_layout(state.effect_pipeline_layout, None);
         state
             .device
             .as_ref()
             .unwrap()
             .destroy_render_pass(state.render_pass, None);
+        if let Some(passes) = state.target_render_passes.take() {
+            let device = state.device.as_ref().unwrap();
+            device.destroy_render_pass(passes.clear, None);
+            device.destroy_render_pass(passes.load, None);
+        }
         state
             .device
             .as_ref()
@@ -2376,6 +2855,8 @@ fn create_image(
 }
 
 fn color_blend_for(mode: BlendMode) -> vk::PipelineColorBlendAttachmentState {
+    // Alpha factors only matter in render targets, where coverage must
+    // accumulate the way the software backend composites it.
     match mode {
         BlendMode::Alpha => vk::PipelineColorBlendAttachmentState::default()
             .color_write_mask(vk::ColorComponentFlags::RGBA)
@@ -2384,7 +2865,7 @@ fn color_blend_for(mode: BlendMode) -> vk::PipelineColorBlendAttachmentState {
             .dst_color_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
             .color_blend_op(vk::BlendOp::ADD)
             .src_alpha_blend_factor(vk::BlendFactor::ONE)
-            .dst_alpha_blend_factor(vk::BlendFactor::ZERO)
+            .dst_alpha_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
             .alpha_blend_op(vk::BlendOp::ADD),
         BlendMode::Add => vk::PipelineColorBlendAttachmentState::default()
             .color_write_mask(vk::ColorComponentFlags::RGBA)
@@ -2393,7 +2874,7 @@ fn color_blend_for(mode: BlendMode) -> vk::PipelineColorBlendAttachmentState {
             .dst_color_blend_factor(vk::BlendFactor::ONE)
             .color_blend_op(vk::BlendOp::ADD)
             .src_alpha_blend_factor(vk::BlendFactor::ONE)
-            .dst_alpha_blend_factor(vk::BlendFactor::ZERO)
+            .dst_alpha_blend_factor(vk::BlendFactor::ONE)
             .alpha_blend_op(vk::BlendOp::ADD),
         BlendMode::Multiply => vk::PipelineColorBlendAttachmentState::default()
             .color_write_mask(vk::ColorComponentFlags::RGBA)
@@ -3913,6 +4394,68 @@ fn create_render_pass(device: &Device, format: vk::Format) -> Result<vk::RenderP
     unsafe { device.create_render_pass(&create_info, None) }
 }
 
+/// Builds an offscreen color pass. Both variants finish in the shader-read
+/// layout; the load variant also starts there to keep the previous contents.
+fn create_target_render_pass(
+    device: &Device,
+    format: vk::Format,
+    clear: bool,
+) -> Result<vk::RenderPass, vk::Result> {
+    let (load_op, initial_layout) = if clear {
+        (vk::AttachmentLoadOp::CLEAR, vk::ImageLayout::UNDEFINED)
+    } else {
+        (
+            vk::AttachmentLoadOp::LOAD,
+            vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        )
+    };
+    let color_attachment = vk::AttachmentDescription::default()
+        .format(format)
+        .samples(vk::SampleCountFlags::TYPE_1)
+        .load_op(load_op)
+        .store_op(vk::AttachmentStoreOp::STORE)
+        .stencil_load_op(vk::AttachmentLoadOp::DONT_CARE)
+        .stencil_store_op(vk::AttachmentStoreOp::DONT_CARE)
+        .initial_layout(initial_layout)
+        .final_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+    let color_attachment_ref = vk::AttachmentReference::default()
+        .attachment(0)
+        .layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+    let subpass = vk::SubpassDescription::default()
+        .pipeline_bind_point(vk::PipelineBindPoint::GRAPHICS)
+        .color_attachments(std::slice::from_ref(&color_attachment_ref));
+    let dependencies = [
+        // Earlier samples and writes of this target, including prior frames.
+        vk::SubpassDependency::default()
+            .src_subpass(vk::SUBPASS_EXTERNAL)
+            .dst_subpass(0)
+            .src_stage_mask(
+                vk::PipelineStageFlags::FRAGMENT_SHADER
+                    | vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+            )
+            .src_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+            .dst_access_mask(
+                vk::AccessFlags::COLOR_ATTACHMENT_READ | vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+            ),
+        // Later passes sample what this one wrote.
+        vk::SubpassDependency::default()
+            .src_subpass(0)
+            .dst_subpass(vk::SUBPASS_EXTERNAL)
+            .src_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+            .src_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags::FRAGMENT_SHADER)
+            .dst_access_mask(vk::AccessFlags::SHADER_READ),
+    ];
+    let create_info = vk::RenderPassCreateInfo::default()
+        .attachments(std::slice::from_ref(&color_attachment))
+        .subpasses(std::slice::from_ref(&subpass))
+        .dependencies(&dependencies);
+    // SAFETY: The render-pass create info references only stack data for the duration of the
+    // call and matches the surface pass's single color attachment, keeping pipelines compatible.
+    unsafe { device.create_render_pass(&create_info, None) }
+}
+
 fn create_command_pool(
     device: &Device,
     queue_family_index: u32,
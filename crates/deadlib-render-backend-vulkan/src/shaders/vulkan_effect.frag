@@ -0,0 +1,57 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform sampler2D u_tex;
+
+// Matches deadlib_render_core::EffectUniforms.
+layout(push_constant) uniform EffectPush {
+    vec4 tint;
+    vec4 params;
+    vec2 texel_size;
+    uint program;
+    uint pad;
+} pc;
+
+layout(location = 0) in vec2 v_uv;
+layout(location = 0) out vec4 outColor;
+
+// Mirrors the software backend's shade_effect tap for tap.
+const float BLUR_WEIGHTS[5] = float[5](1.0, 4.0, 6.0, 4.0, 1.0);
+const vec3 LUMA = vec3(0.2126, 0.7152, 0.0722);
+const float TAU = 6.28318530718;
+
+vec4 shade(vec2 uv) {
+    if (pc.program == 0u) {
+        float step_px = pc.params.x * 0.5;
+        vec4 sum = vec4(0.0);
+        for (int j = 0; j < 5; ++j) {
+            for (int i = 0; i < 5; ++i) {
+                vec2 offset = vec2(float(i - 2), float(j - 2)) * step_px * pc.texel_size;
+                sum += texture(u_tex, uv + offset) * (BLUR_WEIGHTS[i] * BLUR_WEIGHTS[j] / 256.0);
+            }
+        }
+        return sum;
+    }
+    if (pc.program == 1u) {
+        vec2 offset = pc.params.xy * pc.texel_size;
+        vec4 r = texture(u_tex, uv + offset);
+        vec4 g = texture(u_tex, uv);
+        vec4 b = texture(u_tex, uv - offset);
+        return vec4(r.r, g.g, b.b, max(max(r.a, g.a), b.a));
+    }
+    if (pc.program == 2u) {
+        vec4 c = texture(u_tex, uv);
+        float luma = dot(c.rgb, LUMA);
+        vec3 graded = (mix(vec3(luma), c.rgb, pc.params.x) - 0.5) * pc.params.y + 0.5 + pc.params.z;
+        return vec4(clamp(graded, 0.0, 1.0), c.a);
+    }
+    if (pc.params.y <= 0.0) {
+        return texture(u_tex, uv);
+    }
+    float y = uv.y / pc.texel_size.y;
+    float shift = pc.params.x * sin(TAU * y / pc.params.y + pc.params.z);
+    return texture(u_tex, vec2(uv.x + shift * pc.texel_size.x, uv.y));
+}
+
+void main() {
+    outColor = shade(v_uv) * pc.tint;
+}
@@ -0,0 +1,12 @@
+#version 450
+
+// Unit quad [-0.5..0.5] stretched over the whole pass.
+layout(location = 0) in vec2 a_pos;
+layout(location = 1) in vec2 a_uv;
+
+layout(location = 0) out vec2 v_uv;
+
+void main() {
+    gl_Position = vec4(a_pos * 2.0, 0.0, 1.0);
+    v_uv = a_uv;
+}
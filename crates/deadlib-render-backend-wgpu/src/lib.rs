@@ -1,8 +1,9 @@
 use deadlib_render_core::{
-    BlendMode, ClockDomainTrace, DrawOp, DrawStats, FastU64Map, PresentModePolicy,
-    PresentModeTrace, PresentStats, RenderFrame, SamplerDesc, SamplerFilter, SamplerWrap,
-    TMeshCacheKey, TextureHandle, TexturedMeshBufferCache, TexturedMeshUploads, TexturedMeshVertex,
-    draw_storage_stats, resolve_textured_meshes,
+    BlendMode, ClockDomainTrace, DrawOp, DrawStats, DrawStorageStats, EffectUniforms, FastU64Map,
+    PresentModePolicy, PresentModeTrace, PresentStats, RenderFrame, SamplerDesc, SamplerFilter,
+    SamplerWrap, ShaderProgramId, TMeshCacheKey, TextureHandle, TexturedMeshBufferCache,
+    TexturedMeshUploads, TexturedMeshVertex, draw_storage_stats, render_target_slot,
+    resolve_textured_meshes,
 };
 use glam::Mat4 as Matrix4;
 use image::RgbaImage;
@@ -129,6 +130,12 @@ struct MeshPipelineSet {
 }
 
 const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+const RENDER_TARGET_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+const RENDER_TARGET_SAMPLER: SamplerDesc = SamplerDesc {
+    filter: SamplerFilter::Linear,
+    wrap: SamplerWrap::Clamp,
+    mipmaps: false,
+};
 
 impl MeshPipelineSet {
     #[inline(always)]
@@ -142,6 +149,51 @@ impl MeshPipelineSet {
     }
 }
 
+/// Pipelines that draw one pass: the surface or an offscreen target.
+#[derive(Clone, Copy)]
+struct PassPipelines<'a> {
+    sprite: &'a PipelineSet,
+    mesh: &'a MeshPipelineSet,
+    tmesh: &'a PipelineSet,
+    tmesh_depth: &'a PipelineSet,
+    effect: &'a PipelineSet,
+}
+
+/// Render-target variants of the surface pipelines. Targets keep alpha, so
+/// these write every channel into `RENDER_TARGET_FORMAT`.
+struct TargetPipelines {
+    sprite: PipelineSet,
+    mesh: MeshPipelineSet,
+    tmesh: PipelineSet,
+    tmesh_depth: PipelineSet,
+    effect: PipelineSet,
+}
+
+impl TargetPipelines {
+    const fn pass(&self) -> PassPipelines<'_> {
+        PassPipelines {
+            sprite: &self.sprite,
+            mesh: &self.mesh,
+            tmesh: &self.tmesh,
+            tmesh_depth: &self.tmesh_depth,
+            effect: &self.effect,
+        }
+    }
+}
+
+/// Pipelines compiled from a user program's WGSL.
+struct CustomEffect {
+    screen: PipelineSet,
+    target: PipelineSet,
+}
+
+struct RenderTarget {
+    texture: Texture,
+    _depth_texture: wgpu::Texture,
+    depth_view: wgpu::TextureView,
+    size: (u32, u32),
+}
+
 enum ProjState {
     Immediates,
     Uniform {
@@ -153,6 +205,17 @@ enum ProjState {
     },
 }
 
+/// Effect uniforms for one frame: a dynamic-offset slot per `DrawOp::Effect`,
+/// in op order across the offscreen passes and then the screen.
+struct EffectUniformBuffer {
+    layout: wgpu::BindGroupLayout,
+    buffer: wgpu::Buffer,
+    group: wgpu::BindGroup,
+    stride: u64,
+    capacity: usize,
+    upload: Vec<u8>,
+}
+
 // A handle to a wgpu texture and its bind group.
 pub struct Texture {
     id: u64,
@@ -201,7 +264,8 @@ pub struct State {
     api: Api,
     proj: ProjState,
     _instance: wgpu::Instance,
-    surface: wgpu::Surface<'static>,
+    /// `None` for headless states, which only draw offscreen passes.
+    surface: Option<wgpu::Surface<'static>>,
     adapter: wgpu::Adapter,
     device: wgpu::Device,
     queue: wgpu::Queue,
@@ -221,6 +285,15 @@ pub struct State {
     tmesh_pipeline_layout: wgpu::PipelineLayout,
     tmesh_pipelines: PipelineSet,
     tmesh_depth_pipelines: PipelineSet,
+    effect_shader: wgpu::ShaderModule,
+    effect_pipeline_layout: wgpu::PipelineLayout,
+    effect_pipelines: PipelineSet,
+    effect_uniforms: EffectUniformBuffer,
+    /// `None` for programs without WGSL or that failed to compile; those
+    /// draw through [`deadlib_render_core::ShaderEffect::PASSTHROUGH`].
+    custom_effects: HashMap<ShaderProgramId, Option<CustomEffect>>,
+    target_pipelines: Option<TargetPipelines>,
+    render_targets: HashMap<u32, RenderTarget>,
     depth_texture: wgpu::Texture,
     depth_view: wgpu::TextureView,
     vertex_buffer: wgpu::Buffer,
@@ -352,6 +425,78 @@ fn init(
     .map_err(|e| format!("No suitable {} adapter found: {e}", api.name()))?;
     log_wgpu_adapter_info(api, &adapter);
 
+    let size = window.inner_size();
+    init_state(
+        api,
+        instance,
+        Some(surface),
+        adapter,
+        (size.width, size.height),
+        vsync_enabled,
+        present_mode_policy,
+    )
+}
+
+/// Opens a windowless state on the first API with an adapter. It has no
+/// surface, so `draw` skips its frames; use `draw_render_targets` instead.
+#[cfg(feature = "test-util")]
+pub fn init_headless(width: u32, height: u32) -> Result<State, Box<dyn Error>> {
+    let apis = [
+        #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
+        Api::Vulkan,
+        #[cfg(target_os = "macos")]
+        Api::Metal,
+        #[cfg(target_os = "windows")]
+        Api::DirectX,
+        Api::OpenGL,
+    ];
+    let mut last_error = None;
+    for api in apis {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: api.backends(),
+            flags: wgpu::InstanceFlags::empty(),
+            memory_budget_thresholds: Default::default(),
+            backend_options: Default::default(),
+            display: None,
+        });
+        let adapter =
+            match pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter: false,
+                apply_limit_buckets: false,
+            })) {
+                Ok(adapter) => adapter,
+                Err(e) => {
+                    last_error = Some(format!("No suitable {} adapter found: {e}", api.name()));
+                    continue;
+                }
+            };
+        log_wgpu_adapter_info(api, &adapter);
+        return init_state(
+            api,
+            instance,
+            None,
+            adapter,
+            (width, height),
+            false,
+            PresentModePolicy::Mailbox,
+        );
+    }
+    Err(last_error
+        .unwrap_or_else(|| "No wgpu API is available".to_string())
+        .into())
+}
+
+fn init_state(
+    api: Api,
+    instance: wgpu::Instance,
+    surface: Option<wgpu::Surface<'static>>,
+    adapter: wgpu::Adapter,
+    size: (u32, u32),
+    vsync_enabled: bool,
+    present_mode_policy: PresentModePolicy,
+) -> Result<State, Box<dyn Error>> {
     #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
     let want_immediates = matches!(api, Api::Vulkan);
     #[cfg(any(target_pointer_width = "32", target_vendor = "win7"))]
@@ -386,28 +531,43 @@ fn init(
         experimental_features: Default::default(),
     }))?;
 
-    let size = window.inner_size();
-    let caps = surface.get_capabilities(&adapter);
-    let format = pick_format(&caps);
-    let present_mode = pick_present_mode(&caps.present_modes, vsync_enabled, present_mode_policy);
-    let alpha_mode = pick_alpha_mode(&caps);
+    // Headless states keep the config only for the screen pipelines' format.
+    let (usage, format, present_mode, alpha_mode) = match &surface {
+        Some(surface) => {
+            let caps = surface.get_capabilities(&adapter);
+            (
+                pick_surface_usage(&caps),
+                pick_format(&caps),
+                pick_present_mode(&caps.present_modes, vsync_enabled, present_mode_policy),
+                pick_alpha_mode(&caps),
+            )
+        }
+        None => (
+            wgpu::TextureUsages::RENDER_ATTACHMENT,
+            RENDER_TARGET_FORMAT,
+            wgpu::PresentMode::Fifo,
+            wgpu::CompositeAlphaMode::Opaque,
+        ),
+    };
 
     let config = wgpu::SurfaceConfiguration {
-        usage: pick_surface_usage(&caps),
+        usage,
         format,
         color_space: wgpu::SurfaceColorSpace::Auto,
-        width: size.width.max(1),
-        height: size.height.max(1),
+        width: size.0.max(1),
+        height: size.1.max(1),
         present_mode,
         alpha_mode,
         view_formats: vec![],
         desired_maximum_frame_latency: 0,
     };
-    surface.configure(&device, &config);
+    if let Some(surface) = &surface {
+        surface.configure(&device, &config);
+    }
     let (depth_texture, depth_view) =
         create_depth_target(&device, config.width.max(1), config.height.max(1));
 
-    let projection = ortho_for_window(size.width, size.height);
+    let projection = ortho_for_window(size.0, size.1);
     let proj = if use_immediates {
         ProjState::Immediates
     } else {
@@ -448,6 +608,9 @@ fn init(
         build_mesh_pipeline_set(&device, &proj, format);
     let (tmesh_shader, tmesh_pipeline_layout, tmesh_pipelines, tmesh_depth_pipelines) =
         build_textured_mesh_pipeline_set(&device, &proj, &bind_layout, format);
+    let effect_uniforms = init_effect_uniforms(&device);
+    let (effect_shader, effect_pipeline_layout, effect_pipelines) =
+        build_effect_pipeline_set(&device, &bind_layout, &effect_uniforms.layout, format);
 
     let vertex_data = [
         Vertex {
@@ -537,6 +700,13 @@ fn init(
         tmesh_pipeline_layout,
         tmesh_pipelines,
         tmesh_depth_pipelines,
+        effect_shader,
+        effect_pipeline_layout,
+        effect_pipelines,
+        effect_uniforms,
+        custom_effects: HashMap::new(),
+        target_pipelines: None,
+        render_targets: HashMap::new(),
         depth_texture,
         depth_view,
         vertex_buffer,
@@ -553,7 +723,7 @@ fn init(
         tmesh_vertex_capacity,
         tmesh_instance_buffer,
         tmesh_instance_capacity,
-        window_size: (size.width, size.height),
+        window_size: size,
         vsync_enabled,
         present_mode_policy,
         next_texture_id: 1,
@@ -759,6 +929,18 @@ fn proj_binding(buffer: &wgpu::Buffer) -> wgpu::BindingResource<'_> {
     })
 }
 
+impl State {
+    const fn pass_pipelines(&self) -> PassPipelines<'_> {
+        PassPipelines {
+            sprite: &self.pipelines,
+            mesh: &self.mesh_pipelines,
+            tmesh: &self.tmesh_pipelines,
+            tmesh_depth: &self.tmesh_depth_pipelines,
+            effect: &self.effect_pipelines,
+        }
+    }
+}
+
 pub fn create_texture(
     state: &mut State,
     image: &RgbaImage,
@@ -797,6 +979,11 @@ pub fn create_texture(
         size,
     );
 
+    Ok(bind_texture(state, texture, sampler_desc))
+}
+
+/// Wraps a sampled texture with its clamp and repeat bind groups.
+fn bind_texture(state: &mut State, texture: wgpu::Texture, sampler_desc: SamplerDesc) -> Texture {
     let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
     let sampler = get_sampler(state, sampler_desc);
     let bind_group = state.device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -838,13 +1025,73 @@ pub fn create_texture(
     let id = state.next_texture_id;
     state.next_texture_id = state.next_texture_id.wrapping_add(1);
 
-    Ok(Texture {
+    Texture {
         id,
         _texture: texture,
         _view: view,
         bind_group: Arc::new(bind_group),
         bind_group_repeat: Arc::new(bind_group_repeat),
-    })
+    }
+}
+
+/// Creates or resizes the targets for this frame's offscreen passes.
+fn ensure_render_targets(state: &mut State, frame: &RenderFrame) {
+    for (pass, _) in frame.render_target_passes() {
+        if pass.width == 0
+            || pass.height == 0
+            || state
+                .render_targets
+                .get(&pass.slot)
+                .is_some_and(|target| target.size == (pass.width, pass.height))
+        {
+            continue;
+        }
+        let texture = state.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("wgpu render target"),
+            size: wgpu::Extent3d {
+                width: pass.width,
+                height: pass.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: RENDER_TARGET_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let texture = bind_texture(state, texture, RENDER_TARGET_SAMPLER);
+        let (depth_texture, depth_view) =
+            create_depth_target(&state.device, pass.width, pass.height);
+        state.render_targets.insert(
+            pass.slot,
+            RenderTarget {
+                texture,
+                _depth_texture: depth_texture,
+                depth_view,
+                size: (pass.width, pass.height),
+            },
+        );
+    }
+    if state.target_pipelines.is_none() && !state.render_targets.is_empty() {
+        state.target_pipelines = Some(build_target_pipelines(state));
+    }
+}
+
+#[inline(always)]
+fn resolve_texture<'a>(
+    textures: &'a impl TextureLookup,
+    targets: &'a HashMap<u32, RenderTarget>,
+    handle: TextureHandle,
+    active_target: Option<u32>,
+) -> Option<&'a Texture> {
+    match render_target_slot(handle) {
+        Some(slot) if active_target == Some(slot) => None,
+        Some(slot) => targets.get(&slot).map(|target| &target.texture),
+        None => textures.wgpu_texture(handle),
+    }
 }
 
 fn create_depth_target(
@@ -969,67 +1216,18 @@ pub fn draw(
     }
 
     let backend_prepare_started = Instant::now();
-    {
-        let uploads = &mut state.uploads;
-        let device = &state.device;
-        let cached_tmesh = &mut state.cached_tmesh;
-        let cached_tmesh_bytes = &mut state.cached_tmesh_bytes;
-        resolve_textured_meshes(frame, uploads, |cache_key, vertices| {
-            ensure_cached_tmesh(
-                device,
-                cached_tmesh,
-                cached_tmesh_bytes,
-                cache_key,
-                vertices,
-            )
-            .then_some(cache_key)
-        });
-        stats.storage = draw_storage_stats(frame, Some(uploads));
-    }
+    stats.storage = prepare_textured_meshes(state, frame);
     stats.backend_prepare_us = elapsed_us_since(backend_prepare_started);
 
     let backend_upload_started = Instant::now();
-    let instance_len = frame.sprite_instances.len();
-    ensure_instance_capacity(state, instance_len);
-    if instance_len > 0 {
-        state.queue.write_buffer(
-            &state.instance_buffer,
-            0,
-            cast_slice(frame.sprite_instances.as_slice()),
-        );
-    }
-    let mesh_len = frame.mesh_vertices.len();
-    ensure_mesh_vertex_capacity(state, mesh_len);
-    if mesh_len > 0 {
-        state.queue.write_buffer(
-            &state.mesh_vertex_buffer,
-            0,
-            cast_slice(frame.mesh_vertices.as_slice()),
-        );
-    }
-    let tmesh_len = state.uploads.vertices.len();
-    ensure_tmesh_vertex_capacity(state, tmesh_len);
-    if tmesh_len > 0 {
-        state.queue.write_buffer(
-            &state.tmesh_vertex_buffer,
-            0,
-            cast_slice(state.uploads.vertices.as_slice()),
-        );
-    }
-    let tmesh_instance_len = frame.tmesh_instances.len();
-    ensure_tmesh_instance_capacity(state, tmesh_instance_len);
-    if tmesh_instance_len > 0 {
-        state.queue.write_buffer(
-            &state.tmesh_instance_buffer,
-            0,
-            cast_slice(frame.tmesh_instances.as_slice()),
-        );
-    }
-    upload_projections(state, &frame.cameras);
+    upload_frame(state, frame);
     stats.backend_upload_us = elapsed_us_since(backend_upload_started);
 
     let acquire_started = Instant::now();
-    let (surface_frame, suboptimal) = match state.surface.get_current_texture() {
+    let Some(surface) = state.surface.as_ref() else {
+        return Ok(stats);
+    };
+    let (surface_frame, suboptimal) = match surface.get_current_texture() {
         wgpu::CurrentSurfaceTexture::Success(frame) => (frame, false),
         wgpu::CurrentSurfaceTexture::Suboptimal(frame) => (frame, true),
         wgpu::CurrentSurfaceTexture::Lost | wgpu::CurrentSurfaceTexture::Outdated => {
@@ -1058,7 +1256,8 @@ pub fn draw(
     stats.backend_setup_us = elapsed_us_since(backend_setup_started);
 
     let backend_record_started = Instant::now();
-    let mut vertices_drawn = 0u32;
+    let (mut vertices_drawn, effect_base) =
+        record_target_passes(&mut encoder, state, frame, textures);
     {
         let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("wgpu render pass"),
@@ -1089,173 +1288,16 @@ pub fn draw(
             multiview_mask: None,
         });
 
-        let camera_count = frame.cameras.len();
-        let texture_group = match state.proj {
-            ProjState::Immediates => 0,
-            ProjState::Uniform { .. } => 1,
-        };
-
-        let mut last_kind: Option<u8> = None; // 0=sprite, 1=mesh, 2=textured mesh
-        let mut last_blend: Option<BlendMode> = None;
-        let mut last_bind: Option<u64> = None;
-        let mut last_camera: Option<u8> = None;
-        let mut tmesh_buffer_cache = TexturedMeshBufferCache::default();
-        let mut last_tmesh_depth_test: Option<bool> = None;
-        for op in &frame.ops {
-            match op {
-                DrawOp::Sprite(run) => {
-                    let Some(tex) = textures.wgpu_texture(run.texture_handle) else {
-                        continue;
-                    };
-                    if last_kind != Some(0) {
-                        pass.set_vertex_buffer(0, state.vertex_buffer.slice(..));
-                        pass.set_vertex_buffer(1, state.instance_buffer.slice(..));
-                        pass.set_index_buffer(
-                            state.index_buffer.slice(..),
-                            wgpu::IndexFormat::Uint16,
-                        );
-                        last_kind = Some(0);
-                        last_blend = None;
-                        last_bind = None;
-                        last_camera = None;
-                        tmesh_buffer_cache.reset();
-                        last_tmesh_depth_test = None;
-                    }
-                    if last_blend != Some(run.blend) {
-                        pass.set_pipeline(state.pipelines.get(run.blend));
-                        last_blend = Some(run.blend);
-                        last_bind = None;
-                    }
-                    if last_camera != Some(run.camera) {
-                        set_camera(
-                            &mut pass,
-                            &state.proj,
-                            run.camera,
-                            camera_count,
-                            &frame.cameras,
-                            state.projection,
-                        );
-                        last_camera = Some(run.camera);
-                    }
-                    if last_bind != Some(tex.id) {
-                        pass.set_bind_group(texture_group, Some(tex.bind_group.as_ref()), &[]);
-                        last_bind = Some(tex.id);
-                    }
-                    pass.draw_indexed(
-                        0..state.index_count,
-                        0,
-                        run.instance_start..(run.instance_start + run.instance_count),
-                    );
-                    vertices_drawn = vertices_drawn.saturating_add(4 * run.instance_count);
-                }
-                DrawOp::Mesh(run) => {
-                    if run.vertex_count == 0 {
-                        continue;
-                    }
-                    if last_kind != Some(1) {
-                        pass.set_vertex_buffer(0, state.mesh_vertex_buffer.slice(..));
-                        last_kind = Some(1);
-                        last_blend = None;
-                        last_bind = None;
-                        last_camera = None;
-                        tmesh_buffer_cache.reset();
-                        last_tmesh_depth_test = None;
-                    }
-                    if last_blend != Some(run.blend) {
-                        pass.set_pipeline(state.mesh_pipelines.get(run.blend));
-                        last_blend = Some(run.blend);
-                    }
-                    if last_camera != Some(run.camera) {
-                        set_camera(
-                            &mut pass,
-                            &state.proj,
-                            run.camera,
-                            camera_count,
-                            &frame.cameras,
-                            state.projection,
-                        );
-                        last_camera = Some(run.camera);
-                    }
-                    pass.draw(
-                        run.vertex_start..(run.vertex_start + run.vertex_count),
-                        0..1,
-                    );
-                    vertices_drawn = vertices_drawn.saturating_add(run.vertex_count);
-                }
-                DrawOp::TexturedMesh(run) => {
-                    let Some(source) = state.uploads.source(run.geometry) else {
-                        continue;
-                    };
-                    if source.vertex_count() == 0 || run.instance_count == 0 {
-                        continue;
-                    }
-                    let Some(tex) = textures.wgpu_texture(run.texture_handle) else {
-                        continue;
-                    };
-                    if last_kind != Some(2) {
-                        pass.set_vertex_buffer(1, state.tmesh_instance_buffer.slice(..));
-                        last_kind = Some(2);
-                        last_blend = None;
-                        last_bind = None;
-                        last_camera = None;
-                        tmesh_buffer_cache.reset();
-                        last_tmesh_depth_test = None;
-                    }
-                    if last_blend != Some(run.blend)
-                        || last_tmesh_depth_test != Some(run.depth_test)
-                    {
-                        pass.set_pipeline(if run.depth_test {
-                            state.tmesh_depth_pipelines.get(run.blend)
-                        } else {
-                            state.tmesh_pipelines.get(run.blend)
-                        });
-                        last_blend = Some(run.blend);
-                        last_tmesh_depth_test = Some(run.depth_test);
-                        last_bind = None;
-                    }
-                    if last_camera != Some(run.camera) {
-                        set_camera(
-                            &mut pass,
-                            &state.proj,
-                            run.camera,
-                            camera_count,
-                            &frame.cameras,
-                            state.projection,
-                        );
-                        last_camera = Some(run.camera);
-                    }
-                    let bind_key = tex.id.wrapping_shl(1) | 1;
-                    if last_bind != Some(bind_key) {
-                        pass.set_bind_group(
-                            texture_group,
-                            Some(tex.bind_group_repeat.as_ref()),
-                            &[],
-                        );
-                        last_bind = Some(bind_key);
-                    }
-                    if tmesh_buffer_cache.update_required(source) {
-                        if let Some(cache_key) = source.buffer_key() {
-                            let Some(entry) = state.cached_tmesh.get(&cache_key) else {
-                                tmesh_buffer_cache.reset();
-                                continue;
-                            };
-                            pass.set_vertex_buffer(0, entry.buffer.slice(..));
-                        } else {
-                            pass.set_vertex_buffer(0, state.tmesh_vertex_buffer.slice(..));
-                        }
-                    }
-                    let draw_start = source.vertex_start();
-                    let draw_end = draw_start + source.vertex_count();
-                    pass.draw(
-                        draw_start..draw_end,
-                        run.instance_start..(run.instance_start + run.instance_count),
-                    );
-                    let tri_count = source.vertex_count() / 3;
-                    vertices_drawn =
-                        vertices_drawn.saturating_add(tri_count.saturating_mul(run.instance_count));
-                }
-            }
-        }
+        vertices_drawn = vertices_drawn.saturating_add(record_ops(
+            &mut pass,
+            state,
+            state.pass_pipelines(),
+            frame,
+            frame.screen_ops(),
+            textures,
+            None,
+            effect_base,
+        ));
         drop(pass);
     }
 
@@ -1448,6 +1490,552 @@ pub fn draw(
     Ok(stats)
 }
 
+fn prepare_textured_meshes(state: &mut State, frame: &RenderFrame) -> DrawStorageStats {
+    let uploads = &mut state.uploads;
+    let device = &state.device;
+    let cached_tmesh = &mut state.cached_tmesh;
+    let cached_tmesh_bytes = &mut state.cached_tmesh_bytes;
+    resolve_textured_meshes(frame, uploads, |cache_key, vertices| {
+        ensure_cached_tmesh(
+            device,
+            cached_tmesh,
+            cached_tmesh_bytes,
+            cache_key,
+            vertices,
+        )
+        .then_some(cache_key)
+    });
+    draw_storage_stats(frame, Some(uploads))
+}
+
+/// Writes the frame's instances, vertices, cameras and effect params, and
+/// sizes its offscreen targets.
+fn upload_frame(state: &mut State, frame: &RenderFrame) {
+    let instance_len = frame.sprite_instances.len();
+    ensure_instance_capacity(state, instance_len);
+    if instance_len > 0 {
+        state.queue.write_buffer(
+            &state.instance_buffer,
+            0,
+            cast_slice(frame.sprite_instances.as_slice()),
+        );
+    }
+    let mesh_len = frame.mesh_vertices.len();
+    ensure_mesh_vertex_capacity(state, mesh_len);
+    if mesh_len > 0 {
+        state.queue.write_buffer(
+            &state.mesh_vertex_buffer,
+            0,
+            cast_slice(frame.mesh_vertices.as_slice()),
+        );
+    }
+    let tmesh_len = state.uploads.vertices.len();
+    ensure_tmesh_vertex_capacity(state, tmesh_len);
+    if tmesh_len > 0 {
+        state.queue.write_buffer(
+            &state.tmesh_vertex_buffer,
+            0,
+            cast_slice(state.uploads.vertices.as_slice()),
+        );
+    }
+    let tmesh_instance_len = frame.tmesh_instances.len();
+    ensure_tmesh_instance_capacity(state, tmesh_instance_len);
+    if tmesh_instance_len > 0 {
+        state.queue.write_buffer(
+            &state.tmesh_instance_buffer,
+            0,
+            cast_slice(frame.tmesh_instances.as_slice()),
+        );
+    }
+    upload_projections(state, &frame.cameras);
+    ensure_render_targets(state, frame);
+    ensure_custom_effects(state, frame);
+    upload_effect_uniforms(state, frame);
+}
+
+/// Records every offscreen pass in frame order. Returns the vertices drawn
+/// and the first effect slot left for the screen pass.
+fn record_target_passes(
+    encoder: &mut wgpu::CommandEncoder,
+    state: &State,
+    frame: &RenderFrame,
+    textures: &impl TextureLookup,
+) -> (u32, u32) {
+    let mut vertices_drawn = 0u32;
+    let mut effect_base = 0u32;
+    let Some(target_pipelines) = state.target_pipelines.as_ref() else {
+        return (vertices_drawn, effect_base);
+    };
+    for (target_pass, ops) in frame.render_target_passes() {
+        let pass_effect_base = effect_base;
+        effect_base += effect_count(ops);
+        let Some(target) = state.render_targets.get(&target_pass.slot) else {
+            continue;
+        };
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("wgpu render target pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &target.texture._view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: if target_pass.clear {
+                        wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)
+                    } else {
+                        wgpu::LoadOp::Load
+                    },
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &target.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Discard,
+                }),
+                stencil_ops: None,
+            }),
+            occlusion_query_set: None,
+            timestamp_writes: None,
+            multiview_mask: None,
+        });
+        vertices_drawn = vertices_drawn.saturating_add(record_ops(
+            &mut pass,
+            state,
+            target_pipelines.pass(),
+            frame,
+            ops,
+            textures,
+            Some(target_pass.slot),
+            pass_effect_base,
+        ));
+    }
+    (vertices_drawn, effect_base)
+}
+
+/// Draws only the frame's offscreen passes and reads every live target back.
+/// Works on headless states, so parity tests can diff targets without a window.
+#[cfg(feature = "test-util")]
+pub fn draw_render_targets(
+    state: &mut State,
+    frame: &RenderFrame,
+    textures: &impl TextureLookup,
+) -> Result<HashMap<u32, RgbaImage>, Box<dyn Error>> {
+    prepare_textured_meshes(state, frame);
+    upload_frame(state, frame);
+    let mut encoder = state
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("wgpu render target readback encoder"),
+        });
+    record_target_passes(&mut encoder, state, frame, textures);
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let readbacks: Vec<_> = state
+        .render_targets
+        .iter()
+        .map(|(&slot, target)| {
+            let (width, height) = target.size;
+            let padded_bytes_per_row = (4 * width).div_ceil(align) * align;
+            let buffer = state.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("wgpu render target readback"),
+                size: u64::from(padded_bytes_per_row) * u64::from(height),
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            });
+            encoder.copy_texture_to_buffer(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &target.texture._texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::TexelCopyBufferInfo {
+                    buffer: &buffer,
+                    layout: wgpu::TexelCopyBufferLayout {
+                        offset: 0,
+                        bytes_per_row: Some(padded_bytes_per_row),
+                        rows_per_image: Some(height),
+                    },
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+            (slot, buffer, target.size, padded_bytes_per_row as usize)
+        })
+        .collect();
+    state.queue.submit(Some(encoder.finish()));
+
+    let mut images = HashMap::with_capacity(readbacks.len());
+    for (slot, buffer, (width, height), padded_row_bytes) in readbacks {
+        let slice = buffer.slice(..);
+        let (tx, rx) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |res| {
+            let _ = tx.send(res);
+        });
+        let _ = state.device.poll(wgpu::PollType::Wait {
+            submission_index: None,
+            timeout: None,
+        });
+        rx.recv()??;
+        let data = slice.get_mapped_range()?;
+        let row_bytes = width as usize * 4;
+        let mut rgba = Vec::with_capacity(row_bytes * height as usize);
+        for row in data.chunks(padded_row_bytes).take(height as usize) {
+            rgba.extend_from_slice(&row[..row_bytes]);
+        }
+        drop(data);
+        buffer.unmap();
+        let image = RgbaImage::from_raw(width, height, rgba)
+            .ok_or("render target readback has the wrong size")?;
+        images.insert(slot, image);
+    }
+    Ok(images)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn record_ops(
+    pass: &mut wgpu::RenderPass<'_>,
+    state: &State,
+    pipelines: PassPipelines<'_>,
+    frame: &RenderFrame,
+    ops: &[DrawOp],
+    textures: &impl TextureLookup,
+    active_target: Option<u32>,
+    effect_base: u32,
+) -> u32 {
+    let mut vertices = 0u32;
+    let mut effect_slot = effect_base;
+    let camera_count = frame.cameras.len();
+    let texture_group = match state.proj {
+        ProjState::Immediates => 0,
+        ProjState::Uniform { .. } => 1,
+    };
+
+    let mut last_kind: Option<u8> = None; // 0=sprite, 1=mesh, 2=textured mesh, 3=effect
+    let mut last_blend: Option<BlendMode> = None;
+    let mut last_bind: Option<u64> = None;
+    let mut last_camera: Option<u8> = None;
+    let mut tmesh_buffer_cache = TexturedMeshBufferCache::default();
+    let mut last_tmesh_depth_test: Option<bool> = None;
+    // `None` while the built-in effect pipelines are bound.
+    let mut last_custom_effect: Option<ShaderProgramId> = None;
+    for op in ops {
+        match op {
+            DrawOp::Sprite(run) => {
+                let Some(tex) = resolve_texture(
+                    textures,
+                    &state.render_targets,
+                    run.texture_handle,
+                    active_target,
+                ) else {
+                    continue;
+                };
+                if last_kind != Some(0) {
+                    pass.set_vertex_buffer(0, state.vertex_buffer.slice(..));
+                    pass.set_vertex_buffer(1, state.instance_buffer.slice(..));
+                    pass.set_index_buffer(state.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+                    last_kind = Some(0);
+                    last_blend = None;
+                    last_bind = None;
+                    last_camera = None;
+                    tmesh_buffer_cache.reset();
+                    last_tmesh_depth_test = None;
+                }
+                if last_blend != Some(run.blend) {
+                    pass.set_pipeline(pipelines.sprite.get(run.blend));
+                    last_blend = Some(run.blend);
+                    last_bind = None;
+                }
+                if last_camera != Some(run.camera) {
+                    set_camera(
+                        pass,
+                        &state.proj,
+                        run.camera,
+                        camera_count,
+                        &frame.cameras,
+                        state.projection,
+                    );
+                    last_camera = Some(run.camera);
+                }
+                if last_bind != Some(tex.id) {
+                    pass.set_bind_group(texture_group, Some(tex.bind_group.as_ref()), &[]);
+                    last_bind = Some(tex.id);
+                }
+                pass.draw_indexed(
+                    0..state.index_count,
+                    0,
+                    run.instance_start..(run.instance_start + run.instance_count),
+                );
+                vertices = vertices.saturating_add(4 * run.instance_count);
+            }
+            DrawOp::Mesh(run) => {
+                if run.vertex_count == 0 {
+                    continue;
+                }
+                if last_kind != Some(1) {
+                    pass.set_vertex_buffer(0, state.mesh_vertex_buffer.slice(..));
+                    last_kind = Some(1);
+                    last_blend = None;
+                    last_bind = None;
+                    last_camera = None;
+                    tmesh_buffer_cache.reset();
+                    last_tmesh_depth_test = None;
+                }
+                if last_blend != Some(run.blend) {
+                    pass.set_pipeline(pipelines.mesh.get(run.blend));
+                    last_blend = Some(run.blend);
+                }
+                if last_camera != Some(run.camera) {
+                    set_camera(
+                        pass,
+                        &state.proj,
+                        run.camera,
+                        camera_count,
+                        &frame.cameras,
+                        state.projection,
+                    );
+                    last_camera = Some(run.camera);
+                }
+                pass.draw(
+                    run.vertex_start..(run.vertex_start + run.vertex_count),
+                    0..1,
+                );
+                vertices = vertices.saturating_add(run.vertex_count);
+            }
+            DrawOp::TexturedMesh(run) => {
+                let Some(source) = state.uploads.source(run.geometry) else {
+                    continue;
+                };
+                if source.vertex_count() == 0 || run.instance_count == 0 {
+                    continue;
+                }
+                let Some(tex) = resolve_texture(
+                    textures,
+                    &state.render_targets,
+                    run.texture_handle,
+                    active_target,
+                ) else {
+                    continue;
+                };
+                if last_kind != Some(2) {
+                    pass.set_vertex_buffer(1, state.tmesh_instance_buffer.slice(..));
+                    last_kind = Some(2);
+                    last_blend = None;
+                    last_bind = None;
+                    last_camera = None;
+                    tmesh_buffer_cache.reset();
+                    last_tmesh_depth_test = None;
+                }
+                if last_blend != Some(run.blend) || last_tmesh_depth_test != Some(run.depth_test) {
+                    pass.set_pipeline(if run.depth_test {
+                        pipelines.tmesh_depth.get(run.blend)
+                    } else {
+                        pipelines.tmesh.get(run.blend)
+                    });
+                    last_blend = Some(run.blend);
+                    last_tmesh_depth_test = Some(run.depth_test);
+                    last_bind = None;
+                }
+                if last_camera != Some(run.camera) {
+                    set_camera(
+                        pass,
+                        &state.proj,
+                        run.camera,
+                        camera_count,
+                        &frame.cameras,
+                        state.projection,
+                    );
+                    last_camera = Some(run.camera);
+                }
+                let bind_key = tex.id.wrapping_shl(1) | 1;
+                if last_bind != Some(bind_key) {
+                    pass.set_bind_group(texture_group, Some(tex.bind_group_repeat.as_ref()), &[]);
+                    last_bind = Some(bind_key);
+                }
+                if tmesh_buffer_cache.update_required(source) {
+                    if let Some(cache_key) = source.buffer_key() {
+                        let Some(entry) = state.cached_tmesh.get(&cache_key) else {
+                            tmesh_buffer_cache.reset();
+                            continue;
+                        };
+                        pass.set_vertex_buffer(0, entry.buffer.slice(..));
+                    } else {
+                        pass.set_vertex_buffer(0, state.tmesh_vertex_buffer.slice(..));
+                    }
+                }
+                let draw_start = source.vertex_start();
+                let draw_end = draw_start + source.vertex_count();
+                pass.draw(
+                    draw_start..draw_end,
+                    run.instance_start..(run.instance_start + run.instance_count),
+                );
+                let tri_count = source.vertex_count() / 3;
+                vertices = vertices.saturating_add(tri_count.saturating_mul(run.instance_count));
+            }
+            DrawOp::Effect(run) => {
+                let slot = effect_slot;
+                effect_slot += 1;
+                let Some(tex) = resolve_texture(
+                    textures,
+                    &state.render_targets,
+                    run.texture_handle,
+                    active_target,
+                ) else {
+                    continue;
+                };
+                if last_kind != Some(3) {
+                    pass.set_vertex_buffer(0, state.vertex_buffer.slice(..));
+                    pass.set_index_buffer(state.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+                    last_kind = Some(3);
+                    last_blend = None;
+                    last_bind = None;
+                    last_camera = None;
+                    tmesh_buffer_cache.reset();
+                    last_tmesh_depth_test = None;
+                }
+                let custom = match state.custom_effects.get(&run.effect.program()) {
+                    Some(Some(custom)) if active_target.is_some() => Some(&custom.target),
+                    Some(Some(custom)) => Some(&custom.screen),
+                    _ => None,
+                };
+                let custom_id = custom.map(|_| run.effect.program());
+                if last_blend != Some(run.blend) || last_custom_effect != custom_id {
+                    let effect = custom.unwrap_or(pipelines.effect);
+                    pass.set_pipeline(effect.get(run.blend));
+                    last_blend = Some(run.blend);
+                    last_custom_effect = custom_id;
+                }
+                let offset = (u64::from(slot) * state.effect_uniforms.stride) as u32;
+                pass.set_bind_group(0, Some(tex.bind_group.as_ref()), &[]);
+                pass.set_bind_group(1, &state.effect_uniforms.group, &[offset]);
+                pass.draw_indexed(0..state.index_count, 0, 0..1);
+                vertices = vertices.saturating_add(4);
+            }
+            DrawOp::BeginTarget(_) | DrawOp::EndTarget => {}
+        }
+    }
+    vertices
+}
+
+#[inline(always)]
+fn effect_count(ops: &[DrawOp]) -> u32 {
+    ops.iter()
+        .filter(|op| matches!(op, DrawOp::Effect(_)))
+        .count() as u32
+}
+
+/// Stages one uniform slot per effect op, in the order `render` records them.
+fn upload_effect_uniforms(state: &mut State, frame: &RenderFrame) {
+    let effects = &mut state.effect_uniforms;
+    let stride = effects.stride as usize;
+    effects.upload.clear();
+    let ops = frame
+        .render_target_passes()
+        .flat_map(|(_, ops)| ops)
+        .chain(frame.screen_ops());
+    for op in ops {
+        let DrawOp::Effect(run) = op else {
+            continue;
+        };
+        let source_size = render_target_slot(run.texture_handle)
+            .and_then(|slot| state.render_targets.get(&slot))
+            .map_or([1, 1], |target| [target.size.0, target.size.1]);
+        let compiled = matches!(
+            state.custom_effects.get(&run.effect.program()),
+            Some(Some(_))
+        );
+        let run = if compiled { *run } else { run.builtin_only() };
+        let uniforms = EffectUniforms::new(run, source_size);
+        let offset = effects.upload.len();
+        effects.upload.resize(offset + stride, 0);
+        effects.upload[offset..offset + EFFECT_BYTES as usize]
+            .copy_from_slice(bytemuck::bytes_of(&uniforms));
+    }
+    if effects.upload.is_empty() {
+        return;
+    }
+    let needed = effects.upload.len() / stride;
+    if needed > effects.capacity {
+        let capacity = needed.next_power_of_two();
+        effects.buffer = create_effect_buffer(&state.device, capacity as u64 * effects.stride);
+        effects.group = create_effect_group(&state.device, &effects.layout, &effects.buffer);
+        effects.capacity = capacity;
+    }
+    state
+        .queue
+        .write_buffer(&effects.buffer, 0, &effects.upload);
+}
+
+/// Compiles the user programs the frame runs for the first time.
+fn ensure_custom_effects(state: &mut State, frame: &RenderFrame) {
+    for op in &frame.ops {
+        let DrawOp::Effect(run) = op else {
+            continue;
+        };
+        let id = run.effect.program();
+        if run.effect.is_builtin() || state.custom_effects.contains_key(&id) {
+            continue;
+        }
+        let compiled = compile_custom_effect(state, id);
+        state.custom_effects.insert(id, compiled);
+    }
+}
+
+fn compile_custom_effect(state: &State, id: ShaderProgramId) -> Option<CustomEffect> {
+    let Some(program) = deadlib_render_core::shader_program(id) else {
+        warn!("wgpu: shader program {id} is not registered; drawing it unchanged.");
+        return None;
+    };
+    let Some(wgsl) = program.sources.wgsl.as_deref() else {
+        warn!(
+            "wgpu: shader program '{}' has no WGSL source; drawing it unchanged.",
+            program.name
+        );
+        return None;
+    };
+    let device = &state.device;
+    let scope = device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("wgpu custom effect shader module"),
+        source: wgpu::ShaderSource::Wgsl(Cow::Owned(format!(
+            "{EFFECT_PRELUDE}\n{wgsl}\n{CUSTOM_EFFECT_MAIN}"
+        ))),
+    });
+    let pipelines = |format, write_mask| {
+        let effect = |mode| {
+            build_effect_pipeline(
+                device,
+                &state.effect_pipeline_layout,
+                format,
+                write_mask,
+                mode,
+                &shader,
+            )
+        };
+        PipelineSet {
+            alpha: effect(BlendMode::Alpha),
+            add: effect(BlendMode::Add),
+            multiply: effect(BlendMode::Multiply),
+            subtract: effect(BlendMode::Subtract),
+        }
+    };
+    let custom = CustomEffect {
+        screen: pipelines(state.config.format, surface_write_mask()),
+        target: pipelines(RENDER_TARGET_FORMAT, wgpu::ColorWrites::ALL),
+    };
+    if let Some(error) = pollster::block_on(scope.pop()) {
+        warn!(
+            "wgpu: shader program '{}' failed to compile; drawing it unchanged: {error}",
+            program.name
+        );
+        return None;
+    }
+    Some(custom)
+}
+
 #[inline(always)]
 fn upload_projections(state: &mut State, cameras: &[Matrix4]) {
     let ProjState::Uniform { .. } = state.proj else {
@@ -1557,7 +2145,13 @@ pub fn set_default_projection(state: &mut State, projection: Matrix4) {
     state.projection = projection;
 }
 
+pub fn release_render_targets(state: &mut State) {
+    state.render_targets.clear();
+    state.target_pipelines = None;
+}
+
 pub fn cleanup(state: &mut State) {
+    release_render_targets(state);
     info!("{} (wgpu) backend cleanup complete.", state.api.name());
 }
 
@@ -1657,11 +2251,75 @@ fn ensure_projection_capacity(state: &mut State, needed: usize) -> bool {
     true
 }
 
+fn init_effect_uniforms(device: &wgpu::Device) -> EffectUniformBuffer {
+    let align = device.limits().min_uniform_buffer_offset_alignment as u64;
+    let stride = if align > 0 {
+        EFFECT_BYTES.div_ceil(align) * align
+    } else {
+        EFFECT_BYTES
+    };
+    let capacity = 4usize;
+    let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("wgpu effect layout"),
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: true,
+                min_binding_size: wgpu::BufferSize::new(EFFECT_BYTES),
+            },
+            count: None,
+        }],
+    });
+    let buffer = create_effect_buffer(device, capacity as u64 * stride);
+    let group = create_effect_group(device, &layout, &buffer);
+    EffectUniformBuffer {
+        layout,
+        buffer,
+        group,
+        stride,
+        capacity,
+        upload: Vec::with_capacity(capacity * stride as usize),
+    }
+}
+
+fn create_effect_buffer(device: &wgpu::Device, size: u64) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("wgpu effect uniforms"),
+        size,
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+fn create_effect_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("wgpu effect group"),
+        layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                buffer,
+                offset: 0,
+                size: wgpu::BufferSize::new(EFFECT_BYTES),
+            }),
+        }],
+    })
+}
+
 fn reconfigure_surface(state: &mut State) {
     if state.window_size.0 == 0 || state.window_size.1 == 0 {
         return;
     }
-    let caps = state.surface.get_capabilities(&state.adapter);
+    let Some(surface) = state.surface.as_ref() else {
+        return;
+    };
+    let caps = surface.get_capabilities(&state.adapter);
     let new_format = pick_format(&caps);
     let format_changed = new_format != state.config.format;
     state.config.format = new_format;
@@ -1674,7 +2332,7 @@ fn reconfigure_surface(state: &mut State) {
     state.config.usage = pick_surface_usage(&caps);
     state.config.width = state.window_size.0;
     state.config.height = state.window_size.1;
-    state.surface.configure(&state.device, &state.config);
+    surface.configure(&state.device, &state.config);
     (state.depth_texture, state.depth_view) = create_depth_target(
         &state.device,
         state.config.width.max(1),
@@ -1714,6 +2372,17 @@ fn reconfigure_surface(state: &mut State) {
         state.tmesh_pipeline_layout = tmesh_pipeline_layout;
         state.tmesh_pipelines = tmesh_pipelines;
         state.tmesh_depth_pipelines = tmesh_depth_pipelines;
+        let (effect_shader, effect_pipeline_layout, effect_pipelines) = build_effect_pipeline_set(
+            &state.device,
+            &state.bind_layout,
+            &state.effect_uniforms.layout,
+            state.config.format,
+        );
+        state.effect_shader = effect_shader;
+        state.effect_pipeline_layout = effect_pipeline_layout;
+        state.effect_pipelines = effect_pipelines;
+        state.custom_effects.clear();
+        state.target_pipelines = None;
     }
 }
 
@@ -1822,6 +2491,8 @@ fn blend_state(mode: BlendMode) -> Option<wgpu::BlendState> {
         operation: op,
     };
     match mode {
+        // Alpha factors only matter in render targets, where coverage must
+        // accumulate the way the software backend composites it.
         BlendMode::Alpha => Some(wgpu::BlendState {
             color: comp(
                 wgpu::BlendFactor::SrcAlpha,
@@ -1829,7 +2500,7 @@ fn blend_state(mode: BlendMode) -> Option<wgpu::BlendState> {
                 wgpu::BlendOperation::Add,
             ),
             alpha: comp(
-                wgpu::BlendFactor::SrcAlpha,
+                wgpu::BlendFactor::One,
                 wgpu::BlendFactor::OneMinusSrcAlpha,
                 wgpu::BlendOperation::Add,
             ),
@@ -1841,7 +2512,7 @@ fn blend_state(mode: BlendMode) -> Option<wgpu::BlendState> {
                 wgpu::BlendOperation::Add,
             ),
             alpha: comp(
-                wgpu::BlendFactor::SrcAlpha,
+                wgpu::BlendFactor::One,
                 wgpu::BlendFactor::One,
                 wgpu::BlendOperation::Add,
             ),
@@ -1908,12 +2579,27 @@ fn build_pipeline_set(
     };
 
     let pipelines = PipelineSet {
-        alpha: build_pipeline(device, &pipeline_layout, format, BlendMode::Alpha, &shader),
-        add: build_pipeline(device, &pipeline_layout, format, BlendMode::Add, &shader),
+        alpha: build_pipeline(
+            device,
+            &pipeline_layout,
+            format,
+            surface_write_mask(),
+            BlendMode::Alpha,
+            &shader,
+        ),
+        add: build_pipeline(
+            device,
+            &pipeline_layout,
+            format,
+            surface_write_mask(),
+            BlendMode::Add,
+            &shader,
+        ),
         multiply: build_pipeline(
             device,
             &pipeline_layout,
             format,
+            surface_write_mask(),
             BlendMode::Multiply,
             &shader,
         ),
@@ -1921,6 +2607,7 @@ fn build_pipeline_set(
             device,
             &pipeline_layout,
             format,
+            surface_write_mask(),
             BlendMode::Subtract,
             &shader,
         ),
@@ -1960,12 +2647,27 @@ fn build_mesh_pipeline_set(
     };
 
     let pipelines = MeshPipelineSet {
-        alpha: build_mesh_pipeline(device, &pipeline_layout, format, BlendMode::Alpha, &shader),
-        add: build_mesh_pipeline(device, &pipeline_layout, format, BlendMode::Add, &shader),
+        alpha: build_mesh_pipeline(
+            device,
+            &pipeline_layout,
+            format,
+            surface_write_mask(),
+            BlendMode::Alpha,
+            &shader,
+        ),
+        add: build_mesh_pipeline(
+            device,
+            &pipeline_layout,
+            format,
+            surface_write_mask(),
+            BlendMode::Add,
+            &shader,
+        ),
         multiply: build_mesh_pipeline(
             device,
             &pipeline_layout,
             format,
+            surface_write_mask(),
             BlendMode::Multiply,
             &shader,
         ),
@@ -1973,6 +2675,7 @@ fn build_mesh_pipeline_set(
             device,
             &pipeline_layout,
             format,
+            surface_write_mask(),
             BlendMode::Subtract,
             &shader,
         ),
@@ -2025,6 +2728,7 @@ fn build_textured_mesh_pipeline_set(
             device,
             &pipeline_layout,
             format,
+            surface_write_mask(),
             BlendMode::Alpha,
             &shader,
             false,
@@ -2033,6 +2737,7 @@ fn build_textured_mesh_pipeline_set(
             device,
             &pipeline_layout,
             format,
+            surface_write_mask(),
             BlendMode::Add,
             &shader,
             false,
@@ -2041,6 +2746,7 @@ fn build_textured_mesh_pipeline_set(
             device,
             &pipeline_layout,
             format,
+            surface_write_mask(),
             BlendMode::Multiply,
             &shader,
             false,
@@ -2049,6 +2755,7 @@ fn build_textured_mesh_pipeline_set(
             device,
             &pipeline_layout,
             format,
+            surface_write_mask(),
             BlendMode::Subtract,
             &shader,
             false,
@@ -2059,6 +2766,7 @@ fn build_textured_mesh_pipeline_set(
             device,
             &pipeline_layout,
             format,
+            surface_write_mask(),
             BlendMode::Alpha,
             &shader,
             true,
@@ -2067,6 +2775,7 @@ fn build_textured_mesh_pipeline_set(
             device,
             &pipeline_layout,
             format,
+            surface_write_mask(),
             BlendMode::Add,
             &shader,
             true,
@@ -2075,6 +2784,7 @@ fn build_textured_mesh_pipeline_set(
             device,
             &pipeline_layout,
             format,
+            surface_write_mask(),
             BlendMode::Multiply,
             &shader,
             true,
@@ -2083,6 +2793,7 @@ fn build_textured_mesh_pipeline_set(
             device,
             &pipeline_layout,
             format,
+            surface_write_mask(),
             BlendMode::Subtract,
             &shader,
             true,
@@ -2092,10 +2803,174 @@ fn build_textured_mesh_pipeline_set(
     (shader, pipeline_layout, pipelines, depth_pipelines)
 }
 
+fn build_target_pipelines(state: &State) -> TargetPipelines {
+    let device = &state.device;
+    let format = RENDER_TARGET_FORMAT;
+    let write_mask = wgpu::ColorWrites::ALL;
+    let sprite = |mode| {
+        build_pipeline(
+            device,
+            &state.pipeline_layout,
+            format,
+            write_mask,
+            mode,
+            &state.shader,
+        )
+    };
+    let mesh = |mode| {
+        build_mesh_pipeline(
+            device,
+            &state.mesh_pipeline_layout,
+            format,
+            write_mask,
+            mode,
+            &state.mesh_shader,
+        )
+    };
+    let tmesh = |mode, use_depth| {
+        build_tmesh_pipeline(
+            device,
+            &state.tmesh_pipeline_layout,
+            format,
+            write_mask,
+            mode,
+            &state.tmesh_shader,
+            use_depth,
+        )
+    };
+    let effect = |mode| {
+        build_effect_pipeline(
+            device,
+            &state.effect_pipeline_layout,
+            format,
+            write_mask,
+            mode,
+            &state.effect_shader,
+        )
+    };
+    TargetPipelines {
+        sprite: PipelineSet {
+            alpha: sprite(BlendMode::Alpha),
+            add: sprite(BlendMode::Add),
+            multiply: sprite(BlendMode::Multiply),
+            subtract: sprite(BlendMode::Subtract),
+        },
+        mesh: MeshPipelineSet {
+            alpha: mesh(BlendMode::Alpha),
+            add: mesh(BlendMode::Add),
+            multiply: mesh(BlendMode::Multiply),
+            subtract: mesh(BlendMode::Subtract),
+        },
+        tmesh: PipelineSet {
+            alpha: tmesh(BlendMode::Alpha, false),
+            add: tmesh(BlendMode::Add, false),
+            multiply: tmesh(BlendMode::Multiply, false),
+            subtract: tmesh(BlendMode::Subtract, false),
+        },
+        tmesh_depth: PipelineSet {
+            alpha: tmesh(BlendMode::Alpha, true),
+            add: tmesh(BlendMode::Add, true),
+            multiply: tmesh(BlendMode::Multiply, true),
+            subtract: tmesh(BlendMode::Subtract, true),
+        },
+        effect: PipelineSet {
+            alpha: effect(BlendMode::Alpha),
+            add: effect(BlendMode::Add),
+            multiply: effect(BlendMode::Multiply),
+            subtract: effect(BlendMode::Subtract),
+        },
+    }
+}
+
+fn build_effect_pipeline_set(
+    device: &wgpu::Device,
+    bind_layout: &wgpu::BindGroupLayout,
+    effect_layout: &wgpu::BindGroupLayout,
+    format: wgpu::TextureFormat,
+) -> (wgpu::ShaderModule, wgpu::PipelineLayout, PipelineSet) {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("wgpu effect shader module"),
+        source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(EFFECT_SHADER)),
+    });
+    let layouts = [Some(bind_layout), Some(effect_layout)];
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("wgpu effect pipeline layout"),
+        bind_group_layouts: &layouts,
+        immediate_size: 0,
+    });
+    let effect = |mode| {
+        build_effect_pipeline(
+            device,
+            &pipeline_layout,
+            format,
+            surface_write_mask(),
+            mode,
+            &shader,
+        )
+    };
+    let pipelines = PipelineSet {
+        alpha: effect(BlendMode::Alpha),
+        add: effect(BlendMode::Add),
+        multiply: effect(BlendMode::Multiply),
+        subtract: effect(BlendMode::Subtract),
+    };
+    (shader, pipeline_layout, pipelines)
+}
+
+fn build_effect_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    format: wgpu::TextureFormat,
+    write_mask: wgpu::ColorWrites,
+    mode: BlendMode,
+    shader: &wgpu::ShaderModule,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("wgpu effect pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs_main"),
+            buffers: &[Some(vertex_layout())],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs_main"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: blend_state(mode),
+                write_mask,
+            })],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            unclipped_depth: false,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: DEPTH_FORMAT,
+            depth_write_enabled: Some(false),
+            depth_compare: Some(wgpu::CompareFunction::Always),
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState::default(),
+        multiview_mask: None,
+        cache: None,
+    })
+}
+
 fn build_pipeline(
     device: &wgpu::Device,
     layout: &wgpu::PipelineLayout,
     format: wgpu::TextureFormat,
+    write_mask: wgpu::ColorWrites,
     mode: BlendMode,
     shader: &wgpu::ShaderModule,
 ) -> wgpu::RenderPipeline {
@@ -2114,7 +2989,7 @@ fn build_pipeline(
             targets: &[Some(wgpu::ColorTargetState {
                 format,
                 blend: blend_state(mode),
-                write_mask: surface_write_mask(),
+                write_mask,
             })],
             compilation_options: wgpu::PipelineCompilationOptions::default(),
         }),
@@ -2144,6 +3019,7 @@ fn build_mesh_pipeline(
     device: &wgpu::Device,
     layout: &wgpu::PipelineLayout,
     format: wgpu::TextureFormat,
+    write_mask: wgpu::ColorWrites,
     mode: BlendMode,
     shader: &wgpu::ShaderModule,
 ) -> wgpu::RenderPipeline {
@@ -2162,7 +3038,7 @@ fn build_mesh_pipeline(
             targets: &[Some(wgpu::ColorTargetState {
                 format,
                 blend: blend_state(mode),
-                write_mask: surface_write_mask(),
+                write_mask,
             })],
             compilation_options: wgpu::PipelineCompilationOptions::default(),
         }),
@@ -2192,6 +3068,7 @@ fn build_tmesh_pipeline(
     device: &wgpu::Device,
     layout: &wgpu::PipelineLayout,
     format: wgpu::TextureFormat,
+    write_mask: wgpu::ColorWrites,
     mode: BlendMode,
     shader: &wgpu::ShaderModule,
     use_depth: bool,
@@ -2214,7 +3091,7 @@ fn build_tmesh_pipeline(
             targets: &[Some(wgpu::ColorTargetState {
                 format,
                 blend: blend_state(mode),
-                write_mask: surface_write_mask(),
+                write_mask,
             })],
             compilation_options: wgpu::PipelineCompilationOptions::default(),
         }),
@@ -2327,6 +3204,7 @@ const INSTANCE_ATTRS: [wgpu::VertexAttribute; 10] = wgpu::vertex_attr_array![
 ];
 
 const PROJ_BYTES: u64 = mem::size_of::<[[f32; 4]; 4]>() as u64;
+const EFFECT_BYTES: u64 = mem::size_of::<EffectUniforms>() as u64;
 
 #[inline(always)]
 fn cast_slice<T: bytemuck::Pod>(data: &[T]) -> &[u8] {
@@ -2406,6 +3284,12 @@ const TMESH_SHADER_IMM: &str = include_str!("shaders/wgpu_tmesh.wgsl");
 const SHADER_UBO: &str = include_str!("shaders/wgpu_sprite_ubo.wgsl");
 const MESH_SHADER_UBO: &str = include_str!("shaders/wgpu_mesh_ubo.wgsl");
 const TMESH_SHADER_UBO: &str = include_str!("shaders/wgpu_tmesh_ubo.wgsl");
+const EFFECT_PRELUDE: &str = include_str!("shaders/wgpu_effect_prelude.wgsl");
+const EFFECT_SHADER: &str = concat!(
+    include_str!("shaders/wgpu_effect_prelude.wgsl"),
+    include_str!("shaders/wgpu_effect.wgsl")
+);
+const CUSTOM_EFFECT_MAIN: &str = include_str!("shaders/wgpu_effect_custom.wgsl");
 
 #[cfg(test)]
 mod tests {
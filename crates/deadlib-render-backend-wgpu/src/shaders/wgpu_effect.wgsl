@@ -0,0 +1,48 @@
+const LUMA: vec3<f32> = vec3<f32>(0.2126, 0.7152, 0.0722);
+const TAU: f32 = 6.28318530718;
+
+// Mirrors the software backend's shade_effect tap for tap.
+fn shade(uv: vec2<f32>) -> vec4<f32> {
+    let params = u_effect.params;
+    let texel = u_effect.texel_size;
+    switch u_effect.program {
+        case 0u: {
+            let weights = array<f32, 5>(1.0, 4.0, 6.0, 4.0, 1.0);
+            let step_px = params.x * 0.5;
+            var sum = vec4<f32>(0.0);
+            for (var j = 0; j < 5; j++) {
+                for (var i = 0; i < 5; i++) {
+                    let offset = vec2<f32>(f32(i - 2), f32(j - 2)) * step_px * texel;
+                    sum += tap(uv + offset) * (weights[i] * weights[j] / 256.0);
+                }
+            }
+            return sum;
+        }
+        case 1u: {
+            let offset = params.xy * texel;
+            let r = tap(uv + offset);
+            let g = tap(uv);
+            let b = tap(uv - offset);
+            return vec4<f32>(r.r, g.g, b.b, max(max(r.a, g.a), b.a));
+        }
+        case 2u: {
+            let c = tap(uv);
+            let luma = dot(c.rgb, LUMA);
+            let graded = (mix(vec3<f32>(luma), c.rgb, params.x) - 0.5) * params.y + 0.5 + params.z;
+            return vec4<f32>(clamp(graded, vec3<f32>(0.0), vec3<f32>(1.0)), c.a);
+        }
+        default: {
+            if (params.y <= 0.0) {
+                return tap(uv);
+            }
+            let y = uv.y / texel.y;
+            let shift = params.x * sin(TAU * y / params.y + params.z);
+            return tap(vec2<f32>(uv.x + shift * texel.x, uv.y));
+        }
+    }
+}
+
+@fragment
+fn fs_main(input: VertexOut) -> @location(0) vec4<f32> {
+    return shade(input.uv) * u_effect.tint;
+}
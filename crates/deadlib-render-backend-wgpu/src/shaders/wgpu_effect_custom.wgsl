@@ -0,0 +1,4 @@
+@fragment
+fn fs_main(input: VertexOut) -> @location(0) vec4<f32> {
+    return effect(input.uv) * u_effect.tint;
+}
@@ -0,0 +1,37 @@
+// Shared by the built-in effects and user programs. A user program defines
+// `fn effect(uv: vec2<f32>) -> vec4<f32>` returning the untinted colour and
+// may read `u_effect.params` and `u_effect.texel_size` or sample through `tap`.
+
+struct Effect {
+    tint: vec4<f32>,
+    params: vec4<f32>,
+    texel_size: vec2<f32>,
+    program: u32,
+    _pad: u32,
+};
+
+@group(0) @binding(0) var u_sampler: sampler;
+@group(0) @binding(1) var u_tex: texture_2d<f32>;
+@group(1) @binding(0) var<uniform> u_effect: Effect;
+
+struct VertexIn {
+    @location(0) pos: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+};
+
+struct VertexOut {
+    @builtin(position) pos: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(input: VertexIn) -> VertexOut {
+    var out: VertexOut;
+    out.pos = vec4<f32>(input.pos * 2.0, 0.0, 1.0);
+    out.uv = input.uv;
+    return out;
+}
+
+fn tap(uv: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(u_tex, u_sampler, uv, 0.0);
+}
@@ -0,0 +1,254 @@
+use deadlib_render_backend_software as software;
+use deadlib_render_backend_wgpu as wgpu_backend;
+use deadlib_render_core::{
+    BlendMode, DrawOp, EffectRun, RenderFrame, RenderTargetPass, SamplerDesc, SamplerFilter,
+    SamplerWrap, ShaderEffect, ShaderProgram, ShaderSources, SpriteInstanceRaw, SpriteRun,
+    TextureHandle, register_shader_program, render_target_handle,
+};
+use glam::Mat4;
+use image::{Rgba, RgbaImage};
+use std::collections::HashMap;
+
+const WIDTH: u32 = 96;
+const HEIGHT: u32 = 80;
+const TEXTURE_HANDLE: TextureHandle = 7;
+const TRAIL_SLOT: u32 = 0;
+const COPY_SLOT: u32 = 1;
+// Blend rounding may land one step apart per pass; two frames of blending
+// stay within two.
+const TOLERANCE: u8 = 2;
+const SAMPLER: SamplerDesc = SamplerDesc {
+    filter: SamplerFilter::Linear,
+    wrap: SamplerWrap::Clamp,
+    mipmaps: false,
+};
+
+struct SoftwareTextures(software::Texture);
+
+impl software::TextureLookup for SoftwareTextures {
+    fn software_texture(&self, handle: TextureHandle) -> Option<&software::Texture> {
+        (handle == TEXTURE_HANDLE).then_some(&self.0)
+    }
+}
+
+struct WgpuTextures(wgpu_backend::Texture);
+
+impl wgpu_backend::TextureLookup for WgpuTextures {
+    fn wgpu_texture(&self, handle: TextureHandle) -> Option<&wgpu_backend::Texture> {
+        (handle == TEXTURE_HANDLE).then_some(&self.0)
+    }
+}
+
+fn projection() -> Mat4 {
+    let half_w = 0.5 * 480.0 * WIDTH as f32 / HEIGHT as f32;
+    glam::camera::rh::proj::opengl::orthographic(-half_w, half_w, -240.0, 240.0, -1.0, 1.0)
+}
+
+fn instance(center: [f32; 2], size: [f32; 2], alpha: f32) -> SpriteInstanceRaw {
+    SpriteInstanceRaw {
+        center: [center[0], center[1], 0.0, 1.0],
+        size,
+        rot_sin_cos: [0.0, 1.0],
+        tint: [1.0, 1.0, 1.0, alpha],
+        uv_scale: [1.0; 2],
+        uv_offset: [0.0; 2],
+        local_offset: [0.0; 2],
+        local_offset_rot_sin_cos: [0.0, 1.0],
+        edge_fade: [0.0; 4],
+        texture_mask: 0.0,
+    }
+}
+
+fn run(instance_start: u32, instance_count: u32, texture_handle: TextureHandle) -> DrawOp {
+    DrawOp::Sprite(SpriteRun {
+        instance_start,
+        instance_count,
+        blend: BlendMode::Alpha,
+        texture_handle,
+        camera: 0,
+    })
+}
+
+const fn pass(slot: u32, clear: bool) -> DrawOp {
+    DrawOp::BeginTarget(RenderTargetPass {
+        slot,
+        width: WIDTH,
+        height: HEIGHT,
+        clear,
+    })
+}
+
+/// A moving opaque square plus a fixed half-transparent one, drawn into a
+/// preserved target or into one fed last frame's copy. Edges sit on pixel
+/// boundaries so coverage rules cannot differ between rasterizers.
+fn trail_frame(x: f32, feedback: bool) -> RenderFrame {
+    let logical_width = 480.0 * WIDTH as f32 / HEIGHT as f32;
+    let ops = if feedback {
+        vec![
+            pass(COPY_SLOT, true),
+            run(0, 1, render_target_handle(TRAIL_SLOT)),
+            DrawOp::EndTarget,
+            pass(TRAIL_SLOT, true),
+            run(0, 1, render_target_handle(COPY_SLOT)),
+            run(1, 2, TEXTURE_HANDLE),
+            DrawOp::EndTarget,
+        ]
+    } else {
+        vec![
+            pass(TRAIL_SLOT, false),
+            run(1, 2, TEXTURE_HANDLE),
+            DrawOp::EndTarget,
+        ]
+    };
+    RenderFrame {
+        clear_color: [0.0, 0.0, 0.0, 1.0],
+        cameras: vec![projection()],
+        sprite_instances: vec![
+            instance([0.0, 0.0], [logical_width, 480.0], 1.0),
+            instance([x, 0.0], [60.0, 60.0], 1.0),
+            instance([0.0, 48.0], [120.0, 36.0], 0.5),
+        ],
+        mesh_vertices: Vec::new(),
+        tmesh_instances: Vec::new(),
+        tmesh_geometries: Vec::new(),
+        ops,
+    }
+}
+
+fn differing_pixels(expected: &RgbaImage, actual: &RgbaImage) -> Vec<(u32, u32)> {
+    assert_eq!(expected.dimensions(), actual.dimensions());
+    expected
+        .enumerate_pixels()
+        .filter(|(x, y, pixel)| {
+            pixel
+                .0
+                .iter()
+                .zip(actual.get_pixel(*x, *y).0)
+                .any(|(a, b)| a.abs_diff(b) > TOLERANCE)
+        })
+        .map(|(x, y, _)| (x, y))
+        .collect()
+}
+
+#[test]
+fn wgpu_render_targets_match_software_over_two_frames() {
+    let mut state = match wgpu_backend::init_headless(WIDTH, HEIGHT) {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("skipping wgpu target parity: {e}");
+            return;
+        }
+    };
+    let image = RgbaImage::from_pixel(8, 8, Rgba([200, 100, 50, 255]));
+    let software_textures =
+        SoftwareTextures(software::create_texture(&image, SAMPLER).expect("software texture"));
+    let wgpu_textures = WgpuTextures(
+        wgpu_backend::create_texture(&mut state, &image, SAMPLER).expect("wgpu texture"),
+    );
+
+    for feedback in [false, true] {
+        wgpu_backend::release_render_targets(&mut state);
+        let mut software_targets = HashMap::new();
+        for (frame_index, x) in [-96.0, 96.0].into_iter().enumerate() {
+            let frame = trail_frame(x, feedback);
+            software::draw_render_targets(
+                &frame,
+                &software_textures,
+                projection(),
+                &mut software_targets,
+            );
+            let wgpu_targets =
+                wgpu_backend::draw_render_targets(&mut state, &frame, &wgpu_textures)
+                    .expect("wgpu target readback");
+
+            let expected = &software_targets[&TRAIL_SLOT].image;
+            // Both squares must be in the target, so the diff below compares
+            // drawn content rather than two empty textures.
+            let left = expected.get_pixel(WIDTH / 2 - 16, HEIGHT / 2).0;
+            assert_eq!(left, [200, 100, 50, 255], "feedback: {feedback}");
+            assert_ne!(expected.get_pixel(WIDTH / 2, HEIGHT / 2 - 8).0[3], 0);
+            let differing = differing_pixels(expected, &wgpu_targets[&TRAIL_SLOT]);
+            assert!(
+                differing.is_empty(),
+                "feedback: {feedback}, frame {frame_index}: {} pixel(s) differ, first at {:?}",
+                differing.len(),
+                differing[0],
+            );
+        }
+    }
+}
+
+/// User programs have no software reference, which draws them unchanged. An
+/// identity program must compile and match that; a broken one must fall back
+/// to it instead of failing the frame.
+#[test]
+fn wgpu_custom_effects_match_software_passthrough() {
+    let mut state = match wgpu_backend::init_headless(WIDTH, HEIGHT) {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("skipping wgpu custom effect parity: {e}");
+            return;
+        }
+    };
+    let image = RgbaImage::from_pixel(8, 8, Rgba([200, 100, 50, 255]));
+    let software_textures =
+        SoftwareTextures(software::create_texture(&image, SAMPLER).expect("software texture"));
+    let wgpu_textures = WgpuTextures(
+        wgpu_backend::create_texture(&mut state, &image, SAMPLER).expect("wgpu texture"),
+    );
+
+    for (name, wgsl) in [
+        (
+            "parity-identity",
+            "fn effect(uv: vec2<f32>) -> vec4<f32> { return tap(uv); }",
+        ),
+        ("parity-broken", "fn effect(uv: vec2<f32>) -> vec4<f32> {"),
+    ] {
+        let effect = register_shader_program(ShaderProgram {
+            name: name.to_owned(),
+            params: Vec::new(),
+            sources: ShaderSources {
+                wgsl: Some(wgsl.into()),
+                glsl: None,
+            },
+        })
+        .expect("register");
+        let mut frame = trail_frame(0.0, false);
+        frame.ops.extend([
+            pass(COPY_SLOT, true),
+            DrawOp::Effect(EffectRun {
+                texture_handle: render_target_handle(TRAIL_SLOT),
+                effect: ShaderEffect::Custom {
+                    program: effect,
+                    params: [0.0; 4],
+                },
+                tint: [1.0; 4],
+                blend: BlendMode::Alpha,
+            }),
+            DrawOp::EndTarget,
+        ]);
+        wgpu_backend::release_render_targets(&mut state);
+        let mut software_targets = HashMap::new();
+        software::draw_render_targets(
+            &frame,
+            &software_textures,
+            projection(),
+            &mut software_targets,
+        );
+        let wgpu_targets = wgpu_backend::draw_render_targets(&mut state, &frame, &wgpu_textures)
+            .expect("wgpu target readback");
+
+        let expected = &software_targets[&COPY_SLOT].image;
+        assert_eq!(
+            expected.get_pixel(WIDTH / 2, HEIGHT / 2).0,
+            [200, 100, 50, 255]
+        );
+        let differing = differing_pixels(expected, &wgpu_targets[&COPY_SLOT]);
+        assert!(
+            differing.is_empty(),
+            "{name}: {} pixel(s) differ, first at {:?}",
+            differing.len(),
+            differing[0],
+        );
+    }
+}
@@ -0,0 +1,800 @@
+//! Recorded `RenderFrame` streams for replaying a renderer bug through
+//! another backend.
+//!
+//! A capture is a header followed by length-prefixed records. A texture
+//! record precedes the first frame that samples its handle and carries the
+//! full RGBA pixels; a later record for the same handle replaces them.
+//! Retained mesh geometry is written once per allocation and referenced by
+//! cache key afterwards, so replay sees the same reuse pattern. A user
+//! shader program is written with its sources before the first frame that
+//! runs it and registered again on replay.
+
+use crate::{
+    BlendMode, DrawOp, EffectRun, FastU64Map, MeshRun, ProjectionMatrix, RenderFrame,
+    RenderTargetPass, SamplerDesc, SamplerFilter, SamplerWrap, ShaderEffect, ShaderParam,
+    ShaderProgram, ShaderProgramId, ShaderSources, SpriteRun, TextureHandle, TexturedMeshGeometry,
+    TexturedMeshRun, TexturedMeshVertex, TexturedMeshVertices,
+};
+use bincode::{Decode, Encode};
+use bytemuck::Pod;
+use glam::Mat4;
+use std::io::{self, Read, Write};
+use std::sync::Arc;
+
+const CAPTURE_MAGIC: u64 = u64::from_le_bytes(*b"DSFRAMES");
+/// Version 2 adds user shader program records; version 1 captures only
+/// name built-in effects.
+const CAPTURE_VERSION: u32 = 2;
+
+/// One RGBA8 texture upload bound to `handle`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CapturedTexture {
+    pub handle: TextureHandle,
+    pub sampler: SamplerDesc,
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+/// One drawn frame with the surface state it was drawn against.
+#[derive(Clone)]
+pub struct CapturedFrame {
+    pub width: u32,
+    pub height: u32,
+    pub projection: ProjectionMatrix,
+    pub frame: RenderFrame,
+}
+
+pub enum CaptureEvent {
+    Texture(CapturedTexture),
+    Frame(Box<CapturedFrame>),
+}
+
+#[derive(Encode, Decode)]
+enum Record {
+    Texture(WireTexture),
+    Frame(WireFrame),
+    Program(WireProgram),
+}
+
+#[derive(Encode, Decode)]
+struct WireProgram {
+    id: u32,
+    name: String,
+    params: Vec<(String, f32)>,
+    wgsl: Option<String>,
+    glsl: Option<String>,
+}
+
+#[derive(Encode, Decode)]
+struct WireTexture {
+    handle: u64,
+    filter: u8,
+    wrap: u8,
+    mipmaps: bool,
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+#[derive(Encode, Decode)]
+struct WireFrame {
+    width: u32,
+    height: u32,
+    projection: [f32; 16],
+    clear_color: [f32; 4],
+    cameras: Vec<[f32; 16]>,
+    sprite_instances: Vec<u8>,
+    mesh_vertices: Vec<u8>,
+    tmesh_instances: Vec<u8>,
+    tmesh_geometries: Vec<WireGeometry>,
+    ops: Vec<WireOp>,
+}
+
+#[derive(Encode, Decode)]
+struct WireGeometry {
+    cache_key: u64,
+    vertices: WireVertices,
+}
+
+#[derive(Encode, Decode)]
+enum WireVertices {
+    Shared(Vec<u8>),
+    Reusable(Vec<u8>),
+    Transient(Vec<u8>),
+    /// The allocation last written under the same cache key.
+    Repeat,
+}
+
+#[derive(Encode, Decode)]
+enum WireOp {
+    Sprite {
+        instance_start: u32,
+        instance_count: u32,
+        blend: u8,
+        texture_handle: u64,
+        camera: u8,
+    },
+    Mesh {
+        vertex_start: u32,
+        vertex_count: u32,
+        blend: u8,
+        camera: u8,
+    },
+    TexturedMesh {
+        geometry: u32,
+        instance_start: u32,
+        instance_count: u32,
+        blend: u8,
+        texture_handle: u64,
+        camera: u8,
+        depth_test: bool,
+    },
+    Effect {
+        texture_handle: u64,
+        program: u32,
+        params: [f32; 4],
+        tint: [f32; 4],
+        blend: u8,
+    },
+    BeginTarget {
+        slot: u32,
+        width: u32,
+        height: u32,
+        clear: bool,
+    },
+    EndTarget,
+}
+
+/// Streams texture uploads and frames into a capture.
+///
+/// Retained geometry stays referenced until the writer is dropped so a
+/// reused address is never mistaken for the same allocation.
+pub struct CaptureWriter<W: Write> {
+    out: W,
+    retained: FastU64Map<TexturedMeshVertices>,
+    programs: Vec<ShaderProgramId>,
+    frames: u32,
+}
+
+impl<W: Write> CaptureWriter<W> {
+    pub fn new(mut out: W) -> io::Result<Self> {
+        out.write_all(&CAPTURE_MAGIC.to_le_bytes())?;
+        out.write_all(&CAPTURE_VERSION.to_le_bytes())?;
+        Ok(Self {
+            out,
+            retained: FastU64Map::default(),
+            programs: Vec::new(),
+            frames: 0,
+        })
+    }
+
+    #[inline(always)]
+    pub const fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    pub fn write_texture(&mut self, texture: CapturedTexture) -> io::Result<()> {
+        self.write_record(&Record::Texture(WireTexture {
+            handle: texture.handle,
+            filter: filter_code(texture.sampler.filter),
+            wrap: wrap_code(texture.sampler.wrap),
+            mipmaps: texture.sampler.mipmaps,
+            width: texture.width,
+            height: texture.height,
+            rgba: texture.rgba,
+        }))
+    }
+
+    /// Frames are flushed as they are written, so a capture cut short by a
+    /// driver crash still replays up to the last complete frame.
+    pub fn write_frame(
+        &mut self,
+        width: u32,
+        height: u32,
+        projection: ProjectionMatrix,
+        frame: &RenderFrame,
+    ) -> io::Result<()> {
+        for op in &frame.ops {
+            if let DrawOp::Effect(run) = op
+                && !run.effect.is_builtin()
+            {
+                self.write_program(run.effect.program())?;
+            }
+        }
+        let tmesh_geometries = frame
+            .tmesh_geometries
+            .iter()
+            .map(|geometry| self.wire_geometry(geometry))
+            .collect();
+        self.write_record(&Record::Frame(WireFrame {
+            width,
+            height,
+            projection: projection.to_cols_array(),
+            clear_color: frame.clear_color,
+            cameras: frame.cameras.iter().map(Mat4::to_cols_array).collect(),
+            sprite_instances: pod_bytes(&frame.sprite_instances),
+            mesh_vertices: pod_bytes(&frame.mesh_vertices),
+            tmesh_instances: pod_bytes(&frame.tmesh_instances),
+            tmesh_geometries,
+            ops: frame.ops.iter().map(|op| wire_op(*op)).collect(),
+        }))?;
+        self.out.flush()?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn write_program(&mut self, id: ShaderProgramId) -> io::Result<()> {
+        if self.programs.contains(&id) {
+            return Ok(());
+        }
+        let program = crate::shader_program(id)
+            .ok_or_else(|| io::Error::other("frame runs an unregistered shader program"))?;
+        self.programs.push(id);
+        self.write_record(&Record::Program(WireProgram {
+            id,
+            name: program.name.clone(),
+            params: program
+                .params
+                .iter()
+                .map(|param| (param.name.clone(), param.default))
+                .collect(),
+            wgsl: program.sources.wgsl.as_deref().map(str::to_owned),
+            glsl: program.sources.glsl.as_deref().map(str::to_owned),
+        }))
+    }
+
+    fn wire_geometry(&mut self, geometry: &TexturedMeshGeometry) -> WireGeometry {
+        let bytes = || pod_bytes(geometry.vertices.as_ref());
+        let vertices = match &geometry.vertices {
+            TexturedMeshVertices::Transient(_) => WireVertices::Transient(bytes()),
+            retained
+                if self
+                    .retained
+                    .get(&geometry.cache_key)
+                    .is_some_and(|known| same_allocation(known, retained)) =>
+            {
+                WireVertices::Repeat
+            }
+            retained => {
+                self.retained.insert(geometry.cache_key, retained.clone());
+                if matches!(retained, TexturedMeshVertices::Shared(_)) {
+                    WireVertices::Shared(bytes())
+                } else {
+                    WireVertices::Reusable(bytes())
+                }
+            }
+        };
+        WireGeometry {
+            cache_key: geometry.cache_key,
+            vertices,
+        }
+    }
+
+    fn write_record(&mut self, record: &Record) -> io::Result<()> {
+        let body = bincode::encode_to_vec(record, bincode::config::standard())
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        let len = u32::try_from(body.len())
+            .map_err(|_| io::Error::other("capture record exceeds 4 GiB"))?;
+        self.out.write_all(&len.to_le_bytes())?;
+        self.out.write_all(&body)
+    }
+}
+
+/// Reads a capture back as the events that were written.
+pub struct CaptureReader<R: Read> {
+    input: R,
+    retained: FastU64Map<TexturedMeshVertices>,
+    /// Captured program ids mapped to the ids they were registered under.
+    programs: FastU64Map<ShaderProgramId>,
+    body: Vec<u8>,
+}
+
+impl<R: Read> CaptureReader<R> {
+    pub fn new(mut input: R) -> io::Result<Self> {
+        let mut header = [0u8; 12];
+        input.read_exact(&mut header)?;
+        if header[..8] != CAPTURE_MAGIC.to_le_bytes() {
+            return Err(invalid("not a frame capture"));
+        }
+        let version = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+        if version != CAPTURE_VERSION {
+            return Err(invalid(&format!(
+                "unsupported frame capture version {version}"
+            )));
+        }
+        Ok(Self {
+            input,
+            retained: FastU64Map::default(),
+            programs: FastU64Map::default(),
+            body: Vec::new(),
+        })
+    }
+
+    /// `None` at a clean end of the capture.
+    pub fn next_event(&mut self) -> io::Result<Option<CaptureEvent>> {
+        loop {
+            let mut len = [0u8; 4];
+            if self.input.read(&mut len[..1])? == 0 {
+                return Ok(None);
+            }
+            self.input.read_exact(&mut len[1..])?;
+            self.body.resize(u32::from_le_bytes(len) as usize, 0);
+            self.input.read_exact(&mut self.body)?;
+            let (record, _) =
+                bincode::decode_from_slice::<Record, _>(&self.body, bincode::config::standard())
+                    .map_err(|e| invalid(&e.to_string()))?;
+            return Ok(Some(match record {
+                Record::Texture(texture) => CaptureEvent::Texture(CapturedTexture {
+                    handle: texture.handle,
+                    sampler: SamplerDesc {
+                        filter: filter_from_code(texture.filter)?,
+                        wrap: wrap_from_code(texture.wrap)?,
+                        mipmaps: texture.mipmaps,
+                    },
+                    width: texture.width,
+                    height: texture.height,
+                    rgba: texture.rgba,
+                }),
+                Record::Frame(frame) => CaptureEvent::Frame(Box::new(self.frame(frame)?)),
+                Record::Program(program) => {
+                    self.register_program(program)?;
+                    continue;
+                }
+            }));
+        }
+    }
+
+    fn register_program(&mut self, wire: WireProgram) -> io::Result<()> {
+        let id = crate::register_shader_program(ShaderProgram {
+            name: wire.name,
+            params: wire
+                .params
+                .into_iter()
+                .map(|(name, default)| ShaderParam { name, default })
+                .collect(),
+            sources: ShaderSources {
+                wgsl: wire.wgsl.map(Into::into),
+                glsl: wire.glsl.map(Into::into),
+            },
+        })
+        .map_err(|e| invalid(&e.to_string()))?;
+        self.programs.insert(u64::from(wire.id), id);
+        Ok(())
+    }
+
+    fn frame(&mut self, wire: WireFrame) -> io::Result<CapturedFrame> {
+        let mut tmesh_geometries = Vec::with_capacity(wire.tmesh_geometries.len());
+        for geometry in wire.tmesh_geometries {
+            let vertices = match geometry.vertices {
+                WireVertices::Transient(bytes) => {
+                    TexturedMeshVertices::Transient(pod_from_bytes(&bytes)?)
+                }
+                WireVertices::Shared(bytes) => {
+                    let vertices =
+                        TexturedMeshVertices::Shared(Arc::from(pod_from_bytes::<
+                            TexturedMeshVertex,
+                        >(&bytes)?));
+                    self.retained.insert(geometry.cache_key, vertices.clone());
+                    vertices
+                }
+                WireVertices::Reusable(bytes) => {
+                    let vertices =
+                        TexturedMeshVertices::Reusable(Arc::new(pod_from_bytes(&bytes)?));
+                    self.retained.insert(geometry.cache_key, vertices.clone());
+                    vertices
+                }
+                WireVertices::Repeat => self
+                    .retained
+                    .get(&geometry.cache_key)
+                    .cloned()
+                    .ok_or_else(|| invalid("geometry repeat before its first write"))?,
+            };
+            tmesh_geometries.push(TexturedMeshGeometry {
+                vertices,
+                cache_key: geometry.cache_key,
+            });
+        }
+        Ok(CapturedFrame {
+            width: wire.width,
+            height: wire.height,
+            projection: Mat4::from_cols_array(&wire.projection),
+            frame: RenderFrame {
+                clear_color: wire.clear_color,
+                cameras: wire.cameras.iter().map(Mat4::from_cols_array).collect(),
+                sprite_instances: pod_from_bytes(&wire.sprite_instances)?,
+                mesh_vertices: pod_from_bytes(&wire.mesh_vertices)?,
+                tmesh_instances: pod_from_bytes(&wire.tmesh_instances)?,
+                tmesh_geometries,
+                ops: wire
+                    .ops
+                    .into_iter()
+                    .map(|op| op_from_wire(op, &self.programs))
+                    .collect::<io::Result<_>>()?,
+            },
+        })
+    }
+}
+
+fn same_allocation(known: &TexturedMeshVertices, current: &TexturedMeshVertices) -> bool {
+    match (known, current) {
+        (TexturedMeshVertices::Shared(known), TexturedMeshVertices::Shared(current)) => {
+            Arc::ptr_eq(known, current)
+        }
+        (TexturedMeshVertices::Reusable(known), TexturedMeshVertices::Reusable(current)) => {
+            Arc::ptr_eq(known, current)
+        }
+        _ => false,
+    }
+}
+
+#[inline(always)]
+fn pod_bytes<T: Pod>(values: &[T]) -> Vec<u8> {
+    bytemuck::cast_slice(values).to_vec()
+}
+
+fn pod_from_bytes<T: Pod>(bytes: &[u8]) -> io::Result<Vec<T>> {
+    if !bytes.len().is_multiple_of(size_of::<T>()) {
+        return Err(invalid("truncated vertex or instance data"));
+    }
+    Ok(bytemuck::pod_collect_to_vec(bytes))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned())
+}
+
+const fn filter_code(filter: SamplerFilter) -> u8 {
+    match filter {
+        SamplerFilter::Linear => 0,
+        SamplerFilter::Nearest => 1,
+    }
+}
+
+fn filter_from_code(code: u8) -> io::Result<SamplerFilter> {
+    match code {
+        0 => Ok(SamplerFilter::Linear),
+        1 => Ok(SamplerFilter::Nearest),
+        _ => Err(invalid("unknown sampler filter")),
+    }
+}
+
+const fn wrap_code(wrap: SamplerWrap) -> u8 {
+    match wrap {
+        SamplerWrap::Clamp => 0,
+        SamplerWrap::Repeat => 1,
+    }
+}
+
+fn wrap_from_code(code: u8) -> io::Result<SamplerWrap> {
+    match code {
+        0 => Ok(SamplerWrap::Clamp),
+        1 => Ok(SamplerWrap::Repeat),
+        _ => Err(invalid("unknown sampler wrap")),
+    }
+}
+
+const fn blend_code(blend: BlendMode) -> u8 {
+    match blend {
+        BlendMode::Alpha => 0,
+        BlendMode::Add => 1,
+        BlendMode::Multiply => 2,
+        BlendMode::Subtract => 3,
+    }
+}
+
+fn blend_from_code(code: u8) -> io::Result<BlendMode> {
+    match code {
+        0 => Ok(BlendMode::Alpha),
+        1 => Ok(BlendMode::Add),
+        2 => Ok(BlendMode::Multiply),
+        3 => Ok(BlendMode::Subtract),
+        _ => Err(invalid("unknown blend mode")),
+    }
+}
+
+const fn wire_op(op: DrawOp) -> WireOp {
+    match op {
+        DrawOp::Sprite(run) => WireOp::Sprite {
+            instance_start: run.instance_start,
+            instance_count: run.instance_count,
+            blend: blend_code(run.blend),
+            texture_handle: run.texture_handle,
+            camera: run.camera,
+        },
+        DrawOp::Mesh(run) => WireOp::Mesh {
+            vertex_start: run.vertex_start,
+            vertex_count: run.vertex_count,
+            blend: blend_code(run.blend),
+            camera: run.camera,
+        },
+        DrawOp::TexturedMesh(run) => WireOp::TexturedMesh {
+            geometry: run.geometry,
+            instance_start: run.instance_start,
+            instance_count: run.instance_count,
+            blend: blend_code(run.blend),
+            texture_handle: run.texture_handle,
+            camera: run.camera,
+            depth_test: run.depth_test,
+        },
+        DrawOp::Effect(run) => WireOp::Effect {
+            texture_handle: run.texture_handle,
+            program: run.effect.program(),
+            params: run.effect.params(),
+            tint: run.tint,
+            blend: blend_code(run.blend),
+        },
+        DrawOp::BeginTarget(pass) => WireOp::BeginTarget {
+            slot: pass.slot,
+            width: pass.width,
+            height: pass.height,
+            clear: pass.clear,
+        },
+        DrawOp::EndTarget => WireOp::EndTarget,
+    }
+}
+
+fn op_from_wire(op: WireOp, programs: &FastU64Map<ShaderProgramId>) -> io::Result<DrawOp> {
+    Ok(match op {
+        WireOp::Sprite {
+            instance_start,
+            instance_count,
+            blend,
+            texture_handle,
+            camera,
+        } => DrawOp::Sprite(SpriteRun {
+            instance_start,
+            instance_count,
+            blend: blend_from_code(blend)?,
+            texture_handle,
+            camera,
+        }),
+        WireOp::Mesh {
+            vertex_start,
+            vertex_count,
+            blend,
+            camera,
+        } => DrawOp::Mesh(MeshRun {
+            vertex_start,
+            vertex_count,
+            blend: blend_from_code(blend)?,
+            camera,
+        }),
+        WireOp::TexturedMesh {
+            geometry,
+            instance_start,
+            instance_count,
+            blend,
+            texture_handle,
+            camera,
+            depth_test,
+        } => DrawOp::TexturedMesh(TexturedMeshRun {
+            geometry,
+            instance_start,
+            instance_count,
+            blend: blend_from_code(blend)?,
+            texture_handle,
+            camera,
+            depth_test,
+        }),
+        WireOp::Effect {
+            texture_handle,
+            program,
+            params,
+            tint,
+            blend,
+        } => {
+            let program = if (program as usize) < ShaderEffect::NAMES.len() {
+                program
+            } else {
+                *programs
+                    .get(&u64::from(program))
+                    .ok_or_else(|| invalid("unknown shader effect"))?
+            };
+            let effect = ShaderEffect::from_program(program, params);
+            DrawOp::Effect(EffectRun {
+                texture_handle,
+                effect,
+                tint,
+                blend: blend_from_code(blend)?,
+            })
+        }
+        WireOp::BeginTarget {
+            slot,
+            width,
+            height,
+            clear,
+        } => DrawOp::BeginTarget(RenderTargetPass {
+            slot,
+            width,
+            height,
+            clear,
+        }),
+        WireOp::EndTarget => DrawOp::EndTarget,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame_compare::compare_render_frames_semantic;
+    use crate::{TexturedMeshInstanceRaw, render_target_handle};
+
+    fn frame(geometry: &Arc<[TexturedMeshVertex]>) -> RenderFrame {
+        RenderFrame {
+            clear_color: [0.1, 0.2, 0.3, 1.0],
+            cameras: vec![Mat4::IDENTITY],
+            sprite_instances: Vec::new(),
+            mesh_vertices: vec![crate::MeshVertex::default(); 3],
+            tmesh_instances: vec![TexturedMeshInstanceRaw::new(
+                Mat4::IDENTITY,
+                [1.0; 4],
+                [1.0; 2],
+                [0.0; 2],
+                [0.0; 2],
+                true,
+            )],
+            tmesh_geometries: vec![TexturedMeshGeometry {
+                vertices: TexturedMeshVertices::Shared(Arc::clone(geometry)),
+                cache_key: 41,
+            }],
+            ops: vec![
+                DrawOp::BeginTarget(RenderTargetPass {
+                    slot: 0,
+                    width: 64,
+                    height: 32,
+                    clear: true,
+                }),
+                DrawOp::TexturedMesh(TexturedMeshRun {
+                    geometry: 0,
+                    instance_start: 0,
+                    instance_count: 1,
+                    blend: BlendMode::Add,
+                    texture_handle: 7,
+                    camera: 0,
+                    depth_test: false,
+                }),
+                DrawOp::EndTarget,
+                DrawOp::Effect(EffectRun {
+                    texture_handle: render_target_handle(0),
+                    effect: ShaderEffect::Wave {
+                        amplitude: 4.0,
+                        wavelength: 32.0,
+                        phase: 0.5,
+                    },
+                    tint: [1.0; 4],
+                    blend: BlendMode::Alpha,
+                }),
+            ],
+        }
+    }
+
+    #[test]
+    fn captures_round_trip_textures_frames_and_retained_geometry() {
+        let geometry: Arc<[TexturedMeshVertex]> = Arc::from([TexturedMeshVertex::default(); 3]);
+        let texture = CapturedTexture {
+            handle: 7,
+            sampler: SamplerDesc {
+                filter: SamplerFilter::Nearest,
+                wrap: SamplerWrap::Repeat,
+                mipmaps: true,
+            },
+            width: 1,
+            height: 2,
+            rgba: vec![1, 2, 3, 4, 5, 6, 7, 8],
+        };
+        let expected = frame(&geometry);
+        let mut writer = CaptureWriter::new(Vec::new()).expect("header");
+        writer.write_texture(texture.clone()).expect("texture");
+        writer
+            .write_frame(640, 480, Mat4::IDENTITY, &expected)
+            .expect("first frame");
+        writer
+            .write_frame(640, 480, Mat4::IDENTITY, &expected)
+            .expect("second frame");
+        assert_eq!(writer.frames(), 2);
+        let bytes = writer.finish().expect("finish");
+
+        let mut reader = CaptureReader::new(bytes.as_slice()).expect("header");
+        let Some(CaptureEvent::Texture(actual)) = reader.next_event().expect("texture") else {
+            panic!("texture record expected first");
+        };
+        assert_eq!(actual, texture);
+        let mut frames = Vec::new();
+        while let Some(event) = reader.next_event().expect("frame") {
+            let CaptureEvent::Frame(frame) = event else {
+                panic!("only frames follow");
+            };
+            assert_eq!((frame.width, frame.height), (640, 480));
+            assert_eq!(
+                compare_render_frames_semantic(&expected, &frame.frame),
+                Ok(())
+            );
+            frames.push(frame);
+        }
+        assert_eq!(frames.len(), 2);
+        let [first, second] =
+            [&frames[0], &frames[1]].map(|frame| match &frame.frame.tmesh_geometries[0].vertices {
+                TexturedMeshVertices::Shared(vertices) => Arc::clone(vertices),
+                _ => panic!("shared geometry keeps its storage"),
+            });
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn user_programs_travel_with_the_first_frame_that_runs_them() {
+        let program = crate::register_shader_program(ShaderProgram {
+            name: "capture-invert".to_owned(),
+            params: vec![ShaderParam {
+                name: "amount".to_owned(),
+                default: 1.0,
+            }],
+            sources: ShaderSources {
+                wgsl: None,
+                glsl: Some(
+                    "vec4 effect(vec2 uv) { vec4 c = tap(uv); return vec4(1.0 - c.rgb, c.a); }"
+                        .into(),
+                ),
+            },
+        })
+        .expect("register");
+        let mut expected = frame(&Arc::from([TexturedMeshVertex::default(); 3]));
+        let Some(DrawOp::Effect(run)) = expected.ops.last_mut() else {
+            panic!("frame ends with an effect");
+        };
+        run.effect = ShaderEffect::Custom {
+            program,
+            params: [0.5, 0.0, 0.0, 0.0],
+        };
+        let mut writer = CaptureWriter::new(Vec::new()).expect("header");
+        for _ in 0..2 {
+            writer
+                .write_frame(1, 1, Mat4::IDENTITY, &expected)
+                .expect("frame");
+        }
+        let bytes = writer.finish().expect("finish");
+
+        let mut reader = CaptureReader::new(bytes.as_slice()).expect("header");
+        let mut frames = 0;
+        while let Some(event) = reader.next_event().expect("event") {
+            let CaptureEvent::Frame(frame) = event else {
+                panic!("program records produce no event");
+            };
+            assert_eq!(
+                compare_render_frames_semantic(&expected, &frame.frame),
+                Ok(())
+            );
+            frames += 1;
+        }
+        assert_eq!(frames, 2);
+        assert_eq!(
+            bytes
+                .windows(14)
+                .filter(|w| *w == b"capture-invert")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn truncated_captures_are_rejected() {
+        assert!(CaptureReader::new(&b"DSFRAMES"[..]).is_err());
+        let mut writer = CaptureWriter::new(Vec::new()).expect("header");
+        writer
+            .write_frame(
+                1,
+                1,
+                Mat4::IDENTITY,
+                &frame(&Arc::from([TexturedMeshVertex::default(); 3])),
+            )
+            .expect("frame");
+        let bytes = writer.finish().expect("finish");
+        let mut reader = CaptureReader::new(&bytes[..bytes.len() - 1]).expect("header");
+        assert!(reader.next_event().is_err());
+    }
+}
@@ -0,0 +1,460 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+/// Index of a fragment program in the [`ShaderRegistry`]; the shaders switch
+/// on it.
+pub type ShaderProgramId = u32;
+
+/// Uniform parameters a program receives, packed into one `vec4`.
+pub const MAX_SHADER_PARAMS: usize = 4;
+
+/// Fragment effects applied while compositing a render target.
+///
+/// The built-in variants run the same program on every backend that supports
+/// effects; the software backend is their reference implementation. Lengths
+/// are in target pixels and angles in radians. `Custom` runs a program
+/// registered with [`register_shader_program`] on the backends that can
+/// compile its sources and draws the target unchanged elsewhere.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShaderEffect {
+    /// 5x5 binomial blur whose taps sit `radius / 2` pixels apart.
+    Blur { radius: f32 },
+    /// Samples red at `+offset` and blue at `-offset` around green.
+    ChromaticAberration { offset: [f32; 2] },
+    /// Saturation around Rec. 709 luma, contrast around mid-grey, then an
+    /// additive brightness shift.
+    ColorGrade {
+        saturation: f32,
+        contrast: f32,
+        brightness: f32,
+    },
+    /// Shifts each row horizontally by `amplitude * sin(2π y / wavelength +
+    /// phase)`.
+    Wave {
+        amplitude: f32,
+        wavelength: f32,
+        phase: f32,
+    },
+    /// A user-provided program and its uniform parameters.
+    Custom {
+        program: ShaderProgramId,
+        params: [f32; MAX_SHADER_PARAMS],
+    },
+}
+
+impl ShaderEffect {
+    /// Built-in program names in `program()` order. They are registered
+    /// first, so their ids never change.
+    pub const NAMES: [&'static str; 4] = ["blur", "chromaticaberration", "colorgrade", "wave"];
+
+    /// Draws the source unchanged; backends substitute it for custom programs
+    /// they cannot run.
+    pub const PASSTHROUGH: Self = Self::ColorGrade {
+        saturation: 1.0,
+        contrast: 1.0,
+        brightness: 0.0,
+    };
+
+    /// Looks up a registered program by name (case-insensitive) and fills its
+    /// uniforms from `params` in declaration order; missing values keep the
+    /// program's defaults.
+    pub fn from_name(name: &str, params: &[f32]) -> Option<Self> {
+        let registry = shader_registry()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        registry.effect(name, params)
+    }
+
+    /// Builds the effect for `program` from fully resolved parameters.
+    pub const fn from_program(program: ShaderProgramId, params: [f32; MAX_SHADER_PARAMS]) -> Self {
+        match program {
+            0 => Self::Blur { radius: params[0] },
+            1 => Self::ChromaticAberration {
+                offset: [params[0], params[1]],
+            },
+            2 => Self::ColorGrade {
+                saturation: params[0],
+                contrast: params[1],
+                brightness: params[2],
+            },
+            3 => Self::Wave {
+                amplitude: params[0],
+                wavelength: params[1],
+                phase: params[2],
+            },
+            _ => Self::Custom { program, params },
+        }
+    }
+
+    /// Program index the shaders switch on.
+    #[inline(always)]
+    pub const fn program(self) -> ShaderProgramId {
+        match self {
+            Self::Blur { .. } => 0,
+            Self::ChromaticAberration { .. } => 1,
+            Self::ColorGrade { .. } => 2,
+            Self::Wave { .. } => 3,
+            Self::Custom { program, .. } => program,
+        }
+    }
+
+    #[inline(always)]
+    pub const fn is_builtin(self) -> bool {
+        !matches!(self, Self::Custom { .. })
+    }
+
+    /// Packed uniform parameters, in declaration order.
+    #[inline(always)]
+    pub const fn params(self) -> [f32; MAX_SHADER_PARAMS] {
+        match self {
+            Self::Blur { radius } => [radius, 0.0, 0.0, 0.0],
+            Self::ChromaticAberration { offset } => [offset[0], offset[1], 0.0, 0.0],
+            Self::ColorGrade {
+                saturation,
+                contrast,
+                brightness,
+            } => [saturation, contrast, brightness, 0.0],
+            Self::Wave {
+                amplitude,
+                wavelength,
+                phase,
+            } => [amplitude, wavelength, phase, 0.0],
+            Self::Custom { params, .. } => params,
+        }
+    }
+}
+
+/// A named uniform parameter and the value it takes when a request omits it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShaderParam {
+    pub name: String,
+    pub default: f32,
+}
+
+/// Fragment sources for a user program, one per shading language.
+///
+/// Each source defines `effect(uv)` returning the untinted colour. The
+/// backend supplies the entry point, the `tap(uv)` sampler helper and the
+/// `params` and `texel_size` uniforms; see the wgpu and OpenGL effect shaders
+/// for the exact prelude. Built-ins carry no sources.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ShaderSources {
+    pub wgsl: Option<Arc<str>>,
+    pub glsl: Option<Arc<str>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShaderProgram {
+    pub name: String,
+    pub params: Vec<ShaderParam>,
+    pub sources: ShaderSources,
+}
+
+impl ShaderProgram {
+    /// Program defaults overlaid with `values` in declaration order.
+    pub fn resolve_params(&self, values: &[f32]) -> [f32; MAX_SHADER_PARAMS] {
+        let mut params = [0.0; MAX_SHADER_PARAMS];
+        for (index, param) in self.params.iter().enumerate() {
+            params[index] = values.get(index).copied().unwrap_or(param.default);
+        }
+        params
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShaderRegistryError {
+    EmptyName,
+    /// Built-in programs cannot be replaced.
+    BuiltinName(String),
+    TooManyParams(usize),
+    NoSource,
+}
+
+impl core::fmt::Display for ShaderRegistryError {
+    fn fmt(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::EmptyName => formatter.write_str("shader program name is empty"),
+            Self::BuiltinName(name) => {
+                write!(formatter, "'{name}' is a built-in shader program")
+            }
+            Self::TooManyParams(count) => write!(
+                formatter,
+                "shader programs take at most {MAX_SHADER_PARAMS} params, got {count}"
+            ),
+            Self::NoSource => formatter.write_str("shader program has no WGSL or GLSL source"),
+        }
+    }
+}
+
+impl std::error::Error for ShaderRegistryError {}
+
+/// Fragment programs by id and case-insensitive name.
+///
+/// Ids are never reused: registering a changed program under a taken name
+/// allocates a new id, so backends can cache compiled pipelines per id.
+#[derive(Debug)]
+pub struct ShaderRegistry {
+    programs: Vec<Arc<ShaderProgram>>,
+    by_name: HashMap<String, ShaderProgramId>,
+}
+
+impl Default for ShaderRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShaderRegistry {
+    /// A registry holding only the built-in programs.
+    pub fn new() -> Self {
+        let param = |name: &str, default: f32| ShaderParam {
+            name: name.to_owned(),
+            default,
+        };
+        let builtins = [
+            vec![param("radius", 0.0)],
+            vec![param("offsetx", 0.0), param("offsety", 0.0)],
+            vec![
+                param("saturation", 1.0),
+                param("contrast", 1.0),
+                param("brightness", 0.0),
+            ],
+            vec![
+                param("amplitude", 0.0),
+                param("wavelength", 0.0),
+                param("phase", 0.0),
+            ],
+        ];
+        let mut registry = Self {
+            programs: Vec::new(),
+            by_name: HashMap::new(),
+        };
+        for (name, params) in ShaderEffect::NAMES.into_iter().zip(builtins) {
+            registry.insert(ShaderProgram {
+                name: name.to_owned(),
+                params,
+                sources: ShaderSources::default(),
+            });
+        }
+        registry
+    }
+
+    fn insert(&mut self, program: ShaderProgram) -> ShaderProgramId {
+        let id = self.programs.len() as ShaderProgramId;
+        self.by_name.insert(program.name.to_ascii_lowercase(), id);
+        self.programs.push(Arc::new(program));
+        id
+    }
+
+    /// Adds a user program, or returns the existing id when an identical
+    /// program is already registered under its name.
+    pub fn register(
+        &mut self,
+        program: ShaderProgram,
+    ) -> Result<ShaderProgramId, ShaderRegistryError> {
+        if program.name.is_empty() {
+            return Err(ShaderRegistryError::EmptyName);
+        }
+        if program.params.len() > MAX_SHADER_PARAMS {
+            return Err(ShaderRegistryError::TooManyParams(program.params.len()));
+        }
+        if program.sources.wgsl.is_none() && program.sources.glsl.is_none() {
+            return Err(ShaderRegistryError::NoSource);
+        }
+        if let Some(id) = self.id(&program.name) {
+            if (id as usize) < ShaderEffect::NAMES.len() {
+                return Err(ShaderRegistryError::BuiltinName(program.name));
+            }
+            if *self.programs[id as usize] == program {
+                return Ok(id);
+            }
+        }
+        Ok(self.insert(program))
+    }
+
+    pub fn id(&self, name: &str) -> Option<ShaderProgramId> {
+        self.by_name.get(&name.to_ascii_lowercase()).copied()
+    }
+
+    pub fn program(&self, id: ShaderProgramId) -> Option<&Arc<ShaderProgram>> {
+        self.programs.get(id as usize)
+    }
+
+    /// Builds the effect for the program registered as `name`.
+    pub fn effect(&self, name: &str, params: &[f32]) -> Option<ShaderEffect> {
+        let id = self.id(name)?;
+        let params = self.programs[id as usize].resolve_params(params);
+        Some(ShaderEffect::from_program(id, params))
+    }
+}
+
+fn shader_registry() -> &'static RwLock<ShaderRegistry> {
+    static REGISTRY: OnceLock<RwLock<ShaderRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(ShaderRegistry::new()))
+}
+
+/// Registers a user program in the process-wide registry that
+/// [`ShaderEffect::from_name`] and the backends read.
+pub fn register_shader_program(
+    program: ShaderProgram,
+) -> Result<ShaderProgramId, ShaderRegistryError> {
+    shader_registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .register(program)
+}
+
+/// The program registered under `id` in the process-wide registry.
+pub fn shader_program(id: ShaderProgramId) -> Option<Arc<ShaderProgram>> {
+    shader_registry()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .program(id)
+        .cloned()
+}
+
+/// Uniform block shared by the GPU effect programs (std140-compatible).
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct EffectUniforms {
+    pub tint: [f32; 4],
+    pub params: [f32; 4],
+    pub texel_size: [f32; 2],
+    pub program: u32,
+    pub _pad: u32,
+}
+
+impl EffectUniforms {
+    #[inline(always)]
+    pub fn new(run: EffectRun, source_size: [u32; 2]) -> Self {
+        Self {
+            tint: run.tint,
+            params: run.effect.params(),
+            texel_size: [
+                1.0 / source_size[0].max(1) as f32,
+                1.0 / source_size[1].max(1) as f32,
+            ],
+            program: run.effect.program(),
+            _pad: 0,
+        }
+    }
+}
+
+/// Draws a finished render target over the whole current pass through an
+/// effect.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EffectRun {
+    /// A [`crate::render_target_handle`]; other handles draw nothing.
+    pub texture_handle: crate::TextureHandle,
+    pub effect: ShaderEffect,
+    pub tint: [f32; 4],
+    pub blend: crate::BlendMode,
+}
+
+impl EffectRun {
+    /// Swaps a user program for [`ShaderEffect::PASSTHROUGH`], for backends
+    /// that cannot run it.
+    #[inline(always)]
+    pub const fn builtin_only(self) -> Self {
+        if self.effect.is_builtin() {
+            self
+        } else {
+            Self {
+                effect: ShaderEffect::PASSTHROUGH,
+                ..self
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sepia() -> ShaderProgram {
+        ShaderProgram {
+            name: "Sepia".to_owned(),
+            params: vec![ShaderParam {
+                name: "amount".to_owned(),
+                default: 1.0,
+            }],
+            sources: ShaderSources {
+                wgsl: Some("fn effect(uv: vec2<f32>) -> vec4<f32> { return tap(uv); }".into()),
+                glsl: None,
+            },
+        }
+    }
+
+    #[test]
+    fn builtins_round_trip_through_names() {
+        let registry = ShaderRegistry::new();
+        let effects = [
+            ShaderEffect::Blur { radius: 4.0 },
+            ShaderEffect::ChromaticAberration {
+                offset: [2.0, -1.0],
+            },
+            ShaderEffect::ColorGrade {
+                saturation: 0.5,
+                contrast: 1.25,
+                brightness: -0.1,
+            },
+            ShaderEffect::Wave {
+                amplitude: 3.0,
+                wavelength: 40.0,
+                phase: 1.5,
+            },
+        ];
+        for effect in effects {
+            let name = &registry.program(effect.program()).unwrap().name;
+            assert_eq!(registry.effect(name, &effect.params()), Some(effect));
+        }
+    }
+
+    #[test]
+    fn missing_params_fall_back_to_program_defaults() {
+        let mut registry = ShaderRegistry::new();
+        assert_eq!(
+            registry.effect("ColorGrade", &[0.0]),
+            Some(ShaderEffect::ColorGrade {
+                saturation: 0.0,
+                contrast: 1.0,
+                brightness: 0.0,
+            })
+        );
+        assert_eq!(registry.effect("sepia", &[]), None);
+
+        let id = registry.register(sepia()).unwrap();
+        assert_eq!(
+            registry.effect("SEPIA", &[]),
+            Some(ShaderEffect::Custom {
+                program: id,
+                params: [1.0, 0.0, 0.0, 0.0],
+            })
+        );
+    }
+
+    #[test]
+    fn user_programs_get_fresh_ids_and_cannot_replace_builtins() {
+        let mut registry = ShaderRegistry::new();
+        let first = registry.register(sepia()).unwrap();
+        assert_eq!(first as usize, ShaderEffect::NAMES.len());
+        assert_eq!(registry.register(sepia()), Ok(first));
+
+        let mut changed = sepia();
+        changed.params[0].default = 0.5;
+        let second = registry.register(changed).unwrap();
+        assert_ne!(second, first);
+        assert_eq!(registry.id("sepia"), Some(second));
+        assert!(registry.program(first).is_some());
+
+        let mut blur = sepia();
+        blur.name = "Blur".to_owned();
+        assert_eq!(
+            registry.register(blur),
+            Err(ShaderRegistryError::BuiltinName("Blur".to_owned()))
+        );
+        let mut empty = sepia();
+        empty.sources = ShaderSources::default();
+        assert_eq!(registry.register(empty), Err(ShaderRegistryError::NoSource));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    BlendMode, INVALID_TMESH_CACHE_KEY, MeshVertex, SpriteInstanceRaw, TMeshCacheKey,
+    BlendMode, EffectRun, INVALID_TMESH_CACHE_KEY, MeshVertex, SpriteInstanceRaw, TMeshCacheKey,
     TextureHandle, TexturedMeshInstanceRaw, TexturedMeshVertex, TexturedMeshVertices,
 };
 use glam::Mat4;
@@ -9,6 +9,13 @@ use glam::Mat4;
 /// Geometry, instances, and commands are already in final painter order. GPU
 /// backends only resolve retained textured geometry to cached or frame-local
 /// storage before executing `ops`; they do not reconstruct draw commands.
+///
+/// Offscreen passes lead `ops`: each one is a `BeginTarget`..`EndTarget`
+/// span, passes never nest, and every op after the last `EndTarget` draws to
+/// the screen. Later ops sample a finished pass through
+/// [`render_target_handle`]. A run that samples the target it is drawing
+/// into is skipped, and a target a backend cannot provide resolves like a
+/// missing texture.
 #[derive(Clone)]
 pub struct RenderFrame {
     pub clear_color: [f32; 4],
@@ -54,11 +61,179 @@ pub struct TexturedMeshRun {
     pub depth_test: bool,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum DrawOp {
     Sprite(SpriteRun),
     Mesh(MeshRun),
     TexturedMesh(TexturedMeshRun),
+    Effect(EffectRun),
+    BeginTarget(RenderTargetPass),
+    EndTarget,
+}
+
+/// One offscreen pass into a backend-owned render target.
+///
+/// Targets are keyed by `slot` and live until the backend releases them, so
+/// a pass with `clear == false` draws over the previous frame's contents.
+/// Cleared targets start fully transparent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RenderTargetPass {
+    pub slot: u32,
+    pub width: u32,
+    pub height: u32,
+    pub clear: bool,
+}
+
+/// First texture handle reserved for render targets.
+///
+/// Uploaded textures are allocated densely from 1, so handles at or above
+/// this value never collide with them.
+pub const RENDER_TARGET_HANDLE_BASE: TextureHandle = 1 << 48;
+
+#[inline(always)]
+pub const fn render_target_handle(slot: u32) -> TextureHandle {
+    RENDER_TARGET_HANDLE_BASE + slot as TextureHandle
+}
+
+#[inline(always)]
+pub const fn render_target_slot(handle: TextureHandle) -> Option<u32> {
+    if handle >= RENDER_TARGET_HANDLE_BASE
+        && handle - RENDER_TARGET_HANDLE_BASE <= u32::MAX as TextureHandle
+    {
+        Some((handle - RENDER_TARGET_HANDLE_BASE) as u32)
+    } else {
+        None
+    }
+}
+
+/// Iterates the offscreen passes at the head of a frame's ops.
+pub struct RenderTargetPasses<'a> {
+    ops: &'a [DrawOp],
+}
+
+impl<'a> RenderTargetPasses<'a> {
+    /// Ops left after the passes consumed so far; the screen pass once the
+    /// iterator is exhausted.
+    #[inline(always)]
+    pub const fn remainder(&self) -> &'a [DrawOp] {
+        self.ops
+    }
+}
+
+impl<'a> Iterator for RenderTargetPasses<'a> {
+    type Item = (RenderTargetPass, &'a [DrawOp]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (DrawOp::BeginTarget(pass), rest) = self.ops.split_first()? else {
+            return None;
+        };
+        let pass = *pass;
+        let end = rest
+            .iter()
+            .position(|op| matches!(op, DrawOp::EndTarget | DrawOp::BeginTarget(_)))
+            .unwrap_or(rest.len());
+        let body = &rest[..end];
+        self.ops = match rest.get(end) {
+            Some(DrawOp::EndTarget) => &rest[end + 1..],
+            _ => &rest[end..],
+        };
+        Some((pass, body))
+    }
+}
+
+impl RenderFrame {
+    #[inline(always)]
+    pub fn render_target_passes(&self) -> RenderTargetPasses<'_> {
+        RenderTargetPasses { ops: &self.ops }
+    }
+
+    /// Ops drawn to the screen, after every leading offscreen pass.
+    pub fn screen_ops(&self) -> &[DrawOp] {
+        let mut passes = self.render_target_passes();
+        for _ in passes.by_ref() {}
+        passes.remainder()
+    }
+
+    /// Draws `source` into the offscreen `pass` ahead of this frame's ops, so
+    /// they can sample it through `render_target_handle(pass.slot)`.
+    ///
+    /// `source`'s own passes lead, so their slots must not collide with
+    /// `pass` or with this frame's passes. Its screen ops draw over a quad of
+    /// its clear color. `source`'s buffers are taken over rather than copied:
+    /// this frame's data is appended to them, and `source` is left holding
+    /// this frame's old buffers, empty, for reuse. Returns `false`, leaving
+    /// both frames untouched, when the combined cameras no longer fit an
+    /// op's `u8` index.
+    pub fn prepend_target(&mut self, source: &mut Self, pass: RenderTargetPass) -> bool {
+        let Ok(clear_camera) = u8::try_from(source.cameras.len() + self.cameras.len()) else {
+            return false;
+        };
+        let camera_base = source.cameras.len() as u8;
+        let sprite_base = saturating_u32(source.sprite_instances.len());
+        let mesh_base = saturating_u32(source.mesh_vertices.len());
+        let tmesh_base = saturating_u32(source.tmesh_instances.len());
+        let geometry_base = saturating_u32(source.tmesh_geometries.len());
+
+        source.cameras.append(&mut self.cameras);
+        source.cameras.push(Mat4::IDENTITY);
+        source.sprite_instances.append(&mut self.sprite_instances);
+        source.mesh_vertices.append(&mut self.mesh_vertices);
+        let clear_start = saturating_u32(source.mesh_vertices.len());
+        source.mesh_vertices.extend(
+            [
+                [-1.0, -1.0],
+                [1.0, -1.0],
+                [1.0, 1.0],
+                [-1.0, -1.0],
+                [1.0, 1.0],
+                [-1.0, 1.0],
+            ]
+            .map(|pos| MeshVertex {
+                pos,
+                color: source.clear_color,
+            }),
+        );
+        source.tmesh_instances.append(&mut self.tmesh_instances);
+        source.tmesh_geometries.append(&mut self.tmesh_geometries);
+
+        let rebase = |op: DrawOp| match op {
+            DrawOp::Sprite(run) => DrawOp::Sprite(SpriteRun {
+                instance_start: run.instance_start + sprite_base,
+                camera: run.camera + camera_base,
+                ..run
+            }),
+            DrawOp::Mesh(run) => DrawOp::Mesh(MeshRun {
+                vertex_start: run.vertex_start + mesh_base,
+                camera: run.camera + camera_base,
+                ..run
+            }),
+            DrawOp::TexturedMesh(run) => DrawOp::TexturedMesh(TexturedMeshRun {
+                geometry: run.geometry + geometry_base,
+                instance_start: run.instance_start + tmesh_base,
+                camera: run.camera + camera_base,
+                ..run
+            }),
+            DrawOp::Effect(_) | DrawOp::BeginTarget(_) | DrawOp::EndTarget => op,
+        };
+        let screen_start = source.ops.len() - source.screen_ops().len();
+        source.ops.splice(
+            screen_start..screen_start,
+            [
+                DrawOp::BeginTarget(pass),
+                DrawOp::Mesh(MeshRun {
+                    vertex_start: clear_start,
+                    vertex_count: 6,
+                    blend: BlendMode::Alpha,
+                    camera: clear_camera,
+                }),
+            ],
+        );
+        source.ops.push(DrawOp::EndTarget);
+        source.ops.extend(self.ops.drain(..).map(rebase));
+        source.clear_color = self.clear_color;
+        std::mem::swap(self, source);
+        true
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -370,4 +545,171 @@ mod tests {
         assert_eq!(uploads.vertices.capacity(), vertex_capacity);
         assert_eq!(uploads.sources.capacity(), source_capacity);
     }
+
+    fn sprite_op(texture_handle: TextureHandle) -> DrawOp {
+        DrawOp::Sprite(SpriteRun {
+            instance_start: 0,
+            instance_count: 1,
+            blend: BlendMode::Alpha,
+            texture_handle,
+            camera: 0,
+        })
+    }
+
+    #[test]
+    fn render_target_handles_round_trip_outside_texture_range() {
+        assert_eq!(render_target_slot(render_target_handle(0)), Some(0));
+        assert_eq!(
+            render_target_slot(render_target_handle(u32::MAX)),
+            Some(u32::MAX)
+        );
+        assert_eq!(render_target_slot(1), None);
+        assert_eq!(render_target_slot(RENDER_TARGET_HANDLE_BASE - 1), None);
+        assert_eq!(render_target_slot(u64::MAX), None);
+    }
+
+    #[test]
+    fn render_target_passes_split_leading_spans_from_screen_ops() {
+        let a = RenderTargetPass {
+            slot: 0,
+            width: 64,
+            height: 32,
+            clear: true,
+        };
+        let b = RenderTargetPass { slot: 1, ..a };
+        let mut frame = frame(Vec::new());
+        frame.ops = vec![
+            DrawOp::BeginTarget(a),
+            sprite_op(1),
+            sprite_op(2),
+            DrawOp::EndTarget,
+            DrawOp::BeginTarget(b),
+            DrawOp::EndTarget,
+            sprite_op(render_target_handle(0)),
+        ];
+
+        let passes = frame.render_target_passes().collect::<Vec<_>>();
+        assert_eq!(passes.len(), 2);
+        assert_eq!(passes[0], (a, &frame.ops[1..3]));
+        assert_eq!(passes[1], (b, &[][..]));
+        assert_eq!(frame.screen_ops(), &[sprite_op(render_target_handle(0))]);
+    }
+
+    #[test]
+    fn unterminated_render_target_pass_ends_at_next_begin() {
+        let pass = RenderTargetPass {
+            slot: 3,
+            width: 8,
+            height: 8,
+            clear: false,
+        };
+        let mut frame = frame(Vec::new());
+        frame.ops = vec![
+            DrawOp::BeginTarget(pass),
+            sprite_op(1),
+            DrawOp::BeginTarget(pass),
+            sprite_op(2),
+        ];
+
+        let passes = frame.render_target_passes().collect::<Vec<_>>();
+        assert_eq!(
+            passes,
+            vec![(pass, &frame.ops[1..2]), (pass, &frame.ops[3..])]
+        );
+        assert!(frame.screen_ops().is_empty());
+        frame.ops.clear();
+        assert_eq!(frame.render_target_passes().count(), 0);
+    }
+
+    #[test]
+    fn prepended_target_takes_source_buffers_and_rebases_own_ops() {
+        let inner = RenderTargetPass {
+            slot: 0,
+            width: 8,
+            height: 8,
+            clear: true,
+        };
+        let outer = RenderTargetPass {
+            slot: 9,
+            width: 320,
+            height: 180,
+            ..inner
+        };
+        let mut source = frame(Vec::new());
+        source.clear_color = [0.1, 0.2, 0.3, 1.0];
+        source.cameras = vec![Mat4::IDENTITY; 2];
+        source.sprite_instances = vec![bytemuck::Zeroable::zeroed(); 2];
+        let mut screen_sprite = sprite_op(render_target_handle(0));
+        if let DrawOp::Sprite(run) = &mut screen_sprite {
+            run.instance_start = 1;
+            run.camera = 1;
+        }
+        source.ops = vec![
+            DrawOp::BeginTarget(inner),
+            sprite_op(5),
+            DrawOp::EndTarget,
+            screen_sprite,
+        ];
+        // Spare room, so taking the buffer over keeps its allocation.
+        source.sprite_instances.reserve(8);
+        let source_sprites = source.sprite_instances.as_ptr();
+        let mut target = frame(Vec::new());
+        target.clear_color = [0.0, 0.0, 0.0, 1.0];
+        target.cameras = vec![Mat4::IDENTITY];
+        target.sprite_instances = vec![bytemuck::Zeroable::zeroed(); 3];
+        target.ops = vec![sprite_op(render_target_handle(9))];
+
+        assert!(target.prepend_target(&mut source, outer));
+
+        assert_eq!(target.clear_color, [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(target.sprite_instances.as_ptr(), source_sprites);
+        assert_eq!(target.cameras.len(), 4);
+        assert_eq!(target.sprite_instances.len(), 5);
+        assert_eq!(target.mesh_vertices.len(), 6);
+        assert_eq!(target.mesh_vertices[0].color, [0.1, 0.2, 0.3, 1.0]);
+        assert!(source.ops.is_empty() && source.sprite_instances.is_empty());
+        let mut expected_own = sprite_op(render_target_handle(9));
+        if let DrawOp::Sprite(run) = &mut expected_own {
+            run.instance_start += 2;
+            run.camera += 2;
+        }
+        assert_eq!(
+            target.ops,
+            vec![
+                DrawOp::BeginTarget(inner),
+                sprite_op(5),
+                DrawOp::EndTarget,
+                DrawOp::BeginTarget(outer),
+                DrawOp::Mesh(MeshRun {
+                    vertex_start: 0,
+                    vertex_count: 6,
+                    blend: BlendMode::Alpha,
+                    camera: 3,
+                }),
+                screen_sprite,
+                DrawOp::EndTarget,
+                expected_own,
+            ]
+        );
+        assert_eq!(target.screen_ops(), &[expected_own]);
+    }
+
+    #[test]
+    fn prepended_target_refuses_camera_overflow() {
+        let pass = RenderTargetPass {
+            slot: 0,
+            width: 8,
+            height: 8,
+            clear: true,
+        };
+        let mut source = frame(Vec::new());
+        source.cameras = vec![Mat4::IDENTITY; 200];
+        let mut target = frame(Vec::new());
+        target.cameras = vec![Mat4::IDENTITY; 56];
+
+        assert!(!target.prepend_target(&mut source, pass));
+        assert_eq!(target.cameras.len(), 56);
+        assert_eq!(source.cameras.len(), 200);
+        assert!(target.ops.is_empty());
+    }
 }
@@ -1,7 +1,11 @@
+mod capture;
+mod effect;
 mod frame;
 #[cfg(any(test, feature = "test-util"))]
 pub mod frame_compare;
 
+pub use capture::*;
+pub use effect::*;
 pub use frame::*;
 
 use glam::Mat4 as Matrix4;
@@ -351,6 +355,21 @@ pub enum BackendType {
     DirectX,
 }
 
+impl BackendType {
+    /// Whether the backend executes `DrawOp::BeginTarget` passes. Every
+    /// backend does; legacy OpenGL contexts, which lack framebuffer objects,
+    /// draw each effect's pass straight to the screen instead.
+    pub const fn supports_render_targets(self) -> bool {
+        true
+    }
+
+    /// Whether the backend runs `DrawOp::Effect` programs. Effects sample
+    /// render targets, so this never exceeds `supports_render_targets`.
+    pub const fn supports_shader_effects(self) -> bool {
+        self.supports_render_targets()
+    }
+}
+
 impl core::fmt::Display for BackendType {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
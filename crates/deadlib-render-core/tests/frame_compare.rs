@@ -1,8 +1,9 @@
 use deadlib_render_core::{
-    BlendMode, DrawOp, MeshRun, MeshVertex, RenderFrame, SpriteInstanceRaw, SpriteRun,
-    TexturedMeshGeometry, TexturedMeshInstanceRaw, TexturedMeshRun, TexturedMeshVertex,
+    BlendMode, DrawOp, MeshRun, MeshVertex, RenderFrame, RenderTargetPass, SpriteInstanceRaw,
+    SpriteRun, TexturedMeshGeometry, TexturedMeshInstanceRaw, TexturedMeshRun, TexturedMeshVertex,
     TexturedMeshVertices,
     frame_compare::{FrameMismatch, compare_render_frames, compare_render_frames_semantic},
+    render_target_handle,
 };
 use glam::Mat4;
 use std::sync::Arc;
@@ -161,3 +162,50 @@ fn semantic_comparison_accepts_equivalent_sprite_run_coalescing() {
     assert_ne!(compare_render_frames(&expected, &actual), Ok(()));
     assert_eq!(compare_render_frames_semantic(&expected, &actual), Ok(()));
 }
+
+// Only the frame structure is checked here. Target pixels are diffed between
+// wgpu and software in the wgpu backend's `target_parity` test.
+#[test]
+fn semantic_comparison_keeps_render_target_passes_apart() {
+    let pass = RenderTargetPass {
+        slot: 0,
+        width: 640,
+        height: 480,
+        clear: true,
+    };
+    let mut expected = render_fixture();
+    expected.sprite_instances.push(sprite_instance());
+    expected.ops.splice(
+        0..0,
+        [
+            DrawOp::BeginTarget(pass),
+            DrawOp::Sprite(SpriteRun {
+                instance_start: 1,
+                instance_count: 1,
+                blend: BlendMode::Alpha,
+                texture_handle: 7,
+                camera: 0,
+            }),
+            DrawOp::EndTarget,
+        ],
+    );
+    let DrawOp::Sprite(run) = &mut expected.ops[3] else {
+        panic!("screen pass starts with a sprite operation");
+    };
+    run.texture_handle = render_target_handle(pass.slot);
+    assert_eq!(
+        compare_render_frames_semantic(&expected, &expected.clone()),
+        Ok(())
+    );
+
+    let mut actual = expected.clone();
+    actual.ops[0] = DrawOp::BeginTarget(RenderTargetPass {
+        clear: false,
+        ..pass
+    });
+    assert_ne!(compare_render_frames_semantic(&expected, &actual), Ok(()));
+
+    let mut actual = expected.clone();
+    actual.ops.remove(2);
+    assert_ne!(compare_render_frames_semantic(&expected, &actual), Ok(()));
+}
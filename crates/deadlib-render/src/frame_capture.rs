@@ -0,0 +1,262 @@
+use crate::Texture;
+use deadlib_render_core::{
+    CaptureWriter, CapturedTexture, DrawOp, FastU64Map, INVALID_TEXTURE_HANDLE, ProjectionMatrix,
+    RenderFrame, SamplerDesc, TextureHandle, TextureHandleMap, render_target_slot,
+};
+use image::RgbaImage;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A capture that stopped, either at its deadline or on a write error.
+pub struct FrameCaptureSummary {
+    pub path: PathBuf,
+    pub frames: u32,
+}
+
+/// A mirrored texture for another backend to upload under the same handle.
+pub struct SharedTexture {
+    pub handle: TextureHandle,
+    pub sampler: SamplerDesc,
+    pub image: Arc<RgbaImage>,
+}
+
+/// Which mirrored textures a second backend has already received.
+#[derive(Default)]
+pub struct TextureShareCursor {
+    /// `(texture id, generation)` last shared for each handle.
+    sent: FastU64Map<(u64, u32)>,
+}
+
+struct MirroredTexture {
+    /// `None` once an update went uncopied; the next copied update restores
+    /// it.
+    image: Option<Arc<RgbaImage>>,
+    sampler: SamplerDesc,
+    generation: u32,
+    /// A recording or second backend sampled this texture, so its updates
+    /// are copied too.
+    referenced: bool,
+}
+
+struct Recording {
+    writer: CaptureWriter<BufWriter<File>>,
+    path: PathBuf,
+    deadline: Instant,
+    /// `(texture id, generation)` last written for each handle.
+    written: FastU64Map<(u64, u32)>,
+}
+
+/// CPU copies of every texture the backend owns, kept so a capture started
+/// mid-session can write textures uploaded long before it, and so a second
+/// backend can draw the same handles.
+///
+/// Every texture is copied once when created. Updates, which dynamic media
+/// makes every frame, are only copied for textures a consumer has sampled
+/// or while a recording runs; the rest drop their now stale copy.
+pub(crate) struct FrameCapture {
+    mirror: FastU64Map<MirroredTexture>,
+    size: (u32, u32),
+    projection: ProjectionMatrix,
+    recording: Option<Recording>,
+    finished: Option<Result<FrameCaptureSummary, String>>,
+}
+
+impl FrameCapture {
+    pub(crate) fn new(width: u32, height: u32) -> Self {
+        Self {
+            mirror: FastU64Map::default(),
+            size: (width, height),
+            projection: ProjectionMatrix::IDENTITY,
+            recording: None,
+            finished: None,
+        }
+    }
+
+    pub(crate) fn texture_created(&mut self, id: u64, image: &RgbaImage, sampler: SamplerDesc) {
+        self.mirror.insert(
+            id,
+            MirroredTexture {
+                image: Some(Arc::new(image.clone())),
+                sampler,
+                generation: 0,
+                referenced: false,
+            },
+        );
+    }
+
+    pub(crate) fn texture_updated(&mut self, id: u64, image: &RgbaImage) {
+        if let Some(mirrored) = self.mirror.get_mut(&id) {
+            mirrored.image =
+                (mirrored.referenced || self.recording.is_some()).then(|| Arc::new(image.clone()));
+            mirrored.generation = mirrored.generation.wrapping_add(1);
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn texture_dropped(&mut self, id: u64) {
+        self.mirror.remove(&id);
+    }
+
+    #[inline(always)]
+    pub(crate) const fn set_size(&mut self, width: u32, height: u32) {
+        self.size = (width, height);
+    }
+
+    #[inline(always)]
+    pub(crate) const fn set_projection(&mut self, projection: ProjectionMatrix) {
+        self.projection = projection;
+    }
+
+    #[inline(always)]
+    pub(crate) const fn recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub(crate) fn start(&mut self, path: &Path, duration: Duration) -> io::Result<()> {
+        if self.recording.is_some() {
+            return Err(io::Error::other("a frame capture is already recording"));
+        }
+        self.recording = Some(Recording {
+            writer: CaptureWriter::new(BufWriter::new(File::create(path)?))?,
+            path: path.to_path_buf(),
+            deadline: Instant::now() + duration,
+            written: FastU64Map::default(),
+        });
+        Ok(())
+    }
+
+    pub(crate) fn record(&mut self, frame: &RenderFrame, textures: &TextureHandleMap<Texture>) {
+        let Some(recording) = self.recording.as_mut() else {
+            return;
+        };
+        let outcome = match write_frame(
+            recording,
+            &mut self.mirror,
+            self.size,
+            self.projection,
+            frame,
+            textures,
+        ) {
+            Err(e) => Err(format!("{}: {e}", recording.path.display())),
+            Ok(()) if Instant::now() >= recording.deadline => Ok(()),
+            Ok(()) => return,
+        };
+        let Some(recording) = self.recording.take() else {
+            return;
+        };
+        let frames = recording.writer.frames();
+        let path = recording.path;
+        self.finished = Some(outcome.and_then(|()| {
+            recording
+                .writer
+                .finish()
+                .map_err(|e| format!("{}: {e}", path.display()))?;
+            Ok(FrameCaptureSummary { path, frames })
+        }));
+    }
+
+    pub(crate) fn share(
+        &mut self,
+        frame: &RenderFrame,
+        textures: &TextureHandleMap<Texture>,
+        cursor: &mut TextureShareCursor,
+        out: &mut Vec<SharedTexture>,
+    ) {
+        let _ = for_each_changed_texture(
+            &mut self.mirror,
+            frame,
+            textures,
+            &mut cursor.sent,
+            |handle, sampler, image| {
+                out.push(SharedTexture {
+                    handle,
+                    sampler,
+                    image: Arc::clone(image),
+                });
+                Ok(())
+            },
+        );
+    }
+
+    #[inline(always)]
+    pub(crate) fn take_finished(&mut self) -> Option<Result<FrameCaptureSummary, String>> {
+        self.finished.take()
+    }
+}
+
+#[inline(always)]
+const fn sampled_texture(op: &DrawOp) -> Option<TextureHandle> {
+    match op {
+        DrawOp::Sprite(run) => Some(run.texture_handle),
+        DrawOp::TexturedMesh(run) => Some(run.texture_handle),
+        DrawOp::Effect(run) => Some(run.texture_handle),
+        DrawOp::Mesh(_) | DrawOp::BeginTarget(_) | DrawOp::EndTarget => None,
+    }
+}
+
+/// Visits each texture `frame` samples whose contents changed since `seen`
+/// last recorded its handle, and marks every sampled texture referenced.
+/// Textures created before mirroring was enabled, or whose last update went
+/// uncopied, have no copy and are skipped.
+fn for_each_changed_texture(
+    mirror: &mut FastU64Map<MirroredTexture>,
+    frame: &RenderFrame,
+    textures: &TextureHandleMap<Texture>,
+    seen: &mut FastU64Map<(u64, u32)>,
+    mut visit: impl FnMut(TextureHandle, SamplerDesc, &Arc<RgbaImage>) -> io::Result<()>,
+) -> io::Result<()> {
+    for handle in frame.ops.iter().filter_map(sampled_texture) {
+        if handle == INVALID_TEXTURE_HANDLE || render_target_slot(handle).is_some() {
+            continue;
+        }
+        let Some(texture) = textures.get(&handle) else {
+            continue;
+        };
+        let Some(mirrored) = mirror.get_mut(&texture.capture_id) else {
+            continue;
+        };
+        mirrored.referenced = true;
+        let Some(image) = &mirrored.image else {
+            continue;
+        };
+        let stamp = (texture.capture_id, mirrored.generation);
+        if seen.get(&handle) == Some(&stamp) {
+            continue;
+        }
+        visit(handle, mirrored.sampler, image)?;
+        seen.insert(handle, stamp);
+    }
+    Ok(())
+}
+
+/// Textures are written just before the first frame that samples them, and
+/// again whenever their contents changed.
+fn write_frame(
+    recording: &mut Recording,
+    mirror: &mut FastU64Map<MirroredTexture>,
+    (width, height): (u32, u32),
+    projection: ProjectionMatrix,
+    frame: &RenderFrame,
+    textures: &TextureHandleMap<Texture>,
+) -> io::Result<()> {
+    let writer = &mut recording.writer;
+    for_each_changed_texture(
+        mirror,
+        frame,
+        textures,
+        &mut recording.written,
+        |handle, sampler, image| {
+            writer.write_texture(CapturedTexture {
+                handle,
+                sampler,
+                width: image.width(),
+                height: image.height(),
+                rgba: image.as_raw().clone(),
+            })
+        },
+    )?;
+    writer.write_frame(width, height, projection, frame)
+}
@@ -7,10 +7,12 @@ use deadlib_render_backend_vulkan as vulkan;
 use deadlib_render_backend_wgpu as wgpu_core;
 pub use deadlib_render_core::*;
 use image::RgbaImage;
-use std::{error::Error, sync::Arc};
+use std::{error::Error, path::Path, sync::Arc, time::Duration};
 use winit::window::Window;
 
+mod frame_capture;
 mod window_size;
+pub use frame_capture::{FrameCaptureSummary, SharedTexture, TextureShareCursor};
 pub use window_size::{
     render_size_for_physical, render_size_for_window, request_window_size,
     with_requested_window_size,
@@ -19,7 +21,13 @@ pub use window_size::{
 // --- Public API Facade ---
 
 // A handle to a backend-specific texture resource.
-pub enum Texture {
+pub struct Texture {
+    inner: TextureImpl,
+    // Unique within the backend that created it; keys frame-capture copies.
+    capture_id: u64,
+}
+
+enum TextureImpl {
     #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
     Vulkan(vulkan::Texture),
     #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
@@ -64,8 +72,8 @@ struct WgpuTextureLookup<'a> {
 #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
 impl vulkan::TextureLookup for VulkanTextureLookup<'_> {
     fn vulkan_texture(&self, handle: TextureHandle) -> Option<&vulkan::Texture> {
-        match self.0.get(&handle)? {
-            Texture::Vulkan(texture) => Some(texture),
+        match &self.0.get(&handle)?.inner {
+            TextureImpl::Vulkan(texture) => Some(texture),
             _ => None,
         }
     }
@@ -73,8 +81,8 @@ impl vulkan::TextureLookup for VulkanTextureLookup<'_> {
 
 impl opengl::TextureLookup for OpenGlTextureLookup<'_> {
     fn opengl_texture(&self, handle: TextureHandle) -> Option<&opengl::Texture> {
-        match self.0.get(&handle)? {
-            Texture::OpenGL(texture) => Some(texture),
+        match &self.0.get(&handle)?.inner {
+            TextureImpl::OpenGL(texture) => Some(texture),
             _ => None,
         }
     }
@@ -83,8 +91,8 @@ impl opengl::TextureLookup for OpenGlTextureLookup<'_> {
 #[cfg(target_os = "macos")]
 impl metal::TextureLookup for MetalTextureLookup<'_> {
     fn metal_texture(&self, handle: TextureHandle) -> Option<&metal::Texture> {
-        match self.0.get(&handle)? {
-            Texture::Metal(texture) => Some(texture),
+        match &self.0.get(&handle)?.inner {
+            TextureImpl::Metal(texture) => Some(texture),
             _ => None,
         }
     }
@@ -92,14 +100,14 @@ impl metal::TextureLookup for MetalTextureLookup<'_> {
 
 impl wgpu_core::TextureLookup for WgpuTextureLookup<'_> {
     fn wgpu_texture(&self, handle: TextureHandle) -> Option<&wgpu_core::Texture> {
-        match (self.kind, self.textures.get(&handle)?) {
+        match (self.kind, &self.textures.get(&handle)?.inner) {
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
-            (WgpuTextureKind::Vulkan, Texture::VulkanWgpu(texture)) => Some(texture),
+            (WgpuTextureKind::Vulkan, TextureImpl::VulkanWgpu(texture)) => Some(texture),
             #[cfg(target_os = "macos")]
-            (WgpuTextureKind::MetalWgpu, Texture::MetalWgpu(texture)) => Some(texture),
-            (WgpuTextureKind::OpenGL, Texture::OpenGLWgpu(texture)) => Some(texture),
+            (WgpuTextureKind::MetalWgpu, TextureImpl::MetalWgpu(texture)) => Some(texture),
+            (WgpuTextureKind::OpenGL, TextureImpl::OpenGLWgpu(texture)) => Some(texture),
             #[cfg(target_os = "windows")]
-            (WgpuTextureKind::DirectX, Texture::DirectX(texture)) => Some(texture),
+            (WgpuTextureKind::DirectX, TextureImpl::DirectX(texture)) => Some(texture),
             _ => None,
         }
     }
@@ -107,8 +115,8 @@ impl wgpu_core::TextureLookup for WgpuTextureLookup<'_> {
 
 impl software::TextureLookup for SoftwareTextureLookup<'_> {
     fn software_texture(&self, handle: TextureHandle) -> Option<&software::Texture> {
-        match self.0.get(&handle)? {
-            Texture::Software(texture) => Some(texture),
+        match &self.0.get(&handle)?.inner {
+            TextureImpl::Software(texture) => Some(texture),
             _ => None,
         }
     }
@@ -133,7 +141,11 @@ enum BackendImpl {
 
 /// A public, opaque wrapper around the active rendering backend.
 /// This hides platform-specific variants from the rest of the application.
-pub struct Backend(BackendImpl);
+pub struct Backend {
+    imp: BackendImpl,
+    next_texture_id: u64,
+    capture: Option<Box<frame_capture::FrameCapture>>,
+}
 
 impl Backend {
     pub fn draw(
@@ -142,7 +154,10 @@ impl Backend {
         textures: &TextureHandleMap<Texture>,
         apply_present_back_pressure: bool,
     ) -> Result<DrawStats, Box<dyn Error>> {
-        match &mut self.0 {
+        if let Some(capture) = &mut self.capture {
+            capture.record(frame, textures);
+        }
+        match &mut self.imp {
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
             BackendImpl::Vulkan(state) => vulkan::draw(
                 state,
@@ -212,7 +227,7 @@ impl Backend {
     }
 
     pub fn request_screenshot(&mut self) {
-        match &mut self.0 {
+        match &mut self.imp {
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
             BackendImpl::Vulkan(state) => vulkan::request_screenshot(state),
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
@@ -230,7 +245,7 @@ impl Backend {
     }
 
     pub fn capture_frame(&mut self) -> Result<RgbaImage, Box<dyn Error>> {
-        match &mut self.0 {
+        match &mut self.imp {
             BackendImpl::OpenGL(state) => opengl::capture_frame(state),
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
             BackendImpl::Vulkan(state) => vulkan::capture_frame(state),
@@ -241,23 +256,73 @@ impl Backend {
             #[cfg(target_os = "macos")]
             BackendImpl::MetalWgpu(state) => wgpu_core::capture_frame(state),
             BackendImpl::OpenGLWgpu(state) => wgpu_core::capture_frame(state),
-            BackendImpl::Software(_) => Err(std::io::Error::other(
-                "Screenshot capture is not implemented for Software renderer yet",
-            )
-            .into()),
+            BackendImpl::Software(state) => software::capture_frame(state),
             #[cfg(target_os = "windows")]
             BackendImpl::DirectX(state) => wgpu_core::capture_frame(state),
         }
     }
 
+    /// Keeps a CPU copy of every texture created from now on so a frame
+    /// capture can write it or another backend can share it. Updates are
+    /// copied only once something samples the texture. Enable right after
+    /// creating the backend; earlier textures stay missing.
+    pub fn enable_texture_mirror(&mut self, width: u32, height: u32) {
+        if self.capture.is_none() {
+            self.capture = Some(Box::new(frame_capture::FrameCapture::new(width, height)));
+        }
+    }
+
+    /// Writes every drawn frame, and the textures it samples, to `path` for
+    /// `duration`. Needs `enable_texture_mirror`.
+    pub fn start_frame_capture(
+        &mut self,
+        path: &Path,
+        duration: Duration,
+    ) -> Result<(), Box<dyn Error>> {
+        let Some(capture) = &mut self.capture else {
+            return Err(std::io::Error::other("frame capture is not enabled").into());
+        };
+        capture.start(path, duration)?;
+        Ok(())
+    }
+
+    pub fn frame_capture_recording(&self) -> bool {
+        self.capture
+            .as_ref()
+            .is_some_and(|capture| capture.recording())
+    }
+
+    /// The capture that stopped since the last call, if any.
+    pub fn take_finished_frame_capture(&mut self) -> Option<Result<FrameCaptureSummary, String>> {
+        self.capture.as_mut()?.take_finished()
+    }
+
+    /// Appends the mirrored textures `frame` samples that `cursor` has not
+    /// seen in their current contents. Needs `enable_texture_mirror`; from
+    /// then on, updates to those textures are mirrored as well.
+    pub fn share_frame_textures(
+        &mut self,
+        frame: &RenderFrame,
+        textures: &TextureHandleMap<Texture>,
+        cursor: &mut TextureShareCursor,
+        out: &mut Vec<SharedTexture>,
+    ) {
+        if let Some(capture) = &mut self.capture {
+            capture.share(frame, textures, cursor, out);
+        }
+    }
+
     pub fn configure_software_threads(&mut self, threads: Option<usize>) {
-        if let BackendImpl::Software(state) = &mut self.0 {
+        if let BackendImpl::Software(state) = &mut self.imp {
             software::set_thread_hint(state, threads);
         }
     }
 
     pub fn resize(&mut self, width: u32, height: u32) {
-        match &mut self.0 {
+        if let Some(capture) = &mut self.capture {
+            capture.set_size(width, height);
+        }
+        match &mut self.imp {
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
             BackendImpl::Vulkan(state) => vulkan::resize(state, width, height),
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
@@ -275,7 +340,10 @@ impl Backend {
     }
 
     pub fn set_default_projection(&mut self, projection: ProjectionMatrix) {
-        match &mut self.0 {
+        if let Some(capture) = &mut self.capture {
+            capture.set_projection(projection);
+        }
+        match &mut self.imp {
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
             BackendImpl::Vulkan(state) => vulkan::set_default_projection(state, projection),
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
@@ -292,8 +360,28 @@ impl Backend {
         }
     }
 
+    /// Drops cached offscreen targets so their memory is not held past the
+    /// screen that drew them.
+    pub fn release_render_targets(&mut self) {
+        match &mut self.imp {
+            #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
+            BackendImpl::Vulkan(state) => vulkan::release_render_targets(state),
+            #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
+            BackendImpl::VulkanWgpu(state) => wgpu_core::release_render_targets(state),
+            #[cfg(target_os = "macos")]
+            BackendImpl::Metal(state) => metal::release_render_targets(state),
+            #[cfg(target_os = "macos")]
+            BackendImpl::MetalWgpu(state) => wgpu_core::release_render_targets(state),
+            BackendImpl::OpenGL(state) => opengl::release_render_targets(state),
+            BackendImpl::OpenGLWgpu(state) => wgpu_core::release_render_targets(state),
+            BackendImpl::Software(state) => software::release_render_targets(state),
+            #[cfg(target_os = "windows")]
+            BackendImpl::DirectX(state) => wgpu_core::release_render_targets(state),
+        }
+    }
+
     pub fn cleanup(&mut self) {
-        match &mut self.0 {
+        match &mut self.imp {
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
             BackendImpl::Vulkan(state) => vulkan::cleanup(state),
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
@@ -315,43 +403,57 @@ impl Backend {
         image: &RgbaImage,
         sampler: SamplerDesc,
     ) -> Result<Texture, Box<dyn Error>> {
-        match &mut self.0 {
+        let inner = self.create_texture_impl(image, sampler)?;
+        let capture_id = self.next_texture_id;
+        self.next_texture_id += 1;
+        if let Some(capture) = &mut self.capture {
+            capture.texture_created(capture_id, image, sampler);
+        }
+        Ok(Texture { inner, capture_id })
+    }
+
+    fn create_texture_impl(
+        &mut self,
+        image: &RgbaImage,
+        sampler: SamplerDesc,
+    ) -> Result<TextureImpl, Box<dyn Error>> {
+        match &mut self.imp {
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
             BackendImpl::Vulkan(state) => {
                 let tex = vulkan::create_texture(state, image, sampler)?;
-                Ok(Texture::Vulkan(tex))
+                Ok(TextureImpl::Vulkan(tex))
             }
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
             BackendImpl::VulkanWgpu(state) => {
                 let tex = wgpu_core::create_texture(state, image, sampler)?;
-                Ok(Texture::VulkanWgpu(tex))
+                Ok(TextureImpl::VulkanWgpu(tex))
             }
             #[cfg(target_os = "macos")]
             BackendImpl::Metal(state) => {
                 let tex = metal::create_texture(state, image, sampler)?;
-                Ok(Texture::Metal(tex))
+                Ok(TextureImpl::Metal(tex))
             }
             #[cfg(target_os = "macos")]
             BackendImpl::MetalWgpu(state) => {
                 let tex = wgpu_core::create_texture(state, image, sampler)?;
-                Ok(Texture::MetalWgpu(tex))
+                Ok(TextureImpl::MetalWgpu(tex))
             }
             BackendImpl::OpenGL(state) => {
                 let tex = opengl::create_texture(state, image, sampler)?;
-                Ok(Texture::OpenGL(tex))
+                Ok(TextureImpl::OpenGL(tex))
             }
             BackendImpl::OpenGLWgpu(state) => {
                 let tex = wgpu_core::create_texture(state, image, sampler)?;
-                Ok(Texture::OpenGLWgpu(tex))
+                Ok(TextureImpl::OpenGLWgpu(tex))
             }
             BackendImpl::Software(_state) => {
                 let tex = software::create_texture(image, sampler)?;
-                Ok(Texture::Software(tex))
+                Ok(TextureImpl::Software(tex))
             }
             #[cfg(target_os = "windows")]
             BackendImpl::DirectX(state) => {
                 let tex = wgpu_core::create_texture(state, image, sampler)?;
-                Ok(Texture::DirectX(tex))
+                Ok(TextureImpl::DirectX(tex))
             }
         }
     }
@@ -361,48 +463,58 @@ impl Backend {
         texture: &mut Texture,
         image: &RgbaImage,
     ) -> Result<(), Box<dyn Error>> {
-        match (&mut self.0, texture) {
+        let capture_id = texture.capture_id;
+        let result = match (&mut self.imp, &mut texture.inner) {
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
-            (BackendImpl::Vulkan(state), Texture::Vulkan(texture)) => {
+            (BackendImpl::Vulkan(state), TextureImpl::Vulkan(texture)) => {
                 vulkan::update_texture(state, texture, image)
             }
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
-            (BackendImpl::VulkanWgpu(state), Texture::VulkanWgpu(texture)) => {
+            (BackendImpl::VulkanWgpu(state), TextureImpl::VulkanWgpu(texture)) => {
                 wgpu_core::update_texture(state, texture, image)
             }
             #[cfg(target_os = "macos")]
-            (BackendImpl::Metal(state), Texture::Metal(texture)) => {
+            (BackendImpl::Metal(state), TextureImpl::Metal(texture)) => {
                 metal::update_texture(state, texture, image)
             }
             #[cfg(target_os = "macos")]
-            (BackendImpl::MetalWgpu(state), Texture::MetalWgpu(texture)) => {
+            (BackendImpl::MetalWgpu(state), TextureImpl::MetalWgpu(texture)) => {
                 wgpu_core::update_texture(state, texture, image)
             }
-            (BackendImpl::OpenGL(state), Texture::OpenGL(texture)) => {
+            (BackendImpl::OpenGL(state), TextureImpl::OpenGL(texture)) => {
                 opengl::update_texture(state, texture, image)?;
                 Ok(())
             }
-            (BackendImpl::OpenGLWgpu(state), Texture::OpenGLWgpu(texture)) => {
+            (BackendImpl::OpenGLWgpu(state), TextureImpl::OpenGLWgpu(texture)) => {
                 wgpu_core::update_texture(state, texture, image)
             }
-            (BackendImpl::Software(_state), Texture::Software(texture)) => {
+            (BackendImpl::Software(_state), TextureImpl::Software(texture)) => {
                 software::update_texture(texture, image)
             }
             #[cfg(target_os = "windows")]
-            (BackendImpl::DirectX(state), Texture::DirectX(texture)) => {
+            (BackendImpl::DirectX(state), TextureImpl::DirectX(texture)) => {
                 wgpu_core::update_texture(state, texture, image)
             }
             _ => Err(std::io::Error::other("texture/backend mismatch").into()),
+        };
+        if result.is_ok()
+            && let Some(capture) = &mut self.capture
+        {
+            capture.texture_updated(capture_id, image);
         }
+        result
     }
 
     pub fn retire_texture(&mut self, texture: Texture) {
-        match (&mut self.0, texture) {
+        if let Some(capture) = &mut self.capture {
+            capture.texture_dropped(texture.capture_id);
+        }
+        match (&mut self.imp, texture.inner) {
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
-            (BackendImpl::Vulkan(state), Texture::Vulkan(texture)) => {
+            (BackendImpl::Vulkan(state), TextureImpl::Vulkan(texture)) => {
                 vulkan::retire_texture(state, texture);
             }
-            (BackendImpl::OpenGL(state), Texture::OpenGL(texture)) => {
+            (BackendImpl::OpenGL(state), TextureImpl::OpenGL(texture)) => {
                 opengl::delete_texture(state, &texture);
             }
             (_, texture) => drop(texture),
@@ -413,7 +525,12 @@ impl Backend {
         self.wait_for_idle();
 
         let old_textures = std::mem::take(textures);
-        match &mut self.0 {
+        if let Some(capture) = &mut self.capture {
+            for texture in old_textures.values() {
+                capture.texture_dropped(texture.capture_id);
+            }
+        }
+        match &mut self.imp {
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
             BackendImpl::Vulkan(_) => {
                 // Vulkan textures are cleaned up by their Drop implementation.
@@ -433,7 +550,7 @@ impl Backend {
             }
             BackendImpl::OpenGL(state) => {
                 for tex in old_textures.values() {
-                    if let Texture::OpenGL(texture) = tex {
+                    if let TextureImpl::OpenGL(texture) = &tex.inner {
                         opengl::delete_texture(state, texture);
                     }
                 }
@@ -452,7 +569,7 @@ impl Backend {
     }
 
     pub fn wait_for_idle(&mut self) {
-        match &mut self.0 {
+        match &mut self.imp {
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
             BackendImpl::Vulkan(state) => vulkan::wait_for_idle(state),
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
@@ -535,7 +652,11 @@ pub fn create_backend(
             gfx_debug_enabled,
         )?)),
     };
-    Ok(Backend(backend_impl))
+    Ok(Backend {
+        imp: backend_impl,
+        next_texture_id: 1,
+        capture: None,
+    })
 }
 
 impl Backend {
@@ -544,7 +665,7 @@ impl Backend {
         vsync_enabled: bool,
         present_mode_policy: PresentModePolicy,
     ) {
-        match &mut self.0 {
+        match &mut self.imp {
             #[cfg(all(not(target_pointer_width = "32"), not(target_vendor = "win7")))]
             BackendImpl::Vulkan(state) => {
                 vulkan::set_present_config(state, vsync_enabled, present_mode_policy)
@@ -1,16 +1,18 @@
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use deadlib_present::actors::TextAttribute;
 use deadlib_render_core::{SamplerDesc, SamplerFilter, SamplerWrap, TexturedMeshVertex};
 use deadsync_noteskin::{NUM_QUANTIZATIONS, Style};
 use deadsync_song_lua::{
-    compile_song_lua_with_default_host, overlay_model_layers_from_slots,
-    song_lua_human_player_count, song_lua_style_info,
+    compile_live_song_lua_with_default_host, compile_song_lua_with_default_host,
+    overlay_model_layers_from_slots, song_lua_human_player_count, song_lua_style_info,
 };
 
 pub use deadsync_song_lua::{
-    SONG_LUA_INITIAL_LIFE, SongLuaCapturedActor, SongLuaColumnOffsetWindow, SongLuaCompileContext,
-    SongLuaCompileInfo, SongLuaDifficulty, SongLuaEaseTarget, SongLuaEaseWindow,
+    SONG_LUA_INITIAL_LIFE, SONG_LUA_LIVE_FRAME_BUDGET, SongLuaCapturedActor,
+    SongLuaColumnOffsetWindow, SongLuaCompileContext, SongLuaCompileInfo, SongLuaDifficulty,
+    SongLuaEaseTarget, SongLuaEaseWindow, SongLuaLiveRuntime, SongLuaLiveStats,
     SongLuaMessageEvent, SongLuaModWindow, SongLuaNoteHideWindow, SongLuaNoteskinResolver,
     SongLuaOverlayBlendMode, SongLuaOverlayCommandBlock, SongLuaOverlayEase,
     SongLuaOverlayMeshVertex, SongLuaOverlayMessageCommand, SongLuaOverlayModelDraw,
@@ -60,6 +62,22 @@ pub fn compile_song_lua(
     )
 }
 
+pub fn compile_live_song_lua(
+    entry_path: &Path,
+    context: &SongLuaCompileContext,
+    budget: Duration,
+) -> Result<(CompiledSongLua, Option<SongLuaLiveRuntime>), String> {
+    compile_live_song_lua_with_default_host(
+        entry_path,
+        context,
+        song_lua_noteskin_resolver(),
+        crate::noteskin::load_itg_model_slots_from_path,
+        model_layer_from_slot,
+        |context, noteskin| multitap_arrow_visual_spec(noteskin, context),
+        budget,
+    )
+}
+
 fn song_lua_noteskin_resolver() -> SongLuaNoteskinResolver {
     SongLuaNoteskinResolver {
         resolve_path: crate::noteskin::song_lua_noteskin_resolve_path,
@@ -150,6 +168,7 @@ fn multitap_arrow_visual_spec(
         SongLuaOverlayKind::Sprite {
             texture_path: PathBuf::from(texture_key.as_ref()),
             texture_key,
+            shader_effect: None,
         },
         state,
     ))
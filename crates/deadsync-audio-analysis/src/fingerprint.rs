@@ -0,0 +1,197 @@
+//! Coarse audio fingerprints for spotting the same song in different packs.
+//!
+//! The fingerprint is the direction of change of a downmixed 100 ms energy
+//! envelope. It ignores gain, sample rate and codec, so a re-encoded or
+//! normalized copy still matches, while different songs agree on roughly half
+//! of their windows. Copies are compared over a range of start shifts to allow
+//! for differing lead-in silence.
+
+use deadsync_audio_decode as decode;
+use std::path::Path;
+
+/// Envelope windows per second of audio.
+pub const FINGERPRINT_WINDOWS_PER_SECOND: u32 = 10;
+/// Fraction of agreeing windows at which two fingerprints are the same audio.
+pub const FINGERPRINT_MATCH_THRESHOLD: f32 = 0.8;
+/// Largest lead-in difference, in windows, tried when comparing.
+const MAX_SHIFT_WINDOWS: usize = 5 * FINGERPRINT_WINDOWS_PER_SECOND as usize;
+/// Fewest windows with a clear rise or fall needed before a match counts.
+const MIN_COMPARED_WINDOWS: usize = 100;
+/// Mean square below which a window counts as silence (about -60 dBFS).
+const SILENCE_MEAN_SQUARE: f64 = 1.0e-6;
+/// Log-energy change below which a window counts as flat.
+const FLAT_LOG_DELTA: f64 = 0.05;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AudioFingerprint {
+    /// Per window: 1 when energy rose, -1 when it fell, 0 when flat or silent.
+    pub steps: Vec<i8>,
+}
+
+impl AudioFingerprint {
+    /// Builds the fingerprint from mono samples in `-1.0..=1.0`.
+    pub fn from_mono_samples(samples: &[f32], sample_rate_hz: u32) -> Self {
+        let window = (sample_rate_hz / FINGERPRINT_WINDOWS_PER_SECOND).max(1) as usize;
+        let energies: Vec<f64> = samples
+            .chunks_exact(window)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|s| f64::from(*s) * f64::from(*s))
+                    .sum::<f64>()
+                    / window as f64
+            })
+            .collect();
+        Self::from_energies(&energies)
+    }
+
+    fn from_energies(energies: &[f64]) -> Self {
+        let steps = energies
+            .windows(2)
+            .map(|pair| {
+                if pair[0] < SILENCE_MEAN_SQUARE && pair[1] < SILENCE_MEAN_SQUARE {
+                    return 0;
+                }
+                let delta =
+                    (pair[1] + SILENCE_MEAN_SQUARE).ln() - (pair[0] + SILENCE_MEAN_SQUARE).ln();
+                if delta.abs() < FLAT_LOG_DELTA {
+                    0
+                } else if delta > 0.0 {
+                    1
+                } else {
+                    -1
+                }
+            })
+            .collect();
+        Self { steps }
+    }
+}
+
+/// Fraction of agreeing windows when `lead` and `trail` start together.
+fn agreement(lead: &[i8], trail: &[i8]) -> Option<f32> {
+    let mut compared = 0usize;
+    let mut agreed = 0usize;
+    for (x, y) in lead.iter().zip(trail) {
+        if *x == 0 || *y == 0 {
+            continue;
+        }
+        compared += 1;
+        agreed += usize::from(x == y);
+    }
+    (compared >= MIN_COMPARED_WINDOWS).then(|| agreed as f32 / compared as f32)
+}
+
+/// Best fraction of agreeing windows over all allowed start shifts, or 0 when
+/// the overlap is too short or too quiet to say.
+pub fn fingerprint_similarity(a: &AudioFingerprint, b: &AudioFingerprint) -> f32 {
+    let mut best = 0.0_f32;
+    for shift in 0..=MAX_SHIFT_WINDOWS {
+        if let Some(lead) = a.steps.get(shift..)
+            && let Some(score) = agreement(lead, &b.steps)
+        {
+            best = best.max(score);
+        }
+        if shift > 0
+            && let Some(lead) = b.steps.get(shift..)
+            && let Some(score) = agreement(lead, &a.steps)
+        {
+            best = best.max(score);
+        }
+    }
+    best
+}
+
+#[inline(always)]
+pub fn fingerprints_match(a: &AudioFingerprint, b: &AudioFingerprint) -> bool {
+    fingerprint_similarity(a, b) >= FINGERPRINT_MATCH_THRESHOLD
+}
+
+/// Decodes `path` and fingerprints its downmixed envelope.
+pub fn compute_fingerprint(path: &Path) -> Result<AudioFingerprint, String> {
+    let opened = decode::open_file(path).map_err(|e| e.to_string())?;
+    let channels = opened.channels.max(1);
+    let sample_rate = opened.sample_rate_hz.max(1);
+    let window = (sample_rate / FINGERPRINT_WINDOWS_PER_SECOND).max(1) as usize;
+    let mut reader = opened.reader;
+    let mut buf: Vec<i16> = Vec::new();
+    let mut energies = Vec::new();
+    let mut window_sum = 0.0_f64;
+    let mut window_frames = 0usize;
+    loop {
+        buf.clear();
+        match reader.read_dec_packet_into(&mut buf) {
+            Ok(false) => break,
+            Ok(true) => {}
+            Err(e) => return Err(e.to_string()),
+        }
+        for frame in buf.chunks_exact(channels) {
+            let sum: f64 = frame.iter().map(|s| f64::from(*s)).sum();
+            let mono = sum / (channels as f64 * 32768.0);
+            window_sum += mono * mono;
+            window_frames += 1;
+            if window_frames == window {
+                energies.push(window_sum / window as f64);
+                window_sum = 0.0;
+                window_frames = 0;
+            }
+        }
+    }
+    if energies.is_empty() {
+        return Err("decoder produced no samples".to_string());
+    }
+    Ok(AudioFingerprint::from_energies(&energies))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// A deterministic "song": a tone whose loudness wanders pseudo-randomly.
+    fn song(seed: u32, seconds: usize, lead_in: usize) -> Vec<f32> {
+        let rate = 1000usize;
+        let mut state = seed;
+        let mut samples = vec![0.0; lead_in * rate / 10];
+        for _ in 0..seconds * 10 {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let level = 0.1 + (state >> 16) as f32 / 65_536.0 * 0.8;
+            samples.extend((0..rate / 10).map(|ix| level * if ix % 2 == 0 { 1.0 } else { -1.0 }));
+        }
+        samples
+    }
+
+    #[test]
+    fn gain_and_lead_in_do_not_hide_a_copy() {
+        let original = AudioFingerprint::from_mono_samples(&song(7, 60, 0), 1000);
+        let quieter: Vec<f32> = song(7, 60, 12).iter().map(|s| s * 0.5).collect();
+        let copy = AudioFingerprint::from_mono_samples(&quieter, 1000);
+        assert!(fingerprints_match(&original, &copy));
+        assert!(fingerprints_match(&copy, &original));
+    }
+
+    #[test]
+    fn different_songs_do_not_match() {
+        let a = AudioFingerprint::from_mono_samples(&song(7, 60, 0), 1000);
+        let b = AudioFingerprint::from_mono_samples(&song(8, 60, 0), 1000);
+        assert!(
+            !fingerprints_match(&a, &b),
+            "{}",
+            fingerprint_similarity(&a, &b)
+        );
+    }
+
+    #[test]
+    fn short_or_silent_audio_never_matches() {
+        let silent = AudioFingerprint::from_mono_samples(&vec![0.0; 60_000], 1000);
+        assert_eq!(fingerprint_similarity(&silent, &silent), 0.0);
+        let short = AudioFingerprint::from_mono_samples(&song(7, 5, 0), 1000);
+        assert_eq!(fingerprint_similarity(&short, &short), 0.0);
+    }
+
+    #[test]
+    fn fingerprints_credits_ogg() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../assets/music/credits.ogg");
+        let fingerprint = compute_fingerprint(&path).expect("fingerprint");
+        assert!(!fingerprint.steps.is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod cache;
+pub mod fingerprint;
 
 use deadsync_audio_decode as decode;
 use ebur128::{EbuR128, Mode};
@@ -11,6 +12,10 @@ pub use cache::{
     replaygain_cache_info_if_fresh, replaygain_content_hash, replaygain_path_hash,
     replaygain_source_mtime_unix_nanos, write_replaygain_cache_file,
 };
+pub use fingerprint::{
+    AudioFingerprint, FINGERPRINT_MATCH_THRESHOLD, compute_fingerprint, fingerprint_similarity,
+    fingerprints_match,
+};
 
 /// EBU R 128 / ReplayGain 2.0 reference loudness.
 const TARGET_LUFS: f64 = -18.0;
@@ -29,6 +29,23 @@ pub const fn sync_pref_offset(pref: SyncPref, default: SyncPref) -> f32 {
     }
 }
 
+/// Song offset shift applied to every chart of a pack: the `Pack.ini`
+/// preference when pack offsets are enabled, plus the pack's sync bias.
+#[inline(always)]
+pub fn pack_sync_shift_seconds(
+    pref: SyncPref,
+    default: SyncPref,
+    pack_ini_offsets: bool,
+    bias_ms: f32,
+) -> f32 {
+    let pref_offset = if pack_ini_offsets {
+        sync_pref_offset(pref, default)
+    } else {
+        0.0
+    };
+    pref_offset + bias_ms / 1000.0
+}
+
 pub fn format_display_bpm_range(range: Option<(f64, f64)>, music_rate: f32) -> String {
     let Some((lo, hi)) = range else {
         return String::new();
@@ -221,11 +238,30 @@ pub struct SongPack {
     pub folder_series: String,
     pub year: i32,
     pub sync_pref: SyncPref,
+    /// Machine-set bias from the pack's `PackSync.ini`, in ms, applied on
+    /// top of the `Pack.ini` preference. Positive moves notes earlier, like
+    /// raising `#OFFSET`.
+    pub sync_bias_ms: f32,
+    /// Bias an unfinished `#OFFSET` rewrite already wrote into simfiles, in
+    /// ms; those simfiles only get the rest of `sync_bias_ms`.
+    pub sync_bias_written_ms: Vec<(PathBuf, f32)>,
     pub directory: PathBuf,
     pub banner_path: Option<PathBuf>,
     pub songs: Vec<Arc<SongData>>,
 }
 
+impl SongPack {
+    /// The pack's bias still to apply to `simfile_path` at load.
+    pub fn sync_bias_ms_for(&self, simfile_path: &Path) -> f32 {
+        let written_ms = self
+            .sync_bias_written_ms
+            .iter()
+            .find(|(path, _)| path == simfile_path)
+            .map_or(0.0, |(_, ms)| *ms);
+        self.sync_bias_ms - written_ms
+    }
+}
+
 impl SongData {
     #[inline(always)]
     fn is_video_path(path: &Path) -> bool {
@@ -1025,4 +1061,16 @@ mod tests {
             ITG_SYNC_OFFSET_SECONDS
         );
     }
+
+    #[test]
+    fn pack_sync_shift_adds_bias_even_without_pack_ini_offsets() {
+        assert_eq!(
+            pack_sync_shift_seconds(SyncPref::Itg, SyncPref::Null, false, 9.0),
+            0.009
+        );
+        assert_eq!(
+            pack_sync_shift_seconds(SyncPref::Itg, SyncPref::Null, true, 9.0),
+            ITG_SYNC_OFFSET_SECONDS + 0.009
+        );
+    }
 }
@@ -90,6 +90,10 @@ pub struct Config {
     pub center_image_add_width: i32,
     pub center_image_add_height: i32,
     pub video_renderer: BackendType,
+    /// Open a second window with the spectator layout during gameplay.
+    pub spectator_window: bool,
+    /// Renderer for the spectator window, independent of `video_renderer`.
+    pub spectator_renderer: BackendType,
     /// Native high-DPI/Retina rendering. Currently affects macOS OpenGL only.
     pub high_dpi: bool,
     /// Hide the OS mouse cursor while it is inside the DeadSync window.
@@ -137,6 +141,10 @@ pub struct Config {
     // 1 = Single-threaded
     // N >= 2 = cap at N threads (clamped to available cores).
     pub software_renderer_threads: u8,
+    /// Keep song-lua scripts running during gameplay for callbacks the
+    /// compiler could not bake; falls back to compiled output when a frame
+    /// runs over budget.
+    pub song_lua_live_runtime: bool,
     // When parsing simfiles at startup:
     // 0 = Auto (use all logical cores) for cache misses
     // 1 = Single-threaded
@@ -405,6 +413,8 @@ impl Default for Config {
             center_image_add_width: system.center_image_add_width,
             center_image_add_height: system.center_image_add_height,
             video_renderer: BackendType::OpenGL,
+            spectator_window: DEFAULT_SPECTATOR_WINDOW,
+            spectator_renderer: BackendType::Software,
             high_dpi: system.high_dpi,
             hide_mouse_cursor: system.hide_mouse_cursor,
             gfx_debug: system.gfx_debug,
@@ -422,6 +432,7 @@ impl Default for Config {
             smx_default_pad_config: SmxPadPreset::Low,
             smx_default_light_brightness: DEFAULT_SMX_DEFAULT_LIGHT_BRIGHTNESS,
             software_renderer_threads: runtime.software_renderer_threads,
+            song_lua_live_runtime: runtime.song_lua_live_runtime,
             song_parsing_threads: runtime.song_parsing_threads,
             simply_love_color: theme.simply_love_color,
             show_select_music_gameplay_timer: theme.show_select_music_gameplay_timer,
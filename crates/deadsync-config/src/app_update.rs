@@ -403,6 +403,14 @@ pub fn set_video_renderer(cfg: &mut Config, renderer: BackendType) -> bool {
     set_if_changed(&mut cfg.video_renderer, renderer)
 }
 
+pub fn set_spectator_window(cfg: &mut Config, enabled: bool) -> bool {
+    set_if_changed(&mut cfg.spectator_window, enabled)
+}
+
+pub fn set_spectator_renderer(cfg: &mut Config, renderer: BackendType) -> bool {
+    set_if_changed(&mut cfg.spectator_renderer, renderer)
+}
+
 pub fn set_present_mode_policy(cfg: &mut Config, mode: PresentModePolicy) -> bool {
     set_if_changed(&mut cfg.present_mode_policy, mode)
 }
@@ -427,6 +435,10 @@ pub fn set_menu_music(cfg: &mut Config, enabled: bool) -> bool {
     set_if_changed(&mut cfg.menu_music, enabled)
 }
 
+pub fn set_song_lua_live_runtime(cfg: &mut Config, enabled: bool) -> bool {
+    set_if_changed(&mut cfg.song_lua_live_runtime, enabled)
+}
+
 pub fn set_software_renderer_threads(cfg: &mut Config, threads: u8) -> bool {
     set_if_changed(&mut cfg.software_renderer_threads, threads)
 }
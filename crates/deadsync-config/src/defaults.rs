@@ -16,6 +16,7 @@ pub const DEFAULT_CDTITLE_CACHE: bool = true;
 pub const DEFAULT_DISPLAY_WIDTH: u32 = 1600;
 pub const DEFAULT_DISPLAY_HEIGHT: u32 = 900;
 pub const DEFAULT_DISPLAY_ASPECT_RATIO: f32 = 16.0 / 9.0;
+pub const DEFAULT_SPECTATOR_WINDOW: bool = false;
 pub const DEFAULT_CENTER_IMAGE_TRANSLATE_X: i32 = 0;
 pub const DEFAULT_CENTER_IMAGE_TRANSLATE_Y: i32 = 0;
 pub const DEFAULT_CENTER_IMAGE_ADD_WIDTH: i32 = 0;
@@ -32,6 +33,7 @@ pub const DEFAULT_SMX_UNDERGLOW_GRB: bool = false;
 pub const DEFAULT_SMX_IDLE_LIGHTS_BLACK: bool = false;
 pub const DEFAULT_SMX_DEFAULT_LIGHT_BRIGHTNESS: u8 = 100;
 pub const DEFAULT_SOFTWARE_RENDERER_THREADS: u8 = 1;
+pub const DEFAULT_SONG_LUA_LIVE_RUNTIME: bool = false;
 pub const DEFAULT_SONG_PARSING_THREADS: u8 = 0;
 pub const DEFAULT_SIMPLY_LOVE_COLOR: i32 = 2;
 pub const DEFAULT_SHOW_SELECT_MUSIC_GAMEPLAY_TIMER: bool = true;
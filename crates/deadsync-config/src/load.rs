@@ -157,6 +157,8 @@ pub fn load_app_config(conf: &SimpleIni, default: Config) -> Config {
                 height: default.display_height,
                 aspect_ratio: default.display_aspect_ratio,
                 video_renderer: default.video_renderer,
+                spectator_window: default.spectator_window,
+                spectator_renderer: default.spectator_renderer,
             },
             input_hardware: SystemInputHardwareLoadOptions {
                 gamepad_backend: default.windows_gamepad_backend,
@@ -236,6 +238,8 @@ fn apply_display_opts(
     cfg.display_height = display.height;
     cfg.display_aspect_ratio = display.aspect_ratio;
     cfg.video_renderer = display.video_renderer;
+    cfg.spectator_window = display.spectator_window;
+    cfg.spectator_renderer = display.spectator_renderer;
 }
 
 fn apply_system_opts(loaded: SystemOptions, cfg: &mut Config) {
@@ -400,6 +404,7 @@ fn apply_runtime_opts(
     cfg.only_dedicated_menu_buttons = loaded.only_dedicated_menu_buttons;
     cfg.theme_flag = loaded.theme_flag;
     cfg.software_renderer_threads = loaded.software_renderer_threads;
+    cfg.song_lua_live_runtime = loaded.song_lua_live_runtime;
 
     cfg.input_debounce_seconds = io.input_debounce_seconds;
     cfg.lights_driver = io.lights_driver;
@@ -26,9 +26,10 @@ use crate::defaults::{
     DEFAULT_SHOW_VERSION_OVERLAY, DEFAULT_SMOOTH_HISTOGRAM, DEFAULT_SMX_IDLE_LIGHTS_BLACK,
     DEFAULT_SMX_INPUT, DEFAULT_SMX_MANAGES_PAD_CONFIG, DEFAULT_SMX_PANEL_LIGHTS,
     DEFAULT_SMX_UNDERGLOW_GRB, DEFAULT_SMX_UNDERGLOW_THEME, DEFAULT_SOFTWARE_RENDERER_THREADS,
-    DEFAULT_SONG_PARSING_THREADS, DEFAULT_SORT_MUSIC_WHEEL_BY_SERIES,
-    DEFAULT_SUBMIT_ARROWCLOUD_FAILS, DEFAULT_THREE_KEY_NAVIGATION, DEFAULT_TRANSLATED_TITLES,
-    DEFAULT_UPDATER_INSTALL_ENABLED, DEFAULT_USE_FSRS,
+    DEFAULT_SONG_LUA_LIVE_RUNTIME, DEFAULT_SONG_PARSING_THREADS,
+    DEFAULT_SORT_MUSIC_WHEEL_BY_SERIES, DEFAULT_SUBMIT_ARROWCLOUD_FAILS,
+    DEFAULT_THREE_KEY_NAVIGATION, DEFAULT_TRANSLATED_TITLES, DEFAULT_UPDATER_INSTALL_ENABLED,
+    DEFAULT_USE_FSRS,
 };
 use crate::ini::SimpleIni;
 use crate::machine::{
@@ -360,6 +361,8 @@ pub struct DisplayOptions<'a> {
     pub video_renderer: &'a str,
     pub vsync: bool,
     pub windowed: bool,
+    pub spectator_window: bool,
+    pub spectator_renderer: &'a str,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -383,6 +386,8 @@ pub struct DisplayLoadOptions<F, P, V> {
     pub height: u32,
     pub aspect_ratio: f32,
     pub video_renderer: V,
+    pub spectator_window: bool,
+    pub spectator_renderer: V,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -453,8 +458,16 @@ where
             .unwrap_or(default.aspect_ratio),
         video_renderer: conf
             .get("Options", "VideoRenderer")
-            .and_then(parse_video_renderer)
+            .and_then(&parse_video_renderer)
             .unwrap_or(default.video_renderer),
+        spectator_window: parse_u8_bool_or_default(
+            conf.get("Options", "SpectatorWindow"),
+            default.spectator_window,
+        ),
+        spectator_renderer: conf
+            .get("Options", "SpectatorRenderer")
+            .and_then(parse_video_renderer)
+            .unwrap_or(default.spectator_renderer),
     }
 }
 
@@ -932,6 +945,11 @@ pub fn push_display_frame_timing_option_lines(content: &mut String, options: Dis
     push_line(content, "PresentModePolicy", options.present_mode_policy);
 }
 
+pub fn push_display_spectator_option_lines(content: &mut String, options: DisplayOptions<'_>) {
+    push_line(content, "SpectatorRenderer", options.spectator_renderer);
+    push_bool(content, "SpectatorWindow", options.spectator_window);
+}
+
 pub fn push_display_video_tail_option_lines(content: &mut String, options: DisplayOptions<'_>) {
     push_line(content, "VideoRenderer", options.video_renderer);
     push_bool(content, "Vsync", options.vsync);
@@ -1422,6 +1440,7 @@ pub struct RuntimeOptions {
     pub only_dedicated_menu_buttons: bool,
     pub theme_flag: ThemeFlag,
     pub software_renderer_threads: u8,
+    pub song_lua_live_runtime: bool,
 }
 
 impl Default for RuntimeOptions {
@@ -1441,6 +1460,7 @@ impl Default for RuntimeOptions {
             only_dedicated_menu_buttons: DEFAULT_ONLY_DEDICATED_MENU_BUTTONS,
             theme_flag: ThemeFlag::SimplyLove,
             software_renderer_threads: DEFAULT_SOFTWARE_RENDERER_THREADS,
+            song_lua_live_runtime: DEFAULT_SONG_LUA_LIVE_RUNTIME,
         }
     }
 }
@@ -1497,6 +1517,10 @@ pub fn load_runtime_options(conf: &SimpleIni, default: RuntimeOptions) -> Runtim
             .get("Options", "SoftwareRendererThreads")
             .and_then(parse_auto_threads_u8)
             .unwrap_or(default.software_renderer_threads),
+        song_lua_live_runtime: conf
+            .get("Options", "SongLuaLiveRuntime")
+            .and_then(parse_loose_bool_str)
+            .unwrap_or(default.song_lua_live_runtime),
     }
 }
 
@@ -1565,6 +1589,10 @@ pub fn push_runtime_menu_option_lines(content: &mut String, options: RuntimeOpti
     );
 }
 
+pub fn push_runtime_song_lua_option_lines(content: &mut String, options: RuntimeOptions) {
+    push_bool(content, "SongLuaLiveRuntime", options.song_lua_live_runtime);
+}
+
 pub fn push_runtime_worker_theme_option_lines(content: &mut String, options: RuntimeOptions) {
     push_line(content, "SongParsingThreads", options.song_parsing_threads);
     push_line(
@@ -2398,6 +2426,7 @@ mod tests {
             only_dedicated_menu_buttons: false,
             theme_flag: ThemeFlag::SimplyLove,
             software_renderer_threads: 1,
+            song_lua_live_runtime: false,
         }
     }
 
@@ -2425,6 +2454,8 @@ mod tests {
                 DisplayHeight=1080
                 DisplayAspectRatio=1.3333334
                 VideoRenderer=a
+                SpectatorWindow=1
+                SpectatorRenderer=c
                 "#),
             DisplayLoadOptions {
                 vsync: false,
@@ -2437,6 +2468,8 @@ mod tests {
                 height: 900,
                 aspect_ratio: 16.0 / 9.0,
                 video_renderer: 'b',
+                spectator_window: false,
+                spectator_renderer: 'b',
             },
             parse_letter_token,
             parse_letter_token,
@@ -2458,6 +2491,8 @@ mod tests {
                 height: 1080,
                 aspect_ratio: 4.0 / 3.0,
                 video_renderer: 'a',
+                spectator_window: true,
+                spectator_renderer: 'c',
             },
         );
     }
@@ -2477,6 +2512,8 @@ mod tests {
                 height: 900,
                 aspect_ratio: 16.0 / 9.0,
                 video_renderer: 'b',
+                spectator_window: false,
+                spectator_renderer: 'b',
             },
             parse_letter_token,
             parse_letter_token,
@@ -2912,12 +2949,15 @@ mod tests {
             video_renderer: "OpenGL",
             vsync: true,
             windowed: false,
+            spectator_window: true,
+            spectator_renderer: "Software",
         };
 
         push_display_size_option_lines(&mut content, options);
         push_display_monitor_option_lines(&mut content, options);
         push_display_fullscreen_option_lines(&mut content, options);
         push_display_frame_timing_option_lines(&mut content, options);
+        push_display_spectator_option_lines(&mut content, options);
         push_display_video_tail_option_lines(&mut content, options);
 
         assert_eq!(
@@ -2930,6 +2970,8 @@ mod tests {
                 "FullscreenType=Borderless\n",
                 "MaxFps=144\n",
                 "PresentModePolicy=immediate\n",
+                "SpectatorRenderer=Software\n",
+                "SpectatorWindow=1\n",
                 "VideoRenderer=OpenGL\n",
                 "Vsync=1\n",
                 "Windowed=0\n",
@@ -3032,6 +3074,7 @@ mod tests {
             OnlyDedicatedMenuButtons=1
             Theme=Simply Love
             SoftwareRendererThreads=auto
+            SongLuaLiveRuntime=1
             "#,
         );
 
@@ -3051,6 +3094,7 @@ mod tests {
         assert!(loaded.only_dedicated_menu_buttons);
         assert_eq!(loaded.theme_flag, ThemeFlag::SimplyLove);
         assert_eq!(loaded.software_renderer_threads, 0);
+        assert!(loaded.song_lua_live_runtime);
     }
 
     #[test]
@@ -3382,6 +3426,7 @@ mod tests {
             only_dedicated_menu_buttons: false,
             theme_flag: ThemeFlag::SimplyLove,
             software_renderer_threads: 3,
+            song_lua_live_runtime: true,
         };
 
         push_runtime_cache_option_lines(&mut content, options);
@@ -3390,6 +3435,7 @@ mod tests {
         push_runtime_navigation_option_lines(&mut content, options);
         push_runtime_lights_option_lines(&mut content, options);
         push_runtime_menu_option_lines(&mut content, options);
+        push_runtime_song_lua_option_lines(&mut content, options);
         push_runtime_worker_theme_option_lines(&mut content, options);
 
         assert_eq!(
@@ -3404,6 +3450,7 @@ mod tests {
                 "UseFSRs=0\n",
                 "LightsSimplifyBass=1\n",
                 "OnlyDedicatedMenuButtons=0\n",
+                "SongLuaLiveRuntime=1\n",
                 "SongParsingThreads=6\n",
                 "SoftwareRendererThreads=3\n",
                 "Theme=Simply Love\n",
@@ -73,6 +73,7 @@ fn update_config(apply: impl FnOnce(&mut Config) -> bool) -> bool {
 
 update_config_fn!(pub fn update_menu_music(enabled: bool) => set_menu_music);
 update_config_fn!(pub fn update_software_renderer_threads(threads: u8) => set_software_renderer_threads);
+update_config_fn!(pub fn update_song_lua_live_runtime(enabled: bool) => set_song_lua_live_runtime);
 update_config_fn!(pub fn update_audio_sample_rate(rate: Option<u32>) => set_audio_sample_rate);
 update_config_fn!(pub fn update_audio_output_device(index: Option<u16>) => set_audio_output_device);
 update_config_fn!(pub fn update_audio_output_mode(mode: AudioOutputMode) => set_audio_output_mode);
@@ -86,6 +87,8 @@ update_config_fn2!(pub fn update_display_resolution(width: u32, height: u32) =>
 update_config_fn!(pub fn update_display_aspect_ratio(aspect_ratio: f32) => set_display_aspect_ratio);
 update_config_fn!(pub fn update_display_monitor(monitor: usize) => set_display_monitor);
 update_config_fn!(pub fn update_video_renderer(renderer: BackendType) => set_video_renderer);
+update_config_fn!(pub fn update_spectator_window(enabled: bool) => set_spectator_window);
+update_config_fn!(pub fn update_spectator_renderer(renderer: BackendType) => set_spectator_renderer);
 update_config_fn!(pub fn update_gfx_debug(enabled: bool) => set_gfx_debug);
 update_config_fn!(pub fn update_high_dpi(enabled: bool) => set_high_dpi);
 update_config_fn!(pub fn update_hide_mouse_cursor(enabled: bool) => set_hide_mouse_cursor);
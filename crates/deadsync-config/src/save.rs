@@ -13,12 +13,13 @@ use crate::options::{
     StatsOverlayOptions, SystemInputHardwareOptions, SystemOptions,
     push_display_frame_timing_option_lines, push_display_fullscreen_option_lines,
     push_display_monitor_option_lines, push_display_size_option_lines,
-    push_display_video_tail_option_lines, push_gameplay_bg_color_option_line,
-    push_runtime_audio_backend_option_lines, push_runtime_cache_option_lines,
-    push_runtime_fastload_option_lines, push_runtime_input_debounce_option_lines,
-    push_runtime_lights_driver_option_lines, push_runtime_lights_option_lines,
-    push_runtime_lights_port_option_lines, push_runtime_menu_option_lines,
-    push_runtime_navigation_option_lines, push_runtime_song_deletion_option_line,
+    push_display_spectator_option_lines, push_display_video_tail_option_lines,
+    push_gameplay_bg_color_option_line, push_runtime_audio_backend_option_lines,
+    push_runtime_cache_option_lines, push_runtime_fastload_option_lines,
+    push_runtime_input_debounce_option_lines, push_runtime_lights_driver_option_lines,
+    push_runtime_lights_option_lines, push_runtime_lights_port_option_lines,
+    push_runtime_menu_option_lines, push_runtime_navigation_option_lines,
+    push_runtime_song_deletion_option_line, push_runtime_song_lua_option_lines,
     push_runtime_worker_theme_option_lines, push_select_music_option_lines,
     push_stats_overlay_option_lines, push_system_banner_cache_option_lines,
     push_system_bg_brightness_option_lines, push_system_cdtitle_center_option_lines,
@@ -163,10 +164,12 @@ where
     push_runtime_lights_option_lines(content, options.runtime);
     push_runtime_lights_port_option_lines(content, options.runtime_io);
     push_runtime_menu_option_lines(content, options.runtime);
+    push_runtime_song_lua_option_lines(content, options.runtime);
     push_display_monitor_option_lines(content, options.display);
     push_runtime_worker_theme_option_lines(content, options.runtime);
     push_audio_tail_option_lines(content, options.audio);
     push_system_translation_option_lines(content, options.system);
+    push_display_spectator_option_lines(content, options.display);
     push_display_video_tail_option_lines(content, options.display);
     push_audio_write_current_screen_option_lines(content, options.audio);
     content.push('\n');
@@ -212,9 +215,11 @@ where
     push_runtime_lights_option_lines(content, options.runtime);
     push_runtime_lights_port_option_lines(content, options.runtime_io);
     push_runtime_menu_option_lines(content, options.runtime);
+    push_runtime_song_lua_option_lines(content, options.runtime);
     push_runtime_worker_theme_option_lines(content, options.runtime);
     push_audio_tail_option_lines(content, options.audio);
     push_system_translation_option_lines(content, options.system);
+    push_display_spectator_option_lines(content, options.display);
     push_display_video_tail_option_lines(content, options.display);
     push_audio_write_current_screen_option_lines(content, options.audio);
     content.push('\n');
@@ -247,6 +252,7 @@ pub fn build_saved_app_config_file(
 ) -> String {
     let gameplay_bg_color = cfg.gameplay_bg_color.to_hex();
     let video_renderer = cfg.video_renderer.to_string();
+    let spectator_renderer = cfg.spectator_renderer.to_string();
     let practice = keycode_to_token(cfg.music_select_shortcut_practice);
     let song_search = keycode_to_token(cfg.music_select_shortcut_song_search);
     let load_songs = keycode_to_token(cfg.music_select_shortcut_load_songs);
@@ -265,6 +271,7 @@ pub fn build_saved_app_config_file(
                     cfg,
                     cfg.present_mode_policy.as_str(),
                     video_renderer.as_str(),
+                    spectator_renderer.as_str(),
                 ),
                 runtime_io: runtime_io_options(
                     cfg,
@@ -312,6 +319,7 @@ pub fn build_default_app_config_file() -> String {
     let default = Config::default();
     let gameplay_bg_color = default.gameplay_bg_color.to_hex();
     let video_renderer = default.video_renderer.to_string();
+    let spectator_renderer = default.spectator_renderer.to_string();
     let practice = keycode_to_token(default.music_select_shortcut_practice);
     let song_search = keycode_to_token(default.music_select_shortcut_song_search);
     let load_songs = keycode_to_token(default.music_select_shortcut_load_songs);
@@ -330,6 +338,7 @@ pub fn build_default_app_config_file() -> String {
                     &default,
                     default.present_mode_policy.as_str(),
                     video_renderer.as_str(),
+                    spectator_renderer.as_str(),
                 ),
                 runtime_io: runtime_io_options(
                     &default,
@@ -409,6 +418,7 @@ fn display_options<'a>(
     cfg: &Config,
     present_mode_policy: &'a str,
     video_renderer: &'a str,
+    spectator_renderer: &'a str,
 ) -> DisplayOptions<'a> {
     DisplayOptions {
         width: cfg.display_width,
@@ -421,6 +431,8 @@ fn display_options<'a>(
         video_renderer,
         vsync: cfg.vsync,
         windowed: cfg.windowed,
+        spectator_window: cfg.spectator_window,
+        spectator_renderer,
     }
 }
 
@@ -525,6 +537,7 @@ fn runtime_options(cfg: &Config) -> RuntimeOptions {
         only_dedicated_menu_buttons: cfg.only_dedicated_menu_buttons,
         theme_flag: cfg.theme_flag,
         software_renderer_threads: cfg.software_renderer_threads,
+        song_lua_live_runtime: cfg.song_lua_live_runtime,
     }
 }
 
@@ -427,6 +427,7 @@ fn window_benchmark() {
                 base_mini_percent: 0.0,
                 attack_windows: &masks,
                 song_lua_ease_windows: &eases,
+                song_lua_live_windows: &[],
             },
             old_state,
         );
@@ -468,6 +469,7 @@ fn window_benchmark() {
                 base_mini_percent: 0.0,
                 attack_windows: &indexed.mask_windows[0],
                 song_lua_ease_windows: &indexed.song_lua_ease_windows[0],
+                song_lua_live_windows: &[],
             },
             new_state,
             mask_indices,
@@ -527,6 +529,7 @@ fn idle_attack_benchmark() {
         base_mini_percent: 0.0,
         attack_windows: &indexed.mask_windows[0],
         song_lua_ease_windows: &indexed.song_lua_ease_windows[0],
+        song_lua_live_windows: &[],
     };
     let reference = measure(IDLE_ATTACK_FRAMES, || {
         refresh_checksum(refresh_active_attack_player_indexed_reference(
@@ -573,6 +576,7 @@ fn legacy_refresh_attack_state(
             base_mini_percent: base.mini_percent,
             attack_windows: &attacks.mask_windows[0],
             song_lua_ease_windows: &attacks.song_lua_ease_windows[0],
+            song_lua_live_windows: &[],
         },
         ActiveAttackRefreshState {
             attack_current_appearance: attacks.current_appearance[0],
@@ -1272,6 +1272,7 @@ pub struct SongLuaRuntimeVisuals<OverlayActor, CapturedActor, StateDelta> {
         Vec<SongLuaVisualLayerRuntime<OverlayActor, CapturedActor, StateDelta>>,
     pub player_actors: [CapturedActor; MAX_PLAYERS],
     pub player_events: [Vec<SongLuaOverlayMessageRuntime>; MAX_PLAYERS],
+    pub song_background: CapturedActor,
     pub song_foreground: CapturedActor,
     pub song_foreground_events: Vec<SongLuaOverlayMessageRuntime>,
     pub hidden_players: [bool; MAX_PLAYERS],
@@ -1300,6 +1301,7 @@ pub fn build_song_lua_runtime_visuals<OverlayActor, CapturedActor, StateDelta>(
     >,
     player_actors: [CapturedActor; MAX_PLAYERS],
     player_events: [Vec<SongLuaOverlayMessageRuntime>; MAX_PLAYERS],
+    song_background: CapturedActor,
     song_foreground: CapturedActor,
     song_foreground_events: Vec<SongLuaOverlayMessageRuntime>,
     hidden_players: [bool; MAX_PLAYERS],
@@ -1317,6 +1319,7 @@ pub fn build_song_lua_runtime_visuals<OverlayActor, CapturedActor, StateDelta>(
         foreground_visual_layers,
         player_actors,
         player_events,
+        song_background,
         song_foreground,
         song_foreground_events,
         hidden_players,
@@ -1951,6 +1954,27 @@ pub fn append_song_lua_runtime_ease_window(
     }
 }
 
+/// Appends a live song-lua value that holds at any time until the next frame
+/// replaces the live windows.
+pub fn append_song_lua_live_window(
+    out: &mut Vec<SongLuaEaseMaskWindow>,
+    target: SongLuaRuntimeEaseTarget<'_>,
+    value: f32,
+) -> SongLuaRuntimeEaseAppend {
+    append_song_lua_runtime_ease_window(
+        out,
+        f32::MIN,
+        f32::MIN,
+        f32::MAX,
+        target,
+        value,
+        value,
+        None,
+        None,
+        None,
+    )
+}
+
 pub fn append_song_lua_runtime_ease_window_like<Target>(
     out: &mut Vec<SongLuaEaseMaskWindow>,
     start_second: f32,
@@ -4191,6 +4215,9 @@ pub struct ActiveAttackRefreshInput<'a> {
     pub base_mini_percent: f32,
     pub attack_windows: &'a [AttackMaskWindow],
     pub song_lua_ease_windows: &'a [SongLuaEaseMaskWindow],
+    /// Per-frame windows from a live song-lua runtime, applied after the
+    /// compiled eases.
+    pub song_lua_live_windows: &'a [SongLuaEaseMaskWindow],
 }
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -4234,6 +4261,8 @@ pub struct ActiveAttackRefreshOutput {
 pub struct GameplayAttackRuntimeState {
     pub mask_windows: [Vec<AttackMaskWindow>; MAX_PLAYERS],
     pub song_lua_ease_windows: [Vec<SongLuaEaseMaskWindow>; MAX_PLAYERS],
+    /// Replaced every frame by the live song-lua runtime; never indexed.
+    pub song_lua_live_windows: [Vec<SongLuaEaseMaskWindow>; MAX_PLAYERS],
     window_indices: [GameplayPlayerWindowIndex; MAX_PLAYERS],
     pub cleared_for_outro: bool,
     pub clear_all: [bool; MAX_PLAYERS],
@@ -4407,6 +4436,7 @@ impl Default for GameplayAttackRuntimeState {
         Self {
             mask_windows: std::array::from_fn(|_| Vec::new()),
             song_lua_ease_windows: std::array::from_fn(|_| Vec::new()),
+            song_lua_live_windows: std::array::from_fn(|_| Vec::new()),
             window_indices: std::array::from_fn(|_| GameplayPlayerWindowIndex::default()),
             cleared_for_outro: false,
             clear_all: [false; MAX_PLAYERS],
@@ -4473,6 +4503,7 @@ impl GameplayAttackRuntimeState {
     ) -> bool {
         self.mask_windows[player].is_empty()
             && self.song_lua_ease_windows[player].is_empty()
+            && self.song_lua_live_windows[player].is_empty()
             && !self.cleared_for_outro
             && !self.clear_all[player]
             && self.chart[player] == ChartAttackEffects::default()
@@ -4524,6 +4555,7 @@ impl GameplayAttackRuntimeState {
                 base_mini_percent: base.mini_percent,
                 attack_windows: &self.mask_windows[player],
                 song_lua_ease_windows: &self.song_lua_ease_windows[player],
+                song_lua_live_windows: &self.song_lua_live_windows[player],
             },
             ActiveAttackRefreshState {
                 attack_current_appearance: self.current_appearance[player],
@@ -4736,6 +4768,7 @@ pub fn refresh_active_attack_player(
 ) -> ActiveAttackRefreshOutput {
     if input.attack_windows.is_empty()
         && input.song_lua_ease_windows.is_empty()
+        && input.song_lua_live_windows.is_empty()
         && !input.attacks_cleared_for_outro
     {
         return refresh_idle_attack_player(input, state);
@@ -4796,12 +4829,14 @@ pub fn refresh_active_attack_player_indexed(
 ) -> ActiveAttackRefreshOutput {
     if input.attack_windows.is_empty()
         && input.song_lua_ease_windows.is_empty()
+        && input.song_lua_live_windows.is_empty()
         && !input.attacks_cleared_for_outro
     {
         return refresh_active_attack_player(input, state);
     }
     if attack_window_indices.is_empty()
         && ease_window_indices.is_empty()
+        && input.song_lua_live_windows.is_empty()
         && !input.attacks_cleared_for_outro
         && appearance_bits_eq(state.attack_current_appearance, input.base_appearance)
     {
@@ -4874,6 +4909,12 @@ fn refresh_active_attack_player_full(
             ease_window_indices,
             input.now,
         );
+        apply_song_lua_player_eases_selected(
+            &mut player_transform,
+            input.song_lua_live_windows,
+            None,
+            input.now,
+        );
         let mut visual = state.outro_attack_visual;
         approach_visual_overrides_to_base(&mut visual, input.base_visual, input.delta_time);
         return ActiveAttackRefreshOutput {
@@ -4946,6 +4987,15 @@ fn refresh_active_attack_player_full(
         input.now,
         base_mini_percent,
     );
+    apply_song_lua_attack_eases_selected(
+        &mut attack,
+        &mut appearance,
+        &mut player_transform,
+        input.song_lua_live_windows,
+        None,
+        input.now,
+        base_mini_percent,
+    );
     if let Some(mini) = attack.mini_percent.filter(|v| v.is_finite()) {
         attack.mini_percent = Some(mini.clamp(-100.0, 150.0));
     }
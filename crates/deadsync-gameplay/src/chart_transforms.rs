@@ -2509,6 +2509,28 @@ fn turn_take_from(turn: GameplayTurnOption, cols: usize, seed: u64) -> Option<Ve
     }
 }
 
+/// Column each chart column lands on under `turn`, for mapping a recorded play
+/// back onto its chart. `None` for turns that rearrange row by row.
+pub fn turn_lane_map(turn: GameplayTurnOption, cols: usize, seed: u64) -> Option<Vec<usize>> {
+    if matches!(
+        turn,
+        GameplayTurnOption::Blender | GameplayTurnOption::Random
+    ) {
+        return None;
+    }
+    let mut old_to_new: Vec<usize> = (0..cols).collect();
+    if let Some(take_from) = turn_take_from(turn, cols, seed)
+        && take_from.len() == cols
+    {
+        for (new_col, &old_col) in take_from.iter().enumerate() {
+            if old_col < cols {
+                old_to_new[old_col] = new_col;
+            }
+        }
+    }
+    Some(old_to_new)
+}
+
 pub fn apply_turn_permutation(
     notes: &mut [Note],
     note_range: (usize, usize),
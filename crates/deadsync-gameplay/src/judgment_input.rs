@@ -211,7 +211,10 @@ pub fn build_player_judgment_timing_for_options(
     disabled_windows: [bool; 5],
     music_rate: f32,
 ) -> PlayerJudgmentTiming {
-    timing_profile.fa_plus_window_s = Some(fantastic_window_seconds(fantastic_options));
+    // FA+ is checked before W1, so a W1 narrowed by a timing override must
+    // also cap it or hits outside W1 would still score blue Fantastics.
+    timing_profile.fa_plus_window_s =
+        Some(fantastic_window_seconds(fantastic_options).min(timing_profile.windows_s[0]));
     let profile_music_ns = TimingProfileNs::from_profile_scaled(&timing_profile, music_rate);
     let largest_tap_window_music_ns =
         largest_enabled_tap_window_ns(&profile_music_ns, &disabled_windows)
@@ -230,6 +233,7 @@ pub fn build_player_judgment_timing<Profile: GameplayProfileData>(
     player_profile: &Profile,
     music_rate: f32,
 ) -> PlayerJudgmentTiming {
+    let timing_profile = timing_profile.with_adjust(&player_profile.timing_window_adjust());
     let base_fa_plus_s = timing_profile
         .fa_plus_window_s
         .unwrap_or(timing_profile.windows_s[0]);
@@ -665,6 +669,16 @@ pub fn process_input_edges<Profile, OverlayActor, CapturedActor, StateDelta>(
             } else {
                 state.trigger_receptor_step_pulse(lane_idx);
             }
+            if edge.input_slot != INPUT_SLOT_INVALID {
+                state.control.latency_probe.record(GameplayLatencyProbe {
+                    source: edge.source,
+                    input_slot: edge.input_slot,
+                    captured_at: edge.captured_at,
+                    captured_host_nanos: edge.captured_host_nanos,
+                    judged_at: Instant::now(),
+                    hit_note,
+                });
+            }
         } else if edge_judges_lift {
             let hit_lift = state.judge_a_lift(lane_idx, edge.event_music_time_ns);
             if hit_lift && state.tick_mode() == GameplayTimingTickMode::Hit {
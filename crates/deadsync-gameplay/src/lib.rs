@@ -1,4 +1,4 @@
-use deadsync_chart::song::sync_pref_offset;
+use deadsync_chart::song::pack_sync_shift_seconds;
 use deadsync_chart::{ChartData, ChartDisplayBpm, GameplayChartData, SongData, SyncPref};
 use deadsync_core::input::{InputSource, Lane, LaneMask, MAX_COLS, MAX_PLAYERS};
 use deadsync_core::note::NoteType;
@@ -28,8 +28,9 @@ use deadsync_rules::stream::{
 };
 use deadsync_rules::timing::{
     BeatInfo, BeatInfoCache, FA_PLUS_W0_MS, FA_PLUS_W010_MS, TimingData, TimingProfile,
-    TimingProfileNs, TimingSegments, WindowCounts, classify_offset_ns_with_disabled_windows,
-    combo_multipliers_at_beat, largest_enabled_tap_window_ns,
+    TimingProfileNs, TimingSegments, TimingWindowAdjust, WindowCounts,
+    classify_offset_ns_with_disabled_windows, combo_multipliers_at_beat,
+    largest_enabled_tap_window_ns,
 };
 use std::collections::VecDeque;
 use std::hash::Hasher;
@@ -3,9 +3,6 @@ pub const fn player_life_is_dead(life: f32, is_failing: bool) -> bool {
     is_failing || life <= 0.0
 }
 
-const SURVIVAL_FULL_LIFE_SECONDS: f32 = 90.0;
-const SURVIVAL_MIN_GAIN_SECONDS: f32 = 15.0;
-
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub enum CourseLifeConfig {
     #[default]
@@ -44,11 +41,6 @@ pub enum CourseLifeEvent {
     ForceFail,
 }
 
-#[inline(always)]
-fn survival_life(seconds: f32) -> f32 {
-    (seconds / SURVIVAL_FULL_LIFE_SECONDS).clamp(0.0, 1.0)
-}
-
 pub fn init_course_life(
     config: CourseLifeConfig,
     carry: Option<CourseLifeState>,
@@ -70,27 +62,23 @@ pub fn init_course_life(
                     total_lives,
                     reward_lives,
                 },
-                lives as f32 / total_lives as f32,
+                deadsync_rules::life::battery_life(lives, total_lives),
             )
         }
         CourseLifeConfig::Survival { gain_seconds } => {
             let carried_seconds = match carry {
                 Some(CourseLifeState::Survival {
                     remaining_seconds, ..
-                }) => remaining_seconds.max(0.0),
+                }) => remaining_seconds,
                 _ => 0.0,
             };
-            let start_seconds = carried_seconds
-                + if gain_seconds.is_finite() {
-                    gain_seconds.max(SURVIVAL_MIN_GAIN_SECONDS)
-                } else {
-                    SURVIVAL_MIN_GAIN_SECONDS
-                };
+            let start_seconds =
+                deadsync_rules::life::survival_start_seconds(carried_seconds, gain_seconds);
             (
                 CourseLifeState::Survival {
                     remaining_seconds: start_seconds,
                 },
-                survival_life(start_seconds),
+                deadsync_rules::life::survival_life(start_seconds),
             )
         }
     }
@@ -118,15 +106,12 @@ pub fn course_life_carry(state: CourseLifeState) -> CourseLifeState {
 #[inline(always)]
 const fn battery_lives_lost(event: CourseLifeEvent) -> u32 {
     match event {
-        CourseLifeEvent::Tap(
-            JudgeGrade::Fantastic | JudgeGrade::Excellent | JudgeGrade::Great,
-        )
-        | CourseLifeEvent::CheckpointHit
+        CourseLifeEvent::Tap(grade) => deadsync_rules::life::judge_battery_lives_lost(grade),
+        CourseLifeEvent::CheckpointHit
         | CourseLifeEvent::CheckpointMiss
         | CourseLifeEvent::HoldHeld => 0,
-        CourseLifeEvent::Tap(JudgeGrade::Decent | JudgeGrade::WayOff | JudgeGrade::Miss)
-        | CourseLifeEvent::Mine
-        | CourseLifeEvent::HoldLetGo => 1,
+        CourseLifeEvent::Mine => deadsync_rules::life::BATTERY_HIT_MINE_LIVES_LOST,
+        CourseLifeEvent::HoldLetGo => deadsync_rules::life::BATTERY_LET_GO_LIVES_LOST,
         CourseLifeEvent::ForceFail => u32::MAX,
     }
 }
@@ -134,15 +119,12 @@ const fn battery_lives_lost(event: CourseLifeEvent) -> u32 {
 #[inline(always)]
 const fn survival_seconds_change(event: CourseLifeEvent) -> f32 {
     match event {
-        CourseLifeEvent::Tap(JudgeGrade::Fantastic) => 0.2,
-        CourseLifeEvent::Tap(JudgeGrade::Excellent)
-        | CourseLifeEvent::CheckpointHit
+        CourseLifeEvent::Tap(grade) => deadsync_rules::life::judge_survival_seconds(grade),
+        CourseLifeEvent::CheckpointHit
         | CourseLifeEvent::CheckpointMiss
-        | CourseLifeEvent::HoldHeld => 0.0,
-        CourseLifeEvent::Tap(JudgeGrade::Great) => -0.5,
-        CourseLifeEvent::Tap(JudgeGrade::Decent) => -1.0,
-        CourseLifeEvent::Tap(JudgeGrade::WayOff) | CourseLifeEvent::Mine => -2.0,
-        CourseLifeEvent::Tap(JudgeGrade::Miss) | CourseLifeEvent::HoldLetGo => -4.0,
+        | CourseLifeEvent::HoldHeld => deadsync_rules::life::SURVIVAL_HELD,
+        CourseLifeEvent::Mine => deadsync_rules::life::SURVIVAL_HIT_MINE,
+        CourseLifeEvent::HoldLetGo => deadsync_rules::life::SURVIVAL_LET_GO,
         CourseLifeEvent::ForceFail => f32::MIN,
     }
 }
@@ -189,10 +171,9 @@ pub fn update_course_life_time(
         return;
     }
     let old_life = player.life;
-    if delta_time.is_finite() {
-        *remaining_seconds = (*remaining_seconds - delta_time.max(0.0)).max(0.0);
-    }
-    player.life = survival_life(*remaining_seconds);
+    *remaining_seconds =
+        deadsync_rules::life::apply_survival_seconds(*remaining_seconds, -delta_time.max(0.0));
+    player.life = deadsync_rules::life::survival_life(*remaining_seconds);
     record_player_life_change(player, current_music_time, old_life);
     fail_course_life(player, current_music_time);
 }
@@ -226,15 +207,16 @@ pub fn apply_course_life_event(
             lives, total_lives, ..
         } => {
             *lives = lives.saturating_sub(battery_lives_lost(event));
-            player.life = *lives as f32 / (*total_lives).max(1) as f32;
+            player.life = deadsync_rules::life::battery_life(*lives, *total_lives);
         }
         CourseLifeState::Survival { remaining_seconds } => {
             let change = survival_seconds_change(event);
             if change == f32::MIN {
                 player.life = 0.0;
             } else {
-                *remaining_seconds = (*remaining_seconds + change).max(0.0);
-                player.life = survival_life(*remaining_seconds);
+                *remaining_seconds =
+                    deadsync_rules::life::apply_survival_seconds(*remaining_seconds, change);
+                player.life = deadsync_rules::life::survival_life(*remaining_seconds);
             }
         }
     }
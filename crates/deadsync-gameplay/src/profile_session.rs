@@ -135,6 +135,7 @@ pub trait GameplayProfileData: Clone {
     fn mini_indicator_options(&self) -> GameplayMiniIndicatorOptions;
     fn target_score(&self) -> GameplayTargetScoreSetting;
     fn timing_disabled_windows(&self) -> [bool; 5];
+    fn timing_window_adjust(&self) -> TimingWindowAdjust;
     fn column_flash_options(&self) -> ColumnFlashOptions;
     fn tap_explosion_options(&self) -> TapExplosionOptions;
     fn fantastic_options(&self, base_fa_plus_s: f32) -> FantasticWindowOptions;
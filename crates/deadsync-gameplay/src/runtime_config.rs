@@ -1,12 +1,10 @@
-﻿#[derive(Clone, Copy, Debug, PartialEq)]
-#[derive(Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
 pub enum GameplayFailType {
     Immediate,
     #[default]
     ImmediateContinue,
 }
 
-
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum HoldToExitKey {
     Start,
@@ -227,6 +225,7 @@ pub struct GameplayConfig {
     pub machine_enable_replays: bool,
     pub center_1player_notefield: bool,
     pub delayed_back: bool,
+    pub song_lua_live_runtime: bool,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -355,6 +354,7 @@ impl Default for GameplayConfig {
             machine_enable_replays: true,
             center_1player_notefield: false,
             delayed_back: true,
+            song_lua_live_runtime: false,
         }
     }
 }
@@ -410,6 +410,7 @@ pub fn init_gameplay_runtime<
     session: GameplaySession,
     config: GameplayConfig,
     pack_sync_pref: SyncPref,
+    pack_sync_bias_ms: f32,
     mini_indicator_data: GameplayMiniIndicatorData,
     noteskin_data: GameplayNoteskinData,
     build_song_lua_runtime: BuildSongLuaRuntime,
@@ -479,11 +480,12 @@ where
         z
     });
 
-    let pack_sync_offset_seconds = if config.machine_pack_ini_offsets {
-        sync_pref_offset(pack_sync_pref, config.machine_default_sync_pref)
-    } else {
-        0.0
-    };
+    let pack_sync_offset_seconds = pack_sync_shift_seconds(
+        pack_sync_pref,
+        config.machine_default_sync_pref,
+        config.machine_pack_ini_offsets,
+        pack_sync_bias_ms,
+    );
     let player_global_offset_shift_seconds: [f32; MAX_PLAYERS] = std::array::from_fn(|player| {
         if !config.machine_allow_per_player_global_offsets || player >= num_players {
             return 0.0;
@@ -1469,6 +1471,7 @@ where
             tick_mode,
             assist_clap: GameplayAssistClapState::new(assist_clap_rows),
             update_trace: GameplayUpdateTraceState::default(),
+            latency_probe: GameplayLatencyProbeState::default(),
         },
         pending_input: GameplayPendingInputState::with_capacity(pending_edges_capacity),
     };
@@ -97,6 +97,7 @@ where
             || (0..self.setup.num_players).any(|player| {
                 !self.mods.attacks.mask_windows[player].is_empty()
                     || !self.mods.attacks.song_lua_ease_windows[player].is_empty()
+                    || !self.mods.attacks.song_lua_live_windows[player].is_empty()
             });
         self.display
             .notefield_motion
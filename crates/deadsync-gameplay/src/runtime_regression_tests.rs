@@ -26,6 +26,7 @@ mod runtime_regression_tests {
         mini_percent: f32,
         density_graph: bool,
         combo_milestones_enabled: bool,
+        timing_window_adjust: TimingWindowAdjust,
     }
 
     impl Default for TestProfile {
@@ -47,6 +48,7 @@ mod runtime_regression_tests {
                 mini_percent: 0.0,
                 density_graph: false,
                 combo_milestones_enabled: true,
+                timing_window_adjust: TimingWindowAdjust::default(),
             }
         }
     }
@@ -120,6 +122,10 @@ mod runtime_regression_tests {
             [false; 5]
         }
 
+        fn timing_window_adjust(&self) -> TimingWindowAdjust {
+            self.timing_window_adjust
+        }
+
         fn column_flash_options(&self) -> ColumnFlashOptions {
             self.column_flash_options
         }
@@ -256,6 +262,7 @@ mod runtime_regression_tests {
                     [(); MAX_PLAYERS],
                     std::array::from_fn(|_| Vec::new()),
                     (),
+                    (),
                     Vec::new(),
                     [false; MAX_PLAYERS],
                     std::array::from_fn(|_| SongLuaNoteHideWindows::default()),
@@ -915,6 +922,7 @@ mod runtime_regression_tests {
             session,
             config,
             SyncPref::Default,
+            0.0,
             GameplayMiniIndicatorData::default(),
             GameplayNoteskinData::default(),
             NoSongLuaRuntime,
@@ -952,6 +960,7 @@ mod runtime_regression_tests {
             GameplaySession::default(),
             GameplayConfig::default(),
             SyncPref::Default,
+            0.0,
             GameplayMiniIndicatorData::default(),
             GameplayNoteskinData::default(),
             NoSongLuaRuntime,
@@ -1020,6 +1029,7 @@ mod runtime_regression_tests {
             },
             GameplayConfig::default(),
             SyncPref::Default,
+            0.0,
             GameplayMiniIndicatorData::default(),
             GameplayNoteskinData::default(),
             NoSongLuaRuntime,
@@ -4083,6 +4093,67 @@ mod runtime_regression_tests {
         );
     }
 
+    #[test]
+    fn score_valid_rejects_custom_timing_windows() {
+        let profile = TestProfile {
+            timing_window_adjust: TimingWindowAdjust {
+                scale: 0.75,
+                ..TimingWindowAdjust::default()
+            },
+            ..TestProfile::default()
+        };
+        let chart = test_chart(ArrowStats::default(), TimingSegments::default(), None);
+
+        assert_eq!(
+            score_invalid_reason_lines_for_chart(
+                &chart,
+                &profile,
+                ScrollSpeedSetting::default(),
+                1.0
+            ),
+            vec!["custom timing windows are enabled"]
+        );
+    }
+
+    #[test]
+    fn player_judgment_timing_applies_custom_timing_windows() {
+        let profile = TestProfile {
+            timing_window_adjust: TimingWindowAdjust {
+                windows_ms: [Some(15.0), None, None, None, None],
+                ..TimingWindowAdjust::default()
+            },
+            ..TestProfile::default()
+        };
+        let timing =
+            build_player_judgment_timing(TimingProfile::default_itg_with_fa_plus(), &profile, 1.0);
+
+        assert_eq!(timing.profile_music_ns.windows_ns[0], 15_000_000);
+        assert!(timing.profile_music_ns.fa_plus_window_ns.unwrap() <= 15_000_000);
+    }
+
+    #[test]
+    fn narrowed_fantastic_window_caps_fa_plus() {
+        // FA+ is a sub-window of Fantastic: pinning W1 below the 15 ms FA+
+        // window must not let hits outside W1 still count as blue Fantastics.
+        let profile = TestProfile {
+            timing_window_adjust: TimingWindowAdjust {
+                windows_ms: [Some(12.0), None, None, None, None],
+                ..TimingWindowAdjust::default()
+            },
+            ..TestProfile::default()
+        };
+        let timing =
+            build_player_judgment_timing(TimingProfile::default_itg_with_fa_plus(), &profile, 1.0);
+        assert_eq!(timing.profile_music_ns.fa_plus_window_ns, Some(12_000_000));
+
+        let early = note_hit_eval_for_timing(timing, 1_000_000_000, 1_005_000_000).unwrap();
+        assert_eq!(early.grade, JudgeGrade::Fantastic);
+        assert_eq!(early.window, TimingWindow::W0);
+        let outside_w1 = note_hit_eval_for_timing(timing, 1_000_000_000, 1_013_000_000).unwrap();
+        assert_eq!(outside_w1.grade, JudgeGrade::Excellent);
+        assert_eq!(outside_w1.window, TimingWindow::W2);
+    }
+
     #[test]
     fn score_valid_keeps_turn_options_rankable() {
         let profile = TestProfile {
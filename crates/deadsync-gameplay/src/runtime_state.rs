@@ -971,6 +971,7 @@ pub struct GameplayControlRuntimeState {
     pub tick_mode: GameplayTimingTickMode,
     pub assist_clap: GameplayAssistClapState,
     pub update_trace: GameplayUpdateTraceState,
+    pub latency_probe: GameplayLatencyProbeState,
 }
 
 pub struct GameplayRuntimeState<Profile, OverlayActor, CapturedActor, StateDelta> {
@@ -3089,6 +3089,19 @@ where
         self.boundary.commands.drain_audio()
     }
 
+    #[inline(always)]
+    pub fn set_latency_probe_enabled(&mut self, enabled: bool) {
+        self.control.latency_probe.enabled = enabled;
+        if !enabled {
+            self.control.latency_probe.probes.clear();
+        }
+    }
+
+    #[inline(always)]
+    pub fn drain_latency_probes(&mut self) -> std::vec::Drain<'_, GameplayLatencyProbe> {
+        self.control.latency_probe.probes.drain(..)
+    }
+
     #[inline(always)]
     pub fn push_audio_command(&mut self, command: GameplayAudioCommand) {
         self.boundary.commands.push_audio(command);
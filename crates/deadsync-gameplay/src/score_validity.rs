@@ -3,6 +3,7 @@ pub struct ScoreValidityOptions {
     pub chart_effects: ChartAttackEffects,
     pub attack_mode: GameplayAttackMode,
     pub music_rate: f32,
+    pub custom_timing_windows: bool,
 }
 
 impl Default for ScoreValidityOptions {
@@ -11,6 +12,7 @@ impl Default for ScoreValidityOptions {
             chart_effects: ChartAttackEffects::default(),
             attack_mode: GameplayAttackMode::default(),
             music_rate: 1.0,
+            custom_timing_windows: false,
         }
     }
 }
@@ -24,6 +26,9 @@ pub fn score_invalid_reason_lines_for_options(
     if rate < 1.0 {
         reasons.push("music rate is below 1.0x");
     }
+    if options.custom_timing_windows {
+        reasons.push("custom timing windows are enabled");
+    }
 
     let remove_mask = options.chart_effects.remove_mask;
     if (remove_mask & REMOVE_MASK_BIT_NO_HOLDS) != 0 && chart.stats.holds > 0 {
@@ -95,6 +100,7 @@ pub fn score_invalid_reason_lines_for_chart<Profile: GameplayProfileData>(
             chart_effects: profile.chart_effects(),
             attack_mode: profile.attack_mode(),
             music_rate,
+            custom_timing_windows: !profile.timing_window_adjust().is_identity(),
         },
     )
 }
@@ -967,6 +967,7 @@ mod tests {
             foreground_visual_layers: Vec::new(),
             player_actors: [9_u16; MAX_PLAYERS],
             player_events: std::array::from_fn(|_| Vec::new()),
+            song_background: 10_u16,
             song_foreground: 11_u16,
             song_foreground_events: vec![build_song_lua_overlay_message_runtime(5.0, 4)],
             hidden_players: [false; MAX_PLAYERS],
@@ -2334,6 +2335,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn live_song_lua_windows_apply_after_compiled_eases() {
+        let mut eases: [Vec<SongLuaEaseMaskWindow>; MAX_PLAYERS] =
+            std::array::from_fn(|_| Vec::new());
+        eases[0].push(song_lua_ease_mask_window(
+            SongLuaEaseMaskTarget::PlayerRotationZ,
+            0.0,
+            10.0,
+            10.0,
+            0.0,
+            90.0,
+        ));
+        let mut state = GameplayAttackRuntimeState::new(std::array::from_fn(|_| Vec::new()), eases);
+        let base = AttackBaseEffects::default();
+
+        assert_eq!(
+            append_song_lua_live_window(
+                &mut state.song_lua_live_windows[0],
+                SongLuaRuntimeEaseTarget::Player(SongLuaEaseMaskTarget::PlayerRotationZ),
+                30.0,
+            ),
+            SongLuaRuntimeEaseAppend::Appended,
+        );
+        let live = state
+            .refresh_player(0, 5.0, 1.0 / 120.0, base, SongLuaPlayerTransform::default())
+            .expect("live windows keep the player refreshing");
+        assert_near(live.rotation_z, 30.0);
+
+        state.song_lua_live_windows[0].clear();
+        let compiled = state
+            .refresh_player(0, 5.0, 1.0 / 120.0, base, live)
+            .expect("compiled eases still apply");
+        assert_near(compiled.rotation_z, 45.0);
+    }
+
     #[test]
     fn outro_attack_visual_clear_snapshots_active_visual_once() {
         let mut cleared = false;
@@ -2398,6 +2434,7 @@ mod tests {
                 base_mini_percent: 10.0,
                 attack_windows: &attack_windows,
                 song_lua_ease_windows: &lua_windows,
+                song_lua_live_windows: &[],
             },
             ActiveAttackRefreshState {
                 attack_current_appearance: AppearanceEffects::default(),
@@ -2483,6 +2520,7 @@ mod tests {
                 base_mini_percent: 10.0,
                 attack_windows: &indexed.mask_windows[0],
                 song_lua_ease_windows: &indexed.song_lua_ease_windows[0],
+                song_lua_live_windows: &[],
             };
             assert_eq!(
                 refresh_active_attack_player_indexed(input, state, mask_indices, ease_indices),
@@ -2523,6 +2561,7 @@ mod tests {
             base_mini_percent: 0.0,
             attack_windows: &masks,
             song_lua_ease_windows: &eases,
+            song_lua_live_windows: &[],
         };
         let settled = ActiveAttackRefreshState {
             attack_current_appearance: AppearanceEffects::default(),
@@ -2573,6 +2612,7 @@ mod tests {
                 base_mini_percent: 0.0,
                 attack_windows: &[],
                 song_lua_ease_windows: &lua_windows,
+                song_lua_live_windows: &[],
             },
             ActiveAttackRefreshState {
                 attack_current_appearance: AppearanceEffects::default(),
@@ -2666,6 +2706,7 @@ mod tests {
                     base_mini_percent: 20.0,
                     attack_windows: &[],
                     song_lua_ease_windows: &[],
+                    song_lua_live_windows: &[],
                 };
                 assert_eq!(
                     refresh_active_attack_player(input, state),
@@ -5772,6 +5813,27 @@ mod tests {
         assert_eq!(summary.peak_pending_edges, 0);
     }
 
+    #[test]
+    fn gameplay_latency_probe_state_records_only_when_enabled() {
+        let now = Instant::now();
+        let probe = GameplayLatencyProbe {
+            source: InputSource::Gamepad,
+            input_slot: 5,
+            captured_at: now,
+            captured_host_nanos: 1_000,
+            judged_at: now,
+            hit_note: true,
+        };
+        let mut probes = GameplayLatencyProbeState::default();
+
+        probes.record(probe);
+        assert!(probes.probes.is_empty());
+
+        probes.enabled = true;
+        probes.record(probe);
+        assert_eq!(probes.probes, vec![probe]);
+    }
+
     #[test]
     fn gameplay_update_trace_state_collects_capacity_growth() {
         let initial = GameplayCapacityTraceSnapshot {
@@ -18860,6 +18922,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn turn_lane_map_matches_applied_turns() {
+        let mut notes = [
+            test_note_at(NoteType::Tap, None, false, 48, 1.0),
+            test_note_at(NoteType::Tap, None, false, 48, 1.0),
+            test_note_at(NoteType::Tap, None, false, 48, 1.0),
+            test_note_at(NoteType::Tap, None, false, 48, 1.0),
+        ];
+        for (col, note) in notes.iter_mut().enumerate() {
+            note.column = col;
+        }
+        apply_turn_options(
+            &mut notes,
+            [(0, 4), (0, 0)],
+            4,
+            1,
+            [GameplayTurnOption::Shuffle, GameplayTurnOption::None],
+            29,
+        );
+
+        let columns: Vec<usize> = notes.iter().map(|note| note.column).collect();
+        assert_eq!(
+            turn_lane_map(GameplayTurnOption::Shuffle, 4, 29),
+            Some(columns)
+        );
+        assert_eq!(
+            turn_lane_map(GameplayTurnOption::None, 4, 29),
+            Some(vec![0, 1, 2, 3])
+        );
+        assert_eq!(turn_lane_map(GameplayTurnOption::Blender, 4, 29), None);
+    }
+
     #[test]
     fn pump_turn_mappings_match_itgmania() {
         assert_eq!(
@@ -571,6 +571,33 @@ impl GameplayInputLatencyTrace {
     }
 }
 
+/// A live lane press kept for the end-to-end latency report. The shell drains
+/// these every frame and pairs each one with the present that first showed it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameplayLatencyProbe {
+    pub source: InputSource,
+    pub input_slot: u32,
+    pub captured_at: Instant,
+    pub captured_host_nanos: u64,
+    pub judged_at: Instant,
+    pub hit_note: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct GameplayLatencyProbeState {
+    pub enabled: bool,
+    pub probes: Vec<GameplayLatencyProbe>,
+}
+
+impl GameplayLatencyProbeState {
+    #[inline(always)]
+    pub fn record(&mut self, probe: GameplayLatencyProbe) {
+        if self.enabled {
+            self.probes.push(probe);
+        }
+    }
+}
+
 #[inline(always)]
 pub fn gameplay_input_latency_sample(
     captured_at: Instant,
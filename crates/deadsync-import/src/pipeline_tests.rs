@@ -135,6 +135,8 @@ fn library() -> Vec<SongPack> {
         folder_series: String::new(),
         year: 0,
         sync_pref: deadsync_chart::SyncPref::Default,
+        sync_bias_ms: 0.0,
+        sync_bias_written_ms: Vec::new(),
         directory: PathBuf::from("Songs/My Pack"),
         banner_path: None,
         songs: vec![Arc::new(song(
@@ -16,6 +16,8 @@ pub mod hidraw;
 pub mod iohid;
 #[cfg(any(target_os = "macos", test, feature = "bench-support"))]
 mod iohid_filter;
+#[cfg(target_os = "linux")]
+pub mod midi;
 #[cfg(any(target_os = "linux", target_os = "freebsd", test))]
 mod poll_registration;
 #[cfg(any(unix, test, feature = "bench-support"))]
@@ -40,6 +42,9 @@ pub enum PadBackend {
     WindowsWgi,
     #[cfg(target_os = "linux")]
     LinuxEvdev,
+    /// MIDI notes through the ALSA sequencer.
+    #[cfg(target_os = "linux")]
+    LinuxMidi,
     #[cfg(target_os = "freebsd")]
     FreeBsdHidraw,
     #[cfg(target_os = "freebsd")]
@@ -304,6 +309,8 @@ pub enum GpSystemEvent {
         id: PadId,
         vendor_id: Option<u16>,
         product_id: Option<u16>,
+        /// Stable device UUID, shared with the device's input events.
+        uuid: [u8; 16],
         backend: PadBackend,
         /// True when this connection is part of startup enumeration.
         initial: bool,
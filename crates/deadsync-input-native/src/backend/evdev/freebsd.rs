@@ -544,6 +544,7 @@ fn open_dev(
         id,
         vendor_id: spec.vendor_id,
         product_id: spec.product_id,
+        uuid: spec.uuid,
         backend: PadBackend::FreeBsdEvdev,
         initial,
     });
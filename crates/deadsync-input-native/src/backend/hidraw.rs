@@ -557,6 +557,7 @@ fn add_dev_if_new(
         id,
         vendor_id: pending.vendor_id,
         product_id: pending.product_id,
+        uuid: pending.uuid,
         backend: PadBackend::FreeBsdHidraw,
         initial,
     });
@@ -546,6 +546,7 @@ extern "C" fn on_match(
             id,
             vendor_id,
             product_id,
+            uuid,
             backend: PadBackend::MacOsIohid,
             initial: !ctx.startup_complete_sent,
         });
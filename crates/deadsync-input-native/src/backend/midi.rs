@@ -0,0 +1,428 @@
+//! ALSA sequencer MIDI input for drum pads and keyboard controllers.
+//!
+//! Every readable MIDI port is subscribed to one capture port of our own, and
+//! the system announce port reports hotplug. Note-on/off become
+//! `PadEvent::RawButton` with a `PadCode::midi_note` code, so notes bind in
+//! `[Keymaps]` like any other raw button. Events are stamped with the host
+//! clock on receipt.
+
+use super::{BackendHost, GpSystemEvent, PadBackend, PadOrderBackend, uuid_from_bytes};
+use alsa::seq;
+use deadsync_input::{PadCode, PadEvent, PadId};
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::ffi::CString;
+
+const SYSTEM_CLIENT: i32 = 0;
+const SYSTEM_ANNOUNCE_PORT: i32 = 1;
+const MIDI_CHANNELS: usize = 16;
+
+/// Notes currently held on one port, one bit per note for each channel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct HeldNotes([u128; MIDI_CHANNELS]);
+
+impl HeldNotes {
+    /// Applies a note edge and reports the raw button edges it produces.
+    /// Drum modules often repeat note-on without a note-off in between, so a
+    /// second note-on releases the held note first to keep every hit an edge.
+    fn apply(
+        &mut self,
+        channel: u8,
+        note: u8,
+        velocity: Option<u8>,
+        mut edge: impl FnMut(PadCode, f32, bool),
+    ) {
+        let bits = &mut self.0[usize::from(channel) & (MIDI_CHANNELS - 1)];
+        let bit = 1u128 << (note & 0x7F);
+        let code = PadCode::midi_note(channel, note);
+        let held = *bits & bit != 0;
+        match velocity {
+            Some(velocity) => {
+                if held {
+                    edge(code, 0.0, false);
+                }
+                *bits |= bit;
+                edge(code, f32::from(velocity) / 127.0, true);
+            }
+            None => {
+                if held {
+                    *bits &= !bit;
+                    edge(code, 0.0, false);
+                }
+            }
+        }
+    }
+
+    /// Releases every held note, e.g. when the port disappears mid-press.
+    fn release_all(&mut self, mut edge: impl FnMut(PadCode, f32, bool)) {
+        for (channel, bits) in self.0.iter_mut().enumerate() {
+            while *bits != 0 {
+                let note = bits.trailing_zeros() as u8;
+                *bits &= !(1u128 << note);
+                edge(PadCode::midi_note(channel as u8, note), 0.0, false);
+            }
+        }
+    }
+}
+
+/// Note-on with velocity 0 is the running-status spelling of note-off.
+#[inline(always)]
+const fn note_velocity(on: bool, velocity: u8) -> Option<u8> {
+    if on && velocity > 0 {
+        Some(velocity)
+    } else {
+        None
+    }
+}
+
+/// Sequencer client numbers change between boots, so identity comes from the
+/// client and port names instead.
+fn port_uuid(client_name: &str, port_name: &str) -> [u8; 16] {
+    uuid_from_bytes(format!("midi:{client_name}:{port_name}").as_bytes())
+}
+
+fn port_display_name(client_name: &str, port_name: &str) -> String {
+    if port_name.starts_with(client_name) {
+        port_name.to_string()
+    } else {
+        format!("{client_name} ({port_name})")
+    }
+}
+
+struct Port {
+    addr: seq::Addr,
+    id: PadId,
+    uuid: [u8; 16],
+    name: String,
+    held: HeldNotes,
+}
+
+struct Backend {
+    seq: seq::Seq,
+    dest: seq::Addr,
+}
+
+impl Backend {
+    fn open() -> alsa::Result<Self> {
+        let seq = seq::Seq::open(None, Some(alsa::Direction::Capture), false)?;
+        let name = CString::new("deadsync").expect("static name has no NUL");
+        seq.set_client_name(&name)?;
+        let port = seq.create_simple_port(
+            &CString::new("deadsync input").expect("static name has no NUL"),
+            seq::PortCap::WRITE | seq::PortCap::SUBS_WRITE,
+            seq::PortType::MIDI_GENERIC | seq::PortType::APPLICATION,
+        )?;
+        let dest = seq::Addr {
+            client: seq.client_id()?,
+            port,
+        };
+        Ok(Self { seq, dest })
+    }
+
+    fn subscribe(&self, sender: seq::Addr) -> alsa::Result<()> {
+        let subs = seq::PortSubscribe::empty()?;
+        subs.set_sender(sender);
+        subs.set_dest(self.dest);
+        self.seq.subscribe_port(&subs)
+    }
+
+    /// Readable MIDI ports of other clients, skipping the system client.
+    fn input_port(&self, info: &seq::PortInfo) -> Option<(seq::Addr, [u8; 16], String)> {
+        let addr = info.get_addr();
+        if addr.client == SYSTEM_CLIENT || addr.client == self.dest.client {
+            return None;
+        }
+        let caps = info.get_capability();
+        if !caps.contains(seq::PortCap::READ | seq::PortCap::SUBS_READ)
+            || caps.contains(seq::PortCap::NO_EXPORT)
+            || !info.get_type().contains(seq::PortType::MIDI_GENERIC)
+        {
+            return None;
+        }
+        let client = self.seq.get_any_client_info(addr.client).ok()?;
+        let client_name = client.get_name().ok()?;
+        let port_name = info.get_name().ok()?;
+        Some((
+            addr,
+            port_uuid(client_name, port_name),
+            port_display_name(client_name, port_name),
+        ))
+    }
+
+    fn enumerate(&self) -> Vec<(seq::Addr, [u8; 16], String)> {
+        let mut ports = Vec::new();
+        for client in seq::ClientIter::new(&self.seq) {
+            for info in seq::PortIter::new(&self.seq, client.get_client()) {
+                ports.extend(self.input_port(&info));
+            }
+        }
+        ports
+    }
+}
+
+fn add_port(
+    backend: &Backend,
+    ports: &mut Vec<Port>,
+    id_by_uuid: &mut HashMap<[u8; 16], PadId>,
+    (addr, uuid, name): (seq::Addr, [u8; 16], String),
+    initial: bool,
+    emit_sys: &mut impl FnMut(GpSystemEvent),
+    host: BackendHost,
+) {
+    if ports.iter().any(|port| port.addr == addr) {
+        return;
+    }
+    if let Err(err) = backend.subscribe(addr) {
+        warn!("linux midi: failed to subscribe to {name} ({err})");
+        return;
+    }
+    // MIDI ports share the evdev order so their ids never collide with the
+    // evdev pads running alongside them.
+    let id = *id_by_uuid
+        .entry(uuid)
+        .or_insert_with(|| host.pad_id_for_uuid(PadOrderBackend::LinuxEvdev, uuid));
+    info!(
+        "linux midi: opened {name} at {}:{} as pad {}",
+        addr.client,
+        addr.port,
+        usize::from(id)
+    );
+    emit_sys(GpSystemEvent::Connected {
+        name: name.clone(),
+        id,
+        vendor_id: None,
+        product_id: None,
+        uuid,
+        backend: PadBackend::LinuxMidi,
+        initial,
+    });
+    ports.push(Port {
+        addr,
+        id,
+        uuid,
+        name,
+        held: HeldNotes::default(),
+    });
+}
+
+fn remove_ports(
+    ports: &mut Vec<Port>,
+    gone: impl Fn(seq::Addr) -> bool,
+    emit_pad: &mut impl FnMut(PadEvent),
+    emit_sys: &mut impl FnMut(GpSystemEvent),
+    host: BackendHost,
+) {
+    for idx in (0..ports.len()).rev() {
+        if !gone(ports[idx].addr) {
+            continue;
+        }
+        let mut port = ports.swap_remove(idx);
+        let (timestamp, host_nanos) = host.sample_time();
+        port.held.release_all(|code, value, pressed| {
+            emit_pad(PadEvent::RawButton {
+                id: port.id,
+                timestamp,
+                host_nanos,
+                code,
+                uuid: port.uuid,
+                value,
+                pressed,
+            });
+        });
+        emit_sys(GpSystemEvent::Disconnected {
+            name: port.name,
+            id: port.id,
+            backend: PadBackend::LinuxMidi,
+            initial: false,
+        });
+    }
+}
+
+/// Run the MIDI backend on the current thread. Returns quietly when the
+/// machine has no ALSA sequencer.
+pub fn run(
+    mut emit_pad: impl FnMut(PadEvent),
+    mut emit_sys: impl FnMut(GpSystemEvent),
+    host: BackendHost,
+) {
+    let backend = match Backend::open() {
+        Ok(backend) => backend,
+        Err(err) => {
+            debug!("linux midi: ALSA sequencer unavailable ({err})");
+            return;
+        }
+    };
+    if let Err(err) = backend.subscribe(seq::Addr {
+        client: SYSTEM_CLIENT,
+        port: SYSTEM_ANNOUNCE_PORT,
+    }) {
+        warn!("linux midi: hotplug unavailable ({err})");
+    }
+    let mut ports: Vec<Port> = Vec::new();
+    let mut id_by_uuid: HashMap<[u8; 16], PadId> = HashMap::new();
+    for spec in backend.enumerate() {
+        add_port(
+            &backend,
+            &mut ports,
+            &mut id_by_uuid,
+            spec,
+            true,
+            &mut emit_sys,
+            host,
+        );
+    }
+
+    let mut input = backend.seq.input();
+    loop {
+        let event = match input.event_input() {
+            Ok(event) => event,
+            // The kernel queue overran while we were descheduled; the dropped
+            // events are gone, so carry on with the next one.
+            Err(err) if err.errno() == libc::ENOSPC => continue,
+            Err(err) => {
+                warn!("linux midi: sequencer read failed ({err}); stopping MIDI input");
+                return;
+            }
+        };
+        let kind = event.get_type();
+        let source = event.get_source();
+        match kind {
+            seq::EventType::Noteon | seq::EventType::Noteoff => {
+                let Some(note) = event.get_data::<seq::EvNote>() else {
+                    continue;
+                };
+                let Some(port) = ports.iter_mut().find(|port| port.addr == source) else {
+                    continue;
+                };
+                let (timestamp, host_nanos) = host.sample_time();
+                let (id, uuid) = (port.id, port.uuid);
+                port.held.apply(
+                    note.channel,
+                    note.note,
+                    note_velocity(kind == seq::EventType::Noteon, note.velocity),
+                    |code, value, pressed| {
+                        emit_pad(PadEvent::RawButton {
+                            id,
+                            timestamp,
+                            host_nanos,
+                            code,
+                            uuid,
+                            value,
+                            pressed,
+                        });
+                    },
+                );
+            }
+            seq::EventType::PortStart => {
+                let Some(addr) = event.get_data::<seq::Addr>() else {
+                    continue;
+                };
+                let Some(spec) = backend
+                    .seq
+                    .get_any_port_info(addr)
+                    .ok()
+                    .and_then(|info| backend.input_port(&info))
+                else {
+                    continue;
+                };
+                add_port(
+                    &backend,
+                    &mut ports,
+                    &mut id_by_uuid,
+                    spec,
+                    false,
+                    &mut emit_sys,
+                    host,
+                );
+            }
+            seq::EventType::PortExit => {
+                let Some(addr) = event.get_data::<seq::Addr>() else {
+                    continue;
+                };
+                remove_ports(
+                    &mut ports,
+                    |port| port == addr,
+                    &mut emit_pad,
+                    &mut emit_sys,
+                    host,
+                );
+            }
+            seq::EventType::ClientExit => {
+                let Some(addr) = event.get_data::<seq::Addr>() else {
+                    continue;
+                };
+                remove_ports(
+                    &mut ports,
+                    |port| port.client == addr.client,
+                    &mut emit_pad,
+                    &mut emit_sys,
+                    host,
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(
+        held: &mut HeldNotes,
+        channel: u8,
+        note: u8,
+        velocity: Option<u8>,
+    ) -> Vec<(u32, bool)> {
+        let mut out = Vec::new();
+        held.apply(channel, note, velocity, |code, _, pressed| {
+            out.push((code.into_u32(), pressed));
+        });
+        out
+    }
+
+    #[test]
+    fn notes_become_raw_button_edges() {
+        let mut held = HeldNotes::default();
+        let snare = PadCode::midi_note(9, 38).into_u32();
+        assert_eq!(edges(&mut held, 9, 38, Some(100)), [(snare, true)]);
+        assert_eq!(
+            edges(&mut held, 9, 38, Some(90)),
+            [(snare, false), (snare, true)],
+            "a repeated hit re-triggers"
+        );
+        assert_eq!(edges(&mut held, 9, 38, None), [(snare, false)]);
+        assert!(edges(&mut held, 9, 38, None).is_empty());
+        assert_eq!(note_velocity(true, 0), None);
+        assert_eq!(note_velocity(false, 64), None);
+        assert_eq!(note_velocity(true, 64), Some(64));
+    }
+
+    #[test]
+    fn release_all_lifts_every_held_note() {
+        let mut held = HeldNotes::default();
+        edges(&mut held, 0, 60, Some(1));
+        edges(&mut held, 15, 127, Some(1));
+        let mut released = Vec::new();
+        held.release_all(|code, _, pressed| released.push((code.as_midi_note(), pressed)));
+        assert_eq!(released, [(Some((0, 60)), false), (Some((15, 127)), false)]);
+        assert_eq!(held, HeldNotes::default());
+    }
+
+    #[test]
+    fn port_identity_ignores_client_numbers() {
+        assert_eq!(
+            port_uuid("Alesis Nitro", "Alesis Nitro MIDI 1"),
+            port_uuid("Alesis Nitro", "Alesis Nitro MIDI 1")
+        );
+        assert_ne!(
+            port_uuid("Alesis Nitro", "MIDI 1"),
+            port_uuid("Roland TD-1", "MIDI 1")
+        );
+        assert_eq!(
+            port_display_name("Alesis Nitro", "Alesis Nitro MIDI 1"),
+            "Alesis Nitro MIDI 1"
+        );
+        assert_eq!(port_display_name("nanoPAD2", "MIDI 1"), "nanoPAD2 (MIDI 1)");
+    }
+}
@@ -102,6 +102,7 @@ impl Ctx {
             id: dev.id,
             vendor_id: dev.vendor_id,
             product_id: dev.product_id,
+            uuid: dev.uuid,
             backend: PadBackend::WindowsRawInput,
             initial,
         });
@@ -623,6 +623,7 @@ fn add_controller(ctx: &mut Ctx, controller: RawGameController) {
         id,
         vendor_id,
         product_id,
+        uuid,
         backend: PadBackend::WindowsWgi,
         initial,
     });
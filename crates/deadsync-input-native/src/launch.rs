@@ -67,6 +67,17 @@ pub fn run_linux_backend(
     crate::backend::evdev::run(emit_pad, emit_sys, emit_key, host);
 }
 
+/// Run the ALSA sequencer MIDI backend on the current thread. It runs beside
+/// the evdev backend and returns early when no sequencer is available.
+#[cfg(target_os = "linux")]
+pub fn run_linux_midi_backend(
+    emit_pad: impl FnMut(PadEvent) + Send + 'static,
+    emit_sys: impl FnMut(GpSystemEvent) + Send + 'static,
+    host: BackendHost,
+) {
+    crate::backend::midi::run(emit_pad, emit_sys, host);
+}
+
 #[cfg(target_os = "freebsd")]
 pub fn run_freebsd_backend(
     emit_pad: impl FnMut(PadEvent) + Send + 'static,
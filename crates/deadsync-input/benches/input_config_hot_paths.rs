@@ -266,6 +266,16 @@ fn binding_checksum(binding: InputBinding) -> u64 {
             );
             0x3000 ^ uuid
         }
+        InputBinding::PadAxis(binding) => {
+            let uuid = binding.uuid.unwrap_or_default().into_iter().fold(
+                binding.code_u32 as u64
+                    ^ (binding.device.unwrap_or_default() as u64).rotate_left(11)
+                    ^ (binding.press as u64).rotate_left(23)
+                    ^ (binding.release as u64).rotate_left(37),
+                |sum, byte| sum.rotate_left(3) ^ u64::from(byte),
+            );
+            0x4000 ^ (binding.direction as u64) ^ uuid
+        }
     }
 }
 
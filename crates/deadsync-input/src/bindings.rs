@@ -3,8 +3,9 @@ use std::fmt::Write as _;
 use winit::keyboard::KeyCode;
 
 use crate::{
-    ALL_VIRTUAL_ACTIONS, InputBinding, Keymap, VirtualAction, gamepad_code_binding_to_token,
-    parse_gamepad_code_binding, parse_pad_dir,
+    ALL_VIRTUAL_ACTIONS, AxisDirection, GamepadAxisBinding, InputBinding, Keymap, VirtualAction,
+    gamepad_code_binding_to_token, parse_gamepad_code_binding, parse_pad_binding_filters,
+    parse_pad_code_number, parse_pad_dir,
 };
 
 pub fn default_keymap() -> Keymap {
@@ -268,9 +269,36 @@ pub fn binding_to_token(binding: InputBinding) -> String {
             format!("Pad{device}::Dir::{dir:?}")
         }
         InputBinding::GamepadCode(binding) => gamepad_code_binding_to_token(binding),
+        InputBinding::PadAxis(binding) => pad_axis_binding_to_token(binding),
     }
 }
 
+/// Writes `PadAxis[0x<code>]+` or `-`, then the optional `@<device>` and
+/// `#<uuid>` filters, then `~<press>/<release>` when the thresholds differ
+/// from [`GamepadAxisBinding::default_thresholds`].
+pub fn pad_axis_binding_to_token(binding: GamepadAxisBinding) -> String {
+    let mut s = String::with_capacity(64);
+    let sign = match binding.direction {
+        AxisDirection::Positive => '+',
+        AxisDirection::Negative => '-',
+    };
+    let _ = write!(&mut s, "PadAxis[0x{:08X}]{sign}", binding.code_u32);
+    if let Some(device) = binding.device {
+        let _ = write!(&mut s, "@{device}");
+    }
+    if let Some(uuid) = binding.uuid {
+        s.push('#');
+        for b in &uuid {
+            let _ = write!(&mut s, "{b:02X}");
+        }
+    }
+    if (binding.press, binding.release) != GamepadAxisBinding::default_thresholds(binding.direction)
+    {
+        let _ = write!(&mut s, "~{}/{}", binding.press, binding.release);
+    }
+    s
+}
+
 #[inline(always)]
 pub fn parse_keycode(t: &str) -> Option<InputBinding> {
     let name = t.strip_prefix("KeyCode::")?;
@@ -503,6 +531,39 @@ pub fn parse_pad_code(t: &str) -> Option<InputBinding> {
     parse_gamepad_code_binding(t).map(InputBinding::GamepadCode)
 }
 
+/// Parses a `PadAxis[code]+` / `PadAxis[code]-` token. Thresholds must put
+/// `press` beyond `release` on the bound side; anything else is rejected so a
+/// typo cannot produce a binding that never releases.
+pub fn parse_pad_axis(t: &str) -> Option<InputBinding> {
+    let rest = t.strip_prefix("PadAxis[")?;
+    let end = rest.find(']')?;
+    let code_u32 = parse_pad_code_number(&rest[..end])?;
+    let tail = &rest[end + 1..];
+    let direction = match tail.as_bytes().first()? {
+        b'+' => AxisDirection::Positive,
+        b'-' => AxisDirection::Negative,
+        _ => return None,
+    };
+    let (device, uuid, tail) = parse_pad_binding_filters(&tail[1..]);
+    let (press, release) = if tail.is_empty() {
+        GamepadAxisBinding::default_thresholds(direction)
+    } else {
+        let (press, release) = tail.strip_prefix('~')?.split_once('/')?;
+        (press.parse().ok()?, release.parse().ok()?)
+    };
+    let binding = GamepadAxisBinding {
+        code_u32,
+        direction,
+        press,
+        release,
+        device,
+        uuid,
+    };
+    binding
+        .thresholds_valid()
+        .then_some(InputBinding::PadAxis(binding))
+}
+
 #[inline(always)]
 pub fn parse_pad_device_binding(t: &str) -> Option<InputBinding> {
     let mut parts = t.split("::");
@@ -537,6 +598,7 @@ pub fn parse_binding_token(tok: &str) -> Option<InputBinding> {
     let t = tok.trim();
     parse_keycode(t)
         .or_else(|| parse_pad_code(t))
+        .or_else(|| parse_pad_axis(t))
         .or_else(|| parse_pad_dir_binding(t))
 }
 
@@ -1400,6 +1462,91 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_pad_axis_defaults_and_thresholds() {
+        let axis = |direction, press, release| {
+            Some(InputBinding::PadAxis(GamepadAxisBinding {
+                code_u32: 0x10,
+                direction,
+                press,
+                release,
+                device: None,
+                uuid: None,
+            }))
+        };
+        assert_eq!(
+            parse_binding_token("PadAxis[0x10]+"),
+            axis(AxisDirection::Positive, 1, 0)
+        );
+        assert_eq!(
+            parse_binding_token("PadAxis[16]-"),
+            axis(AxisDirection::Negative, -1, 0)
+        );
+        assert_eq!(
+            parse_binding_token("PadAxis[0x10]+~200/120"),
+            axis(AxisDirection::Positive, 200, 120)
+        );
+        assert_eq!(
+            parse_binding_token("PadAxis[0x10]-~-200/-120"),
+            axis(AxisDirection::Negative, -200, -120)
+        );
+    }
+
+    #[test]
+    fn parse_pad_axis_rejects_invalid() {
+        assert_eq!(parse_pad_axis("PadAxis[0x10]"), None);
+        assert_eq!(parse_pad_axis("PadAxis[0x10]*"), None);
+        assert_eq!(parse_pad_axis("PadAxis[xyz]+"), None);
+        assert_eq!(parse_pad_axis("PadAxis[0x10]+~120/200"), None);
+        assert_eq!(parse_pad_axis("PadAxis[0x10]-~200/120"), None);
+        assert_eq!(parse_pad_axis("PadAxis[0x10]+~200"), None);
+        assert_eq!(parse_pad_axis("PadAxis[0x10]+junk"), None);
+    }
+
+    #[test]
+    fn round_trip_pad_axis() {
+        let cases = [
+            GamepadAxisBinding {
+                code_u32: 0x0003_0010,
+                direction: AxisDirection::Positive,
+                press: 1,
+                release: 0,
+                device: None,
+                uuid: None,
+            },
+            GamepadAxisBinding {
+                code_u32: 2,
+                direction: AxisDirection::Negative,
+                press: -20_000,
+                release: -12_000,
+                device: Some(1),
+                uuid: None,
+            },
+            GamepadAxisBinding {
+                code_u32: 0xFF,
+                direction: AxisDirection::Positive,
+                press: 180,
+                release: 150,
+                device: Some(3),
+                uuid: Some([0xAB; 16]),
+            },
+        ];
+        for binding in cases {
+            let input = InputBinding::PadAxis(binding);
+            let token = binding_to_token(input);
+            assert_eq!(
+                parse_binding_token(&token),
+                Some(input),
+                "round-trip failed for {binding:?}: token was {token:?}"
+            );
+        }
+        assert_eq!(
+            pad_axis_binding_to_token(cases[0]),
+            "PadAxis[0x00030010]+",
+            "default thresholds are not written"
+        );
+    }
+
     #[test]
     fn gamepad_binding_token_round_trips() {
         let binding = InputBinding::GamepadCode(GamepadCodeBinding {
@@ -19,10 +19,12 @@ use crate::debounce::{
     DebounceEdges, DebounceStore, DebounceWindows, DebouncedEdge, debounce_input_edge_in_store_mut,
     emit_due_debounce_edges_from_mut,
 };
+use crate::player_bindings::{PlayerBindings, with_player_input_state};
 use crate::{
-    GamepadCodeBinding, InputEvent, PAD_ID_COUNT_CAP, PadCode, PadDir, PadEvent, PadId,
-    RawKeyboardEvent, SYSTEM_ACTION_MASK, VirtualAction, clamp_input_debounce_seconds,
-    emit_normalized_actions,
+    AxisDirection, GamepadAxisBinding, GamepadCodeBinding, InputEvent, PAD_ID_COUNT_CAP,
+    PLAYER_COUNT, PadCode, PadDir, PadEvent, PadId, RawKeyboardEvent, SYSTEM_ACTION_MASK,
+    VirtualAction, actions_on_player, clamp_input_debounce_seconds, emit_normalized_actions,
+    player_action_mask,
 };
 use deadsync_core::input::InputSource;
 
@@ -32,6 +34,7 @@ pub enum InputBinding {
     PadDir(PadDir),
     PadDirOn { device: usize, dir: PadDir },
     GamepadCode(GamepadCodeBinding),
+    PadAxis(GamepadAxisBinding),
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -41,6 +44,27 @@ struct PadCodeRev {
     uuid: Option<[u8; 16]>,
 }
 
+/// Axis bindings share hysteresis state when their code, side and thresholds
+/// match, so reverse maps key them by the binding with its filters cleared.
+#[inline(always)]
+const fn pad_axis_key(binding: GamepadAxisBinding) -> GamepadAxisBinding {
+    GamepadAxisBinding {
+        device: None,
+        uuid: None,
+        ..binding
+    }
+}
+
+#[inline(always)]
+fn pad_axis_sort_key(binding: &GamepadAxisBinding) -> (u32, bool, i32, i32) {
+    (
+        binding.code_u32,
+        binding.direction == AxisDirection::Negative,
+        binding.press,
+        binding.release,
+    )
+}
+
 const KEY_CODE_CAP: usize = KeyCode::F35 as usize + 1;
 
 #[inline(always)]
@@ -183,6 +207,10 @@ struct CompiledKeymap {
     pad_dir_on_extra: FxHashMap<(usize, PadDir), u32>,
     pad_code_rev: Box<[(u32, CompiledPadCodeMap)]>,
     pad_code_lookup: [u16; PAD_CODE_LOOKUP_CAP],
+    // Sorted by code; each entry owns one debounce slot per device.
+    pad_axis_rev: Box<[(GamepadAxisBinding, CompiledPadCodeMap)]>,
+    // Native pad index claimed by each side for the session.
+    pad_claims: [Option<usize>; PLAYER_COUNT],
     key_slot_count: usize,
     pad_stride: usize,
     pad_slot_count: usize,
@@ -199,6 +227,8 @@ impl Default for CompiledKeymap {
             pad_dir_on_extra: FxHashMap::default(),
             pad_code_rev: Box::new([]),
             pad_code_lookup: [UNMAPPED_PAD_CODE_INDEX; PAD_CODE_LOOKUP_CAP],
+            pad_axis_rev: Box::new([]),
+            pad_claims: [None; PLAYER_COUNT],
             key_slot_count: 0,
             pad_stride: 4,
             pad_slot_count: 0,
@@ -311,11 +341,27 @@ impl CompiledKeymap {
                 _ => pad_code_lookup[projected] = COLLIDING_PAD_CODE_INDEX,
             }
         }
+        let mut pad_axis_rev = Vec::with_capacity(km.pad_axis_rev.len());
+        for (&key, entries) in &km.pad_axis_rev {
+            let compiled = compile_pad_code_map(entries, next_pad_button_slot);
+            for entry in entries {
+                if !entry.act.is_system()
+                    && let Some(device) = entry.device
+                {
+                    max_pad_device = Some(max_pad_device.map_or(device, |max| max.max(device)));
+                }
+            }
+            pad_axis_rev.push((key, compiled));
+            next_pad_button_slot = next_pad_button_slot.saturating_add(1);
+        }
+        pad_axis_rev.sort_unstable_by_key(|(key, _)| pad_axis_sort_key(key));
+        let pad_axis_rev = pad_axis_rev.into_boxed_slice();
         let pad_stride = 4 + next_pad_button_slot as usize;
         let has_pad_bindings = pad_dir_rev.iter().any(|&mask| mask != 0)
             || pad_dir_on_rev.iter().any(|&mask| mask != 0)
             || !pad_dir_on_extra.is_empty()
-            || !pad_code_rev.is_empty();
+            || !pad_code_rev.is_empty()
+            || !pad_axis_rev.is_empty();
         let pad_slot_count = if has_pad_bindings {
             pad_stride.saturating_mul(max_pad_device.map_or(1, |max| max.saturating_add(1)))
         } else {
@@ -336,6 +382,8 @@ impl CompiledKeymap {
             pad_dir_on_extra,
             pad_code_rev,
             pad_code_lookup,
+            pad_axis_rev,
+            pad_claims: [None; PLAYER_COUNT],
             key_slot_count: next_key_slot as usize,
             pad_stride,
             pad_slot_count,
@@ -352,6 +400,7 @@ pub struct Keymap {
     pad_dir_rev: [Vec<VirtualAction>; 4],
     pad_dir_on_rev: HashMap<(usize, PadDir), Vec<VirtualAction>>,
     pad_code_rev: HashMap<u32, Vec<PadCodeRev>>,
+    pad_axis_rev: HashMap<GamepadAxisBinding, Vec<PadCodeRev>>,
 }
 
 impl Default for Keymap {
@@ -363,12 +412,16 @@ impl Default for Keymap {
             pad_dir_rev: std::array::from_fn(|_| Vec::new()),
             pad_dir_on_rev: HashMap::new(),
             pad_code_rev: HashMap::new(),
+            pad_axis_rev: HashMap::new(),
         }
     }
 }
 
 static KEYMAP: std::sync::LazyLock<RwLock<Keymap>> =
     std::sync::LazyLock::new(|| RwLock::new(Keymap::default()));
+// The machine keymap with joined players' overlays folded in.
+static ACTIVE_KEYMAP: std::sync::LazyLock<RwLock<Keymap>> =
+    std::sync::LazyLock::new(|| RwLock::new(Keymap::default()));
 static COMPILED_KEYMAP: std::sync::LazyLock<RwLock<Arc<CompiledKeymap>>> =
     std::sync::LazyLock::new(|| RwLock::new(Arc::new(CompiledKeymap::default())));
 static COMPILED_KEYMAP_GEN: AtomicU64 = AtomicU64::new(1);
@@ -380,6 +433,8 @@ struct ThreadInputState {
     compiled: Option<Arc<CompiledKeymap>>,
     keyboard: DebounceStore,
     pad: DebounceStore,
+    // Hysteresis state of axis bindings, indexed by pad debounce slot.
+    axis_held: Vec<bool>,
 }
 
 thread_local! {
@@ -395,6 +450,8 @@ fn reset_debounce_state(key_slot_count: usize, pad_slot_capacity: usize) {
         let mut state = state.borrow_mut();
         state.keyboard.prepare_slots(key_slot_count);
         state.pad.prepare_slots(pad_slot_capacity);
+        state.axis_held.clear();
+        state.axis_held.resize(pad_slot_capacity, false);
     });
 }
 
@@ -406,11 +463,19 @@ fn refresh_compiled_keymap(state: &mut ThreadInputState, generation: u64) {
     }
 }
 
+/// The machine keymap as configured in `deadsync.ini`.
 #[inline(always)]
 pub fn with_keymap<R>(f: impl FnOnce(&Keymap) -> R) -> R {
     f(&KEYMAP.read().unwrap())
 }
 
+/// The keymap input is currently mapped through, including any per-player
+/// overlays. Use this for runtime checks; edit and save [`with_keymap`].
+#[inline(always)]
+pub fn with_active_keymap<R>(f: impl FnOnce(&Keymap) -> R) -> R {
+    f(&ACTIVE_KEYMAP.read().unwrap())
+}
+
 #[inline(always)]
 pub fn get_keymap() -> Keymap {
     KEYMAP.read().unwrap().clone()
@@ -418,10 +483,28 @@ pub fn get_keymap() -> Keymap {
 
 #[inline(always)]
 pub fn set_keymap(new_map: Keymap) {
-    let compiled = Arc::new(CompiledKeymap::from_keymap(&new_map));
+    *KEYMAP.write().unwrap() = new_map;
+    publish_active_keymap();
+}
+
+/// Rebuilds the active keymap from the machine keymap and player overlays.
+pub(crate) fn publish_active_keymap() {
+    let mut active = KEYMAP.read().unwrap().clone();
+    let pad_claims = with_player_input_state(|players| {
+        let pad_claims = players.live_claims().map(|pad| pad.map(usize::from));
+        for (player, bindings) in players.bindings.iter().enumerate() {
+            if let Some(bindings) = bindings {
+                active.apply_player_bindings(player, bindings, pad_claims[player]);
+            }
+        }
+        pad_claims
+    });
+    let mut compiled = CompiledKeymap::from_keymap(&active);
+    compiled.pad_claims = pad_claims;
+    let compiled = Arc::new(compiled);
     let key_slot_count = compiled.key_slot_count;
     let pad_slot_capacity = compiled.pad_slot_capacity;
-    *KEYMAP.write().unwrap() = new_map;
+    *ACTIVE_KEYMAP.write().unwrap() = active;
     *COMPILED_KEYMAP.write().unwrap() = Arc::clone(&compiled);
     reset_debounce_state(key_slot_count, pad_slot_capacity);
     let generation = COMPILED_KEYMAP_GEN
@@ -471,7 +554,7 @@ fn with_compiled_keymap<R>(f: impl FnOnce(&CompiledKeymap) -> R) -> R {
 
 #[inline(always)]
 fn player_has_action_set(actions: &[VirtualAction]) -> bool {
-    with_keymap(|km| {
+    with_active_keymap(|km| {
         actions
             .iter()
             .all(|action| km.binding_at(*action, 0).is_some())
@@ -517,6 +600,18 @@ pub fn any_player_has_dedicated_menu_buttons_for_mode(three_key_navigation: bool
     }
 }
 
+/// The pad that produced a gamepad-sourced `input_slot` under the current
+/// keymap, for grouping gameplay edges by device.
+pub fn input_slot_pad_id(input_slot: u32) -> Option<PadId> {
+    if input_slot == crate::INPUT_SLOT_INVALID {
+        return None;
+    }
+    with_compiled_keymap(|km| {
+        let id = (input_slot as usize).checked_div(km.pad_stride)?;
+        Some(PadId(id as u32))
+    })
+}
+
 #[inline(always)]
 pub fn set_input_debounce_seconds(seconds: f32) {
     let clamped = clamp_input_debounce_seconds(seconds);
@@ -585,6 +680,17 @@ impl Keymap {
                         }
                     }
                 }
+                InputBinding::PadAxis(binding) => {
+                    let key = pad_axis_key(binding);
+                    if let Some(v) = self.pad_axis_rev.get_mut(&key) {
+                        v.retain(|e| {
+                            e.act != action || e.device != binding.device || e.uuid != binding.uuid
+                        });
+                        if v.is_empty() {
+                            self.pad_axis_rev.remove(&key);
+                        }
+                    }
+                }
             }
         }
     }
@@ -615,6 +721,15 @@ impl Keymap {
                         device: binding.device,
                         uuid: binding.uuid,
                     }),
+                InputBinding::PadAxis(binding) => self
+                    .pad_axis_rev
+                    .entry(pad_axis_key(binding))
+                    .or_default()
+                    .push(PadCodeRev {
+                        act: action,
+                        device: binding.device,
+                        uuid: binding.uuid,
+                    }),
             }
         }
     }
@@ -628,6 +743,47 @@ impl Keymap {
         self.add_rev(action, inputs);
     }
 
+    /// Rebinds `player`'s side to the overlay's actions and takes the overlay's
+    /// inputs away from every other action. Unfiltered pad bindings are scoped
+    /// to the player's claimed controller when there is one.
+    pub(crate) fn apply_player_bindings(
+        &mut self,
+        player: usize,
+        bindings: &PlayerBindings,
+        claimed: Option<usize>,
+    ) {
+        let overlay: Vec<(VirtualAction, Vec<InputBinding>)> = bindings
+            .iter()
+            .map(|(action, inputs)| {
+                let inputs = inputs
+                    .iter()
+                    .map(|&input| scope_binding_to_device(input, claimed))
+                    .collect();
+                (action.on_player(player), inputs)
+            })
+            .collect();
+        let mut displaced: Vec<(VirtualAction, Vec<InputBinding>)> = Vec::new();
+        for (&action, inputs) in &self.map {
+            if overlay.iter().any(|(bound, _)| *bound == action) {
+                continue;
+            }
+            if inputs
+                .iter()
+                .any(|input| overlay.iter().any(|(_, taken)| taken.contains(input)))
+            {
+                let kept = inputs
+                    .iter()
+                    .copied()
+                    .filter(|input| !overlay.iter().any(|(_, taken)| taken.contains(input)))
+                    .collect();
+                displaced.push((action, kept));
+            }
+        }
+        for (action, inputs) in displaced.into_iter().chain(overlay) {
+            self.bind(action, &inputs);
+        }
+    }
+
     /// Returns the first keyboard key bound to this virtual action, if any.
     /// This reflects the first `KeyCode::...` token listed for the action
     /// in `deadsync.ini` (or the hardcoded default keymap).
@@ -696,26 +852,60 @@ impl Keymap {
                 let Some(entries) = self.pad_code_rev.get(&code.into_u32()) else {
                     return false;
                 };
-                for entry in entries {
-                    if let Some(d_expected) = entry.device
-                        && d_expected != dev
-                    {
-                        continue;
-                    }
-                    if let Some(u_expected) = entry.uuid
-                        && u_expected != uuid
-                    {
-                        continue;
-                    }
-                    return true;
-                }
-                false
+                pad_code_entries_match(entries, dev, uuid)
+            }
+            PadEvent::RawAxis { id, code, uuid, .. } => {
+                let dev = usize::from(id);
+                let code = code.into_u32();
+                self.pad_axis_rev.iter().any(|(key, entries)| {
+                    key.code_u32 == code && pad_code_entries_match(entries, dev, uuid)
+                })
             }
-            PadEvent::RawAxis { .. } => false,
         }
     }
 }
 
+#[inline(always)]
+const fn scope_binding_to_device(binding: InputBinding, device: Option<usize>) -> InputBinding {
+    let Some(device) = device else {
+        return binding;
+    };
+    match binding {
+        InputBinding::PadDir(dir) => InputBinding::PadDirOn { device, dir },
+        InputBinding::GamepadCode(code) if code.device.is_none() && code.uuid.is_none() => {
+            InputBinding::GamepadCode(GamepadCodeBinding {
+                device: Some(device),
+                ..code
+            })
+        }
+        InputBinding::PadAxis(axis) if axis.device.is_none() && axis.uuid.is_none() => {
+            InputBinding::PadAxis(GamepadAxisBinding {
+                device: Some(device),
+                ..axis
+            })
+        }
+        other => other,
+    }
+}
+
+#[inline(always)]
+fn pad_code_entries_match(entries: &[PadCodeRev], dev: usize, uuid: [u8; 16]) -> bool {
+    for entry in entries {
+        if let Some(d_expected) = entry.device
+            && d_expected != dev
+        {
+            continue;
+        }
+        if let Some(u_expected) = entry.uuid
+            && u_expected != uuid
+        {
+            continue;
+        }
+        return true;
+    }
+    false
+}
+
 // INI parsing and default emission moved to config.rs
 
 #[cfg(any(test, feature = "bench-support"))]
@@ -965,6 +1155,34 @@ fn collect_pad_button_binding_from_compiled(
     })
 }
 
+#[inline(always)]
+fn pad_axis_maps(km: &CompiledKeymap, code: u32) -> &[(GamepadAxisBinding, CompiledPadCodeMap)] {
+    let start = km
+        .pad_axis_rev
+        .partition_point(|(key, _)| key.code_u32 < code);
+    let len = km.pad_axis_rev[start..].partition_point(|(key, _)| key.code_u32 == code);
+    &km.pad_axis_rev[start..start + len]
+}
+
+/// Routes a claimed controller to its side. Actions the device already maps
+/// to that side win; otherwise the other side's actions are mirrored over.
+#[inline(always)]
+fn claimed_pad_mask(km: &CompiledKeymap, dev: usize, mask: u32) -> u32 {
+    let mut player = 0;
+    while player < PLAYER_COUNT {
+        if km.pad_claims[player] == Some(dev) {
+            let own = mask & player_action_mask(player);
+            return if own != 0 {
+                (mask & SYSTEM_ACTION_MASK) | own
+            } else {
+                actions_on_player(mask, player)
+            };
+        }
+        player += 1;
+    }
+    mask
+}
+
 #[inline(always)]
 fn pad_slot_base(km: &CompiledKeymap, id: PadId) -> usize {
     usize::from(id).saturating_mul(km.pad_stride)
@@ -1078,7 +1296,16 @@ pub fn map_keycode_event_with_host(
 
 #[inline(always)]
 pub fn map_pad_event_with(ev: &PadEvent, mut emit: impl FnMut(InputEvent)) {
-    if matches!(ev, PadEvent::RawAxis { .. }) {
+    if let PadEvent::RawAxis {
+        id,
+        timestamp,
+        host_nanos,
+        code,
+        uuid,
+        value,
+    } = *ev
+    {
+        map_pad_axis_event_with(id, code, uuid, value, timestamp, host_nanos, emit);
         return;
     }
     let generation = COMPILED_KEYMAP_GEN.load(Ordering::Acquire);
@@ -1103,7 +1330,7 @@ pub fn map_pad_event_with(ev: &PadEvent, mut emit: impl FnMut(InputEvent)) {
                 }
                 (
                     pad_dir_slot_from_compiled(km, id, dir),
-                    mask,
+                    claimed_pad_mask(km, usize::from(id), mask),
                     pressed,
                     timestamp,
                     host_nanos,
@@ -1121,7 +1348,7 @@ pub fn map_pad_event_with(ev: &PadEvent, mut emit: impl FnMut(InputEvent)) {
                 let binding = collect_pad_button_binding_from_compiled(km, id, code, uuid)?;
                 (
                     pad_button_slot_from_compiled(km, id, binding.slot),
-                    binding.mask,
+                    claimed_pad_mask(km, usize::from(id), binding.mask),
                     pressed,
                     timestamp,
                     host_nanos,
@@ -1146,6 +1373,68 @@ pub fn map_pad_event_with(ev: &PadEvent, mut emit: impl FnMut(InputEvent)) {
     emit_debounced_edges(edges, &mut emit);
 }
 
+/// Turns threshold crossings of every axis binding on `code` into presses and
+/// releases on that binding's pad slot, which then debounce like buttons.
+fn map_pad_axis_event_with(
+    id: PadId,
+    code: PadCode,
+    uuid: [u8; 16],
+    value: f32,
+    timestamp: Instant,
+    host_nanos: u64,
+    mut emit: impl FnMut(InputEvent),
+) {
+    let generation = COMPILED_KEYMAP_GEN.load(Ordering::Acquire);
+    let dev = usize::from(id);
+    let code = code.into_u32();
+    // One binding per pass keeps `emit` outside the thread-state borrow
+    // without buffering edges.
+    let mut index = 0;
+    loop {
+        let step = THREAD_INPUT_STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            refresh_compiled_keymap(&mut state, generation);
+            let state = &mut *state;
+            let km = state
+                .compiled
+                .as_deref()
+                .expect("compiled keymap cache initialized");
+            let (binding, code_map) = pad_axis_maps(km, code).get(index)?;
+            let mask = collect_pad_code_mask(code_map, dev, uuid);
+            if mask == 0 {
+                return Some(None);
+            }
+            let mask = claimed_pad_mask(km, dev, mask);
+            let slot = pad_button_slot_from_compiled(km, id, code_map.slot);
+            if slot >= state.axis_held.len() {
+                state.axis_held.resize(slot + 1, false);
+            }
+            let held = state.axis_held[slot];
+            let pressed = binding.held_after(held, value);
+            if pressed == held {
+                return Some(None);
+            }
+            state.axis_held[slot] = pressed;
+            Some(Some(debounce_input_edge_in_store_mut(
+                &mut state.pad,
+                slot,
+                mask,
+                InputSource::Gamepad,
+                pressed,
+                timestamp,
+                host_nanos,
+                debounce_windows(),
+            )))
+        });
+        match step {
+            None => break,
+            Some(Some(edges)) => emit_debounced_edges(edges, &mut emit),
+            Some(None) => {}
+        }
+        index += 1;
+    }
+}
+
 pub fn drain_debounced_input_events_with(mut emit: impl FnMut(InputEvent)) -> bool {
     THREAD_INPUT_STATE.with(|state| {
         let mut state = state.borrow_mut();
@@ -1312,6 +1601,8 @@ mod tests {
         assert_eq!(actual.len(), 2);
         assert_eq!(actual[1].action, VirtualAction::p1_down);
         assert_eq!(actual[1].input_slot, 281);
+        assert_eq!(input_slot_pad_id(9), Some(PadId(2)));
+        assert_eq!(input_slot_pad_id(281), Some(PadId(70)));
     }
 
     #[test]
@@ -1749,6 +2040,221 @@ mod tests {
         assert!(actual.is_empty());
     }
 
+    #[test]
+    fn axis_bindings_cross_thresholds_with_hysteresis() {
+        struct DebounceWindowReset(u32);
+
+        impl Drop for DebounceWindowReset {
+            fn drop(&mut self) {
+                INPUT_DEBOUNCE_NANOS.store(self.0, Ordering::Relaxed);
+            }
+        }
+
+        let _guard = lock_test_guard();
+        let _reset = TestReset::capture();
+        let old_window = INPUT_DEBOUNCE_NANOS.swap(debounce_nanos(0.001), Ordering::Relaxed);
+        let _window_reset = DebounceWindowReset(old_window);
+        let axis = |direction, press, release| GamepadAxisBinding {
+            code_u32: 9,
+            direction,
+            press,
+            release,
+            device: Some(2),
+            uuid: None,
+        };
+        let mut km = Keymap::default();
+        km.bind(
+            VirtualAction::p1_start,
+            &[InputBinding::PadAxis(axis(
+                AxisDirection::Positive,
+                200,
+                100,
+            ))],
+        );
+        km.bind(
+            VirtualAction::p1_back,
+            &[InputBinding::PadAxis(axis(
+                AxisDirection::Negative,
+                -200,
+                -100,
+            ))],
+        );
+        set_keymap(km);
+
+        let event = |id, value| PadEvent::RawAxis {
+            id: PadId(id),
+            timestamp: Instant::now(),
+            host_nanos: 17,
+            code: PadCode(9),
+            uuid: [0x5a; 16],
+            value,
+        };
+        let feed = |id, value| {
+            let mut actual = Vec::new();
+            map_pad_event_with(&event(id, value), |input| {
+                actual.push((input.action, input.pressed));
+            });
+            std::thread::sleep(Duration::from_millis(3));
+            actual
+        };
+
+        assert!(feed(2, 150.0).is_empty(), "below press threshold");
+        assert!(feed(1, 250.0).is_empty(), "other device is filtered out");
+        assert_eq!(feed(2, 250.0), [(VirtualAction::p1_start, true)]);
+        assert!(feed(2, 150.0).is_empty(), "between thresholds stays held");
+        assert_eq!(feed(2, 90.0), [(VirtualAction::p1_start, false)]);
+        assert!(
+            feed(2, 150.0).is_empty(),
+            "between thresholds stays released"
+        );
+        assert_eq!(feed(2, -250.0), [(VirtualAction::p1_back, true)]);
+        assert!(feed(2, -150.0).is_empty());
+        assert_eq!(feed(2, 0.0), [(VirtualAction::p1_back, false)]);
+        assert!(with_keymap(|km| km.pad_event_mapped(&event(2, 0.0))));
+        assert!(!with_keymap(|km| km.pad_event_mapped(&event(1, 0.0))));
+    }
+
+    #[test]
+    fn player_overlays_and_claims_route_to_their_side() {
+        struct PlayerInputReset;
+
+        impl Drop for PlayerInputReset {
+            fn drop(&mut self) {
+                for player in 0..PLAYER_COUNT {
+                    crate::set_player_bindings(player, None);
+                    crate::release_controller_claim(player);
+                }
+            }
+        }
+
+        let _guard = lock_test_guard();
+        let _reset = TestReset::capture();
+        let _players = PlayerInputReset;
+        let mut km = Keymap::default();
+        km.bind(VirtualAction::p1_up, &[InputBinding::PadDir(PadDir::Up)]);
+        km.bind(VirtualAction::p2_up, &[InputBinding::Key(KeyCode::KeyW)]);
+        km.bind(VirtualAction::p2_left, &[InputBinding::Key(KeyCode::KeyA)]);
+        set_keymap(km);
+
+        let mut overlay = crate::PlayerBindings::default();
+        overlay.bind(VirtualAction::p1_left, &[InputBinding::Key(KeyCode::KeyW)]);
+        crate::set_player_bindings(1, Some(overlay));
+        with_active_keymap(|km| {
+            assert_eq!(
+                km.first_key_binding(VirtualAction::p2_left),
+                Some(KeyCode::KeyW)
+            );
+            assert_eq!(km.first_key_binding(VirtualAction::p2_up), None);
+        });
+        assert_eq!(
+            with_keymap(|km| km.first_key_binding(VirtualAction::p2_left)),
+            Some(KeyCode::KeyA),
+            "the machine keymap is never overlaid"
+        );
+
+        let dir = |id, pressed| PadEvent::Dir {
+            id: PadId(id),
+            timestamp: Instant::now(),
+            host_nanos: 3,
+            dir: PadDir::Up,
+            pressed,
+        };
+        let feed = |id, pressed| {
+            let mut actual = Vec::new();
+            map_pad_event_with(&dir(id, pressed), |input| {
+                actual.push((input.action, input.pressed));
+            });
+            actual
+        };
+        crate::claim_controller(
+            1,
+            crate::ControllerClaim {
+                pad: PadId(3),
+                uuid: [3; 16],
+            },
+        );
+        assert_eq!(feed(3, true), [(VirtualAction::p2_up, true)]);
+        assert_eq!(feed(0, true), [(VirtualAction::p1_up, true)]);
+
+        crate::claim_controller(
+            0,
+            crate::ControllerClaim {
+                pad: PadId(3),
+                uuid: [3; 16],
+            },
+        );
+        assert_eq!(crate::controller_claims()[1], None, "a pad has one owner");
+    }
+
+    #[test]
+    fn controller_claims_follow_their_device_across_reconnects() {
+        struct PlayerInputReset;
+
+        impl Drop for PlayerInputReset {
+            fn drop(&mut self) {
+                for player in 0..PLAYER_COUNT {
+                    crate::release_controller_claim(player);
+                }
+            }
+        }
+
+        let _guard = lock_test_guard();
+        let _reset = TestReset::capture();
+        let _players = PlayerInputReset;
+        let mut km = Keymap::default();
+        km.bind(VirtualAction::p1_up, &[InputBinding::PadDir(PadDir::Up)]);
+        set_keymap(km);
+
+        // Each pad is pressed once so debounce never holds an edge back.
+        let press = |id| {
+            let mut actual = Vec::new();
+            let ev = PadEvent::Dir {
+                id: PadId(id),
+                timestamp: Instant::now(),
+                host_nanos: 3,
+                dir: PadDir::Up,
+                pressed: true,
+            };
+            map_pad_event_with(&ev, |input| actual.push(input.action));
+            actual
+        };
+        let claimed = [9; 16];
+        crate::claim_controller(
+            1,
+            crate::ControllerClaim {
+                pad: PadId(2),
+                uuid: claimed,
+            },
+        );
+        crate::controller_disconnected(PadId(2));
+        crate::controller_connected(PadId(2), [4; 16]);
+        assert_eq!(
+            press(2),
+            [VirtualAction::p1_up],
+            "another device reusing the index is not claimed"
+        );
+
+        crate::controller_connected(PadId(5), claimed);
+        assert_eq!(
+            crate::controller_claims()[1].map(|claim| claim.pad),
+            Some(PadId(5))
+        );
+        assert_eq!(press(5), [VirtualAction::p2_up]);
+
+        crate::claim_controller(
+            0,
+            crate::ControllerClaim {
+                pad: PadId(5),
+                uuid: claimed,
+            },
+        );
+        assert_eq!(
+            crate::controller_claims()[1],
+            None,
+            "a device has one owner whatever index it was claimed under"
+        );
+    }
+
     #[test]
     fn map_pad_event_with_ignores_duplicate_raw_button_state() {
         let _guard = lock_test_guard();
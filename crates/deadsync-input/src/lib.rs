@@ -9,6 +9,7 @@ pub mod bindings;
 pub mod debounce;
 pub mod fsr;
 pub mod keymap;
+pub mod player_bindings;
 
 #[cfg(any(test, feature = "bench-support"))]
 #[doc(hidden)]
@@ -16,9 +17,9 @@ pub use bindings::load_keymap_from_ini_entries_reference;
 pub use bindings::{
     DEFAULT_KEYMAP_INI_LINES, binding_to_token, cleared_keymap, default_binding_for_action,
     default_key_for_action, default_keymap, editable_key_binding_slot_indices, keycode_to_token,
-    keymap_ini_lines, load_keymap_from_ini_entries, parse_binding_token, parse_keycode,
-    parse_keycode_to_key, parse_pad_code, parse_pad_device_binding, parse_pad_dir_binding,
-    protected_default_key_for_action, restore_available_default_bindings,
+    keymap_ini_lines, load_keymap_from_ini_entries, pad_axis_binding_to_token, parse_binding_token,
+    parse_keycode, parse_keycode_to_key, parse_pad_axis, parse_pad_code, parse_pad_device_binding,
+    parse_pad_dir_binding, protected_default_key_for_action, restore_available_default_bindings,
     updated_keymap_unique_gamepad, updated_keymap_unique_keyboard,
     write_default_keymap_ini_section, write_keymap_ini_section,
 };
@@ -28,9 +29,14 @@ pub use keymap::PadLookupBench;
 pub use keymap::{
     InputBinding, Keymap, any_player_has_dedicated_menu_buttons_for_mode,
     any_player_has_four_way_menu_buttons, any_player_has_three_key_menu_buttons,
-    clear_debounce_state, drain_debounced_input_events_with, get_keymap, map_keycode_event_with,
-    map_keycode_event_with_host, map_pad_event_with, map_raw_key_event_with,
-    set_input_debounce_seconds, set_keymap, with_keymap,
+    clear_debounce_state, drain_debounced_input_events_with, get_keymap, input_slot_pad_id,
+    map_keycode_event_with, map_keycode_event_with_host, map_pad_event_with,
+    map_raw_key_event_with, set_input_debounce_seconds, set_keymap, with_active_keymap,
+    with_keymap,
+};
+pub use player_bindings::{
+    ControllerClaim, PlayerBindings, claim_controller, controller_claims, controller_connected,
+    controller_disconnected, release_controller_claim, set_player_bindings,
 };
 
 pub const INPUT_SLOT_INVALID: u32 = u32::MAX;
@@ -131,11 +137,30 @@ impl From<PadId> for usize {
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct PadCode(pub u32);
 
+/// Code page of MIDI notes: `0x0090_0CNN` for channel `C` and note `NN`.
+/// `0x90` is the note-on status byte, well clear of evdev and HID codes.
+pub const MIDI_NOTE_CODE_PAGE: u32 = 0x0090_0000;
+
 impl PadCode {
     #[inline(always)]
     pub const fn into_u32(self) -> u32 {
         self.0
     }
+
+    /// Code emitted for `note` (0-127) on MIDI `channel` (0-15).
+    #[inline(always)]
+    pub const fn midi_note(channel: u8, note: u8) -> Self {
+        Self(MIDI_NOTE_CODE_PAGE | ((channel as u32 & 0x0F) << 8) | (note as u32 & 0x7F))
+    }
+
+    /// `(channel, note)` when this code came from a MIDI note.
+    #[inline(always)]
+    pub const fn as_midi_note(self) -> Option<(u8, u8)> {
+        if self.0 & !0x0FFF != MIDI_NOTE_CODE_PAGE || self.0 & 0x80 != 0 {
+            return None;
+        }
+        Some((((self.0 >> 8) & 0x0F) as u8, (self.0 & 0x7F) as u8))
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -212,6 +237,65 @@ pub struct GamepadCodeBinding {
     pub uuid: Option<[u8; 16]>,
 }
 
+/// Which side of rest an axis binding watches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AxisDirection {
+    Positive,
+    Negative,
+}
+
+/// Low-level binding of one side of a raw axis (or hat) to a virtual button.
+///
+/// - `code_u32` is the emitted `PadCode(u32)` from `PadEvent::RawAxis`.
+/// - `press` and `release` are raw axis values in the backend's own units, as
+///   shown on the Sandbox screen. A positive binding presses once the value
+///   reaches `press` and releases once it falls back to `release`; a negative
+///   binding mirrors that below rest. The gap between them is the hysteresis
+///   that keeps a noisy axis from chattering.
+/// - `device` and `uuid` filter like they do for [`GamepadCodeBinding`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GamepadAxisBinding {
+    pub code_u32: u32,
+    pub direction: AxisDirection,
+    pub press: i32,
+    pub release: i32,
+    pub device: Option<usize>,
+    pub uuid: Option<[u8; 16]>,
+}
+
+impl GamepadAxisBinding {
+    /// Thresholds used when a token does not spell them out: any deflection
+    /// from zero presses. This suits hats and axes that rest at zero.
+    #[inline(always)]
+    pub const fn default_thresholds(direction: AxisDirection) -> (i32, i32) {
+        match direction {
+            AxisDirection::Positive => (1, 0),
+            AxisDirection::Negative => (-1, 0),
+        }
+    }
+
+    /// `true` when `press` lies strictly beyond `release` on this binding's side.
+    #[inline(always)]
+    pub const fn thresholds_valid(&self) -> bool {
+        match self.direction {
+            AxisDirection::Positive => self.press > self.release,
+            AxisDirection::Negative => self.press < self.release,
+        }
+    }
+
+    /// Applies the thresholds to a new axis `value`, given whether the binding
+    /// was held before it. Values between the thresholds keep the old state.
+    #[inline(always)]
+    pub fn held_after(&self, held: bool, value: f32) -> bool {
+        match (self.direction, held) {
+            (AxisDirection::Positive, false) => value >= self.press as f32,
+            (AxisDirection::Positive, true) => value > self.release as f32,
+            (AxisDirection::Negative, false) => value <= self.press as f32,
+            (AxisDirection::Negative, true) => value < self.release as f32,
+        }
+    }
+}
+
 pub fn gamepad_code_binding_to_token(binding: GamepadCodeBinding) -> String {
     const BASE_LEN: usize = "PadCode[0x00000000]".len();
     const UUID_LEN: usize = 33;
@@ -264,17 +348,33 @@ pub fn parse_gamepad_code_binding(t: &str) -> Option<GamepadCodeBinding> {
     let rest = t.strip_prefix("PadCode[")?;
     let end = rest.find(']')?;
     let code_str = &rest[..end];
-    let mut tail = &rest[end + 1..];
+    let tail = &rest[end + 1..];
+
+    let code_u32 = parse_pad_code_number(code_str)?;
 
-    let code_u32 = if let Some(hex) = code_str
+    let (device, uuid, _) = parse_pad_binding_filters(tail);
+    Some(GamepadCodeBinding {
+        code_u32,
+        device,
+        uuid,
+    })
+}
+
+/// Parses the bracketed code of a raw pad token as `0x`-prefixed hex or decimal.
+pub(crate) fn parse_pad_code_number(code_str: &str) -> Option<u32> {
+    if let Some(hex) = code_str
         .strip_prefix("0x")
         .or_else(|| code_str.strip_prefix("0X"))
     {
-        u32::from_str_radix(hex, 16).ok()?
+        u32::from_str_radix(hex, 16).ok()
     } else {
-        code_str.parse::<u32>().ok()?
-    };
+        code_str.parse::<u32>().ok()
+    }
+}
 
+/// Parses the optional `@<device>` and `#<uuid>` filters that follow a raw pad
+/// code, returning them with the unparsed remainder of the token.
+pub(crate) fn parse_pad_binding_filters(mut tail: &str) -> (Option<usize>, Option<[u8; 16]>, &str) {
     let mut device = None;
     let mut uuid = None;
     loop {
@@ -313,12 +413,7 @@ pub fn parse_gamepad_code_binding(t: &str) -> Option<GamepadCodeBinding> {
         }
         break;
     }
-
-    Some(GamepadCodeBinding {
-        code_u32,
-        device,
-        uuid,
-    })
+    (device, uuid, tail)
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -437,6 +532,27 @@ impl VirtualAction {
         matches!(self, Self::system_fast_forward | Self::system_slow_down)
     }
 
+    /// Player index (0 for P1, 1 for P2) of a player-scoped action.
+    #[inline(always)]
+    pub const fn player(self) -> Option<usize> {
+        if self.bit() & P1_ACTION_MASK != 0 {
+            Some(0)
+        } else if self.bit() & P2_ACTION_MASK != 0 {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    /// The same action for `player`; system actions are returned unchanged.
+    #[inline(always)]
+    pub const fn on_player(self, player: usize) -> Self {
+        match Self::from_ix(actions_on_player(self.bit(), player).trailing_zeros() as usize) {
+            Some(action) => action,
+            None => self,
+        }
+    }
+
     #[inline(always)]
     pub const fn is_gameplay_arrow(self) -> bool {
         matches!(
@@ -524,6 +640,55 @@ pub const ALL_VIRTUAL_ACTIONS: [VirtualAction; VirtualAction::COUNT] = [
 pub const SYSTEM_ACTION_MASK: u32 =
     VirtualAction::system_fast_forward.bit() | VirtualAction::system_slow_down.bit();
 
+/// Number of player sides an action can belong to.
+pub const PLAYER_COUNT: usize = 2;
+
+const P1_ROW_ACTION_MASK: u32 = (1 << (VirtualAction::p1_restart as u32 + 1)) - 1;
+const P1_ACTION_MASK: u32 = P1_ROW_ACTION_MASK | VirtualAction::p1_center.bit();
+const P2_ACTION_MASK: u32 = (P1_ROW_ACTION_MASK << P2_ROW_SHIFT) | VirtualAction::p2_center.bit();
+const P2_ROW_SHIFT: u32 = VirtualAction::p2_up as u32;
+
+const _: () =
+    assert!(VirtualAction::p2_restart as u32 == VirtualAction::p1_restart as u32 + P2_ROW_SHIFT);
+const _: () = assert!(VirtualAction::p2_center as u32 == VirtualAction::p1_center as u32 + 1);
+
+/// Every player-scoped action bit belonging to `player`.
+#[inline(always)]
+pub const fn player_action_mask(player: usize) -> u32 {
+    if player == 0 {
+        P1_ACTION_MASK
+    } else {
+        P2_ACTION_MASK
+    }
+}
+
+/// Moves every player-scoped action in `mask` onto `player`, keeping system
+/// actions. Used to route a claimed controller to its side.
+#[inline(always)]
+pub const fn actions_on_player(mask: u32, player: usize) -> u32 {
+    let system = mask & !(P1_ACTION_MASK | P2_ACTION_MASK);
+    let rows = (mask & P1_ROW_ACTION_MASK) | ((mask >> P2_ROW_SHIFT) & P1_ROW_ACTION_MASK);
+    let centers = ((mask & VirtualAction::p1_center.bit()) != 0)
+        | ((mask & VirtualAction::p2_center.bit()) != 0);
+    if player == 0 {
+        system
+            | rows
+            | if centers {
+                VirtualAction::p1_center.bit()
+            } else {
+                0
+            }
+    } else {
+        system
+            | (rows << P2_ROW_SHIFT)
+            | if centers {
+                VirtualAction::p2_center.bit()
+            } else {
+                0
+            }
+    }
+}
+
 #[inline(always)]
 pub fn action_from_ini_key_lower(key: &str) -> Option<VirtualAction> {
     use VirtualAction::{
@@ -940,6 +1105,19 @@ mod tests {
         assert_eq!(parse_gamepad_code_binding("NotPadCode[0x01]"), None);
     }
 
+    #[test]
+    fn midi_note_codes_round_trip_and_parse_as_pad_codes() {
+        let code = PadCode::midi_note(9, 38);
+        assert_eq!(code.into_u32(), 0x0090_0926);
+        assert_eq!(code.as_midi_note(), Some((9, 38)));
+        assert_eq!(PadCode(0x0090_0080).as_midi_note(), None);
+        assert_eq!(PadCode(0x0001_0126).as_midi_note(), None);
+        assert_eq!(
+            parse_gamepad_code_binding("PadCode[0x00900926]").map(|b| b.code_u32),
+            Some(code.into_u32())
+        );
+    }
+
     #[test]
     fn gamepad_code_bindings_round_trip_config_tokens() {
         let cases = [
@@ -0,0 +1,328 @@
+//! Per-player binding overlays and session controller claims.
+//!
+//! The machine keymap stays the single source of truth for `deadsync.ini`.
+//! A joined player may layer their own bindings on top of it and claim one
+//! controller for their side; both are folded into the active keymap that
+//! drives input mapping, never into the saved base keymap.
+
+use std::fmt::Write as _;
+use std::sync::{LazyLock, RwLock};
+
+use crate::bindings::{binding_to_token, parse_binding_token};
+use crate::keymap::{InputBinding, publish_active_keymap};
+use crate::{PLAYER_COUNT, PadId, VirtualAction};
+
+/// Side-neutral binding overlay, stored against P1 actions and remapped onto
+/// whichever side the owning player joins.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlayerBindings {
+    actions: Vec<(VirtualAction, Vec<InputBinding>)>,
+}
+
+impl PlayerBindings {
+    /// Replaces the overlay for `action`. System actions stay machine-wide and
+    /// are ignored; an empty `inputs` list removes the action from the overlay.
+    pub fn bind(&mut self, action: VirtualAction, inputs: &[InputBinding]) {
+        if action.player().is_none() {
+            return;
+        }
+        let action = action.on_player(0);
+        self.actions.retain(|(bound, _)| *bound != action);
+        if !inputs.is_empty() {
+            self.actions.push((action, inputs.to_vec()));
+            self.actions.sort_unstable_by_key(|(bound, _)| bound.ix());
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Overlay bindings for `action` on either side, if any.
+    pub fn bindings(&self, action: VirtualAction) -> Option<&[InputBinding]> {
+        let action = action.on_player(0);
+        self.actions
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map(|(_, inputs)| inputs.as_slice())
+    }
+
+    /// Overlaid actions as P1 actions, in action order.
+    pub fn iter(&self) -> impl Iterator<Item = (VirtualAction, &[InputBinding])> {
+        self.actions
+            .iter()
+            .map(|(action, inputs)| (*action, inputs.as_slice()))
+    }
+
+    /// Parses `[Keymaps]` entries keyed by side-neutral action names such as
+    /// `Up` or `MenuLeft`. `P1_`/`P2_` prefixes are accepted and dropped so a
+    /// machine keymap line can be pasted in unchanged.
+    pub fn from_ini_entries<'a, I>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let mut bindings = Self::default();
+        for (key, value) in entries {
+            let key = key.trim();
+            let neutral = strip_player_prefix(key).unwrap_or(key);
+            let Some(action) = crate::action_from_ini_key(&format!("P1_{neutral}")) else {
+                continue;
+            };
+            let inputs: Vec<InputBinding> =
+                value.split(',').filter_map(parse_binding_token).collect();
+            bindings.bind(action, &inputs);
+        }
+        bindings
+    }
+
+    /// `(key, value)` lines for a `[Keymaps]` section, in action order.
+    pub fn ini_lines(&self) -> Vec<(&'static str, String)> {
+        self.iter()
+            .map(|(action, inputs)| {
+                let key = crate::action_to_ini_key(action);
+                let key = strip_player_prefix(key).unwrap_or(key);
+                let tokens: Vec<String> = inputs.iter().map(|&b| binding_to_token(b)).collect();
+                (key, tokens.join(","))
+            })
+            .collect()
+    }
+
+    pub fn write_ini_section(&self, content: &mut String) {
+        content.push_str("[Keymaps]\n");
+        for (key, value) in self.ini_lines() {
+            writeln!(content, "{key}={value}").expect("writing into String cannot fail");
+        }
+        content.push('\n');
+    }
+}
+
+#[inline(always)]
+fn strip_player_prefix(key: &str) -> Option<&str> {
+    let prefix = key.get(..3)?;
+    if prefix.eq_ignore_ascii_case("p1_") || prefix.eq_ignore_ascii_case("p2_") {
+        Some(&key[3..])
+    } else {
+        None
+    }
+}
+
+/// A controller bound to one side for the rest of the session. The claim
+/// belongs to the device `uuid`; `pad` is the index it was last seen under and
+/// follows the device when it reconnects under a different index. Every input
+/// from that device is routed to the side regardless of which side the
+/// machine keymap assigns it to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ControllerClaim {
+    pub pad: PadId,
+    pub uuid: [u8; 16],
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct PlayerInputState {
+    pub(crate) bindings: [Option<PlayerBindings>; PLAYER_COUNT],
+    pub(crate) claims: [Option<ControllerClaim>; PLAYER_COUNT],
+    /// Claims whose device is gone, or whose `pad` index now belongs to
+    /// another device. They route nothing until the device reconnects.
+    pub(crate) detached: [bool; PLAYER_COUNT],
+}
+
+impl PlayerInputState {
+    /// Pad index routed to each side, skipping detached claims.
+    pub(crate) fn live_claims(&self) -> [Option<PadId>; PLAYER_COUNT] {
+        std::array::from_fn(|player| {
+            self.claims[player]
+                .filter(|_| !self.detached[player])
+                .map(|claim| claim.pad)
+        })
+    }
+}
+
+static PLAYER_INPUT: LazyLock<RwLock<PlayerInputState>> =
+    LazyLock::new(|| RwLock::new(PlayerInputState::default()));
+
+#[inline(always)]
+pub(crate) fn with_player_input_state<R>(f: impl FnOnce(&PlayerInputState) -> R) -> R {
+    f(&PLAYER_INPUT.read().unwrap())
+}
+
+/// Applies `bindings` to `player`'s side, or drops their overlay on `None`.
+/// Republishes the active keymap only when the overlay actually changes.
+pub fn set_player_bindings(player: usize, bindings: Option<PlayerBindings>) {
+    if player >= PLAYER_COUNT {
+        return;
+    }
+    let bindings = bindings.filter(|b| !b.is_empty());
+    {
+        let mut state = PLAYER_INPUT.write().unwrap();
+        if state.bindings[player] == bindings {
+            return;
+        }
+        state.bindings[player] = bindings;
+    }
+    publish_active_keymap();
+}
+
+/// Claims a controller for `player`, taking it away from the other side.
+pub fn claim_controller(player: usize, claim: ControllerClaim) {
+    if player >= PLAYER_COUNT {
+        return;
+    }
+    {
+        let mut state = PLAYER_INPUT.write().unwrap();
+        if state.claims[player] == Some(claim) && !state.detached[player] {
+            return;
+        }
+        for other in 0..PLAYER_COUNT {
+            let Some(existing) = state.claims[other] else {
+                continue;
+            };
+            if existing.uuid == claim.uuid {
+                state.claims[other] = None;
+                state.detached[other] = false;
+            } else if existing.pad == claim.pad {
+                // The claimed device is live under this index, so whatever
+                // held it before has gone away.
+                state.detached[other] = true;
+            }
+        }
+        state.claims[player] = Some(claim);
+        state.detached[player] = false;
+    }
+    log::info!("Controller {} claimed for P{}", claim.pad.0, player + 1);
+    publish_active_keymap();
+}
+
+pub fn release_controller_claim(player: usize) {
+    if player >= PLAYER_COUNT {
+        return;
+    }
+    {
+        let mut state = PLAYER_INPUT.write().unwrap();
+        if state.claims[player].take().is_none() {
+            return;
+        }
+        state.detached[player] = false;
+    }
+    publish_active_keymap();
+}
+
+/// Points claims on `uuid` at the index the device just connected under, and
+/// detaches claims whose old index has been handed to a different device.
+pub fn controller_connected(pad: PadId, uuid: [u8; 16]) {
+    let mut changed = false;
+    {
+        let mut guard = PLAYER_INPUT.write().unwrap();
+        let state = &mut *guard;
+        for player in 0..PLAYER_COUNT {
+            let Some(claim) = state.claims[player].as_mut() else {
+                continue;
+            };
+            if claim.uuid == uuid {
+                if claim.pad != pad || state.detached[player] {
+                    log::info!(
+                        "Controller claimed for P{} reconnected as {}",
+                        player + 1,
+                        pad.0
+                    );
+                    claim.pad = pad;
+                    state.detached[player] = false;
+                    changed = true;
+                }
+            } else if claim.pad == pad && !state.detached[player] {
+                state.detached[player] = true;
+                changed = true;
+            }
+        }
+    }
+    if changed {
+        publish_active_keymap();
+    }
+}
+
+/// Detaches claims on the device that was at `pad` until it reconnects.
+pub fn controller_disconnected(pad: PadId) {
+    let mut changed = false;
+    {
+        let mut state = PLAYER_INPUT.write().unwrap();
+        for player in 0..PLAYER_COUNT {
+            if state.claims[player].is_some_and(|claim| claim.pad == pad) && !state.detached[player]
+            {
+                state.detached[player] = true;
+                changed = true;
+            }
+        }
+    }
+    if changed {
+        publish_active_keymap();
+    }
+}
+
+#[inline(always)]
+pub fn controller_claims() -> [Option<ControllerClaim>; PLAYER_COUNT] {
+    PLAYER_INPUT.read().unwrap().claims
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeyCode;
+
+    #[test]
+    fn overlays_are_side_neutral_and_round_trip_ini() {
+        let bindings = PlayerBindings::from_ini_entries([
+            ("Up", "KeyCode::KeyW,PadDir::Up"),
+            ("P2_MenuLeft", "KeyCode::KeyQ"),
+            ("Operator", "KeyCode::F1"),
+            ("Nonsense", "KeyCode::KeyZ"),
+        ]);
+        assert_eq!(
+            bindings.bindings(VirtualAction::p2_up),
+            Some(
+                &[
+                    InputBinding::Key(KeyCode::KeyW),
+                    InputBinding::PadDir(crate::PadDir::Up)
+                ][..]
+            )
+        );
+        assert_eq!(
+            bindings.ini_lines(),
+            [
+                ("Up", "KeyCode::KeyW,PadDir::Up".to_string()),
+                ("MenuLeft", "KeyCode::KeyQ".to_string()),
+                ("Operator", "KeyCode::F1".to_string()),
+            ]
+        );
+        let lines = bindings.ini_lines();
+        let reparsed =
+            PlayerBindings::from_ini_entries(lines.iter().map(|(k, v)| (*k, v.as_str())));
+        assert_eq!(reparsed, bindings);
+    }
+
+    #[test]
+    fn actions_move_between_sides() {
+        assert_eq!(
+            VirtualAction::p1_menu_left.on_player(1),
+            VirtualAction::p2_menu_left
+        );
+        assert_eq!(
+            VirtualAction::p2_center.on_player(0),
+            VirtualAction::p1_center
+        );
+        assert_eq!(
+            VirtualAction::system_fast_forward.on_player(1),
+            VirtualAction::system_fast_forward
+        );
+        assert_eq!(VirtualAction::p2_restart.player(), Some(1));
+        assert_eq!(VirtualAction::system_slow_down.player(), None);
+        let mask = VirtualAction::p1_up.bit()
+            | VirtualAction::p2_start.bit()
+            | VirtualAction::system_slow_down.bit();
+        assert_eq!(
+            crate::actions_on_player(mask, 1),
+            VirtualAction::p2_up.bit()
+                | VirtualAction::p2_start.bit()
+                | VirtualAction::system_slow_down.bit()
+        );
+    }
+}
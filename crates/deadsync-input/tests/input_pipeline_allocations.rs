@@ -1,8 +1,8 @@
 use deadsync_input::{
-    GamepadCodeBinding, InputBinding, KeyCode, Keymap, PAD_ID_COUNT_CAP, PadCode, PadDir, PadEvent,
-    PadId, RawKeyboardEvent, VirtualAction, clear_debounce_state,
-    drain_debounced_input_events_with, map_keycode_event_with, map_pad_event_with,
-    map_raw_key_event_with, set_input_debounce_seconds, set_keymap,
+    AxisDirection, GamepadAxisBinding, GamepadCodeBinding, InputBinding, KeyCode, Keymap,
+    PAD_ID_COUNT_CAP, PadCode, PadDir, PadEvent, PadId, RawKeyboardEvent, VirtualAction,
+    clear_debounce_state, drain_debounced_input_events_with, map_keycode_event_with,
+    map_pad_event_with, map_raw_key_event_with, set_input_debounce_seconds, set_keymap,
 };
 use std::alloc::{GlobalAlloc, Layout, System};
 use std::hint::black_box;
@@ -119,6 +119,17 @@ fn configured_input_pipeline_is_allocation_free() {
             dir: PadDir::Up,
         }],
     );
+    keymap.bind(
+        VirtualAction::p2_down,
+        &[InputBinding::PadAxis(GamepadAxisBinding {
+            code_u32: 8,
+            direction: AxisDirection::Positive,
+            press: 1,
+            release: 0,
+            device: Some(PAD_ID_COUNT_CAP - 1),
+            uuid: None,
+        })],
+    );
     set_input_debounce_seconds(0.2);
     set_keymap(keymap);
 
@@ -168,7 +179,15 @@ fn configured_input_pipeline_is_allocation_free() {
         host_nanos: 5,
         code: PadCode(8),
         uuid: [7; 16],
-        value: 0.5,
+        value: 1.0,
+    };
+    let pad_axis_rest = PadEvent::RawAxis {
+        id: pad_id,
+        timestamp,
+        host_nanos: 9,
+        code: PadCode(8),
+        uuid: [7; 16],
+        value: 0.0,
     };
     let pad_unmapped = PadEvent::RawButton {
         id: pad_id,
@@ -207,6 +226,8 @@ fn configured_input_pipeline_is_allocation_free() {
         map_pad_event_with(black_box(&pad_press), |_| emitted += 1);
         map_pad_event_with(black_box(&dir_release), |_| emitted += 1);
         map_pad_event_with(black_box(&dir_press), |_| emitted += 1);
+        map_pad_event_with(black_box(&pad_axis), |_| emitted += 1);
+        map_pad_event_with(black_box(&pad_axis_rest), |_| emitted += 1);
     }
     map_raw_key_event_with(&key_release, |_| emitted += 1);
     map_raw_key_event_with(&key_unmapped, |_| emitted += 1);
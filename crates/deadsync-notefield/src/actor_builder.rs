@@ -1,4 +1,4 @@
-use crate::{FieldPlacement, MeasureLineMode};
+use crate::{FieldPlacement, MeasureLineMode, NotefieldHudAnchors};
 use deadlib_present::actors::{Actor, SharedActorFrameScratch, SizeSpec};
 use deadsync_core::input::MAX_COLS;
 use std::ops::Range;
@@ -96,6 +96,7 @@ pub struct BuiltNotefield {
     pub judgment_draw_range: Option<Range<usize>>,
     pub combo_actors: Option<CapturedActorSource>,
     pub combo_draw_range: Option<Range<usize>>,
+    pub hud_anchors: NotefieldHudAnchors,
 }
 
 pub type CapturedActorSource = [Arc<[Actor]>; 1];
@@ -140,6 +141,7 @@ impl BuiltNotefield {
             judgment_draw_range: None,
             combo_actors: None,
             combo_draw_range: None,
+            hud_anchors: NotefieldHudAnchors::default(),
         }
     }
 }
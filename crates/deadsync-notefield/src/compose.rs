@@ -18,7 +18,7 @@ use deadsync_rules::scroll::ScrollSpeedSetting;
 use deadsync_rules::timing::{
     DelaySegment, ScrollSegment, StopSegment, TimeSignatureSegment, TimingData,
 };
-use deadsync_theme::NotefieldStyle;
+use deadsync_theme::{HudLayout, NotefieldStyle};
 
 /// Screen and player geometry supplied by the gameplay presentation boundary.
 #[derive(Clone, Copy, Debug)]
@@ -198,6 +198,8 @@ pub struct NotefieldOptions {
     pub mini_indicator_position: LayoutMiniIndicatorPosition,
     pub mini_indicator_zoom: f32,
     pub counter_left: bool,
+    /// Profile offsets, zooms and hides applied on top of theme placement.
+    pub hud_layout: HudLayout,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -440,8 +440,9 @@ mod tests {
     use deadsync_rules::timing::TimingData;
     use deadsync_theme::{
         ColumnCueStyle, ColumnFlashLayoutStyle, ColumnFlashStyle, ComboFeedbackStyle,
-        CounterHudStyle, ErrorBarLayers, ErrorBarPalette, ErrorBarStyle, JudgmentFeedbackStyle,
-        MiniIndicatorStyle, NotefieldActorStyle, NotefieldStyle, ReceptorStyle,
+        CounterHudStyle, ErrorBarLayers, ErrorBarPalette, ErrorBarStyle, HudLayout,
+        JudgmentFeedbackStyle, MiniIndicatorStyle, NotefieldActorStyle, NotefieldStyle,
+        ReceptorStyle,
     };
 
     #[derive(Clone, Debug)]
@@ -856,6 +857,7 @@ mod tests {
             measure_counter: None,
             mini_indicator_position: LayoutMiniIndicatorPosition::Default,
             mini_indicator_zoom: 1.0,
+            hud_layout: HudLayout::IDENTITY,
             counter_left: false,
         }
     }
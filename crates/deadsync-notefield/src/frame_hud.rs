@@ -13,6 +13,7 @@ use crate::feedback::{
 use crate::field_frame::actor_from_flat_draw;
 use crate::hud::{
     CounterHudRequest, MiniIndicatorRequest, compose_counter_hud, compose_mini_indicator,
+    mini_indicator_x,
 };
 use crate::judgment_feedback::{
     IndicatorSprite, JudgmentFeedbackRequest, TapJudgmentFeedback, TapJudgmentSprite,
@@ -25,6 +26,7 @@ use deadsync_gameplay::{
     JudgmentRenderInfo, OffsetIndicatorText,
 };
 use deadsync_rules::stream::StreamSegment;
+use deadsync_theme::HudElement;
 use std::ops::Range;
 
 /// Prepared combo values and renderer-neutral assets for one HUD frame.
@@ -147,6 +149,54 @@ pub struct NotefieldHudComposeResult {
     pub combo_draw_range: Option<Range<usize>>,
     pub judgment_actors: Option<CapturedActorSource>,
     pub judgment_draw_range: Option<Range<usize>>,
+    pub anchors: NotefieldHudAnchors,
+}
+
+/// Where each movable HUD element is anchored this frame, with the profile
+/// layout applied. Elements report an anchor even while they draw nothing,
+/// so an editor can mark them on a still field.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NotefieldHudAnchors {
+    pub playfield_center_x: f32,
+    pub judgment: [f32; 2],
+    pub error_bar: [f32; 2],
+    pub measure_counter: Option<[f32; 2]>,
+    pub mini_indicator: [f32; 2],
+}
+
+fn hud_anchors<S>(
+    request: &NotefieldComposeRequest<'_, S>,
+    prepared: &PreparedNotefield<'_, S>,
+) -> NotefieldHudAnchors {
+    let field = prepared.field;
+    let zmod = field.hud_layout.zmod_layout;
+    let layout = request.options.hud_layout;
+    let (mini_x, _) = mini_indicator_x(
+        &request.style.mini_indicator,
+        request.options.mini_indicator_position,
+        request.options.counter_left,
+        field.playfield_center_x,
+        prepared.field_zoom,
+        zmod.subtractive_scoring_addx,
+    );
+    let counter = layout.get(HudElement::MeasureCounter);
+    NotefieldHudAnchors {
+        playfield_center_x: field.playfield_center_x,
+        judgment: layout
+            .get(HudElement::Judgment)
+            .apply([field.judgment_x, field.hud_layout.judgment_y]),
+        error_bar: layout
+            .get(HudElement::ErrorBar)
+            .apply([field.error_bar_x, field.hud_layout.error_bar_y]),
+        measure_counter: request
+            .options
+            .measure_counter
+            .and(zmod.measure_counter_y)
+            .map(|y| counter.apply([field.playfield_center_x, y])),
+        mini_indicator: layout
+            .get(HudElement::MiniIndicator)
+            .apply([mini_x, zmod.subtractive_scoring_y]),
+    }
 }
 
 /// Compose the complete canonical HUD sequence after concrete theme chrome.
@@ -191,12 +241,18 @@ pub fn compose_notefield_hud<S>(
         compose_error(draws, request, prepared, error_bar);
     }
 
-    if let (Some(options), Some(counter)) = (request.options.measure_counter, frame.counter) {
+    let counter_layout = request.options.hud_layout.get(HudElement::MeasureCounter);
+    if let (Some(options), Some(counter), false) = (
+        request.options.measure_counter,
+        frame.counter,
+        counter_layout.hidden,
+    ) {
+        let [dx, dy] = counter_layout.offset;
         compose_counter_hud(
             actors,
             draws,
             CounterHudRequest {
-                style: request.style.counter_hud,
+                style: request.style.counter_hud.scaled(counter_layout.zoom),
                 segments: counter.segments,
                 broken_run_lookup: counter.broken_run_lookup,
                 current_beat: prepared.current_beat,
@@ -209,9 +265,15 @@ pub fn compose_notefield_hud<S>(
                 left: options.left,
                 broken_run: options.broken_run,
                 run_timer: options.run_timer,
-                measure_counter_y: prepared.field.hud_layout.zmod_layout.measure_counter_y,
-                subtractive_scoring_y: prepared.field.hud_layout.zmod_layout.subtractive_scoring_y,
-                playfield_center_x: prepared.field.playfield_center_x,
+                measure_counter_y: prepared
+                    .field
+                    .hud_layout
+                    .zmod_layout
+                    .measure_counter_y
+                    .map(|y| y + dy),
+                subtractive_scoring_y: prepared.field.hud_layout.zmod_layout.subtractive_scoring_y
+                    + dy,
+                playfield_center_x: prepared.field.playfield_center_x + dx,
                 field_zoom: prepared.field_zoom,
                 font: counter.font,
                 frame_text_slot: counter.frame_text_slot,
@@ -221,8 +283,10 @@ pub fn compose_notefield_hud<S>(
         );
     }
 
-    if let Some(mini) = frame.mini.as_ref() {
+    let mini_layout = request.options.hud_layout.get(HudElement::MiniIndicator);
+    if let Some(mini) = frame.mini.as_ref().filter(|_| !mini_layout.hidden) {
         let layout = prepared.field.hud_layout.zmod_layout;
+        let [dx, dy] = mini_layout.offset;
         compose_mini_indicator(
             actors,
             draws,
@@ -233,11 +297,11 @@ pub fn compose_notefield_hud<S>(
                 failed: mini.failed,
                 position: request.options.mini_indicator_position,
                 counter_left: request.options.counter_left,
-                playfield_center_x: prepared.field.playfield_center_x,
+                playfield_center_x: prepared.field.playfield_center_x + dx,
                 field_zoom: prepared.field_zoom,
                 layout_add_x: layout.subtractive_scoring_addx,
-                y: layout.subtractive_scoring_y,
-                zoom: request.options.mini_indicator_zoom,
+                y: layout.subtractive_scoring_y + dy,
+                zoom: request.options.mini_indicator_zoom * mini_layout.zoom,
                 font: mini.font,
             },
         );
@@ -277,6 +341,7 @@ pub fn compose_notefield_hud<S>(
         combo_draw_range,
         judgment_actors,
         judgment_draw_range,
+        anchors: hud_anchors(request, prepared),
     }
 }
 
@@ -353,11 +418,12 @@ fn compose_error<S>(
     } else {
         field.column_receptor_ys[..num_cols].iter().sum::<f32>() / num_cols as f32
     };
-    let show = request.options.frame_features.error_bar;
+    let bar_layout = request.options.hud_layout.get(HudElement::ErrorBar);
+    let show = request.options.frame_features.error_bar && !bar_layout.hidden;
     compose_error_bar(
         draws,
         ErrorBarComposeRequest {
-            style: request.style.error_bar,
+            style: request.style.error_bar.scaled(bar_layout.zoom),
             modes: request.options.error_bar_modes,
             state: ErrorBarState {
                 mono_ticks: frame.mono_ticks,
@@ -370,9 +436,9 @@ fn compose_error<S>(
             },
             visible: !prepared.blind_active && show,
             elapsed_s: request.visual.elapsed_screen_s,
-            position: [field.error_bar_x, layout.error_bar_y],
+            position: bar_layout.apply([field.error_bar_x, layout.error_bar_y]),
             average_y,
-            max_height: layout.error_bar_max_h,
+            max_height: layout.error_bar_max_h * bar_layout.zoom,
             mini: prepared.mini,
             timing_windows_s: frame.timing_windows_s,
             blue_fantastic_window_s: Some(request.options.blue_fantastic_window_s),
@@ -414,7 +480,8 @@ fn compose_judgment<S>(
     frame: &JudgmentHudFrame<'_>,
 ) {
     let options = request.options;
-    let (tap, tap_sprite) = if prepared.blind_active {
+    let tap_layout = options.hud_layout.get(HudElement::Judgment);
+    let (tap, tap_sprite) = if prepared.blind_active || tap_layout.hidden {
         (None, None)
     } else if let Some(frame) = frame.tap.as_ref() {
         let judgment = &frame.render.judgment;
@@ -468,14 +535,14 @@ fn compose_judgment<S>(
             elapsed_screen: request.visual.elapsed_screen_s,
             tap,
             tap_sprite,
-            tap_xy: [field.judgment_x, field.hud_layout.judgment_y],
+            tap_xy: tap_layout.apply([field.judgment_x, field.hud_layout.judgment_y]),
             judgment_back: options.judgment_back,
             judgment_zoom: judgment_actor_zoom(
                 prepared.mini,
                 options.judgment_back,
                 request.visual.perspective.tilt,
                 request.visual.perspective.skew,
-            ),
+            ) * tap_layout.zoom,
             held_misses: frame.held_misses,
             held_miss_sprite,
             hold_judgments: frame.hold_judgments,
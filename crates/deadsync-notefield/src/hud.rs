@@ -388,6 +388,27 @@ pub(crate) struct MiniIndicatorRequest {
     pub font: &'static str,
 }
 
+/// Text x and horizontal alignment of the mini score indicator.
+pub(crate) fn mini_indicator_x(
+    style: &MiniIndicatorStyle,
+    position: LayoutMiniIndicatorPosition,
+    counter_left: bool,
+    playfield_center_x: f32,
+    field_zoom: f32,
+    layout_add_x: f32,
+) -> (f32, f32) {
+    let column_width = ScrollSpeedSetting::ARROW_SPACING * field_zoom;
+    let mut x = playfield_center_x + column_width * style.column_offset;
+    if position == LayoutMiniIndicatorPosition::UnderUpArrow {
+        x += style.under_up_x_offset + layout_add_x;
+    }
+    if counter_left {
+        (x, 0.5)
+    } else {
+        (x + style.unanchored_x_offset, 0.0)
+    }
+}
+
 /// Compose the canonical gameplay mini score indicator.
 pub(crate) fn compose_mini_indicator(
     actors: &mut Vec<Actor>,
@@ -404,17 +425,14 @@ pub(crate) fn compose_mini_indicator(
     } else {
         request.color
     };
-    let column_width = ScrollSpeedSetting::ARROW_SPACING * request.field_zoom;
-    let mut x = request.playfield_center_x + column_width * request.style.column_offset;
-    if request.position == LayoutMiniIndicatorPosition::UnderUpArrow {
-        x += request.style.under_up_x_offset + request.layout_add_x;
-    }
-    let align_x = if request.counter_left {
-        0.5
-    } else {
-        x += request.style.unanchored_x_offset;
-        0.0
-    };
+    let (x, align_x) = mini_indicator_x(
+        &request.style,
+        request.position,
+        request.counter_left,
+        request.playfield_center_x,
+        request.field_zoom,
+        request.layout_add_x,
+    );
 
     let run = HudTextRun {
         content: request.text,
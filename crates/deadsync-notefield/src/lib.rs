@@ -46,7 +46,8 @@ pub use field_frame::{
 pub use frame_feedback::{NotefieldFeedbackFrameView, NotefieldLaneFeedback};
 pub use frame_hud::{
     ComboHudFrame, CounterHudFrame, ErrorBarHudFrame, JudgmentHudFrame, MiniHudFrame,
-    NotefieldHudComposeResult, NotefieldHudFrameView, TapJudgmentHudFrame, compose_notefield_hud,
+    NotefieldHudAnchors, NotefieldHudComposeResult, NotefieldHudFrameView, TapJudgmentHudFrame,
+    compose_notefield_hud,
 };
 pub use holds::{HoldMeshScratch, HoldMeshScratchStats, offset_center};
 pub use hud::{COUNTER_TEXT_SLOTS_PER_PLAYER, MEASURE_COUNTER_LOOKAHEAD_MAX};
@@ -3872,6 +3873,7 @@ mod tests {
         assert!(built.field_actors.is_none());
         assert!(built.judgment_actors.is_none());
         assert!(built.combo_actors.is_none());
+        assert_eq!(built.hud_anchors, super::NotefieldHudAnchors::default());
     }
 
     #[test]
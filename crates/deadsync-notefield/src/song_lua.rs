@@ -400,6 +400,9 @@ pub fn song_lua_player_y_fold_actor(actor: Actor, pivot_x: f32, rotation_y_deg:
         },
         Actor::CameraPush { view_proj } => Actor::CameraPush { view_proj },
         Actor::CameraPop => Actor::CameraPop,
+        // Target contents live in their own space; only the sprite that
+        // samples the target follows the fold.
+        target @ (Actor::RenderTarget { .. } | Actor::ShaderEffect { .. }) => target,
         Actor::Shadow {
             len,
             color,
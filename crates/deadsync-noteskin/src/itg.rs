@@ -1,3 +1,4 @@
+use deadlib_render_core::ShaderEffect;
 use hashbrown::{Equivalent, HashMap as BorrowMap};
 use log::warn;
 use std::collections::{HashMap, HashSet};
@@ -848,6 +849,25 @@ pub fn note_display_metrics(metrics: &IniData) -> NoteDisplayMetrics {
             note_color_type,
         };
     }
+    out.field_shader_effect = metrics
+        .get("NoteDisplay", "FieldShaderEffect")
+        .and_then(parse_ini_value)
+        .and_then(|name| {
+            let params = metrics
+                .get("NoteDisplay", "FieldShaderEffectParams")
+                .and_then(parse_ini_value)
+                .map(|raw| {
+                    raw.split(',')
+                        .filter_map(parse_ini_float)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            let effect = ShaderEffect::from_name(name, &params);
+            if effect.is_none() {
+                warn!("Noteskin requests unknown field shader effect '{name}'");
+            }
+            effect
+        });
     out
 }
 
@@ -1025,6 +1045,7 @@ mod tests {
         texture_key_for_path,
     };
     use crate::{NoteAnimPart, NoteColorType, Style};
+    use deadlib_render_core::ShaderEffect;
     use std::fs;
     use std::path::{Path, PathBuf};
     use std::sync::{Arc, Mutex};
@@ -1424,6 +1445,30 @@ mod tests {
         assert_eq!(roll_translate.note_color_type, NoteColorType::Denominator);
     }
 
+    #[test]
+    fn note_display_metrics_read_field_shader_effect() {
+        let metrics = ini_section(
+            "NoteDisplay",
+            &[
+                ("FieldShaderEffect", "ChromaticAberration"),
+                ("FieldShaderEffectParams", "\"2, -1\" // px"),
+            ],
+        );
+        assert_eq!(
+            note_display_metrics(&metrics).field_shader_effect,
+            Some(ShaderEffect::ChromaticAberration {
+                offset: [2.0, -1.0]
+            })
+        );
+
+        let unknown = ini_section("NoteDisplay", &[("FieldShaderEffect", "sepia")]);
+        assert_eq!(note_display_metrics(&unknown).field_shader_effect, None);
+        assert_eq!(
+            note_display_metrics(&IniData::default()).field_shader_effect,
+            None
+        );
+    }
+
     #[test]
     fn parse_ini_float_trims_quotes_and_comments() {
         assert_eq!(parse_ini_float(" \"1.25\" ; comment"), Some(1.25));
@@ -1,3 +1,5 @@
+use deadlib_render_core::ShaderEffect;
+
 pub const NUM_QUANTIZATIONS: usize = 9;
 pub const ITG_DANCE_COL_SPACING: i32 = 64;
 pub const ITG_PUMP_COL_SPACING: i32 = 48;
@@ -141,6 +143,9 @@ pub struct NoteDisplayMetrics {
     pub hold_active_is_add_layer: bool,
     pub part_animation: [NotePartAnimation; NOTE_ANIM_PART_COUNT],
     pub part_texture_translate: [NotePartTextureTranslate; NOTE_ANIM_PART_COUNT],
+    /// Effect drawn over the whole notefield, from
+    /// `FieldShaderEffect` and `FieldShaderEffectParams`.
+    pub field_shader_effect: Option<ShaderEffect>,
 }
 
 pub fn clamped_hold_let_go_gray_percent(metrics: &NoteDisplayMetrics) -> f32 {
@@ -220,6 +225,7 @@ impl Default for NoteDisplayMetrics {
             hold_active_is_add_layer: false,
             part_animation: [NotePartAnimation::default(); NOTE_ANIM_PART_COUNT],
             part_texture_translate: [NotePartTextureTranslate::default(); NOTE_ANIM_PART_COUNT],
+            field_shader_effect: None,
         }
     }
 }
@@ -6,6 +6,7 @@ pub mod player_leaderboards;
 pub mod runtime;
 pub mod score_compat;
 pub mod score_import;
+pub mod song_vote;
 pub mod srpg_shop;
 pub mod stepmaniaonline;
 
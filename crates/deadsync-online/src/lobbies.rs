@@ -13,6 +13,7 @@ use std::time::{Duration, Instant};
 use tungstenite::stream::MaybeTlsStream;
 use tungstenite::{Message, WebSocket};
 
+use crate::song_vote::{LobbySongVote, SongVote};
 use deadsync_profile::PlayerSide;
 
 pub const LOBBY_SERVICE_URL: &str = "ws://syncservice.groovestats.com:1337";
@@ -533,6 +534,8 @@ pub struct LobbyMachinePlayer {
     pub score: Option<f32>,
     #[serde(rename = "exScore")]
     pub ex_score: Option<f32>,
+    #[serde(skip_serializing_if = "LobbySongVote::is_empty")]
+    pub song_vote: LobbySongVote,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -556,6 +559,7 @@ pub fn lobby_machine_player(
         judgments: stats.and_then(|stats| stats.judgments.clone()),
         score: stats.and_then(|stats| stats.score),
         ex_score: stats.and_then(|stats| stats.ex_score),
+        song_vote: LobbySongVote::default(),
     }
 }
 
@@ -574,6 +578,7 @@ pub struct LocalLobbyPlayer<'a> {
     pub screen_name: &'a str,
     pub ready: bool,
     pub stats: Option<&'a MachinePlayerStats>,
+    pub song_vote: Option<&'a LobbySongVote>,
 }
 
 pub fn local_lobby_machine_state_value(
@@ -602,6 +607,7 @@ pub fn runtime_local_lobby_machine_state_value(
     p2_stats: Option<&MachinePlayerStats>,
 ) -> Value {
     let players = deadsync_profile::runtime_session_players_view();
+    let song_votes = runtime_local_song_votes();
     local_lobby_machine_state_value(
         LocalLobbyPlayer {
             side: PlayerSide::P1,
@@ -610,6 +616,7 @@ pub fn runtime_local_lobby_machine_state_value(
             screen_name,
             ready: p1_ready,
             stats: p1_stats,
+            song_vote: Some(&song_votes[0]),
         },
         LocalLobbyPlayer {
             side: PlayerSide::P2,
@@ -618,19 +625,24 @@ pub fn runtime_local_lobby_machine_state_value(
             screen_name,
             ready: p2_ready,
             stats: p2_stats,
+            song_vote: Some(&song_votes[1]),
         },
         players.active_side,
     )
 }
 
 pub fn local_lobby_machine_player(player: LocalLobbyPlayer<'_>) -> LobbyMachinePlayer {
-    lobby_machine_player(
+    let mut machine_player = lobby_machine_player(
         lobby_player_id(player.side),
         player.display_name,
         player.screen_name,
         player.ready,
         player.stats,
-    )
+    );
+    if let Some(song_vote) = player.song_vote {
+        machine_player.song_vote = song_vote.clone();
+    }
+    machine_player
 }
 
 #[inline(always)]
@@ -649,6 +661,7 @@ pub struct LobbyPlayer {
     pub judgments: Option<LobbyJudgments>,
     pub score: Option<f32>,
     pub ex_score: Option<f32>,
+    pub song_vote: LobbySongVote,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -687,6 +700,8 @@ pub struct LobbyStatePlayerData {
     pub score: Option<f32>,
     #[serde(rename = "exScore", default)]
     pub ex_score: Option<f32>,
+    #[serde(default)]
+    pub song_vote: Option<LobbySongVote>,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -760,12 +775,24 @@ pub fn joined_lobby_from_state(data: LobbyStateData) -> JoinedLobby {
                 judgments: player.judgments,
                 score: player.score,
                 ex_score: player.ex_score,
+                song_vote: player.song_vote.unwrap_or_default(),
             })
             .collect(),
         song_info: data.song_info,
     }
 }
 
+/// The shared nomination vote for a joined lobby, keyed by each vote's
+/// voter id.
+pub fn song_vote_from_lobby(joined: &JoinedLobby) -> SongVote {
+    SongVote::from_players(
+        joined
+            .players
+            .iter()
+            .map(|player| (player.label.as_str(), &player.song_vote)),
+    )
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ResponseStatus {
     pub event: String,
@@ -1098,6 +1125,8 @@ static RUNTIME_LAST_MACHINE_STATE_SIG: LazyLock<Mutex<Option<String>>> =
     LazyLock::new(|| Mutex::new(None));
 static RUNTIME_LAST_MACHINE_STATE_INPUT: LazyLock<Mutex<Option<RuntimeMachineStateInput>>> =
     LazyLock::new(|| Mutex::new(None));
+static RUNTIME_LOCAL_SONG_VOTES: LazyLock<Mutex<[LobbySongVote; 2]>> =
+    LazyLock::new(|| Mutex::new([LobbySongVote::default(), LobbySongVote::default()]));
 static RUNTIME_RECONNECT_STATE: LazyLock<Mutex<ReconnectState>> =
     LazyLock::new(|| Mutex::new(ReconnectState::default()));
 static RUNTIME_TEST_MUTEX: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
@@ -1196,6 +1225,7 @@ impl RuntimeMachineStateInput {
     }
 
     fn machine_state_value(&self) -> Value {
+        let song_votes = runtime_local_song_votes();
         local_lobby_machine_state_value(
             LocalLobbyPlayer {
                 side: PlayerSide::P1,
@@ -1204,6 +1234,7 @@ impl RuntimeMachineStateInput {
                 screen_name: self.screen_name.as_str(),
                 ready: self.p1_ready,
                 stats: self.p1_stats.as_ref(),
+                song_vote: Some(&song_votes[0]),
             },
             LocalLobbyPlayer {
                 side: PlayerSide::P2,
@@ -1212,6 +1243,7 @@ impl RuntimeMachineStateInput {
                 screen_name: self.screen_name.as_str(),
                 ready: self.p2_ready,
                 stats: self.p2_stats.as_ref(),
+                song_vote: Some(&song_votes[1]),
             },
             self.active_side,
         )
@@ -1223,6 +1255,32 @@ fn runtime_clear_machine_state_cache() {
     *RUNTIME_LAST_MACHINE_STATE_INPUT.lock().unwrap() = None;
 }
 
+#[inline(always)]
+fn runtime_local_song_votes() -> [LobbySongVote; 2] {
+    RUNTIME_LOCAL_SONG_VOTES.lock().unwrap().clone()
+}
+
+#[inline(always)]
+const fn side_index(side: PlayerSide) -> usize {
+    match side {
+        PlayerSide::P1 => 0,
+        PlayerSide::P2 => 1,
+    }
+}
+
+/// Replaces one local side's nomination and bans. The next machine-state
+/// update carries them to the rest of the lobby.
+pub fn runtime_set_local_song_vote(side: PlayerSide, song_vote: LobbySongVote) {
+    RUNTIME_LOCAL_SONG_VOTES.lock().unwrap()[side_index(side)] = song_vote;
+    runtime_clear_machine_state_cache();
+}
+
+pub fn runtime_clear_local_song_votes() {
+    *RUNTIME_LOCAL_SONG_VOTES.lock().unwrap() =
+        [LobbySongVote::default(), LobbySongVote::default()];
+    runtime_clear_machine_state_cache();
+}
+
 #[derive(Debug)]
 pub struct RuntimeViewRefresh {
     pub generation: u64,
@@ -1371,7 +1429,7 @@ pub fn runtime_join_lobby_with_password(hooks: LobbyRuntimeHooks, code: &str, pa
 
 pub fn runtime_leave_lobby(hooks: LobbyRuntimeHooks) {
     runtime_clear_reconnect_target();
-    runtime_clear_machine_state_cache();
+    runtime_clear_local_song_votes();
     let should_send_leave = {
         let mut snapshot = runtime_lock_snapshot();
         apply_local_lobby_leave(Arc::make_mut(&mut *snapshot))
@@ -2065,6 +2123,7 @@ mod tests {
             judgments: None,
             score: Some(98.5),
             ex_score: Some(97.25),
+            song_vote: LobbySongVote::default(),
         };
         {
             let mut snapshot = runtime_lock_snapshot();
@@ -2136,6 +2195,7 @@ mod tests {
                     judgments: None,
                     score: None,
                     ex_score: None,
+                    song_vote: LobbySongVote::default(),
                 }],
                 song_info: None,
             });
@@ -2303,6 +2363,7 @@ mod tests {
                 screen_name: "ScreenSelectMusic",
                 ready: true,
                 stats: None,
+                song_vote: None,
             },
             LocalLobbyPlayer {
                 side: PlayerSide::P2,
@@ -2314,6 +2375,7 @@ mod tests {
                     score: Some(98.5),
                     ..MachinePlayerStats::default()
                 }),
+                song_vote: None,
             },
             PlayerSide::P2,
         );
@@ -2339,6 +2401,7 @@ mod tests {
                     ex_score: Some(97.25),
                     ..MachinePlayerStats::default()
                 }),
+                song_vote: None,
             },
             LocalLobbyPlayer {
                 side: PlayerSide::P2,
@@ -2347,6 +2410,7 @@ mod tests {
                 screen_name: "ScreenSelectMusic",
                 ready: true,
                 stats: None,
+                song_vote: None,
             },
             PlayerSide::P2,
         );
@@ -2779,6 +2843,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn song_votes_round_trip_through_machine_and_lobby_state() {
+        let song_vote = LobbySongVote {
+            nomination: Some(LobbySongInfo {
+                song_path: "Pack/Song".to_string(),
+                chart_hash: Some("deadbeef".to_string()),
+                ..LobbySongInfo::default()
+            }),
+            ..LobbySongVote::default()
+        };
+        let value = local_lobby_machine_state_value(
+            LocalLobbyPlayer {
+                side: PlayerSide::P1,
+                display_name: "Alice",
+                joined: true,
+                screen_name: "ScreenSelectMusic",
+                ready: true,
+                stats: None,
+                song_vote: Some(&song_vote),
+            },
+            LocalLobbyPlayer {
+                side: PlayerSide::P2,
+                display_name: "Bob",
+                joined: true,
+                screen_name: "ScreenSelectMusic",
+                ready: true,
+                stats: None,
+                song_vote: None,
+            },
+            PlayerSide::P1,
+        );
+        assert_eq!(
+            value["player1"]["songVote"]["nomination"]["songPath"],
+            "Pack/Song"
+        );
+        assert!(value["player2"].get("songVote").is_none());
+
+        let data: LobbyStateData = serde_json::from_value(serde_json::json!({
+            "code": "ROOM",
+            "players": [value["player1"].clone(), value["player2"].clone()],
+        }))
+        .expect("lobby state should parse");
+        let joined = joined_lobby_from_state(data);
+        assert_eq!(joined.players[0].song_vote, song_vote);
+        assert!(joined.players[1].song_vote.is_empty());
+        assert_eq!(song_vote_from_lobby(&joined).nominations().len(), 1);
+    }
+
     fn test_lobby_player(screen_name: &str, ready: bool) -> LobbyPlayer {
         LobbyPlayer {
             label: "Local".to_string(),
@@ -2787,6 +2899,7 @@ mod tests {
             judgments: None,
             score: None,
             ex_score: None,
+            song_vote: LobbySongVote::default(),
         }
     }
 
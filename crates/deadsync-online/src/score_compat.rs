@@ -60,7 +60,7 @@ pub use profile::{
     machine_scalar_record_local as get_machine_scalar_record_local,
     personal_leaderboard_local_for_side as get_personal_leaderboard_local_for_side,
     played_chart_counts_for_id as played_chart_counts_for_profile, played_chart_counts_for_machine,
-    prewarm_select_music_score_caches,
+    prewarm_select_music_score_caches, recent_play_ex_for_id as recent_play_ex_for_profile,
     recent_played_chart_hashes_for_id as recent_played_chart_hashes_for_profile,
     recent_played_chart_hashes_for_machine, save_local_summary_score_for_side,
     scorebox_profile_snapshot, seed_session_gs_score_for_id as seed_session_gs_score,
@@ -0,0 +1,410 @@
+//! Nomination-based song selection for lobbies and local versus play.
+//!
+//! Every player nominates one chart. The vote is then decided either by a
+//! weighted roulette (charts nominated by several players get more slots) or by
+//! players taking turns banning candidates until a single chart remains. Both
+//! outcomes are deterministic for a given vote, so every lobby member computes
+//! the same winner and the existing `selectSong` broadcast stays idempotent.
+
+use crate::lobbies::LobbySongInfo;
+use serde::{Deserialize, Serialize};
+
+/// A player's nomination and bans, as carried on the lobby machine state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbySongVote {
+    /// Stable id of the voting profile, so votes survive display-name
+    /// changes and two players sharing a name. Empty falls back to the
+    /// lobby label.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub voter: String,
+    #[serde(default)]
+    pub nomination: Option<LobbySongInfo>,
+    /// Candidate keys (see [`song_vote_key`]) this player has banned.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bans: Vec<String>,
+}
+
+impl LobbySongVote {
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.nomination.is_none() && self.bans.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SongNomination {
+    pub player: String,
+    pub song_info: LobbySongInfo,
+}
+
+/// A distinct nominated chart that has not been banned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SongCandidate {
+    pub key: String,
+    pub song_info: LobbySongInfo,
+    pub weight: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SongVoteError {
+    NotYourTurn,
+    NotACandidate,
+    LastCandidate,
+}
+
+impl std::fmt::Display for SongVoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotYourTurn => f.write_str("it is not this player's turn to ban"),
+            Self::NotACandidate => f.write_str("that chart is not a remaining nomination"),
+            Self::LastCandidate => f.write_str("the last remaining nomination cannot be banned"),
+        }
+    }
+}
+
+impl std::error::Error for SongVoteError {}
+
+/// Identity used to merge and ban nominations: song path plus chart hash.
+pub fn song_vote_key(song_info: &LobbySongInfo) -> String {
+    let path = song_info
+        .song_path
+        .trim()
+        .replace('\\', "/")
+        .trim_matches('/')
+        .to_ascii_lowercase();
+    format!("{path}|{}", song_info.chart_hash.as_deref().unwrap_or(""))
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SongVote {
+    nominations: Vec<SongNomination>,
+    banned: Vec<String>,
+}
+
+impl SongVote {
+    /// Builds a vote from `(label, vote)` pairs, e.g. the players of a joined
+    /// lobby. Each vote is keyed by its `voter` id, or by `label` when it has
+    /// none. Bans are trusted as sent; turn order is only enforced locally.
+    pub fn from_players<'a>(
+        players: impl IntoIterator<Item = (&'a str, &'a LobbySongVote)>,
+    ) -> Self {
+        let mut vote = Self::default();
+        let mut bans = Vec::new();
+        for (label, entry) in players {
+            if let Some(song_info) = entry.nomination.as_ref() {
+                vote.nominate(song_voter_id(label, entry), song_info.clone());
+            }
+            bans.extend(entry.bans.iter().cloned());
+        }
+        for key in bans {
+            if !vote.banned.contains(&key) {
+                vote.banned.push(key);
+            }
+        }
+        vote
+    }
+
+    #[inline(always)]
+    pub fn nominations(&self) -> &[SongNomination] {
+        &self.nominations
+    }
+
+    #[inline(always)]
+    pub fn banned(&self) -> &[String] {
+        &self.banned
+    }
+
+    pub fn nomination_for(&self, player: &str) -> Option<&LobbySongInfo> {
+        self.nominations
+            .iter()
+            .find(|nomination| nomination.player == player)
+            .map(|nomination| &nomination.song_info)
+    }
+
+    /// Records `player`'s nomination, replacing any earlier one. Nominations
+    /// stay sorted by player id, so ban turns do not depend on the order
+    /// votes arrived in.
+    pub fn nominate(&mut self, player: &str, song_info: LobbySongInfo) {
+        match self
+            .nominations
+            .binary_search_by(|nomination| nomination.player.as_str().cmp(player))
+        {
+            Ok(ix) => self.nominations[ix].song_info = song_info,
+            Err(ix) => self.nominations.insert(
+                ix,
+                SongNomination {
+                    player: player.to_string(),
+                    song_info,
+                },
+            ),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.nominations.clear();
+        self.banned.clear();
+    }
+
+    /// Remaining candidates sorted by [`song_vote_key`], which also merges them.
+    pub fn candidates(&self) -> Vec<SongCandidate> {
+        let mut out: Vec<SongCandidate> = Vec::with_capacity(self.nominations.len());
+        for nomination in &self.nominations {
+            let key = song_vote_key(&nomination.song_info);
+            if self.banned.contains(&key) {
+                continue;
+            }
+            if let Some(candidate) = out.iter_mut().find(|candidate| candidate.key == key) {
+                candidate.weight += 1;
+            } else {
+                out.push(SongCandidate {
+                    key,
+                    song_info: nomination.song_info.clone(),
+                    weight: 1,
+                });
+            }
+        }
+        out.sort_unstable_by(|a, b| a.key.cmp(&b.key));
+        out
+    }
+
+    /// The player whose turn it is to ban, while more than one candidate is left.
+    pub fn ban_turn(&self) -> Option<&str> {
+        if self.candidates().len() <= 1 {
+            return None;
+        }
+        let turn = self.banned.len() % self.nominations.len();
+        Some(self.nominations[turn].player.as_str())
+    }
+
+    pub fn ban(&mut self, player: &str, key: &str) -> Result<(), SongVoteError> {
+        let candidates = self.candidates();
+        if candidates.len() <= 1 {
+            return Err(SongVoteError::LastCandidate);
+        }
+        if !candidates.iter().any(|candidate| candidate.key == key) {
+            return Err(SongVoteError::NotACandidate);
+        }
+        if self.ban_turn() != Some(player) {
+            return Err(SongVoteError::NotYourTurn);
+        }
+        self.banned.push(key.to_string());
+        Ok(())
+    }
+
+    /// The winner once at least two nominations narrowed down to one chart,
+    /// either by bans or because everyone nominated the same chart.
+    pub fn decided(&self) -> Option<LobbySongInfo> {
+        if self.nominations.len() < 2 {
+            return None;
+        }
+        let mut candidates = self.candidates();
+        (candidates.len() == 1).then(|| candidates.remove(0).song_info)
+    }
+
+    /// Seed shared by everyone who sees the same vote. `salt` separates rounds,
+    /// e.g. the lobby code.
+    pub fn seed(&self, salt: &str) -> u64 {
+        let mut keys: Vec<String> = self
+            .nominations
+            .iter()
+            .map(|nomination| song_vote_key(&nomination.song_info))
+            .collect();
+        keys.sort_unstable();
+        let mut banned: Vec<&String> = self.banned.iter().collect();
+        banned.sort_unstable();
+        let mut hash = fnv1a(0xcbf2_9ce4_8422_2325, salt.as_bytes());
+        for key in keys.iter().chain(banned) {
+            hash = fnv1a(hash, key.as_bytes());
+            hash = fnv1a(hash, &[0]);
+        }
+        hash
+    }
+
+    /// Weighted random pick among the remaining candidates.
+    pub fn roulette(&self, seed: u64) -> Option<LobbySongInfo> {
+        let candidates = self.candidates();
+        let total: u64 = candidates
+            .iter()
+            .map(|candidate| u64::from(candidate.weight))
+            .sum();
+        if total == 0 {
+            return None;
+        }
+        let mut slot = splitmix64(seed) % total;
+        for candidate in candidates {
+            let weight = u64::from(candidate.weight);
+            if slot < weight {
+                return Some(candidate.song_info);
+            }
+            slot -= weight;
+        }
+        None
+    }
+}
+
+/// The id a lobby vote is keyed by: its `voter`, or `label` for votes sent
+/// without one.
+#[inline(always)]
+pub fn song_voter_id<'a>(label: &'a str, vote: &'a LobbySongVote) -> &'a str {
+    if vote.voter.is_empty() {
+        label
+    } else {
+        vote.voter.as_str()
+    }
+}
+
+#[inline(always)]
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+#[inline(always)]
+fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song(path: &str, hash: &str) -> LobbySongInfo {
+        LobbySongInfo {
+            song_path: path.to_string(),
+            chart_hash: Some(hash.to_string()),
+            ..LobbySongInfo::default()
+        }
+    }
+
+    #[test]
+    fn nominations_merge_by_chart_and_replace_per_player() {
+        let mut vote = SongVote::default();
+        vote.nominate("P1", song("Pack/A", "aaa"));
+        vote.nominate("P2", song("pack\\a", "aaa"));
+        vote.nominate("P3", song("Pack/B", "bbb"));
+        vote.nominate("P3", song("Pack/C", "ccc"));
+
+        let candidates = vote.candidates();
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].weight, 2);
+        assert_eq!(candidates[1].song_info.song_path, "Pack/C");
+        assert_eq!(vote.decided(), None);
+    }
+
+    #[test]
+    fn bans_alternate_until_one_chart_remains() {
+        let mut vote = SongVote::default();
+        vote.nominate("P1", song("Pack/A", "aaa"));
+        vote.nominate("P2", song("Pack/B", "bbb"));
+        vote.nominate("P3", song("Pack/C", "ccc"));
+        let b = song_vote_key(&song("Pack/B", "bbb"));
+        let c = song_vote_key(&song("Pack/C", "ccc"));
+
+        assert_eq!(vote.ban_turn(), Some("P1"));
+        assert_eq!(vote.ban("P2", &b), Err(SongVoteError::NotYourTurn));
+        assert_eq!(vote.ban("P1", "missing"), Err(SongVoteError::NotACandidate));
+        assert_eq!(vote.ban("P1", &b), Ok(()));
+        assert_eq!(vote.ban_turn(), Some("P2"));
+        assert_eq!(vote.ban("P2", &c), Ok(()));
+
+        assert_eq!(vote.ban_turn(), None);
+        assert_eq!(vote.decided().map(|s| s.song_path), Some("Pack/A".into()));
+    }
+
+    #[test]
+    fn roulette_is_deterministic_and_only_picks_candidates() {
+        let mut vote = SongVote::default();
+        vote.nominate("P1", song("Pack/A", "aaa"));
+        vote.nominate("P2", song("Pack/B", "bbb"));
+        let seed = vote.seed("ABCD");
+
+        let mut reordered = SongVote::default();
+        reordered.nominate("P2", song("Pack/B", "bbb"));
+        reordered.nominate("P1", song("Pack/A", "aaa"));
+        assert_eq!(reordered.seed("ABCD"), seed);
+
+        let picked = vote.roulette(seed).expect("two candidates");
+        assert!(["Pack/A", "Pack/B"].contains(&picked.song_path.as_str()));
+        assert_eq!(vote.roulette(seed), Some(picked));
+        assert_eq!(SongVote::default().roulette(seed), None);
+    }
+
+    #[test]
+    fn lobby_entries_build_a_shared_vote() {
+        let p1 = LobbySongVote {
+            nomination: Some(song("Pack/A", "aaa")),
+            ..LobbySongVote::default()
+        };
+        let p2 = LobbySongVote {
+            nomination: Some(song("Pack/B", "bbb")),
+            bans: vec![song_vote_key(&song("Pack/A", "aaa"))],
+            ..LobbySongVote::default()
+        };
+
+        let vote = SongVote::from_players([("P1", &p1), ("P2", &p2)]);
+        assert_eq!(vote.decided().map(|s| s.song_path), Some("Pack/B".into()));
+    }
+
+    #[test]
+    fn votes_key_by_voter_id_not_display_name() {
+        let first = LobbySongVote {
+            voter: "guid-1".to_string(),
+            nomination: Some(song("Pack/A", "aaa")),
+            ..LobbySongVote::default()
+        };
+        let second = LobbySongVote {
+            voter: "guid-2".to_string(),
+            nomination: Some(song("Pack/B", "bbb")),
+            ..LobbySongVote::default()
+        };
+
+        // Two players sharing a display name still cast two nominations.
+        let vote = SongVote::from_players([("Alex", &first), ("Alex", &second)]);
+        assert_eq!(vote.nominations().len(), 2);
+        assert_eq!(vote.nomination_for("guid-2"), second.nomination.as_ref());
+        assert_eq!(vote.ban_turn(), Some("guid-1"));
+    }
+
+    #[test]
+    fn arrival_order_does_not_change_turns_or_roulette() {
+        let a = LobbySongVote {
+            voter: "guid-a".to_string(),
+            nomination: Some(song("Pack/A", "aaa")),
+            ..LobbySongVote::default()
+        };
+        let b = LobbySongVote {
+            voter: "guid-b".to_string(),
+            nomination: Some(song("Pack/B", "bbb")),
+            bans: vec![song_vote_key(&song("Pack/D", "ddd"))],
+        };
+        let c = LobbySongVote {
+            voter: "guid-c".to_string(),
+            nomination: Some(song("Pack/C", "ccc")),
+            ..LobbySongVote::default()
+        };
+        let d = LobbySongVote {
+            voter: "guid-d".to_string(),
+            nomination: Some(song("Pack/D", "ddd")),
+            bans: vec![song_vote_key(&song("Pack/C", "ccc"))],
+        };
+
+        let forward = SongVote::from_players([("A", &a), ("B", &b), ("C", &c), ("D", &d)]);
+        let backward = SongVote::from_players([("D", &d), ("C", &c), ("B", &b), ("A", &a)]);
+        assert_eq!(forward.ban_turn(), backward.ban_turn());
+        assert_eq!(forward.candidates(), backward.candidates());
+        for salt in ["ABCD", "EFGH", "IJKL", "MNOP"] {
+            assert_eq!(forward.seed(salt), backward.seed(salt));
+            assert_eq!(
+                forward.roulette(forward.seed(salt)),
+                backward.roulette(backward.seed(salt))
+            );
+        }
+    }
+}
@@ -80,6 +80,7 @@ pub struct GameplayPackData {
     pub pack_group: Arc<str>,
     pub pack_banner_path: Option<PathBuf>,
     pub sync_pref: deadsync_chart::SyncPref,
+    pub sync_bias_ms: f32,
 }
 
 pub fn song_pack_group(song: &deadsync_chart::SongData) -> Arc<str> {
@@ -102,6 +103,7 @@ pub fn gameplay_pack_data(
     let pack_group = song_pack_group(song);
     let mut pack_banner_path = None;
     let mut sync_pref = deadsync_chart::SyncPref::Default;
+    let mut sync_bias_ms = 0.0;
     if !pack_group.is_empty()
         && let Some(pack) = deadsync_simfile::runtime_cache::get_song_cache()
             .iter()
@@ -109,6 +111,7 @@ pub fn gameplay_pack_data(
     {
         pack_banner_path = pack.banner_path.clone();
         sync_pref = pack.sync_pref;
+        sync_bias_ms = pack.sync_bias_ms_for(&song.simfile_path);
     }
     if let Some(course_name) = course_name {
         pack_banner_path = course_banner_path.cloned();
@@ -116,12 +119,14 @@ pub fn gameplay_pack_data(
             pack_group: course_name.clone(),
             pack_banner_path,
             sync_pref,
+            sync_bias_ms,
         };
     }
     GameplayPackData {
         pack_group,
         pack_banner_path,
         sync_pref,
+        sync_bias_ms,
     }
 }
 
@@ -201,6 +206,7 @@ pub fn gameplay_config_from_config(
         machine_enable_replays: cfg.machine_enable_replays,
         center_1player_notefield: cfg.center_1player_notefield,
         delayed_back: cfg.delayed_back,
+        song_lua_live_runtime: cfg.song_lua_live_runtime,
     }
 }
 
@@ -217,6 +223,26 @@ pub fn score_display_mode_from_profile(
     }
 }
 
+/// Life rule picked by the player's `LifeType` option. Courses with their own
+/// `#LIVES`/`#GAINSECONDS` rules override this.
+pub fn life_config_from_profile(
+    profile: &deadsync_profile::Profile,
+) -> deadsync_gameplay::CourseLifeConfig {
+    match profile.life_type {
+        deadsync_profile::LifeType::Bar => deadsync_gameplay::CourseLifeConfig::Bar,
+        deadsync_profile::LifeType::Battery => deadsync_gameplay::CourseLifeConfig::Battery {
+            total_lives: u32::from(profile.battery_lives.clamp(
+                deadsync_profile::BATTERY_LIVES_MIN,
+                deadsync_profile::BATTERY_LIVES_MAX,
+            )),
+            reward_lives: u32::from(profile.battery_reward_lives.clamp(
+                deadsync_profile::BATTERY_REWARD_LIVES_MIN,
+                deadsync_profile::BATTERY_REWARD_LIVES_MAX,
+            )),
+        },
+    }
+}
+
 fn gameplay_target_score_setting(
     setting: deadsync_profile::TargetScoreSetting,
 ) -> deadsync_gameplay::GameplayTargetScoreSetting {
@@ -294,6 +320,19 @@ pub fn chart_effects_from_profile(
     }
 }
 
+pub fn timing_window_adjust_from_profile(
+    profile: &deadsync_profile::Profile,
+) -> deadsync_rules::timing::TimingWindowAdjust {
+    let overrides = profile.timing_window_overrides;
+    deadsync_rules::timing::TimingWindowAdjust {
+        scale: f32::from(deadsync_profile::clamp_timing_window_scale(
+            profile.timing_window_scale,
+        )) / 100.0,
+        windows_ms: overrides.windows_ms.map(|ms| ms.map(f32::from)),
+        mine_ms: overrides.mine_ms.map(f32::from),
+    }
+}
+
 pub fn score_invalid_reason_lines_for_profile(
     chart: &deadsync_chart::ChartData,
     profile: &deadsync_profile::Profile,
@@ -305,6 +344,7 @@ pub fn score_invalid_reason_lines_for_profile(
             chart_effects: chart_effects_from_profile(profile),
             attack_mode: gameplay_attack_mode(profile.attack_mode),
             music_rate,
+            custom_timing_windows: profile.custom_timing_windows_active(),
         },
     )
 }
@@ -513,6 +553,34 @@ pub fn song_lua_runtime_ease_windows(
         .collect()
 }
 
+/// Replaces each player's live windows with the values a
+/// [`deadsync_song_lua::SongLuaLiveRuntime`] frame produced.
+pub fn song_lua_live_ease_windows(
+    windows: &[deadsync_song_lua::SongLuaEaseWindow],
+    num_players: usize,
+    out: &mut [Vec<deadsync_gameplay::SongLuaEaseMaskWindow>],
+) {
+    use deadsync_gameplay::SongLuaRuntimeEaseTargetLike;
+
+    for (player, player_out) in out.iter_mut().enumerate() {
+        player_out.clear();
+        if player >= num_players {
+            continue;
+        }
+        for window in windows {
+            if !deadsync_gameplay::song_lua_target_matches_player(window.player, player) {
+                continue;
+            }
+            let target = song_lua_runtime_ease_target(&window.target);
+            deadsync_gameplay::append_song_lua_live_window(
+                player_out,
+                target.as_runtime_ease_target(),
+                window.to,
+            );
+        }
+    }
+}
+
 pub fn song_lua_runtime_column_offset_windows(
     windows: &[deadsync_song_lua::SongLuaColumnOffsetWindow],
 ) -> Vec<deadsync_gameplay::SongLuaRuntimeColumnOffsetWindow> {
@@ -1075,6 +1143,41 @@ fn gameplay_turn_option(
     }
 }
 
+fn local_turn_mod(turn: deadsync_profile::TurnOption) -> deadsync_score::LocalTurnMod {
+    match turn {
+        deadsync_profile::TurnOption::None => deadsync_score::LocalTurnMod::None,
+        deadsync_profile::TurnOption::Mirror => deadsync_score::LocalTurnMod::Mirror,
+        deadsync_profile::TurnOption::LRMirror => deadsync_score::LocalTurnMod::LRMirror,
+        deadsync_profile::TurnOption::UDMirror => deadsync_score::LocalTurnMod::UDMirror,
+        deadsync_profile::TurnOption::Left => deadsync_score::LocalTurnMod::Left,
+        deadsync_profile::TurnOption::Right => deadsync_score::LocalTurnMod::Right,
+        deadsync_profile::TurnOption::Shuffle => deadsync_score::LocalTurnMod::Shuffle,
+        deadsync_profile::TurnOption::Blender => deadsync_score::LocalTurnMod::Blender,
+        deadsync_profile::TurnOption::Random => deadsync_score::LocalTurnMod::Random,
+    }
+}
+
+/// Lane each chart column was played on under a score's recorded turn mod, or
+/// `None` when the turn rearranged the chart row by row.
+pub fn replay_lane_map(
+    turn: deadsync_score::LocalTurnMod,
+    seed: u64,
+    cols: usize,
+) -> Option<Vec<usize>> {
+    let turn = match turn {
+        deadsync_score::LocalTurnMod::None => deadsync_gameplay::GameplayTurnOption::None,
+        deadsync_score::LocalTurnMod::Mirror => deadsync_gameplay::GameplayTurnOption::Mirror,
+        deadsync_score::LocalTurnMod::LRMirror => deadsync_gameplay::GameplayTurnOption::LRMirror,
+        deadsync_score::LocalTurnMod::UDMirror => deadsync_gameplay::GameplayTurnOption::UDMirror,
+        deadsync_score::LocalTurnMod::Left => deadsync_gameplay::GameplayTurnOption::Left,
+        deadsync_score::LocalTurnMod::Right => deadsync_gameplay::GameplayTurnOption::Right,
+        deadsync_score::LocalTurnMod::Shuffle => deadsync_gameplay::GameplayTurnOption::Shuffle,
+        deadsync_score::LocalTurnMod::Blender => deadsync_gameplay::GameplayTurnOption::Blender,
+        deadsync_score::LocalTurnMod::Random => deadsync_gameplay::GameplayTurnOption::Random,
+    };
+    deadsync_gameplay::turn_lane_map(turn, cols, seed)
+}
+
 fn mini_indicator_mode(
     mode: deadsync_profile::MiniIndicator,
 ) -> deadsync_gameplay::GameplayMiniIndicatorMode {
@@ -1180,6 +1283,10 @@ impl deadsync_gameplay::GameplayProfileData for GameplayProfile {
         self.timing_windows.disabled_windows()
     }
 
+    fn timing_window_adjust(&self) -> deadsync_rules::timing::TimingWindowAdjust {
+        timing_window_adjust_from_profile(self)
+    }
+
     fn column_flash_options(&self) -> deadsync_gameplay::ColumnFlashOptions {
         let mask = self.column_flash_mask;
         deadsync_gameplay::ColumnFlashOptions {
@@ -1367,6 +1474,55 @@ mod gameplay_profile_tests {
         profile.hide_combo = true;
         assert!(!profile.combo_milestones_enabled());
     }
+
+    #[test]
+    fn custom_timing_windows_map_to_gameplay_adjust() {
+        let mut profile = GameplayProfile(deadsync_profile::Profile::default());
+        assert!(profile.timing_window_adjust().is_identity());
+
+        profile.timing_window_scale = 75;
+        profile.timing_window_overrides.windows_ms[0] = Some(15);
+        let adjust = profile.timing_window_adjust();
+        assert_eq!(adjust.scale, 0.75);
+        assert_eq!(adjust.windows_ms[0], Some(15.0));
+        assert_eq!(adjust.mine_ms, None);
+    }
+
+    #[test]
+    fn replay_lane_map_follows_the_recorded_turn() {
+        assert_eq!(
+            replay_lane_map(deadsync_score::LocalTurnMod::Mirror, 0, 4),
+            Some(vec![3, 2, 1, 0])
+        );
+        assert_eq!(
+            replay_lane_map(deadsync_score::LocalTurnMod::Left, 0, 4),
+            Some(vec![1, 3, 0, 2])
+        );
+        assert_eq!(
+            replay_lane_map(deadsync_score::LocalTurnMod::Random, 7, 4),
+            None
+        );
+    }
+
+    #[test]
+    fn battery_life_config_carries_reward_lives() {
+        let mut profile = deadsync_profile::Profile::default();
+        assert_eq!(
+            life_config_from_profile(&profile),
+            deadsync_gameplay::CourseLifeConfig::Bar
+        );
+
+        profile.life_type = deadsync_profile::LifeType::Battery;
+        profile.battery_lives = 3;
+        profile.battery_reward_lives = 9;
+        assert_eq!(
+            life_config_from_profile(&profile),
+            deadsync_gameplay::CourseLifeConfig::Battery {
+                total_lives: 3,
+                reward_lives: u32::from(deadsync_profile::BATTERY_REWARD_LIVES_MAX),
+            }
+        );
+    }
 }
 
 pub fn itl_score_calc_input_from_runtime<RuntimeProfile, OverlayActor, CapturedActor, StateDelta>(
@@ -1910,6 +2066,9 @@ where
             .timing_for_player(player_idx)
             .map(|timing| timing.get_time_for_beat_ns(0.0))
             .unwrap_or(0),
+        scoring_model: profile.scoring_model.kind(),
+        turn_mod: local_turn_mod(profile.turn_option),
+        turn_seed: deadsync_gameplay::turn_seed_for_song(gs.song()),
         replay,
     }
 }
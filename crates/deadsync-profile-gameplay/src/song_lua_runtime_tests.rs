@@ -661,6 +661,38 @@ fn song_lua_confusion_offset_ease_scales_like_itgmania() {
     assert!(windows[0].to.abs() <= 0.000_1);
 }
 
+#[test]
+fn song_lua_live_windows_replace_matching_players_only() {
+    let live = |player, target, to| SongLuaEaseWindow {
+        player,
+        unit: SongLuaTimeUnit::Beat,
+        start: 4.0,
+        limit: 1.0,
+        span_mode: SongLuaSpanMode::Len,
+        target,
+        from: to,
+        to,
+        easing: None,
+        sustain: None,
+        opt1: None,
+        opt2: None,
+    };
+    let windows = [
+        live(None, SongLuaEaseTarget::PlayerX, 3.0),
+        live(Some(2), SongLuaEaseTarget::PlayerZ, 5.0),
+    ];
+    let mut out = [Vec::new(), Vec::new()];
+    super::song_lua_live_ease_windows(&windows, 2, &mut out);
+    assert_eq!(out[0].len(), 1);
+    assert_eq!(out[1].len(), 2);
+    assert!(matches!(out[1][1].target, SongLuaEaseMaskTarget::PlayerZ));
+    assert_eq!(song_lua_ease_window_value(&out[1][1], 1_000.0), Some(5.0));
+
+    super::song_lua_live_ease_windows(&windows, 1, &mut out);
+    assert!(matches!(out[0][0].target, SongLuaEaseMaskTarget::PlayerX));
+    assert!(out[1].is_empty());
+}
+
 #[test]
 fn confusion_offset_fixture_reaches_runtime_windows() {
     let root = deadsync_root();
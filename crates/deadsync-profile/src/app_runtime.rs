@@ -250,6 +250,14 @@ pub fn total_songs_played_for_side(side: PlayerSide) -> u32 {
     total_songs_played_for_id(&profile_id)
 }
 
+/// Local score files of the profile playing `side`, with their chart hashes.
+pub fn local_score_files_for_side(side: PlayerSide) -> Vec<(String, PathBuf)> {
+    let Some(profile_id) = crate::runtime_active_local_profile_id_for_side(side) else {
+        return Vec::new();
+    };
+    deadsync_score::local_score_files_in_root(&score_profile_paths_for_id(&profile_id).local_dir())
+}
+
 pub fn recent_played_chart_hashes_for_machine() -> Vec<String> {
     deadsync_score::runtime_recent_played_chart_hashes_for_machine(&profiles_root())
 }
@@ -265,6 +273,10 @@ pub fn recent_played_chart_hashes_for_id(profile_id: &str) -> Vec<String> {
     )
 }
 
+pub fn recent_play_ex_for_id(profile_id: &str) -> Vec<(String, f64)> {
+    deadsync_score::runtime_recent_play_ex_for_profile(profile_id, score_profile_paths_for_id)
+}
+
 pub fn played_chart_counts_for_id(profile_id: &str) -> Vec<(String, u32)> {
     deadsync_score::runtime_played_chart_counts_for_profile(profile_id, score_profile_paths_for_id)
 }
@@ -1738,6 +1750,17 @@ pub fn set_arrowcloud_api_key_for_side(side: PlayerSide, api_key: &str) {
     }
 }
 
+pub fn set_hud_layout_for_side(side: PlayerSide, layout: deadsync_theme::HudLayout) {
+    if let Some(error) = crate::runtime_set_hud_layout_for_side(
+        &profiles_root(),
+        side,
+        layout,
+        warn_duplicate_profile_guid,
+    ) {
+        warn!("Failed to save {}: {}", error.path.display(), error.error);
+    }
+}
+
 pub fn set_arrowcloud_api_key_for_id(profile_id: &str, api_key: &str) {
     if let Some(error) = crate::runtime_set_arrowcloud_api_key_for_id(
         &profiles_root(),
@@ -36,8 +36,8 @@ pub use crate::app_runtime::{
     itl_song_folders_unlocked_with_profiles,
     load_default_profiles_for_joined_sides_from_config as load_default_profiles_for_joined_sides,
     load_pad_configs, load_profiles_from_config as load, local_profile_dir_for_id,
-    local_score_profile_source_for_id, local_score_profile_sources, machine_common_player_options,
-    machine_leaderboard_local, machine_leaderboard_local_with_names,
+    local_score_files_for_side, local_score_profile_source_for_id, local_score_profile_sources,
+    machine_common_player_options, machine_leaderboard_local, machine_leaderboard_local_with_names,
     machine_leaderboard_local_without_names, machine_record_local, machine_replays_local,
     machine_scalar_record_local, mark_known_pack_names_for_local_profile, mark_pack_known,
     mark_packs_known, personal_leaderboard_local_for_side, played_chart_counts_for_id,
@@ -52,10 +52,10 @@ pub use crate::app_runtime::{
     set_active_profile_for_side_from_config as set_active_profile_for_side,
     set_active_profiles_from_config as set_active_profiles, set_arrowcloud_api_key_for_id,
     set_arrowcloud_api_key_for_side, set_cached_itl_file_for_id, set_default_pad_config,
-    set_groovestats_credentials_for_id, set_groovestats_credentials_for_side, should_warn_itl_cmod,
-    smx_gif_packs_from_config as smx_gif_packs, sync_known_packs, toggle_favorite,
-    toggle_pack_favorite, toggle_series_favorite, total_songs_played_for_id,
-    total_songs_played_for_side,
+    set_groovestats_credentials_for_id, set_groovestats_credentials_for_side,
+    set_hud_layout_for_side, should_warn_itl_cmod, smx_gif_packs_from_config as smx_gif_packs,
+    sync_known_packs, toggle_favorite, toggle_pack_favorite, toggle_series_favorite,
+    total_songs_played_for_id, total_songs_played_for_side,
     update_default_profile_for_side_from_config as set_default_profile_for_side,
     update_machine_default_background_filter, update_machine_default_light_brightness_from_config,
     update_machine_default_noteskin_from_config as update_machine_default_noteskin,
@@ -244,6 +244,18 @@ pub fn with_runtime_heart_rate_device_ids<R>(
     }))
 }
 
+/// Binding overlays of joined sides, read from the live profiles.
+pub fn with_runtime_input_bindings<R>(
+    read: impl FnOnce([Option<&deadsync_input::PlayerBindings>; PLAYER_SLOTS]) -> R,
+) -> R {
+    let joined = [PlayerSide::P1, PlayerSide::P2].map(runtime_session_side_joined);
+    let profiles = runtime_lock_profiles();
+    read(std::array::from_fn(|player| {
+        let bindings = &profiles[player].input_bindings;
+        (joined[player] && !bindings.is_empty()).then_some(bindings)
+    }))
+}
+
 /// Per-player configured maximum heart rate (bpm), read from the live profiles.
 /// Used to convert a monitor's bpm reading into a percentage-of-max color zone.
 pub fn runtime_max_heart_rates() -> [u16; PLAYER_SLOTS] {
@@ -597,6 +609,9 @@ pub fn runtime_apply_loaded_profile_data_for_side(
     arrowcloud_ini_loaded: bool,
     arrowcloud_get: impl FnMut(&str, &str) -> Option<String>,
     avatar_path: Option<PathBuf>,
+    scoring_windows: Option<Arc<deadsync_rules::scoring::CustomWindows>>,
+    hud_layout: deadsync_theme::HudLayout,
+    input_bindings: deadsync_input::PlayerBindings,
 ) {
     let play_style = runtime_session_play_style();
     let mut profiles = runtime_lock_profiles();
@@ -621,6 +636,9 @@ pub fn runtime_apply_loaded_profile_data_for_side(
     );
     profile.avatar_path = avatar_path;
     profile.avatar_texture_key = None;
+    profile.custom_scoring_windows = scoring_windows;
+    profile.hud_layout = hud_layout;
+    profile.input_bindings = input_bindings;
     let max_heart_rate_changed = profile.max_heart_rate != previous_max_heart_rate;
     drop(profiles);
     runtime_mark_favorites_changed();
@@ -663,6 +681,9 @@ pub fn runtime_load_profile_data_for_side(
         favorited_packs,
         favorited_series,
         avatar_path,
+        scoring_windows,
+        hud_layout,
+        input_bindings,
     } = load_profile_sidecars_dir(profile_dir, default_profile);
 
     runtime_apply_loaded_profile_data_for_side(
@@ -693,6 +714,9 @@ pub fn runtime_load_profile_data_for_side(
                 .and_then(|ini| ini.get(section, key))
         },
         avatar_path,
+        scoring_windows,
+        hud_layout,
+        input_bindings,
     );
 
     RuntimeProfileLoadReport {
@@ -1288,6 +1312,23 @@ pub fn runtime_set_arrowcloud_api_key_for_side(
     runtime_save_arrowcloud_api_key_for_side(root, side, duplicate)
 }
 
+pub fn runtime_set_hud_layout_for_side(
+    root: &Path,
+    side: PlayerSide,
+    layout: deadsync_theme::HudLayout,
+    duplicate: impl FnMut(&str, &Path, &Path, &Path),
+) -> Option<RuntimeProfileSidecarWriteError> {
+    runtime_lock_profiles()[player_side_index(side)].hud_layout = layout;
+    let profile_id = runtime_active_local_profile_id_for_side(side)?;
+    let dir = runtime_profile_dir_for_profile_id(root, &profile_id, duplicate);
+    write_hud_layout_dir(&dir, &layout)
+        .err()
+        .map(|error| RuntimeProfileSidecarWriteError {
+            path: hud_layout_path(&dir),
+            error,
+        })
+}
+
 pub fn runtime_set_arrowcloud_api_key_for_id(
     root: &Path,
     profile_id: &str,
@@ -3298,6 +3339,9 @@ pub const PROFILE_STATS_TMP_FILE: &str = "stats.bin.tmp";
 pub const FAVORITES_FILE: &str = "favorites.txt";
 pub const FAVORITED_PACKS_FILE: &str = "favorited_packs.txt";
 pub const FAVORITED_SERIES_FILE: &str = "favorited_series.txt";
+pub const SCORING_WINDOWS_FILE: &str = "scoring_windows.ini";
+pub const HUD_LAYOUT_FILE: &str = "hud_layout.ini";
+pub const INPUT_BINDINGS_FILE: &str = "input_bindings.ini";
 
 #[inline(always)]
 pub fn profile_ini_path(dir: &Path) -> PathBuf {
@@ -3339,6 +3383,21 @@ pub fn favorited_series_path(dir: &Path) -> PathBuf {
     dir.join(FAVORITED_SERIES_FILE)
 }
 
+#[inline(always)]
+pub fn scoring_windows_path(dir: &Path) -> PathBuf {
+    dir.join(SCORING_WINDOWS_FILE)
+}
+
+#[inline(always)]
+pub fn hud_layout_path(dir: &Path) -> PathBuf {
+    dir.join(HUD_LAYOUT_FILE)
+}
+
+#[inline(always)]
+pub fn input_bindings_path(dir: &Path) -> PathBuf {
+    dir.join(INPUT_BINDINGS_FILE)
+}
+
 /// Read the embedded `Guid` and `DisplayName` from a folder's `profile.ini`.
 pub fn read_profile_identity_dir(dir: &Path) -> (Option<String>, Option<String>) {
     match fs::read_to_string(profile_ini_path(dir)) {
@@ -5290,6 +5349,90 @@ impl core::fmt::Display for LifeMeterType {
     }
 }
 
+/// Which life rule gameplay uses outside Oni/Survival courses: the regular
+/// ITG bar, or ITGmania's battery (a fixed number of lives).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LifeType {
+    #[default]
+    Bar,
+    Battery,
+}
+
+impl FromStr for LifeType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        with_lower_option_key(s, |key| match key {
+            "" | "bar" => Ok(Self::Bar),
+            "battery" => Ok(Self::Battery),
+            other => Err(format!("'{other}' is not a valid LifeType setting")),
+        })
+    }
+}
+
+impl core::fmt::Display for LifeType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Bar => write!(f, "Bar"),
+            Self::Battery => write!(f, "Battery"),
+        }
+    }
+}
+
+/// Extra scoring model shown beside ITG on evaluation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScoringModel {
+    #[default]
+    Off,
+    DdrMoney,
+    Wife3,
+    /// Windows from the profile's `scoring_windows.ini`.
+    Custom,
+}
+
+impl ScoringModel {
+    pub const fn kind(self) -> Option<deadsync_rules::scoring::ScoringModelKind> {
+        match self {
+            Self::Off => None,
+            Self::DdrMoney => Some(deadsync_rules::scoring::ScoringModelKind::DdrMoney),
+            Self::Wife3 => Some(deadsync_rules::scoring::ScoringModelKind::Wife3),
+            Self::Custom => Some(deadsync_rules::scoring::ScoringModelKind::Custom),
+        }
+    }
+}
+
+impl FromStr for ScoringModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        with_compact_option_key(s, |key| match key {
+            "" | "off" | "none" => Ok(Self::Off),
+            "ddr" | "ddrmoney" | "money" => Ok(Self::DdrMoney),
+            "wife3" | "wife" => Ok(Self::Wife3),
+            "custom" => Ok(Self::Custom),
+            other => Err(format!("'{other}' is not a valid ScoringModel setting")),
+        })
+    }
+}
+
+impl core::fmt::Display for ScoringModel {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Off => write!(f, "Off"),
+            Self::DdrMoney => write!(f, "DDR"),
+            Self::Wife3 => write!(f, "Wife3"),
+            Self::Custom => write!(f, "Custom"),
+        }
+    }
+}
+
+pub const BATTERY_LIVES_MIN: u8 = 1;
+pub const BATTERY_LIVES_MAX: u8 = 10;
+// ITGmania PlayerOptions::m_BatteryLives default.
+pub const BATTERY_LIVES_DEFAULT: u8 = 4;
+pub const BATTERY_REWARD_LIVES_MIN: u8 = 0;
+pub const BATTERY_REWARD_LIVES_MAX: u8 = 5;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ErrorBarTrim {
     #[default]
@@ -5374,6 +5517,121 @@ impl core::fmt::Display for TimingWindowsOption {
     }
 }
 
+pub const TIMING_WINDOW_SCALE_MIN: u8 = 50;
+pub const TIMING_WINDOW_SCALE_MAX: u8 = 150;
+pub const TIMING_WINDOW_SCALE_DEFAULT: u8 = 100;
+pub const TIMING_WINDOW_SCALE_STEP: u8 = 5;
+pub const TIMING_WINDOW_OVERRIDE_MAX_MS: u8 = 180;
+
+#[inline(always)]
+pub const fn clamp_timing_window_scale(percent: u8) -> u8 {
+    if percent < TIMING_WINDOW_SCALE_MIN {
+        TIMING_WINDOW_SCALE_MIN
+    } else if percent > TIMING_WINDOW_SCALE_MAX {
+        TIMING_WINDOW_SCALE_MAX
+    } else {
+        percent
+    }
+}
+
+/// Absolute training overrides for W1..W5 and the mine window, in
+/// milliseconds. Persisted as `W1:15,Mine:60`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TimingWindowOverrides {
+    pub windows_ms: [Option<u8>; 5],
+    pub mine_ms: Option<u8>,
+}
+
+impl TimingWindowOverrides {
+    /// Override slots in W1..W5, Mine order.
+    pub const SLOTS: usize = 6;
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.windows_ms.iter().all(Option::is_none) && self.mine_ms.is_none()
+    }
+
+    #[inline(always)]
+    pub fn slot(&self, slot: usize) -> Option<u8> {
+        match slot {
+            0..=4 => self.windows_ms[slot],
+            _ => self.mine_ms,
+        }
+    }
+
+    /// Sets one slot; values outside `1..=TIMING_WINDOW_OVERRIDE_MAX_MS`
+    /// clear it.
+    pub fn set_slot(&mut self, slot: usize, ms: Option<u8>) {
+        let ms = ms.filter(|ms| (1..=TIMING_WINDOW_OVERRIDE_MAX_MS).contains(ms));
+        match slot {
+            0..=4 => self.windows_ms[slot] = ms,
+            _ => self.mine_ms = ms,
+        }
+    }
+
+    /// `(label, ms)` pairs in W1..W5, Mine order.
+    pub fn entries(&self) -> impl Iterator<Item = (&'static str, u8)> + '_ {
+        const LABELS: [&str; 5] = ["W1", "W2", "W3", "W4", "W5"];
+        LABELS
+            .into_iter()
+            .zip(self.windows_ms)
+            .chain(std::iter::once(("Mine", self.mine_ms)))
+            .filter_map(|(label, ms)| ms.map(|ms| (label, ms)))
+    }
+}
+
+impl FromStr for TimingWindowOverrides {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut overrides = Self::default();
+        let trimmed = s.trim();
+        if trimmed.is_empty() || trimmed.eq_ignore_ascii_case("none") {
+            return Ok(overrides);
+        }
+        for entry in trimmed.split(',') {
+            let Some((key, value)) = entry.split_once(':') else {
+                return Err(format!(
+                    "'{entry}' is not a valid TimingWindowOverrides entry"
+                ));
+            };
+            let ms = value
+                .trim()
+                .trim_end_matches("ms")
+                .parse::<u8>()
+                .ok()
+                .filter(|ms| (1..=TIMING_WINDOW_OVERRIDE_MAX_MS).contains(ms))
+                .ok_or_else(|| format!("'{value}' is not a valid timing window in ms"))?;
+            let slot = match key.trim().to_ascii_lowercase().as_str() {
+                "w1" => &mut overrides.windows_ms[0],
+                "w2" => &mut overrides.windows_ms[1],
+                "w3" => &mut overrides.windows_ms[2],
+                "w4" => &mut overrides.windows_ms[3],
+                "w5" => &mut overrides.windows_ms[4],
+                "mine" | "mines" => &mut overrides.mine_ms,
+                other => return Err(format!("'{other}' is not a valid timing window")),
+            };
+            *slot = Some(ms);
+        }
+        Ok(overrides)
+    }
+}
+
+impl core::fmt::Display for TimingWindowOverrides {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "None");
+        }
+        for (i, (label, ms)) in self.entries().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{label}:{ms}")?;
+        }
+        Ok(())
+    }
+}
+
 bitflags! {
     /// Persisted bitmask of enabled Step Statistics gameplay widgets.
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
@@ -6215,6 +6473,12 @@ pub fn evaluation_mods_text(profile: &Profile, speed_mod: ScrollSpeedSetting) ->
             .join("/");
         parts.push(format!("No {windows}"));
     }
+    if profile.timing_window_scale != TIMING_WINDOW_SCALE_DEFAULT {
+        parts.push(format!("{}% Windows", profile.timing_window_scale));
+    }
+    for (label, ms) in profile.timing_window_overrides.entries() {
+        parts.push(format!("{label} {ms}ms"));
+    }
     parts.push(profile.noteskin.to_string());
     Arc::<str>::from(parts.join(", "))
 }
@@ -6945,6 +7209,60 @@ pub fn load_favorited_series_dir(dir: &Path) -> HashSet<String> {
     parse_favorited_packs_content(&text)
 }
 
+pub fn load_scoring_windows_dir(dir: &Path) -> Option<Arc<deadsync_rules::scoring::CustomWindows>> {
+    let path = scoring_windows_path(dir);
+    let text = fs::read_to_string(&path).ok()?;
+    match deadsync_rules::scoring::parse_custom_windows(&text) {
+        Ok(windows) => Some(Arc::new(windows)),
+        Err(e) => {
+            log::warn!("Ignoring scoring windows '{}': {e}", path.display());
+            None
+        }
+    }
+}
+
+/// A missing or unreadable layout falls back to theme placement.
+pub fn load_hud_layout_dir(dir: &Path) -> deadsync_theme::HudLayout {
+    let path = hud_layout_path(dir);
+    let Ok(text) = fs::read_to_string(&path) else {
+        return deadsync_theme::HudLayout::IDENTITY;
+    };
+    deadsync_theme::parse_hud_layout(&text).unwrap_or_else(|e| {
+        log::warn!("Ignoring HUD layout '{}': {e}", path.display());
+        deadsync_theme::HudLayout::IDENTITY
+    })
+}
+
+/// Removes the file for an untouched layout so theme updates keep applying.
+pub fn write_hud_layout_dir(dir: &Path, layout: &deadsync_theme::HudLayout) -> std::io::Result<()> {
+    let path = hud_layout_path(dir);
+    if layout.is_identity() {
+        return match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+    fs::create_dir_all(dir)?;
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, layout.to_ini())?;
+    fs::rename(&tmp_path, &path)
+}
+
+/// Reads the `[Keymaps]` overlay; a missing file leaves the machine keymap alone.
+pub fn load_input_bindings_dir(dir: &Path) -> deadsync_input::PlayerBindings {
+    let Ok(ini) = ProfileIni::load(&input_bindings_path(dir)) else {
+        return deadsync_input::PlayerBindings::default();
+    };
+    ini.sections
+        .get("Keymaps")
+        .map(|section| {
+            deadsync_input::PlayerBindings::from_ini_entries(
+                section.iter().map(|(k, v)| (k.as_str(), v.as_str())),
+            )
+        })
+        .unwrap_or_default()
+}
+
 #[derive(Debug)]
 pub struct ProfileSidecarLoadData {
     pub stats: ProfileStats,
@@ -6953,6 +7271,9 @@ pub struct ProfileSidecarLoadData {
     pub favorited_packs: HashSet<String>,
     pub favorited_series: HashSet<String>,
     pub avatar_path: Option<PathBuf>,
+    pub scoring_windows: Option<Arc<deadsync_rules::scoring::CustomWindows>>,
+    pub hud_layout: deadsync_theme::HudLayout,
+    pub input_bindings: deadsync_input::PlayerBindings,
 }
 
 pub fn load_profile_sidecars_dir(dir: &Path, default_profile: &Profile) -> ProfileSidecarLoadData {
@@ -6981,6 +7302,9 @@ pub fn load_profile_sidecars_dir(dir: &Path, default_profile: &Profile) -> Profi
         favorited_packs: load_favorited_packs_dir(dir),
         favorited_series: load_favorited_series_dir(dir),
         avatar_path: find_profile_avatar_path(dir),
+        scoring_windows: load_scoring_windows_dir(dir),
+        hud_layout: load_hud_layout_dir(dir),
+        input_bindings: load_input_bindings_dir(dir),
     }
 }
 
@@ -7169,6 +7493,8 @@ pub struct PlayerOptionsData {
     pub hide_early_dw_flash: bool,
     pub hide_early_dw_column_flash: bool,
     pub timing_windows: TimingWindowsOption,
+    pub timing_window_scale: u8,
+    pub timing_window_overrides: TimingWindowOverrides,
     pub show_fa_plus_window: bool,
     pub show_ex_score: bool,
     pub show_hard_ex_score: bool,
@@ -7233,6 +7559,12 @@ pub struct PlayerOptionsData {
     pub target_score_percent: u8,
     pub target_score_miss_policy: TargetScoreMissPolicy,
     pub lifemeter_type: LifeMeterType,
+    pub life_type: LifeType,
+    /// Starting lives for `LifeType::Battery` (`[1, 10]`).
+    pub battery_lives: u8,
+    /// Lives `LifeType::Battery` gains back after each cleared stage (`[0, 5]`).
+    pub battery_reward_lives: u8,
+    pub scoring_model: ScoringModel,
     pub measure_counter: MeasureCounter,
     pub measure_counter_lookahead: u8,
     pub measure_counter_left: bool,
@@ -7315,6 +7647,8 @@ fn default_player_options() -> PlayerOptionsData {
         hide_early_dw_flash: false,
         hide_early_dw_column_flash: false,
         timing_windows: TimingWindowsOption::default(),
+        timing_window_scale: TIMING_WINDOW_SCALE_DEFAULT,
+        timing_window_overrides: TimingWindowOverrides::default(),
         show_fa_plus_window: false,
         show_ex_score: false,
         show_hard_ex_score: false,
@@ -7371,6 +7705,10 @@ fn default_player_options() -> PlayerOptionsData {
         target_score_percent: 100,
         target_score_miss_policy: TargetScoreMissPolicy::default(),
         lifemeter_type: LifeMeterType::default(),
+        life_type: LifeType::default(),
+        battery_lives: BATTERY_LIVES_DEFAULT,
+        battery_reward_lives: BATTERY_REWARD_LIVES_MIN,
+        scoring_model: ScoringModel::default(),
         measure_counter: MeasureCounter::default(),
         measure_counter_lookahead: 2,
         measure_counter_left: true,
@@ -7928,6 +8266,22 @@ where
     options.lifemeter_type = get("LifeMeterType")
         .and_then(|s| LifeMeterType::from_str(&s).ok())
         .unwrap_or(options.lifemeter_type);
+    options.life_type = get("LifeType")
+        .and_then(|s| LifeType::from_str(&s).ok())
+        .unwrap_or(options.life_type);
+    options.battery_lives = get("BatteryLives")
+        .and_then(|s| s.parse::<u8>().ok())
+        .map_or(options.battery_lives, |v| {
+            v.clamp(BATTERY_LIVES_MIN, BATTERY_LIVES_MAX)
+        });
+    options.battery_reward_lives = get("BatteryRewardLives")
+        .and_then(|s| s.parse::<u8>().ok())
+        .map_or(options.battery_reward_lives, |v| {
+            v.clamp(BATTERY_REWARD_LIVES_MIN, BATTERY_REWARD_LIVES_MAX)
+        });
+    options.scoring_model = get("ScoringModel")
+        .and_then(|s| ScoringModel::from_str(&s).ok())
+        .unwrap_or(options.scoring_model);
     options.measure_counter = get("MeasureCounter")
         .and_then(|s| MeasureCounter::from_str(&s).ok())
         .unwrap_or(options.measure_counter);
@@ -8006,6 +8360,12 @@ where
     options.timing_windows = get("TimingWindows")
         .and_then(|s| TimingWindowsOption::from_str(&s).ok())
         .unwrap_or(options.timing_windows);
+    options.timing_window_scale = get("TimingWindowScale")
+        .and_then(|s| s.parse::<u8>().ok())
+        .map_or(options.timing_window_scale, clamp_timing_window_scale);
+    options.timing_window_overrides = get("TimingWindowOverrides")
+        .and_then(|s| TimingWindowOverrides::from_str(&s).ok())
+        .unwrap_or(options.timing_window_overrides);
     options.hide_targets = load_u8_bool(&mut get, "HideTargets", options.hide_targets);
     options.hide_song_bg = load_u8_bool(&mut get, "HideSongBG", options.hide_song_bg);
     options.hide_combo = load_u8_bool(&mut get, "HideCombo", options.hide_combo);
@@ -8152,6 +8512,14 @@ pub fn append_player_options_section(
         i32::from(options.hide_early_dw_column_flash)
     ));
     content.push_str(&format!("TimingWindows={}\n", options.timing_windows));
+    content.push_str(&format!(
+        "TimingWindowScale={}\n",
+        options.timing_window_scale
+    ));
+    content.push_str(&format!(
+        "TimingWindowOverrides={}\n",
+        options.timing_window_overrides
+    ));
     content.push_str(&format!(
         "HideTargets={}\n",
         i32::from(options.hide_targets)
@@ -8464,6 +8832,13 @@ pub fn append_player_options_section(
         options.target_score_miss_policy
     ));
     content.push_str(&format!("LifeMeterType={}\n", options.lifemeter_type));
+    content.push_str(&format!("LifeType={}\n", options.life_type));
+    content.push_str(&format!("BatteryLives={}\n", options.battery_lives));
+    content.push_str(&format!(
+        "BatteryRewardLives={}\n",
+        options.battery_reward_lives
+    ));
+    content.push_str(&format!("ScoringModel={}\n", options.scoring_model));
     content.push_str(&format!("MeasureCounter={}\n", options.measure_counter));
     content.push_str(&format!(
         "MeasureCounterLookahead={}\n",
@@ -8809,6 +9184,12 @@ pub struct Profile {
     pub tap_explosion_active_mask: TapExplosionMask,
     pub avatar_path: Option<PathBuf>,
     pub avatar_texture_key: Option<String>,
+    /// Parsed `scoring_windows.ini`, used by `ScoringModel::Custom`.
+    pub custom_scoring_windows: Option<Arc<deadsync_rules::scoring::CustomWindows>>,
+    /// Parsed `hud_layout.ini`; clamped to the theme's limits at gameplay start.
+    pub hud_layout: deadsync_theme::HudLayout,
+    /// Parsed `input_bindings.ini`, layered over the machine keymap while joined.
+    pub input_bindings: deadsync_input::PlayerBindings,
     pub scroll_speed: ScrollSpeedSetting,
     pub no_cmod_alternative: NoCmodAlternative,
     pub scroll_option: ScrollOption,
@@ -8831,6 +9212,9 @@ pub struct Profile {
     pub hide_early_dw_flash: bool,
     pub hide_early_dw_column_flash: bool,
     pub timing_windows: TimingWindowsOption,
+    /// Training scale, in percent, applied to every timing window.
+    pub timing_window_scale: u8,
+    pub timing_window_overrides: TimingWindowOverrides,
     // FA+ visual options (Simply Love semantics).
     // These do not change core timing semantics; they only affect HUD/UX.
     pub show_fa_plus_window: bool,
@@ -8915,6 +9299,10 @@ pub struct Profile {
     pub target_score_percent: u8,
     pub target_score_miss_policy: TargetScoreMissPolicy,
     pub lifemeter_type: LifeMeterType,
+    pub life_type: LifeType,
+    pub battery_lives: u8,
+    pub battery_reward_lives: u8,
+    pub scoring_model: ScoringModel,
     pub measure_counter: MeasureCounter,
     pub measure_counter_lookahead: u8,
     pub measure_counter_left: bool,
@@ -9030,6 +9418,9 @@ impl Default for Profile {
             tap_explosion_active_mask: player_options.tap_explosion_active_mask,
             avatar_path: None,
             avatar_texture_key: None,
+            custom_scoring_windows: None,
+            hud_layout: deadsync_theme::HudLayout::IDENTITY,
+            input_bindings: deadsync_input::PlayerBindings::default(),
             scroll_speed: player_options.scroll_speed,
             no_cmod_alternative: player_options.no_cmod_alternative,
             scroll_option: player_options.scroll_option,
@@ -9048,6 +9439,8 @@ impl Default for Profile {
             hide_early_dw_flash: player_options.hide_early_dw_flash,
             hide_early_dw_column_flash: player_options.hide_early_dw_column_flash,
             timing_windows: player_options.timing_windows,
+            timing_window_scale: player_options.timing_window_scale,
+            timing_window_overrides: player_options.timing_window_overrides,
             show_fa_plus_window: player_options.show_fa_plus_window,
             show_ex_score: player_options.show_ex_score,
             show_hard_ex_score: player_options.show_hard_ex_score,
@@ -9104,6 +9497,10 @@ impl Default for Profile {
             target_score_percent: player_options.target_score_percent,
             target_score_miss_policy: player_options.target_score_miss_policy,
             lifemeter_type: player_options.lifemeter_type,
+            life_type: player_options.life_type,
+            battery_lives: player_options.battery_lives,
+            battery_reward_lives: player_options.battery_reward_lives,
+            scoring_model: player_options.scoring_model,
             measure_counter: player_options.measure_counter,
             measure_counter_lookahead: player_options.measure_counter_lookahead,
             measure_counter_left: player_options.measure_counter_left,
@@ -9490,6 +9887,28 @@ impl Profile {
         set_value_if_changed(&mut self.timing_windows, setting)
     }
 
+    pub fn set_timing_window_scale(&mut self, percent: u8) -> bool {
+        set_value_if_changed(
+            &mut self.timing_window_scale,
+            clamp_timing_window_scale(percent),
+        )
+    }
+
+    pub fn set_timing_window_overrides(&mut self, overrides: TimingWindowOverrides) -> bool {
+        let mut sanitized = TimingWindowOverrides::default();
+        for slot in 0..TimingWindowOverrides::SLOTS {
+            sanitized.set_slot(slot, overrides.slot(slot));
+        }
+        set_value_if_changed(&mut self.timing_window_overrides, sanitized)
+    }
+
+    /// Whether training windows replace the stock ITG timing for this player.
+    #[inline(always)]
+    pub fn custom_timing_windows_active(&self) -> bool {
+        self.timing_window_scale != TIMING_WINDOW_SCALE_DEFAULT
+            || !self.timing_window_overrides.is_empty()
+    }
+
     pub fn set_perspective(&mut self, setting: Perspective) -> bool {
         set_value_if_changed(&mut self.perspective, setting)
     }
@@ -9874,6 +10293,28 @@ impl Profile {
         set_value_if_changed(&mut self.lifemeter_type, setting)
     }
 
+    pub fn set_life_type(&mut self, setting: LifeType) -> bool {
+        set_value_if_changed(&mut self.life_type, setting)
+    }
+
+    pub fn set_battery_lives(&mut self, lives: u8) -> bool {
+        set_value_if_changed(
+            &mut self.battery_lives,
+            lives.clamp(BATTERY_LIVES_MIN, BATTERY_LIVES_MAX),
+        )
+    }
+
+    pub fn set_battery_reward_lives(&mut self, lives: u8) -> bool {
+        set_value_if_changed(
+            &mut self.battery_reward_lives,
+            lives.clamp(BATTERY_REWARD_LIVES_MIN, BATTERY_REWARD_LIVES_MAX),
+        )
+    }
+
+    pub fn set_scoring_model(&mut self, setting: ScoringModel) -> bool {
+        set_value_if_changed(&mut self.scoring_model, setting)
+    }
+
     pub fn set_measure_counter(&mut self, setting: MeasureCounter) -> bool {
         set_value_if_changed(&mut self.measure_counter, setting)
     }
@@ -9984,6 +10425,8 @@ impl Profile {
             hide_early_dw_flash: self.hide_early_dw_flash,
             hide_early_dw_column_flash: self.hide_early_dw_column_flash,
             timing_windows: self.timing_windows,
+            timing_window_scale: self.timing_window_scale,
+            timing_window_overrides: self.timing_window_overrides,
             show_fa_plus_window: self.show_fa_plus_window,
             show_ex_score: self.show_ex_score,
             show_hard_ex_score: self.show_hard_ex_score,
@@ -10040,6 +10483,10 @@ impl Profile {
             target_score_percent: self.target_score_percent,
             target_score_miss_policy: self.target_score_miss_policy,
             lifemeter_type: self.lifemeter_type,
+            life_type: self.life_type,
+            battery_lives: self.battery_lives,
+            battery_reward_lives: self.battery_reward_lives,
+            scoring_model: self.scoring_model,
             measure_counter: self.measure_counter,
             measure_counter_lookahead: self.measure_counter_lookahead,
             measure_counter_left: self.measure_counter_left,
@@ -10124,6 +10571,8 @@ impl Profile {
         self.hide_early_dw_flash = options.hide_early_dw_flash;
         self.hide_early_dw_column_flash = options.hide_early_dw_column_flash;
         self.timing_windows = options.timing_windows;
+        self.timing_window_scale = options.timing_window_scale;
+        self.timing_window_overrides = options.timing_window_overrides;
         self.show_fa_plus_window = options.show_fa_plus_window;
         self.show_ex_score = options.show_ex_score;
         self.show_hard_ex_score = options.show_hard_ex_score;
@@ -10180,6 +10629,10 @@ impl Profile {
         self.target_score_percent = options.target_score_percent;
         self.target_score_miss_policy = options.target_score_miss_policy;
         self.lifemeter_type = options.lifemeter_type;
+        self.life_type = options.life_type;
+        self.battery_lives = options.battery_lives;
+        self.battery_reward_lives = options.battery_reward_lives;
+        self.scoring_model = options.scoring_model;
         self.measure_counter = options.measure_counter;
         self.measure_counter_lookahead = options.measure_counter_lookahead;
         self.measure_counter_left = options.measure_counter_left;
@@ -12321,6 +12774,9 @@ mod tests {
         assert!(sidecars.favorited_packs.is_empty());
         assert!(sidecars.favorited_series.is_empty());
         assert!(sidecars.avatar_path.is_none());
+        assert!(sidecars.scoring_windows.is_none());
+        assert!(sidecars.hud_layout.is_identity());
+        assert!(sidecars.input_bindings.is_empty());
 
         let _ = fs::remove_dir_all(dir);
     }
@@ -12343,6 +12799,19 @@ mod tests {
         save_favorited_packs_dir(&dir, &packs);
         save_favorited_series_dir(&dir, &series);
         fs::write(&avatar, b"avatar").expect("avatar should write");
+        fs::write(
+            scoring_windows_path(&dir),
+            "Name=Tight\nWindow=Perfect,15,3\n",
+        )
+        .expect("scoring windows should write");
+        let mut layout = deadsync_theme::HudLayout::IDENTITY;
+        layout.get_mut(deadsync_theme::HudElement::ErrorBar).offset = [0.0, -20.0];
+        write_hud_layout_dir(&dir, &layout).expect("HUD layout should write");
+        fs::write(
+            input_bindings_path(&dir),
+            "[Keymaps]\nUp=KeyCode::KeyI\nP2_Start=KeyCode::Enter\n",
+        )
+        .expect("input bindings should write");
 
         let sidecars = load_profile_sidecars_dir(&dir, &default_profile);
 
@@ -12352,6 +12821,28 @@ mod tests {
         assert_eq!(sidecars.favorited_packs, packs);
         assert_eq!(sidecars.favorited_series, series);
         assert_eq!(sidecars.avatar_path, Some(avatar));
+        assert_eq!(
+            sidecars.scoring_windows.as_ref().map(|w| w.name.as_str()),
+            Some("Tight")
+        );
+        assert_eq!(sidecars.hud_layout, layout);
+        let up = deadsync_input::InputBinding::Key(deadsync_input::KeyCode::KeyI);
+        assert_eq!(
+            sidecars
+                .input_bindings
+                .bindings(deadsync_input::VirtualAction::p2_up),
+            Some(&[up][..])
+        );
+        assert!(
+            sidecars
+                .input_bindings
+                .bindings(deadsync_input::VirtualAction::p1_start)
+                .is_some()
+        );
+
+        write_hud_layout_dir(&dir, &deadsync_theme::HudLayout::IDENTITY)
+            .expect("default HUD layout should clear");
+        assert!(!hud_layout_path(&dir).exists());
 
         let _ = fs::remove_dir_all(dir);
     }
@@ -12834,6 +13325,10 @@ mod tests {
             ("StepStatsExtra", "CatJAM"),
             ("TargetScore", "A"),
             ("LifeMeterType", "Vertical"),
+            ("LifeType", "Battery"),
+            ("BatteryLives", "25"),
+            ("BatteryRewardLives", "2"),
+            ("ScoringModel", "Wife3"),
             ("MeasureCounter", "16th"),
             ("MeasureCounterLookahead", "9"),
             ("MeasureCounterLeft", "1"),
@@ -12857,6 +13352,8 @@ mod tests {
             ("HideEarlyDecentWayOffFlash", "1"),
             ("HideEarlyDecentWayOffColumnFlash", "1"),
             ("TimingWindows", "WayOffs"),
+            ("TimingWindowScale", "200"),
+            ("TimingWindowOverrides", "W1:15, Mine:60ms"),
             ("HideTargets", "1"),
             ("HideSongBG", "1"),
             ("HideCombo", "1"),
@@ -12907,6 +13404,10 @@ mod tests {
         assert_eq!(options.step_stats_extra, StepStatsExtra::gif("CatJAM"));
         assert_eq!(options.target_score, TargetScoreSetting::A);
         assert_eq!(options.lifemeter_type, LifeMeterType::Vertical);
+        assert_eq!(options.life_type, LifeType::Battery);
+        assert_eq!(options.battery_lives, BATTERY_LIVES_MAX);
+        assert_eq!(options.battery_reward_lives, 2);
+        assert_eq!(options.scoring_model, ScoringModel::Wife3);
         assert_eq!(options.measure_counter, MeasureCounter::Sixteenth);
         assert_eq!(options.measure_counter_lookahead, 4);
         assert!(options.measure_counter_left);
@@ -12930,6 +13431,14 @@ mod tests {
         assert!(options.hide_early_dw_flash);
         assert!(options.hide_early_dw_column_flash);
         assert_eq!(options.timing_windows, TimingWindowsOption::WayOffs);
+        assert_eq!(options.timing_window_scale, TIMING_WINDOW_SCALE_MAX);
+        assert_eq!(
+            options.timing_window_overrides,
+            TimingWindowOverrides {
+                windows_ms: [Some(15), None, None, None, None],
+                mine_ms: Some(60),
+            }
+        );
         assert!(options.hide_targets);
         assert!(options.hide_song_bg);
         assert!(options.hide_combo);
@@ -14519,6 +15028,32 @@ ApiKey = gs-key
         assert!(LifeMeterType::from_str("horizontal").is_err());
     }
 
+    #[test]
+    fn life_type_round_trips() {
+        for setting in [LifeType::Bar, LifeType::Battery] {
+            assert_eq!(setting.to_string().parse::<LifeType>(), Ok(setting));
+        }
+        assert_eq!(LifeType::from_str(""), Ok(LifeType::Bar));
+        assert!(LifeType::from_str("time").is_err());
+    }
+
+    #[test]
+    fn scoring_model_round_trips() {
+        for setting in [
+            ScoringModel::Off,
+            ScoringModel::DdrMoney,
+            ScoringModel::Wife3,
+            ScoringModel::Custom,
+        ] {
+            assert_eq!(setting.to_string().parse::<ScoringModel>(), Ok(setting));
+        }
+        assert_eq!(
+            ScoringModel::from_str("ddr money"),
+            Ok(ScoringModel::DdrMoney)
+        );
+        assert!(ScoringModel::from_str("osu").is_err());
+    }
+
     #[test]
     fn error_bar_trim_round_trips() {
         for setting in [
@@ -14566,6 +15101,36 @@ ApiKey = gs-key
         assert!(TimingWindowsOption::from_str("misses").is_err());
     }
 
+    #[test]
+    fn timing_window_overrides_round_trip_and_flag_custom_timing() {
+        let overrides = TimingWindowOverrides {
+            windows_ms: [Some(15), None, Some(80), None, None],
+            mine_ms: Some(60),
+        };
+        assert_eq!(overrides.to_string(), "W1:15,W3:80,Mine:60");
+        assert_eq!(overrides.to_string().parse(), Ok(overrides));
+        assert_eq!(
+            TimingWindowOverrides::from_str("None"),
+            Ok(TimingWindowOverrides::default())
+        );
+        assert!(TimingWindowOverrides::from_str("W6:10").is_err());
+        assert!(TimingWindowOverrides::from_str("W1:0").is_err());
+
+        let mut profile = Profile::default();
+        assert!(!profile.custom_timing_windows_active());
+        assert!(profile.set_timing_window_scale(75));
+        assert!(profile.custom_timing_windows_active());
+        assert!(profile.set_timing_window_overrides(overrides));
+        assert!(!profile.set_timing_window_overrides(overrides));
+        let mut out_of_range = overrides;
+        out_of_range.windows_ms[1] = Some(TIMING_WINDOW_OVERRIDE_MAX_MS + 1);
+        assert!(!profile.set_timing_window_overrides(out_of_range));
+        assert_eq!(
+            evaluation_mods_text(&profile, ScrollSpeedSetting::XMod(1.0)).as_ref(),
+            "X1.00, Overhead, 75% Windows, W1 15ms, W3 80ms, Mine 60ms, cel"
+        );
+    }
+
     #[test]
     fn step_statistics_mask_round_trips_and_accepts_legacy_aliases() {
         let mask = StepStatisticsMask::DENSITY_GRAPH
@@ -2,12 +2,12 @@ use crate::{
     AccelEffectsMask, AppearanceEffectsMask, AttackMode, ColumnFlashBrightness, ColumnFlashMask,
     ColumnFlashSize, ComboColors, ComboFont, ComboMode, ErrorBarMask, ErrorBarTrim,
     HeldMissGraphic, HideLightType, HoldJudgmentGraphic, HoldsMask, InsertMask, JudgmentGraphic,
-    LifeMeterType, LiveTimingStatsMask, MeasureCounter, MeasureLines, MiniIndicator,
+    LifeMeterType, LifeType, LiveTimingStatsMask, MeasureCounter, MeasureLines, MiniIndicator,
     MiniIndicatorColor, MiniIndicatorPosition, MiniIndicatorScoreType, MiniIndicatorSize,
     MiniIndicatorSubtractiveDisplay, NoCmodAlternative, NoteSkin, Perspective, PlayStyle,
     PlayerSide, Profile, RemoveMask, ScatterplotMaxWindow, ScoreDisplayMode, ScorePosition,
-    ScrollOption, StepStatisticsMask, StepStatsExtra, TapExplosionMask, TargetScoreSetting,
-    TimingWindowsOption, TurnOption, VisualEffectsMask,
+    ScoringModel, ScrollOption, StepStatisticsMask, StepStatsExtra, TapExplosionMask,
+    TargetScoreSetting, TimingWindowOverrides, TimingWindowsOption, TurnOption, VisualEffectsMask,
     app_runtime::{save_profile_ini_for_side, save_profile_stats_for_side},
     runtime_mark_heart_rate_devices_changed, runtime_mark_max_heart_rates_changed,
     runtime_session_side_guest, runtime_update_profile_for_side,
@@ -221,6 +221,16 @@ pub fn update_timing_windows_for_side(side: PlayerSide, setting: TimingWindowsOp
     profile_ini_update(side, |profile| profile.set_timing_windows(setting))
 }
 
+pub fn update_timing_window_scale_for_side(side: PlayerSide, percent: u8) {
+    profile_ini_update(side, |profile| profile.set_timing_window_scale(percent))
+}
+
+pub fn update_timing_window_overrides_for_side(side: PlayerSide, overrides: TimingWindowOverrides) {
+    profile_ini_update(side, |profile| {
+        profile.set_timing_window_overrides(overrides)
+    })
+}
+
 pub fn update_hide_options_for_side(
     side: PlayerSide,
     hide_targets: bool,
@@ -619,6 +629,22 @@ pub fn update_lifemeter_type_for_side(side: PlayerSide, setting: LifeMeterType)
     profile_ini_update(side, |profile| profile.set_lifemeter_type(setting))
 }
 
+pub fn update_life_type_for_side(side: PlayerSide, setting: LifeType) {
+    profile_ini_update(side, |profile| profile.set_life_type(setting))
+}
+
+pub fn update_battery_lives_for_side(side: PlayerSide, lives: u8) {
+    profile_ini_update(side, |profile| profile.set_battery_lives(lives))
+}
+
+pub fn update_battery_reward_lives_for_side(side: PlayerSide, lives: u8) {
+    profile_ini_update(side, |profile| profile.set_battery_reward_lives(lives))
+}
+
+pub fn update_scoring_model_for_side(side: PlayerSide, setting: ScoringModel) {
+    profile_ini_update(side, |profile| profile.set_scoring_model(setting))
+}
+
 pub fn update_error_bar_options_for_side(side: PlayerSide, up: bool, multi_tick: bool) {
     profile_ini_update(side, |profile| {
         profile.set_error_bar_options(up, multi_tick)
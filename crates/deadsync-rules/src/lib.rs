@@ -2,6 +2,7 @@ pub mod combo;
 pub mod judgment;
 pub mod life;
 pub mod note;
+pub mod scoring;
 pub mod scroll;
 pub mod stream;
 pub mod timing;
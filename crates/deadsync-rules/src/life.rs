@@ -20,6 +20,26 @@ pub const LIFE_HIT_MINE: f32 = -0.050;
 pub const LIFE_HELD: f32 = 0.008;
 pub const LIFE_LET_GO: f32 = -0.080;
 
+// ITGmania LifeMeterBattery: Decent and worse, hit mines and dropped holds each
+// cost one life; Fantastic through Great are free.
+pub const BATTERY_HIT_MINE_LIVES_LOST: u32 = 1;
+pub const BATTERY_LET_GO_LIVES_LOST: u32 = 1;
+
+// ITGmania LifeMeterTime (Survival courses): life is seconds remaining, drained
+// in real time and topped up by each stage's #GAINSECONDS. The bar shows the
+// remainder against a fixed full-life duration.
+pub const SURVIVAL_FULL_LIFE_SECONDS: f32 = 90.0;
+pub const SURVIVAL_MIN_GAIN_SECONDS: f32 = 15.0;
+pub const SURVIVAL_FANTASTIC: f32 = 0.2;
+pub const SURVIVAL_EXCELLENT: f32 = 0.0;
+pub const SURVIVAL_GREAT: f32 = -0.5;
+pub const SURVIVAL_DECENT: f32 = -1.0;
+pub const SURVIVAL_WAY_OFF: f32 = -2.0;
+pub const SURVIVAL_MISS: f32 = -4.0;
+pub const SURVIVAL_HIT_MINE: f32 = -2.0;
+pub const SURVIVAL_HELD: f32 = 0.0;
+pub const SURVIVAL_LET_GO: f32 = -4.0;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct LifeMeter {
     pub life: f32,
@@ -66,6 +86,65 @@ pub const fn judge_life_delta(grade: JudgeGrade) -> f32 {
     }
 }
 
+#[inline(always)]
+pub const fn judge_battery_lives_lost(grade: JudgeGrade) -> u32 {
+    match grade {
+        JudgeGrade::Fantastic | JudgeGrade::Excellent | JudgeGrade::Great => 0,
+        JudgeGrade::Decent | JudgeGrade::WayOff | JudgeGrade::Miss => 1,
+    }
+}
+
+/// Map remaining battery lives onto the 0..1 life bar.
+#[inline(always)]
+pub fn battery_life(lives: u32, total_lives: u32) -> f32 {
+    (lives as f32 / total_lives.max(1) as f32).clamp(0.0, 1.0)
+}
+
+#[inline(always)]
+pub const fn judge_survival_seconds(grade: JudgeGrade) -> f32 {
+    match grade {
+        JudgeGrade::Fantastic => SURVIVAL_FANTASTIC,
+        JudgeGrade::Excellent => SURVIVAL_EXCELLENT,
+        JudgeGrade::Great => SURVIVAL_GREAT,
+        JudgeGrade::Decent => SURVIVAL_DECENT,
+        JudgeGrade::WayOff => SURVIVAL_WAY_OFF,
+        JudgeGrade::Miss => SURVIVAL_MISS,
+    }
+}
+
+/// Map remaining survival seconds onto the 0..1 life bar.
+#[inline(always)]
+pub fn survival_life(seconds: f32) -> f32 {
+    (seconds / SURVIVAL_FULL_LIFE_SECONDS).clamp(0.0, 1.0)
+}
+
+/// Seconds a survival stage starts with: whatever the previous stage left over
+/// plus the stage's `#GAINSECONDS`, never less than the ITGmania minimum.
+#[inline(always)]
+pub fn survival_start_seconds(carried_seconds: f32, gain_seconds: f32) -> f32 {
+    let carried_seconds = if carried_seconds.is_finite() {
+        carried_seconds.max(0.0)
+    } else {
+        0.0
+    };
+    let gain_seconds = if gain_seconds.is_finite() {
+        gain_seconds.max(SURVIVAL_MIN_GAIN_SECONDS)
+    } else {
+        SURVIVAL_MIN_GAIN_SECONDS
+    };
+    carried_seconds + gain_seconds
+}
+
+/// Apply a judgment bonus/penalty or a real-time drain (negative `change`) to a
+/// survival clock. The clock never goes below zero.
+#[inline(always)]
+pub fn apply_survival_seconds(remaining_seconds: f32, change: f32) -> f32 {
+    if !change.is_finite() {
+        return remaining_seconds.max(0.0);
+    }
+    (remaining_seconds + change).max(0.0)
+}
+
 /// Record ITGmania-compatible life samples, retaining each plateau endpoint
 /// and shifting the pre-change value when two samples share a timestamp.
 #[inline]
@@ -230,6 +309,39 @@ mod tests {
         assert_eq!(judge_life_delta(JudgeGrade::Miss), LIFE_MISS);
     }
 
+    #[test]
+    fn battery_loses_lives_only_on_decent_or_worse() {
+        assert_eq!(judge_battery_lives_lost(JudgeGrade::Fantastic), 0);
+        assert_eq!(judge_battery_lives_lost(JudgeGrade::Excellent), 0);
+        assert_eq!(judge_battery_lives_lost(JudgeGrade::Great), 0);
+        assert_eq!(judge_battery_lives_lost(JudgeGrade::Decent), 1);
+        assert_eq!(judge_battery_lives_lost(JudgeGrade::WayOff), 1);
+        assert_eq!(judge_battery_lives_lost(JudgeGrade::Miss), 1);
+        assert_eq!(battery_life(3, 4), 0.75);
+        assert_eq!(battery_life(1, 0), 1.0);
+    }
+
+    #[test]
+    fn survival_start_carries_remaining_time_and_enforces_minimum_gain() {
+        assert_eq!(survival_start_seconds(0.0, 0.0), SURVIVAL_MIN_GAIN_SECONDS);
+        assert_eq!(survival_start_seconds(10.0, 30.0), 40.0);
+        assert_eq!(
+            survival_start_seconds(f32::NAN, f32::INFINITY),
+            SURVIVAL_MIN_GAIN_SECONDS
+        );
+    }
+
+    #[test]
+    fn survival_clock_maps_to_life_and_never_goes_negative() {
+        let remaining = apply_survival_seconds(3.0, judge_survival_seconds(JudgeGrade::Miss));
+
+        assert_eq!(remaining, 0.0);
+        assert_eq!(survival_life(remaining), 0.0);
+        assert_eq!(survival_life(45.0), 0.5);
+        assert_eq!(survival_life(SURVIVAL_FULL_LIFE_SECONDS * 2.0), 1.0);
+        assert_eq!(apply_survival_seconds(5.0, f32::NEG_INFINITY), 5.0);
+    }
+
     #[test]
     fn life_history_compacts_only_interior_plateau_samples() {
         let mut history = Vec::new();
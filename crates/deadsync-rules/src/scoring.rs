@@ -0,0 +1,397 @@
+//! Alternative scoring models shown next to ITG on evaluation.
+//!
+//! These re-judge the recorded per-note timing errors under another game's
+//! rules. They never feed back into ITG grading, life, or submission.
+
+use crate::judgment::JudgeGrade;
+use crate::note::{HoldResult, MineResult, Note};
+use deadsync_core::note::NoteType;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScoringModelKind {
+    DdrMoney,
+    Wife3,
+    Custom,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AltScore {
+    pub points: f64,
+    pub possible: f64,
+}
+
+impl AltScore {
+    /// Score as a percentage, clamped to `[0, 100]`.
+    pub fn percent(self) -> f64 {
+        if self.possible <= 0.0 {
+            return 0.0;
+        }
+        (self.points / self.possible * 100.0).clamp(0.0, 100.0)
+    }
+}
+
+// DDR A20 windows (ms) and money-score values.
+pub const DDR_MARVELOUS_MS: f32 = 16.7;
+pub const DDR_PERFECT_MS: f32 = 33.3;
+pub const DDR_GREAT_MS: f32 = 91.7;
+pub const DDR_GOOD_MS: f32 = 141.7;
+pub const DDR_MAX_SCORE: f64 = 1_000_000.0;
+
+// Etterna Wife3 at judge 4.
+pub const WIFE3_MAX_POINTS: f64 = 2.0;
+pub const WIFE3_MISS_WEIGHT: f64 = -5.5;
+pub const WIFE3_MINE_HIT_WEIGHT: f64 = -7.0;
+pub const WIFE3_HOLD_DROP_WEIGHT: f64 = -4.5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum NoteOutcome {
+    Tap(f32),
+    TapMiss,
+    HoldHeld,
+    HoldDropped,
+    MineHit,
+    MineAvoided,
+}
+
+/// Walk the scoreable results for one player, stopping at `fail_time_ns`.
+fn for_each_outcome(
+    notes: &[Note],
+    note_times_ns: &[i64],
+    fail_time_ns: Option<i64>,
+    mut f: impl FnMut(NoteOutcome),
+) {
+    for (i, note) in notes.iter().enumerate() {
+        if note.is_fake || !note.can_be_judged {
+            continue;
+        }
+        if let Some(fail_ns) = fail_time_ns
+            && note_times_ns.get(i).copied().unwrap_or(0) > fail_ns
+        {
+            continue;
+        }
+        if matches!(note.note_type, NoteType::Mine) {
+            match note.mine_result {
+                Some(MineResult::Hit) => f(NoteOutcome::MineHit),
+                Some(MineResult::Avoided) => f(NoteOutcome::MineAvoided),
+                None => {}
+            }
+            continue;
+        }
+        let Some(result) = note.result.as_ref() else {
+            continue;
+        };
+        if result.grade == JudgeGrade::Miss {
+            f(NoteOutcome::TapMiss);
+        } else {
+            f(NoteOutcome::Tap(result.time_error_ms.abs()));
+        }
+        if let Some(result) = note.hold.as_ref().and_then(|hold| hold.result) {
+            f(match result {
+                HoldResult::Held => NoteOutcome::HoldHeld,
+                HoldResult::LetGo | HoldResult::Missed => NoteOutcome::HoldDropped,
+            });
+        }
+    }
+}
+
+/// DDR A20 money score. Every tap, freeze and shock arrow (mine) is one step
+/// worth `1,000,000 / steps`; Perfect loses 10, Great is 60% - 10, Good is
+/// 20% - 10. The total is floored to a multiple of 10.
+pub fn ddr_money_score(
+    notes: &[Note],
+    note_times_ns: &[i64],
+    fail_time_ns: Option<i64>,
+) -> AltScore {
+    let mut steps = 0_u32;
+    for_each_outcome(notes, note_times_ns, None, |_| steps += 1);
+    if steps == 0 {
+        return AltScore::default();
+    }
+    let step = DDR_MAX_SCORE / f64::from(steps);
+    let mut points = 0.0;
+    for_each_outcome(notes, note_times_ns, fail_time_ns, |outcome| {
+        points += match outcome {
+            NoteOutcome::Tap(ms) if ms <= DDR_MARVELOUS_MS => step,
+            NoteOutcome::Tap(ms) if ms <= DDR_PERFECT_MS => step - 10.0,
+            NoteOutcome::Tap(ms) if ms <= DDR_GREAT_MS => step * 0.6 - 10.0,
+            NoteOutcome::Tap(ms) if ms <= DDR_GOOD_MS => step * 0.2 - 10.0,
+            NoteOutcome::HoldHeld | NoteOutcome::MineAvoided => step,
+            NoteOutcome::Tap(_)
+            | NoteOutcome::TapMiss
+            | NoteOutcome::HoldDropped
+            | NoteOutcome::MineHit => 0.0,
+        };
+    });
+    AltScore {
+        points: ((points / 10.0).floor() * 10.0).max(0.0),
+        possible: DDR_MAX_SCORE,
+    }
+}
+
+// Abramowitz & Stegun 7.1.26; max error 1.5e-7, plenty for a display score.
+fn erf(x: f64) -> f64 {
+    let sign = x.signum();
+    let x = x.abs();
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    sign * (1.0 - poly * (-x * x).exp())
+}
+
+/// Etterna's Wife3 curve for one tap at `error_ms` (judge 4).
+pub fn wife3_points(error_ms: f32) -> f64 {
+    const RIDIC_MS: f64 = 5.0;
+    const MAX_BOO_MS: f64 = 180.0;
+    const ZERO_MS: f64 = 65.0;
+    const DEV_MS: f64 = 22.7;
+    let ms = f64::from(error_ms.abs());
+    if ms <= RIDIC_MS {
+        WIFE3_MAX_POINTS
+    } else if ms <= ZERO_MS {
+        WIFE3_MAX_POINTS * erf((ZERO_MS - ms) / DEV_MS)
+    } else if ms <= MAX_BOO_MS {
+        (ms - ZERO_MS) * WIFE3_MISS_WEIGHT / (MAX_BOO_MS - ZERO_MS)
+    } else {
+        WIFE3_MISS_WEIGHT
+    }
+}
+
+/// Wife3 accuracy: two points per tap, with misses, hit mines and dropped
+/// holds as penalties.
+pub fn wife3_score(notes: &[Note], note_times_ns: &[i64], fail_time_ns: Option<i64>) -> AltScore {
+    let mut possible = 0.0;
+    for_each_outcome(notes, note_times_ns, None, |outcome| {
+        if matches!(outcome, NoteOutcome::Tap(_) | NoteOutcome::TapMiss) {
+            possible += WIFE3_MAX_POINTS;
+        }
+    });
+    let mut points = 0.0;
+    for_each_outcome(notes, note_times_ns, fail_time_ns, |outcome| {
+        points += match outcome {
+            NoteOutcome::Tap(ms) => wife3_points(ms),
+            NoteOutcome::TapMiss => WIFE3_MISS_WEIGHT,
+            NoteOutcome::HoldDropped => WIFE3_HOLD_DROP_WEIGHT,
+            NoteOutcome::MineHit => WIFE3_MINE_HIT_WEIGHT,
+            NoteOutcome::HoldHeld | NoteOutcome::MineAvoided => 0.0,
+        };
+    });
+    AltScore { points, possible }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomWindow {
+    pub name: String,
+    pub ms: f32,
+    pub weight: f64,
+}
+
+/// User-defined windows, loaded from a profile's `scoring_windows.ini`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomWindows {
+    pub name: String,
+    /// Sorted tightest first.
+    pub windows: Vec<CustomWindow>,
+    pub miss_weight: f64,
+    pub hold_held_weight: f64,
+    pub hold_dropped_weight: f64,
+    pub mine_hit_weight: f64,
+}
+
+/// Parse a custom windows file:
+///
+/// ```text
+/// Name=Tight
+/// Window=Perfect,15,3
+/// Window=Great,45,2
+/// Window=Good,90,1
+/// Miss=0
+/// HoldHeld=1
+/// HoldDropped=0
+/// MineHit=-1
+/// ```
+///
+/// `Window=` takes `name,ms,weight`. Blank lines, `#`/`;` comments and a
+/// leading `[Section]` header are ignored.
+pub fn parse_custom_windows(text: &str) -> Result<CustomWindows, String> {
+    let mut out = CustomWindows {
+        name: "Custom".to_string(),
+        windows: Vec::new(),
+        miss_weight: 0.0,
+        hold_held_weight: 0.0,
+        hold_dropped_weight: 0.0,
+        mine_hit_weight: 0.0,
+    };
+    let weight = |key: &str, value: &str, line: usize| {
+        value
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite())
+            .ok_or_else(|| format!("line {line}: '{value}' is not a valid {key} weight"))
+    };
+    for (idx, raw) in text.lines().enumerate() {
+        let line = idx + 1;
+        let raw = raw.trim();
+        if raw.is_empty() || raw.starts_with(['#', ';', '[']) {
+            continue;
+        }
+        let Some((key, value)) = raw.split_once('=') else {
+            return Err(format!("line {line}: expected Key=Value"));
+        };
+        let (key, value) = (key.trim(), value.trim());
+        match key.to_ascii_lowercase().as_str() {
+            "name" if !value.is_empty() => out.name = value.to_string(),
+            "window" => {
+                let mut parts = value.split(',').map(str::trim);
+                let (Some(name), Some(ms), Some(w), None) =
+                    (parts.next(), parts.next(), parts.next(), parts.next())
+                else {
+                    return Err(format!("line {line}: Window expects name,ms,weight"));
+                };
+                let ms = ms
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|ms| ms.is_finite() && *ms > 0.0)
+                    .ok_or_else(|| format!("line {line}: '{ms}' is not a valid window size"))?;
+                out.windows.push(CustomWindow {
+                    name: name.to_string(),
+                    ms,
+                    weight: weight("Window", w, line)?,
+                });
+            }
+            "miss" => out.miss_weight = weight("Miss", value, line)?,
+            "holdheld" => out.hold_held_weight = weight("HoldHeld", value, line)?,
+            "holddropped" => out.hold_dropped_weight = weight("HoldDropped", value, line)?,
+            "minehit" => out.mine_hit_weight = weight("MineHit", value, line)?,
+            _ => return Err(format!("line {line}: unknown key '{key}'")),
+        }
+    }
+    if out.windows.is_empty() {
+        return Err("no Window= entries".to_string());
+    }
+    out.windows.sort_by(|a, b| a.ms.total_cmp(&b.ms));
+    Ok(out)
+}
+
+/// Score against user windows: each tap earns the weight of the tightest
+/// window containing it, and the best weight is the per-tap maximum.
+pub fn custom_windows_score(
+    windows: &CustomWindows,
+    notes: &[Note],
+    note_times_ns: &[i64],
+    fail_time_ns: Option<i64>,
+) -> AltScore {
+    let best = windows
+        .windows
+        .iter()
+        .map(|w| w.weight)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let mut possible = 0.0;
+    for_each_outcome(notes, note_times_ns, None, |outcome| {
+        possible += match outcome {
+            NoteOutcome::Tap(_) | NoteOutcome::TapMiss => best,
+            NoteOutcome::HoldHeld | NoteOutcome::HoldDropped => windows.hold_held_weight.max(0.0),
+            NoteOutcome::MineHit | NoteOutcome::MineAvoided => 0.0,
+        };
+    });
+    let mut points = 0.0;
+    for_each_outcome(notes, note_times_ns, fail_time_ns, |outcome| {
+        points += match outcome {
+            NoteOutcome::Tap(ms) => windows
+                .windows
+                .iter()
+                .find(|w| ms <= w.ms)
+                .map_or(windows.miss_weight, |w| w.weight),
+            NoteOutcome::TapMiss => windows.miss_weight,
+            NoteOutcome::HoldHeld => windows.hold_held_weight,
+            NoteOutcome::HoldDropped => windows.hold_dropped_weight,
+            NoteOutcome::MineHit => windows.mine_hit_weight,
+            NoteOutcome::MineAvoided => 0.0,
+        };
+    });
+    AltScore { points, possible }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::judgment::{Judgment, judgment_time_error_music_ns_from_ms};
+
+    fn tap(row_index: usize, grade: JudgeGrade, time_error_ms: f32) -> Note {
+        Note {
+            beat: row_index as f32,
+            quantization_idx: 0,
+            column: 0,
+            note_type: NoteType::Tap,
+            row_index,
+            result: Some(Judgment {
+                time_error_ms,
+                time_error_music_ns: judgment_time_error_music_ns_from_ms(time_error_ms, 1.0),
+                grade,
+                window: None,
+                miss_because_held: false,
+            }),
+            early_result: None,
+            hold: None,
+            mine_result: None,
+            is_fake: false,
+            can_be_judged: true,
+        }
+    }
+
+    #[test]
+    fn ddr_money_rejudges_by_ddr_windows() {
+        let notes = [
+            tap(0, JudgeGrade::Fantastic, 10.0),
+            tap(1, JudgeGrade::Fantastic, -20.0),
+            tap(2, JudgeGrade::Excellent, 40.0),
+            tap(3, JudgeGrade::Miss, 0.0),
+        ];
+        let score = ddr_money_score(&notes, &[0; 4], None);
+        // 250k + (250k - 10) + (150k - 10) + 0, floored to 10.
+        assert_eq!(score.points, 649_980.0);
+        assert_eq!(score.possible, DDR_MAX_SCORE);
+    }
+
+    #[test]
+    fn wife3_curve_matches_reference_points() {
+        assert_eq!(wife3_points(0.0), WIFE3_MAX_POINTS);
+        assert_eq!(wife3_points(-5.0), WIFE3_MAX_POINTS);
+        assert!(wife3_points(65.0).abs() < 1e-6);
+        assert_eq!(wife3_points(200.0), WIFE3_MISS_WEIGHT);
+        assert!((wife3_points(40.0) - 1.7613).abs() < 1e-3);
+
+        let notes = [
+            tap(0, JudgeGrade::Fantastic, 1.0),
+            tap(1, JudgeGrade::Miss, 0.0),
+        ];
+        let score = wife3_score(&notes, &[0, 0], None);
+        assert_eq!(score.possible, 4.0);
+        assert_eq!(score.points, WIFE3_MAX_POINTS + WIFE3_MISS_WEIGHT);
+        assert_eq!(score.percent(), 0.0);
+    }
+
+    #[test]
+    fn custom_windows_parse_and_score() {
+        let windows = parse_custom_windows(
+            "[Windows]\nName=Tight\nWindow=Great,45,2\nWindow=Perfect,15,3\n# c\nMiss=-1\n",
+        )
+        .unwrap();
+        assert_eq!(windows.name, "Tight");
+        assert_eq!(windows.windows[0].name, "Perfect");
+
+        let notes = [
+            tap(0, JudgeGrade::Fantastic, 10.0),
+            tap(1, JudgeGrade::Excellent, 30.0),
+            tap(2, JudgeGrade::Great, 80.0),
+        ];
+        let score = custom_windows_score(&windows, &notes, &[0, 10, 20], Some(15));
+        assert_eq!(score.possible, 9.0);
+        assert_eq!(score.points, 5.0);
+
+        assert!(parse_custom_windows("Name=Empty\n").is_err());
+        assert!(parse_custom_windows("Window=Perfect,-1,3\n").is_err());
+        assert!(parse_custom_windows("Bogus=1\n").is_err());
+    }
+}
@@ -95,6 +95,62 @@ impl TimingProfile {
             s[4] * 1000.0,
         ]
     }
+
+    /// Applies a training window adjustment. Adjusted tap and mine windows are
+    /// capped at the stock Way Off window so the shared late-resolution
+    /// distance still covers them, and W1..W5 never shrink below the window
+    /// inside them.
+    pub fn with_adjust(mut self, adjust: &TimingWindowAdjust) -> Self {
+        if adjust.is_identity() {
+            return self;
+        }
+        let scale = if adjust.scale.is_finite() && adjust.scale > 0.0 {
+            adjust.scale
+        } else {
+            1.0
+        };
+        let max_s = BASE_W5_S + TIMING_WINDOW_ADD_S;
+        let mut floor_s = 0.0_f32;
+        for (window_s, override_ms) in self.windows_s.iter_mut().zip(adjust.windows_ms) {
+            let seconds = override_ms.map_or(*window_s * scale, |ms| ms / 1000.0);
+            *window_s = seconds.clamp(floor_s, max_s);
+            floor_s = *window_s;
+        }
+        self.fa_plus_window_s = self
+            .fa_plus_window_s
+            .map(|seconds| (seconds * scale).min(self.windows_s[0]));
+        self.mine_window_s = adjust
+            .mine_ms
+            .map_or(self.mine_window_s * scale, |ms| ms / 1000.0)
+            .clamp(0.0, max_s);
+        self
+    }
+}
+
+/// Per-player training windows: every window is multiplied by `scale`, then
+/// any explicit override (milliseconds, padding included) replaces it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TimingWindowAdjust {
+    pub scale: f32,
+    pub windows_ms: [Option<f32>; 5],
+    pub mine_ms: Option<f32>,
+}
+
+impl Default for TimingWindowAdjust {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            windows_ms: [None; 5],
+            mine_ms: None,
+        }
+    }
+}
+
+impl TimingWindowAdjust {
+    #[inline(always)]
+    pub fn is_identity(&self) -> bool {
+        self.scale == 1.0 && self.windows_ms.iter().all(Option::is_none) && self.mine_ms.is_none()
+    }
 }
 
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
@@ -3612,6 +3668,47 @@ mod tests {
         assert_eq!(judged, (JudgeGrade::Great, TimingWindow::W3));
     }
 
+    #[test]
+    fn window_adjust_scales_then_applies_overrides() {
+        let stock = TimingProfile::default_itg_with_fa_plus();
+        let identity = stock.with_adjust(&TimingWindowAdjust::default());
+        assert_eq!(identity.windows_s, stock.windows_s);
+        assert_eq!(identity.mine_window_s, stock.mine_window_s);
+
+        let adjusted = stock.with_adjust(&TimingWindowAdjust {
+            scale: 0.5,
+            windows_ms: [Some(15.0), None, None, None, None],
+            mine_ms: Some(60.0),
+        });
+
+        assert!((adjusted.windows_s[0] - 0.015).abs() < 1e-6);
+        assert!((adjusted.windows_s[1] - stock.windows_s[1] * 0.5).abs() < 1e-6);
+        assert!(adjusted.fa_plus_window_s.unwrap() <= adjusted.windows_s[0]);
+        assert!((adjusted.mine_window_s - 0.060).abs() < 1e-6);
+
+        let profile_ns = TimingProfileNs::from_profile_scaled(&adjusted, 1.0);
+        let offset_ns = 20_000_000;
+        assert_eq!(
+            classify_offset_ns_with_disabled_windows(offset_ns, &profile_ns, &[false; 5]),
+            Some((JudgeGrade::Excellent, TimingWindow::W2))
+        );
+    }
+
+    #[test]
+    fn window_adjust_keeps_windows_ordered_and_capped() {
+        let stock = TimingProfile::default_itg_with_fa_plus();
+        let adjusted = stock.with_adjust(&TimingWindowAdjust {
+            scale: 2.0,
+            windows_ms: [None, Some(5.0), None, None, None],
+            mine_ms: None,
+        });
+
+        assert_eq!(adjusted.windows_s[1], adjusted.windows_s[0]);
+        assert_eq!(adjusted.windows_s[4], stock.windows_s[4]);
+        assert!(adjusted.windows_s.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(adjusted.mine_window_s <= stock.windows_s[4]);
+    }
+
     #[test]
     fn disabled_bottom_windows_turn_outer_w4_hits_into_misses() {
         let profile = TimingProfile::default_itg_with_fa_plus();
@@ -169,6 +169,11 @@ pub fn local_score_from_itg(hs: &ImportedHighScore) -> Option<LocalScoreEntry> {
         hands_achieved: 0,
         fail_time,
         beat0_time_ns: 0,
+        offset_mean_ms: None,
+        offset_median_ms: None,
+        scoring_model: None,
+        turn_mod: None,
+        turn_seed: 0,
         replay: Vec::new(),
     })
 }
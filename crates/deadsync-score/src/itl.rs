@@ -2068,6 +2068,8 @@ mod tests {
             folder_series: String::new(),
             year: 0,
             sync_pref: deadsync_chart::SyncPref::Default,
+            sync_bias_ms: 0.0,
+            sync_bias_written_ms: Vec::new(),
             directory: PathBuf::new(),
             banner_path: None,
             songs: vec![song_with_charts(charts)],
@@ -4,6 +4,7 @@ use deadsync_core::input::InputSource;
 use deadsync_core::note::NoteType;
 use deadsync_core::song_time::{SongTimeNs, song_time_ns_from_seconds, song_time_ns_invalid};
 use deadsync_rules::note::{HoldResult, MineResult, Note};
+use deadsync_rules::scoring::ScoringModelKind;
 use deadsync_rules::scroll::ScrollSpeedSetting;
 use deadsync_rules::{judgment, timing};
 use log::{debug, warn};
@@ -22,8 +23,13 @@ pub mod import;
 pub mod itl;
 pub mod leaderboard;
 pub mod local_store;
+pub mod offset_drift;
+pub mod pattern_analysis;
+pub mod profile_history;
 pub mod select_music;
+pub mod skill_rating;
 pub mod stage_stats;
+pub mod workout;
 pub use column_judgments::*;
 pub use event_progress::*;
 pub use import::{ImportedHighScore, grade_from_itg, local_score_from_itg, parse_itg_datetime_ms};
@@ -78,7 +84,12 @@ pub use itl::{
 };
 pub use leaderboard::*;
 pub use local_store::*;
+pub use offset_drift::*;
+pub use pattern_analysis::*;
+pub use profile_history::*;
 pub use select_music::*;
+pub use skill_rating::*;
+pub use workout::*;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
 pub enum Grade {
@@ -467,6 +478,11 @@ fn score_cache_snapshot_header(index: usize) -> LocalScoreHeader {
         hands_achieved: 1,
         fail_time: None,
         beat0_time_ns: 0,
+        offset_mean_ms: None,
+        offset_median_ms: None,
+        scoring_model: None,
+        turn_mod: None,
+        turn_seed: 0,
     }
 }
 
@@ -1726,6 +1742,13 @@ pub fn runtime_recent_played_chart_hashes_for_profile(
     recent_played_chart_hashes_in_root(&score_paths(profile_id).local_dir())
 }
 
+pub fn runtime_recent_play_ex_for_profile(
+    profile_id: &str,
+    score_paths: ProfilePathsFn,
+) -> Vec<(String, f64)> {
+    recent_local_play_ex_in_root(&score_paths(profile_id).local_dir(), SKILL_RECENT_PLAYS)
+}
+
 pub fn runtime_played_chart_counts_for_profile(
     profile_id: &str,
     score_paths: ProfilePathsFn,
@@ -2013,6 +2036,23 @@ impl HeldScoreCaches {
         merged.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
         merged
     }
+
+    /// Snapshot a loaded profile's best passing local EX percent per chart.
+    ///
+    /// Sorted by chart hash like [`Self::merged_profile_scores`].
+    pub fn local_profile_ex_scores(&self, profile_id: &str) -> Vec<(String, f64)> {
+        let Some(index) = self.local.loaded_profiles.get(profile_id) else {
+            return Vec::new();
+        };
+        let mut scores: Vec<_> = index
+            .best_ex
+            .iter()
+            .filter(|(_, best)| best.grade != Grade::Failed)
+            .map(|(chart_hash, best)| (chart_hash.clone(), best.percent))
+            .collect();
+        scores.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        scores
+    }
 }
 
 /// Maximum number of attempts before the backoff schedule saturates. For
@@ -3950,7 +3990,10 @@ pub fn fix_local_ex_grade(grade: Grade, ex_score_percent: f64) -> Grade {
     }
 }
 
-pub const LOCAL_SCORE_VERSION: u16 = 1;
+pub const LOCAL_SCORE_VERSION: u16 = 4;
+const LOCAL_SCORE_VERSION_V3: u16 = 3;
+const LOCAL_SCORE_VERSION_V2: u16 = 2;
+const LOCAL_SCORE_VERSION_V1: u16 = 1;
 pub const LOCAL_SCORE_INDEX_VERSION: u16 = 3;
 
 #[derive(Debug, Clone, Encode, Decode)]
@@ -4089,6 +4132,9 @@ pub struct LocalScoreGameplayPlayer<'a> {
     pub mines_avoided: u32,
     pub hands_achieved: u32,
     pub beat0_time_ns: SongTimeNs,
+    pub scoring_model: Option<ScoringModelKind>,
+    pub turn_mod: LocalTurnMod,
+    pub turn_seed: u64,
     pub replay: Vec<LocalReplayEdge>,
 }
 
@@ -4182,6 +4228,9 @@ pub fn save_local_gameplay_scores<'a, W, L>(
             mines_avoided: player.mines_avoided,
             hands_achieved: player.hands_achieved,
             beat0_time_ns: player.beat0_time_ns,
+            scoring_model: player.scoring_model,
+            turn_mod: player.turn_mod,
+            turn_seed: player.turn_seed,
             replay: player.replay,
         });
         write_score(
@@ -4245,6 +4294,229 @@ pub struct LocalScoreHeader {
     pub hands_achieved: u32,
     pub fail_time: Option<f32>,
     pub beat0_time_ns: SongTimeNs,
+    /// Tap timing offset of the play in ms; positive is late. `None` for
+    /// scores saved before offsets were recorded, and for imports.
+    pub offset_mean_ms: Option<f32>,
+    pub offset_median_ms: Option<f32>,
+    /// Companion scoring model chosen for the play. `None` for scores saved
+    /// before the model was recorded, and for imports.
+    pub scoring_model: Option<LocalScoringModel>,
+    /// Turn mod the notes were remapped with. Replay lanes are the remapped
+    /// ones. `None` for older scores and imports; readers treat those as
+    /// played without a turn.
+    pub turn_mod: Option<LocalTurnMod>,
+    /// Seed the shuffling turn mods drew from.
+    pub turn_seed: u64,
+}
+
+/// Turn mod a score was set with, mirroring the gameplay option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum LocalTurnMod {
+    None,
+    Mirror,
+    LRMirror,
+    UDMirror,
+    Left,
+    Right,
+    Shuffle,
+    Blender,
+    Random,
+}
+
+/// Scoring model shown beside ITG when a score was set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum LocalScoringModel {
+    /// No companion model; ITG only.
+    Itg,
+    DdrMoney,
+    Wife3,
+    Custom,
+}
+
+impl LocalScoringModel {
+    pub const fn from_kind(kind: Option<ScoringModelKind>) -> Self {
+        match kind {
+            None => Self::Itg,
+            Some(ScoringModelKind::DdrMoney) => Self::DdrMoney,
+            Some(ScoringModelKind::Wife3) => Self::Wife3,
+            Some(ScoringModelKind::Custom) => Self::Custom,
+        }
+    }
+
+    pub const fn kind(self) -> Option<ScoringModelKind> {
+        match self {
+            Self::Itg => None,
+            Self::DdrMoney => Some(ScoringModelKind::DdrMoney),
+            Self::Wife3 => Some(ScoringModelKind::Wife3),
+            Self::Custom => Some(ScoringModelKind::Custom),
+        }
+    }
+}
+
+/// Header layout written before the turn mod was stored.
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+struct LocalScoreHeaderV3 {
+    version: u16,
+    played_at_ms: i64,
+    music_rate: f32,
+    score_percent: f64,
+    grade_code: u8,
+    lamp_index: Option<u8>,
+    lamp_judge_count: Option<u8>,
+    ex_score_percent: f64,
+    hard_ex_score_percent: f64,
+    judgment_counts: [u32; 6],
+    holds_held: u32,
+    holds_total: u32,
+    rolls_held: u32,
+    rolls_total: u32,
+    mines_avoided: u32,
+    mines_total: u32,
+    hands_achieved: u32,
+    fail_time: Option<f32>,
+    beat0_time_ns: SongTimeNs,
+    offset_mean_ms: Option<f32>,
+    offset_median_ms: Option<f32>,
+    scoring_model: Option<LocalScoringModel>,
+}
+
+impl From<LocalScoreHeaderV3> for LocalScoreHeader {
+    fn from(v3: LocalScoreHeaderV3) -> Self {
+        Self {
+            version: LOCAL_SCORE_VERSION,
+            played_at_ms: v3.played_at_ms,
+            music_rate: v3.music_rate,
+            score_percent: v3.score_percent,
+            grade_code: v3.grade_code,
+            lamp_index: v3.lamp_index,
+            lamp_judge_count: v3.lamp_judge_count,
+            ex_score_percent: v3.ex_score_percent,
+            hard_ex_score_percent: v3.hard_ex_score_percent,
+            judgment_counts: v3.judgment_counts,
+            holds_held: v3.holds_held,
+            holds_total: v3.holds_total,
+            rolls_held: v3.rolls_held,
+            rolls_total: v3.rolls_total,
+            mines_avoided: v3.mines_avoided,
+            mines_total: v3.mines_total,
+            hands_achieved: v3.hands_achieved,
+            fail_time: v3.fail_time,
+            beat0_time_ns: v3.beat0_time_ns,
+            offset_mean_ms: v3.offset_mean_ms,
+            offset_median_ms: v3.offset_median_ms,
+            scoring_model: v3.scoring_model,
+            turn_mod: None,
+            turn_seed: 0,
+        }
+    }
+}
+
+/// Header layout written before the scoring model was stored.
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+struct LocalScoreHeaderV2 {
+    version: u16,
+    played_at_ms: i64,
+    music_rate: f32,
+    score_percent: f64,
+    grade_code: u8,
+    lamp_index: Option<u8>,
+    lamp_judge_count: Option<u8>,
+    ex_score_percent: f64,
+    hard_ex_score_percent: f64,
+    judgment_counts: [u32; 6],
+    holds_held: u32,
+    holds_total: u32,
+    rolls_held: u32,
+    rolls_total: u32,
+    mines_avoided: u32,
+    mines_total: u32,
+    hands_achieved: u32,
+    fail_time: Option<f32>,
+    beat0_time_ns: SongTimeNs,
+    offset_mean_ms: Option<f32>,
+    offset_median_ms: Option<f32>,
+}
+
+impl From<LocalScoreHeaderV2> for LocalScoreHeaderV3 {
+    fn from(v2: LocalScoreHeaderV2) -> Self {
+        Self {
+            version: LOCAL_SCORE_VERSION_V3,
+            played_at_ms: v2.played_at_ms,
+            music_rate: v2.music_rate,
+            score_percent: v2.score_percent,
+            grade_code: v2.grade_code,
+            lamp_index: v2.lamp_index,
+            lamp_judge_count: v2.lamp_judge_count,
+            ex_score_percent: v2.ex_score_percent,
+            hard_ex_score_percent: v2.hard_ex_score_percent,
+            judgment_counts: v2.judgment_counts,
+            holds_held: v2.holds_held,
+            holds_total: v2.holds_total,
+            rolls_held: v2.rolls_held,
+            rolls_total: v2.rolls_total,
+            mines_avoided: v2.mines_avoided,
+            mines_total: v2.mines_total,
+            hands_achieved: v2.hands_achieved,
+            fail_time: v2.fail_time,
+            beat0_time_ns: v2.beat0_time_ns,
+            offset_mean_ms: v2.offset_mean_ms,
+            offset_median_ms: v2.offset_median_ms,
+            scoring_model: None,
+        }
+    }
+}
+
+/// Header layout written before per-play offsets were stored. Still decoded so
+/// existing score files keep loading.
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+struct LocalScoreHeaderV1 {
+    version: u16,
+    played_at_ms: i64,
+    music_rate: f32,
+    score_percent: f64,
+    grade_code: u8,
+    lamp_index: Option<u8>,
+    lamp_judge_count: Option<u8>,
+    ex_score_percent: f64,
+    hard_ex_score_percent: f64,
+    judgment_counts: [u32; 6],
+    holds_held: u32,
+    holds_total: u32,
+    rolls_held: u32,
+    rolls_total: u32,
+    mines_avoided: u32,
+    mines_total: u32,
+    hands_achieved: u32,
+    fail_time: Option<f32>,
+    beat0_time_ns: SongTimeNs,
+}
+
+impl From<LocalScoreHeaderV1> for LocalScoreHeaderV2 {
+    fn from(v1: LocalScoreHeaderV1) -> Self {
+        Self {
+            version: LOCAL_SCORE_VERSION_V2,
+            played_at_ms: v1.played_at_ms,
+            music_rate: v1.music_rate,
+            score_percent: v1.score_percent,
+            grade_code: v1.grade_code,
+            lamp_index: v1.lamp_index,
+            lamp_judge_count: v1.lamp_judge_count,
+            ex_score_percent: v1.ex_score_percent,
+            hard_ex_score_percent: v1.hard_ex_score_percent,
+            judgment_counts: v1.judgment_counts,
+            holds_held: v1.holds_held,
+            holds_total: v1.holds_total,
+            rolls_held: v1.rolls_held,
+            rolls_total: v1.rolls_total,
+            mines_avoided: v1.mines_avoided,
+            mines_total: v1.mines_total,
+            hands_achieved: v1.hands_achieved,
+            fail_time: v1.fail_time,
+            beat0_time_ns: v1.beat0_time_ns,
+            offset_mean_ms: None,
+            offset_median_ms: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Encode, Decode)]
@@ -4268,10 +4540,45 @@ pub struct LocalScoreEntry {
     pub hands_achieved: u32,
     pub fail_time: Option<f32>,
     pub beat0_time_ns: SongTimeNs,
+    pub offset_mean_ms: Option<f32>,
+    pub offset_median_ms: Option<f32>,
+    pub scoring_model: Option<LocalScoringModel>,
+    pub turn_mod: Option<LocalTurnMod>,
+    pub turn_seed: u64,
     pub replay: Vec<LocalReplayEdge>,
 }
 
 impl LocalScoreEntry {
+    fn from_header(h: LocalScoreHeader, replay: Vec<LocalReplayEdge>) -> Self {
+        Self {
+            version: h.version,
+            played_at_ms: h.played_at_ms,
+            music_rate: h.music_rate,
+            score_percent: h.score_percent,
+            grade_code: h.grade_code,
+            lamp_index: h.lamp_index,
+            lamp_judge_count: h.lamp_judge_count,
+            ex_score_percent: h.ex_score_percent,
+            hard_ex_score_percent: h.hard_ex_score_percent,
+            judgment_counts: h.judgment_counts,
+            holds_held: h.holds_held,
+            holds_total: h.holds_total,
+            rolls_held: h.rolls_held,
+            rolls_total: h.rolls_total,
+            mines_avoided: h.mines_avoided,
+            mines_total: h.mines_total,
+            hands_achieved: h.hands_achieved,
+            fail_time: h.fail_time,
+            beat0_time_ns: h.beat0_time_ns,
+            offset_mean_ms: h.offset_mean_ms,
+            offset_median_ms: h.offset_median_ms,
+            scoring_model: h.scoring_model,
+            turn_mod: h.turn_mod,
+            turn_seed: h.turn_seed,
+            replay,
+        }
+    }
+
     pub fn header(&self) -> LocalScoreHeader {
         LocalScoreHeader {
             version: self.version,
@@ -4293,6 +4600,11 @@ impl LocalScoreEntry {
             hands_achieved: self.hands_achieved,
             fail_time: self.fail_time,
             beat0_time_ns: self.beat0_time_ns,
+            offset_mean_ms: self.offset_mean_ms,
+            offset_median_ms: self.offset_median_ms,
+            scoring_model: self.scoring_model,
+            turn_mod: self.turn_mod,
+            turn_seed: self.turn_seed,
         }
     }
 }
@@ -4319,6 +4631,9 @@ pub struct LocalScoreGameplayEntryInput<'a> {
     pub mines_avoided: u32,
     pub hands_achieved: u32,
     pub beat0_time_ns: SongTimeNs,
+    pub scoring_model: Option<ScoringModelKind>,
+    pub turn_mod: LocalTurnMod,
+    pub turn_seed: u64,
     pub replay: Vec<LocalReplayEdge>,
 }
 
@@ -4363,6 +4678,7 @@ pub fn local_score_entry_from_gameplay_input(
     grade = promote_quint_grade(grade, ex_score_percent);
     let (lamp_index, lamp_judge_count) =
         compute_local_lamp(input.counts, grade, input.white_fantastics);
+    let offsets = play_offset_summary(input.notes);
 
     LocalScoreEntry {
         version: LOCAL_SCORE_VERSION,
@@ -4384,6 +4700,11 @@ pub fn local_score_entry_from_gameplay_input(
         hands_achieved: input.hands_achieved,
         fail_time: input.fail_time,
         beat0_time_ns: input.beat0_time_ns,
+        offset_mean_ms: offsets.mean_ms,
+        offset_median_ms: offsets.median_ms,
+        scoring_model: Some(LocalScoringModel::from_kind(input.scoring_model)),
+        turn_mod: Some(input.turn_mod),
+        turn_seed: input.turn_seed,
         replay: input.replay,
     }
 }
@@ -4430,6 +4751,11 @@ pub fn local_score_entry_from_stage_summary(
         hands_achieved: 0,
         fail_time: (summary.grade == Grade::Failed).then_some(0.0),
         beat0_time_ns: 0,
+        offset_mean_ms: None,
+        offset_median_ms: None,
+        scoring_model: None,
+        turn_mod: None,
+        turn_seed: 0,
         replay: Vec::new(),
     }
 }
@@ -4501,28 +4827,51 @@ pub fn cached_score_from_local_header(h: &LocalScoreHeader) -> CachedScore {
     cached_score(grade, h.score_percent, lamp_index, lamp_judge_count)
 }
 
-pub fn decode_local_score_header(bytes: &[u8]) -> Option<LocalScoreHeader> {
-    let Ok((h, _)) =
-        bincode::decode_from_slice::<LocalScoreHeader, _>(bytes, bincode::config::standard())
-    else {
-        return None;
-    };
-    if h.version != LOCAL_SCORE_VERSION {
-        return None;
+/// Decodes the header of either score layout, returning the bytes it used.
+fn decode_local_score_header_prefix(bytes: &[u8]) -> Option<(LocalScoreHeader, usize)> {
+    let config = bincode::config::standard();
+    let (version, _) = bincode::decode_from_slice::<u16, _>(bytes, config).ok()?;
+    match version {
+        LOCAL_SCORE_VERSION => {
+            bincode::decode_from_slice::<LocalScoreHeader, _>(bytes, config).ok()
+        }
+        LOCAL_SCORE_VERSION_V3 => {
+            bincode::decode_from_slice::<LocalScoreHeaderV3, _>(bytes, config)
+                .ok()
+                .map(|(h, read)| (h.into(), read))
+        }
+        LOCAL_SCORE_VERSION_V2 => {
+            bincode::decode_from_slice::<LocalScoreHeaderV2, _>(bytes, config)
+                .ok()
+                .map(|(h, read)| (LocalScoreHeaderV3::from(h).into(), read))
+        }
+        LOCAL_SCORE_VERSION_V1 => {
+            bincode::decode_from_slice::<LocalScoreHeaderV1, _>(bytes, config)
+                .ok()
+                .map(|(h, read)| {
+                    (
+                        LocalScoreHeaderV3::from(LocalScoreHeaderV2::from(h)).into(),
+                        read,
+                    )
+                })
+        }
+        _ => None,
     }
-    Some(h)
+}
+
+pub fn decode_local_score_header(bytes: &[u8]) -> Option<LocalScoreHeader> {
+    decode_local_score_header_prefix(bytes).map(|(h, _)| h)
 }
 
 pub fn decode_local_score_entry(bytes: &[u8]) -> Option<LocalScoreEntry> {
-    let Ok((entry, _)) =
-        bincode::decode_from_slice::<LocalScoreEntry, _>(bytes, bincode::config::standard())
-    else {
-        return None;
-    };
-    if entry.version != LOCAL_SCORE_VERSION {
-        return None;
-    }
-    Some(entry)
+    let (h, read) = decode_local_score_header_prefix(bytes)?;
+    // The replay is the only field after the header in every layout.
+    let (replay, _) = bincode::decode_from_slice::<Vec<LocalReplayEdge>, _>(
+        &bytes[read..],
+        bincode::config::standard(),
+    )
+    .ok()?;
+    Some(LocalScoreEntry::from_header(h, replay))
 }
 
 pub fn encode_local_score_entry(entry: &LocalScoreEntry) -> Option<Vec<u8>> {
@@ -7387,6 +7736,11 @@ mod tests {
             hands_achieved: 0,
             fail_time: Some(12.0),
             beat0_time_ns: 0,
+            offset_mean_ms: None,
+            offset_median_ms: None,
+            scoring_model: None,
+            turn_mod: None,
+            turn_seed: 0,
         };
 
         let cached = cached_score_from_local_header(&header);
@@ -7419,6 +7773,11 @@ mod tests {
             hands_achieved: 0,
             fail_time: None,
             beat0_time_ns: 0,
+            offset_mean_ms: None,
+            offset_median_ms: None,
+            scoring_model: None,
+            turn_mod: None,
+            turn_seed: 0,
         };
 
         let cached = cached_score_from_local_header(&header);
@@ -7450,6 +7809,11 @@ mod tests {
             hands_achieved: 0,
             fail_time: None,
             beat0_time_ns: 0,
+            offset_mean_ms: None,
+            offset_median_ms: None,
+            scoring_model: None,
+            turn_mod: None,
+            turn_seed: 0,
         };
 
         let cached = cached_score_from_local_header(&header);
@@ -7481,6 +7845,11 @@ mod tests {
             hands_achieved: 11,
             fail_time: None,
             beat0_time_ns: -250_000_000,
+            offset_mean_ms: Some(-3.5),
+            offset_median_ms: Some(-2.0),
+            scoring_model: Some(LocalScoringModel::Wife3),
+            turn_mod: Some(LocalTurnMod::Shuffle),
+            turn_seed: 0x5EED_1234,
             replay: vec![LocalReplayEdge::new(
                 1_500_000_000,
                 2,
@@ -7498,6 +7867,122 @@ mod tests {
         assert_eq!(decoded.replay[0].input_source(), InputSource::Gamepad);
     }
 
+    #[test]
+    fn local_score_v1_files_decode_without_offsets() {
+        let mut entry = test_local_score_entry(1_700_000_000_000, 0.95);
+        entry.replay = vec![LocalReplayEdge::new(
+            1_500_000_000,
+            2,
+            true,
+            InputSource::Gamepad,
+        )];
+        let h = entry.header();
+        let v1 = LocalScoreHeaderV1 {
+            version: LOCAL_SCORE_VERSION_V1,
+            played_at_ms: h.played_at_ms,
+            music_rate: h.music_rate,
+            score_percent: h.score_percent,
+            grade_code: h.grade_code,
+            lamp_index: h.lamp_index,
+            lamp_judge_count: h.lamp_judge_count,
+            ex_score_percent: h.ex_score_percent,
+            hard_ex_score_percent: h.hard_ex_score_percent,
+            judgment_counts: h.judgment_counts,
+            holds_held: h.holds_held,
+            holds_total: h.holds_total,
+            rolls_held: h.rolls_held,
+            rolls_total: h.rolls_total,
+            mines_avoided: h.mines_avoided,
+            mines_total: h.mines_total,
+            hands_achieved: h.hands_achieved,
+            fail_time: h.fail_time,
+            beat0_time_ns: h.beat0_time_ns,
+        };
+        let config = bincode::config::standard();
+        let mut bytes = bincode::encode_to_vec(v1, config).unwrap();
+        bytes.extend(bincode::encode_to_vec(&entry.replay, config).unwrap());
+
+        let decoded = decode_local_score_entry(&bytes).expect("v1 score should decode");
+        assert_eq!(decoded, entry);
+        assert_eq!(decode_local_score_header(&bytes), Some(h));
+    }
+
+    #[test]
+    fn local_score_v2_files_decode_without_scoring_model() {
+        let mut entry = test_local_score_entry(1_700_000_000_000, 0.95);
+        entry.offset_mean_ms = Some(1.5);
+        entry.offset_median_ms = Some(1.0);
+        let h = entry.header();
+        let v2 = LocalScoreHeaderV2 {
+            version: LOCAL_SCORE_VERSION_V2,
+            played_at_ms: h.played_at_ms,
+            music_rate: h.music_rate,
+            score_percent: h.score_percent,
+            grade_code: h.grade_code,
+            lamp_index: h.lamp_index,
+            lamp_judge_count: h.lamp_judge_count,
+            ex_score_percent: h.ex_score_percent,
+            hard_ex_score_percent: h.hard_ex_score_percent,
+            judgment_counts: h.judgment_counts,
+            holds_held: h.holds_held,
+            holds_total: h.holds_total,
+            rolls_held: h.rolls_held,
+            rolls_total: h.rolls_total,
+            mines_avoided: h.mines_avoided,
+            mines_total: h.mines_total,
+            hands_achieved: h.hands_achieved,
+            fail_time: h.fail_time,
+            beat0_time_ns: h.beat0_time_ns,
+            offset_mean_ms: h.offset_mean_ms,
+            offset_median_ms: h.offset_median_ms,
+        };
+        let config = bincode::config::standard();
+        let mut bytes = bincode::encode_to_vec(v2, config).unwrap();
+        bytes.extend(bincode::encode_to_vec(&entry.replay, config).unwrap());
+
+        let decoded = decode_local_score_entry(&bytes).expect("v2 score should decode");
+        assert_eq!(decoded, entry);
+        assert_eq!(decoded.scoring_model, None);
+    }
+
+    #[test]
+    fn local_score_v3_files_decode_without_turn_mod() {
+        let mut entry = test_local_score_entry(1_700_000_000_000, 0.95);
+        entry.scoring_model = Some(LocalScoringModel::DdrMoney);
+        let h = entry.header();
+        let v3 = LocalScoreHeaderV3 {
+            version: LOCAL_SCORE_VERSION_V3,
+            played_at_ms: h.played_at_ms,
+            music_rate: h.music_rate,
+            score_percent: h.score_percent,
+            grade_code: h.grade_code,
+            lamp_index: h.lamp_index,
+            lamp_judge_count: h.lamp_judge_count,
+            ex_score_percent: h.ex_score_percent,
+            hard_ex_score_percent: h.hard_ex_score_percent,
+            judgment_counts: h.judgment_counts,
+            holds_held: h.holds_held,
+            holds_total: h.holds_total,
+            rolls_held: h.rolls_held,
+            rolls_total: h.rolls_total,
+            mines_avoided: h.mines_avoided,
+            mines_total: h.mines_total,
+            hands_achieved: h.hands_achieved,
+            fail_time: h.fail_time,
+            beat0_time_ns: h.beat0_time_ns,
+            offset_mean_ms: h.offset_mean_ms,
+            offset_median_ms: h.offset_median_ms,
+            scoring_model: h.scoring_model,
+        };
+        let config = bincode::config::standard();
+        let mut bytes = bincode::encode_to_vec(v3, config).unwrap();
+        bytes.extend(bincode::encode_to_vec(&entry.replay, config).unwrap());
+
+        let decoded = decode_local_score_entry(&bytes).expect("v3 score should decode");
+        assert_eq!(decoded, entry);
+        assert_eq!(decoded.turn_mod, None);
+    }
+
     fn test_local_score_entry(played_at_ms: i64, score_percent: f64) -> LocalScoreEntry {
         LocalScoreEntry {
             version: LOCAL_SCORE_VERSION,
@@ -7519,10 +8004,53 @@ mod tests {
             hands_achieved: 11,
             fail_time: None,
             beat0_time_ns: 0,
+            offset_mean_ms: None,
+            offset_median_ms: None,
+            scoring_model: None,
+            turn_mod: None,
+            turn_seed: 0,
             replay: Vec::new(),
         }
     }
 
+    #[test]
+    fn profile_history_waits_for_missing_charts_and_maps_turned_replays() {
+        let dir = test_dir("history-missing-chart");
+        let mut entry = test_local_score_entry(1234, 0.9);
+        entry.turn_mod = Some(LocalTurnMod::Mirror);
+        entry.replay = vec![LocalReplayEdge {
+            event_music_time_ns: 1_005_000_000,
+            lane: 3,
+            pressed: true,
+            source: 0,
+        }];
+        write_local_score_entry_file(&dir, "deadbeef", &mut entry).unwrap();
+        let mirror = |turn: LocalTurnMod, _: u64, columns: usize| -> Option<Vec<usize>> {
+            (turn == LocalTurnMod::Mirror).then(|| (0..columns).rev().collect())
+        };
+
+        let mut history = ProfileHistory::default();
+        assert_eq!(
+            refresh_profile_history(&mut history, &dir, 150, |_| None, mirror),
+            0
+        );
+        assert!(history.plays.is_empty());
+
+        let chart = HistoryChart {
+            meter: 9,
+            columns: 4,
+            notes: vec![PatternNote::new(1_000_000_000, 4.0, 0, 120.0)],
+            beat0_time_ns: 0,
+        };
+        assert_eq!(
+            refresh_profile_history(&mut history, &dir, 150, |_| Some(chart.clone()), mirror),
+            1
+        );
+        assert_eq!(history.plays[0].meter, 9);
+        assert_eq!(history.plays[0].offsets.mean_ms(), Some(5.0));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn local_score_append_updates_disk_index() {
         let dir = test_dir("local-append-index");
@@ -7594,6 +8122,11 @@ mod tests {
             hands_achieved: 0,
             fail_time: None,
             beat0_time_ns: 0,
+            offset_mean_ms: None,
+            offset_median_ms: None,
+            scoring_model: None,
+            turn_mod: None,
+            turn_seed: 0,
         };
         let mut newer = older;
         newer.score_percent = 0.9900;
@@ -7939,6 +8472,9 @@ mod tests {
                 mines_avoided: 0,
                 hands_achieved: 0,
                 beat0_time_ns: 123,
+                scoring_model: None,
+                turn_mod: LocalTurnMod::None,
+                turn_seed: 0,
                 replay: Vec::new(),
             }
         };
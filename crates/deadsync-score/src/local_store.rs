@@ -137,9 +137,20 @@ impl ScoreProfilePaths {
     pub fn local_index_path(&self) -> PathBuf {
         self.local_dir().join("index.bin")
     }
+
+    #[inline(always)]
+    pub fn local_history_path(&self) -> PathBuf {
+        self.local_dir().join("history.bin")
+    }
+
+    /// Daily workout logs, one `YYYY-MM-DD.bin` per day with a session.
+    #[inline(always)]
+    pub fn workouts_dir(&self) -> PathBuf {
+        self.profile_dir.join("workouts")
+    }
 }
 
-fn write_index_file<T: bincode::Encode>(
+pub(crate) fn write_index_file<T: bincode::Encode>(
     path: &Path,
     value: &T,
 ) -> Result<(), ScoreIndexWriteError> {
@@ -390,6 +401,28 @@ pub fn recent_played_chart_hashes_in_root(root: &Path) -> Vec<String> {
         .collect()
 }
 
+/// EX percent of the `limit` most recent plays under `root`, newest first,
+/// failed plays included.
+pub fn recent_local_play_ex_in_root(root: &Path, limit: usize) -> Vec<(String, f64)> {
+    let mut plays: Vec<(i64, String, PathBuf)> = local_score_files_in_root(root)
+        .into_iter()
+        .filter_map(|(chart_hash, path)| {
+            let name = path.file_name()?.to_str()?;
+            let (_, played_at_ms) = parse_score_file_name(name)?;
+            Some((played_at_ms, chart_hash, path))
+        })
+        .collect();
+    plays.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    plays
+        .into_iter()
+        .filter_map(|(_, chart_hash, path)| {
+            let header = read_local_score_header(&path)?;
+            Some((chart_hash, header.ex_score_percent))
+        })
+        .take(limit)
+        .collect()
+}
+
 pub fn recent_played_chart_hashes_in_profiles_root(profiles_root: &Path) -> Vec<String> {
     let Ok(read_dir) = fs::read_dir(profiles_root) else {
         return Vec::new();
@@ -488,6 +521,41 @@ pub fn played_chart_counts_in_profiles_root(profiles_root: &Path) -> Vec<(String
     ranked
 }
 
+fn collect_score_files_in_dir(dir: &Path, out: &mut Vec<(String, PathBuf)>) {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some((chart_hash, _played_at_ms)) = parse_score_file_name(name) else {
+            continue;
+        };
+        out.push((chart_hash.to_string(), path));
+    }
+}
+
+/// Every local score file in a profile's score root, with its chart hash.
+pub fn local_score_files_in_root(root: &Path) -> Vec<(String, PathBuf)> {
+    let mut out = Vec::new();
+    collect_score_files_in_dir(root, &mut out);
+    let Ok(read_dir) = fs::read_dir(root) else {
+        return out;
+    };
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_score_files_in_dir(&path, &mut out);
+        }
+    }
+    out
+}
+
 pub fn read_local_score_header(path: &Path) -> Option<LocalScoreHeader> {
     let file = fs::File::open(path).ok()?;
     let mut buf = Vec::with_capacity(1024);
@@ -0,0 +1,289 @@
+//! Timing-offset drift across plays.
+//!
+//! Each local score stores the mean and median tap offset of its play. This
+//! module gathers those medians for a profile (or every profile on the
+//! machine) and looks for two kinds of consistent bias: recent plays leaning
+//! one way, which points at the global offset, and packs that sit away from
+//! the player's usual timing, which points at the pack's sync.
+
+use crate::{local_score_files_in_root, parse_score_file_name, read_local_score_header};
+use deadsync_rules::judgment::JudgeGrade;
+use deadsync_rules::note::Note;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Most recent plays compared against zero for a global offset proposal.
+pub const OFFSET_DRIFT_RECENT_PLAYS: usize = 20;
+/// Recent plays needed before a global offset change is proposed.
+pub const OFFSET_DRIFT_MIN_RECENT_PLAYS: usize = 10;
+/// Smallest recent median, in ms, worth a global offset change.
+pub const OFFSET_DRIFT_GLOBAL_THRESHOLD_MS: f32 = 5.0;
+/// Plays of one pack needed before it can be flagged.
+pub const OFFSET_DRIFT_MIN_PACK_PLAYS: usize = 3;
+/// Distance from the baseline, in ms, at which a pack looks off-sync.
+pub const OFFSET_DRIFT_PACK_THRESHOLD_MS: f32 = 8.0;
+/// Share of plays that must lean the same way for a bias to count as
+/// consistent rather than noise.
+const OFFSET_DRIFT_AGREEMENT: f32 = 0.75;
+
+/// Mean and median tap offset of one play, in ms; positive is late.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PlayOffsetSummary {
+    pub mean_ms: Option<f32>,
+    pub median_ms: Option<f32>,
+}
+
+/// Uses the same judgments autosync trusts: Fantastic through Great taps.
+pub fn play_offset_summary(notes: &[Note]) -> PlayOffsetSummary {
+    let mut offsets: Vec<f32> = notes
+        .iter()
+        .filter(|note| note.can_be_judged)
+        .filter_map(|note| note.result.as_ref())
+        .filter(|judgment| {
+            matches!(
+                judgment.grade,
+                JudgeGrade::Fantastic | JudgeGrade::Excellent | JudgeGrade::Great
+            ) && judgment.time_error_ms.is_finite()
+        })
+        .map(|judgment| judgment.time_error_ms)
+        .collect();
+    if offsets.is_empty() {
+        return PlayOffsetSummary::default();
+    }
+    let mean = offsets.iter().map(|&ms| f64::from(ms)).sum::<f64>() / offsets.len() as f64;
+    PlayOffsetSummary {
+        mean_ms: Some(mean as f32),
+        median_ms: median_ms(&mut offsets),
+    }
+}
+
+fn median_ms(values: &mut [f32]) -> Option<f32> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable_by(f32::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) * 0.5
+    } else {
+        values[mid]
+    })
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OffsetDriftPlay {
+    pub chart_hash: String,
+    pub played_at_ms: i64,
+    pub median_ms: f32,
+}
+
+/// Plays under a local score root that recorded an offset, oldest first.
+pub fn offset_drift_plays_in_root(root: &Path) -> Vec<OffsetDriftPlay> {
+    let mut plays: Vec<OffsetDriftPlay> = local_score_files_in_root(root)
+        .into_iter()
+        .filter_map(|(chart_hash, path)| {
+            let name = path.file_name()?.to_str()?;
+            parse_score_file_name(name)?;
+            let header = read_local_score_header(&path)?;
+            Some(OffsetDriftPlay {
+                chart_hash,
+                played_at_ms: header.played_at_ms,
+                median_ms: header.offset_median_ms.filter(|ms| ms.is_finite())?,
+            })
+        })
+        .collect();
+    plays.sort_by(|a, b| {
+        a.played_at_ms
+            .cmp(&b.played_at_ms)
+            .then_with(|| a.chart_hash.cmp(&b.chart_hash))
+    });
+    plays
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackOffset {
+    pub pack: String,
+    pub plays: usize,
+    pub median_ms: f32,
+    /// Pack median minus the baseline; positive means the pack plays late.
+    pub delta_ms: f32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OffsetDriftReport {
+    /// Oldest first.
+    pub plays: Vec<OffsetDriftPlay>,
+    /// Median over every play.
+    pub baseline_ms: Option<f32>,
+    /// Median over the last [`OFFSET_DRIFT_RECENT_PLAYS`] plays.
+    pub recent_ms: Option<f32>,
+    /// Change to the global offset, in ms, that would center recent plays.
+    /// Only set when they lean consistently one way.
+    pub global_offset_change_ms: Option<f32>,
+    /// Packs that sit consistently away from the baseline, furthest first.
+    pub off_sync_packs: Vec<PackOffset>,
+}
+
+/// Whether enough of `values` fall on the same side of `center` as `bias`.
+fn leans_consistently(values: impl Iterator<Item = f32>, center: f32, bias: f32) -> bool {
+    let mut total = 0usize;
+    let mut agreeing = 0usize;
+    for value in values {
+        total += 1;
+        if (value - center).signum() == bias.signum() {
+            agreeing += 1;
+        }
+    }
+    total > 0 && agreeing as f32 >= total as f32 * OFFSET_DRIFT_AGREEMENT
+}
+
+pub fn offset_drift_report(
+    plays: Vec<OffsetDriftPlay>,
+    mut pack_for_hash: impl FnMut(&str) -> Option<String>,
+) -> OffsetDriftReport {
+    let mut medians: Vec<f32> = plays.iter().map(|play| play.median_ms).collect();
+    let baseline_ms = median_ms(&mut medians);
+    let recent = &plays[plays.len().saturating_sub(OFFSET_DRIFT_RECENT_PLAYS)..];
+    let mut recent_medians: Vec<f32> = recent.iter().map(|play| play.median_ms).collect();
+    let recent_ms = median_ms(&mut recent_medians);
+
+    // Autosync moves the global offset by the mean early offset, so a late
+    // lean asks for a lower global offset.
+    let global_offset_change_ms = recent_ms.filter(|&recent_ms| {
+        recent.len() >= OFFSET_DRIFT_MIN_RECENT_PLAYS
+            && recent_ms.abs() >= OFFSET_DRIFT_GLOBAL_THRESHOLD_MS
+            && leans_consistently(recent.iter().map(|play| play.median_ms), 0.0, recent_ms)
+    });
+
+    let mut by_pack: HashMap<String, Vec<f32>> = HashMap::new();
+    for play in &plays {
+        if let Some(pack) = pack_for_hash(&play.chart_hash) {
+            by_pack.entry(pack).or_default().push(play.median_ms);
+        }
+    }
+    let mut off_sync_packs: Vec<PackOffset> = match baseline_ms {
+        Some(baseline) => by_pack
+            .into_iter()
+            .filter(|(_, medians)| medians.len() >= OFFSET_DRIFT_MIN_PACK_PLAYS)
+            .filter_map(|(pack, mut medians)| {
+                let median = median_ms(&mut medians)?;
+                let delta_ms = median - baseline;
+                (delta_ms.abs() >= OFFSET_DRIFT_PACK_THRESHOLD_MS
+                    && leans_consistently(medians.iter().copied(), baseline, delta_ms))
+                .then_some(PackOffset {
+                    pack,
+                    plays: medians.len(),
+                    median_ms: median,
+                    delta_ms,
+                })
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    off_sync_packs.sort_by(|a, b| {
+        b.delta_ms
+            .abs()
+            .total_cmp(&a.delta_ms.abs())
+            .then_with(|| a.pack.cmp(&b.pack))
+    });
+
+    OffsetDriftReport {
+        plays,
+        baseline_ms,
+        recent_ms,
+        global_offset_change_ms: global_offset_change_ms.map(|ms| -ms),
+        off_sync_packs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deadsync_core::note::NoteType;
+    use deadsync_rules::judgment::Judgment;
+
+    fn tap(grade: JudgeGrade, time_error_ms: f32) -> Note {
+        Note {
+            beat: 0.0,
+            quantization_idx: 0,
+            column: 0,
+            note_type: NoteType::Tap,
+            row_index: 0,
+            result: Some(Judgment {
+                time_error_ms,
+                time_error_music_ns: 0,
+                grade,
+                window: None,
+                miss_because_held: false,
+            }),
+            early_result: None,
+            hold: None,
+            mine_result: None,
+            is_fake: false,
+            can_be_judged: true,
+        }
+    }
+
+    fn play(hash: &str, played_at_ms: i64, median_ms: f32) -> OffsetDriftPlay {
+        OffsetDriftPlay {
+            chart_hash: hash.to_string(),
+            played_at_ms,
+            median_ms,
+        }
+    }
+
+    #[test]
+    fn play_offsets_ignore_decents_and_misses() {
+        let notes = [
+            tap(JudgeGrade::Fantastic, 4.0),
+            tap(JudgeGrade::Excellent, 10.0),
+            tap(JudgeGrade::Great, -2.0),
+            tap(JudgeGrade::Great, 6.0),
+            tap(JudgeGrade::Decent, 120.0),
+            tap(JudgeGrade::Miss, 0.0),
+        ];
+        let summary = play_offset_summary(&notes);
+        assert_eq!(summary.mean_ms, Some(4.5));
+        assert_eq!(summary.median_ms, Some(5.0));
+        assert_eq!(play_offset_summary(&[]), PlayOffsetSummary::default());
+    }
+
+    #[test]
+    fn consistent_late_recent_plays_propose_lowering_the_global_offset() {
+        let mut plays: Vec<_> = (0..10).map(|i| play("old", i, 0.0)).collect();
+        plays.extend((10..30).map(|i| play("new", i, if i % 5 == 0 { -1.0 } else { 10.0 })));
+        let report = offset_drift_report(plays, |_| None);
+        assert_eq!(report.recent_ms, Some(10.0));
+        assert_eq!(report.global_offset_change_ms, Some(-10.0));
+
+        // Noisy plays that only average late do not count as drift.
+        let noisy: Vec<_> = (0..20)
+            .map(|i| play("a", i, if i % 2 == 0 { 30.0 } else { -4.0 }))
+            .collect();
+        assert_eq!(
+            offset_drift_report(noisy, |_| None).global_offset_change_ms,
+            None
+        );
+    }
+
+    #[test]
+    fn packs_far_from_the_baseline_are_flagged() {
+        let mut plays = Vec::new();
+        for i in 0..10 {
+            plays.push(play("synced", i, 1.0));
+        }
+        for i in 10..14 {
+            plays.push(play("early", i, -14.0));
+        }
+        for i in 14..16 {
+            plays.push(play("few", i, 40.0));
+        }
+        let report = offset_drift_report(plays, |hash| Some(format!("Pack {hash}")));
+        assert_eq!(report.baseline_ms, Some(1.0));
+        assert_eq!(report.off_sync_packs.len(), 1);
+        let pack = &report.off_sync_packs[0];
+        assert_eq!(pack.pack, "Pack early");
+        assert_eq!(pack.plays, 4);
+        assert_eq!(pack.delta_ms, -15.0);
+    }
+}
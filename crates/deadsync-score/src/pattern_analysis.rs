@@ -0,0 +1,929 @@
+//! Per-pattern timing analysis built from stored replays.
+//!
+//! A chart is reduced to its pressed notes, in the lanes the player saw after
+//! their turn mod, and each note is tagged with the pattern it sits in.
+//! Crossovers, footswitches, candles and brackets come from the feet rssp's
+//! step parity puts on each row (see [`PatternRowFeet`]). Stored replays are
+//! then re-judged note by note, and timing error and misses are accumulated
+//! per pattern and BPM band across a profile's history.
+
+use crate::LocalReplayEdge;
+use deadsync_chart::{ChartData, GameplayChartData, SongData};
+use deadsync_core::note::NoteType;
+use deadsync_core::song_time::{SongTimeNs, song_time_ns_from_seconds};
+use deadsync_rules::timing::{BASE_W5_S, TIMING_WINDOW_ADD_S};
+use std::collections::HashMap;
+use std::ops::{Range, RangeInclusive};
+use std::path::PathBuf;
+
+/// Presses further than the way-off window from every note never judge it.
+const PATTERN_MISS_WINDOW_S: f32 = BASE_W5_S + TIMING_WINDOW_ADD_S;
+/// Rows at most this far apart are played as one run with alternating feet.
+const PATTERN_RUN_MAX_GAP_BEATS: f32 = 0.5;
+/// A measure with this many note rows counts as stream, like the breakdowns.
+const PATTERN_STREAM_MEASURE_ROWS: usize = 16;
+/// Categories with fewer judged notes are too noisy to rank.
+pub const PATTERN_MIN_NOTES: u32 = 50;
+
+/// Row beats from the chart and from step parity match within this.
+const PATTERN_ROW_BEAT_EPSILON: f32 = 1e-3;
+
+const PANEL_DOWN: u8 = 1;
+const PANEL_UP: u8 = 2;
+const PANELS_PER_PAD: u8 = 4;
+
+pub const PATTERN_CATEGORY_COUNT: usize = 7;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PatternCategory {
+    Stream,
+    Break,
+    Jack,
+    Footswitch,
+    Crossover,
+    Candle,
+    Bracket,
+}
+
+impl PatternCategory {
+    pub const ALL: [Self; PATTERN_CATEGORY_COUNT] = [
+        Self::Stream,
+        Self::Break,
+        Self::Jack,
+        Self::Footswitch,
+        Self::Crossover,
+        Self::Candle,
+        Self::Bracket,
+    ];
+
+    #[inline(always)]
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    #[inline(always)]
+    const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+pub const PATTERN_BPM_BAND_COUNT: usize = 5;
+
+/// Effective BPM (chart BPM times music rate) a note was played at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PatternBpmBand {
+    Under150,
+    From150,
+    From175,
+    From200,
+    From225,
+}
+
+impl PatternBpmBand {
+    pub const ALL: [Self; PATTERN_BPM_BAND_COUNT] = [
+        Self::Under150,
+        Self::From150,
+        Self::From175,
+        Self::From200,
+        Self::From225,
+    ];
+
+    #[inline(always)]
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn from_bpm(bpm: f32) -> Self {
+        match bpm {
+            bpm if bpm >= 225.0 => Self::From225,
+            bpm if bpm >= 200.0 => Self::From200,
+            bpm if bpm >= 175.0 => Self::From175,
+            bpm if bpm >= 150.0 => Self::From150,
+            _ => Self::Under150,
+        }
+    }
+
+    /// Lower bound and exclusive upper bound, if any.
+    pub const fn bpm_range(self) -> (f32, Option<f32>) {
+        match self {
+            Self::Under150 => (0.0, Some(150.0)),
+            Self::From150 => (150.0, Some(175.0)),
+            Self::From175 => (175.0, Some(200.0)),
+            Self::From200 => (200.0, Some(225.0)),
+            Self::From225 => (225.0, None),
+        }
+    }
+}
+
+/// The categories a note belongs to. Every note is either stream or break.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PatternTags(u8);
+
+impl PatternTags {
+    #[inline(always)]
+    pub const fn contains(self, category: PatternCategory) -> bool {
+        self.0 & category.bit() != 0
+    }
+
+    #[inline(always)]
+    fn insert(&mut self, category: PatternCategory) {
+        self.0 |= category.bit();
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = PatternCategory> {
+        PatternCategory::ALL
+            .into_iter()
+            .filter(move |&category| self.contains(category))
+    }
+}
+
+/// A note judged on press, in chart order.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PatternNote {
+    pub time_ns: SongTimeNs,
+    pub beat: f32,
+    pub column: u8,
+    pub bpm: f32,
+    pub tags: PatternTags,
+}
+
+impl PatternNote {
+    pub const fn new(time_ns: SongTimeNs, beat: f32, column: u8, bpm: f32) -> Self {
+        Self {
+            time_ns,
+            beat,
+            column,
+            bpm,
+            tags: PatternTags(0),
+        }
+    }
+}
+
+/// Feet step parity put on one row, in played lanes. Built from
+/// `deadsync_simfile::timing::crossover_annotations`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PatternRowFeet {
+    pub beat: f32,
+    pub left_foot_mask: u8,
+    pub right_foot_mask: u8,
+    pub crossover: bool,
+    pub bracket: bool,
+}
+
+/// Tagged notes for one player's columns of a loaded chart. `lanes` maps each
+/// chart column to the lane it was played on (see the score's turn mod), and
+/// `feet` holds the step parity of those played rows, sorted by beat.
+pub fn pattern_notes_from_chart(
+    chart: &GameplayChartData,
+    columns: usize,
+    lanes: &[usize],
+    feet: &[PatternRowFeet],
+) -> Vec<PatternNote> {
+    let timing = &chart.timing;
+    let mut notes: Vec<PatternNote> = chart
+        .parsed_notes
+        .iter()
+        .filter(|note| {
+            matches!(
+                note.note_type,
+                NoteType::Tap | NoteType::Hold | NoteType::Roll
+            ) && note.column < columns
+        })
+        .filter_map(|note| {
+            let beat = timing.get_beat_for_row(note.row_index)?;
+            timing.is_judgable_at_beat(beat).then(|| {
+                PatternNote::new(
+                    timing.get_time_for_beat_ns(beat),
+                    beat,
+                    lanes.get(note.column).copied().unwrap_or(note.column) as u8,
+                    timing.get_bpm_for_beat(beat),
+                )
+            })
+        })
+        .collect();
+    notes.sort_by(|a, b| a.time_ns.cmp(&b.time_ns).then(a.column.cmp(&b.column)));
+    classify_pattern_notes(&mut notes, feet);
+    notes
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Foot {
+    Left,
+    Right,
+}
+
+impl PatternRowFeet {
+    fn foot(&self, panel: u8) -> Option<Foot> {
+        let bit = 1u8.checked_shl(u32::from(panel))?;
+        if self.left_foot_mask & bit != 0 {
+            Some(Foot::Left)
+        } else if self.right_foot_mask & bit != 0 {
+            Some(Foot::Right)
+        } else {
+            None
+        }
+    }
+}
+
+/// Down to up (or back) on the same pad with one foot.
+#[inline(always)]
+fn is_candle(from: u8, to: u8) -> bool {
+    from / PANELS_PER_PAD == to / PANELS_PER_PAD
+        && matches!(
+            (from % PANELS_PER_PAD, to % PANELS_PER_PAD),
+            (PANEL_DOWN, PANEL_UP) | (PANEL_UP, PANEL_DOWN)
+        )
+}
+
+fn pattern_rows(notes: &[PatternNote]) -> Vec<Range<usize>> {
+    let mut rows: Vec<Range<usize>> = Vec::new();
+    for (ix, note) in notes.iter().enumerate() {
+        match rows.last_mut() {
+            Some(row) if notes[row.start].time_ns == note.time_ns => row.end = ix + 1,
+            _ => rows.push(ix..ix + 1),
+        }
+    }
+    rows
+}
+
+#[inline(always)]
+fn pattern_measure(beat: f32) -> i64 {
+    (beat / 4.0).floor() as i64
+}
+
+/// Tags `notes` (sorted by time) with their pattern categories. Foot-based
+/// categories need the row's entry in `feet`; rows without one (styles step
+/// parity does not cover) only get stream, break and jack.
+pub fn classify_pattern_notes(notes: &mut [PatternNote], feet: &[PatternRowFeet]) {
+    let rows = pattern_rows(notes);
+    let mut rows_per_measure: HashMap<i64, usize> = HashMap::new();
+    for row in &rows {
+        *rows_per_measure
+            .entry(pattern_measure(notes[row.start].beat))
+            .or_default() += 1;
+    }
+
+    let mut feet_ix = 0;
+    let mut last_panel: [Option<u8>; 2] = [None; 2];
+    let mut prev_beat: Option<f32> = None;
+    let mut prev_single: Option<(u8, Option<Foot>)> = None;
+    for row in &rows {
+        let beat = notes[row.start].beat;
+        while feet_ix < feet.len() && feet[feet_ix].beat < beat - PATTERN_ROW_BEAT_EPSILON {
+            feet_ix += 1;
+        }
+        let row_feet = feet
+            .get(feet_ix)
+            .filter(|row_feet| (row_feet.beat - beat).abs() <= PATTERN_ROW_BEAT_EPSILON);
+        let stream = rows_per_measure
+            .get(&pattern_measure(beat))
+            .is_some_and(|&count| count >= PATTERN_STREAM_MEASURE_ROWS);
+        let in_run = prev_beat.is_some_and(|prev| beat - prev <= PATTERN_RUN_MAX_GAP_BEATS);
+        let mut tags = PatternTags::default();
+        tags.insert(if stream {
+            PatternCategory::Stream
+        } else {
+            PatternCategory::Break
+        });
+
+        if row.len() == 1 {
+            let panel = notes[row.start].column;
+            let foot = row_feet.and_then(|row_feet| row_feet.foot(panel));
+            if in_run
+                && let Some((prev_panel, prev_foot)) = prev_single
+                && prev_panel == panel
+            {
+                // The same panel with the other foot is a footswitch.
+                match (prev_foot, foot) {
+                    (Some(prev), Some(foot)) if prev != foot => {
+                        tags.insert(PatternCategory::Footswitch)
+                    }
+                    _ => tags.insert(PatternCategory::Jack),
+                }
+            }
+            if row_feet.is_some_and(|row_feet| row_feet.crossover) {
+                tags.insert(PatternCategory::Crossover);
+            }
+            if let Some(foot) = foot {
+                let slot = &mut last_panel[foot as usize];
+                if in_run && slot.is_some_and(|from| is_candle(from, panel)) {
+                    tags.insert(PatternCategory::Candle);
+                }
+                *slot = Some(panel);
+            }
+            prev_single = Some((panel, foot));
+        } else {
+            if in_run && row_feet.is_some_and(|row_feet| row_feet.bracket) {
+                tags.insert(PatternCategory::Bracket);
+            }
+            // Jumps and brackets put both feet down again.
+            last_panel = [None; 2];
+            prev_single = None;
+        }
+
+        for note in &mut notes[row.clone()] {
+            note.tags = tags;
+        }
+        prev_beat = Some(beat);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PatternJudgment {
+    /// Real-time error in milliseconds; positive is late.
+    Hit(f32),
+    Miss,
+    /// After the last input, e.g. the player quit or failed out.
+    Unplayed,
+}
+
+/// Re-judges a stored replay against `notes`, which must already be in played
+/// lanes (see [`pattern_notes_from_chart`]). Each press takes the closest
+/// unjudged note in its lane within the way-off window, like gameplay does.
+/// `beat0_shift_ns` moves the recorded inputs onto the current chart timing.
+pub fn judge_pattern_replay(
+    notes: &[PatternNote],
+    replay: &[LocalReplayEdge],
+    beat0_shift_ns: SongTimeNs,
+    music_rate: f32,
+) -> Vec<PatternJudgment> {
+    let rate = if music_rate.is_finite() && music_rate > 0.0 {
+        music_rate
+    } else {
+        1.0
+    };
+    // Replays store music time, so the real window is wider at higher rates.
+    let window_ns = song_time_ns_from_seconds(PATTERN_MISS_WINDOW_S * rate);
+    let mut judgments = vec![PatternJudgment::Miss; notes.len()];
+
+    let mut lanes: HashMap<u8, Vec<usize>> = HashMap::new();
+    for (ix, note) in notes.iter().enumerate() {
+        lanes.entry(note.column).or_default().push(ix);
+    }
+    let mut lane_start: HashMap<u8, usize> = HashMap::new();
+    let mut judged = vec![false; notes.len()];
+    let mut last_press_ns: Option<SongTimeNs> = None;
+
+    let mut presses: Vec<(SongTimeNs, u8)> = replay
+        .iter()
+        .filter(|edge| edge.pressed)
+        .map(|edge| {
+            (
+                edge.event_music_time_ns.saturating_add(beat0_shift_ns),
+                edge.lane,
+            )
+        })
+        .collect();
+    presses.sort_unstable();
+
+    for (press_ns, lane) in presses {
+        last_press_ns = Some(press_ns);
+        let Some(lane_notes) = lanes.get(&lane) else {
+            continue;
+        };
+        let start = lane_start.entry(lane).or_default();
+        while *start < lane_notes.len()
+            && notes[lane_notes[*start]].time_ns.saturating_add(window_ns) < press_ns
+        {
+            *start += 1;
+        }
+        let closest = lane_notes[*start..]
+            .iter()
+            .copied()
+            .take_while(|&ix| notes[ix].time_ns <= press_ns.saturating_add(window_ns))
+            .filter(|&ix| !judged[ix])
+            .min_by_key(|&ix| (notes[ix].time_ns - press_ns).abs());
+        if let Some(ix) = closest {
+            judged[ix] = true;
+            let error_ns = press_ns - notes[ix].time_ns;
+            judgments[ix] = PatternJudgment::Hit(error_ns as f32 / 1_000_000.0 / rate);
+        }
+    }
+
+    let cutoff_ns = last_press_ns.map(|time| time.saturating_add(window_ns));
+    for (note, judgment) in notes.iter().zip(&mut judgments) {
+        if *judgment == PatternJudgment::Miss
+            && cutoff_ns.is_none_or(|cutoff| note.time_ns > cutoff)
+        {
+            *judgment = PatternJudgment::Unplayed;
+        }
+    }
+    judgments
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PatternStats {
+    pub notes: u32,
+    pub misses: u32,
+    pub abs_error_ms: f64,
+    pub error_ms: f64,
+}
+
+impl PatternStats {
+    fn add(&mut self, judgment: PatternJudgment) {
+        match judgment {
+            PatternJudgment::Hit(error_ms) => {
+                self.notes += 1;
+                self.abs_error_ms += f64::from(error_ms.abs());
+                self.error_ms += f64::from(error_ms);
+            }
+            PatternJudgment::Miss => {
+                self.notes += 1;
+                self.misses += 1;
+            }
+            PatternJudgment::Unplayed => {}
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.notes += other.notes;
+        self.misses += other.misses;
+        self.abs_error_ms += other.abs_error_ms;
+        self.error_ms += other.error_ms;
+    }
+
+    #[inline(always)]
+    pub const fn hits(&self) -> u32 {
+        self.notes - self.misses
+    }
+
+    pub fn miss_rate(&self) -> f32 {
+        if self.notes == 0 {
+            0.0
+        } else {
+            self.misses as f32 / self.notes as f32
+        }
+    }
+
+    pub fn mean_abs_error_ms(&self) -> Option<f32> {
+        let hits = self.hits();
+        (hits > 0).then(|| (self.abs_error_ms / f64::from(hits)) as f32)
+    }
+
+    /// Positive means the player tends to be late.
+    pub fn mean_error_ms(&self) -> Option<f32> {
+        let hits = self.hits();
+        (hits > 0).then(|| (self.error_ms / f64::from(hits)) as f32)
+    }
+
+    /// Mean absolute error with each miss counted as the full miss window, so
+    /// timing and misses rank on one scale.
+    pub fn penalty_ms(&self) -> Option<f32> {
+        (self.notes > 0).then(|| {
+            let miss_ms = f64::from(self.misses) * f64::from(PATTERN_MISS_WINDOW_S) * 1000.0;
+            ((self.abs_error_ms + miss_ms) / f64::from(self.notes)) as f32
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PatternFocusKind {
+    Category(PatternCategory),
+    BpmBand(PatternBpmBand),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PatternFocus {
+    pub kind: PatternFocusKind,
+    pub stats: PatternStats,
+    /// Penalty relative to the profile's overall penalty; above 1 is weaker.
+    pub relative: f32,
+}
+
+/// One judged play's stats, kept so a replay is only re-judged once.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PatternPlay {
+    pub meter: u32,
+    pub overall: PatternStats,
+    pub categories: [PatternStats; PATTERN_CATEGORY_COUNT],
+    pub bpm_bands: [PatternStats; PATTERN_BPM_BAND_COUNT],
+}
+
+impl PatternPlay {
+    /// `None` when the replay never reached a note.
+    pub fn judge(
+        meter: u32,
+        notes: &[PatternNote],
+        judgments: &[PatternJudgment],
+        music_rate: f32,
+    ) -> Option<Self> {
+        if !judgments
+            .iter()
+            .any(|j| !matches!(j, PatternJudgment::Unplayed))
+        {
+            return None;
+        }
+        let rate = if music_rate.is_finite() && music_rate > 0.0 {
+            music_rate
+        } else {
+            1.0
+        };
+        let mut play = Self {
+            meter,
+            ..Self::default()
+        };
+        for (note, &judgment) in notes.iter().zip(judgments) {
+            play.overall.add(judgment);
+            for category in note.tags.iter() {
+                play.categories[category.index()].add(judgment);
+            }
+            play.bpm_bands[PatternBpmBand::from_bpm(note.bpm * rate).index()].add(judgment);
+        }
+        Some(play)
+    }
+}
+
+/// Pattern stats accumulated over a profile's replays.
+#[derive(Clone, Debug, Default)]
+pub struct PatternProfile {
+    pub plays: u32,
+    pub overall: PatternStats,
+    pub categories: [PatternStats; PATTERN_CATEGORY_COUNT],
+    pub bpm_bands: [PatternStats; PATTERN_BPM_BAND_COUNT],
+    /// Per-chart category stats, for pointing back at charts already played.
+    pub charts: HashMap<String, [PatternStats; PATTERN_CATEGORY_COUNT]>,
+    /// Meter of every analyzed play.
+    pub meters: Vec<u32>,
+}
+
+impl PatternProfile {
+    pub fn add_play(&mut self, chart_hash: &str, play: &PatternPlay) {
+        self.plays += 1;
+        self.meters.push(play.meter);
+        self.overall.merge(&play.overall);
+        let chart = self.charts.entry(chart_hash.to_string()).or_default();
+        for ((total, per_chart), stats) in self
+            .categories
+            .iter_mut()
+            .zip(chart.iter_mut())
+            .zip(&play.categories)
+        {
+            total.merge(stats);
+            per_chart.merge(stats);
+        }
+        for (total, stats) in self.bpm_bands.iter_mut().zip(&play.bpm_bands) {
+            total.merge(stats);
+        }
+    }
+
+    #[inline(always)]
+    pub fn stats(&self, kind: PatternFocusKind) -> PatternStats {
+        match kind {
+            PatternFocusKind::Category(category) => self.categories[category.index()],
+            PatternFocusKind::BpmBand(band) => self.bpm_bands[band.index()],
+        }
+    }
+
+    /// Categories and BPM bands with enough notes, weakest first.
+    pub fn focuses(&self) -> Vec<PatternFocus> {
+        let Some(overall) = self.overall.penalty_ms().filter(|penalty| *penalty > 0.0) else {
+            return Vec::new();
+        };
+        let mut focuses: Vec<PatternFocus> = PatternCategory::ALL
+            .into_iter()
+            .map(PatternFocusKind::Category)
+            .chain(
+                PatternBpmBand::ALL
+                    .into_iter()
+                    .map(PatternFocusKind::BpmBand),
+            )
+            .filter_map(|kind| {
+                let stats = self.stats(kind);
+                if stats.notes < PATTERN_MIN_NOTES {
+                    return None;
+                }
+                Some(PatternFocus {
+                    kind,
+                    stats,
+                    relative: stats.penalty_ms()? / overall,
+                })
+            })
+            .collect();
+        focuses.sort_by(|a, b| b.relative.total_cmp(&a.relative));
+        focuses
+    }
+
+    /// Median played meter, widened by one either side.
+    pub fn practice_meters(&self) -> Option<RangeInclusive<u32>> {
+        let mut meters = self.meters.clone();
+        meters.sort_unstable();
+        let median = *meters.get(meters.len() / 2)?;
+        Some(median.saturating_sub(1)..=median + 1)
+    }
+}
+
+/// How much of `kind` a chart offers, or `None` when chart data cannot tell.
+pub fn pattern_chart_density(kind: PatternFocusKind, chart: &ChartData) -> Option<f32> {
+    let steps = chart.stats.total_steps.max(1) as f32;
+    let stream = chart.total_streams as f32 / chart.total_measures.max(1) as f32;
+    let tech = &chart.tech_counts;
+    match kind {
+        PatternFocusKind::Category(PatternCategory::Stream) => Some(stream),
+        PatternFocusKind::Category(PatternCategory::Jack) => Some(tech.jacks as f32 / steps),
+        PatternFocusKind::Category(PatternCategory::Footswitch) => {
+            Some(tech.footswitches as f32 / steps)
+        }
+        PatternFocusKind::Category(PatternCategory::Crossover) => {
+            Some(tech.crossovers as f32 / steps)
+        }
+        PatternFocusKind::Category(PatternCategory::Bracket) => Some(tech.brackets as f32 / steps),
+        PatternFocusKind::Category(PatternCategory::Break | PatternCategory::Candle) => None,
+        PatternFocusKind::BpmBand(band) => {
+            let (low, high) = band.bpm_range();
+            let bpm = chart.max_bpm as f32;
+            let in_band = bpm >= low && high.is_none_or(|high| bpm < high);
+            Some(if in_band { stream } else { 0.0 })
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatternChartPick {
+    pub chart_hash: String,
+    pub simfile_path: PathBuf,
+    pub title: String,
+    pub difficulty: String,
+    pub meter: u32,
+}
+
+fn pattern_chart_pick(song: &SongData, chart: &ChartData) -> PatternChartPick {
+    PatternChartPick {
+        chart_hash: chart.short_hash.clone(),
+        simfile_path: song.simfile_path.clone(),
+        title: song.display_full_title(false),
+        difficulty: chart.difficulty.clone(),
+        meter: chart.meter,
+    }
+}
+
+/// Charts to practice `kind` on. Library charts near the player's level are
+/// ranked by how much of the pattern they contain; patterns the chart data
+/// cannot measure fall back to played charts where the player did worst.
+pub fn recommend_pattern_charts<'a>(
+    profile: &PatternProfile,
+    kind: PatternFocusKind,
+    chart_type: &str,
+    songs: impl IntoIterator<Item = &'a SongData>,
+    limit: usize,
+) -> Vec<PatternChartPick> {
+    let meters = profile.practice_meters().unwrap_or(0..=u32::MAX);
+    let mut ranked: Vec<(f32, &'a SongData, &'a ChartData)> = Vec::new();
+    for song in songs {
+        for chart in &song.charts {
+            if !chart.has_note_data || !chart.chart_type.eq_ignore_ascii_case(chart_type) {
+                continue;
+            }
+            let weight = match (kind, pattern_chart_density(kind, chart)) {
+                (_, Some(density)) if meters.contains(&chart.meter) => density,
+                (_, Some(_)) => continue,
+                (PatternFocusKind::Category(category), None) => {
+                    let Some(stats) = profile.charts.get(&chart.short_hash) else {
+                        continue;
+                    };
+                    let stats = stats[category.index()];
+                    stats.penalty_ms().unwrap_or(0.0) * stats.notes as f32
+                }
+                (PatternFocusKind::BpmBand(_), None) => continue,
+            };
+            if weight > 0.0 {
+                ranked.push((weight, song, chart));
+            }
+        }
+    }
+    ranked.sort_by(|a, b| {
+        b.0.total_cmp(&a.0)
+            .then_with(|| a.2.short_hash.cmp(&b.2.short_hash))
+    });
+    ranked.dedup_by(|a, b| a.2.short_hash == b.2.short_hash);
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|(_, song, chart)| pattern_chart_pick(song, chart))
+        .collect()
+}
+
+/// The analysis shown on the strengths/weaknesses screen.
+#[derive(Clone, Debug, Default)]
+pub struct PatternReport {
+    pub profile: PatternProfile,
+    pub focuses: Vec<PatternFocus>,
+    /// Practice charts for the weakest focuses, weakest first.
+    pub picks: Vec<(PatternFocusKind, Vec<PatternChartPick>)>,
+    /// Plays skipped because their chart is no longer in the library.
+    pub missing_plays: u32,
+}
+
+pub fn build_pattern_report<'a>(
+    profile: PatternProfile,
+    missing_plays: u32,
+    chart_type: &str,
+    songs: impl IntoIterator<Item = &'a SongData> + Clone,
+    weakest: usize,
+    picks_per_focus: usize,
+) -> PatternReport {
+    let focuses = profile.focuses();
+    let picks = focuses
+        .iter()
+        .filter(|focus| focus.relative > 1.0)
+        .take(weakest)
+        .map(|focus| {
+            (
+                focus.kind,
+                recommend_pattern_charts(
+                    &profile,
+                    focus.kind,
+                    chart_type,
+                    songs.clone(),
+                    picks_per_focus,
+                ),
+            )
+        })
+        .collect();
+    PatternReport {
+        profile,
+        focuses,
+        picks,
+        missing_plays,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEAT_NS: SongTimeNs = 500_000_000;
+
+    fn notes(steps: &[(f32, &[u8])]) -> Vec<PatternNote> {
+        let mut out = Vec::new();
+        for &(beat, panels) in steps {
+            for &panel in panels {
+                out.push(PatternNote::new(
+                    (beat * BEAT_NS as f32) as SongTimeNs,
+                    beat,
+                    panel,
+                    120.0,
+                ));
+            }
+        }
+        out
+    }
+
+    /// Step parity for `steps`, one `(left_mask, right_mask, crossover)` per
+    /// row; a row with both feet down is a bracket.
+    fn parity(steps: &[(f32, &[u8])], rows: &[(u8, u8, bool)]) -> Vec<PatternRowFeet> {
+        steps
+            .iter()
+            .zip(rows)
+            .map(|(&(beat, _), &(left, right, crossover))| PatternRowFeet {
+                beat,
+                left_foot_mask: left,
+                right_foot_mask: right,
+                crossover,
+                bracket: (left | right).count_ones() > 1 && (left == 0 || right == 0),
+            })
+            .collect()
+    }
+
+    fn tagged(steps: &[(f32, &[u8])], rows: &[(u8, u8, bool)]) -> Vec<PatternNote> {
+        let mut out = notes(steps);
+        classify_pattern_notes(&mut out, &parity(steps, rows));
+        out
+    }
+
+    const L: u8 = 1 << 0;
+    const D: u8 = 1 << 1;
+    const U: u8 = 1 << 2;
+    const R: u8 = 1 << 3;
+
+    fn press(time_ns: SongTimeNs, lane: u8) -> LocalReplayEdge {
+        LocalReplayEdge {
+            event_music_time_ns: time_ns,
+            lane,
+            pressed: true,
+            source: 0,
+        }
+    }
+
+    #[test]
+    fn step_parity_feet_find_crossovers_candles_and_jacks() {
+        // L D R with the left foot crossing over to the right panel.
+        let crossover = tagged(
+            &[(0.0, &[0]), (0.25, &[1]), (0.5, &[3])],
+            &[(L, 0, false), (0, D, false), (R, 0, true)],
+        );
+        assert!(crossover[2].tags.contains(PatternCategory::Crossover));
+        assert!(!crossover[1].tags.contains(PatternCategory::Crossover));
+
+        // D L U: the right foot moves straight from down to up.
+        let candle = tagged(
+            &[(0.0, &[1]), (0.25, &[0]), (0.5, &[2])],
+            &[(0, D, false), (L, 0, false), (0, U, false)],
+        );
+        assert!(candle[2].tags.contains(PatternCategory::Candle));
+        assert!(!candle[0].tags.contains(PatternCategory::Crossover));
+
+        let jack = tagged(
+            &[(0.0, &[3]), (0.5, &[3]), (4.0, &[3])],
+            &[(0, R, false), (0, R, false), (0, R, false)],
+        );
+        assert!(jack[1].tags.contains(PatternCategory::Jack));
+        assert!(!jack[2].tags.contains(PatternCategory::Jack));
+        assert!(jack[0].tags.contains(PatternCategory::Break));
+
+        let bracket = tagged(
+            &[(0.0, &[1]), (0.25, &[0, 2])],
+            &[(0, D, false), (L | U, 0, false)],
+        );
+        assert!(bracket[1].tags.contains(PatternCategory::Bracket));
+
+        // Without step parity only the feet-free categories are tagged.
+        let mut bare = notes(&[(0.0, &[0]), (0.25, &[1]), (0.5, &[3]), (0.75, &[3])]);
+        classify_pattern_notes(&mut bare, &[]);
+        assert!(!bare[2].tags.contains(PatternCategory::Crossover));
+        assert!(bare[3].tags.contains(PatternCategory::Jack));
+    }
+
+    #[test]
+    fn full_measures_are_stream_and_foot_changes_are_footswitches() {
+        let mut steps: Vec<(f32, &[u8])> = Vec::new();
+        let mut rows = Vec::new();
+        for ix in 0..16 {
+            let (panels, row): (&[u8], _) = match ix {
+                5 => (&[2], (U, 0, false)),
+                6 => (&[2], (0, U, false)),
+                _ if ix % 2 == 0 => (&[0], (L, 0, false)),
+                _ => (&[3], (0, R, false)),
+            };
+            steps.push((ix as f32 * 0.25, panels));
+            rows.push(row);
+        }
+        let stream = tagged(&steps, &rows);
+        assert!(
+            stream
+                .iter()
+                .all(|note| note.tags.contains(PatternCategory::Stream))
+        );
+        assert!(stream[6].tags.contains(PatternCategory::Footswitch));
+        assert!(!stream[6].tags.contains(PatternCategory::Jack));
+    }
+
+    #[test]
+    fn replays_judge_the_closest_note_and_stop_at_the_last_input() {
+        let chart = notes(&[(0.0, &[0]), (1.0, &[0]), (2.0, &[1]), (8.0, &[2])]);
+        let replay = [
+            press(10_000_000, 0),
+            press(BEAT_NS - 20_000_000, 0),
+            // A press on an empty lane still shows how far the player got.
+            press(4 * BEAT_NS, 3),
+        ];
+        let judgments = judge_pattern_replay(&chart, &replay, 0, 1.0);
+        assert_eq!(judgments[0], PatternJudgment::Hit(10.0));
+        assert_eq!(judgments[1], PatternJudgment::Hit(-20.0));
+        assert_eq!(judgments[2], PatternJudgment::Miss);
+        assert_eq!(judgments[3], PatternJudgment::Unplayed);
+
+        let shifted = judge_pattern_replay(&chart, &replay, 5_000_000, 2.0);
+        assert_eq!(shifted[0], PatternJudgment::Hit(7.5));
+
+        let unplayed = judge_pattern_replay(&chart, &[], 0, 1.0);
+        assert_eq!(PatternPlay::judge(12, &chart, &unplayed, 1.0), None);
+    }
+
+    #[test]
+    fn profile_ranks_the_weakest_focus_first() {
+        let chart = tagged(
+            &[(0.0, &[0]), (0.25, &[1]), (0.5, &[3]), (4.0, &[2])],
+            &[(L, 0, false), (0, D, false), (R, 0, true), (0, U, false)],
+        );
+        let judgments = [
+            PatternJudgment::Hit(5.0),
+            PatternJudgment::Hit(5.0),
+            PatternJudgment::Miss,
+            PatternJudgment::Hit(5.0),
+        ];
+        let play = PatternPlay::judge(12, &chart, &judgments, 1.0).expect("play reached notes");
+        let mut profile = PatternProfile::default();
+        for _ in 0..PATTERN_MIN_NOTES {
+            profile.add_play("abc", &play);
+        }
+
+        let focuses = profile.focuses();
+        assert_eq!(
+            focuses[0].kind,
+            PatternFocusKind::Category(PatternCategory::Crossover)
+        );
+        assert!(focuses[0].relative > 1.0);
+        // Every note is a break at under 150 BPM, so both match the overall.
+        assert_eq!(focuses.len(), 3);
+        assert!(
+            focuses[1..]
+                .iter()
+                .all(|focus| (focus.relative - 1.0).abs() < 1e-6)
+        );
+        assert_eq!(profile.practice_meters(), Some(11..=13));
+        assert_eq!(
+            profile.charts["abc"][PatternCategory::Crossover.index()].misses,
+            50
+        );
+    }
+}
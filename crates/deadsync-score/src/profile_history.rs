@@ -0,0 +1,537 @@
+//! Long-term progress history for a local profile.
+//!
+//! Every local score file is reduced once to a small [`HistoryPlay`] record
+//! and kept in `scores/local/history.bin`, so refreshing only reads score
+//! files that are new since the last refresh. Daily and weekly aggregates are
+//! derived from those records on demand.
+
+use crate::local_store::write_index_file;
+use crate::{
+    LocalReplayEdge, LocalScoreEntry, LocalTurnMod, PatternJudgment, PatternNote,
+    ScoreIndexWriteError, judge_pattern_replay, local_score_files_in_root, parse_score_file_name,
+    read_local_score_entry,
+};
+use bincode::{Decode, Encode};
+use chrono::{Datelike, NaiveDate, TimeZone};
+use deadsync_core::song_time::{SongTimeNs, song_time_ns_invalid, song_time_ns_to_seconds};
+use deadsync_rules::judgment::step_calories;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// Version 2 gives plays saved without a turn mod their offsets; version 1
+/// files cached those empty, so they are rebuilt rather than decoded.
+pub const PROFILE_HISTORY_VERSION: u16 = 2;
+
+/// Presses this close together count as one jump for calories, like gameplay.
+const HISTORY_JUMP_WINDOW_NS: SongTimeNs = 250_000_000;
+
+/// Running sums for a mean and standard deviation of timing offsets.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Encode, Decode)]
+pub struct OffsetMoments {
+    pub count: u32,
+    pub sum_ms: f64,
+    pub sum_sq_ms: f64,
+}
+
+impl OffsetMoments {
+    #[inline(always)]
+    pub fn add(&mut self, offset_ms: f32) {
+        let offset_ms = f64::from(offset_ms);
+        self.count += 1;
+        self.sum_ms += offset_ms;
+        self.sum_sq_ms += offset_ms * offset_ms;
+    }
+
+    #[inline(always)]
+    pub fn merge(&mut self, other: &Self) {
+        self.count += other.count;
+        self.sum_ms += other.sum_ms;
+        self.sum_sq_ms += other.sum_sq_ms;
+    }
+
+    pub fn from_judgments(judgments: &[PatternJudgment]) -> Self {
+        let mut moments = Self::default();
+        for judgment in judgments {
+            if let PatternJudgment::Hit(offset_ms) = *judgment {
+                moments.add(offset_ms);
+            }
+        }
+        moments
+    }
+
+    /// Positive means late.
+    pub fn mean_ms(&self) -> Option<f32> {
+        (self.count > 0).then(|| (self.sum_ms / f64::from(self.count)) as f32)
+    }
+
+    pub fn stddev_ms(&self) -> Option<f32> {
+        let mean = self.sum_ms / f64::from(self.count.max(1));
+        (self.count > 1).then(|| {
+            let variance = self.sum_sq_ms / f64::from(self.count) - mean * mean;
+            variance.max(0.0).sqrt() as f32
+        })
+    }
+}
+
+/// One local score, reduced to what the history needs.
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct HistoryPlay {
+    pub chart_hash: String,
+    pub played_at_ms: i64,
+    /// 0 when the chart was not in the library at ingest time.
+    pub meter: u32,
+    pub ex_score_percent: f64,
+    pub steps_hit: u32,
+    pub play_seconds: f32,
+    pub calories: f32,
+    pub offsets: OffsetMoments,
+}
+
+/// Library data for a played chart; offsets need its notes.
+#[derive(Clone, Debug, Default)]
+pub struct HistoryChart {
+    pub meter: u32,
+    pub columns: usize,
+    /// In chart columns; [`HistoryChart::played_notes`] moves them to the
+    /// lanes a play saw.
+    pub notes: Vec<PatternNote>,
+    pub beat0_time_ns: SongTimeNs,
+}
+
+impl HistoryChart {
+    /// The notes with each column moved through `lanes`, the way the play's
+    /// turn mod moved them.
+    pub fn played_notes(&self, lanes: &[usize]) -> Vec<PatternNote> {
+        let mut notes: Vec<PatternNote> = self
+            .notes
+            .iter()
+            .map(|note| PatternNote {
+                column: lanes
+                    .get(usize::from(note.column))
+                    .map_or(note.column, |&lane| lane as u8),
+                ..*note
+            })
+            .collect();
+        notes.sort_by(|a, b| a.time_ns.cmp(&b.time_ns).then(a.column.cmp(&b.column)));
+        notes
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Encode, Decode)]
+pub struct ProfileHistory {
+    /// Oldest first.
+    pub plays: Vec<HistoryPlay>,
+}
+
+#[derive(Encode, Decode)]
+struct ProfileHistoryFile {
+    version: u16,
+    history: ProfileHistory,
+}
+
+pub fn decode_profile_history(bytes: &[u8]) -> Option<ProfileHistory> {
+    let (file, _) =
+        bincode::decode_from_slice::<ProfileHistoryFile, _>(bytes, bincode::config::standard())
+            .ok()?;
+    (file.version == PROFILE_HISTORY_VERSION).then_some(file.history)
+}
+
+pub fn encode_profile_history(history: &ProfileHistory) -> Option<Vec<u8>> {
+    bincode::encode_to_vec(
+        ProfileHistoryFile {
+            version: PROFILE_HISTORY_VERSION,
+            history: history.clone(),
+        },
+        bincode::config::standard(),
+    )
+    .ok()
+}
+
+pub fn load_profile_history_file(path: &Path) -> Option<ProfileHistory> {
+    let bytes = std::fs::read(path).ok()?;
+    decode_profile_history(&bytes)
+}
+
+pub fn save_profile_history_file(
+    path: &Path,
+    history: &ProfileHistory,
+) -> Result<(), ScoreIndexWriteError> {
+    write_index_file(
+        path,
+        &ProfileHistoryFile {
+            version: PROFILE_HISTORY_VERSION,
+            history: history.clone(),
+        },
+    )
+}
+
+/// Step calories from the replay's presses, the way gameplay counts them:
+/// each press burns by how many of the player's lanes went down with it.
+fn replay_calories(replay: &[LocalReplayEdge], weight_pounds: i32) -> f32 {
+    let mut pressed_since: HashMap<u8, SongTimeNs> = HashMap::new();
+    let mut calories = 0.0;
+    for edge in replay {
+        if !edge.pressed {
+            pressed_since.remove(&edge.lane);
+            continue;
+        }
+        pressed_since.insert(edge.lane, edge.event_music_time_ns);
+        let tracks = pressed_since
+            .values()
+            .filter(|&&since| {
+                let age = edge.event_music_time_ns.saturating_sub(since);
+                (0..HISTORY_JUMP_WINDOW_NS).contains(&age)
+            })
+            .count();
+        calories += step_calories(weight_pounds, tracks);
+    }
+    calories
+}
+
+/// `lanes` maps chart columns to the lanes the replay was recorded in; without
+/// it the replay cannot be matched to notes and the play gets no offsets.
+pub fn history_play_from_entry(
+    chart_hash: &str,
+    entry: &LocalScoreEntry,
+    chart: &HistoryChart,
+    lanes: Option<&[usize]>,
+    weight_pounds: i32,
+) -> HistoryPlay {
+    let rate = if entry.music_rate.is_finite() && entry.music_rate > 0.0 {
+        entry.music_rate
+    } else {
+        1.0
+    };
+    let last_edge_s = entry
+        .replay
+        .last()
+        .map(|edge| song_time_ns_to_seconds(edge.event_music_time_ns));
+    let last_note_s = chart
+        .notes
+        .last()
+        .map(|note| song_time_ns_to_seconds(note.time_ns));
+    let end_s = entry
+        .fail_time
+        .or(last_edge_s)
+        .or(last_note_s)
+        .unwrap_or(0.0);
+
+    let offsets = match lanes {
+        Some(lanes) if !entry.replay.is_empty() => {
+            let shift_ns = if song_time_ns_invalid(chart.beat0_time_ns)
+                || song_time_ns_invalid(entry.beat0_time_ns)
+            {
+                0
+            } else {
+                chart.beat0_time_ns.saturating_sub(entry.beat0_time_ns)
+            };
+            OffsetMoments::from_judgments(&judge_pattern_replay(
+                &chart.played_notes(lanes),
+                &entry.replay,
+                shift_ns,
+                entry.music_rate,
+            ))
+        }
+        _ => OffsetMoments::default(),
+    };
+
+    HistoryPlay {
+        chart_hash: chart_hash.to_string(),
+        played_at_ms: entry.played_at_ms,
+        meter: chart.meter,
+        ex_score_percent: entry.ex_score_percent,
+        steps_hit: entry.judgment_counts[..5].iter().sum(),
+        play_seconds: end_s.max(0.0) / rate,
+        calories: replay_calories(&entry.replay, weight_pounds),
+        offsets,
+    }
+}
+
+/// Adds every score file under `root` that the history has not seen yet.
+/// Plays on charts `chart_for_hash` cannot find are left out, so a later
+/// refresh adds them once the chart is back. `replay_lanes` maps a recorded
+/// turn mod, seed and column count to the replay's lanes. Returns how many
+/// plays were added.
+pub fn refresh_profile_history(
+    history: &mut ProfileHistory,
+    root: &Path,
+    weight_pounds: i32,
+    mut chart_for_hash: impl FnMut(&str) -> Option<HistoryChart>,
+    replay_lanes: impl Fn(LocalTurnMod, u64, usize) -> Option<Vec<usize>>,
+) -> usize {
+    let known: HashSet<(&str, i64)> = history
+        .plays
+        .iter()
+        .map(|play| (play.chart_hash.as_str(), play.played_at_ms))
+        .collect();
+    let mut charts: HashMap<String, Option<HistoryChart>> = HashMap::new();
+    let mut added = Vec::new();
+    for (chart_hash, path) in local_score_files_in_root(root) {
+        let Some((_, played_at_ms)) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(parse_score_file_name)
+        else {
+            continue;
+        };
+        if known.contains(&(chart_hash.as_str(), played_at_ms)) {
+            continue;
+        }
+        let Some(chart) = charts
+            .entry(chart_hash.clone())
+            .or_insert_with(|| chart_for_hash(&chart_hash))
+        else {
+            continue;
+        };
+        let Some(entry) = read_local_score_entry(&path) else {
+            continue;
+        };
+        // Scores saved before turn mods were recorded count as unturned, so
+        // their offsets are not cached empty in the history file.
+        let turn = entry.turn_mod.unwrap_or(LocalTurnMod::None);
+        let lanes = replay_lanes(turn, entry.turn_seed, chart.columns);
+        added.push(history_play_from_entry(
+            &chart_hash,
+            &entry,
+            chart,
+            lanes.as_deref(),
+            weight_pounds,
+        ));
+    }
+    let count = added.len();
+    history.plays.extend(added);
+    history.plays.sort_by(|a, b| {
+        a.played_at_ms
+            .cmp(&b.played_at_ms)
+            .then_with(|| a.chart_hash.cmp(&b.chart_hash))
+    });
+    count
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryPeriod {
+    Day,
+    Week,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct HistoryMeterEx {
+    pub meter: u32,
+    pub plays: u32,
+    pub mean_ex_percent: f64,
+}
+
+/// Aggregates for one day or one Monday-start week.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct HistoryBucket {
+    #[serde(serialize_with = "serialize_iso_date")]
+    pub start: NaiveDate,
+    pub plays: u32,
+    pub steps_hit: u64,
+    pub play_seconds: f64,
+    pub calories: f64,
+    pub mean_ex_percent: f64,
+    pub ex_by_meter: Vec<HistoryMeterEx>,
+    /// Plays that beat an earlier EX score on the same chart.
+    pub personal_bests: u32,
+    pub offset_mean_ms: Option<f32>,
+    pub offset_stddev_ms: Option<f32>,
+}
+
+fn serialize_iso_date<S: serde::Serializer>(date: &NaiveDate, s: S) -> Result<S::Ok, S::Error> {
+    s.collect_str(date)
+}
+
+#[derive(Default)]
+struct BucketSums {
+    plays: u32,
+    steps_hit: u64,
+    play_seconds: f64,
+    calories: f64,
+    ex_sum: f64,
+    ex_by_meter: BTreeMap<u32, (u32, f64)>,
+    personal_bests: u32,
+    offsets: OffsetMoments,
+}
+
+pub(crate) fn period_start(date: NaiveDate, period: HistoryPeriod) -> NaiveDate {
+    match period {
+        HistoryPeriod::Day => date,
+        HistoryPeriod::Week => {
+            date - chrono::Days::new(u64::from(date.weekday().num_days_from_monday()))
+        }
+    }
+}
+
+/// Buckets in `tz`'s calendar, oldest first. Days without plays are skipped.
+pub fn profile_history_buckets<Tz: TimeZone>(
+    history: &ProfileHistory,
+    period: HistoryPeriod,
+    tz: &Tz,
+) -> Vec<HistoryBucket> {
+    let mut best_ex: HashMap<&str, f64> = HashMap::new();
+    let mut sums: BTreeMap<NaiveDate, BucketSums> = BTreeMap::new();
+    for play in &history.plays {
+        let Some(played_at) = tz.timestamp_millis_opt(play.played_at_ms).single() else {
+            continue;
+        };
+        let bucket = sums
+            .entry(period_start(played_at.date_naive(), period))
+            .or_default();
+        bucket.plays += 1;
+        bucket.steps_hit += u64::from(play.steps_hit);
+        bucket.play_seconds += f64::from(play.play_seconds);
+        bucket.calories += f64::from(play.calories);
+        bucket.ex_sum += play.ex_score_percent;
+        if play.meter > 0 {
+            let meter = bucket.ex_by_meter.entry(play.meter).or_default();
+            meter.0 += 1;
+            meter.1 += play.ex_score_percent;
+        }
+        bucket.offsets.merge(&play.offsets);
+        match best_ex.get_mut(play.chart_hash.as_str()) {
+            Some(best) if play.ex_score_percent > *best => {
+                *best = play.ex_score_percent;
+                bucket.personal_bests += 1;
+            }
+            Some(_) => {}
+            None => {
+                best_ex.insert(&play.chart_hash, play.ex_score_percent);
+            }
+        }
+    }
+    sums.into_iter()
+        .map(|(start, sums)| HistoryBucket {
+            start,
+            plays: sums.plays,
+            steps_hit: sums.steps_hit,
+            play_seconds: sums.play_seconds,
+            calories: sums.calories,
+            mean_ex_percent: sums.ex_sum / f64::from(sums.plays.max(1)),
+            ex_by_meter: sums
+                .ex_by_meter
+                .into_iter()
+                .map(|(meter, (plays, ex_sum))| HistoryMeterEx {
+                    meter,
+                    plays,
+                    mean_ex_percent: ex_sum / f64::from(plays),
+                })
+                .collect(),
+            personal_bests: sums.personal_bests,
+            offset_mean_ms: sums.offsets.mean_ms(),
+            offset_stddev_ms: sums.offsets.stddev_ms(),
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+struct ProfileHistoryExport<'a> {
+    version: u16,
+    profile: &'a str,
+    plays: usize,
+    daily: Vec<HistoryBucket>,
+    weekly: Vec<HistoryBucket>,
+}
+
+/// Daily and weekly aggregates as pretty-printed JSON.
+pub fn profile_history_json<Tz: TimeZone>(
+    history: &ProfileHistory,
+    display_name: &str,
+    tz: &Tz,
+) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&ProfileHistoryExport {
+        version: PROFILE_HISTORY_VERSION,
+        profile: display_name,
+        plays: history.plays.len(),
+        daily: profile_history_buckets(history, HistoryPeriod::Day, tz),
+        weekly: profile_history_buckets(history, HistoryPeriod::Week, tz),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+    // 2024-01-01 was a Monday.
+    const MONDAY_MS: i64 = 1_704_067_200_000;
+
+    fn play(chart_hash: &str, played_at_ms: i64, ex: f64) -> HistoryPlay {
+        HistoryPlay {
+            chart_hash: chart_hash.to_string(),
+            played_at_ms,
+            meter: 10,
+            ex_score_percent: ex,
+            steps_hit: 100,
+            play_seconds: 90.0,
+            calories: 10.0,
+            offsets: OffsetMoments::default(),
+        }
+    }
+
+    #[test]
+    fn history_offset_moments_give_mean_and_stddev() {
+        let mut moments = OffsetMoments::default();
+        for offset in [-2.0, 0.0, 2.0, 4.0] {
+            moments.add(offset);
+        }
+        assert_eq!(moments.mean_ms(), Some(1.0));
+        assert!((moments.stddev_ms().unwrap() - 5.0_f32.sqrt()).abs() < 1e-4);
+        assert_eq!(OffsetMoments::default().mean_ms(), None);
+    }
+
+    #[test]
+    fn history_weeks_start_on_monday_and_count_improvements() {
+        let history = ProfileHistory {
+            plays: vec![
+                play("a", MONDAY_MS, 80.0),
+                play("a", MONDAY_MS + 2 * DAY_MS, 85.0),
+                play("a", MONDAY_MS + 6 * DAY_MS, 84.0),
+                play("b", MONDAY_MS + 7 * DAY_MS, 70.0),
+                play("a", MONDAY_MS + 8 * DAY_MS, 90.0),
+            ],
+        };
+        let weeks = profile_history_buckets(&history, HistoryPeriod::Week, &Utc);
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0].start, NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!(weeks[0].plays, 3);
+        assert_eq!(weeks[0].personal_bests, 1);
+        assert_eq!(weeks[1].start, NaiveDate::from_ymd_opt(2024, 1, 8).unwrap());
+        assert_eq!(weeks[1].personal_bests, 1);
+        assert_eq!(weeks[1].ex_by_meter[0].plays, 2);
+        assert!((weeks[1].mean_ex_percent - 80.0).abs() < 1e-9);
+
+        let days = profile_history_buckets(&history, HistoryPeriod::Day, &Utc);
+        assert_eq!(days.len(), 5);
+        assert!((days[0].play_seconds - 90.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn history_calories_count_jumps_as_two_tracks() {
+        let edge = |time_ns, lane, pressed| LocalReplayEdge {
+            event_music_time_ns: time_ns,
+            lane,
+            pressed,
+            source: 0,
+        };
+        let taps = [edge(0, 0, true), edge(1_000_000_000, 1, true)];
+        let jump = [edge(0, 0, true), edge(10_000_000, 1, true)];
+        let single = step_calories(150, 1);
+        assert!((replay_calories(&taps, 150) - 2.0 * single).abs() < 1e-6);
+        assert!((replay_calories(&jump, 150) - single - step_calories(150, 2)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn history_file_round_trips() {
+        let history = ProfileHistory {
+            plays: vec![play("a", MONDAY_MS, 80.0)],
+        };
+        let bytes = encode_profile_history(&history).unwrap();
+        assert_eq!(decode_profile_history(&bytes), Some(history.clone()));
+        let json = profile_history_json(&history, "Player", &Utc).unwrap();
+        assert!(json.contains("\"start\": \"2024-01-01\""));
+    }
+}
@@ -0,0 +1,293 @@
+//! Per-profile skill estimate and "play next" chart recommendations.
+//!
+//! A play's performance is the chart's difficulty nudged by how well it was
+//! scored in EX, so a clean 12 and a scrappy 13 land close together. The
+//! skill rating is a recency-weighted mean of the best performances among a
+//! profile's recent plays of one chart type, failed plays included.
+
+use deadsync_chart::{ChartData, SongData};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+/// Most recent plays, of any chart type, considered for the rating.
+pub const SKILL_RECENT_PLAYS: usize = 60;
+/// Best performances among the recent plays that make up the rating.
+pub const SKILL_TOP_PLAYS: usize = 15;
+/// Charts scored at or above this EX are not recommended again.
+pub const RECOMMEND_MASTERED_EX_PERCENT: f64 = 96.0;
+/// Width of the difficulty band, above the rating, that gets recommended.
+pub const RECOMMEND_LEVEL_SPAN: f64 = 1.0;
+
+/// EX at which a play counts as exactly the chart's difficulty.
+const EX_PAR_PERCENT: f64 = 85.0;
+/// EX difference worth one level of difficulty.
+const EX_PERCENT_PER_LEVEL: f64 = 5.0;
+/// Cap on how far EX can move a performance away from the chart difficulty.
+const MAX_EX_ADJUST_LEVELS: f64 = 1.0;
+/// Weight kept by each older play relative to the one played after it.
+const RECENCY_DECAY: f64 = 0.97;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SkillRating {
+    pub rating: f64,
+    /// Plays the rating was averaged over.
+    pub plays: usize,
+}
+
+/// Difficulty on the meter scale: the matrix rating when the chart has one.
+pub fn chart_skill_difficulty(chart: &ChartData) -> f64 {
+    if chart.matrix_rating.is_finite() && chart.matrix_rating > 0.0 {
+        chart.matrix_rating
+    } else {
+        f64::from(chart.meter)
+    }
+}
+
+pub fn play_performance(difficulty: f64, ex_percent: f64) -> f64 {
+    let adjust = ((ex_percent - EX_PAR_PERCENT) / EX_PERCENT_PER_LEVEL)
+        .clamp(-MAX_EX_ADJUST_LEVELS, MAX_EX_ADJUST_LEVELS);
+    difficulty + adjust
+}
+
+fn rated_chart(chart: &ChartData, chart_type: &str) -> bool {
+    chart.has_note_data && chart.chart_type.eq_ignore_ascii_case(chart_type)
+}
+
+/// Rates a profile from its most recent plays, newest first.
+///
+/// `recent_plays` holds each play's chart hash and EX percent as scored,
+/// failed plays included; plays on charts not in `songs` or of another chart
+/// type are skipped. Returns `None` until at least one such play is found.
+pub fn skill_rating<'a>(
+    songs: impl IntoIterator<Item = &'a SongData>,
+    chart_type: &str,
+    recent_plays: &[(String, f64)],
+) -> Option<SkillRating> {
+    let mut difficulty_by_hash: FxHashMap<&str, f64> = FxHashMap::default();
+    for song in songs {
+        for chart in &song.charts {
+            if rated_chart(chart, chart_type) {
+                difficulty_by_hash.insert(chart.short_hash.as_str(), chart_skill_difficulty(chart));
+            }
+        }
+    }
+
+    let mut plays: Vec<(f64, f64)> = recent_plays
+        .iter()
+        .take(SKILL_RECENT_PLAYS)
+        .filter(|(_, ex)| ex.is_finite())
+        .filter_map(|(hash, ex)| Some((*difficulty_by_hash.get(hash.as_str())?, *ex)))
+        .enumerate()
+        .map(|(rank, (difficulty, ex))| {
+            let weight = RECENCY_DECAY.powi(rank as i32);
+            (play_performance(difficulty, ex), weight)
+        })
+        .collect();
+    if plays.is_empty() {
+        return None;
+    }
+    plays.sort_by(|a, b| b.0.total_cmp(&a.0));
+    plays.truncate(SKILL_TOP_PLAYS);
+
+    let (sum, weights) = plays
+        .iter()
+        .fold((0.0, 0.0), |(sum, weights), &(performance, weight)| {
+            (sum + performance * weight, weights + weight)
+        });
+    Some(SkillRating {
+        rating: sum / weights,
+        plays: plays.len(),
+    })
+}
+
+/// Songs with an unplayed or not yet mastered chart just above `rating`.
+///
+/// Each song is represented by its chart closest to the middle of the band;
+/// songs are ordered by that distance, then by lowest EX so unplayed charts
+/// come first among equals.
+pub fn recommended_songs(
+    songs: Vec<Arc<SongData>>,
+    chart_type: &str,
+    rating: f64,
+    mut best_ex: impl FnMut(&str) -> Option<f64>,
+) -> Vec<Arc<SongData>> {
+    let target = rating + RECOMMEND_LEVEL_SPAN * 0.5;
+    let mut ranked: Vec<(f64, f64, Arc<SongData>)> = Vec::new();
+    for song in songs {
+        let mut best: Option<(f64, f64)> = None;
+        for chart in &song.charts {
+            if !rated_chart(chart, chart_type) {
+                continue;
+            }
+            let difficulty = chart_skill_difficulty(chart);
+            if difficulty < rating || difficulty > rating + RECOMMEND_LEVEL_SPAN {
+                continue;
+            }
+            let ex = best_ex(&chart.short_hash).unwrap_or(0.0);
+            if ex >= RECOMMEND_MASTERED_EX_PERCENT {
+                continue;
+            }
+            let key = ((difficulty - target).abs(), ex);
+            if best.is_none_or(|best| {
+                key.0
+                    .total_cmp(&best.0)
+                    .then(key.1.total_cmp(&best.1))
+                    .is_lt()
+            }) {
+                best = Some(key);
+            }
+        }
+        if let Some((distance, ex)) = best {
+            ranked.push((distance, ex, song));
+        }
+    }
+    ranked.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    ranked.into_iter().map(|(_, _, song)| song).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn chart(hash: &str, meter: u32, matrix_rating: f64) -> ChartData {
+        ChartData {
+            chart_type: "dance-single".to_string(),
+            difficulty: "Hard".to_string(),
+            description: String::new(),
+            chart_name: String::new(),
+            meter,
+            step_artist: String::new(),
+            music_path: None,
+            short_hash: hash.to_string(),
+            stats: Default::default(),
+            tech_counts: Default::default(),
+            mines_nonfake: 0,
+            stamina_counts: Default::default(),
+            total_streams: 0,
+            matrix_rating,
+            matrix_profile: Box::new([]),
+            max_nps: 0.0,
+            sn_detailed_breakdown: String::new(),
+            sn_partial_breakdown: String::new(),
+            sn_simple_breakdown: String::new(),
+            detailed_breakdown: String::new(),
+            partial_breakdown: String::new(),
+            simple_breakdown: String::new(),
+            total_measures: 0,
+            measure_nps_vec: Vec::new(),
+            measure_seconds_vec: Vec::new(),
+            first_second: 0.0,
+            has_note_data: true,
+            has_chart_attacks: false,
+            possible_grade_points: 0,
+            holds_total: 0,
+            rolls_total: 0,
+            mines_total: 0,
+            display_bpm: None,
+            min_bpm: 120.0,
+            max_bpm: 120.0,
+        }
+    }
+
+    fn song(title: &str, charts: Vec<ChartData>) -> Arc<SongData> {
+        Arc::new(SongData {
+            simfile_path: PathBuf::from(format!("{title}.ssc")),
+            title: title.to_string(),
+            subtitle: String::new(),
+            translit_title: String::new(),
+            translit_subtitle: String::new(),
+            artist: String::new(),
+            translit_artist: String::new(),
+            genre: String::new(),
+            banner_path: None,
+            background_path: None,
+            background_changes: Vec::new(),
+            background_layer2_changes: Vec::new(),
+            foreground_changes: Vec::new(),
+            background_lua_changes: Vec::new(),
+            foreground_lua_changes: Vec::new(),
+            has_lua: false,
+            cdtitle_path: None,
+            music_path: None,
+            display_bpm: String::new(),
+            offset: 0.0,
+            sample_start: None,
+            sample_length: None,
+            min_bpm: 0.0,
+            max_bpm: 0.0,
+            normalized_bpms: String::new(),
+            music_length_seconds: 0.0,
+            first_second: 0.0,
+            total_length_seconds: 0,
+            precise_last_second_seconds: 0.0,
+            charts,
+        })
+    }
+
+    #[test]
+    fn performance_moves_at_most_one_level_from_difficulty() {
+        assert_eq!(play_performance(12.0, 85.0), 12.0);
+        assert_eq!(play_performance(12.0, 90.0), 13.0);
+        assert_eq!(play_performance(12.0, 100.0), 13.0);
+        assert_eq!(play_performance(12.0, 40.0), 11.0);
+        assert_eq!(chart_skill_difficulty(&chart("a", 12, 0.0)), 12.0);
+        assert_eq!(chart_skill_difficulty(&chart("a", 12, 12.5)), 12.5);
+    }
+
+    #[test]
+    fn skill_rating_uses_recent_plays_of_the_chart_type() {
+        let mut double = chart("d", 20, 0.0);
+        double.chart_type = "dance-double".to_string();
+        let songs = [
+            song("a", vec![chart("a", 10, 0.0)]),
+            song("b", vec![chart("b", 12, 0.0), double]),
+        ];
+        let recent = [("d", 100.0), ("b", 85.0), ("missing", 90.0), ("a", 85.0)]
+            .map(|(hash, ex)| (hash.to_string(), ex));
+
+        let rating = skill_rating(songs.iter().map(Arc::as_ref), "dance-single", &recent)
+            .expect("recent plays rate the profile");
+
+        assert_eq!(rating.plays, 2);
+        // The newer 12 outweighs the older 10.
+        let expected = (12.0 + 10.0 * RECENCY_DECAY) / (1.0 + RECENCY_DECAY);
+        assert!((rating.rating - expected).abs() < 1e-9);
+
+        assert!(
+            skill_rating(songs.iter().map(Arc::as_ref), "dance-single", &recent[..1]).is_none()
+        );
+    }
+
+    #[test]
+    fn skill_rating_counts_each_recent_play_including_fails() {
+        let songs = [song("a", vec![chart("a", 12, 0.0)])];
+        // A failed 60% retry after the old 100% pass pulls the rating down.
+        let recent = [("a", 60.0), ("a", 100.0)].map(|(hash, ex)| (hash.to_string(), ex));
+
+        let rating = skill_rating(songs.iter().map(Arc::as_ref), "dance-single", &recent)
+            .expect("recent plays rate the profile");
+
+        assert_eq!(rating.plays, 2);
+        let expected = (11.0 + 13.0 * RECENCY_DECAY) / (1.0 + RECENCY_DECAY);
+        assert!((rating.rating - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn recommendations_skip_mastered_and_out_of_band_charts() {
+        let songs = vec![
+            song("easy", vec![chart("easy", 10, 0.0)]),
+            song("edge", vec![chart("edge", 13, 0.0)]),
+            song("mid", vec![chart("mid", 12, 12.5)]),
+            song("mastered", vec![chart("mastered", 12, 12.5)]),
+            song("low-ex", vec![chart("low-ex", 12, 12.5)]),
+            song("hard", vec![chart("hard", 14, 0.0)]),
+        ];
+        let ex = FxHashMap::from_iter([("mastered", 98.0), ("low-ex", 80.0)]);
+
+        let recommended = recommended_songs(songs, "dance-single", 12.0, |h| ex.get(h).copied());
+        let titles: Vec<&str> = recommended.iter().map(|s| s.title.as_str()).collect();
+
+        assert_eq!(titles, ["mid", "low-ex", "edge"]);
+    }
+}
@@ -0,0 +1,400 @@
+//! Workout sessions and their per-profile daily logs.
+//!
+//! A session tracks one player's progress toward a calorie, time or step goal
+//! across songs. It is rewritten to `workouts/YYYY-MM-DD.bin` (the day it
+//! started) after every song, so a crash loses at most the current song.
+//! Heart-rate samples are optional; every goal works without a sensor.
+
+use crate::ScoreIndexWriteError;
+use crate::local_store::write_index_file;
+use crate::profile_history::{HistoryPeriod, period_start};
+use bincode::{Decode, Encode};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const WORKOUT_LOG_VERSION: u16 = 1;
+/// Heart-rate readings closer together than this are dropped.
+pub const WORKOUT_HEART_RATE_INTERVAL_MS: u32 = 5_000;
+/// Four hours of samples at the minimum interval.
+pub const WORKOUT_HEART_RATE_MAX_SAMPLES: usize = 2_880;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encode, Decode)]
+pub enum WorkoutGoalKind {
+    Calories,
+    Minutes,
+    Steps,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encode, Decode)]
+pub struct WorkoutGoal {
+    pub kind: WorkoutGoalKind,
+    pub target: u32,
+}
+
+/// Goals offered when a player starts a workout.
+pub const WORKOUT_GOAL_PRESETS: [WorkoutGoal; 10] = [
+    WorkoutGoal::calories(100),
+    WorkoutGoal::calories(200),
+    WorkoutGoal::calories(300),
+    WorkoutGoal::calories(500),
+    WorkoutGoal::minutes(15),
+    WorkoutGoal::minutes(30),
+    WorkoutGoal::minutes(60),
+    WorkoutGoal::steps(1_000),
+    WorkoutGoal::steps(2_500),
+    WorkoutGoal::steps(5_000),
+];
+
+impl WorkoutGoal {
+    pub const fn calories(target: u32) -> Self {
+        Self {
+            kind: WorkoutGoalKind::Calories,
+            target,
+        }
+    }
+
+    pub const fn minutes(target: u32) -> Self {
+        Self {
+            kind: WorkoutGoalKind::Minutes,
+            target,
+        }
+    }
+
+    pub const fn steps(target: u32) -> Self {
+        Self {
+            kind: WorkoutGoalKind::Steps,
+            target,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct WorkoutHeartRateSample {
+    /// Wall time since the session started.
+    pub elapsed_ms: u32,
+    pub bpm: u16,
+}
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct WorkoutSession {
+    /// Unix ms; also identifies the session within its day log.
+    pub started_at_ms: i64,
+    pub goal: WorkoutGoal,
+    pub songs: u32,
+    pub calories: f32,
+    /// Time spent playing songs, not browsing between them.
+    pub active_seconds: f32,
+    pub steps: u32,
+    pub heart_rate: Vec<WorkoutHeartRateSample>,
+}
+
+impl WorkoutSession {
+    pub fn new(started_at_ms: i64, goal: WorkoutGoal) -> Self {
+        Self {
+            started_at_ms,
+            goal,
+            songs: 0,
+            calories: 0.0,
+            active_seconds: 0.0,
+            steps: 0,
+            heart_rate: Vec::new(),
+        }
+    }
+
+    pub fn add_stage(&mut self, calories: f32, seconds: f32, steps: u32) {
+        self.songs += 1;
+        if calories.is_finite() && calories > 0.0 {
+            self.calories += calories;
+        }
+        if seconds.is_finite() && seconds > 0.0 {
+            self.active_seconds += seconds;
+        }
+        self.steps = self.steps.saturating_add(steps);
+    }
+
+    /// Progress in the goal's own unit.
+    pub fn goal_amount(&self) -> f32 {
+        match self.goal.kind {
+            WorkoutGoalKind::Calories => self.calories,
+            WorkoutGoalKind::Minutes => self.active_seconds / 60.0,
+            WorkoutGoalKind::Steps => self.steps as f32,
+        }
+    }
+
+    /// Fraction of the goal reached, capped at 1.
+    pub fn progress(&self) -> f32 {
+        if self.goal.target == 0 {
+            return 1.0;
+        }
+        (self.goal_amount() / self.goal.target as f32).clamp(0.0, 1.0)
+    }
+
+    #[inline(always)]
+    pub fn goal_met(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    /// Returns whether the sample was kept.
+    pub fn push_heart_rate(&mut self, elapsed_ms: u32, bpm: u16) -> bool {
+        if bpm == 0 || self.heart_rate.len() >= WORKOUT_HEART_RATE_MAX_SAMPLES {
+            return false;
+        }
+        if let Some(last) = self.heart_rate.last()
+            && elapsed_ms
+                < last
+                    .elapsed_ms
+                    .saturating_add(WORKOUT_HEART_RATE_INTERVAL_MS)
+        {
+            return false;
+        }
+        self.heart_rate
+            .push(WorkoutHeartRateSample { elapsed_ms, bpm });
+        true
+    }
+
+    pub fn average_bpm(&self) -> Option<u16> {
+        let count = self.heart_rate.len() as u32;
+        (count > 0).then(|| {
+            let sum: u32 = self.heart_rate.iter().map(|s| u32::from(s.bpm)).sum();
+            (sum / count) as u16
+        })
+    }
+
+    pub fn peak_bpm(&self) -> Option<u16> {
+        self.heart_rate.iter().map(|sample| sample.bpm).max()
+    }
+}
+
+/// Every session started on one day, oldest first.
+#[derive(Clone, Debug, Default, PartialEq, Encode, Decode)]
+pub struct WorkoutDayLog {
+    pub sessions: Vec<WorkoutSession>,
+}
+
+#[derive(Encode, Decode)]
+struct WorkoutDayFile {
+    version: u16,
+    log: WorkoutDayLog,
+}
+
+#[inline(always)]
+pub fn workout_day_path(workouts_dir: &Path, day: NaiveDate) -> PathBuf {
+    workouts_dir.join(format!("{day}.bin"))
+}
+
+pub fn load_workout_day(path: &Path) -> Option<WorkoutDayLog> {
+    let bytes = std::fs::read(path).ok()?;
+    let (file, _) =
+        bincode::decode_from_slice::<WorkoutDayFile, _>(&bytes, bincode::config::standard())
+            .ok()?;
+    (file.version == WORKOUT_LOG_VERSION).then_some(file.log)
+}
+
+/// Writes `session` into its day log, replacing an earlier copy of it.
+pub fn save_workout_session(
+    workouts_dir: &Path,
+    day: NaiveDate,
+    session: &WorkoutSession,
+) -> Result<(), ScoreIndexWriteError> {
+    let path = workout_day_path(workouts_dir, day);
+    let mut log = load_workout_day(&path).unwrap_or_default();
+    match log
+        .sessions
+        .iter_mut()
+        .find(|known| known.started_at_ms == session.started_at_ms)
+    {
+        Some(known) => *known = session.clone(),
+        None => log.sessions.push(session.clone()),
+    }
+    write_index_file(
+        &path,
+        &WorkoutDayFile {
+            version: WORKOUT_LOG_VERSION,
+            log,
+        },
+    )
+}
+
+/// Every readable day log, oldest first.
+pub fn load_workout_days(workouts_dir: &Path) -> Vec<(NaiveDate, WorkoutDayLog)> {
+    let Ok(entries) = std::fs::read_dir(workouts_dir) else {
+        return Vec::new();
+    };
+    let mut days: Vec<(NaiveDate, WorkoutDayLog)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("bin") {
+                return None;
+            }
+            let day = path.file_stem()?.to_str()?.parse::<NaiveDate>().ok()?;
+            Some((day, load_workout_day(&path)?))
+        })
+        .collect();
+    days.sort_by_key(|(day, _)| *day);
+    days
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WorkoutWeek {
+    /// Monday of the week.
+    pub start: NaiveDate,
+    pub sessions: u32,
+    pub goals_met: u32,
+    pub songs: u32,
+    pub calories: f32,
+    pub active_seconds: f32,
+    pub steps: u64,
+    /// Mean over every heart-rate sample of the week.
+    pub average_bpm: Option<u16>,
+    pub peak_bpm: Option<u16>,
+}
+
+/// Per-week totals, newest first. Weeks without a session are skipped.
+pub fn weekly_workout_summaries(days: &[(NaiveDate, WorkoutDayLog)]) -> Vec<WorkoutWeek> {
+    let mut weeks: BTreeMap<NaiveDate, (WorkoutWeek, u64, u32)> = BTreeMap::new();
+    for (day, log) in days {
+        let start = period_start(*day, HistoryPeriod::Week);
+        let (week, bpm_sum, bpm_count) = weeks.entry(start).or_insert_with(|| {
+            (
+                WorkoutWeek {
+                    start,
+                    ..WorkoutWeek::default()
+                },
+                0,
+                0,
+            )
+        });
+        for session in &log.sessions {
+            week.sessions += 1;
+            week.goals_met += u32::from(session.goal_met());
+            week.songs += session.songs;
+            week.calories += session.calories;
+            week.active_seconds += session.active_seconds;
+            week.steps += u64::from(session.steps);
+            week.peak_bpm = week.peak_bpm.max(session.peak_bpm());
+            for sample in &session.heart_rate {
+                *bpm_sum += u64::from(sample.bpm);
+                *bpm_count += 1;
+            }
+        }
+    }
+    weeks
+        .into_values()
+        .rev()
+        .map(|(mut week, bpm_sum, bpm_count)| {
+            week.average_bpm = (bpm_count > 0).then(|| (bpm_sum / u64::from(bpm_count)) as u16);
+            week
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static TEST_ID: AtomicU64 = AtomicU64::new(0);
+
+    fn temp_dir() -> PathBuf {
+        let id = TEST_ID.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!(
+            "deadsync_workout_test_{}_{}",
+            std::process::id(),
+            id
+        ))
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn session_progress_follows_the_goal_unit() {
+        let mut session = WorkoutSession::new(0, WorkoutGoal::minutes(10));
+        session.add_stage(30.0, 150.0, 400);
+        session.add_stage(f32::NAN, 150.0, 300);
+        assert_eq!(session.songs, 2);
+        assert_eq!(session.goal_amount(), 5.0);
+        assert_eq!(session.progress(), 0.5);
+
+        session.goal = WorkoutGoal::steps(500);
+        assert!(session.goal_met());
+        assert_eq!(session.progress(), 1.0);
+        session.goal = WorkoutGoal::calories(60);
+        assert_eq!(session.progress(), 0.5);
+    }
+
+    #[test]
+    fn heart_rate_samples_are_spaced_and_summarized() {
+        let mut session = WorkoutSession::new(0, WorkoutGoal::calories(100));
+        assert_eq!(session.average_bpm(), None);
+        assert!(session.push_heart_rate(0, 120));
+        assert!(!session.push_heart_rate(1_000, 180));
+        assert!(!session.push_heart_rate(6_000, 0));
+        assert!(session.push_heart_rate(6_000, 140));
+        assert_eq!(session.average_bpm(), Some(130));
+        assert_eq!(session.peak_bpm(), Some(140));
+    }
+
+    #[test]
+    fn saving_a_session_twice_replaces_it_in_the_day_log() {
+        let dir = temp_dir();
+        let day = date(2024, 1, 3);
+        let mut first = WorkoutSession::new(1_000, WorkoutGoal::calories(100));
+        save_workout_session(&dir, day, &first).expect("save first");
+        first.add_stage(40.0, 120.0, 300);
+        save_workout_session(&dir, day, &first).expect("update first");
+        let second = WorkoutSession::new(2_000, WorkoutGoal::steps(1_000));
+        save_workout_session(&dir, day, &second).expect("save second");
+
+        let days = load_workout_days(&dir);
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].0, day);
+        assert_eq!(days[0].1.sessions, vec![first, second]);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn weeks_start_on_monday_and_list_newest_first() {
+        let mut met = WorkoutSession::new(0, WorkoutGoal::calories(50));
+        met.add_stage(60.0, 90.0, 200);
+        met.push_heart_rate(0, 100);
+        let mut short = WorkoutSession::new(1, WorkoutGoal::calories(500));
+        short.add_stage(20.0, 60.0, 100);
+        short.push_heart_rate(0, 150);
+        let days = vec![
+            // 2024-01-01 was a Monday.
+            (
+                date(2024, 1, 1),
+                WorkoutDayLog {
+                    sessions: vec![met.clone()],
+                },
+            ),
+            (
+                date(2024, 1, 7),
+                WorkoutDayLog {
+                    sessions: vec![short],
+                },
+            ),
+            (
+                date(2024, 1, 8),
+                WorkoutDayLog {
+                    sessions: vec![met],
+                },
+            ),
+        ];
+
+        let weeks = weekly_workout_summaries(&days);
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0].start, date(2024, 1, 8));
+        assert_eq!(weeks[1].start, date(2024, 1, 1));
+        assert_eq!(weeks[1].sessions, 2);
+        assert_eq!(weeks[1].goals_met, 1);
+        assert_eq!(weeks[1].calories, 80.0);
+        assert_eq!(weeks[1].steps, 300);
+        assert_eq!(weeks[1].average_bpm, Some(125));
+        assert_eq!(weeks[1].peak_bpm, Some(150));
+    }
+}
@@ -0,0 +1,41 @@
+use super::App;
+use log::{info, warn};
+
+impl App {
+    /// Ctrl+F10: record the `--capture-frames` file for `--capture-seconds`.
+    pub(super) fn start_frame_capture(&mut self) {
+        let Some(request) = self.frame_capture.as_ref() else {
+            warn!("Frame capture needs --capture-frames <file> at launch");
+            return;
+        };
+        let Some(backend) = self.backend.as_mut() else {
+            return;
+        };
+        match backend.start_frame_capture(&request.path, request.duration) {
+            Ok(()) => info!(
+                "Capturing frames to {} for {:.1}s",
+                request.path.display(),
+                request.duration.as_secs_f32()
+            ),
+            Err(e) => warn!("Failed to start frame capture: {e}"),
+        }
+    }
+
+    pub(super) fn poll_frame_capture(&mut self) {
+        let Some(finished) = self
+            .backend
+            .as_mut()
+            .and_then(|backend| backend.take_finished_frame_capture())
+        else {
+            return;
+        };
+        match finished {
+            Ok(summary) => info!(
+                "Frame capture wrote {} frame(s) to {}",
+                summary.frames,
+                summary.path.display()
+            ),
+            Err(e) => warn!("Frame capture stopped: {e}"),
+        }
+    }
+}
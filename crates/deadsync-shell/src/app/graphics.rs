@@ -249,6 +249,7 @@ impl App {
                 hide_cursor: runtime_config.hide_mouse_cursor,
                 gfx_debug_enabled: self.gfx_debug_enabled,
                 software_renderer_threads: self.software_renderer_threads,
+                mirror_textures: self.frame_capture.is_some() || runtime_config.spectator_window,
             },
         );
         let startup = start_renderer_runtime(
@@ -282,6 +283,10 @@ impl App {
 
         self.window = Some(window);
         self.backend = Some(backend);
+        if let Some(spectator) = &mut self.spectator {
+            spectator.reset_textures();
+        }
+        self.open_spectator_window(event_loop);
         info!("Starting event loop...");
         Ok(())
     }
@@ -0,0 +1,89 @@
+use super::App;
+use crate::latency_report::LatencyReportContext;
+use deadlib_platform::dirs;
+use deadlib_render_core::PresentStats;
+use log::{info, warn};
+use std::time::Instant;
+
+impl App {
+    /// Hand this frame's judged presses to the latency report and settle the
+    /// earlier ones whose present has reached the display.
+    pub(super) fn record_latency_report_frame(&mut self, present_stats: PresentStats) {
+        let now = Instant::now();
+        let now_host_nanos = deadlib_platform::host_time::now_nanos();
+        if let Some(gs) = self.state.screens.gameplay_state.as_mut() {
+            let audio_output_delay_ns =
+                deadsync_audio_stream::get_output_timing_snapshot().estimated_output_delay_ns;
+            for probe in gs.gameplay.drain_latency_probes() {
+                self.state.shell.latency_report.record_probe(
+                    probe,
+                    now,
+                    now_host_nanos,
+                    audio_output_delay_ns,
+                );
+            }
+        }
+        self.state
+            .shell
+            .latency_report
+            .record_frame(present_stats, now_host_nanos);
+    }
+
+    pub(super) fn sync_sandbox_latency_view(&mut self) {
+        self.state.screens.sandbox_state.latency = self.state.shell.latency_report.view();
+    }
+
+    fn latency_report_context(&self) -> LatencyReportContext {
+        let present_stats = self.state.shell.last_present_stats;
+        let output_timing = deadsync_audio_stream::get_output_timing_snapshot();
+        LatencyReportContext {
+            renderer: self.backend_type.to_string(),
+            present_policy: self.state.shell.present_mode_policy.to_string(),
+            present_mode: format!("{:?}", present_stats.mode),
+            vsync: self.state.shell.vsync_enabled,
+            max_fps: self
+                .state
+                .shell
+                .frame_loop
+                .frame_interval()
+                .map(|interval| (1.0 / interval.as_secs_f64()).round() as u32)
+                .unwrap_or(0),
+            refresh_ns: present_stats.refresh_ns,
+            audio_output_delay_us: u32::try_from(output_timing.estimated_output_delay_ns / 1_000)
+                .unwrap_or(u32::MAX),
+        }
+    }
+
+    pub(super) fn write_latency_report(&mut self) {
+        let name = format!(
+            "latency-report-{}.json",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        );
+        let path = dirs::app_dirs().data_dir.join(name);
+        let context = self.latency_report_context();
+        let result = self
+            .state
+            .shell
+            .latency_report
+            .to_json(&context)
+            .map_err(|error| format!("cannot encode latency report: {error}"))
+            .and_then(|bytes| {
+                std::fs::write(&path, bytes)
+                    .map_err(|error| format!("cannot write '{}': {error}", path.display()))
+            });
+        let message = match result {
+            Ok(()) => {
+                info!("Wrote latency report to '{}'", path.display());
+                format!("Wrote {}", path.display())
+            }
+            Err(error) => {
+                warn!("Failed to write latency report: {error}");
+                format!("Latency report failed: {error}")
+            }
+        };
+        self.state
+            .shell
+            .interaction
+            .show_message(message, Instant::now());
+    }
+}
@@ -6,14 +6,17 @@ mod audio_requests;
 mod commands;
 mod config_requests;
 mod evaluation_views;
+mod frame_capture;
 mod graphics;
 mod input_routing;
+mod latency_report;
 mod live_case;
 mod lobby_views;
 mod screen_nav;
 mod screenshot;
 mod select_music_views;
 mod smx_runtime;
+mod spectator;
 mod updater;
 
 use self::screenshot::auto_screenshot_eval_results;
@@ -115,7 +118,7 @@ use deadsync_profile_gameplay::{
     gameplay_config_from_config, gameplay_play_style_from_profile,
     gameplay_player_side_from_profile, gameplay_tick_mode_from_profile,
 };
-use deadsync_simfile::{app_runtime as song_loading, sync_offset};
+use deadsync_simfile::{app_runtime as song_loading, pack_sync, sync_offset};
 use deadsync_theme_simply_love::views::{
     OptionsInitView, OptionsPackSyncView, OptionsSongPackView, TimingHealth,
 };
@@ -167,6 +170,7 @@ use deadsync_input as logical_input;
 use deadsync_input::RawKeyboardEvent;
 use deadsync_input::{InputEvent, PadEvent, VirtualAction};
 use deadsync_input_fsr as fsr_input;
+use deadsync_input_native::GpSystemEvent;
 use deadsync_lights::cabinet_chart::{
     cabinet_light_chart_from_loaded, cabinet_light_key, cabinet_light_plan,
 };
@@ -195,10 +199,12 @@ use deadsync_theme_simply_love::views::{
 };
 use deadsync_theme_simply_love::{
     SimplyLoveConfigRequest, SimplyLoveContentRequest, SimplyLoveDebugRequest,
-    SimplyLoveEffect as ThemeEffect, SimplyLoveHardwareRequest,
+    SimplyLoveDuplicateSongsRequest, SimplyLoveEffect as ThemeEffect, SimplyLoveHardwareRequest,
     SimplyLoveInputResult as ThemeInputResult, SimplyLoveLobbyRequest, SimplyLoveMediaRequest,
-    SimplyLoveOnlineRequest, SimplyLoveProfileImportEvent, SimplyLoveProfileRequest,
-    SimplyLoveQrLoginService, SimplyLoveRuntimeRequest, SimplyLoveSyncOwner, SimplyLoveSyncRequest,
+    SimplyLoveOnlineRequest, SimplyLovePackLintRequest, SimplyLovePatternAnalysisRequest,
+    SimplyLoveProfileImportEvent, SimplyLoveProfileRequest, SimplyLoveQrLoginService,
+    SimplyLoveRuntimeRequest, SimplyLoveSongLuaScanRequest, SimplyLoveSyncOwner,
+    SimplyLoveSyncRequest, SimplyLoveWorkoutRequest,
 };
 
 /// The main Options rows that launch standalone child screens should regain
@@ -715,6 +721,7 @@ fn simply_love_visual_policy(config: &config::Config) -> SimplyLoveVisualPolicyV
         title_logo_texture_key: srpg10.then_some(visual_styles::SRPG10_TITLE_LOGO),
         srpg10_tint: srpg10,
         screen_bar,
+        render_targets: config.video_renderer.supports_render_targets(),
     }
 }
 
@@ -1464,6 +1471,10 @@ pub struct App {
     /// parsed before startup; App owns direct Gameplay entry, warmup, capture,
     /// artifact emission, and automatic exit.
     live_case: Option<live_case::LiveCaseRuntime>,
+    /// `--capture-frames` target; the renderer mirrors textures only when set.
+    frame_capture: Option<crate::frame_capture::FrameCaptureRequest>,
+    /// `SpectatorWindow` second window; `None` when disabled or closed.
+    spectator: Option<crate::spectator::SpectatorWindow>,
     /// Game-thread-only one-entry cursor for Gameplay banner media intent.
     /// Its session lifetime and fixed capacity require no allocation or
     /// eviction. The first Gameplay/Practice frame warms it; screen, window,
@@ -1601,10 +1612,18 @@ pub struct App {
     content_reload: crate::content_reload::Service,
     apply_replaygain: crate::apply_replaygain::Service,
     heart_rate: crate::heart_rate::Runtime,
+    player_input: crate::player_input::Runtime,
     qr_login: crate::qr_login::Service,
     score_import: crate::score_import::Service,
     sync_analysis: crate::sync_analysis::Service,
     song_search: crate::song_search::Service,
+    pattern_analysis: crate::pattern_analysis::Service,
+    profile_history: crate::profile_history::Service,
+    offset_drift: crate::offset_drift::Service,
+    pack_lint: crate::pack_lint::Service,
+    song_lua_scan: crate::song_lua_scan::Service,
+    duplicate_songs: crate::duplicate_songs::Service,
+    workout: crate::workout::Runtime,
     ui_text_layout_cache: compose::TextLayoutCache,
     gameplay_text_layout_cache: compose::TextLayoutCache,
     ui_compose_scratch: compose::ComposeScratch,
@@ -1751,7 +1770,12 @@ impl App {
                     &mut state,
                     &self.state.screens.select_music_state,
                 );
+                select_music::adopt_song_lua_scan_levels(
+                    &mut state,
+                    &self.state.screens.select_music_state,
+                );
                 self.state.screens.select_music_state = *state;
+                self.workout.invalidate_select_music();
             }
             crate::profile_load::PreparedState::Course(mut state) => {
                 state.active_color_index = self.state.screens.profile_load_state.active_color_index;
@@ -1841,6 +1865,84 @@ impl App {
         }
     }
 
+    fn poll_pattern_analysis(&mut self) {
+        let Some(result) = self.pattern_analysis.poll() else {
+            return;
+        };
+        if self.state.screens.current_screen == CurrentScreen::SelectMusic {
+            select_music::apply_pattern_analysis_result(
+                &mut self.state.screens.select_music_state,
+                result,
+            );
+        }
+    }
+
+    fn poll_pack_lint(&mut self) {
+        let Some(result) = self.pack_lint.poll() else {
+            return;
+        };
+        if self.state.screens.current_screen == CurrentScreen::SelectMusic {
+            select_music::apply_pack_lint_result(
+                &mut self.state.screens.select_music_state,
+                result,
+            );
+        }
+    }
+
+    fn poll_song_lua_scan(&mut self) {
+        let Some(result) = self.song_lua_scan.poll() else {
+            return;
+        };
+        if self.state.screens.current_screen == CurrentScreen::SelectMusic {
+            select_music::apply_song_lua_scan_result(
+                &mut self.state.screens.select_music_state,
+                result,
+            );
+        }
+    }
+
+    fn poll_duplicate_songs(&mut self) {
+        let Some(result) = self.duplicate_songs.poll() else {
+            return;
+        };
+        if self.state.screens.current_screen == CurrentScreen::SelectMusic {
+            select_music::apply_duplicate_songs_result(
+                &mut self.state.screens.select_music_state,
+                result,
+            );
+        }
+    }
+
+    fn poll_profile_history(&mut self) {
+        let Some(result) = self.profile_history.poll() else {
+            return;
+        };
+        manage_local_profiles::apply_profile_history_result(
+            &mut self.state.screens.manage_local_profiles_state,
+            result,
+        );
+    }
+
+    fn poll_offset_drift(&mut self) {
+        let Some(result) = self.offset_drift.poll() else {
+            return;
+        };
+        manage_local_profiles::apply_offset_drift_result(
+            &mut self.state.screens.manage_local_profiles_state,
+            result,
+        );
+    }
+
+    fn poll_workout_summary(&mut self) {
+        let Some(result) = self.workout.poll_summary() else {
+            return;
+        };
+        manage_local_profiles::apply_workout_summary_result(
+            &mut self.state.screens.manage_local_profiles_state,
+            result,
+        );
+    }
+
     fn poll_sync_analysis(&mut self) {
         let Some(events) = self.sync_analysis.poll() else {
             return;
@@ -3120,6 +3222,13 @@ impl App {
             self.poll_score_import();
             self.poll_sync_analysis();
             self.poll_song_search();
+            self.poll_pattern_analysis();
+            self.poll_profile_history();
+            self.poll_offset_drift();
+            self.poll_workout_summary();
+            self.poll_pack_lint();
+            self.poll_song_lua_scan();
+            self.poll_duplicate_songs();
             self.poll_apply_replaygain();
         }
         if work_caps & frame_work::HEART_RATE_CONFIG != 0 {
@@ -3133,6 +3242,7 @@ impl App {
                 self.heart_rate.refresh_player_options(state);
             }
         }
+        self.player_input.sync();
         let maintenance_us = elapsed_us_since(maintenance_started);
 
         let update_started = Instant::now();
@@ -3154,6 +3264,7 @@ impl App {
             self.drive_smx_player_options_lights(delta_time, frame_policy.smx);
         }
         self.state.shell.interaction.update_message(redraw_started);
+        self.workout.sample_heart_rate(redraw_started);
         if work_caps & frame_work::ONLINE_VIEW != 0 {
             self.sync_active_online_runtime_view(redraw_started);
         }
@@ -3162,6 +3273,8 @@ impl App {
                 &mut self.state.screens.select_music_state,
                 frame_policy.machine_enable_heart_rate_monitors,
             );
+            self.workout
+                .refresh_select_music(&mut self.state.screens.select_music_state);
             self.sync_select_music_runtime_view(self.select_music_policy);
         }
         if work_caps & frame_work::SELECT_COURSE_VIEW != 0 {
@@ -3620,6 +3733,7 @@ impl App {
 
         let apply_present_back_pressure = self.apply_present_back_pressure();
         let mut capture_screenshot = false;
+        let mut drew_frame = false;
         if let Some(backend) = &mut self.backend {
             if self.state.shell.screenshot.pending() {
                 backend.request_screenshot();
@@ -3636,6 +3750,7 @@ impl App {
                     self.state.shell.last_present_stats = stats.present_stats;
                     draw_us = elapsed_us_since(draw_started);
                     capture_screenshot = true;
+                    drew_frame = true;
                 }
                 Err(e) => {
                     error!("Failed to draw frame: {e}");
@@ -3644,6 +3759,9 @@ impl App {
                 }
             }
         }
+        if drew_frame {
+            self.submit_spectator_frame(&mut screen, total_elapsed);
+        }
         if uses_gameplay_present {
             self.gameplay_compose_scratch.recycle_frame(&mut screen);
         } else {
@@ -3667,6 +3785,12 @@ impl App {
         if capture_screenshot {
             self.capture_pending_screenshot(redraw_started);
         }
+        if drew_frame && self.state.shell.latency_report.enabled() {
+            self.record_latency_report_frame(draw_stats.present_stats);
+        }
+        if drew_frame && self.frame_capture.is_some() {
+            self.poll_frame_capture();
+        }
         let frame_finished = Instant::now();
         let frame_seconds = frame_finished.duration_since(prev_frame_end).as_secs_f32();
         self.state.shell.last_frame_end_time = frame_finished;
@@ -3863,6 +3987,8 @@ impl App {
             gameplay_sfx: GameplaySfx::default(),
             music_clock,
             live_case: live_case.map(live_case::LiveCaseRuntime::new),
+            frame_capture: None,
+            spectator: None,
             gameplay_banner_sync_key: None,
             post_select_stage_indices: Vec::new(),
             post_select_stage_key: None,
@@ -3917,10 +4043,18 @@ impl App {
             content_reload: crate::content_reload::Service::default(),
             apply_replaygain: crate::apply_replaygain::Service::default(),
             heart_rate: crate::heart_rate::Runtime::default(),
+            player_input: crate::player_input::Runtime::default(),
             qr_login: crate::qr_login::Service::default(),
             score_import: crate::score_import::Service::default(),
             sync_analysis: crate::sync_analysis::Service::default(),
             song_search: crate::song_search::Service::default(),
+            pattern_analysis: crate::pattern_analysis::Service::default(),
+            profile_history: crate::profile_history::Service::default(),
+            offset_drift: crate::offset_drift::Service::default(),
+            pack_lint: crate::pack_lint::Service::default(),
+            song_lua_scan: crate::song_lua_scan::Service::default(),
+            duplicate_songs: crate::duplicate_songs::Service::default(),
+            workout: crate::workout::Runtime::default(),
             // Screen transitions clear the UI cache, so misses stop inserting
             // once the cache reaches its fixed footprint.
             ui_text_layout_cache: compose::TextLayoutCache::new(UI_TEXT_LAYOUT_CACHE_LIMIT),
@@ -4100,6 +4234,23 @@ impl App {
                     }
                     Vec::new()
                 }
+                SimplyLoveRuntimeRequest::Debug(SimplyLoveDebugRequest::ToggleLatencyReport) => {
+                    let enabled = !self.state.shell.latency_report.enabled();
+                    self.state.shell.latency_report.set_enabled(enabled);
+                    if let Some(gs) = self.state.screens.gameplay_state.as_mut() {
+                        gs.gameplay.set_latency_probe_enabled(enabled);
+                    }
+                    self.sync_sandbox_latency_view();
+                    info!(
+                        "Latency report {}",
+                        if enabled { "recording" } else { "stopped" }
+                    );
+                    Vec::new()
+                }
+                SimplyLoveRuntimeRequest::Debug(SimplyLoveDebugRequest::WriteLatencyReport) => {
+                    self.write_latency_report();
+                    Vec::new()
+                }
                 SimplyLoveRuntimeRequest::RestartGameplay => {
                     self.try_gameplay_restart(event_loop, "missed target");
                     Vec::new()
@@ -4280,6 +4431,13 @@ impl App {
                     profile::toggle_favorite(side, &chart_hash);
                     Vec::new()
                 }
+                SimplyLoveRuntimeRequest::Profile(SimplyLoveProfileRequest::SaveHudLayout {
+                    side,
+                    layout,
+                }) => {
+                    profile::set_hud_layout_for_side(side, layout);
+                    Vec::new()
+                }
                 SimplyLoveRuntimeRequest::Profile(
                     SimplyLoveProfileRequest::TogglePackFavorite { side, pack_name },
                 ) => {
@@ -4342,6 +4500,47 @@ impl App {
                     );
                     Vec::new()
                 }
+                SimplyLoveRuntimeRequest::Profile(
+                    SimplyLoveProfileRequest::LoadProfileHistory { profile_id },
+                ) => {
+                    self.profile_history.start(profile_id);
+                    Vec::new()
+                }
+                SimplyLoveRuntimeRequest::Profile(
+                    SimplyLoveProfileRequest::CancelProfileHistory,
+                ) => {
+                    self.profile_history.cancel();
+                    Vec::new()
+                }
+                SimplyLoveRuntimeRequest::Profile(
+                    SimplyLoveProfileRequest::ExportProfileHistory {
+                        profile_id,
+                        display_name,
+                    },
+                ) => {
+                    let event = crate::profile_history::export(&profile_id, &display_name);
+                    manage_local_profiles::apply_local_profile_event(
+                        &mut self.state.screens.manage_local_profiles_state,
+                        event,
+                    );
+                    Vec::new()
+                }
+                SimplyLoveRuntimeRequest::Profile(SimplyLoveProfileRequest::LoadOffsetDrift {
+                    profile_id,
+                }) => {
+                    self.offset_drift.start(profile_id);
+                    Vec::new()
+                }
+                SimplyLoveRuntimeRequest::Profile(SimplyLoveProfileRequest::CancelOffsetDrift) => {
+                    self.offset_drift.cancel();
+                    Vec::new()
+                }
+                SimplyLoveRuntimeRequest::Profile(
+                    SimplyLoveProfileRequest::LoadWorkoutSummary { profile_id },
+                ) => {
+                    self.workout.load_summary(profile_id);
+                    Vec::new()
+                }
                 SimplyLoveRuntimeRequest::Profile(
                     SimplyLoveProfileRequest::DiscoverItgProfiles,
                 ) => {
@@ -4393,6 +4592,26 @@ impl App {
                     self.sync_analysis.cancel(owner);
                     Vec::new()
                 }
+                SimplyLoveRuntimeRequest::Sync(SimplyLoveSyncRequest::SetPackSyncBias {
+                    pack_dir,
+                    bias_ms,
+                }) => {
+                    self.set_pack_sync_bias(&pack_dir, bias_ms);
+                    Vec::new()
+                }
+                SimplyLoveRuntimeRequest::Sync(SimplyLoveSyncRequest::ApplyPackSyncBias {
+                    pack_dir,
+                    simfile_paths,
+                }) => {
+                    self.apply_pack_sync_bias(&pack_dir, &simfile_paths);
+                    Vec::new()
+                }
+                SimplyLoveRuntimeRequest::Sync(SimplyLoveSyncRequest::UndoPackOffsetRewrite {
+                    pack_dir,
+                }) => {
+                    self.undo_pack_offset_rewrite(&pack_dir);
+                    Vec::new()
+                }
                 SimplyLoveRuntimeRequest::Sync(SimplyLoveSyncRequest::ApplySongOffsetBatch {
                     changes,
                 }) => {
@@ -4421,6 +4640,59 @@ impl App {
                     self.song_search.submit(request);
                     Vec::new()
                 }
+                SimplyLoveRuntimeRequest::PatternAnalysis(request) => {
+                    match request {
+                        SimplyLovePatternAnalysisRequest::Start { side, chart_type } => {
+                            self.pattern_analysis.start(side, chart_type);
+                        }
+                        SimplyLovePatternAnalysisRequest::Cancel => self.pattern_analysis.cancel(),
+                    }
+                    Vec::new()
+                }
+                SimplyLoveRuntimeRequest::PackLint(request) => {
+                    match request {
+                        SimplyLovePackLintRequest::Start { pack_dir } => {
+                            self.pack_lint.start(pack_dir);
+                        }
+                        SimplyLovePackLintRequest::Cancel => self.pack_lint.cancel(),
+                    }
+                    Vec::new()
+                }
+                SimplyLoveRuntimeRequest::SongLuaScan(request) => {
+                    match request {
+                        SimplyLoveSongLuaScanRequest::Start { pack_dir } => {
+                            self.song_lua_scan.start(pack_dir);
+                        }
+                        SimplyLoveSongLuaScanRequest::Cancel => self.song_lua_scan.cancel(),
+                    }
+                    Vec::new()
+                }
+                SimplyLoveRuntimeRequest::DuplicateSongs(request) => {
+                    match request {
+                        SimplyLoveDuplicateSongsRequest::Scan => self.duplicate_songs.start(),
+                        SimplyLoveDuplicateSongsRequest::Cancel => self.duplicate_songs.cancel(),
+                        SimplyLoveDuplicateSongsRequest::Save { choices } => {
+                            if crate::duplicate_songs::save_choices(choices)
+                                && self.state.screens.current_screen == CurrentScreen::SelectMusic
+                            {
+                                select_music::refresh_from_song_packs(
+                                    &mut self.state.screens.select_music_state,
+                                    deadsync_simfile::duplicates::visible_song_packs(),
+                                );
+                            }
+                        }
+                    }
+                    Vec::new()
+                }
+                SimplyLoveRuntimeRequest::Workout(request) => {
+                    match request {
+                        SimplyLoveWorkoutRequest::Start { side, goal } => {
+                            self.workout.start(side, goal);
+                        }
+                        SimplyLoveWorkoutRequest::End { side } => self.workout.end(side),
+                    }
+                    Vec::new()
+                }
                 SimplyLoveRuntimeRequest::Content(request) => {
                     match request {
                         SimplyLoveContentRequest::InitializeLibrary {
@@ -4609,6 +4881,18 @@ impl App {
                     let _ = crate::smx_config::swap_smx_assignment();
                     Vec::new()
                 }
+                SimplyLoveRuntimeRequest::Hardware(
+                    SimplyLoveHardwareRequest::ClaimController { side, claim },
+                ) => {
+                    logical_input::claim_controller(profile_data::player_side_index(side), claim);
+                    Vec::new()
+                }
+                SimplyLoveRuntimeRequest::Hardware(
+                    SimplyLoveHardwareRequest::ReleaseController(side),
+                ) => {
+                    logical_input::release_controller_claim(profile_data::player_side_index(side));
+                    Vec::new()
+                }
                 SimplyLoveRuntimeRequest::Hardware(
                     SimplyLoveHardwareRequest::SetSmxUnderglowTheme(enabled),
                 ) => {
@@ -4757,6 +5041,13 @@ impl App {
                         SimplyLoveLobbyRequest::SelectSong(song) => {
                             deadsync_online::lobbies::runtime_select_song_default(song);
                         }
+                        SimplyLoveLobbyRequest::SongVote { side, vote } => {
+                            deadsync_online::lobbies::runtime_set_local_song_vote(side, vote);
+                            deadsync_online::lobbies::runtime_update_machine_state_default(
+                                "ScreenSelectMusic",
+                                true,
+                            );
+                        }
                         SimplyLoveLobbyRequest::UpdateMachineState { screen_name, ready } => {
                             deadsync_online::lobbies::runtime_update_machine_state_default(
                                 screen_name,
@@ -4935,22 +5226,31 @@ impl App {
         let summary = sync_offset::save_song_offset_changes(
             changes,
             config::song_path_is_writable,
-            |simfile_path| {
-                let updated_song = song_loading::reload_song_in_cache(simfile_path)?;
-                if let Some(po_state) = self.state.screens.player_options_state.as_mut() {
-                    let _ = deadsync_simfile::runtime_cache::replace_song_arc_if_same_simfile(
-                        &mut po_state.song,
-                        &updated_song,
-                    );
-                }
-                Ok(())
-            },
+            |simfile_path| self.reload_saved_song(simfile_path),
         );
+        self.finish_song_offset_save(summary, changes.len())
+    }
+
+    fn reload_saved_song(&mut self, simfile_path: &Path) -> Result<(), String> {
+        let updated_song = song_loading::reload_song_in_cache(simfile_path)?;
+        if let Some(po_state) = self.state.screens.player_options_state.as_mut() {
+            let _ = deadsync_simfile::runtime_cache::replace_song_arc_if_same_simfile(
+                &mut po_state.song,
+                &updated_song,
+            );
+        }
+        Ok(())
+    }
 
+    fn finish_song_offset_save(
+        &mut self,
+        summary: sync_offset::SongOffsetSaveSummary,
+        requested: usize,
+    ) -> Result<sync_offset::SongOffsetSaveSummary, String> {
         if summary.saved_files > 0 {
             select_music::refresh_from_song_packs(
                 &mut self.state.screens.select_music_state,
-                deadsync_simfile::runtime_cache::get_song_cache().clone(),
+                deadsync_simfile::duplicates::visible_song_packs(),
             );
         }
         if summary.skipped_read_only > 0
@@ -4998,7 +5298,7 @@ impl App {
             let mut message = format!(
                 "saved {} of {} change(s); {} read-only, {} write failure(s), {} cache refresh failure(s)",
                 summary.saved_files,
-                changes.len(),
+                requested,
                 summary.skipped_read_only,
                 summary.failed_files,
                 summary.cache_refresh_failures
@@ -5023,6 +5323,92 @@ impl App {
         .map(|_| ())
     }
 
+    /// Mirrors the pack's on-disk bias into the song cache and Select Music.
+    fn refresh_pack_sync_bias(&mut self, pack_dir: &Path) {
+        deadsync_simfile::runtime_cache::set_cached_pack_sync_bias(
+            pack_dir,
+            pack_sync::load_pack_sync_bias_ms(pack_dir),
+            pack_sync::load_pack_sync_written_bias_ms(pack_dir),
+        );
+        select_music::refresh_from_song_packs(
+            &mut self.state.screens.select_music_state,
+            deadsync_simfile::duplicates::visible_song_packs(),
+        );
+    }
+
+    fn set_pack_sync_bias(&mut self, pack_dir: &Path, bias_ms: f32) {
+        let message = match pack_sync::save_pack_sync_bias_ms(pack_dir, bias_ms) {
+            Ok(()) => {
+                let bias_ms = pack_sync::load_pack_sync_bias_ms(pack_dir);
+                info!(
+                    "Set pack sync bias for '{}' to {bias_ms:+.0} ms.",
+                    pack_dir.display()
+                );
+                format!("Pack sync bias set to {bias_ms:+.0} ms.")
+            }
+            Err(e) => {
+                warn!("Failed to save pack sync bias: {e}");
+                format!("Pack sync bias not saved: {e}")
+            }
+        };
+        self.refresh_pack_sync_bias(pack_dir);
+        self.state
+            .shell
+            .interaction
+            .show_message(message, Instant::now());
+    }
+
+    fn apply_pack_sync_bias(&mut self, pack_dir: &Path, simfile_paths: &[PathBuf]) {
+        let result = pack_sync::apply_pack_sync_bias_to_simfiles(
+            pack_dir,
+            simfile_paths,
+            config::song_path_is_writable,
+            |simfile_path| self.reload_saved_song(simfile_path),
+        )
+        .and_then(|summary| self.finish_song_offset_save(summary, simfile_paths.len()));
+        let message = match result {
+            Ok(summary) => format!(
+                "Wrote pack sync bias into {} simfile(s).",
+                summary.saved_files
+            ),
+            Err(e) => {
+                warn!("Failed to write pack sync bias into simfiles: {e}");
+                format!("Pack offset rewrite incomplete: {e}")
+            }
+        };
+        self.refresh_pack_sync_bias(pack_dir);
+        self.state
+            .shell
+            .interaction
+            .show_message(message, Instant::now());
+    }
+
+    fn undo_pack_offset_rewrite(&mut self, pack_dir: &Path) {
+        let requested = pack_sync::load_pack_offset_journal(pack_dir)
+            .map_or(0, |journal| journal.entries.len());
+        let result = pack_sync::undo_pack_offset_rewrite(
+            pack_dir,
+            config::song_path_is_writable,
+            |simfile_path| self.reload_saved_song(simfile_path),
+        )
+        .and_then(|summary| self.finish_song_offset_save(summary, requested));
+        let message = match result {
+            Ok(summary) => format!(
+                "Restored the #OFFSET of {} simfile(s).",
+                summary.saved_files
+            ),
+            Err(e) => {
+                warn!("Failed to undo pack offset rewrite: {e}");
+                format!("Pack offset undo incomplete: {e}")
+            }
+        };
+        self.refresh_pack_sync_bias(pack_dir);
+        self.state
+            .shell
+            .interaction
+            .show_message(message, Instant::now());
+    }
+
     fn maybe_begin_gameplay_offset_prompt(
         &mut self,
         from: CurrentScreen,
@@ -5414,7 +5800,7 @@ impl App {
         };
         select_music::refresh_from_song_packs(
             &mut self.state.screens.select_music_state,
-            deadsync_simfile::runtime_cache::get_song_cache().clone(),
+            deadsync_simfile::duplicates::visible_song_packs(),
         );
 
         if !self.try_gameplay_restart(event_loop, label) {
@@ -5504,7 +5890,7 @@ impl App {
         };
         select_music::refresh_from_song_packs(
             &mut self.state.screens.select_music_state,
-            deadsync_simfile::runtime_cache::get_song_cache().clone(),
+            deadsync_simfile::duplicates::visible_song_packs(),
         );
 
         let target_chart_type = profile::get_session_play_style().chart_type();
@@ -5563,6 +5949,12 @@ impl App {
                     .and_then(|p| p.as_ref())
                 {
                     profile::add_stage_calories_for_side(side, p.calories_burned);
+                    self.workout.record_stage(
+                        side,
+                        p.calories_burned,
+                        stage.duration_seconds,
+                        p.notes_hit,
+                    );
                 }
             }
         }
@@ -6891,7 +7283,7 @@ impl App {
             .controls_mut()
             .update_modifier(raw_key.code, raw_key.pressed);
 
-        if logical_input::with_keymap(|km| {
+        if logical_input::with_active_keymap(|km| {
             km.raw_key_event_has_action(&raw_key, |action| {
                 action == VirtualAction::system_fast_forward
             })
@@ -6902,7 +7294,7 @@ impl App {
                 .controls_mut()
                 .set_fast_forward(raw_key.pressed);
         }
-        if logical_input::with_keymap(|km| {
+        if logical_input::with_active_keymap(|km| {
             km.raw_key_event_has_action(&raw_key, |action| {
                 action == VirtualAction::system_slow_down
             })
@@ -7228,6 +7620,9 @@ impl App {
             options::sync_translated_titles(&mut self.state.screens.options_state, new_value);
             self.audio.play_sfx("assets/sounds/change.ogg");
         }
+        if matches!(app_raw_shortcut, Some(AppRawKeyShortcut::StartFrameCapture)) {
+            self.start_frame_capture();
+        }
         // Screen-specific Escape handling resides in per-screen raw handlers now
 
         let Some(plan) = queued_input_plan else {
@@ -8424,6 +8819,12 @@ impl App {
                     &po_state.player_options,
                     &po_state.heart_rate_device_ids,
                 );
+                let course_life_config = std::array::from_fn(|p| match course_life_config[p] {
+                    deadsync_gameplay::CourseLifeConfig::Bar => {
+                        deadsync_profile_gameplay::life_config_from_profile(&player_profiles[p])
+                    }
+                    config => config,
+                });
                 let gameplay_session = gameplay_session();
                 let gameplay_init_view = crate::gameplay_runtime::init_view(
                     &cfg,
@@ -8558,6 +8959,8 @@ impl App {
                 // Reconcile chart timing and any video fallback once after the
                 // deferred command installs the initial media.
                 gs.background_path_dirty = true;
+                gs.gameplay
+                    .set_latency_probe_enabled(self.state.shell.latency_report.enabled());
                 self.state.screens.gameplay_state = Some(gs);
                 if let Some(gs) = self.state.screens.gameplay_state.as_mut() {
                     crate::gameplay_runtime::enter(
@@ -8849,7 +9252,12 @@ impl App {
                         &mut refreshed,
                         &self.state.screens.select_music_state,
                     );
+                    select_music::adopt_song_lua_scan_levels(
+                        &mut refreshed,
+                        &self.state.screens.select_music_state,
+                    );
                     self.state.screens.select_music_state = refreshed;
+                    self.workout.invalidate_select_music();
                     self.state.screens.select_music_state.active_color_index = current_color_index;
                     let preferred = self.state.session.preferred_difficulty_index;
                     self.state.screens.select_music_state.selected_steps_index = preferred;
@@ -9066,6 +9474,15 @@ impl ApplicationHandler<UserEvent> for App {
     fn user_event(&mut self, event_loop: &ActiveEventLoop, event: UserEvent) {
         match event {
             UserEvent::GamepadSystem(ev) => {
+                match &ev {
+                    GpSystemEvent::Connected { id, uuid, .. } => {
+                        logical_input::controller_connected(*id, *uuid);
+                    }
+                    GpSystemEvent::Disconnected { id, .. } => {
+                        logical_input::controller_disconnected(*id);
+                    }
+                    GpSystemEvent::StartupComplete => {}
+                }
                 let plan = gamepad_system_event_plan(self.state.screens.current_screen, &ev);
                 if plan.forward_to_sandbox {
                     let view = gamepad_system_view(&ev);
@@ -9124,10 +9541,16 @@ impl ApplicationHandler<UserEvent> for App {
                         );
                     }
                     RawPadScreenRoute::SelectMusic => {
-                        screens::select_music::handle_raw_pad_event(
+                        let (consumed, action) = screens::select_music::handle_raw_pad_event(
                             &mut self.state.screens.select_music_state,
                             &ev,
                         );
+                        raw_pad_consumed = consumed;
+                        if !matches!(action, ThemeEffect::None)
+                            && let Err(e) = self.handle_action(action, event_loop)
+                        {
+                            log::error!("Failed to handle Select Music raw pad action: {e}");
+                        }
                     }
                     RawPadScreenRoute::Evaluation => {
                         screens::evaluation::handle_raw_pad_event(
@@ -9182,6 +9605,9 @@ impl ApplicationHandler<UserEvent> for App {
         window_id: winit::window::WindowId,
         event: WindowEvent,
     ) {
+        if self.spectator_window_event(window_id, &event) {
+            return;
+        }
         let Some(window) = self.window.clone() else {
             return;
         };
@@ -9333,6 +9759,7 @@ pub fn run(
     audio: deadsync_audio_stream::AudioControl,
     music_clock: deadsync_audio_stream::MusicClock,
     live_case: Option<crate::live_case::LiveCase>,
+    frame_capture: Option<crate::frame_capture::FrameCaptureRequest>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let (config_generation, config) = config::snapshot();
     let show_stats_mode = config.show_stats_mode.min(2);
@@ -9349,6 +9776,7 @@ pub fn run(
         music_clock,
         live_case,
     );
+    app.frame_capture = frame_capture;
 
     // Spawn background input backend threads; all input stays decoupled from frame rate.
     let proxy = event_loop.create_proxy();
@@ -9377,7 +9805,9 @@ pub fn run(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use deadsync_chart::{ArrowStats, ChartData, SongData, StaminaCounts, TechCounts};
+    use deadsync_chart::{
+        ArrowStats, ChartData, STANDARD_DIFFICULTY_NAMES, SongData, StaminaCounts, TechCounts,
+    };
 
     #[test]
     fn options_restores_main_row_only_from_standalone_main_list_children() {
@@ -9806,6 +10236,7 @@ mod tests {
             window_counts_10ms: timing_rules::WindowCounts::default(),
             ex_score_percent: 0.0,
             hard_ex_score_percent: 0.0,
+            alt_score: None,
             calories_burned: 0.0,
             column_judgments: Vec::new(),
             noteskin: None,
@@ -10009,6 +10440,62 @@ mod tests {
         assert_eq!(course_page.scatter.len(), 1);
     }
 
+    #[test]
+    fn endless_course_summary_only_counts_played_stages() {
+        let song_a = test_song_with_duration("Songs/Test/a.ssc", "a", 60.0);
+        let song_b = test_song_with_duration("Songs/Test/b.ssc", "b", 90.0);
+        let chart = Arc::new(test_chart("stage-a"));
+        let hard_index = STANDARD_DIFFICULTY_NAMES
+            .iter()
+            .position(|name| *name == "Hard")
+            .unwrap();
+        let mut stage_players: [Option<stage_stats::PlayerStageSummary>; MAX_PLAYERS] =
+            std::array::from_fn(|_| None);
+        stage_players[0] = Some(test_player_stage_summary(
+            chart,
+            score_data::Grade::Failed,
+            1.0,
+            500,
+            500,
+        ));
+        let course = CourseRunState {
+            path: PathBuf::from("Courses/Endless.crs"),
+            name: "Endless Course".to_string(),
+            banner_path: None,
+            score_hash: "endless-hash".to_string(),
+            course_difficulty_name: "Hard".to_string(),
+            course_meter: None,
+            course_stepchart_label: "Hard".to_string(),
+            course_type: deadsync_theme_simply_love::views::CourseTypeView::Endless,
+            lives: -1,
+            song_stub: song_a.clone(),
+            stages: [&song_a, &song_b, &song_b]
+                .into_iter()
+                .map(|song| CourseStageRuntime {
+                    steps_index: [hard_index; MAX_PLAYERS],
+                    ..test_course_stage(song.clone())
+                })
+                .collect(),
+            course_display_totals: [CourseDisplayTotals::default(); MAX_PLAYERS],
+            next_stage_index: 1,
+            stage_summaries: vec![stage_stats::StageSummary {
+                song: song_a.clone(),
+                music_rate: 1.0,
+                duration_seconds: 60.0,
+                players: stage_players,
+            }],
+        };
+
+        let summary = build_course_summary_stage(&course).expect("course summary");
+        let graph_stages = build_course_graph_stages(&course, "dance-single");
+        assert!((summary.duration_seconds - 60.0).abs() <= f32::EPSILON);
+        for stages in &graph_stages {
+            assert_eq!(stages.len(), 1);
+            assert_eq!(stages[0].chart.short_hash, "a");
+            assert!((stages[0].song_last_second - 60.0).abs() <= f32::EPSILON);
+        }
+    }
+
     #[test]
     fn course_summary_merges_column_judgments_from_song_pages() {
         let song = test_song_with_duration("Songs/Test/course.ssc", "course", 120.0);
@@ -132,6 +132,13 @@ impl App {
         {
             crate::gameplay_runtime::exit(gs);
         }
+        // Song-lua captures are the only render-target users; free them with
+        // the song instead of holding screen-sized images on every menu.
+        if plan.exit_gameplay
+            && let Some(backend) = self.backend.as_mut()
+        {
+            backend.release_render_targets();
+        }
         if prev != target {
             self.mark_screen_entry_runtime_dirty(target);
         }
@@ -368,6 +375,9 @@ impl App {
         if target == CurrentScreen::GrooveStatsLogin {
             self.enter_groovestats_login();
         }
+        if target == CurrentScreen::Sandbox {
+            self.sync_sandbox_latency_view();
+        }
 
         let mut commands: Vec<Command> = Vec::new();
         commands.extend(self.handle_audio_and_profile_on_fade(prev, target));
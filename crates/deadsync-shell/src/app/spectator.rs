@@ -0,0 +1,82 @@
+use super::{App, CurrentScreen};
+use crate::spectator::SpectatorWindow;
+use deadlib_render_core::RenderFrame;
+use deadsync_config::prelude as config;
+use deadsync_theme_simply_love::screens::spectator;
+use log::{info, warn};
+use winit::event::WindowEvent;
+use winit::event_loop::ActiveEventLoop;
+use winit::window::WindowId;
+
+impl App {
+    pub(super) fn open_spectator_window(&mut self, event_loop: &ActiveEventLoop) {
+        let runtime_config = config::get();
+        if !runtime_config.spectator_window || self.spectator.is_some() {
+            return;
+        }
+        match SpectatorWindow::open(event_loop, runtime_config.spectator_renderer) {
+            Ok(window) => self.spectator = Some(window),
+            Err(e) => warn!("Spectator window disabled: {e}"),
+        }
+    }
+
+    /// Routes events for the spectator window; returns `false` for any
+    /// other window.
+    pub(super) fn spectator_window_event(
+        &mut self,
+        window_id: WindowId,
+        event: &WindowEvent,
+    ) -> bool {
+        let Some(window) = self.spectator.as_mut() else {
+            return false;
+        };
+        if window.window_id() != window_id {
+            return false;
+        }
+        match event {
+            WindowEvent::CloseRequested => {
+                info!("Spectator window closed");
+                self.spectator = None;
+            }
+            WindowEvent::Resized(size) => window.resized(*size),
+            _ => {}
+        }
+        true
+    }
+
+    /// Hands the spectator worker a new frame if it finished the last one.
+    /// `screen` is the main frame just drawn; Gameplay crops it into the
+    /// notefield panels, taking its buffers along.
+    pub(super) fn submit_spectator_frame(&mut self, screen: &mut RenderFrame, total_elapsed: f32) {
+        let (Some(window), Some(backend)) = (self.spectator.as_mut(), self.backend.as_mut()) else {
+            return;
+        };
+        let view = match window.begin_frame(&self.state.shell.metrics) {
+            Ok(Some(view)) => view,
+            Ok(None) => return,
+            Err(_) => {
+                warn!("Spectator renderer stopped; closing its window");
+                self.spectator = None;
+                return;
+            }
+        };
+        let gameplay = match self.state.screens.current_screen {
+            CurrentScreen::Gameplay => self.state.screens.gameplay_state.as_ref(),
+            _ => None,
+        };
+        match gameplay {
+            Some(state) => spectator::push_actors(window.actors_mut(), state, view),
+            None => spectator::push_idle_actors(window.actors_mut(), view),
+        }
+        if !window.submit(
+            gameplay.map(|_| screen),
+            backend,
+            self.asset_manager.textures(),
+            self.asset_manager.fonts(),
+            total_elapsed,
+        ) {
+            warn!("Spectator renderer stopped; closing its window");
+            self.spectator = None;
+        }
+    }
+}
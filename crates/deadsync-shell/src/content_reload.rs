@@ -524,13 +524,13 @@ fn send_finished(tx: &mpsc::SyncSender<SimplyLoveContentReloadEvent>) {
 
 fn finished_event() -> SimplyLoveContentReloadEvent {
     SimplyLoveContentReloadEvent::Finished {
-        song_packs: deadsync_simfile::runtime_cache::get_song_cache().clone(),
+        song_packs: deadsync_simfile::duplicates::visible_song_packs(),
     }
 }
 
 pub(crate) fn reload_song(path: &Path) -> Result<Vec<deadsync_chart::SongPack>, String> {
     deadsync_simfile::app_runtime::reload_song_in_cache(path)?;
-    Ok(deadsync_simfile::runtime_cache::get_song_cache().clone())
+    Ok(deadsync_simfile::duplicates::visible_song_packs())
 }
 
 pub(crate) fn delete_song(
@@ -563,7 +563,7 @@ pub(crate) fn delete_song(
             song_dir.display()
         ));
     }
-    Ok(deadsync_simfile::runtime_cache::get_song_cache().clone())
+    Ok(deadsync_simfile::duplicates::visible_song_packs())
 }
 
 fn validated_song_dir(simfile_path: &Path, song_scan_roots: &[PathBuf]) -> Result<PathBuf, String> {
@@ -209,8 +209,22 @@ fn course_stage_seconds(stage: &CourseStageRuntime) -> f32 {
     }
 }
 
+// Endless runs keep appending stages, so only the ones actually played belong in
+// the course summary; fixed-length courses report the whole trail.
+fn summary_course_stages(course: &CourseRunState) -> &[CourseStageRuntime] {
+    if course.course_type == CourseTypeView::Endless {
+        let played = course.stage_summaries.len().min(course.stages.len());
+        &course.stages[..played]
+    } else {
+        &course.stages
+    }
+}
+
 pub fn course_total_seconds(course: &CourseRunState) -> f32 {
-    course.stages.iter().map(course_stage_seconds).sum()
+    summary_course_stages(course)
+        .iter()
+        .map(course_stage_seconds)
+        .sum()
 }
 
 pub fn course_display_timing_for_run(course: &CourseRunState) -> CourseDisplayTiming {
@@ -339,6 +353,7 @@ pub fn score_info_from_stage(
         window_counts_10ms: player.window_counts_10ms,
         ex_score_percent: player.ex_score_percent,
         hard_ex_score_percent: player.hard_ex_score_percent,
+        alt_score: None,
         calories_burned: player.calories_burned,
         column_judgments: Vec::new(),
         noteskin: None,
@@ -393,9 +408,10 @@ pub fn build_course_graph_stages(
     course: &CourseRunState,
     chart_type: &str,
 ) -> [Vec<CourseGraphStage>; MAX_PLAYERS] {
+    let stages = summary_course_stages(course);
     std::array::from_fn(|player_idx| {
-        let mut out = Vec::with_capacity(course.stages.len());
-        for stage in &course.stages {
+        let mut out = Vec::with_capacity(stages.len());
+        for stage in stages {
             let Some(chart) = stage
                 .song
                 .chart_for_steps_index(chart_type, stage.steps_index[player_idx])
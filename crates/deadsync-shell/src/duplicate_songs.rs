@@ -0,0 +1,87 @@
+//! Duplicate song detection for the Select Music report.
+//!
+//! Songs sharing a chart hash are grouped straight from the song cache; songs
+//! whose titles or music lengths match have their music decoded and
+//! fingerprinted, so the scan runs on a [`OneShotService`].
+
+use crate::one_shot::OneShotService;
+use deadsync_audio_analysis::{AudioFingerprint, compute_fingerprint, fingerprints_match};
+use deadsync_simfile::duplicates::{
+    self, DuplicateChoices, DuplicateCopy, find_duplicates, library_copies,
+};
+use deadsync_theme_simply_love::SimplyLoveDuplicateSongsResult;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Default)]
+pub(crate) struct Service {
+    worker: OneShotService<SimplyLoveDuplicateSongsResult>,
+}
+
+impl Service {
+    pub(crate) fn start(&mut self) {
+        let copies = library_copies(&deadsync_simfile::runtime_cache::get_song_cache());
+        self.worker.start(
+            "duplicate-songs",
+            move |cancel| {
+                let mut fingerprints = HashMap::new();
+                let report =
+                    find_duplicates(&copies, |a, b| same_audio(&mut fingerprints, a, b), cancel)?;
+                let mut choices = duplicates::duplicate_choices();
+                choices.forget_ungrouped(&copies, &report);
+                Some(SimplyLoveDuplicateSongsResult {
+                    report: Ok(report),
+                    choices,
+                })
+            },
+            || SimplyLoveDuplicateSongsResult {
+                report: Err("duplicate song worker stopped".to_string()),
+                choices: DuplicateChoices::default(),
+            },
+        );
+    }
+
+    pub(crate) fn cancel(&mut self) {
+        self.worker.cancel();
+    }
+
+    pub(crate) fn poll(&mut self) -> Option<SimplyLoveDuplicateSongsResult> {
+        self.worker.poll()
+    }
+}
+
+/// Fingerprints are decoded once per music file; a file that cannot be
+/// decoded never matches.
+fn same_audio(
+    fingerprints: &mut HashMap<PathBuf, Option<AudioFingerprint>>,
+    a: &DuplicateCopy,
+    b: &DuplicateCopy,
+) -> bool {
+    let (Some(a_path), Some(b_path)) = (a.music_path.as_ref(), b.music_path.as_ref()) else {
+        return false;
+    };
+    for path in [a_path, b_path] {
+        fingerprints.entry(path.clone()).or_insert_with(|| {
+            compute_fingerprint(path)
+                .inspect_err(|e| log::debug!("No fingerprint for '{}': {e}", path.display()))
+                .ok()
+        });
+    }
+    match (&fingerprints[a_path], &fingerprints[b_path]) {
+        (Some(a), Some(b)) => fingerprints_match(a, b),
+        _ => false,
+    }
+}
+
+/// Saves `choices` if they differ from what is on disk. Returns whether the
+/// wheel needs rebuilding.
+pub(crate) fn save_choices(choices: DuplicateChoices) -> bool {
+    if choices == duplicates::duplicate_choices() {
+        return false;
+    }
+    if let Err(e) = duplicates::set_duplicate_choices(choices) {
+        log::warn!("Failed to save duplicate song choices: {e}");
+        return false;
+    }
+    true
+}
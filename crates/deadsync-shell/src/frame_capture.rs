@@ -0,0 +1,466 @@
+//! Renderer frame captures and their replay.
+//!
+//! `--capture-frames <file>` keeps CPU copies of every texture so Ctrl+F10
+//! can record the exact `RenderFrame` stream for `--capture-seconds`.
+//! `--replay-frames <file>` draws a capture through `--replay-backend`
+//! (Software by default), optionally saving each frame to `--replay-out` or
+//! diffing it against `--replay-diff`, then exits before the game window
+//! opens.
+
+use deadlib_render::{
+    Backend, BackendType, CaptureEvent, CaptureReader, CapturedFrame, CapturedTexture,
+    PresentModePolicy, Texture, TextureHandleMap, create_backend, with_requested_window_size,
+};
+use image::RgbaImage;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use winit::application::ApplicationHandler;
+use winit::event::WindowEvent;
+use winit::event_loop::{ActiveEventLoop, EventLoop};
+use winit::window::{Window, WindowId};
+
+const DEFAULT_CAPTURE_SECONDS: f32 = 10.0;
+const DEFAULT_REPLAY_TOLERANCE: u8 = 2;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameCaptureRequest {
+    pub path: PathBuf,
+    pub duration: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplayOptions {
+    pub capture: PathBuf,
+    pub backend: BackendType,
+    pub diff_backend: Option<BackendType>,
+    pub out_dir: Option<PathBuf>,
+    /// Largest per-channel difference still counted as a match.
+    pub tolerance: u8,
+}
+
+/// Last value of `flag`, as `--flag value` or `--flag=value`.
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Result<Option<&'a str>, String> {
+    let mut value = None;
+    let mut index = 0usize;
+    while index < args.len() {
+        let arg = &args[index];
+        let found = if arg == flag {
+            index += 1;
+            args.get(index).map(String::as_str)
+        } else if let Some(inline) = arg
+            .strip_prefix(flag)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            Some(inline)
+        } else {
+            index += 1;
+            continue;
+        };
+        match found {
+            Some(found) if !found.is_empty() => value = Some(found),
+            _ => return Err(format!("{flag} requires a value")),
+        }
+        index += 1;
+    }
+    Ok(value)
+}
+
+fn backend_flag(args: &[String], flag: &str) -> Result<Option<BackendType>, String> {
+    flag_value(args, flag)?.map(str::parse).transpose()
+}
+
+pub fn cli_capture_request(args: &[String]) -> Result<Option<FrameCaptureRequest>, String> {
+    let seconds = flag_value(args, "--capture-seconds")?;
+    let Some(path) = flag_value(args, "--capture-frames")? else {
+        if seconds.is_some() {
+            return Err("--capture-seconds requires --capture-frames".to_owned());
+        }
+        return Ok(None);
+    };
+    let seconds = match seconds {
+        Some(value) => value
+            .parse::<f32>()
+            .ok()
+            .filter(|seconds| seconds.is_finite() && *seconds > 0.0)
+            .ok_or_else(|| format!("'{value}' is not a positive number of seconds"))?,
+        None => DEFAULT_CAPTURE_SECONDS,
+    };
+    Ok(Some(FrameCaptureRequest {
+        path: PathBuf::from(path),
+        duration: Duration::from_secs_f32(seconds),
+    }))
+}
+
+pub fn cli_replay_options(args: &[String]) -> Result<Option<ReplayOptions>, String> {
+    let Some(capture) = flag_value(args, "--replay-frames")? else {
+        return Ok(None);
+    };
+    let tolerance = match flag_value(args, "--replay-tolerance")? {
+        Some(value) => value
+            .parse::<u8>()
+            .map_err(|_| format!("'{value}' is not a tolerance between 0 and 255"))?,
+        None => DEFAULT_REPLAY_TOLERANCE,
+    };
+    Ok(Some(ReplayOptions {
+        capture: PathBuf::from(capture),
+        backend: backend_flag(args, "--replay-backend")?.unwrap_or(BackendType::Software),
+        diff_backend: backend_flag(args, "--replay-diff")?,
+        out_dir: flag_value(args, "--replay-out")?.map(PathBuf::from),
+        tolerance,
+    }))
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct ImageDiff {
+    pixels: u64,
+    max_delta: u8,
+}
+
+/// Pixels whose largest channel difference exceeds `tolerance`. Images of
+/// different sizes differ everywhere.
+fn diff_images(expected: &RgbaImage, actual: &RgbaImage, tolerance: u8) -> ImageDiff {
+    if expected.dimensions() != actual.dimensions() {
+        return ImageDiff {
+            pixels: u64::from(expected.width().max(actual.width()))
+                * u64::from(expected.height().max(actual.height())),
+            max_delta: u8::MAX,
+        };
+    }
+    let mut diff = ImageDiff::default();
+    for (expected, actual) in expected.pixels().zip(actual.pixels()) {
+        let delta = expected
+            .0
+            .iter()
+            .zip(actual.0)
+            .map(|(expected, actual)| expected.abs_diff(actual))
+            .max()
+            .unwrap_or(0);
+        if delta > tolerance {
+            diff.pixels += 1;
+        }
+        diff.max_delta = diff.max_delta.max(delta);
+    }
+    diff
+}
+
+/// Differing pixels in magenta over a dimmed copy of `expected`.
+fn diff_image(expected: &RgbaImage, actual: &RgbaImage, tolerance: u8) -> RgbaImage {
+    RgbaImage::from_fn(expected.width(), expected.height(), |x, y| {
+        let base = expected.get_pixel(x, y).0;
+        let differs = actual.get_pixel_checked(x, y).is_none_or(|other| {
+            base.iter()
+                .zip(other.0)
+                .any(|(base, other)| base.abs_diff(other) > tolerance)
+        });
+        if differs {
+            image::Rgba([255, 0, 255, 255])
+        } else {
+            image::Rgba([base[0] / 4, base[1] / 4, base[2] / 4, 255])
+        }
+    })
+}
+
+struct ReplayTarget {
+    backend_type: BackendType,
+    _window: Arc<Window>,
+    backend: Backend,
+    textures: TextureHandleMap<Texture>,
+    size: (u32, u32),
+}
+
+impl ReplayTarget {
+    fn new(
+        event_loop: &ActiveEventLoop,
+        backend_type: BackendType,
+        (width, height): (u32, u32),
+    ) -> Result<Self, String> {
+        let attributes = with_requested_window_size(
+            Window::default_attributes()
+                .with_title(format!("DeadSync frame replay ({backend_type})"))
+                .with_resizable(false),
+            backend_type,
+            false,
+            width,
+            height,
+        );
+        let window = Arc::new(
+            event_loop
+                .create_window(attributes)
+                .map_err(|e| format!("failed to open a {backend_type} window: {e}"))?,
+        );
+        let mut backend = create_backend(
+            backend_type,
+            Arc::clone(&window),
+            false,
+            PresentModePolicy::Immediate,
+            false,
+            false,
+        )
+        .map_err(|e| format!("failed to start {backend_type}: {e}"))?;
+        backend.resize(width, height);
+        Ok(Self {
+            backend_type,
+            _window: window,
+            backend,
+            textures: TextureHandleMap::default(),
+            size: (width, height),
+        })
+    }
+
+    fn upload(&mut self, texture: &CapturedTexture) -> Result<(), String> {
+        let image = RgbaImage::from_raw(texture.width, texture.height, texture.rgba.clone())
+            .ok_or_else(|| format!("texture {} has the wrong pixel count", texture.handle))?;
+        let created = self
+            .backend
+            .create_texture(&image, texture.sampler)
+            .map_err(|e| format!("{}: texture {}: {e}", self.backend_type, texture.handle))?;
+        if let Some(old) = self.textures.insert(texture.handle, created) {
+            self.backend.retire_texture(old);
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &CapturedFrame) -> Result<RgbaImage, String> {
+        if self.size != (frame.width, frame.height) {
+            self.size = (frame.width, frame.height);
+            self.backend.resize(frame.width, frame.height);
+        }
+        self.backend.set_default_projection(frame.projection);
+        self.backend.request_screenshot();
+        self.backend
+            .draw(&frame.frame, &self.textures, false)
+            .map_err(|e| format!("{}: {e}", self.backend_type))?;
+        self.backend
+            .capture_frame()
+            .map_err(|e| format!("{}: {e}", self.backend_type))
+    }
+
+    fn shutdown(mut self) {
+        self.backend.dispose_textures(&mut self.textures);
+        self.backend.cleanup();
+    }
+}
+
+#[derive(Default)]
+struct ReplaySummary {
+    frames: u32,
+    differing_frames: u32,
+}
+
+struct Replay<'a> {
+    options: &'a ReplayOptions,
+    result: Option<Result<ReplaySummary, String>>,
+}
+
+impl ApplicationHandler for Replay<'_> {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.result.is_none() {
+            self.result = Some(replay(event_loop, self.options));
+        }
+        event_loop.exit();
+    }
+
+    fn window_event(&mut self, _: &ActiveEventLoop, _: WindowId, _: WindowEvent) {}
+}
+
+fn read_event(
+    reader: &mut CaptureReader<BufReader<File>>,
+    path: &Path,
+) -> Result<Option<CaptureEvent>, String> {
+    reader
+        .next_event()
+        .map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn replay(event_loop: &ActiveEventLoop, options: &ReplayOptions) -> Result<ReplaySummary, String> {
+    let path = &options.capture;
+    let file = File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let mut reader =
+        CaptureReader::new(BufReader::new(file)).map_err(|e| format!("{}: {e}", path.display()))?;
+    if let Some(out_dir) = &options.out_dir {
+        std::fs::create_dir_all(out_dir).map_err(|e| format!("{}: {e}", out_dir.display()))?;
+    }
+
+    // Windows open at the first frame's size, so textures before it wait.
+    let mut pending = Vec::new();
+    let first = loop {
+        match read_event(&mut reader, path)? {
+            Some(CaptureEvent::Texture(texture)) => pending.push(texture),
+            Some(CaptureEvent::Frame(frame)) => break frame,
+            None => return Ok(ReplaySummary::default()),
+        }
+    };
+    let size = (first.width, first.height);
+    let mut targets = vec![ReplayTarget::new(event_loop, options.backend, size)?];
+    if let Some(diff_backend) = options.diff_backend {
+        targets.push(ReplayTarget::new(event_loop, diff_backend, size)?);
+    }
+    for texture in &pending {
+        for target in &mut targets {
+            target.upload(texture)?;
+        }
+    }
+
+    let mut summary = ReplaySummary::default();
+    let mut next = Some(first);
+    loop {
+        let frame = match next.take() {
+            Some(frame) => frame,
+            None => match read_event(&mut reader, path) {
+                Ok(Some(CaptureEvent::Texture(texture))) => {
+                    for target in &mut targets {
+                        target.upload(&texture)?;
+                    }
+                    continue;
+                }
+                Ok(Some(CaptureEvent::Frame(frame))) => frame,
+                Ok(None) => break,
+                // A capture cut short by a crash still replays every
+                // complete frame.
+                Err(e) => {
+                    log::warn!("Stopping replay at frame {}: {e}", summary.frames);
+                    break;
+                }
+            },
+        };
+        let index = summary.frames;
+        let mut images = Vec::with_capacity(targets.len());
+        for target in &mut targets {
+            images.push(target.draw(&frame)?);
+        }
+        if let Some(out_dir) = &options.out_dir {
+            save_image(&images[0], &out_dir.join(format!("frame_{index:05}.png")))?;
+        }
+        if let [expected, actual] = images.as_slice() {
+            let diff = diff_images(expected, actual, options.tolerance);
+            if diff.pixels > 0 {
+                summary.differing_frames += 1;
+                println!(
+                    "frame {index}: {} pixel(s) differ (max delta {})",
+                    diff.pixels, diff.max_delta
+                );
+                if let Some(out_dir) = &options.out_dir {
+                    save_image(actual, &out_dir.join(format!("frame_{index:05}_other.png")))?;
+                    save_image(
+                        &diff_image(expected, actual, options.tolerance),
+                        &out_dir.join(format!("frame_{index:05}_diff.png")),
+                    )?;
+                }
+            }
+        }
+        summary.frames += 1;
+    }
+    for target in targets {
+        target.shutdown();
+    }
+    Ok(summary)
+}
+
+fn save_image(image: &RgbaImage, path: &Path) -> Result<(), String> {
+    image
+        .save(path)
+        .map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Replays the capture and prints a summary; the exit code is 1 on errors or
+/// when any frame differs from `--replay-diff`.
+pub fn run_replay_cli(options: &ReplayOptions) -> i32 {
+    let event_loop = match EventLoop::new() {
+        Ok(event_loop) => event_loop,
+        Err(e) => {
+            eprintln!("Failed to create an event loop for replay: {e}");
+            return 1;
+        }
+    };
+    let mut replay = Replay {
+        options,
+        result: None,
+    };
+    if let Err(e) = event_loop.run_app(&mut replay) {
+        eprintln!("Replay event loop failed: {e}");
+        return 1;
+    }
+    match replay.result {
+        Some(Ok(summary)) => {
+            println!(
+                "{}: {} frame(s) replayed on {}",
+                options.capture.display(),
+                summary.frames,
+                options.backend
+            );
+            if let Some(diff_backend) = options.diff_backend {
+                println!(
+                    "{} frame(s) differ from {diff_backend} beyond tolerance {}",
+                    summary.differing_frames, options.tolerance
+                );
+            }
+            i32::from(summary.differing_frames > 0)
+        }
+        Some(Err(e)) => {
+            eprintln!("Replay failed: {e}");
+            1
+        }
+        None => {
+            eprintln!("Replay never started");
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| (*arg).to_string()).collect()
+    }
+
+    #[test]
+    fn capture_and_replay_flags_parse_with_defaults() {
+        assert_eq!(cli_capture_request(&args(&["--console"])), Ok(None));
+        assert_eq!(
+            cli_capture_request(&args(&[
+                "--capture-frames",
+                "a.dsfc",
+                "--capture-seconds=2.5"
+            ])),
+            Ok(Some(FrameCaptureRequest {
+                path: PathBuf::from("a.dsfc"),
+                duration: Duration::from_secs_f32(2.5),
+            }))
+        );
+        assert!(cli_capture_request(&args(&["--capture-seconds", "3"])).is_err());
+        assert!(
+            cli_capture_request(&args(&["--capture-frames=a", "--capture-seconds=0"])).is_err()
+        );
+
+        let options = cli_replay_options(&args(&["--replay-frames=a.dsfc", "--replay-out", "out"]))
+            .expect("valid flags")
+            .expect("replay requested");
+        assert_eq!(options.backend, BackendType::Software);
+        assert_eq!(options.diff_backend, None);
+        assert_eq!(options.out_dir, Some(PathBuf::from("out")));
+        assert_eq!(options.tolerance, DEFAULT_REPLAY_TOLERANCE);
+        assert!(cli_replay_options(&args(&["--replay-frames"])).is_err());
+        assert!(cli_replay_options(&args(&["--replay-frames=a", "--replay-diff=nope"])).is_err());
+    }
+
+    #[test]
+    fn image_diff_counts_pixels_beyond_tolerance() {
+        let expected = RgbaImage::from_pixel(2, 2, image::Rgba([100, 100, 100, 255]));
+        let mut actual = expected.clone();
+        actual.put_pixel(0, 0, image::Rgba([102, 100, 100, 255]));
+        actual.put_pixel(1, 1, image::Rgba([100, 140, 100, 255]));
+        assert_eq!(
+            diff_images(&expected, &actual, 2),
+            ImageDiff {
+                pixels: 1,
+                max_delta: 40,
+            }
+        );
+        assert_eq!(diff_images(&expected, &RgbaImage::new(3, 2), 255).pixels, 6);
+    }
+}
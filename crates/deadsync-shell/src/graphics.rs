@@ -26,6 +26,7 @@ pub struct RendererInitConfig {
     pub gfx_debug_enabled: bool,
     pub software_renderer_threads: u8,
     pub display_aspect_ratio: f32,
+    pub mirror_textures: bool,
 }
 
 pub struct RendererInitResult {
@@ -42,6 +43,7 @@ pub struct RendererStartupSettings {
     pub hide_cursor: bool,
     pub gfx_debug_enabled: bool,
     pub software_renderer_threads: u8,
+    pub mirror_textures: bool,
 }
 
 #[derive(Clone, Copy)]
@@ -165,6 +167,9 @@ pub fn initialize_renderer(
         config.gfx_debug_enabled,
         config.high_dpi,
     )?;
+    if config.mirror_textures {
+        backend.enable_texture_mirror(size.width, size.height);
+    }
     backend.set_default_projection(space::ortho_for_aspect(config.display_aspect_ratio));
     if config.backend_type == BackendType::Software {
         backend.configure_software_threads(software_thread_count(config.software_renderer_threads));
@@ -203,6 +208,7 @@ pub fn renderer_startup_config(
             gfx_debug_enabled: settings.gfx_debug_enabled,
             software_renderer_threads: settings.software_renderer_threads,
             display_aspect_ratio: shell.display_aspect_ratio,
+            mirror_textures: settings.mirror_textures,
         },
     }
 }
@@ -1147,6 +1153,7 @@ mod tests {
                 hide_cursor: true,
                 gfx_debug_enabled: true,
                 software_renderer_threads: 4,
+                mirror_textures: false,
             },
         );
 
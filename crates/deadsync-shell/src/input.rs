@@ -154,6 +154,7 @@ pub enum AppRawKeyShortcut {
     FrameStatsToggle,
     CycleOverlayMode,
     ToggleTranslatedTitles,
+    StartFrameCapture,
 }
 
 pub fn gamepad_system_event_plan(screen: Screen, ev: &GpSystemEvent) -> GamepadSystemEventPlan {
@@ -226,6 +227,8 @@ const fn pad_backend_name(backend: PadBackend) -> &'static str {
         PadBackend::WindowsWgi => "Windows Gaming Input",
         #[cfg(target_os = "linux")]
         PadBackend::LinuxEvdev => "Linux evdev",
+        #[cfg(target_os = "linux")]
+        PadBackend::LinuxMidi => "Linux ALSA MIDI",
         #[cfg(target_os = "freebsd")]
         PadBackend::FreeBsdHidraw => "FreeBSD hidraw",
         #[cfg(target_os = "freebsd")]
@@ -245,6 +248,7 @@ pub fn gamepad_system_view(ev: &GpSystemEvent) -> GamepadSystemView {
             product_id,
             backend,
             initial,
+            ..
         } => GamepadSystemView::Connected {
             name: name.clone(),
             id: usize::from(*id),
@@ -460,6 +464,10 @@ pub const fn app_raw_key_shortcut(
         return Some(AppRawKeyShortcut::ToggleTranslatedTitles);
     }
 
+    if pressed && !repeat && ctrl_held && matches!(code, KeyCode::F10) {
+        return Some(AppRawKeyShortcut::StartFrameCapture);
+    }
+
     None
 }
 
@@ -1216,6 +1224,14 @@ mod tests {
             app_raw_key_shortcut(true, false, KeyCode::F9, false, false, false, false),
             Some(AppRawKeyShortcut::ToggleTranslatedTitles)
         );
+        assert_eq!(
+            app_raw_key_shortcut(true, false, KeyCode::F10, true, false, false, false),
+            Some(AppRawKeyShortcut::StartFrameCapture)
+        );
+        assert_eq!(
+            app_raw_key_shortcut(true, false, KeyCode::F10, false, false, false, false),
+            None
+        );
     }
 
     #[test]
@@ -1395,6 +1411,7 @@ mod tests {
                 id: PadId(7),
                 vendor_id: None,
                 product_id: None,
+                uuid: [7; 16],
                 backend: PadBackend::Smx,
                 initial: true,
             },
@@ -1412,6 +1429,7 @@ mod tests {
                 id: PadId(7),
                 vendor_id: None,
                 product_id: None,
+                uuid: [7; 16],
                 backend: PadBackend::Smx,
                 initial: false,
             },
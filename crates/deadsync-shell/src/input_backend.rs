@@ -71,6 +71,19 @@ pub fn launch_input_backends(proxy: EventLoopProxy<UserEvent>, config: InputBack
                 input_host,
             );
         });
+        let proxy_pad = proxy.clone();
+        let proxy_sys = proxy.clone();
+        std::thread::spawn(move || {
+            deadsync_input_native::run_linux_midi_backend(
+                move |event| {
+                    let _ = proxy_pad.send_event(UserEvent::Pad(event));
+                },
+                move |event| {
+                    let _ = proxy_sys.send_event(UserEvent::GamepadSystem(event));
+                },
+                input_host,
+            );
+        });
     }
     #[cfg(target_os = "freebsd")]
     {
@@ -0,0 +1,445 @@
+use deadlib_render_core::PresentStats;
+use deadsync_core::input::InputSource;
+use deadsync_gameplay::GameplayLatencyProbe;
+use deadsync_theme_simply_love::screens::sandbox::{
+    LatencyDeviceView, LatencyReportView, LatencyTailView,
+};
+use serde::Serialize;
+use std::time::Instant;
+
+// A probe whose present is never reported complete (backends without
+// present timing) is settled with the submit time after this many frames.
+const PRESENT_WAIT_FRAMES: u8 = 8;
+const MAX_PENDING_PROBES: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum LatencyDevice {
+    Keyboard,
+    Pad(u32),
+}
+
+impl LatencyDevice {
+    fn from_probe(source: InputSource, input_slot: u32) -> Self {
+        match source {
+            InputSource::Keyboard => Self::Keyboard,
+            InputSource::Gamepad => deadsync_input::input_slot_pad_id(input_slot)
+                .map_or(Self::Pad(u32::MAX), |id| Self::Pad(id.0)),
+        }
+    }
+
+    fn label(self) -> String {
+        match self {
+            Self::Keyboard => "Keyboard".to_string(),
+            Self::Pad(u32::MAX) => "Pad ?".to_string(),
+            Self::Pad(id) => format!("Pad {id}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct PendingProbe {
+    device: LatencyDevice,
+    input_host_nanos: u64,
+    judge_us: u32,
+    audio_us: u32,
+    present_id: Option<u32>,
+    submit_host_nanos: u64,
+    frames_waited: u8,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct LatencySample {
+    judge_us: u32,
+    photon_us: u32,
+    audio_us: u32,
+    // False when the backend never reported the present and `photon_us` is
+    // only the input-to-submit time.
+    present_timed: bool,
+}
+
+#[derive(Clone, Debug)]
+struct DeviceSamples {
+    device: LatencyDevice,
+    samples: Vec<LatencySample>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct LatencyTail {
+    pub samples: u32,
+    pub p50_us: u32,
+    pub p95_us: u32,
+    pub p99_us: u32,
+    pub max_us: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DeviceLatencySummary {
+    pub device: String,
+    pub input_to_judgment: LatencyTail,
+    pub input_to_photon: LatencyTail,
+    pub input_to_audio: LatencyTail,
+    pub untimed_presents: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct LatencyReportContext {
+    pub renderer: String,
+    pub present_policy: String,
+    pub present_mode: String,
+    pub vsync: bool,
+    pub max_fps: u32,
+    pub refresh_ns: u64,
+    pub audio_output_delay_us: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct LatencyReportArtifact<'a> {
+    context: &'a LatencyReportContext,
+    devices: Vec<DeviceLatencySummary>,
+}
+
+/// Camera-free end-to-end latency report. Gameplay hands over each live
+/// press with its judgment time; the report pairs it with the present that
+/// first showed the result and with the audio output delay at that moment.
+pub struct LatencyReport {
+    enabled: bool,
+    pending: Vec<PendingProbe>,
+    devices: Vec<DeviceSamples>,
+    generation: u64,
+}
+
+impl LatencyReport {
+    pub const fn new() -> Self {
+        Self {
+            enabled: false,
+            pending: Vec::new(),
+            devices: Vec::new(),
+            generation: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    #[inline(always)]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Turning the report on starts a fresh measurement.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled && !self.enabled {
+            self.pending.clear();
+            self.devices.clear();
+        }
+        self.enabled = enabled;
+        if !enabled {
+            self.pending.clear();
+        }
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    pub fn record_probe(
+        &mut self,
+        probe: GameplayLatencyProbe,
+        now: Instant,
+        now_host_nanos: u64,
+        audio_output_delay_ns: u64,
+    ) {
+        if !self.enabled || self.pending.len() >= MAX_PENDING_PROBES {
+            return;
+        }
+        let input_host_nanos = if probe.captured_host_nanos != 0 {
+            probe.captured_host_nanos
+        } else {
+            let age_ns = now.saturating_duration_since(probe.captured_at).as_nanos();
+            now_host_nanos.saturating_sub(u64::try_from(age_ns).unwrap_or(u64::MAX))
+        };
+        let judge_us = saturating_us(
+            probe
+                .judged_at
+                .saturating_duration_since(probe.captured_at)
+                .as_nanos(),
+        );
+        self.pending.push(PendingProbe {
+            device: LatencyDevice::from_probe(probe.source, probe.input_slot),
+            input_host_nanos,
+            judge_us,
+            audio_us: judge_us.saturating_add(saturating_us(u128::from(audio_output_delay_ns))),
+            present_id: None,
+            submit_host_nanos: 0,
+            frames_waited: 0,
+        });
+    }
+
+    /// Bind probes judged this frame to its present and settle the ones whose
+    /// present the backend now reports as shown.
+    pub fn record_frame(&mut self, stats: PresentStats, submit_host_nanos: u64) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let mut settled = false;
+        let mut index = 0;
+        while index < self.pending.len() {
+            let probe = &mut self.pending[index];
+            let Some(present_id) = probe.present_id else {
+                probe.present_id = Some(stats.submitted_present_id);
+                probe.submit_host_nanos = submit_host_nanos;
+                index += 1;
+                continue;
+            };
+            probe.frames_waited = probe.frames_waited.saturating_add(1);
+            let sample = present_photon_host_nanos(stats, present_id)
+                .map(|photon_host_nanos| (photon_host_nanos, true))
+                .or_else(|| {
+                    (probe.frames_waited >= PRESENT_WAIT_FRAMES)
+                        .then_some((probe.submit_host_nanos, false))
+                });
+            let Some((photon_host_nanos, present_timed)) = sample else {
+                index += 1;
+                continue;
+            };
+            let probe = self.pending.swap_remove(index);
+            self.push_sample(
+                probe.device,
+                LatencySample {
+                    judge_us: probe.judge_us,
+                    photon_us: saturating_us(u128::from(
+                        photon_host_nanos.saturating_sub(probe.input_host_nanos),
+                    )),
+                    audio_us: probe.audio_us,
+                    present_timed,
+                },
+            );
+            settled = true;
+        }
+        if settled {
+            self.generation = self.generation.wrapping_add(1);
+        }
+    }
+
+    fn push_sample(&mut self, device: LatencyDevice, sample: LatencySample) {
+        match self
+            .devices
+            .binary_search_by_key(&device, |entry| entry.device)
+        {
+            Ok(index) => self.devices[index].samples.push(sample),
+            Err(index) => self.devices.insert(
+                index,
+                DeviceSamples {
+                    device,
+                    samples: vec![sample],
+                },
+            ),
+        }
+    }
+
+    pub(crate) fn summaries(&self) -> Vec<DeviceLatencySummary> {
+        self.devices
+            .iter()
+            .map(|entry| DeviceLatencySummary {
+                device: entry.device.label(),
+                input_to_judgment: latency_tail(entry.samples.iter().map(|s| s.judge_us)),
+                input_to_photon: latency_tail(entry.samples.iter().map(|s| s.photon_us)),
+                input_to_audio: latency_tail(entry.samples.iter().map(|s| s.audio_us)),
+                untimed_presents: entry.samples.iter().filter(|s| !s.present_timed).count() as u32,
+            })
+            .collect()
+    }
+
+    pub fn view(&self) -> LatencyReportView {
+        LatencyReportView {
+            enabled: self.enabled,
+            devices: self
+                .summaries()
+                .into_iter()
+                .map(|summary| LatencyDeviceView {
+                    device: summary.device,
+                    judgment: tail_view(summary.input_to_judgment),
+                    photon: tail_view(summary.input_to_photon),
+                    audio: tail_view(summary.input_to_audio),
+                })
+                .collect(),
+        }
+    }
+
+    pub(crate) fn to_json(&self, context: &LatencyReportContext) -> serde_json::Result<Vec<u8>> {
+        serde_json::to_vec_pretty(&LatencyReportArtifact {
+            context,
+            devices: self.summaries(),
+        })
+    }
+}
+
+impl Default for LatencyReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Host time at which `present_id` reached the display, when known. A present
+/// that was overtaken before its completion was observed is backdated from
+/// the latest one by whole refresh intervals.
+fn present_photon_host_nanos(stats: PresentStats, present_id: u32) -> Option<u64> {
+    if stats.host_present_ns == 0 {
+        return None;
+    }
+    let behind = stats.completed_present_id.wrapping_sub(present_id);
+    if behind > u32::MAX / 2 {
+        return None;
+    }
+    if behind == 0 {
+        return Some(stats.host_present_ns);
+    }
+    if stats.refresh_ns == 0 {
+        return None;
+    }
+    Some(
+        stats
+            .host_present_ns
+            .saturating_sub(u64::from(behind).saturating_mul(stats.refresh_ns)),
+    )
+}
+
+// Nearest-rank percentiles, matching the pacing report tails.
+fn latency_tail(values: impl Iterator<Item = u32>) -> LatencyTail {
+    let mut sorted: Vec<u32> = values.collect();
+    if sorted.is_empty() {
+        return LatencyTail::default();
+    }
+    sorted.sort_unstable();
+    let len = sorted.len();
+    let rank = |pct: usize| sorted[(len * pct).div_ceil(100).clamp(1, len) - 1];
+    LatencyTail {
+        samples: len as u32,
+        p50_us: rank(50),
+        p95_us: rank(95),
+        p99_us: rank(99),
+        max_us: sorted[len - 1],
+    }
+}
+
+#[inline(always)]
+fn tail_view(tail: LatencyTail) -> LatencyTailView {
+    LatencyTailView {
+        samples: tail.samples,
+        p50_us: tail.p50_us,
+        p95_us: tail.p95_us,
+        p99_us: tail.p99_us,
+        max_us: tail.max_us,
+    }
+}
+
+#[inline(always)]
+fn saturating_us(nanos: u128) -> u32 {
+    u32::try_from(nanos / 1_000).unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(source: InputSource, captured_host_nanos: u64, judge_us: u64) -> GameplayLatencyProbe {
+        let captured_at = Instant::now();
+        GameplayLatencyProbe {
+            source,
+            input_slot: deadsync_input::INPUT_SLOT_INVALID,
+            captured_at,
+            captured_host_nanos,
+            judged_at: captured_at + std::time::Duration::from_micros(judge_us),
+            hit_note: true,
+        }
+    }
+
+    fn stats(submitted: u32, completed: u32, host_present_ns: u64) -> PresentStats {
+        PresentStats {
+            submitted_present_id: submitted,
+            completed_present_id: completed,
+            host_present_ns,
+            refresh_ns: 10_000_000,
+            ..PresentStats::default()
+        }
+    }
+
+    #[test]
+    fn latency_tail_uses_nearest_rank_percentiles() {
+        assert_eq!(
+            latency_tail(1..=100),
+            LatencyTail {
+                samples: 100,
+                p50_us: 50,
+                p95_us: 95,
+                p99_us: 99,
+                max_us: 100,
+            }
+        );
+        assert_eq!(latency_tail(std::iter::empty()), LatencyTail::default());
+        assert_eq!(latency_tail(std::iter::once(7)).p99_us, 7);
+    }
+
+    #[test]
+    fn probes_settle_when_their_present_completes() {
+        let mut report = LatencyReport::new();
+        report.record_probe(
+            probe(InputSource::Keyboard, 1_000_000, 500),
+            Instant::now(),
+            0,
+            0,
+        );
+        assert!(report.pending.is_empty());
+
+        report.set_enabled(true);
+        report.record_probe(
+            probe(InputSource::Keyboard, 1_000_000, 500),
+            Instant::now(),
+            0,
+            2_000_000,
+        );
+        report.record_frame(stats(4, 2, 5_000_000), 1_200_000);
+        assert_eq!(report.pending[0].present_id, Some(4));
+
+        report.record_frame(stats(5, 3, 15_000_000), 0);
+        assert_eq!(report.pending.len(), 1);
+        let generation = report.generation();
+        report.record_frame(stats(6, 4, 25_000_000), 0);
+        assert!(report.pending.is_empty());
+        assert_ne!(report.generation(), generation);
+
+        let summaries = report.summaries();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].device, "Keyboard");
+        assert_eq!(summaries[0].input_to_judgment.p50_us, 500);
+        assert_eq!(summaries[0].input_to_photon.p50_us, 24_000);
+        assert_eq!(summaries[0].input_to_audio.p50_us, 2_500);
+        assert_eq!(summaries[0].untimed_presents, 0);
+    }
+
+    #[test]
+    fn overtaken_presents_backdate_and_untimed_presents_fall_back_to_submit() {
+        assert_eq!(
+            present_photon_host_nanos(stats(9, 7, 50_000_000), 5),
+            Some(30_000_000)
+        );
+        assert_eq!(present_photon_host_nanos(stats(9, 4, 50_000_000), 5), None);
+        assert_eq!(present_photon_host_nanos(stats(9, 7, 0), 7), None);
+
+        let mut report = LatencyReport::new();
+        report.set_enabled(true);
+        report.record_probe(
+            probe(InputSource::Gamepad, 1_000_000, 0),
+            Instant::now(),
+            0,
+            0,
+        );
+        report.record_frame(stats(1, 0, 0), 3_000_000);
+        for _ in 0..PRESENT_WAIT_FRAMES {
+            report.record_frame(stats(1, 0, 0), 0);
+        }
+        let summaries = report.summaries();
+        assert_eq!(summaries[0].device, "Pad ?");
+        assert_eq!(summaries[0].input_to_photon.max_us, 2_000);
+        assert_eq!(summaries[0].untimed_presents, 1);
+    }
+}
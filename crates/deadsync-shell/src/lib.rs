@@ -5,7 +5,9 @@ mod command;
 mod content_reload;
 mod course;
 mod diagnostics;
+mod duplicate_songs;
 mod dynamic_media;
+pub mod frame_capture;
 mod frame_loop;
 mod frame_pacing_trace;
 mod frame_stats;
@@ -19,17 +21,24 @@ mod input;
 mod input_backend;
 mod input_trace;
 mod interaction;
+mod latency_report;
 mod lighting;
 pub mod live_case;
 mod local_profiles;
 mod main_menu;
 mod mappings;
 mod navigation;
+mod offset_drift;
 mod offset_prompt;
+mod one_shot;
 mod options_runtime;
+pub mod pack_lint;
 mod pad_config;
+mod pattern_analysis;
+mod player_input;
 mod player_options;
 mod post_song;
+mod profile_history;
 mod profile_import;
 mod profile_load;
 mod profile_session;
@@ -44,12 +53,15 @@ mod select_music;
 mod session;
 mod session_results;
 mod smx_config;
+pub mod song_lua_scan;
 mod song_search;
+mod spectator;
 mod stutter_diag;
 mod sync_analysis;
 mod transition_effects;
 mod window;
 mod window_state;
+mod workout;
 
 #[cfg(feature = "bench-support")]
 pub use app::benchmark_smx_screen_work;
@@ -70,6 +82,7 @@ pub(crate) use input_trace::GameplayInputTrace;
 #[cfg(test)]
 pub(crate) use interaction::ExitIntent;
 pub(crate) use interaction::ShellInteractionState;
+pub(crate) use latency_report::LatencyReport;
 pub(crate) use navigation::{TransitionMusicPaths, TransitionState, transition_audio_plan};
 #[cfg(feature = "bench-support")]
 pub use profile_import::BenchmarkProfileImportService;
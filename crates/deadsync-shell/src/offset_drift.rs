@@ -0,0 +1,86 @@
+//! Shell-owned worker for the Manage Profiles timing view.
+//!
+//! Building the report reads the header of every score file of every local
+//! profile, so it runs on its own thread like the progress history refresh.
+
+use crate::one_shot::OneShotService;
+use deadsync_profile::compat as profile;
+use deadsync_score::{OffsetDriftPlay, offset_drift_plays_in_root, offset_drift_report};
+use deadsync_theme_simply_love::{SimplyLoveOffsetDriftReports, SimplyLoveOffsetDriftResult};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// At most one report is built at a time; starting another cancels the
+/// previous one.
+#[derive(Default)]
+pub(crate) struct Service {
+    worker: OneShotService<SimplyLoveOffsetDriftResult>,
+}
+
+impl Service {
+    pub(crate) fn start(&mut self, profile_id: String) {
+        let stopped_profile_id = profile_id.clone();
+        self.worker.start(
+            "offset-drift",
+            move |cancel| {
+                let reports = build(&profile_id, cancel)?;
+                Some(SimplyLoveOffsetDriftResult {
+                    profile_id,
+                    reports: Ok(reports),
+                })
+            },
+            move || SimplyLoveOffsetDriftResult {
+                profile_id: stopped_profile_id,
+                reports: Err("offset drift worker stopped".to_string()),
+            },
+        );
+    }
+
+    pub(crate) fn cancel(&mut self) {
+        self.worker.cancel();
+    }
+
+    pub(crate) fn poll(&mut self) -> Option<SimplyLoveOffsetDriftResult> {
+        self.worker.poll()
+    }
+}
+
+/// Pack folder name for every chart hash in the loaded library.
+fn pack_by_chart_hash() -> HashMap<String, String> {
+    let cache = deadsync_simfile::runtime_cache::get_song_cache();
+    let mut packs = HashMap::new();
+    for pack in cache.iter() {
+        for song in &pack.songs {
+            for chart in &song.charts {
+                packs.insert(chart.short_hash.clone(), pack.name.clone());
+            }
+        }
+    }
+    packs
+}
+
+/// `None` once cancelled.
+fn build(profile_id: &str, cancel: &AtomicBool) -> Option<SimplyLoveOffsetDriftReports> {
+    let local_dir = profile::score_profile_paths_for_id(profile_id).local_dir();
+    let profile_plays = offset_drift_plays_in_root(&local_dir);
+
+    let mut machine_plays: Vec<OffsetDriftPlay> = Vec::new();
+    for source in profile::local_score_profile_sources() {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        machine_plays.extend(offset_drift_plays_in_root(&source.root));
+    }
+    machine_plays.sort_by(|a, b| {
+        a.played_at_ms
+            .cmp(&b.played_at_ms)
+            .then_with(|| a.chart_hash.cmp(&b.chart_hash))
+    });
+
+    let packs = pack_by_chart_hash();
+    let pack_for_hash = |hash: &str| packs.get(hash).cloned();
+    Some(SimplyLoveOffsetDriftReports {
+        profile: offset_drift_report(profile_plays, pack_for_hash),
+        machine: offset_drift_report(machine_plays, pack_for_hash),
+    })
+}
@@ -0,0 +1,133 @@
+//! One background job at a time, for the screens that hand the shell a long
+//! scan and pick the result up later.
+//!
+//! Starting a job cancels the one before it, and a cancelled job never
+//! delivers. A worker that ends without a result (it panicked or gave up) is
+//! reported through the fallback given to [`OneShotService::start`], so the
+//! screen never waits forever.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, mpsc};
+
+struct Job<T> {
+    cancel: Arc<AtomicBool>,
+    rx: mpsc::Receiver<T>,
+    stopped: Box<dyn FnOnce() -> T + Send>,
+}
+
+pub(crate) struct OneShotService<T> {
+    job: Option<Job<T>>,
+}
+
+impl<T> Default for OneShotService<T> {
+    fn default() -> Self {
+        Self { job: None }
+    }
+}
+
+impl<T: Send + 'static> OneShotService<T> {
+    /// Runs `work` on a thread called `name`. `work` should poll the flag it is
+    /// given and return `None` once cancelled; `stopped` builds the result for
+    /// a worker that ends without one.
+    pub(crate) fn start(
+        &mut self,
+        name: &str,
+        work: impl FnOnce(&AtomicBool) -> Option<T> + Send + 'static,
+        stopped: impl FnOnce() -> T + Send + 'static,
+    ) {
+        self.cancel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let thread_cancel = Arc::clone(&cancel);
+        let (tx, rx) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                if let Some(result) = work(&thread_cancel)
+                    && !thread_cancel.load(Ordering::Relaxed)
+                {
+                    let _ = tx.send(result);
+                }
+            });
+        if let Err(e) = spawned {
+            log::warn!("Failed to spawn {name} worker: {e}");
+            return;
+        }
+        self.job = Some(Job {
+            cancel,
+            rx,
+            stopped: Box::new(stopped),
+        });
+    }
+
+    pub(crate) fn cancel(&mut self) {
+        if let Some(job) = self.job.take() {
+            job.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns `None` without touching a channel while nothing is running.
+    pub(crate) fn poll(&mut self) -> Option<T> {
+        let result = match self.job.as_ref()?.rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return None,
+            Err(mpsc::TryRecvError::Disconnected) => (self.job.take()?.stopped)(),
+        };
+        self.job = None;
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn wait<T: Send + 'static>(service: &mut OneShotService<T>) -> Option<T> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if let Some(result) = service.poll() {
+                return Some(result);
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        None
+    }
+
+    #[test]
+    fn one_shot_poll_only_runs_while_a_job_is_active() {
+        let mut service = OneShotService::<u32>::default();
+        assert!(service.poll().is_none());
+
+        service.start("one-shot-test", |_| Some(7), || 0);
+        assert_eq!(wait(&mut service), Some(7));
+        assert!(service.poll().is_none());
+    }
+
+    #[test]
+    fn one_shot_reports_a_worker_that_stops_without_a_result() {
+        let mut service = OneShotService::<Result<u32, String>>::default();
+        service.start(
+            "one-shot-test",
+            |_| None,
+            || Err("worker stopped".to_string()),
+        );
+        assert_eq!(wait(&mut service), Some(Err("worker stopped".to_string())));
+    }
+
+    #[test]
+    fn one_shot_restart_drops_the_cancelled_job() {
+        let mut service = OneShotService::<u32>::default();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        service.start(
+            "one-shot-test",
+            move |cancel| {
+                let _ = release_rx.recv();
+                (!cancel.load(Ordering::Relaxed)).then_some(1)
+            },
+            || 0,
+        );
+        service.start("one-shot-test", |_| Some(2), || 0);
+        let _ = release_tx.send(());
+        assert_eq!(wait(&mut service), Some(2));
+    }
+}
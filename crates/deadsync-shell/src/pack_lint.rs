@@ -0,0 +1,143 @@
+//! Pack linting, from the Select Music pack menu or the command line.
+//!
+//! Linting re-parses every simfile in the pack and decodes its music, so the
+//! in-game pass runs on its own thread. `--lint-pack <dir>` prints the same
+//! report and exits before the window opens.
+
+use crate::one_shot::OneShotService;
+use deadsync_simfile::app_runtime as song_loading;
+use deadsync_simfile::lint::{LintSeverity, PackLintReport};
+use deadsync_theme_simply_love::SimplyLovePackLintResult;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+
+/// At most one pack is linted at a time; starting another cancels the
+/// previous one.
+#[derive(Default)]
+pub(crate) struct Service {
+    worker: OneShotService<SimplyLovePackLintResult>,
+}
+
+impl Service {
+    pub(crate) fn start(&mut self, pack_dir: PathBuf) {
+        let stopped_pack_dir = pack_dir.clone();
+        self.worker.start(
+            "pack-lint",
+            move |cancel| {
+                let mut known = library_chart_hashes(&pack_dir);
+                let report = song_loading::lint_pack(&pack_dir, &mut known, cancel)?;
+                Some(SimplyLovePackLintResult {
+                    pack_dir,
+                    report: Ok(report),
+                })
+            },
+            move || SimplyLovePackLintResult {
+                pack_dir: stopped_pack_dir,
+                report: Err("pack lint worker stopped".to_string()),
+            },
+        );
+    }
+
+    pub(crate) fn cancel(&mut self) {
+        self.worker.cancel();
+    }
+
+    pub(crate) fn poll(&mut self) -> Option<SimplyLovePackLintResult> {
+        self.worker.poll()
+    }
+}
+
+/// Chart hashes of every loaded pack except `pack_dir`, as `pack/title`.
+fn library_chart_hashes(pack_dir: &Path) -> HashMap<String, String> {
+    let cache = deadsync_simfile::runtime_cache::get_song_cache();
+    let mut hashes = HashMap::new();
+    for pack in cache.iter().filter(|pack| pack.directory != pack_dir) {
+        for song in &pack.songs {
+            for chart in &song.charts {
+                if !chart.short_hash.is_empty() {
+                    hashes
+                        .entry(chart.short_hash.clone())
+                        .or_insert_with(|| format!("{}/{}", pack.name, song.title));
+                }
+            }
+        }
+    }
+    hashes
+}
+
+/// Pack directories named by `--lint-pack`, in order.
+pub fn cli_pack_dirs(args: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut dirs = Vec::new();
+    let mut index = 0usize;
+    while index < args.len() {
+        let arg = &args[index];
+        if arg == "--lint-pack" {
+            index += 1;
+            let value = args
+                .get(index)
+                .ok_or("--lint-pack requires a pack directory")?;
+            dirs.push(PathBuf::from(value));
+        } else if let Some(value) = arg.strip_prefix("--lint-pack=") {
+            if value.is_empty() {
+                return Err("--lint-pack requires a pack directory".to_owned());
+            }
+            dirs.push(PathBuf::from(value));
+        }
+        index += 1;
+    }
+    Ok(dirs)
+}
+
+fn print_report(report: &PackLintReport) {
+    println!(
+        "{}: {} song(s) checked, {} error(s), {} warning(s)",
+        report.pack_dir.display(),
+        report.songs_checked,
+        report.count(LintSeverity::Error),
+        report.count(LintSeverity::Warning)
+    );
+    for song in &report.songs {
+        println!("  {} ({})", song.title, song.simfile_path.display());
+        for finding in &song.findings {
+            println!("    {finding}");
+        }
+    }
+    for (path, error) in &report.failures {
+        println!("  failed to read {}: {error}", path.display());
+    }
+}
+
+/// Lints each pack and prints its report. Packs are checked against each
+/// other for duplicate charts; the exit code is 1 if anything is an error.
+pub fn run_cli(pack_dirs: &[PathBuf]) -> i32 {
+    let never = AtomicBool::new(false);
+    let mut known: HashMap<String, String> = HashMap::new();
+    let mut failed = false;
+    for pack_dir in pack_dirs {
+        let Some(report) = song_loading::lint_pack(pack_dir, &mut known, &never) else {
+            continue;
+        };
+        print_report(&report);
+        failed |= report.count(LintSeverity::Error) > 0 || !report.failures.is_empty();
+    }
+    i32::from(failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| (*arg).to_string()).collect()
+    }
+
+    #[test]
+    fn lint_pack_flags_are_collected_in_order() {
+        let dirs = cli_pack_dirs(&args(&["--lint-pack", "A", "--console", "--lint-pack=B"]))
+            .expect("valid flags");
+        assert_eq!(dirs, vec![PathBuf::from("A"), PathBuf::from("B")]);
+        assert!(cli_pack_dirs(&args(&["--lint-pack"])).is_err());
+        assert!(cli_pack_dirs(&args(&["--lint-pack="])).is_err());
+    }
+}
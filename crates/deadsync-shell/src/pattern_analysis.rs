@@ -0,0 +1,306 @@
+//! Shell-owned worker for the Select Music pattern analysis.
+//!
+//! Re-judging a profile's replays means loading every played chart, so the
+//! whole pass runs on a [`OneShotService`]. Judged plays are cached by chart
+//! hash and play time, so a rerun only loads charts with new plays.
+
+use crate::one_shot::OneShotService;
+use deadsync_chart::{GameplayChartData, SongData};
+use deadsync_core::note::NoteType;
+use deadsync_core::song_time::{SongTimeNs, song_time_ns_invalid};
+use deadsync_profile::PlayerSide;
+use deadsync_profile::compat as profile;
+use deadsync_profile_gameplay::replay_lane_map;
+use deadsync_score::{
+    LocalScoreEntry, LocalTurnMod, PatternJudgment, PatternNote, PatternPlay, PatternProfile,
+    PatternReport, PatternRowFeet, build_pattern_report, judge_pattern_replay,
+    pattern_notes_from_chart, read_local_score_entry, read_local_score_header,
+};
+use deadsync_simfile::app_runtime as song_loading;
+use deadsync_simfile::notes::step_type_lanes;
+use deadsync_simfile::timing::crossover_annotations;
+use deadsync_theme_simply_love::SimplyLovePatternAnalysisResult;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Focuses that get practice charts.
+const WEAKEST_FOCUSES: usize = 3;
+const PICKS_PER_FOCUS: usize = 5;
+
+/// Judged plays by chart hash and play time. `None` marks a play that cannot
+/// be judged: it has no replay, or its turn mod cannot be mapped back.
+type PlayCache = HashMap<(String, i64), Option<PatternPlay>>;
+
+/// At most one analysis runs; starting another cancels the previous one.
+#[derive(Default)]
+pub(crate) struct Service {
+    worker: OneShotService<SimplyLovePatternAnalysisResult>,
+    plays: Arc<Mutex<PlayCache>>,
+}
+
+impl Service {
+    pub(crate) fn start(&mut self, side: PlayerSide, chart_type: &'static str) {
+        let plays = Arc::clone(&self.plays);
+        self.worker.start(
+            "pattern-analysis",
+            move |cancel| {
+                let report = analyze_side(side, chart_type, &plays, cancel);
+                Some(SimplyLovePatternAnalysisResult { side, report })
+            },
+            move || SimplyLovePatternAnalysisResult {
+                side,
+                report: Err("pattern analysis worker stopped".to_string()),
+            },
+        );
+    }
+
+    pub(crate) fn cancel(&mut self) {
+        self.worker.cancel();
+    }
+
+    pub(crate) fn poll(&mut self) -> Option<SimplyLovePatternAnalysisResult> {
+        self.worker.poll()
+    }
+}
+
+pub(crate) fn library_charts() -> (Vec<Arc<SongData>>, HashMap<String, (usize, usize)>) {
+    let cache = deadsync_simfile::runtime_cache::get_song_cache();
+    let songs: Vec<Arc<SongData>> = cache
+        .iter()
+        .flat_map(|pack| pack.songs.iter().cloned())
+        .collect();
+    drop(cache);
+    let mut charts = HashMap::new();
+    for (song_ix, song) in songs.iter().enumerate() {
+        for (chart_ix, chart) in song.charts.iter().enumerate() {
+            charts
+                .entry(chart.short_hash.clone())
+                .or_insert((song_ix, chart_ix));
+        }
+    }
+    (songs, charts)
+}
+
+/// Pattern notes in the lanes the player saw, tagged with rssp's step parity
+/// for the styles it covers.
+fn played_pattern_notes(
+    chart: &GameplayChartData,
+    columns: usize,
+    lanes: &[usize],
+) -> Vec<PatternNote> {
+    let feet = match columns {
+        4 => played_row_feet::<4>(chart, lanes),
+        8 => played_row_feet::<8>(chart, lanes),
+        _ => Vec::new(),
+    };
+    pattern_notes_from_chart(chart, columns, lanes, &feet)
+}
+
+fn played_row_feet<const LANES: usize>(
+    chart: &GameplayChartData,
+    lanes: &[usize],
+) -> Vec<PatternRowFeet> {
+    let mut rows: BTreeMap<usize, [u8; LANES]> = BTreeMap::new();
+    for note in &chart.parsed_notes {
+        let lane = lanes.get(note.column).copied().unwrap_or(note.column);
+        if lane >= LANES {
+            continue;
+        }
+        let cell = match note.note_type {
+            NoteType::Tap => b'1',
+            NoteType::Lift => b'L',
+            NoteType::Hold => b'2',
+            NoteType::Roll => b'4',
+            NoteType::Mine => b'M',
+            NoteType::Fake => continue,
+        };
+        let row = rows.entry(note.row_index).or_insert([b'0'; LANES]);
+        if cell != b'M' || row[lane] == b'0' {
+            row[lane] = cell;
+        }
+        if let Some(tail_row) = note.tail_row_index {
+            let tail = rows.entry(tail_row).or_insert([b'0'; LANES]);
+            if tail[lane] == b'0' {
+                tail[lane] = b'3';
+            }
+        }
+    }
+    let mut row_arrays = Vec::with_capacity(rows.len());
+    let mut row_to_beat = Vec::with_capacity(rows.len());
+    for (row_index, row) in rows {
+        if let Some(beat) = chart.timing.get_beat_for_row(row_index) {
+            row_arrays.push(row);
+            row_to_beat.push(beat);
+        }
+    }
+    crossover_annotations::<LANES>(&row_arrays, &row_to_beat, &chart.timing_segments)
+        .into_iter()
+        .map(|annotation| PatternRowFeet {
+            beat: annotation.beat,
+            left_foot_mask: annotation.left_foot_mask,
+            right_foot_mask: annotation.right_foot_mask,
+            crossover: annotation.crossover,
+            bracket: annotation.bracket,
+        })
+        .collect()
+}
+
+/// Notes in played lanes, built once per lane map a chart was played with.
+struct PlayedChart<'a> {
+    chart: &'a GameplayChartData,
+    columns: usize,
+    beat0_ns: SongTimeNs,
+    notes_by_lanes: HashMap<Vec<usize>, Vec<PatternNote>>,
+}
+
+impl<'a> PlayedChart<'a> {
+    fn new(chart: &'a GameplayChartData, chart_type: &str) -> Self {
+        Self {
+            chart,
+            columns: step_type_lanes(chart_type),
+            beat0_ns: chart.timing.get_time_for_beat_ns(0.0),
+            notes_by_lanes: HashMap::new(),
+        }
+    }
+
+    /// The play's notes and re-judged replay. `None` without a replay, or when
+    /// the turn mod rearranged the chart row by row. Scores saved before turn
+    /// mods were recorded are read as played without one.
+    fn judge(&mut self, entry: &LocalScoreEntry) -> Option<(&[PatternNote], Vec<PatternJudgment>)> {
+        if entry.replay.is_empty() {
+            return None;
+        }
+        let turn = entry.turn_mod.unwrap_or(LocalTurnMod::None);
+        let lanes = replay_lane_map(turn, entry.turn_seed, self.columns)?;
+        let (chart, columns) = (self.chart, self.columns);
+        let notes = self
+            .notes_by_lanes
+            .entry(lanes)
+            .or_insert_with_key(|lanes| played_pattern_notes(chart, columns, lanes));
+        let judgments = judge_pattern_replay(
+            notes,
+            &entry.replay,
+            beat0_shift_ns(self.beat0_ns, entry.beat0_time_ns),
+            entry.music_rate,
+        );
+        Some((notes.as_slice(), judgments))
+    }
+}
+
+fn analyze_side(
+    side: PlayerSide,
+    chart_type: &'static str,
+    cache: &Mutex<PlayCache>,
+    cancel: &AtomicBool,
+) -> Result<PatternReport, String> {
+    let files = profile::local_score_files_for_side(side);
+    if files.is_empty() {
+        return Err("no local scores for this profile".to_string());
+    }
+    let mut by_chart: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    for (hash, path) in files {
+        by_chart.entry(hash).or_default().push(path);
+    }
+
+    let (songs, charts) = library_charts();
+    let mut patterns = PatternProfile::default();
+    let mut missing_plays = 0u32;
+    for (hash, paths) in &by_chart {
+        if cancel.load(Ordering::Relaxed) {
+            return Err("cancelled".to_string());
+        }
+        let mut pending = Vec::new();
+        {
+            let cache = cache.lock().unwrap_or_else(|error| error.into_inner());
+            for path in paths {
+                let Some(header) = read_local_score_header(path) else {
+                    continue;
+                };
+                match cache.get(&(hash.clone(), header.played_at_ms)) {
+                    Some(Some(play)) => patterns.add_play(hash, play),
+                    Some(None) => {}
+                    None => pending.push((header.played_at_ms, path)),
+                }
+            }
+        }
+        if pending.is_empty() {
+            continue;
+        }
+        let Some(&(song_ix, chart_ix)) = charts.get(hash) else {
+            missing_plays += pending.len() as u32;
+            continue;
+        };
+        let song = &songs[song_ix];
+        let chart = &song.charts[chart_ix];
+        let loaded = match song_loading::load_gameplay_charts(song, &[chart_ix], 0.0) {
+            Ok(mut loaded) if !loaded.is_empty() => loaded.swap_remove(0),
+            Ok(_) => {
+                missing_plays += pending.len() as u32;
+                continue;
+            }
+            Err(e) => {
+                log::warn!("Pattern analysis could not load chart {hash}: {e}");
+                missing_plays += pending.len() as u32;
+                continue;
+            }
+        };
+        let mut played = PlayedChart::new(&loaded, &chart.chart_type);
+        for (played_at_ms, path) in pending {
+            let Some(entry) = read_local_score_entry(path) else {
+                continue;
+            };
+            let play = played.judge(&entry).and_then(|(notes, judgments)| {
+                PatternPlay::judge(chart.meter, notes, &judgments, entry.music_rate)
+            });
+            if let Some(play) = &play {
+                patterns.add_play(hash, play);
+            }
+            cache
+                .lock()
+                .unwrap_or_else(|error| error.into_inner())
+                .insert((hash.clone(), played_at_ms), play);
+        }
+    }
+    if patterns.plays == 0 {
+        return Err("no replays to analyze".to_string());
+    }
+    Ok(build_pattern_report(
+        patterns,
+        missing_plays,
+        chart_type,
+        songs.iter().map(Arc::as_ref),
+        WEAKEST_FOCUSES,
+        PICKS_PER_FOCUS,
+    ))
+}
+
+/// Same correction replay playback applies when chart timing changed since
+/// the score was recorded.
+fn beat0_shift_ns(current_ns: SongTimeNs, recorded_ns: SongTimeNs) -> SongTimeNs {
+    if song_time_ns_invalid(current_ns) || song_time_ns_invalid(recorded_ns) {
+        0
+    } else {
+        current_ns.saturating_sub(recorded_ns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deadsync_core::song_time::INVALID_SONG_TIME_NS;
+
+    #[test]
+    fn pattern_poll_only_runs_while_a_job_is_active() {
+        let mut service = Service::default();
+        assert!(service.poll().is_none());
+    }
+
+    #[test]
+    fn pattern_beat0_shift_ignores_invalid_times() {
+        assert_eq!(beat0_shift_ns(1_500, 1_000), 500);
+        assert_eq!(beat0_shift_ns(INVALID_SONG_TIME_NS, 1_000), 0);
+        assert_eq!(beat0_shift_ns(1_000, INVALID_SONG_TIME_NS), 0);
+    }
+}
@@ -0,0 +1,33 @@
+use deadsync_profile::PlayerSide;
+
+/// Game-thread owner of per-profile binding overlays and controller claims.
+///
+/// Lifetime: process. Unchanged frames perform one atomic generation load.
+/// A profile or session change clones at most two overlays and republishes
+/// the active keymap only when a side's overlay actually differs. Claims of
+/// sides that are no longer joined are released on the same pass.
+#[derive(Default)]
+pub(crate) struct Runtime {
+    profile_generation: Option<u64>,
+}
+
+impl Runtime {
+    pub(crate) fn sync(&mut self) -> bool {
+        let generation = deadsync_profile::runtime_profile_generation();
+        if self.profile_generation == Some(generation) {
+            return false;
+        }
+        self.profile_generation = Some(generation);
+        let bindings =
+            deadsync_profile::with_runtime_input_bindings(|bindings| bindings.map(Option::cloned));
+        for (player, bindings) in bindings.into_iter().enumerate() {
+            deadsync_input::set_player_bindings(player, bindings);
+        }
+        for (player, side) in [PlayerSide::P1, PlayerSide::P2].into_iter().enumerate() {
+            if !deadsync_profile::runtime_session_side_joined(side) {
+                deadsync_input::release_controller_claim(player);
+            }
+        }
+        true
+    }
+}
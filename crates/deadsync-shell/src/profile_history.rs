@@ -0,0 +1,155 @@
+//! Shell-owned worker for the Manage Profiles progress history.
+//!
+//! Refreshing reads every new score file and loads the charts they were
+//! played on, so it runs on its own thread. The refreshed history is saved
+//! back to `history.bin` before it is handed to the screen.
+
+use crate::one_shot::OneShotService;
+use deadsync_profile::compat as profile;
+use deadsync_profile_gameplay::replay_lane_map;
+use deadsync_score::{
+    HistoryChart, ProfileHistory, load_profile_history_file, pattern_notes_from_chart,
+    profile_history_json, refresh_profile_history, save_profile_history_file,
+};
+use deadsync_simfile::app_runtime as song_loading;
+use deadsync_simfile::notes::step_type_lanes;
+use deadsync_theme_simply_love::{SimplyLoveLocalProfileEvent, SimplyLoveProfileHistoryResult};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const HISTORY_JSON_FILE: &str = "history.json";
+
+/// At most one refresh runs; starting another cancels the previous one.
+#[derive(Default)]
+pub(crate) struct Service {
+    worker: OneShotService<SimplyLoveProfileHistoryResult>,
+}
+
+impl Service {
+    pub(crate) fn start(&mut self, profile_id: String) {
+        let stopped_profile_id = profile_id.clone();
+        self.worker.start(
+            "profile-history",
+            move |cancel| {
+                let history = refresh(&profile_id, cancel)?;
+                Some(SimplyLoveProfileHistoryResult {
+                    profile_id,
+                    history: Ok(history),
+                })
+            },
+            move || SimplyLoveProfileHistoryResult {
+                profile_id: stopped_profile_id,
+                history: Err("profile history worker stopped".to_string()),
+            },
+        );
+    }
+
+    pub(crate) fn cancel(&mut self) {
+        self.worker.cancel();
+    }
+
+    pub(crate) fn poll(&mut self) -> Option<SimplyLoveProfileHistoryResult> {
+        self.worker.poll()
+    }
+}
+
+/// Weight from `profile.ini`, the same value gameplay burns calories with.
+fn weight_pounds(profile_dir: &Path) -> i32 {
+    let mut ini = deadsync_config::prelude::SimpleIni::new();
+    let stored = if ini
+        .load(deadsync_profile::profile_ini_path(profile_dir))
+        .is_ok()
+    {
+        ini.get("Editable", "WeightPounds")
+            .and_then(|raw| raw.trim().parse::<i32>().ok())
+            .map_or(0, deadsync_profile::clamp_weight_pounds)
+    } else {
+        0
+    };
+    deadsync_profile::resolved_weight_pounds(stored)
+}
+
+/// `None` once cancelled. Plays skipped for the cancel, like plays on charts
+/// missing from the library, are added by a later refresh.
+fn refresh(profile_id: &str, cancel: &AtomicBool) -> Option<ProfileHistory> {
+    let paths = profile::score_profile_paths_for_id(profile_id);
+    let history_path = paths.local_history_path();
+    let mut history = load_profile_history_file(&history_path).unwrap_or_default();
+    let weight = weight_pounds(paths.profile_dir());
+    let (songs, charts) = crate::pattern_analysis::library_charts();
+    let chart_for_hash = |hash: &str| {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        let &(song_ix, chart_ix) = charts.get(hash)?;
+        let song = &songs[song_ix];
+        let chart = &song.charts[chart_ix];
+        let loaded = match song_loading::load_gameplay_charts(song, &[chart_ix], 0.0) {
+            Ok(mut loaded) if !loaded.is_empty() => loaded.swap_remove(0),
+            Ok(_) => return None,
+            Err(e) => {
+                log::warn!("Profile history could not load chart {hash}: {e}");
+                return None;
+            }
+        };
+        let columns = step_type_lanes(&chart.chart_type);
+        Some(HistoryChart {
+            meter: chart.meter,
+            columns,
+            notes: pattern_notes_from_chart(&loaded, columns, &[], &[]),
+            beat0_time_ns: loaded.timing.get_time_for_beat_ns(0.0),
+        })
+    };
+    let added = refresh_profile_history(
+        &mut history,
+        &paths.local_dir(),
+        weight,
+        chart_for_hash,
+        replay_lane_map,
+    );
+    if added > 0
+        && let Err(e) = save_profile_history_file(&history_path, &history)
+    {
+        log::warn!("Failed to save profile history for {profile_id}: {e:?}");
+    }
+    (!cancel.load(Ordering::Relaxed)).then_some(history)
+}
+
+/// Exports what the last refresh stored; does not rescan score files.
+pub(crate) fn export(profile_id: &str, display_name: &str) -> SimplyLoveLocalProfileEvent {
+    let paths = profile::score_profile_paths_for_id(profile_id);
+    let history = load_profile_history_file(&paths.local_history_path()).unwrap_or_default();
+    let out_path: PathBuf = paths.profile_dir().join(HISTORY_JSON_FILE);
+    let result = profile_history_json(&history, display_name, &chrono::Local)
+        .map_err(|e| log::warn!("Failed to encode profile history for {profile_id}: {e}"))
+        .and_then(|json| {
+            std::fs::write(&out_path, json).map_err(|e| {
+                log::warn!("Failed to write {}: {e}", out_path.display());
+            })
+        })
+        .map(|()| out_path);
+    SimplyLoveLocalProfileEvent::HistoryExported {
+        profile_id: profile_id.to_string(),
+        result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_poll_only_runs_while_a_job_is_active() {
+        let mut service = Service::default();
+        assert!(service.poll().is_none());
+    }
+
+    #[test]
+    fn history_weight_falls_back_to_default_without_profile_ini() {
+        let dir = std::env::temp_dir().join("deadsync-profile-history-missing-ini");
+        assert_eq!(
+            weight_pounds(&dir),
+            deadsync_profile::resolved_weight_pounds(0)
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     FrameLoopState, FrameStatsController, FrameStatsSample, GameplayInputTrace,
-    GameplayPacingTrace, ShellInteractionState, StutterDiagRecorder, TransitionState,
+    GameplayPacingTrace, LatencyReport, ShellInteractionState, StutterDiagRecorder,
+    TransitionState,
 };
 use deadlib_present::space::{self, Metrics};
 use deadlib_render_core::{DrawStats, PresentModePolicy, PresentStats};
@@ -37,6 +38,7 @@ pub struct ShellState {
     pub overlay_mode: OverlayMode,
     pub stutter_samples: StutterSampleRing,
     pub stutter_diag: StutterDiagRecorder,
+    pub latency_report: LatencyReport,
     pub frame_stats: FrameStatsController,
     pub transition: TransitionState,
     pub display_width: u32,
@@ -75,6 +77,7 @@ impl ShellState {
             overlay_mode: OverlayMode::from_code(overlay_mode),
             stutter_samples: StutterSampleRing::new(),
             stutter_diag: StutterDiagRecorder::new(),
+            latency_report: LatencyReport::new(),
             frame_stats: FrameStatsController::new(
                 cfg.frame_stats_overlay_anchor,
                 cfg.frame_stats_overlay_style,
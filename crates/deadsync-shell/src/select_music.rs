@@ -153,7 +153,7 @@ pub(crate) fn init_view() -> SelectMusicInitView {
     let session = session_view();
     SelectMusicInitView {
         song_scan_roots: deadsync_simfile::app_runtime::collect_song_scan_roots(&songs_root),
-        song_packs: deadsync_simfile::runtime_cache::get_song_cache().clone(),
+        song_packs: deadsync_simfile::duplicates::visible_song_packs(),
         songs_root,
         courses_root: dirs.courses_dir(),
         playlists,
@@ -298,13 +298,16 @@ pub(crate) fn history_view() -> SelectMusicHistoryView {
             available: true,
             played_chart_counts: scores::played_chart_counts_for_profile(profile_id),
             recent_chart_hashes: scores::recent_played_chart_hashes_for_profile(profile_id),
+            recent_play_ex: scores::recent_play_ex_for_profile(profile_id),
             cached_scores: Vec::new(),
+            local_ex_scores: Vec::new(),
         }
     });
     let score_caches = scores::lock_score_caches();
     for (side, profile_id) in sides.iter_mut().zip(profile_ids.iter()) {
         if let Some(profile_id) = profile_id {
             side.cached_scores = score_caches.merged_profile_scores(profile_id);
+            side.local_ex_scores = score_caches.local_profile_ex_scores(profile_id);
         }
     }
     SelectMusicHistoryView {
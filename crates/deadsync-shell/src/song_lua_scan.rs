@@ -0,0 +1,239 @@
+//! Song-lua compatibility scans, from the Select Music pack menu or the
+//! command line.
+//!
+//! Every song-lua entry point in the pack is compiled headlessly with the
+//! gameplay host, so the in-game pass runs on its own thread.
+//! `--scan-song-lua <dir>` prints the same report and exits before the window
+//! opens.
+
+use crate::one_shot::OneShotService;
+use deadsync_assets::song_lua::{SongLuaCompileContext, compile_song_lua};
+use deadsync_chart::SongData;
+use deadsync_simfile::app_runtime as song_loading;
+use deadsync_song_lua::{
+    SongLuaCompatibility, SongLuaCompatibilityLevel, SongLuaScanEntry, SongLuaScanReport,
+    SongLuaScanSong,
+};
+use deadsync_theme_simply_love::SimplyLoveSongLuaScanResult;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// At most one pack is scanned at a time; starting another cancels the
+/// previous one.
+#[derive(Default)]
+pub(crate) struct Service {
+    worker: OneShotService<SimplyLoveSongLuaScanResult>,
+}
+
+impl Service {
+    pub(crate) fn start(&mut self, pack_dir: PathBuf) {
+        let stopped_pack_dir = pack_dir.clone();
+        self.worker.start(
+            "song-lua-scan",
+            move |cancel| {
+                let report = scan_pack(&pack_dir, cancel)?;
+                Some(SimplyLoveSongLuaScanResult {
+                    pack_dir,
+                    report: Ok(report),
+                })
+            },
+            move || SimplyLoveSongLuaScanResult {
+                pack_dir: stopped_pack_dir,
+                report: Err("song lua scan worker stopped".to_string()),
+            },
+        );
+    }
+
+    pub(crate) fn cancel(&mut self) {
+        self.worker.cancel();
+    }
+
+    pub(crate) fn poll(&mut self) -> Option<SimplyLoveSongLuaScanResult> {
+        self.worker.poll()
+    }
+}
+
+/// Foreground and background lua changes in chart order, without repeats.
+fn lua_entry_points(song: &SongData) -> Vec<(PathBuf, f32)> {
+    let mut entries: Vec<(PathBuf, f32)> = Vec::new();
+    let changes = song
+        .foreground_lua_changes
+        .iter()
+        .map(|change| (&change.path, change.start_beat))
+        .chain(
+            song.background_lua_changes
+                .iter()
+                .map(|change| (&change.path, change.start_beat)),
+        );
+    for (path, start_beat) in changes {
+        if !entries.iter().any(|(known, _)| known == path) {
+            entries.push((path.clone(), start_beat));
+        }
+    }
+    entries
+}
+
+fn scan_song(song: &SongData, cancel: &AtomicBool) -> Option<SongLuaScanSong> {
+    let entry_points = lua_entry_points(song);
+    if entry_points.is_empty() {
+        return None;
+    }
+    let song_dir = song.simfile_path.parent().unwrap_or_else(|| Path::new(""));
+    let mut context = SongLuaCompileContext::new(song_dir, song.title.clone());
+    context.song_display_bpms = [song.min_bpm as f32, song.max_bpm as f32];
+    context.music_length_seconds = song_loading::song_music_length_seconds(song);
+    let mut entries = Vec::with_capacity(entry_points.len());
+    for (path, start_beat) in entry_points {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        let result = compile_song_lua(&path, &context);
+        entries.push(SongLuaScanEntry {
+            compatibility: SongLuaCompatibility::from_compile_result(&result, |compiled| {
+                &compiled.info
+            }),
+            path,
+            start_beat,
+        });
+    }
+    Some(SongLuaScanSong {
+        simfile_path: song.simfile_path.clone(),
+        title: song.title.clone(),
+        entries,
+    })
+}
+
+/// Compiles every song-lua entry point in `pack_dir`; `None` once `cancel`
+/// is set.
+pub fn scan_pack(pack_dir: &Path, cancel: &AtomicBool) -> Option<SongLuaScanReport> {
+    let (songs, failures) = song_loading::parse_pack_songs(pack_dir, cancel)?;
+    let mut report = SongLuaScanReport {
+        pack_dir: pack_dir.to_path_buf(),
+        pack_name: pack_dir
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned()),
+        songs_checked: songs.len(),
+        failures,
+        ..SongLuaScanReport::default()
+    };
+    for song in songs.iter().filter(|song| song.has_lua) {
+        if let Some(scanned) = scan_song(song, cancel) {
+            report.songs.push(scanned);
+        }
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+    }
+    Some(report)
+}
+
+/// Pack directories named by `--scan-song-lua`, in order.
+pub fn cli_pack_dirs(args: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut dirs = Vec::new();
+    let mut index = 0usize;
+    while index < args.len() {
+        let arg = &args[index];
+        if arg == "--scan-song-lua" {
+            index += 1;
+            let value = args
+                .get(index)
+                .ok_or("--scan-song-lua requires a pack directory")?;
+            dirs.push(PathBuf::from(value));
+        } else if let Some(value) = arg.strip_prefix("--scan-song-lua=") {
+            if value.is_empty() {
+                return Err("--scan-song-lua requires a pack directory".to_owned());
+            }
+            dirs.push(PathBuf::from(value));
+        }
+        index += 1;
+    }
+    Ok(dirs)
+}
+
+fn level_text(level: SongLuaCompatibilityLevel) -> &'static str {
+    match level {
+        SongLuaCompatibilityLevel::Supported => "supported",
+        SongLuaCompatibilityLevel::Degraded => "degraded",
+        SongLuaCompatibilityLevel::Failed => "failed",
+    }
+}
+
+fn print_report(report: &SongLuaScanReport) {
+    println!(
+        "{}: {} song(s) checked, {} with lua: {} supported, {} degraded, {} failed",
+        report.pack_dir.display(),
+        report.songs_checked,
+        report.songs.len(),
+        report.count(SongLuaCompatibilityLevel::Supported),
+        report.count(SongLuaCompatibilityLevel::Degraded),
+        report.count(SongLuaCompatibilityLevel::Failed)
+    );
+    for song in &report.songs {
+        println!(
+            "  {} ({}): {}",
+            song.title,
+            song.simfile_path.display(),
+            level_text(song.level())
+        );
+        for entry in &song.entries {
+            println!(
+                "    {} @ beat {:.3}: {}",
+                entry.path.display(),
+                entry.start_beat,
+                level_text(entry.compatibility.level())
+            );
+            match &entry.compatibility {
+                SongLuaCompatibility::Supported => {}
+                SongLuaCompatibility::Degraded(dropped) => {
+                    for construct in dropped {
+                        println!("      dropped {construct}");
+                    }
+                }
+                SongLuaCompatibility::Failed(error) => println!("      {error}"),
+            }
+        }
+    }
+    for (path, error) in &report.failures {
+        println!("  failed to read {}: {error}", path.display());
+    }
+}
+
+/// Scans each pack and prints its report; the exit code is 1 if any lua
+/// entry point or simfile failed.
+pub fn run_cli(pack_dirs: &[PathBuf]) -> i32 {
+    let never = AtomicBool::new(false);
+    let mut failed = false;
+    for pack_dir in pack_dirs {
+        let Some(report) = scan_pack(pack_dir, &never) else {
+            continue;
+        };
+        print_report(&report);
+        failed |=
+            report.count(SongLuaCompatibilityLevel::Failed) > 0 || !report.failures.is_empty();
+    }
+    i32::from(failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| (*arg).to_string()).collect()
+    }
+
+    #[test]
+    fn scan_song_lua_flags_are_collected_in_order() {
+        let dirs = cli_pack_dirs(&args(&[
+            "--scan-song-lua",
+            "A",
+            "--lint-pack",
+            "C",
+            "--scan-song-lua=B",
+        ]))
+        .expect("valid flags");
+        assert_eq!(dirs, vec![PathBuf::from("A"), PathBuf::from("B")]);
+        assert!(cli_pack_dirs(&args(&["--scan-song-lua"])).is_err());
+        assert!(cli_pack_dirs(&args(&["--scan-song-lua="])).is_err());
+    }
+}
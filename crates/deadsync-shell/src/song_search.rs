@@ -5,11 +5,12 @@
 //! then only sends queries (`Rank`). Results come back through
 //! [`Service::poll`] tagged with a generation the screen can check.
 
+use deadsync_simfile::song_query::SongQueryPlayerState;
 use deadsync_theme_simply_love::{
     SimplyLoveSongSearchRequest, SimplyLoveSongSearchResult, SongSearchIndex, SongSearchScope,
     build_pack_matches, build_song_matches, build_song_search_index,
 };
-use std::sync::mpsc;
+use std::sync::{Arc, mpsc};
 
 /// A long-lived worker plus its result queue.
 pub(crate) struct Service {
@@ -62,6 +63,7 @@ struct PendingRank {
     query: String,
     scope: SongSearchScope,
     chart_type: &'static str,
+    player: Arc<SongQueryPlayerState>,
 }
 
 /// Fold one request into the worker's state.
@@ -82,6 +84,7 @@ fn absorb(
             query,
             scope,
             chart_type,
+            player,
         } => {
             if pending
                 .as_ref()
@@ -92,6 +95,7 @@ fn absorb(
                     query,
                     scope,
                     chart_type,
+                    player,
                 });
             }
         }
@@ -121,7 +125,9 @@ fn worker_loop(
         let matches = index
             .as_ref()
             .map_or_else(Vec::new, |index| match rank.scope {
-                SongSearchScope::Song => build_song_matches(index, &rank.query, rank.chart_type),
+                SongSearchScope::Song => {
+                    build_song_matches(index, &rank.query, rank.chart_type, &rank.player)
+                }
                 SongSearchScope::Pack => build_pack_matches(index, &rank.query),
             });
 
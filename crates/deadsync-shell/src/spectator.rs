@@ -0,0 +1,334 @@
+//! Optional second window with the theme's spectator layout.
+//!
+//! `SpectatorWindow=1` opens it at startup, drawn through
+//! `SpectatorRenderer` on a worker thread that owns its backend. The game
+//! thread composes the spectator screen from the same gameplay state,
+//! prepends its own finished frame as an offscreen pass for the notefield
+//! panels, and hands both over with the mirrored textures they sample. It
+//! never waits on the worker: while a frame is still being drawn, newer
+//! frames are simply not composed. A worker that stops, on a failed draw or
+//! otherwise, closes the window the next time a frame would be composed.
+
+use deadlib_present::actors::Actor;
+use deadlib_present::compose::{self, ComposeScratch, TextLayoutCache};
+use deadlib_present::font::FontMap;
+use deadlib_present::space::{self, Metrics};
+use deadlib_render::{
+    Backend, BackendType, PresentModePolicy, RenderFrame, RenderTargetPass, SharedTexture, Texture,
+    TextureHandleMap, TextureShareCursor, create_backend, with_requested_window_size,
+};
+use deadsync_assets::PRESENT_TEXTURE_CONTEXT;
+use deadsync_theme_simply_love::screens::spectator::{GAMEPLAY_TARGET_SLOT, SpectatorView};
+use log::{info, warn};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, SyncSender, TryRecvError};
+use std::thread::{self, JoinHandle};
+use winit::dpi::PhysicalSize;
+use winit::event_loop::ActiveEventLoop;
+use winit::window::{Window, WindowId};
+
+const WINDOW_SIZE: (u32, u32) = (1280, 720);
+const CLEAR_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+const TEXT_LAYOUT_CACHE_LIMIT: usize = 4_096;
+
+struct SpectatorFrame {
+    frame: RenderFrame,
+    textures: Vec<SharedTexture>,
+    size: PhysicalSize<u32>,
+}
+
+/// The render worker has exited; the window should be closed.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct WorkerStopped;
+
+pub(crate) struct SpectatorWindow {
+    window: Arc<Window>,
+    frames: Option<SyncSender<SpectatorFrame>>,
+    /// Frames the worker finished drawing, returned for reuse.
+    drawn: Receiver<RenderFrame>,
+    worker: Option<JoinHandle<()>>,
+    in_flight: bool,
+    size: PhysicalSize<u32>,
+    metrics: Metrics,
+    cursor: TextureShareCursor,
+    actors: Vec<Actor>,
+    text_layout_cache: TextLayoutCache,
+    compose_scratch: ComposeScratch,
+}
+
+impl SpectatorWindow {
+    pub(crate) fn open(
+        event_loop: &ActiveEventLoop,
+        backend_type: BackendType,
+    ) -> Result<Self, String> {
+        let attributes = with_requested_window_size(
+            Window::default_attributes().with_title("DeadSync spectator"),
+            backend_type,
+            false,
+            WINDOW_SIZE.0,
+            WINDOW_SIZE.1,
+        );
+        let window = Arc::new(
+            event_loop
+                .create_window(attributes)
+                .map_err(|e| format!("failed to open the spectator window: {e}"))?,
+        );
+        let (frames_tx, frames_rx) = mpsc::sync_channel(1);
+        let (drawn_tx, drawn_rx) = mpsc::sync_channel(1);
+        let worker_window = Arc::clone(&window);
+        let worker = thread::Builder::new()
+            .name("spectator-render".to_string())
+            .spawn(move || run_worker(&worker_window, backend_type, &frames_rx, &drawn_tx))
+            .map_err(|e| format!("failed to start the spectator renderer: {e}"))?;
+        info!("Spectator window opened with {backend_type}");
+        let size = window.inner_size();
+        Ok(Self {
+            window,
+            frames: Some(frames_tx),
+            drawn: drawn_rx,
+            worker: Some(worker),
+            in_flight: false,
+            size,
+            metrics: space::metrics_for_window(size.width, size.height),
+            cursor: TextureShareCursor::default(),
+            actors: Vec::with_capacity(64),
+            text_layout_cache: TextLayoutCache::new(TEXT_LAYOUT_CACHE_LIMIT),
+            compose_scratch: ComposeScratch::default(),
+        })
+    }
+
+    /// The main backend was recreated and its texture ids started over, so
+    /// every texture goes out again.
+    pub(crate) fn reset_textures(&mut self) {
+        self.cursor = TextureShareCursor::default();
+        self.text_layout_cache.clear();
+    }
+
+    #[inline(always)]
+    pub(crate) fn window_id(&self) -> WindowId {
+        self.window.id()
+    }
+
+    pub(crate) fn resized(&mut self, size: PhysicalSize<u32>) {
+        self.size = size;
+        if size.width > 0 && size.height > 0 {
+            self.metrics = space::metrics_for_window(size.width, size.height);
+        }
+    }
+
+    /// Takes back a drawn frame, then returns the layout to compose into
+    /// `actors` when the worker is ready for another one. Fails once the
+    /// worker has stopped.
+    pub(crate) fn begin_frame(
+        &mut self,
+        gameplay_metrics: &Metrics,
+    ) -> Result<Option<SpectatorView>, WorkerStopped> {
+        match take_drawn(&self.drawn) {
+            Ok(Some(mut frame)) => {
+                self.compose_scratch.recycle_frame(&mut frame);
+                self.in_flight = false;
+            }
+            Ok(None) => {}
+            Err(stopped) => {
+                self.in_flight = false;
+                self.frames = None;
+                return Err(stopped);
+            }
+        }
+        if self.in_flight || self.size.width == 0 || self.size.height == 0 {
+            return Ok(None);
+        }
+        self.actors.clear();
+        Ok(Some(SpectatorView {
+            width: self.metrics.right - self.metrics.left,
+            gameplay_width: gameplay_metrics.right - gameplay_metrics.left,
+        }))
+    }
+
+    #[inline(always)]
+    pub(crate) fn actors_mut(&mut self) -> &mut Vec<Actor> {
+        &mut self.actors
+    }
+
+    /// Composes the queued actors, drawing `gameplay` into the notefield
+    /// target when given, and sends the frame off. `gameplay`'s buffers go
+    /// along with it; it is left holding empty ones for the caller to
+    /// recycle. Returns `false` once the worker has stopped.
+    pub(crate) fn submit(
+        &mut self,
+        gameplay: Option<&mut RenderFrame>,
+        backend: &mut Backend,
+        textures: &TextureHandleMap<Texture>,
+        fonts: &FontMap,
+        total_elapsed: f32,
+    ) -> bool {
+        let Some(frames) = &self.frames else {
+            return false;
+        };
+        let mut frame = compose::build_screen_cached_with_scratch_and_texture_context(
+            &self.actors,
+            CLEAR_COLOR,
+            &self.metrics,
+            fonts,
+            total_elapsed,
+            &mut self.text_layout_cache,
+            &mut self.compose_scratch,
+            &PRESENT_TEXTURE_CONTEXT,
+        );
+        self.actors.clear();
+        if let Some(source) = gameplay {
+            let (width, height) = space::current_window_px();
+            let pass = RenderTargetPass {
+                slot: GAMEPLAY_TARGET_SLOT,
+                width,
+                height,
+                clear: true,
+            };
+            if !frame.prepend_target(source, pass) {
+                warn!("Spectator frame skipped the notefield: too many cameras");
+            }
+        }
+        let mut shared = Vec::new();
+        backend.share_frame_textures(&frame, textures, &mut self.cursor, &mut shared);
+        let sent = frames.try_send(SpectatorFrame {
+            frame,
+            textures: shared,
+            size: self.size,
+        });
+        self.in_flight = sent.is_ok();
+        self.in_flight
+    }
+}
+
+/// A frame the worker finished drawing, if any. Fails once the worker has
+/// exited, so a frame still marked in flight never comes back.
+fn take_drawn(drawn: &Receiver<RenderFrame>) -> Result<Option<RenderFrame>, WorkerStopped> {
+    match drawn.try_recv() {
+        Ok(frame) => Ok(Some(frame)),
+        Err(TryRecvError::Empty) => Ok(None),
+        Err(TryRecvError::Disconnected) => Err(WorkerStopped),
+    }
+}
+
+impl Drop for SpectatorWindow {
+    fn drop(&mut self) {
+        self.frames.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn run_worker(
+    window: &Arc<Window>,
+    backend_type: BackendType,
+    frames: &Receiver<SpectatorFrame>,
+    drawn: &SyncSender<RenderFrame>,
+) {
+    let mut backend = match create_backend(
+        backend_type,
+        Arc::clone(window),
+        true,
+        PresentModePolicy::Mailbox,
+        false,
+        false,
+    ) {
+        Ok(backend) => backend,
+        Err(e) => {
+            warn!("Spectator renderer {backend_type} failed to start: {e}");
+            return;
+        }
+    };
+    let mut textures = TextureHandleMap::default();
+    let mut size = PhysicalSize::new(0, 0);
+    draw_frames(frames, drawn, |message| {
+        for shared in &message.textures {
+            match backend.create_texture(&shared.image, shared.sampler) {
+                Ok(texture) => {
+                    if let Some(old) = textures.insert(shared.handle, texture) {
+                        backend.retire_texture(old);
+                    }
+                }
+                Err(e) => warn!("Spectator texture {} failed to upload: {e}", shared.handle),
+            }
+        }
+        if size != message.size {
+            size = message.size;
+            backend.resize(size.width, size.height);
+            backend.set_default_projection(space::ortho_for_aspect(
+                size.width as f32 / size.height.max(1) as f32,
+            ));
+        }
+        backend
+            .draw(&message.frame, &textures, false)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    });
+    backend.dispose_textures(&mut textures);
+    backend.cleanup();
+}
+
+/// Draws each frame the game thread sends and hands it back, until the game
+/// thread hangs up or a draw fails.
+fn draw_frames(
+    frames: &Receiver<SpectatorFrame>,
+    drawn: &SyncSender<RenderFrame>,
+    mut draw: impl FnMut(&SpectatorFrame) -> Result<(), String>,
+) {
+    while let Ok(message) = frames.recv() {
+        if let Err(e) = draw(&message) {
+            warn!("Spectator renderer stopped: {e}");
+            break;
+        }
+        if drawn.send(message.frame).is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> SpectatorFrame {
+        SpectatorFrame {
+            frame: RenderFrame {
+                clear_color: CLEAR_COLOR,
+                cameras: Vec::new(),
+                sprite_instances: Vec::new(),
+                mesh_vertices: Vec::new(),
+                tmesh_instances: Vec::new(),
+                tmesh_geometries: Vec::new(),
+                ops: Vec::new(),
+            },
+            textures: Vec::new(),
+            size: PhysicalSize::new(WINDOW_SIZE.0, WINDOW_SIZE.1),
+        }
+    }
+
+    #[test]
+    fn failed_draw_stops_the_worker_and_reports_it() {
+        let (frames_tx, frames_rx) = mpsc::sync_channel(1);
+        let (drawn_tx, drawn_rx) = mpsc::sync_channel(1);
+        let worker = thread::spawn(move || {
+            let mut draws = 0;
+            draw_frames(&frames_rx, &drawn_tx, |_| {
+                draws += 1;
+                if draws == 1 {
+                    Ok(())
+                } else {
+                    Err("device lost".to_string())
+                }
+            });
+        });
+
+        assert!(matches!(take_drawn(&drawn_rx), Ok(None)));
+        frames_tx.send(message()).unwrap();
+        drawn_rx.recv().expect("the first frame comes back drawn");
+        frames_tx.send(message()).unwrap();
+        worker.join().unwrap();
+
+        assert_eq!(take_drawn(&drawn_rx).err(), Some(WorkerStopped));
+        assert!(frames_tx.try_send(message()).is_err());
+    }
+}
@@ -0,0 +1,198 @@
+//! Shell-owned workout sessions.
+//!
+//! A session belongs to one player side and lives until it is ended from
+//! Select Music or the side's profile changes. It is written to the
+//! profile's day log after every song, so a crash loses at most the song
+//! in progress. Guests can run a session; it just is not saved.
+//!
+//! The weekly summary reads every day log, so it is built on a worker.
+
+use crate::one_shot::OneShotService;
+use deadsync_profile::PlayerSide;
+use deadsync_profile::compat as profile;
+use deadsync_score::{
+    WorkoutGoal, WorkoutSession, load_workout_days, save_workout_session, weekly_workout_summaries,
+};
+use deadsync_theme_simply_love::SimplyLoveWorkoutSummaryResult;
+use deadsync_theme_simply_love::screens::components::select_music::select_music_menu::WorkoutProgressView;
+use deadsync_theme_simply_love::screens::select_music;
+use std::time::{Duration, Instant};
+
+const HEART_RATE_SAMPLE_INTERVAL: Duration =
+    Duration::from_millis(deadsync_score::WORKOUT_HEART_RATE_INTERVAL_MS as u64);
+
+struct ActiveWorkout {
+    profile_id: Option<String>,
+    day: chrono::NaiveDate,
+    started: Instant,
+    session: WorkoutSession,
+}
+
+impl ActiveWorkout {
+    fn save(&self) {
+        let Some(profile_id) = self.profile_id.as_deref() else {
+            return;
+        };
+        let dir = profile::score_profile_paths_for_id(profile_id).workouts_dir();
+        if let Err(e) = save_workout_session(&dir, self.day, &self.session) {
+            log::warn!("Failed to save workout for profile {profile_id}: {e}");
+        }
+    }
+
+    /// Saves a session that has anything to keep, e.g. heart-rate samples
+    /// taken since its last song.
+    fn save_if_started(&self) {
+        if self.session.songs > 0 || !self.session.heart_rate.is_empty() {
+            self.save();
+        }
+    }
+
+    fn view(&self) -> WorkoutProgressView {
+        WorkoutProgressView {
+            goal: self.session.goal,
+            amount: self.session.goal_amount(),
+            progress: self.session.progress(),
+            songs: self.session.songs,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Runtime {
+    sides: [Option<ActiveWorkout>; 2],
+    last_sample: Option<Instant>,
+    /// Bumped whenever a session starts, ends or finishes a song.
+    revision: u64,
+    select_music_view_key: Option<(u64, u64)>,
+    summary: OneShotService<SimplyLoveWorkoutSummaryResult>,
+}
+
+impl Runtime {
+    /// Replaces any session already running on `side`; the old one was saved
+    /// after its last song.
+    pub(crate) fn start(&mut self, side: PlayerSide, goal: WorkoutGoal) {
+        let now = chrono::Local::now();
+        self.end(side);
+        self.sides[deadsync_profile::player_side_index(side)] = Some(ActiveWorkout {
+            profile_id: profile::active_local_profile_id_for_side(side),
+            day: now.date_naive(),
+            started: Instant::now(),
+            session: WorkoutSession::new(now.timestamp_millis(), goal),
+        });
+        self.revision += 1;
+    }
+
+    pub(crate) fn end(&mut self, side: PlayerSide) {
+        let Some(workout) = self.sides[deadsync_profile::player_side_index(side)].take() else {
+            return;
+        };
+        workout.save_if_started();
+        self.revision += 1;
+    }
+
+    pub(crate) fn record_stage(
+        &mut self,
+        side: PlayerSide,
+        calories: f32,
+        seconds: f32,
+        steps: u32,
+    ) {
+        self.drop_stale(side);
+        let Some(workout) = self.sides[deadsync_profile::player_side_index(side)].as_mut() else {
+            return;
+        };
+        workout.session.add_stage(calories, seconds, steps);
+        workout.save();
+        self.revision += 1;
+    }
+
+    /// Takes at most one sample per interval, and only from sensors that are
+    /// configured and connected.
+    pub(crate) fn sample_heart_rate(&mut self, now: Instant) {
+        if self.sides.iter().all(Option::is_none)
+            || self
+                .last_sample
+                .is_some_and(|last| now.duration_since(last) < HEART_RATE_SAMPLE_INTERVAL)
+        {
+            return;
+        }
+        self.last_sample = Some(now);
+        let readings = deadsync_heart_rate::player_readings();
+        for (workout, reading) in self.sides.iter_mut().zip(readings) {
+            let (Some(workout), Some(bpm)) = (workout.as_mut(), reading.bpm) else {
+                continue;
+            };
+            if !reading.configured || !reading.connected {
+                continue;
+            }
+            let elapsed_ms = now
+                .duration_since(workout.started)
+                .as_millis()
+                .min(u128::from(u32::MAX)) as u32;
+            workout.session.push_heart_rate(elapsed_ms, bpm);
+        }
+    }
+
+    /// Unchanged frames cost two integer compares; profile lookups only run
+    /// after a session or the profile store changed.
+    pub(crate) fn refresh_select_music(&mut self, state: &mut select_music::State) -> bool {
+        let key = (
+            self.revision,
+            deadsync_profile::runtime_profile_generation(),
+        );
+        if self.select_music_view_key == Some(key) {
+            return false;
+        }
+        self.drop_stale(PlayerSide::P1);
+        self.drop_stale(PlayerSide::P2);
+        let view = std::array::from_fn(|idx| self.sides[idx].as_ref().map(ActiveWorkout::view));
+        self.select_music_view_key = Some((self.revision, key.1));
+        select_music::set_workout_view(state, view);
+        true
+    }
+
+    /// Called after the Select Music state is rebuilt from scratch.
+    pub(crate) fn invalidate_select_music(&mut self) {
+        self.select_music_view_key = None;
+    }
+
+    /// A session never carries over to whoever takes the side next.
+    fn drop_stale(&mut self, side: PlayerSide) {
+        let idx = deadsync_profile::player_side_index(side);
+        let Some(workout) = self.sides[idx].as_ref() else {
+            return;
+        };
+        if workout.profile_id != profile::active_local_profile_id_for_side(side) {
+            workout.save_if_started();
+            self.sides[idx] = None;
+            self.revision += 1;
+        }
+    }
+
+    /// Builds `profile_id`'s weekly summary on a worker; replaces one still
+    /// being built.
+    pub(crate) fn load_summary(&mut self, profile_id: String) {
+        let stopped_profile_id = profile_id.clone();
+        self.summary.start(
+            "workout-summary",
+            move |_| Some(load_summary(profile_id)),
+            move || SimplyLoveWorkoutSummaryResult {
+                profile_id: stopped_profile_id,
+                weeks: Err("workout summary worker stopped".to_string()),
+            },
+        );
+    }
+
+    pub(crate) fn poll_summary(&mut self) -> Option<SimplyLoveWorkoutSummaryResult> {
+        self.summary.poll()
+    }
+}
+
+fn load_summary(profile_id: String) -> SimplyLoveWorkoutSummaryResult {
+    let dir = profile::score_profile_paths_for_id(&profile_id).workouts_dir();
+    let days = load_workout_days(&dir);
+    SimplyLoveWorkoutSummaryResult {
+        profile_id,
+        weeks: Ok(weekly_workout_summaries(&days)),
+    }
+}
@@ -3,6 +3,7 @@ use crate::cache::{
     RuntimeSongLoadLogEntry, RuntimeSongLoadLogLevel,
 };
 use crate::course::runtime_course_scan_log_entry;
+use crate::lint::PackLintReport;
 use crate::media::{
     BG_ANIMATIONS_DIR, RANDOM_MOVIES_DIR, SONG_MOVIES_DIR, collect_media_roots,
     random_movie_paths_for_song,
@@ -25,8 +26,10 @@ use deadsync_chart::{
     GameplayChartData, SongBackgroundChange, SongData, background::expand_random_background_changes,
 };
 use log::{debug, info, warn};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 pub fn scan_and_load_songs_with_progress_counts<F>(root_path: &Path, progress: &mut F)
 where
@@ -292,6 +295,46 @@ pub fn parse_song_for_test(path: &Path, global_offset_seconds: f32) -> Result<So
     )
 }
 
+/// Lints a pack, decoding each song's music for its length.
+pub fn lint_pack(
+    pack_dir: &Path,
+    known_hashes: &mut HashMap<String, String>,
+    cancel: &AtomicBool,
+) -> Option<PackLintReport> {
+    crate::lint::lint_pack(pack_dir, known_hashes, compute_music_length_seconds, cancel)
+}
+
+/// Parses every song in `pack_dir` without decoding its music; `None` once
+/// `cancel` is set. Simfiles that fail to scan or parse are returned beside
+/// the songs.
+pub fn parse_pack_songs(
+    pack_dir: &Path,
+    cancel: &AtomicBool,
+) -> Option<(Vec<SongData>, Vec<(PathBuf, String)>)> {
+    let (packs, failures) = crate::scan::scan_pack_dirs(&[pack_dir.to_path_buf()], &[]);
+    let mut failures: Vec<_> = failures
+        .into_iter()
+        .map(|failure| (failure.path, failure.error))
+        .collect();
+    let options = parse_song_options();
+    let mut songs = Vec::new();
+    for song_scan in packs.iter().flat_map(|pack| &pack.songs) {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        match crate::song::parse_song_meta_file(&song_scan.simfile, &options, 0.0, |_| 0.0) {
+            Ok(song) => songs.push(song),
+            Err(e) => failures.push((song_scan.simfile.clone(), e)),
+        }
+    }
+    Some((songs, failures))
+}
+
+/// Decodes `song`'s music for its length, for songs parsed without it.
+pub fn song_music_length_seconds(song: &SongData) -> f32 {
+    compute_music_length_seconds(song.music_path.as_deref())
+}
+
 fn bgchange_asset_roots(dirname: &str) -> Vec<PathBuf> {
     let dirs = dirs::app_dirs();
     let cwd = std::env::current_dir().ok();
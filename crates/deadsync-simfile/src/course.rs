@@ -760,7 +760,7 @@ where
         count_course_songs(packs),
         progress,
     );
-    let autogen_courses = autogen_nonstop_group_courses(autogen_courses_root, packs);
+    let autogen_courses = autogen_group_courses(autogen_courses_root, packs);
     let autogen_count = autogen_courses.len();
     report.courses.extend(autogen_courses);
     CourseScanReport {
@@ -822,11 +822,46 @@ pub fn scan_and_load_courses_runtime<F>(
     });
 }
 
-pub fn autogen_nonstop_group_courses(
+/// Stages in each generated random course; Endless repeats them.
+const AUTOGEN_STAGE_COUNT: usize = 4;
+/// Seconds a generated Survival course grants at the start of each stage.
+const AUTOGEN_SURVIVAL_GAIN_SECONDS: f32 = 60.0;
+
+#[derive(Clone, Copy)]
+struct AutogenCourseKind {
+    file_name: &'static str,
+    name_suffix: &'static str,
+    repeat: bool,
+    gain_seconds: f32,
+}
+
+const AUTOGEN_COURSE_KINDS: [AutogenCourseKind; 3] = [
+    AutogenCourseKind {
+        file_name: "__deadsync_autogen_nonstop_random.crs",
+        name_suffix: "Random",
+        repeat: false,
+        gain_seconds: 0.0,
+    },
+    AutogenCourseKind {
+        file_name: "__deadsync_autogen_endless_random.crs",
+        name_suffix: "Endless",
+        repeat: true,
+        gain_seconds: 0.0,
+    },
+    AutogenCourseKind {
+        file_name: "__deadsync_autogen_survival_random.crs",
+        name_suffix: "Survival",
+        repeat: false,
+        gain_seconds: AUTOGEN_SURVIVAL_GAIN_SECONDS,
+    },
+];
+
+/// Builds the random Nonstop, Endless and Survival courses for every pack.
+pub fn autogen_group_courses(
     courses_root: &Path,
     packs: &[SongPack],
 ) -> Vec<(PathBuf, CourseFile)> {
-    let mut out = Vec::with_capacity(packs.len());
+    let mut out = Vec::with_capacity(packs.len() * AUTOGEN_COURSE_KINDS.len());
 
     for pack in packs {
         if pack.songs.is_empty() {
@@ -842,42 +877,43 @@ pub fn autogen_nonstop_group_courses(
         } else {
             pack.name.trim()
         };
-
-        let entries = (0..4)
-            .map(|_| CourseEntry {
-                song: CourseSong::RandomWithinGroup {
-                    group: group_name.to_string(),
+        let banner = pack
+            .banner_path
+            .as_ref()
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        for kind in AUTOGEN_COURSE_KINDS {
+            let entries = (0..AUTOGEN_STAGE_COUNT)
+                .map(|_| CourseEntry {
+                    song: CourseSong::RandomWithinGroup {
+                        group: group_name.to_string(),
+                    },
+                    steps: StepsSpec::Difficulty(Difficulty::Medium),
+                    modifiers: String::new(),
+                    secret: true,
+                    no_difficult: false,
+                    gain_seconds: kind.gain_seconds,
+                    gain_lives: -1,
+                })
+                .collect();
+
+            out.push((
+                courses_root.join(group_name).join(kind.file_name),
+                CourseFile {
+                    name: format!("{display_name} {}", kind.name_suffix),
+                    name_translit: String::new(),
+                    scripter: "Autogen".to_string(),
+                    description: String::new(),
+                    banner: banner.clone(),
+                    background: String::new(),
+                    repeat: kind.repeat,
+                    lives: -1,
+                    meters: [None; 6],
+                    entries,
                 },
-                steps: StepsSpec::Difficulty(Difficulty::Medium),
-                modifiers: String::new(),
-                secret: true,
-                no_difficult: false,
-                gain_seconds: 0.0,
-                gain_lives: -1,
-            })
-            .collect();
-
-        out.push((
-            courses_root
-                .join(group_name)
-                .join("__deadsync_autogen_nonstop_random.crs"),
-            CourseFile {
-                name: format!("{display_name} Random"),
-                name_translit: String::new(),
-                scripter: "Autogen".to_string(),
-                description: String::new(),
-                banner: pack
-                    .banner_path
-                    .as_ref()
-                    .map(|p| p.to_string_lossy().into_owned())
-                    .unwrap_or_default(),
-                background: String::new(),
-                repeat: false,
-                lives: -1,
-                meters: [None; 6],
-                entries,
-            },
-        ));
+            ));
+        }
     }
 
     out
@@ -1298,6 +1334,8 @@ mod tests {
             folder_series: String::new(),
             year: 0,
             sync_pref: SyncPref::Default,
+            sync_bias_ms: 0.0,
+            sync_bias_written_ms: Vec::new(),
             directory: PathBuf::new(),
             banner_path: Some(PathBuf::from("banner.png")),
             songs: (0..songs).map(|_| Arc::new(test_song())).collect(),
@@ -1305,40 +1343,58 @@ mod tests {
     }
 
     #[test]
-    fn autogen_nonstop_group_courses_builds_random_medium_course() {
+    fn autogen_group_courses_builds_random_medium_courses() {
         let courses_root = PathBuf::from("courses");
-        let courses =
-            autogen_nonstop_group_courses(&courses_root, &[song_pack("Pack", "Display", 2)]);
+        let courses = autogen_group_courses(&courses_root, &[song_pack("Pack", "Display", 2)]);
 
-        assert_eq!(courses.len(), 1);
+        assert_eq!(courses.len(), 3);
         assert_eq!(
             courses[0].0,
             courses_root
                 .join("Pack")
                 .join("__deadsync_autogen_nonstop_random.crs")
         );
-        let course = &courses[0].1;
-        assert_eq!(course.name, "Display Random");
-        assert_eq!(course.scripter, "Autogen");
-        assert_eq!(course.banner, "banner.png");
-        assert_eq!(course.entries.len(), 4);
-        for entry in &course.entries {
-            assert!(matches!(
-                &entry.song,
-                CourseSong::RandomWithinGroup { group } if group == "Pack"
-            ));
-            assert!(matches!(
-                entry.steps,
-                StepsSpec::Difficulty(Difficulty::Medium)
-            ));
-            assert!(entry.secret);
-            assert_eq!(entry.gain_lives, -1);
+        for (_, course) in &courses {
+            assert_eq!(course.scripter, "Autogen");
+            assert_eq!(course.banner, "banner.png");
+            assert_eq!(course.entries.len(), 4);
+            for entry in &course.entries {
+                assert!(matches!(
+                    &entry.song,
+                    CourseSong::RandomWithinGroup { group } if group == "Pack"
+                ));
+                assert!(matches!(
+                    entry.steps,
+                    StepsSpec::Difficulty(Difficulty::Medium)
+                ));
+                assert!(entry.secret);
+                assert_eq!(entry.gain_lives, -1);
+            }
         }
+        let kinds: Vec<_> = courses
+            .iter()
+            .map(|(_, course)| (course.name.as_str(), course_type(course)))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("Display Random", CourseType::Nonstop),
+                ("Display Endless", CourseType::Endless),
+                ("Display Survival", CourseType::Survival),
+            ]
+        );
+        assert!(
+            courses[2]
+                .1
+                .entries
+                .iter()
+                .all(|entry| entry.gain_seconds == AUTOGEN_SURVIVAL_GAIN_SECONDS)
+        );
     }
 
     #[test]
-    fn autogen_nonstop_group_courses_skips_empty_or_unnamed_packs() {
-        let courses = autogen_nonstop_group_courses(
+    fn autogen_group_courses_skips_empty_or_unnamed_packs() {
+        let courses = autogen_group_courses(
             Path::new("courses"),
             &[
                 song_pack("Empty", "Empty", 0),
@@ -1347,7 +1403,7 @@ mod tests {
             ],
         );
 
-        assert_eq!(courses.len(), 1);
+        assert_eq!(courses.len(), 3);
         assert_eq!(courses[0].1.name, "Valid Random");
     }
 
@@ -1590,8 +1646,8 @@ mod tests {
         );
 
         assert_eq!(report.failures, Vec::new());
-        assert_eq!(report.autogen_count, 1);
-        assert_eq!(report.courses.len(), 2);
+        assert_eq!(report.autogen_count, 3);
+        assert_eq!(report.courses.len(), 4);
         assert_eq!(report.courses[0].0, course);
         assert_eq!(report.courses[0].1.name, "Base");
         assert_eq!(report.courses[1].1.name, "Display Random");
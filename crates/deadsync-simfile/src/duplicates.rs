@@ -0,0 +1,506 @@
+//! Duplicate songs across packs and song roots.
+//!
+//! Two songs are copies of each other when they share a chart hash, or when
+//! the caller confirms their audio is the same. Audio is only compared for
+//! songs whose music is the same length or whose titles match, whatever their
+//! charts, so a renamed copy with edited charts is still found. Copies
+//! are never deleted: the player can hide a copy from the wheel or prefer one
+//! copy of a group, which hides the others. Those choices are kept per song
+//! directory in `save/duplicate_songs.txt`.
+
+use deadlib_platform::dirs;
+use deadsync_chart::{SongData, SongPack};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+/// One song in the library, as seen by duplicate detection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DuplicateCopy {
+    pub simfile_path: PathBuf,
+    pub pack_dir: PathBuf,
+    pub pack_name: String,
+    pub title: String,
+    pub music_path: Option<PathBuf>,
+    /// Decoded music length; 0 when unknown.
+    pub music_seconds: f32,
+    pub chart_hashes: Vec<String>,
+}
+
+/// Music lengths this close are compared by audio. Re-encoding a song can
+/// shift its decoded length by a few frames of padding.
+const AUDIO_LENGTH_TOLERANCE_SECONDS: f32 = 0.05;
+
+impl DuplicateCopy {
+    pub fn from_song(pack: &SongPack, song: &SongData) -> Self {
+        Self {
+            simfile_path: song.simfile_path.clone(),
+            pack_dir: pack.directory.clone(),
+            pack_name: pack.name.clone(),
+            title: song.title.clone(),
+            music_path: song.music_path.clone(),
+            music_seconds: song.music_length_seconds,
+            chart_hashes: song
+                .charts
+                .iter()
+                .filter(|chart| !chart.short_hash.is_empty())
+                .map(|chart| chart.short_hash.clone())
+                .collect(),
+        }
+    }
+}
+
+pub fn library_copies(packs: &[SongPack]) -> Vec<DuplicateCopy> {
+    packs
+        .iter()
+        .flat_map(|pack| {
+            pack.songs
+                .iter()
+                .map(move |song| DuplicateCopy::from_song(pack, song))
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DuplicateGroup {
+    /// Sorted by pack name.
+    pub copies: Vec<DuplicateCopy>,
+    /// Some copies were grouped because they share a chart.
+    pub same_charts: bool,
+    /// Some copies were grouped because their audio matched.
+    pub same_audio: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DuplicateReport {
+    pub songs_checked: usize,
+    /// Sorted by title.
+    pub groups: Vec<DuplicateGroup>,
+}
+
+/// Title key for audio candidates: case, spacing and punctuation ignored.
+fn title_key(title: &str) -> String {
+    title
+        .chars()
+        .filter(|ch| ch.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn find_root(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+/// Pairs worth an audio comparison: same title, or music of the same length.
+fn audio_candidates(copies: &[DuplicateCopy]) -> BTreeSet<(usize, usize)> {
+    let mut pairs = BTreeSet::new();
+    let mut by_title: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, copy) in copies.iter().enumerate() {
+        let key = title_key(&copy.title);
+        if !key.is_empty() {
+            by_title.entry(key).or_default().push(index);
+        }
+    }
+    for indices in by_title.values() {
+        for (pos, &index) in indices.iter().enumerate() {
+            pairs.extend(indices[..pos].iter().map(|&other| (other, index)));
+        }
+    }
+
+    let mut by_length: Vec<usize> = (0..copies.len())
+        .filter(|&index| copies[index].music_path.is_some() && copies[index].music_seconds > 0.0)
+        .collect();
+    by_length.sort_by(|&a, &b| copies[a].music_seconds.total_cmp(&copies[b].music_seconds));
+    for (pos, &index) in by_length.iter().enumerate() {
+        let seconds = copies[index].music_seconds;
+        for &other in &by_length[pos + 1..] {
+            if copies[other].music_seconds - seconds > AUDIO_LENGTH_TOLERANCE_SECONDS {
+                break;
+            }
+            pairs.insert((index.min(other), index.max(other)));
+        }
+    }
+    pairs
+}
+
+/// Groups copies from different packs. `same_audio` is only asked about
+/// candidate pairs (same title or music length) that no shared chart has
+/// already grouped, so the caller can decode music lazily. Returns `None`
+/// once `cancel` is set.
+pub fn find_duplicates(
+    copies: &[DuplicateCopy],
+    mut same_audio: impl FnMut(&DuplicateCopy, &DuplicateCopy) -> bool,
+    cancel: &AtomicBool,
+) -> Option<DuplicateReport> {
+    let mut parents: Vec<usize> = (0..copies.len()).collect();
+    let mut by_charts = vec![false; copies.len()];
+    let mut by_audio = vec![false; copies.len()];
+
+    let mut by_hash: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, copy) in copies.iter().enumerate() {
+        for hash in &copy.chart_hashes {
+            by_hash.entry(hash.as_str()).or_default().push(index);
+        }
+    }
+    for indices in by_hash.values() {
+        for (pos, &index) in indices.iter().enumerate() {
+            let Some(&other) = indices[..pos]
+                .iter()
+                .find(|&&other| copies[other].pack_dir != copies[index].pack_dir)
+            else {
+                continue;
+            };
+            let (a, b) = (
+                find_root(&mut parents, index),
+                find_root(&mut parents, other),
+            );
+            parents[a] = b;
+            by_charts[index] = true;
+            by_charts[other] = true;
+        }
+    }
+
+    for (other, index) in audio_candidates(copies) {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        if copies[other].pack_dir == copies[index].pack_dir {
+            continue;
+        }
+        let (a, b) = (
+            find_root(&mut parents, index),
+            find_root(&mut parents, other),
+        );
+        if a == b || !same_audio(&copies[other], &copies[index]) {
+            continue;
+        }
+        parents[a] = b;
+        by_audio[index] = true;
+        by_audio[other] = true;
+    }
+
+    let mut groups: BTreeMap<usize, DuplicateGroup> = BTreeMap::new();
+    for index in 0..copies.len() {
+        let root = find_root(&mut parents, index);
+        let group = groups.entry(root).or_default();
+        group.copies.push(copies[index].clone());
+        group.same_charts |= by_charts[index];
+        group.same_audio |= by_audio[index];
+    }
+    let mut groups: Vec<DuplicateGroup> = groups
+        .into_values()
+        .filter(|group| group.copies.len() > 1)
+        .collect();
+    for group in &mut groups {
+        group
+            .copies
+            .sort_by_cached_key(|copy| copy.pack_name.to_lowercase());
+    }
+    groups.sort_by_cached_key(|group| group.copies[0].title.to_lowercase());
+    Some(DuplicateReport {
+        songs_checked: copies.len(),
+        groups,
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DuplicateChoice {
+    Hidden,
+    Preferred,
+}
+
+impl DuplicateChoice {
+    const fn key(self) -> &'static str {
+        match self {
+            Self::Hidden => "hidden",
+            Self::Preferred => "preferred",
+        }
+    }
+}
+
+/// Hide/prefer choices keyed by song directory, so they survive a simfile
+/// switching between `.sm` and `.ssc`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DuplicateChoices {
+    by_song_dir: BTreeMap<PathBuf, DuplicateChoice>,
+}
+
+fn song_dir(simfile_path: &Path) -> &Path {
+    simfile_path.parent().unwrap_or(simfile_path)
+}
+
+impl DuplicateChoices {
+    pub fn get(&self, simfile_path: &Path) -> Option<DuplicateChoice> {
+        self.by_song_dir.get(song_dir(simfile_path)).copied()
+    }
+
+    #[inline(always)]
+    pub fn is_hidden(&self, simfile_path: &Path) -> bool {
+        self.get(simfile_path) == Some(DuplicateChoice::Hidden)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_song_dir.is_empty()
+    }
+
+    fn set(&mut self, simfile_path: &Path, choice: Option<DuplicateChoice>) {
+        let dir = song_dir(simfile_path).to_path_buf();
+        match choice {
+            Some(choice) => {
+                self.by_song_dir.insert(dir, choice);
+            }
+            None => {
+                self.by_song_dir.remove(&dir);
+            }
+        }
+    }
+
+    /// Hides a shown copy or shows a hidden one.
+    pub fn toggle_hidden(&mut self, simfile_path: &Path) {
+        let next = if self.is_hidden(simfile_path) {
+            None
+        } else {
+            Some(DuplicateChoice::Hidden)
+        };
+        self.set(simfile_path, next);
+    }
+
+    /// Prefers `group.copies[index]` and hides the rest of the group. Doing so
+    /// for the copy that is already preferred clears the group's choices.
+    pub fn toggle_preferred(&mut self, group: &DuplicateGroup, index: usize) {
+        let Some(chosen) = group.copies.get(index) else {
+            return;
+        };
+        let clear = self.get(&chosen.simfile_path) == Some(DuplicateChoice::Preferred);
+        for (ix, copy) in group.copies.iter().enumerate() {
+            let choice = if clear {
+                None
+            } else if ix == index {
+                Some(DuplicateChoice::Preferred)
+            } else {
+                Some(DuplicateChoice::Hidden)
+            };
+            self.set(&copy.simfile_path, choice);
+        }
+    }
+
+    /// Drops choices for library songs that no longer have a copy, so a song
+    /// whose other copies were removed comes back to the wheel. Songs outside
+    /// `library` keep their choices in case their root is just unavailable.
+    pub fn forget_ungrouped(&mut self, library: &[DuplicateCopy], report: &DuplicateReport) {
+        let grouped: Vec<&Path> = report
+            .groups
+            .iter()
+            .flat_map(|group| &group.copies)
+            .map(|copy| song_dir(&copy.simfile_path))
+            .collect();
+        for copy in library {
+            let dir = song_dir(&copy.simfile_path);
+            if !grouped.contains(&dir) {
+                self.by_song_dir.remove(dir);
+            }
+        }
+    }
+
+    /// Lines are `<hidden|preferred>\t<song directory>`.
+    fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (dir, choice) in &self.by_song_dir {
+            text.push_str(&format!("{}\t{}\n", choice.key(), dir.display()));
+        }
+        text
+    }
+
+    fn from_text(text: &str) -> Self {
+        let mut choices = Self::default();
+        for line in text.lines() {
+            let Some((key, dir)) = line.split_once('\t') else {
+                continue;
+            };
+            let choice = match key.trim() {
+                "hidden" => DuplicateChoice::Hidden,
+                "preferred" => DuplicateChoice::Preferred,
+                _ => continue,
+            };
+            if !dir.trim().is_empty() {
+                choices
+                    .by_song_dir
+                    .insert(PathBuf::from(dir.trim()), choice);
+            }
+        }
+        choices
+    }
+}
+
+pub fn load_duplicate_choices(path: &Path) -> DuplicateChoices {
+    std::fs::read_to_string(path)
+        .map(|text| DuplicateChoices::from_text(&text))
+        .unwrap_or_default()
+}
+
+/// Writes the choices file, or removes it when nothing is chosen.
+pub fn save_duplicate_choices(path: &Path, choices: &DuplicateChoices) -> Result<(), String> {
+    if choices.is_empty() {
+        return match std::fs::remove_file(path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Failed to remove '{}': {e}", path.display())),
+        };
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create '{}': {e}", parent.display()))?;
+    }
+    std::fs::write(path, choices.to_text())
+        .map_err(|e| format!("Failed to write '{}': {e}", path.display()))
+}
+
+static DUPLICATE_CHOICES: LazyLock<Mutex<Option<DuplicateChoices>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// The saved choices, read from disk on first use.
+pub fn duplicate_choices() -> DuplicateChoices {
+    let mut cached = DUPLICATE_CHOICES.lock().unwrap();
+    cached
+        .get_or_insert_with(|| load_duplicate_choices(&dirs::app_dirs().duplicate_songs_path()))
+        .clone()
+}
+
+pub fn set_duplicate_choices(choices: DuplicateChoices) -> Result<(), String> {
+    save_duplicate_choices(&dirs::app_dirs().duplicate_songs_path(), &choices)?;
+    *DUPLICATE_CHOICES.lock().unwrap() = Some(choices);
+    Ok(())
+}
+
+/// `packs` without hidden copies; packs left empty are dropped.
+pub fn without_hidden_copies(
+    mut packs: Vec<SongPack>,
+    choices: &DuplicateChoices,
+) -> Vec<SongPack> {
+    if choices.is_empty() {
+        return packs;
+    }
+    for pack in &mut packs {
+        pack.songs
+            .retain(|song| !choices.is_hidden(&song.simfile_path));
+    }
+    packs.retain(|pack| !pack.songs.is_empty());
+    packs
+}
+
+/// The loaded library as the wheel shows it.
+pub fn visible_song_packs() -> Vec<SongPack> {
+    let packs = crate::runtime_cache::get_song_cache().clone();
+    without_hidden_copies(packs, &duplicate_choices())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn copy(pack: &str, title: &str, hashes: &[&str]) -> DuplicateCopy {
+        DuplicateCopy {
+            simfile_path: PathBuf::from(format!("Songs/{pack}/{title}/song.ssc")),
+            pack_dir: PathBuf::from(format!("Songs/{pack}")),
+            pack_name: pack.to_string(),
+            title: title.to_string(),
+            music_path: None,
+            music_seconds: 0.0,
+            chart_hashes: hashes.iter().map(|hash| (*hash).to_string()).collect(),
+        }
+    }
+
+    fn with_music(mut copy: DuplicateCopy, seconds: f32) -> DuplicateCopy {
+        copy.music_path = Some(copy.simfile_path.with_extension("ogg"));
+        copy.music_seconds = seconds;
+        copy
+    }
+
+    #[test]
+    fn copies_group_by_chart_hash_or_matching_audio() {
+        let library = [
+            copy("B", "Song", &["aaa"]),
+            copy("A", "SONG", &["aaa", "bbb"]),
+            copy("C", "song!", &["ccc"]),
+            copy("D", "Song", &["ddd"]),
+            copy("A", "Other", &["eee"]),
+            copy("A", "Other", &["fff"]),
+        ];
+        let never = AtomicBool::new(false);
+        let mut asked = Vec::new();
+        let report = find_duplicates(
+            &library,
+            |a, b| {
+                asked.push((a.pack_name.clone(), b.pack_name.clone()));
+                a.pack_name != "D" && b.pack_name != "D"
+            },
+            &never,
+        )
+        .expect("not cancelled");
+        assert_eq!(report.songs_checked, 6);
+        assert_eq!(report.groups.len(), 1);
+        let group = &report.groups[0];
+        let packs: Vec<&str> = group.copies.iter().map(|c| c.pack_name.as_str()).collect();
+        assert_eq!(packs, ["A", "B", "C"]);
+        assert!(group.same_charts && group.same_audio);
+        // Same-pack songs and copies already grouped by chart are not decoded.
+        assert!(!asked.iter().any(|(a, b)| a == "A" && b == "A"));
+        assert!(
+            !asked
+                .iter()
+                .any(|pair| *pair == ("A".to_string(), "B".to_string())
+                    || *pair == ("B".to_string(), "A".to_string()))
+        );
+    }
+
+    #[test]
+    fn renamed_copies_are_compared_by_music_length() {
+        let library = [
+            with_music(copy("A", "Song", &["aaa"]), 120.0),
+            with_music(copy("B", "Song (Remix Edit)", &["bbb"]), 120.03),
+            with_music(copy("C", "Unrelated", &["ccc"]), 95.0),
+        ];
+        let never = AtomicBool::new(false);
+        let mut asked = Vec::new();
+        let report = find_duplicates(
+            &library,
+            |a, b| {
+                asked.push((a.pack_name.clone(), b.pack_name.clone()));
+                true
+            },
+            &never,
+        )
+        .expect("not cancelled");
+        assert_eq!(asked, [("A".to_string(), "B".to_string())]);
+        assert_eq!(report.groups.len(), 1);
+        assert!(report.groups[0].same_audio && !report.groups[0].same_charts);
+    }
+
+    #[test]
+    fn preferring_a_copy_hides_the_rest_and_toggles_back() {
+        let group = DuplicateGroup {
+            copies: vec![copy("A", "Song", &[]), copy("B", "Song", &[])],
+            same_charts: true,
+            same_audio: false,
+        };
+        let mut choices = DuplicateChoices::default();
+        choices.toggle_preferred(&group, 1);
+        assert!(choices.is_hidden(&group.copies[0].simfile_path));
+        assert_eq!(
+            choices.get(&group.copies[1].simfile_path),
+            Some(DuplicateChoice::Preferred)
+        );
+        assert_eq!(DuplicateChoices::from_text(&choices.to_text()), choices);
+        choices.toggle_preferred(&group, 1);
+        assert!(choices.is_empty());
+        choices.toggle_hidden(&group.copies[0].simfile_path);
+        assert!(choices.is_hidden(&group.copies[0].simfile_path));
+        choices.forget_ungrouped(&group.copies, &DuplicateReport::default());
+        assert!(choices.is_empty());
+    }
+}
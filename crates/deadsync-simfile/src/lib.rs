@@ -5,15 +5,19 @@ pub mod bpm;
 pub mod cache;
 pub mod changes;
 pub mod course;
+pub mod duplicates;
 pub mod event_intro;
+pub mod lint;
 pub mod matrix;
 pub mod media;
 pub mod notes;
+pub mod pack_sync;
 pub mod playlist;
 pub mod runtime;
 pub mod runtime_cache;
 pub mod scan;
 pub mod song;
+pub mod song_query;
 pub mod song_search;
 pub mod song_sort;
 pub mod stats;
@@ -0,0 +1,649 @@
+//! Pack linting: chart and simfile problems a pack author should fix.
+//!
+//! Findings come from the parsed song plus its raw simfile text. The parser
+//! resolves `#MUSIC` and `#BANNER` ITG-style and drops the tags, so a tag that
+//! only loads through the fallback would otherwise go unnoticed.
+
+use crate::cache::{
+    CachedChartDisplayBpm, CachedNoteType, SerializableChartData, SerializableSongData,
+    parse_chart_display_bpm,
+};
+use crate::scan::scan_pack_dirs;
+use crate::song::{ParseSongOptions, parse_song_data_file};
+use deadsync_rules::timing::{TimingData, TimingSegments};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// How far a meter may stray from the stream rating before it is flagged.
+pub const LINT_METER_TOLERANCE: f64 = 3.0;
+/// A higher-meter chart whose peak NPS is below this share of an easier
+/// chart's peak is flagged as out of order.
+pub const LINT_NPS_ORDER_RATIO: f64 = 0.8;
+/// Stops shorter than one 60 Hz frame cannot be seen or felt.
+pub const LINT_FRAME_SECONDS: f32 = 1.0 / 60.0;
+pub const LINT_OFFSET_LIMIT_SECONDS: f32 = 10.0;
+pub const LINT_DISPLAY_BPM_TOLERANCE: f64 = 1.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintSeverity {
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LintKind {
+    MeterMismatch {
+        meter: u32,
+        rating: f64,
+    },
+    /// Denser than a chart with a higher meter in the same song.
+    MeterOrder {
+        meter: u32,
+        nps: f64,
+        easier_meter: u32,
+        easier_nps: f64,
+    },
+    MissingMusic {
+        tag: String,
+    },
+    /// The tag names no file; `resolved` is what the fallback picked instead.
+    MusicTagMismatch {
+        tag: String,
+        resolved: String,
+    },
+    MissingBanner {
+        tag: String,
+    },
+    BannerTagMismatch {
+        tag: String,
+        resolved: String,
+    },
+    NotesInWarps {
+        count: usize,
+        first_beat: f32,
+    },
+    NotesInFakes {
+        count: usize,
+        first_beat: f32,
+    },
+    ShortStop {
+        beat: f32,
+        seconds: f32,
+    },
+    OverlappingHolds {
+        count: usize,
+        first_beat: f32,
+    },
+    DisplayBpmMismatch {
+        display: (f64, f64),
+        actual: (f64, f64),
+    },
+    OffsetOutOfRange {
+        offset: f32,
+    },
+    /// The same chart hash is already in `other` (`pack/title`).
+    DuplicateChart {
+        other: String,
+    },
+}
+
+impl LintKind {
+    pub const fn severity(&self) -> LintSeverity {
+        match self {
+            Self::MissingMusic { .. } | Self::OverlappingHolds { .. } => LintSeverity::Error,
+            _ => LintSeverity::Warning,
+        }
+    }
+}
+
+impl fmt::Display for LintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MeterMismatch { meter, rating } => {
+                write!(f, "meter {meter} but stream rating is {rating:.1}")
+            }
+            Self::MeterOrder {
+                meter,
+                nps,
+                easier_meter,
+                easier_nps,
+            } => write!(
+                f,
+                "meter {meter} peaks at {nps:.1} NPS, below the {easier_meter} at {easier_nps:.1} NPS"
+            ),
+            Self::MissingMusic { tag } if tag.is_empty() => f.write_str("no music file"),
+            Self::MissingMusic { tag } => write!(f, "#MUSIC file '{tag}' not found"),
+            Self::MusicTagMismatch { tag, resolved } => {
+                write!(f, "#MUSIC '{tag}' not found, loads '{resolved}' instead")
+            }
+            Self::MissingBanner { tag } if tag.is_empty() => f.write_str("no banner"),
+            Self::MissingBanner { tag } => write!(f, "#BANNER file '{tag}' not found"),
+            Self::BannerTagMismatch { tag, resolved } => {
+                write!(f, "#BANNER '{tag}' not found, loads '{resolved}' instead")
+            }
+            Self::NotesInWarps { count, first_beat } => {
+                write!(
+                    f,
+                    "{count} note(s) inside warps, first at beat {first_beat:.3}"
+                )
+            }
+            Self::NotesInFakes { count, first_beat } => write!(
+                f,
+                "{count} note(s) inside fake segments, first at beat {first_beat:.3}"
+            ),
+            Self::ShortStop { beat, seconds } => write!(
+                f,
+                "stop at beat {beat:.3} lasts {:.1} ms, under one frame",
+                seconds * 1000.0
+            ),
+            Self::OverlappingHolds { count, first_beat } => write!(
+                f,
+                "{count} note(s) overlap a hold, first at beat {first_beat:.3}"
+            ),
+            Self::DisplayBpmMismatch { display, actual } => write!(
+                f,
+                "#DISPLAYBPM {} but the chart runs at {}",
+                display_bpm_range_text(*display),
+                display_bpm_range_text(*actual)
+            ),
+            Self::OffsetOutOfRange { offset } => write!(f, "#OFFSET {offset:.3} is out of range"),
+            Self::DuplicateChart { other } => write!(f, "same chart as {other}"),
+        }
+    }
+}
+
+pub fn display_bpm_range_text((min, max): (f64, f64)) -> String {
+    if (max - min).abs() < 0.5 {
+        format!("{min:.0}")
+    } else {
+        format!("{min:.0}-{max:.0}")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LintFinding {
+    /// `None` for findings about the song as a whole.
+    pub chart: Option<String>,
+    pub kind: LintKind,
+}
+
+impl LintFinding {
+    #[inline(always)]
+    pub const fn severity(&self) -> LintSeverity {
+        self.kind.severity()
+    }
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity() {
+            LintSeverity::Warning => "warning",
+            LintSeverity::Error => "error",
+        };
+        match &self.chart {
+            Some(chart) => write!(f, "{severity}: {chart}: {}", self.kind),
+            None => write!(f, "{severity}: {}", self.kind),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SongLintReport {
+    pub simfile_path: PathBuf,
+    pub title: String,
+    pub findings: Vec<LintFinding>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PackLintReport {
+    pub pack_dir: PathBuf,
+    pub pack_name: String,
+    pub songs_checked: usize,
+    /// Only songs with at least one finding.
+    pub songs: Vec<SongLintReport>,
+    pub failures: Vec<(PathBuf, String)>,
+}
+
+impl PackLintReport {
+    pub fn count(&self, severity: LintSeverity) -> usize {
+        self.songs
+            .iter()
+            .flat_map(|song| &song.findings)
+            .filter(|finding| finding.severity() == severity)
+            .count()
+    }
+}
+
+#[inline(always)]
+pub fn chart_label(chart: &SerializableChartData) -> String {
+    format!("{} {} {}", chart.chart_type, chart.difficulty, chart.meter)
+}
+
+/// The value of a header tag, before the first `#NOTES`/`#NOTEDATA`.
+pub fn raw_header_tag<'a>(text: &'a str, tag: &str) -> Option<&'a str> {
+    let mut rest = text;
+    while let Some(start) = rest.find('#') {
+        rest = &rest[start + 1..];
+        let colon = rest.find(':')?;
+        let name = rest[..colon].trim();
+        if name.eq_ignore_ascii_case("NOTES") || name.eq_ignore_ascii_case("NOTEDATA") {
+            return None;
+        }
+        let value_end = rest[colon + 1..]
+            .find(';')
+            .map_or(rest.len(), |end| colon + 1 + end);
+        if name.eq_ignore_ascii_case(tag) {
+            return Some(rest[colon + 1..value_end].trim());
+        }
+        rest = &rest[value_end..];
+    }
+    None
+}
+
+fn asset_finding(
+    simfile_dir: &Path,
+    tag: &str,
+    resolved: Option<&str>,
+    missing: fn(String) -> LintKind,
+    mismatch: fn(String, String) -> LintKind,
+) -> Option<LintKind> {
+    if !tag.is_empty() && simfile_dir.join(tag).is_file() {
+        return None;
+    }
+    match resolved {
+        None => Some(missing(tag.to_string())),
+        Some(_) if tag.is_empty() => None,
+        Some(path) => {
+            let name = Path::new(path).file_name().map_or_else(
+                || path.to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+            Some(mismatch(tag.to_string(), name))
+        }
+    }
+}
+
+fn song_asset_findings(song: &SerializableSongData, simfile_text: &str) -> Vec<LintKind> {
+    let simfile_dir = Path::new(&song.simfile_path)
+        .parent()
+        .unwrap_or_else(|| Path::new("."));
+    let music = asset_finding(
+        simfile_dir,
+        raw_header_tag(simfile_text, "MUSIC").unwrap_or_default(),
+        song.music_path.as_deref(),
+        |tag| LintKind::MissingMusic { tag },
+        |tag, resolved| LintKind::MusicTagMismatch { tag, resolved },
+    );
+    let banner = asset_finding(
+        simfile_dir,
+        raw_header_tag(simfile_text, "BANNER").unwrap_or_default(),
+        song.banner_path.as_deref(),
+        |tag| LintKind::MissingBanner { tag },
+        |tag, resolved| LintKind::BannerTagMismatch { tag, resolved },
+    );
+    music.into_iter().chain(banner).collect()
+}
+
+fn meter_findings(charts: &[SerializableChartData]) -> Vec<Vec<LintKind>> {
+    charts
+        .iter()
+        .map(|chart| {
+            let mut kinds = Vec::new();
+            let rating = chart.matrix_rating;
+            if rating.is_finite()
+                && rating > 0.0
+                && (f64::from(chart.meter) - rating).abs() > LINT_METER_TOLERANCE
+            {
+                kinds.push(LintKind::MeterMismatch {
+                    meter: chart.meter,
+                    rating,
+                });
+            }
+            // The densest easier chart is the one worth naming.
+            let easier = charts
+                .iter()
+                .filter(|other| {
+                    other.chart_type == chart.chart_type
+                        && other.meter < chart.meter
+                        && chart.max_nps > 0.0
+                        && chart.max_nps < other.max_nps * LINT_NPS_ORDER_RATIO
+                })
+                .max_by(|a, b| a.max_nps.total_cmp(&b.max_nps));
+            if let Some(easier) = easier {
+                kinds.push(LintKind::MeterOrder {
+                    meter: chart.meter,
+                    nps: chart.max_nps,
+                    easier_meter: easier.meter,
+                    easier_nps: easier.max_nps,
+                });
+            }
+            kinds
+        })
+        .collect()
+}
+
+fn note_findings(chart: &SerializableChartData, timing: &TimingData) -> Vec<LintKind> {
+    let beat_of = |row: u32| chart.row_to_beat.get(row as usize).copied();
+    let mut warps = (0usize, f32::NAN);
+    let mut fakes = (0usize, f32::NAN);
+    let mut overlaps = (0usize, f32::NAN);
+    let mut hold_tails: Vec<Option<u32>> = Vec::new();
+    let mut notes: Vec<_> = chart.parsed_notes.iter().collect();
+    notes.sort_by_key(|note| note.row_index);
+    for note in notes {
+        if matches!(note.note_type, CachedNoteType::Mine | CachedNoteType::Fake) {
+            continue;
+        }
+        let Some(beat) = beat_of(note.row_index) else {
+            continue;
+        };
+        let bump = |(count, first): &mut (usize, f32)| {
+            if *count == 0 {
+                *first = beat;
+            }
+            *count += 1;
+        };
+        if timing.is_warp_at_beat(beat) {
+            bump(&mut warps);
+        } else if timing.is_fake_at_beat(beat) {
+            bump(&mut fakes);
+        }
+        let column = note.column as usize;
+        if hold_tails.len() <= column {
+            hold_tails.resize(column + 1, None);
+        }
+        if hold_tails[column].is_some_and(|tail| note.row_index <= tail) {
+            bump(&mut overlaps);
+        }
+        if let Some(tail) = note.tail_row_index
+            && matches!(note.note_type, CachedNoteType::Hold | CachedNoteType::Roll)
+        {
+            hold_tails[column] = Some(hold_tails[column].map_or(tail, |prev| prev.max(tail)));
+        }
+    }
+    let mut kinds = Vec::new();
+    if warps.0 > 0 {
+        kinds.push(LintKind::NotesInWarps {
+            count: warps.0,
+            first_beat: warps.1,
+        });
+    }
+    if fakes.0 > 0 {
+        kinds.push(LintKind::NotesInFakes {
+            count: fakes.0,
+            first_beat: fakes.1,
+        });
+    }
+    if overlaps.0 > 0 {
+        kinds.push(LintKind::OverlappingHolds {
+            count: overlaps.0,
+            first_beat: overlaps.1,
+        });
+    }
+    kinds
+}
+
+fn timing_findings(
+    chart: &SerializableChartData,
+    segments: &TimingSegments,
+    song_display_bpm: Option<&CachedChartDisplayBpm>,
+) -> Vec<LintKind> {
+    let mut kinds: Vec<LintKind> = segments
+        .stops
+        .iter()
+        .filter(|stop| stop.duration > 0.0 && stop.duration < LINT_FRAME_SECONDS)
+        .map(|stop| LintKind::ShortStop {
+            beat: stop.beat,
+            seconds: stop.duration,
+        })
+        .collect();
+    let actual = (chart.min_bpm, chart.max_bpm);
+    if let Some(CachedChartDisplayBpm::Specified { min, max }) =
+        chart.display_bpm.as_ref().or(song_display_bpm)
+        && actual.0.is_finite()
+        && actual.1.is_finite()
+        && actual.1 > 0.0
+        && ((min - actual.0).abs() > LINT_DISPLAY_BPM_TOLERANCE
+            || (max - actual.1).abs() > LINT_DISPLAY_BPM_TOLERANCE)
+    {
+        kinds.push(LintKind::DisplayBpmMismatch {
+            display: (*min, *max),
+            actual,
+        });
+    }
+    kinds
+}
+
+/// Findings every chart shares are reported once for the song.
+fn push_chart_findings(
+    findings: &mut Vec<LintFinding>,
+    labels: &[String],
+    per_chart: Vec<Vec<LintKind>>,
+) {
+    let shared = per_chart.len() > 1
+        && !per_chart[0].is_empty()
+        && per_chart.iter().all(|kinds| *kinds == per_chart[0]);
+    if shared {
+        let kinds = per_chart.into_iter().next().unwrap_or_default();
+        findings.extend(
+            kinds
+                .into_iter()
+                .map(|kind| LintFinding { chart: None, kind }),
+        );
+        return;
+    }
+    for (label, kinds) in labels.iter().zip(per_chart) {
+        findings.extend(kinds.into_iter().map(|kind| LintFinding {
+            chart: Some(label.clone()),
+            kind,
+        }));
+    }
+}
+
+/// Lints one parsed song. `known_hashes` maps chart hashes seen elsewhere to
+/// a `pack/title` label.
+pub fn lint_song(
+    song: &SerializableSongData,
+    simfile_text: &str,
+    known_hashes: &HashMap<String, String>,
+) -> Vec<LintFinding> {
+    let mut findings: Vec<LintFinding> = song_asset_findings(song, simfile_text)
+        .into_iter()
+        .map(|kind| LintFinding { chart: None, kind })
+        .collect();
+    if !song.offset.is_finite() || song.offset.abs() > LINT_OFFSET_LIMIT_SECONDS {
+        findings.push(LintFinding {
+            chart: None,
+            kind: LintKind::OffsetOutOfRange {
+                offset: song.offset,
+            },
+        });
+    }
+
+    let song_display_bpm = parse_chart_display_bpm(raw_header_tag(simfile_text, "DISPLAYBPM"));
+    let mut notes = Vec::with_capacity(song.charts.len());
+    let mut timing = Vec::with_capacity(song.charts.len());
+    let mut offset_flagged = findings
+        .iter()
+        .any(|finding| matches!(finding.kind, LintKind::OffsetOutOfRange { .. }));
+    for chart in &song.charts {
+        let segments: TimingSegments = chart.timing_segments.clone().into();
+        let data = TimingData::from_segments(-song.offset, 0.0, &segments, &chart.row_to_beat);
+        notes.push(note_findings(chart, &data));
+        timing.push(timing_findings(chart, &segments, song_display_bpm.as_ref()));
+        // Beat 0 past the end of the music means the offset is unusable.
+        let beat0 = data.get_time_for_beat(0.0);
+        if !offset_flagged && song.music_length_seconds > 0.0 && beat0 > song.music_length_seconds {
+            offset_flagged = true;
+            findings.push(LintFinding {
+                chart: None,
+                kind: LintKind::OffsetOutOfRange {
+                    offset: song.offset,
+                },
+            });
+        }
+    }
+    let labels: Vec<String> = song.charts.iter().map(chart_label).collect();
+    push_chart_findings(&mut findings, &labels, timing);
+    push_chart_findings(&mut findings, &labels, meter_findings(&song.charts));
+    push_chart_findings(&mut findings, &labels, notes);
+    for (chart, label) in song.charts.iter().zip(labels) {
+        if chart.short_hash.is_empty() {
+            continue;
+        }
+        if let Some(other) = known_hashes.get(&chart.short_hash) {
+            findings.push(LintFinding {
+                chart: Some(label),
+                kind: LintKind::DuplicateChart {
+                    other: other.clone(),
+                },
+            });
+        }
+    }
+    findings
+}
+
+/// Lints every song in `pack_dir`; `None` once `cancel` is set. The pack's
+/// own charts are added to `known_hashes`, so repeats within the pack and in
+/// packs linted later are caught too.
+pub fn lint_pack(
+    pack_dir: &Path,
+    known_hashes: &mut HashMap<String, String>,
+    mut music_len: impl FnMut(Option<&Path>) -> f32,
+    cancel: &AtomicBool,
+) -> Option<PackLintReport> {
+    let (packs, failures) = scan_pack_dirs(&[pack_dir.to_path_buf()], &[]);
+    let mut report = PackLintReport {
+        pack_dir: pack_dir.to_path_buf(),
+        pack_name: pack_dir
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned()),
+        failures: failures
+            .into_iter()
+            .map(|failure| (failure.path, failure.error))
+            .collect(),
+        ..PackLintReport::default()
+    };
+    let options = ParseSongOptions::new(Vec::new(), Vec::new(), Vec::new());
+    for song_scan in packs.iter().flat_map(|pack| &pack.songs) {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        let simfile = &song_scan.simfile;
+        let text = match std::fs::read(simfile) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) => {
+                report.failures.push((simfile.clone(), e.to_string()));
+                continue;
+            }
+        };
+        let song = match parse_song_data_file(simfile, &options, 0.0, &mut music_len) {
+            Ok(song) => song,
+            Err(e) => {
+                report.failures.push((simfile.clone(), e));
+                continue;
+            }
+        };
+        report.songs_checked += 1;
+        let findings = lint_song(&song, &text, known_hashes);
+        for chart in song
+            .charts
+            .iter()
+            .filter(|chart| !chart.short_hash.is_empty())
+        {
+            known_hashes
+                .entry(chart.short_hash.clone())
+                .or_insert_with(|| format!("{}/{}", report.pack_name, song.title));
+        }
+        if !findings.is_empty() {
+            report.songs.push(SongLintReport {
+                simfile_path: simfile.clone(),
+                title: song.title,
+                findings,
+            });
+        }
+    }
+    Some(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_tags_stop_at_the_first_chart() {
+        let text = "#TITLE:Song;\n#music:song.ogg ;\n#NOTEDATA:;\n#BANNER:chart.png;\n";
+        assert_eq!(raw_header_tag(text, "MUSIC"), Some("song.ogg"));
+        assert_eq!(raw_header_tag(text, "TITLE"), Some("Song"));
+        assert_eq!(raw_header_tag(text, "BANNER"), None);
+    }
+
+    #[test]
+    fn asset_tags_that_only_load_through_the_fallback_are_mismatches() {
+        let dir = std::env::temp_dir().join(format!("deadsync_lint_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        std::fs::write(dir.join("song.ogg"), b"").expect("write music");
+        let missing = |tag| LintKind::MissingMusic { tag };
+        let mismatch = |tag, resolved| LintKind::MusicTagMismatch { tag, resolved };
+        let resolved = dir.join("song.ogg").to_string_lossy().into_owned();
+        assert_eq!(
+            asset_finding(&dir, "song.ogg", Some(&resolved), missing, mismatch),
+            None
+        );
+        assert_eq!(
+            asset_finding(&dir, "Song.mp3", Some(&resolved), missing, mismatch),
+            Some(LintKind::MusicTagMismatch {
+                tag: "Song.mp3".to_string(),
+                resolved: "song.ogg".to_string(),
+            })
+        );
+        assert_eq!(
+            asset_finding(&dir, "gone.ogg", None, missing, mismatch),
+            Some(LintKind::MissingMusic {
+                tag: "gone.ogg".to_string(),
+            })
+        );
+        assert_eq!(
+            asset_finding(&dir, "", Some(&resolved), missing, mismatch),
+            None
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn findings_every_chart_shares_are_reported_once() {
+        let labels = vec![
+            "dance-single Easy 3".to_string(),
+            "dance-single Hard 9".to_string(),
+        ];
+        let stop = LintKind::ShortStop {
+            beat: 4.0,
+            seconds: 0.01,
+        };
+        let mut findings = Vec::new();
+        push_chart_findings(
+            &mut findings,
+            &labels,
+            vec![vec![stop.clone()], vec![stop.clone()]],
+        );
+        assert_eq!(
+            findings,
+            vec![LintFinding {
+                chart: None,
+                kind: stop.clone(),
+            }]
+        );
+
+        findings.clear();
+        push_chart_findings(&mut findings, &labels, vec![vec![], vec![stop.clone()]]);
+        assert_eq!(
+            findings,
+            vec![LintFinding {
+                chart: Some(labels[1].clone()),
+                kind: stop,
+            }]
+        );
+    }
+}
@@ -0,0 +1,480 @@
+//! Per-pack sync bias and bulk `#OFFSET` rewriting.
+//!
+//! Packs from different sources follow different sync conventions. The bias
+//! lives in a `PackSync.ini` sidecar in the pack directory and is applied at
+//! chart load without touching the simfiles. Writing it into the simfiles
+//! records every rewritten file in a `PackSync.journal` sidecar, so the
+//! rewrite can be undone as a whole.
+//!
+//! A rewrite that misses some files keeps the bias for them. Until it is
+//! finished, the journal's unsettled entries tell chart loading how much of
+//! the bias each rewritten file already carries.
+
+use crate::sync_offset::{SongOffsetSaveSummary, SongOffsetSyncChange, save_song_offset_changes};
+use deadsync_config::prelude::SimpleIni;
+use std::path::{Path, PathBuf};
+
+pub const PACK_SYNC_FILE_NAME: &str = "PackSync.ini";
+pub const PACK_SYNC_JOURNAL_FILE_NAME: &str = "PackSync.journal";
+/// Largest bias, either way, that can be set for a pack.
+pub const PACK_SYNC_BIAS_LIMIT_MS: f32 = 100.0;
+
+const PACK_SYNC_SECTION: &str = "PackSync";
+const PACK_SYNC_BIAS_KEY: &str = "BiasMs";
+const PACK_SYNC_SETTLED_KEY: &str = "Settled";
+
+#[inline(always)]
+pub fn pack_sync_path(pack_dir: &Path) -> PathBuf {
+    pack_dir.join(PACK_SYNC_FILE_NAME)
+}
+
+#[inline(always)]
+pub fn pack_sync_journal_path(pack_dir: &Path) -> PathBuf {
+    pack_dir.join(PACK_SYNC_JOURNAL_FILE_NAME)
+}
+
+#[inline(always)]
+pub fn clamp_pack_sync_bias_ms(bias_ms: f32) -> f32 {
+    if bias_ms.is_finite() {
+        bias_ms.clamp(-PACK_SYNC_BIAS_LIMIT_MS, PACK_SYNC_BIAS_LIMIT_MS)
+    } else {
+        0.0
+    }
+}
+
+/// The pack's bias in ms; 0 when the sidecar is missing or unreadable.
+pub fn load_pack_sync_bias_ms(pack_dir: &Path) -> f32 {
+    let mut ini = SimpleIni::new();
+    if ini.load(pack_sync_path(pack_dir)).is_err() {
+        return 0.0;
+    }
+    ini.get(PACK_SYNC_SECTION, PACK_SYNC_BIAS_KEY)
+        .and_then(|raw| raw.trim().parse::<f32>().ok())
+        .map_or(0.0, clamp_pack_sync_bias_ms)
+}
+
+/// Writes the bias sidecar, or removes it when the bias is zero.
+pub fn save_pack_sync_bias_ms(pack_dir: &Path, bias_ms: f32) -> Result<(), String> {
+    let path = pack_sync_path(pack_dir);
+    let bias_ms = clamp_pack_sync_bias_ms(bias_ms);
+    if bias_ms.abs() < 0.05 {
+        return match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Failed to remove '{}': {e}", path.display())),
+        };
+    }
+    let text = format!("[{PACK_SYNC_SECTION}]\n{PACK_SYNC_BIAS_KEY}={bias_ms:.1}\n");
+    std::fs::write(&path, text).map_err(|e| format!("Failed to write '{}': {e}", path.display()))
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PackOffsetJournal {
+    /// Bias the pack had before its first rewrite; restored on undo.
+    pub bias_ms: f32,
+    /// Rewritten simfiles and the `#OFFSET` delta each received, in seconds.
+    pub entries: Vec<(PathBuf, f32)>,
+    /// Leading entries whose rewrite finished and cleared the bias. Later
+    /// entries carry part of the bias still set for the pack.
+    pub settled: usize,
+}
+
+impl PackOffsetJournal {
+    /// Bias each simfile carries from unsettled entries, in ms.
+    pub fn written_bias_ms(&self) -> Vec<(PathBuf, f32)> {
+        let mut written: Vec<(PathBuf, f32)> = Vec::new();
+        for (path, delta) in self.entries.iter().skip(self.settled) {
+            match written.iter_mut().find(|(written, _)| written == path) {
+                Some((_, ms)) => *ms += delta * 1000.0,
+                None => written.push((path.clone(), delta * 1000.0)),
+            }
+        }
+        written
+    }
+}
+
+pub fn pack_offset_journal_exists(pack_dir: &Path) -> bool {
+    pack_sync_journal_path(pack_dir).is_file()
+}
+
+/// Journal lines are `BiasMs=<ms>` and `Settled=<count>` followed by
+/// `<delta seconds>\t<path>`, with paths relative to the pack directory where
+/// possible. Deltas keep the three decimals `#OFFSET` is written with.
+fn journal_text(pack_dir: &Path, journal: &PackOffsetJournal) -> String {
+    let mut text = format!(
+        "{PACK_SYNC_BIAS_KEY}={:.1}\n{PACK_SYNC_SETTLED_KEY}={}\n",
+        journal.bias_ms, journal.settled
+    );
+    for (path, delta) in &journal.entries {
+        let path = journal_path_text(pack_dir, path).unwrap_or_default();
+        text.push_str(&format!("{delta:.3}\t{path}\n"));
+    }
+    text
+}
+
+/// The path as the journal stores it; `None` when a line could not hold it
+/// exactly, e.g. it is not UTF-8 or contains a line break.
+fn journal_path_text<'a>(pack_dir: &Path, path: &'a Path) -> Option<&'a str> {
+    let text = path.strip_prefix(pack_dir).unwrap_or(path).to_str()?;
+    (!text.is_empty() && !text.contains(['\t', '\n', '\r'])).then_some(text)
+}
+
+/// Journals written before `Settled` existed only held finished rewrites.
+fn parse_journal(pack_dir: &Path, text: &str) -> Option<PackOffsetJournal> {
+    let mut lines = text.lines().peekable();
+    let bias_ms = lines
+        .next()?
+        .strip_prefix(PACK_SYNC_BIAS_KEY)?
+        .strip_prefix('=')?
+        .trim()
+        .parse::<f32>()
+        .ok()?;
+    let settled = match lines
+        .peek()
+        .and_then(|line| line.strip_prefix(PACK_SYNC_SETTLED_KEY))
+    {
+        Some(value) => {
+            let settled = value.strip_prefix('=')?.trim().parse::<usize>().ok()?;
+            lines.next();
+            Some(settled)
+        }
+        None => None,
+    };
+    let mut entries = Vec::new();
+    for line in lines.filter(|line| !line.trim().is_empty()) {
+        let (delta, path) = line.split_once('\t')?;
+        entries.push((pack_dir.join(path), delta.trim().parse::<f32>().ok()?));
+    }
+    Some(PackOffsetJournal {
+        bias_ms: clamp_pack_sync_bias_ms(bias_ms),
+        settled: settled.unwrap_or(entries.len()).min(entries.len()),
+        entries,
+    })
+}
+
+pub fn load_pack_offset_journal(pack_dir: &Path) -> Option<PackOffsetJournal> {
+    let text = std::fs::read_to_string(pack_sync_journal_path(pack_dir)).ok()?;
+    parse_journal(pack_dir, &text)
+}
+
+/// Bias each simfile in the pack already carries from an unfinished rewrite,
+/// in ms; chart loading takes it off the pack's bias for those files.
+pub fn load_pack_sync_written_bias_ms(pack_dir: &Path) -> Vec<(PathBuf, f32)> {
+    load_pack_offset_journal(pack_dir).map_or_else(Vec::new, |journal| journal.written_bias_ms())
+}
+
+fn write_pack_offset_journal(pack_dir: &Path, journal: &PackOffsetJournal) -> Result<(), String> {
+    let path = pack_sync_journal_path(pack_dir);
+    std::fs::write(&path, journal_text(pack_dir, journal))
+        .map_err(|e| format!("Failed to write '{}': {e}", path.display()))
+}
+
+/// Writes the journal, or removes it once it holds no rewrites.
+fn save_pack_offset_journal(pack_dir: &Path, journal: &PackOffsetJournal) -> Result<(), String> {
+    if !journal.entries.is_empty() {
+        return write_pack_offset_journal(pack_dir, journal);
+    }
+    let path = pack_sync_journal_path(pack_dir);
+    match std::fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove '{}': {e}", path.display())),
+    }
+}
+
+/// Moves the pack's bias into the `#OFFSET` of every simfile in
+/// `simfile_paths`, so timing is unchanged overall.
+///
+/// The journal is written before the first simfile and after each one, so
+/// an interrupted rewrite can still be undone. The bias is cleared only once
+/// every simfile carries it; skipped files keep getting it at load, and
+/// running the rewrite again finishes them. Rewrites made while an earlier
+/// journal is still present extend it, so one undo returns the pack to its
+/// state before the first rewrite.
+pub fn apply_pack_sync_bias_to_simfiles<W, A>(
+    pack_dir: &Path,
+    simfile_paths: &[PathBuf],
+    is_writable: W,
+    mut after_save: A,
+) -> Result<SongOffsetSaveSummary, String>
+where
+    W: FnMut(&Path) -> bool,
+    A: FnMut(&Path) -> Result<(), String>,
+{
+    let bias_ms = load_pack_sync_bias_ms(pack_dir);
+    if bias_ms == 0.0 {
+        return Err("Pack has no sync bias to write".to_string());
+    }
+    let mut journal = load_pack_offset_journal(pack_dir).unwrap_or(PackOffsetJournal {
+        bias_ms,
+        entries: Vec::new(),
+        settled: 0,
+    });
+    let written = journal.written_bias_ms();
+    let mut unjournaled: Option<&Path> = None;
+    let mut unjournaled_files = 0;
+    let mut changes: Vec<SongOffsetSyncChange> = Vec::new();
+    for simfile_path in simfile_paths {
+        let written_ms = written
+            .iter()
+            .find(|(path, _)| path == simfile_path)
+            .map_or(0.0, |(_, ms)| *ms);
+        let pending_ms = bias_ms - written_ms;
+        // `#OFFSET` holds whole milliseconds.
+        if pending_ms.abs() < 0.5 {
+            continue;
+        }
+        if journal_path_text(pack_dir, simfile_path).is_none() {
+            unjournaled.get_or_insert(simfile_path);
+            unjournaled_files += 1;
+            continue;
+        }
+        changes.push(SongOffsetSyncChange {
+            simfile_path: simfile_path.clone(),
+            delta_seconds: pending_ms / 1000.0,
+        });
+    }
+    if !changes.is_empty() {
+        write_pack_offset_journal(pack_dir, &journal)?;
+    }
+    let mut journal_error = None;
+    let mut summary = save_song_offset_changes(&changes, is_writable, |path| {
+        let delta = changes
+            .iter()
+            .find(|change| change.simfile_path == path)
+            .map_or(0.0, |change| change.delta_seconds);
+        journal.entries.push((path.to_path_buf(), delta));
+        if let Err(e) = write_pack_offset_journal(pack_dir, &journal) {
+            journal_error.get_or_insert(e);
+        }
+        after_save(path)
+    });
+    if let Some(e) = journal_error {
+        return Err(e);
+    }
+    if let Some(path) = unjournaled {
+        summary.failed_files += unjournaled_files;
+        if summary.first_failure_error.is_none() {
+            summary.first_failure_path = Some(path.to_path_buf());
+            summary.first_failure_error = Some("Path cannot be recorded for undo".to_string());
+        }
+    }
+    if summary.skipped_read_only == 0 && summary.failed_files == 0 {
+        journal.settled = journal.entries.len();
+        save_pack_sync_bias_ms(pack_dir, 0.0)?;
+    }
+    save_pack_offset_journal(pack_dir, &journal)?;
+    Ok(summary)
+}
+
+/// Reverts every rewrite in the pack's journal and restores the bias it
+/// replaced. Files that fail to revert stay in the journal for another try;
+/// the restored bias then skips what they still carry.
+pub fn undo_pack_offset_rewrite<W, A>(
+    pack_dir: &Path,
+    is_writable: W,
+    mut after_save: A,
+) -> Result<SongOffsetSaveSummary, String>
+where
+    W: FnMut(&Path) -> bool,
+    A: FnMut(&Path) -> Result<(), String>,
+{
+    let Some(journal) = load_pack_offset_journal(pack_dir) else {
+        return Err("Pack has no offset rewrite to undo".to_string());
+    };
+    let mut changes: Vec<SongOffsetSyncChange> = Vec::new();
+    for (path, delta) in &journal.entries {
+        match changes
+            .iter_mut()
+            .find(|change| change.simfile_path == *path)
+        {
+            Some(change) => change.delta_seconds -= delta,
+            None => changes.push(SongOffsetSyncChange {
+                simfile_path: path.clone(),
+                delta_seconds: -delta,
+            }),
+        }
+    }
+    let mut reverted: Vec<PathBuf> = Vec::new();
+    let summary = save_song_offset_changes(&changes, is_writable, |path| {
+        reverted.push(path.to_path_buf());
+        after_save(path)
+    });
+    // Reverted files need the old bias back right away, so what is left is
+    // unsettled against it.
+    let remaining = PackOffsetJournal {
+        bias_ms: journal.bias_ms,
+        entries: journal
+            .entries
+            .into_iter()
+            .filter(|(path, _)| !reverted.contains(path))
+            .collect(),
+        settled: 0,
+    };
+    save_pack_offset_journal(pack_dir, &remaining)?;
+    if !reverted.is_empty() || remaining.entries.is_empty() {
+        save_pack_sync_bias_ms(pack_dir, journal.bias_ms)?;
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static TEST_ID: AtomicU64 = AtomicU64::new(0);
+
+    fn temp_pack() -> PathBuf {
+        let id = TEST_ID.fetch_add(1, Ordering::Relaxed);
+        let root = std::env::temp_dir().join(format!(
+            "deadsync_pack_sync_test_{}_{}",
+            std::process::id(),
+            id
+        ));
+        std::fs::create_dir_all(root.join("Song")).expect("create temp pack");
+        root
+    }
+
+    #[test]
+    fn pack_sync_bias_round_trips_and_zero_removes_the_sidecar() {
+        let pack = temp_pack();
+        assert_eq!(load_pack_sync_bias_ms(&pack), 0.0);
+        save_pack_sync_bias_ms(&pack, 9.0).expect("save bias");
+        assert_eq!(load_pack_sync_bias_ms(&pack), 9.0);
+        save_pack_sync_bias_ms(&pack, 500.0).expect("save clamped bias");
+        assert_eq!(load_pack_sync_bias_ms(&pack), PACK_SYNC_BIAS_LIMIT_MS);
+        save_pack_sync_bias_ms(&pack, 0.0).expect("clear bias");
+        assert!(!pack_sync_path(&pack).exists());
+        let _ = std::fs::remove_dir_all(pack);
+    }
+
+    #[test]
+    fn writing_the_bias_into_simfiles_can_be_undone() {
+        let pack = temp_pack();
+        let simfile = pack.join("Song").join("song.ssc");
+        std::fs::write(&simfile, b"#TITLE:test;\n#OFFSET:0.100;\n").expect("write simfile");
+        save_pack_sync_bias_ms(&pack, -9.0).expect("save bias");
+
+        let paths = [simfile.clone()];
+        let summary = apply_pack_sync_bias_to_simfiles(&pack, &paths, |_| true, |_| Ok(()))
+            .expect("apply bias");
+        assert_eq!(summary.saved_files, 1);
+        let text = std::fs::read_to_string(&simfile).expect("read simfile");
+        assert!(text.contains("#OFFSET:0.091;"));
+        assert_eq!(load_pack_sync_bias_ms(&pack), 0.0);
+        let journal = load_pack_offset_journal(&pack).expect("journal written");
+        assert_eq!(journal.bias_ms, -9.0);
+        assert_eq!(journal.entries, vec![(simfile.clone(), -0.009)]);
+        assert_eq!(journal.settled, 1);
+
+        let summary = undo_pack_offset_rewrite(&pack, |_| true, |_| Ok(())).expect("undo rewrite");
+        assert_eq!(summary.saved_files, 1);
+        let text = std::fs::read_to_string(&simfile).expect("read simfile");
+        assert!(text.contains("#OFFSET:0.100;"));
+        assert_eq!(load_pack_sync_bias_ms(&pack), -9.0);
+        assert!(!pack_offset_journal_exists(&pack));
+        let _ = std::fs::remove_dir_all(pack);
+    }
+
+    #[test]
+    fn undo_keeps_files_that_could_not_be_reverted() {
+        let pack = temp_pack();
+        let simfile = pack.join("Song").join("song.ssc");
+        std::fs::write(&simfile, b"#OFFSET:0.000;\n").expect("write simfile");
+        save_pack_sync_bias_ms(&pack, 5.0).expect("save bias");
+        apply_pack_sync_bias_to_simfiles(
+            &pack,
+            std::slice::from_ref(&simfile),
+            |_| true,
+            |_| Ok(()),
+        )
+        .expect("apply bias");
+
+        let summary = undo_pack_offset_rewrite(&pack, |_| false, |_| Ok(())).expect("undo rewrite");
+        assert_eq!(summary.skipped_read_only, 1);
+        assert!(pack_offset_journal_exists(&pack));
+        assert_eq!(load_pack_sync_bias_ms(&pack), 0.0);
+        let _ = std::fs::remove_dir_all(pack);
+    }
+
+    fn two_song_pack(bias_ms: f32) -> (PathBuf, [PathBuf; 2]) {
+        let pack = temp_pack();
+        std::fs::create_dir_all(pack.join("Other")).expect("create second song");
+        let simfiles = [
+            pack.join("Song").join("song.ssc"),
+            pack.join("Other").join("other.ssc"),
+        ];
+        for simfile in &simfiles {
+            std::fs::write(simfile, b"#OFFSET:0.000;\n").expect("write simfile");
+        }
+        save_pack_sync_bias_ms(&pack, bias_ms).expect("save bias");
+        (pack, simfiles)
+    }
+
+    #[test]
+    fn partial_rewrites_keep_the_bias_for_skipped_files() {
+        let (pack, simfiles) = two_song_pack(5.0);
+        let only_first = |path: &Path| path == simfiles[0];
+
+        let summary = apply_pack_sync_bias_to_simfiles(&pack, &simfiles, only_first, |_| Ok(()))
+            .expect("apply bias");
+        assert_eq!(summary.saved_files, 1);
+        assert_eq!(summary.skipped_read_only, 1);
+        assert_eq!(load_pack_sync_bias_ms(&pack), 5.0);
+        let written = load_pack_sync_written_bias_ms(&pack);
+        assert_eq!(written.len(), 1);
+        assert_eq!(written[0].0, simfiles[0]);
+        assert!((written[0].1 - 5.0).abs() < 1e-3);
+
+        let summary = apply_pack_sync_bias_to_simfiles(&pack, &simfiles, |_| true, |_| Ok(()))
+            .expect("finish rewrite");
+        assert_eq!(summary.saved_files, 1);
+        assert_eq!(load_pack_sync_bias_ms(&pack), 0.0);
+        assert!(load_pack_sync_written_bias_ms(&pack).is_empty());
+        for simfile in &simfiles {
+            let text = std::fs::read_to_string(simfile).expect("read simfile");
+            assert!(text.contains("#OFFSET:0.005;"));
+        }
+        let _ = std::fs::remove_dir_all(pack);
+    }
+
+    #[test]
+    fn partial_undo_restores_the_bias_for_reverted_files() {
+        let (pack, simfiles) = two_song_pack(5.0);
+        apply_pack_sync_bias_to_simfiles(&pack, &simfiles, |_| true, |_| Ok(()))
+            .expect("apply bias");
+
+        let only_first = |path: &Path| path == simfiles[0];
+        let summary = undo_pack_offset_rewrite(&pack, only_first, |_| Ok(())).expect("undo");
+        assert_eq!(summary.saved_files, 1);
+        assert_eq!(load_pack_sync_bias_ms(&pack), 5.0);
+        let written = load_pack_sync_written_bias_ms(&pack);
+        assert_eq!(written.len(), 1);
+        assert_eq!(written[0].0, simfiles[1]);
+        assert!((written[0].1 - 5.0).abs() < 1e-3);
+
+        undo_pack_offset_rewrite(&pack, |_| true, |_| Ok(())).expect("finish undo");
+        assert_eq!(load_pack_sync_bias_ms(&pack), 5.0);
+        assert!(!pack_offset_journal_exists(&pack));
+        let _ = std::fs::remove_dir_all(pack);
+    }
+
+    #[test]
+    fn journals_without_a_settled_count_are_fully_settled() {
+        let pack = Path::new("/pack");
+        let journal =
+            parse_journal(pack, "BiasMs=-9.0\n-0.009\tSong/song.ssc\n").expect("parse old journal");
+        assert_eq!(journal.settled, 1);
+        assert!(journal.written_bias_ms().is_empty());
+
+        let unsettled = PackOffsetJournal {
+            settled: 0,
+            ..journal
+        };
+        assert_eq!(
+            parse_journal(pack, &journal_text(pack, &unsettled)),
+            Some(unsettled)
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use deadsync_chart::song::{standard_difficulty_index, sync_pref_offset};
+use deadsync_chart::song::{pack_sync_shift_seconds, standard_difficulty_index};
 use deadsync_chart::{SongData, SongPack, SyncPref};
 use deadsync_config::app_config::Config;
 use std::path::{Path, PathBuf};
@@ -103,18 +103,33 @@ pub fn pack_sync_offset_for_song_in_packs(
     enabled: bool,
     default_sync_pref: SyncPref,
 ) -> f32 {
-    if !enabled {
-        return 0.0;
-    }
     let Some(pack_group) = crate::event_intro::song_pack_group(song) else {
         return 0.0;
     };
-    let pack_sync_pref = packs
+    let (pack_sync_pref, sync_bias_ms) = packs
         .iter()
         .find(|pack| pack.group_name == pack_group)
-        .map(|pack| pack.sync_pref)
-        .unwrap_or(SyncPref::Default);
-    sync_pref_offset(pack_sync_pref, default_sync_pref)
+        .map_or((SyncPref::Default, 0.0), |pack| {
+            (pack.sync_pref, pack.sync_bias_ms_for(&song.simfile_path))
+        });
+    pack_sync_shift_seconds(pack_sync_pref, default_sync_pref, enabled, sync_bias_ms)
+}
+
+/// Updates the cached bias of the pack in `pack_dir` after its `PackSync.ini`
+/// or `PackSync.journal` changed. Returns whether a cached pack matched.
+pub fn set_cached_pack_sync_bias(
+    pack_dir: &Path,
+    sync_bias_ms: f32,
+    sync_bias_written_ms: Vec<(PathBuf, f32)>,
+) -> bool {
+    let mut cache = get_song_cache();
+    let mut matched = false;
+    for pack in cache.iter_mut().filter(|pack| pack.directory == pack_dir) {
+        pack.sync_bias_ms = sync_bias_ms;
+        pack.sync_bias_written_ms = sync_bias_written_ms.clone();
+        matched = true;
+    }
+    matched
 }
 
 #[inline(always)]
@@ -251,6 +266,8 @@ mod tests {
             folder_series: String::new(),
             year: 0,
             sync_pref,
+            sync_bias_ms: 0.0,
+            sync_bias_written_ms: Vec::new(),
             directory: PathBuf::from("Songs").join(group_name),
             banner_path: None,
             songs,
@@ -337,6 +354,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn pack_sync_bias_applies_without_pack_ini_offsets() {
+        let song = song("Songs/Test Pack/Song/song.ssc");
+        let mut biased = pack("Test Pack", SyncPref::Itg, vec![song.clone()]);
+        biased.sync_bias_ms = 9.0;
+
+        assert_eq!(
+            pack_sync_offset_for_song_in_packs(&song, &[biased], false, SyncPref::Itg),
+            0.009,
+        );
+    }
+
+    #[test]
+    fn pack_sync_bias_skips_what_a_partial_rewrite_wrote() {
+        let rewritten = song("Songs/Test Pack/Song/song.ssc");
+        let pending = song("Songs/Test Pack/Other/other.ssc");
+        let mut biased = pack(
+            "Test Pack",
+            SyncPref::Null,
+            vec![rewritten.clone(), pending.clone()],
+        );
+        biased.sync_bias_ms = 9.0;
+        biased.sync_bias_written_ms = vec![(rewritten.simfile_path.clone(), 9.0)];
+        let packs = [biased];
+
+        assert_eq!(
+            pack_sync_offset_for_song_in_packs(&rewritten, &packs, false, SyncPref::Null),
+            0.0,
+        );
+        assert_eq!(
+            pack_sync_offset_for_song_in_packs(&pending, &packs, false, SyncPref::Null),
+            0.009,
+        );
+    }
+
     #[test]
     fn replace_song_arc_swaps_matching_simfile() {
         let mut current = song_with_charts(
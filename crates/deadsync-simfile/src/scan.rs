@@ -1300,6 +1300,8 @@ pub fn empty_song_pack_from_scan(pack: &PackScan) -> SongPack {
         folder_series: pack.folder_series.clone(),
         year: pack.year,
         sync_pref: pack.sync_pref,
+        sync_bias_ms: crate::pack_sync::load_pack_sync_bias_ms(&pack.dir),
+        sync_bias_written_ms: crate::pack_sync::load_pack_sync_written_bias_ms(&pack.dir),
         directory: pack.dir.clone(),
         banner_path: pack.banner_path.clone(),
         songs: Vec::new(),
@@ -2323,6 +2325,8 @@ mod tests {
             folder_series: String::new(),
             year: 0,
             sync_pref: SyncPref::Default,
+            sync_bias_ms: 0.0,
+            sync_bias_written_ms: Vec::new(),
             directory: root.join(group_name),
             banner_path: None,
             songs: Vec::new(),
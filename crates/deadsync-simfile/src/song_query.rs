@@ -0,0 +1,665 @@
+//! Structured predicates for the Select Music song search.
+//!
+//! A query token is `key`, `key:value`, or `key<op>value` with `<`, `<=`, `>`,
+//! `>=` or `=`. Song-level keys (`artist`, `pack`, `length`, `bpm`) test the
+//! song; every other key tests a chart, and all chart predicates must hold on
+//! the same chart of the searched style. Tokens that do not parse stay in the
+//! free text, so a title like `re:mix` still searches as text.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use deadsync_chart::{ChartData, SongData};
+
+/// Grade tier of a failed score. Tiers follow the grade sprite order, so `0` is
+/// a quint and lower tiers are better.
+pub const SONG_QUERY_FAILED_TIER: u8 = 18;
+
+const GRADE_NAMES: [(&str, u8); 21] = [
+    ("quint", 0),
+    ("5star", 0),
+    ("4star", 1),
+    ("3star", 2),
+    ("2star", 3),
+    ("1star", 4),
+    ("s+", 5),
+    ("s", 6),
+    ("s-", 7),
+    ("a+", 8),
+    ("a", 9),
+    ("a-", 10),
+    ("b+", 11),
+    ("b", 12),
+    ("b-", 13),
+    ("c+", 14),
+    ("c", 15),
+    ("c-", 16),
+    ("d", 17),
+    ("f", SONG_QUERY_FAILED_TIER),
+    ("failed", SONG_QUERY_FAILED_TIER),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SongQueryCmp {
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+}
+
+impl SongQueryCmp {
+    #[inline(always)]
+    fn test(self, value: f64, target: f64) -> bool {
+        match self {
+            Self::Lt => value < target,
+            Self::Le => value <= target,
+            Self::Eq => (value - target).abs() < 1e-6,
+            Self::Ge => value >= target,
+            Self::Gt => value > target,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SongQueryStat {
+    Meter,
+    Nps,
+    Streams,
+    Matrix,
+    Crossovers,
+    Footswitches,
+    Sideswitches,
+    Jacks,
+    Brackets,
+    /// Song length in seconds; written as `2:30` or `150`.
+    Length,
+    /// Highest song BPM.
+    Bpm,
+}
+
+impl SongQueryStat {
+    fn from_key(key: &str) -> Option<Self> {
+        Some(match key {
+            "meter" | "level" => Self::Meter,
+            "nps" => Self::Nps,
+            "streams" | "stream" => Self::Streams,
+            "matrix" => Self::Matrix,
+            "crossovers" | "xo" => Self::Crossovers,
+            "footswitches" | "fs" => Self::Footswitches,
+            "sideswitches" | "ss" => Self::Sideswitches,
+            "jacks" => Self::Jacks,
+            "brackets" => Self::Brackets,
+            "length" | "len" => Self::Length,
+            "bpm" => Self::Bpm,
+            _ => return None,
+        })
+    }
+
+    #[inline(always)]
+    const fn is_song_level(self) -> bool {
+        matches!(self, Self::Length | Self::Bpm)
+    }
+
+    fn chart_value(self, chart: &ChartData) -> f64 {
+        match self {
+            Self::Meter => f64::from(chart.meter),
+            Self::Nps => chart.max_nps,
+            Self::Streams => f64::from(chart.total_streams),
+            Self::Matrix => chart.matrix_rating,
+            Self::Crossovers => f64::from(chart.tech_counts.crossovers),
+            Self::Footswitches => f64::from(chart.tech_counts.footswitches),
+            Self::Sideswitches => f64::from(chart.tech_counts.sideswitches),
+            Self::Jacks => f64::from(chart.tech_counts.jacks),
+            Self::Brackets => f64::from(chart.tech_counts.brackets),
+            Self::Length | Self::Bpm => 0.0,
+        }
+    }
+
+    fn song_value(self, song: &SongData) -> f64 {
+        match self {
+            Self::Length => f64::from(song.music_length_seconds),
+            Self::Bpm => song.max_bpm,
+            _ => 0.0,
+        }
+    }
+
+    fn parse_value(self, value: &str) -> Option<f64> {
+        let value = match self {
+            Self::Length => parse_length_seconds(value)?,
+            _ => value.parse::<f64>().ok()?,
+        };
+        value.is_finite().then_some(value)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SongQueryPredicate {
+    Artist(String),
+    StepArtist(String),
+    Pack(String),
+    Stat {
+        stat: SongQueryStat,
+        cmp: SongQueryCmp,
+        value: f64,
+    },
+    /// Compares grade quality, so `grade<=A` means "A or worse".
+    Grade {
+        cmp: SongQueryCmp,
+        tier: u8,
+    },
+    Played(bool),
+    Favorite,
+    FullCombo(bool),
+}
+
+impl SongQueryPredicate {
+    #[inline(always)]
+    fn is_song_level(&self) -> bool {
+        match self {
+            Self::Artist(_) | Self::Pack(_) => true,
+            Self::Stat { stat, .. } => stat.is_song_level(),
+            _ => false,
+        }
+    }
+
+    #[inline(always)]
+    fn uses_player_state(&self) -> bool {
+        matches!(
+            self,
+            Self::Grade { .. } | Self::Played(_) | Self::Favorite | Self::FullCombo(_)
+        )
+    }
+
+    fn song_matches(&self, song: &SongData, pack_name: &str) -> bool {
+        match self {
+            Self::Artist(needle) => {
+                contains_ignore_ascii_case(&song.artist, needle)
+                    || contains_ignore_ascii_case(&song.translit_artist, needle)
+            }
+            Self::Pack(needle) => contains_ignore_ascii_case(pack_name, needle),
+            Self::Stat { stat, cmp, value } => cmp.test(stat.song_value(song), *value),
+            _ => true,
+        }
+    }
+
+    fn chart_matches(&self, chart: &ChartData, state: SongQueryChartState) -> bool {
+        match self {
+            Self::StepArtist(needle) => contains_ignore_ascii_case(&chart.step_artist, needle),
+            Self::Stat { stat, cmp, value } => cmp.test(stat.chart_value(chart), *value),
+            Self::Grade { cmp, tier } => state.grade_tier.is_some_and(|grade_tier| {
+                cmp.test(grade_quality(grade_tier), grade_quality(*tier))
+            }),
+            Self::Played(played) => state.grade_tier.is_some() == *played,
+            Self::Favorite => state.favorite,
+            Self::FullCombo(full_combo) => state.full_combo == *full_combo,
+            _ => true,
+        }
+    }
+}
+
+/// The local player's standing on one chart.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SongQueryChartState {
+    pub grade_tier: Option<u8>,
+    pub full_combo: bool,
+    pub favorite: bool,
+}
+
+/// Scores and favorites keyed by chart hash, merged across the joined players.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SongQueryPlayerState {
+    charts: HashMap<String, SongQueryChartState>,
+}
+
+impl SongQueryPlayerState {
+    /// Keeps the best grade tier and any full combo seen for `chart_hash`.
+    pub fn record_score(&mut self, chart_hash: &str, grade_tier: u8, full_combo: bool) {
+        let state = self.charts.entry(chart_hash.to_string()).or_default();
+        state.grade_tier = Some(state.grade_tier.map_or(grade_tier, |t| t.min(grade_tier)));
+        state.full_combo |= full_combo;
+    }
+
+    pub fn record_favorite(&mut self, chart_hash: &str) {
+        self.charts
+            .entry(chart_hash.to_string())
+            .or_default()
+            .favorite = true;
+    }
+
+    #[inline(always)]
+    pub fn chart(&self, chart_hash: &str) -> SongQueryChartState {
+        self.charts.get(chart_hash).copied().unwrap_or_default()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.charts.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SongQuery {
+    pub predicates: Vec<SongQueryPredicate>,
+}
+
+impl SongQuery {
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.predicates.is_empty()
+    }
+
+    pub fn uses_player_state(&self) -> bool {
+        self.predicates
+            .iter()
+            .any(SongQueryPredicate::uses_player_state)
+    }
+
+    /// Whether `song` passes every predicate. Chart predicates need one chart of
+    /// `chart_type` (edits included) that satisfies all of them at once.
+    pub fn matches(
+        &self,
+        song: &SongData,
+        pack_name: &str,
+        chart_type: &str,
+        player: &SongQueryPlayerState,
+    ) -> bool {
+        if self.predicates.is_empty() {
+            return true;
+        }
+        if !self
+            .predicates
+            .iter()
+            .filter(|predicate| predicate.is_song_level())
+            .all(|predicate| predicate.song_matches(song, pack_name))
+        {
+            return false;
+        }
+        if self
+            .predicates
+            .iter()
+            .all(SongQueryPredicate::is_song_level)
+        {
+            return true;
+        }
+        song.charts
+            .iter()
+            .filter(|chart| chart.chart_type.eq_ignore_ascii_case(chart_type))
+            .any(|chart| {
+                let state = player.chart(&chart.short_hash);
+                self.predicates
+                    .iter()
+                    .filter(|predicate| !predicate.is_song_level())
+                    .all(|predicate| predicate.chart_matches(chart, state))
+            })
+    }
+}
+
+/// Byte ranges of whitespace-separated tokens; double quotes keep spaces inside
+/// one token, e.g. `artist:"daft punk"`.
+pub fn song_query_token_spans(input: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+    for (ix, ch) in input.char_indices() {
+        if ch == '"' {
+            quoted = !quoted;
+        }
+        if ch.is_whitespace() && !quoted {
+            if let Some(start) = start.take() {
+                spans.push(start..ix);
+            }
+        } else if start.is_none() {
+            start = Some(ix);
+        }
+    }
+    if let Some(start) = start {
+        spans.push(start..input.len());
+    }
+    spans
+}
+
+/// Parses one query token. `None` means the token is plain search text. A range
+/// like `meter:11-13` yields two predicates.
+pub fn parse_song_query_token(token: &str) -> Option<Vec<SongQueryPredicate>> {
+    let token = token.trim();
+    let key_len = token.bytes().take_while(u8::is_ascii_alphanumeric).count();
+    if key_len == 0 {
+        return None;
+    }
+    let key = token[..key_len].to_ascii_lowercase();
+    let rest = &token[key_len..];
+    if rest.is_empty() {
+        return parse_flag(key.as_str()).map(|predicate| vec![predicate]);
+    }
+
+    let (cmp, value) = if let Some(value) = rest.strip_prefix("<=") {
+        (SongQueryCmp::Le, value)
+    } else if let Some(value) = rest.strip_prefix(">=") {
+        (SongQueryCmp::Ge, value)
+    } else if let Some(value) = rest.strip_prefix('<') {
+        (SongQueryCmp::Lt, value)
+    } else if let Some(value) = rest.strip_prefix('>') {
+        (SongQueryCmp::Gt, value)
+    } else if let Some(value) = rest.strip_prefix(':').or_else(|| rest.strip_prefix('=')) {
+        (SongQueryCmp::Eq, value)
+    } else {
+        return None;
+    };
+    let value = value.trim_matches('"').trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    match key.as_str() {
+        "artist" | "stepartist" | "sa" | "pack" | "group" => {
+            if cmp != SongQueryCmp::Eq {
+                return None;
+            }
+            let value = value.to_string();
+            Some(vec![match key.as_str() {
+                "artist" => SongQueryPredicate::Artist(value),
+                "pack" | "group" => SongQueryPredicate::Pack(value),
+                _ => SongQueryPredicate::StepArtist(value),
+            }])
+        }
+        "grade" => {
+            let tier = parse_grade_tier(value)?;
+            Some(vec![SongQueryPredicate::Grade { cmp, tier }])
+        }
+        "fc" => {
+            if cmp != SongQueryCmp::Eq {
+                return None;
+            }
+            let full_combo = parse_yes_no(value)?;
+            Some(vec![SongQueryPredicate::FullCombo(full_combo)])
+        }
+        "played" | "favorite" | "fav" => {
+            if cmp != SongQueryCmp::Eq {
+                return None;
+            }
+            let yes = parse_yes_no(value)?;
+            match key.as_str() {
+                "played" => Some(vec![SongQueryPredicate::Played(yes)]),
+                _ => yes.then(|| vec![SongQueryPredicate::Favorite]),
+            }
+        }
+        _ => {
+            let stat = SongQueryStat::from_key(key.as_str())?;
+            if cmp == SongQueryCmp::Eq
+                && stat != SongQueryStat::Length
+                && let Some((lo, hi)) = value.split_once('-')
+                && !lo.is_empty()
+            {
+                let lo = stat.parse_value(lo)?;
+                let hi = stat.parse_value(hi)?;
+                return Some(vec![
+                    SongQueryPredicate::Stat {
+                        stat,
+                        cmp: SongQueryCmp::Ge,
+                        value: lo.min(hi),
+                    },
+                    SongQueryPredicate::Stat {
+                        stat,
+                        cmp: SongQueryCmp::Le,
+                        value: lo.max(hi),
+                    },
+                ]);
+            }
+            let value = stat.parse_value(value)?;
+            Some(vec![SongQueryPredicate::Stat { stat, cmp, value }])
+        }
+    }
+}
+
+fn parse_flag(key: &str) -> Option<SongQueryPredicate> {
+    Some(match key {
+        "unplayed" => SongQueryPredicate::Played(false),
+        "played" => SongQueryPredicate::Played(true),
+        "favorite" | "fav" => SongQueryPredicate::Favorite,
+        "fc" => SongQueryPredicate::FullCombo(true),
+        _ => return None,
+    })
+}
+
+fn parse_yes_no(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "yes" | "y" | "true" | "1" => Some(true),
+        "no" | "n" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_grade_tier(value: &str) -> Option<u8> {
+    let value = value.to_ascii_lowercase();
+    GRADE_NAMES
+        .iter()
+        .find(|(name, _)| *name == value)
+        .map(|(_, tier)| *tier)
+}
+
+/// `2:30`, `150`, or `150s` as seconds.
+fn parse_length_seconds(value: &str) -> Option<f64> {
+    let value = value.strip_suffix('s').unwrap_or(value);
+    if let Some((minutes, seconds)) = value.split_once(':') {
+        let minutes = minutes.parse::<u32>().ok()?;
+        let seconds = seconds.parse::<f64>().ok()?;
+        return (0.0..60.0)
+            .contains(&seconds)
+            .then(|| f64::from(minutes) * 60.0 + seconds);
+    }
+    value.parse::<f64>().ok()
+}
+
+#[inline(always)]
+fn grade_quality(tier: u8) -> f64 {
+    f64::from(SONG_QUERY_FAILED_TIER.saturating_sub(tier))
+}
+
+#[inline]
+fn contains_ignore_ascii_case(haystack: &str, needle: &str) -> bool {
+    let needle = needle.as_bytes();
+    needle.is_empty()
+        || haystack
+            .as_bytes()
+            .windows(needle.len())
+            .any(|window| window.eq_ignore_ascii_case(needle))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use deadsync_chart::{ArrowStats, StaminaCounts, TechCounts};
+
+    use super::*;
+
+    fn test_chart(hash: &str, meter: u32) -> ChartData {
+        ChartData {
+            chart_type: "dance-single".to_string(),
+            difficulty: "Challenge".to_string(),
+            description: String::new(),
+            chart_name: String::new(),
+            meter,
+            step_artist: "Konami".to_string(),
+            music_path: None,
+            short_hash: hash.to_string(),
+            stats: ArrowStats::default(),
+            tech_counts: TechCounts::default(),
+            mines_nonfake: 0,
+            stamina_counts: StaminaCounts::default(),
+            total_streams: 0,
+            matrix_rating: 0.0,
+            matrix_profile: Box::default(),
+            max_nps: 0.0,
+            sn_detailed_breakdown: String::new(),
+            sn_partial_breakdown: String::new(),
+            sn_simple_breakdown: String::new(),
+            detailed_breakdown: String::new(),
+            partial_breakdown: String::new(),
+            simple_breakdown: String::new(),
+            total_measures: 0,
+            measure_nps_vec: Vec::new(),
+            measure_seconds_vec: Vec::new(),
+            first_second: 0.0,
+            has_note_data: true,
+            has_chart_attacks: false,
+            possible_grade_points: 0,
+            holds_total: 0,
+            rolls_total: 0,
+            mines_total: 0,
+            display_bpm: None,
+            min_bpm: 150.0,
+            max_bpm: 150.0,
+        }
+    }
+
+    fn test_song(charts: Vec<ChartData>) -> SongData {
+        SongData {
+            simfile_path: PathBuf::from("test.sm"),
+            title: "Song".to_string(),
+            subtitle: String::new(),
+            translit_title: String::new(),
+            translit_subtitle: String::new(),
+            artist: "Daft Punk".to_string(),
+            translit_artist: String::new(),
+            genre: String::new(),
+            banner_path: None,
+            background_path: None,
+            background_changes: Vec::new(),
+            background_layer2_changes: Vec::new(),
+            foreground_changes: Vec::new(),
+            background_lua_changes: Vec::new(),
+            foreground_lua_changes: Vec::new(),
+            has_lua: false,
+            cdtitle_path: None,
+            music_path: None,
+            display_bpm: "150".to_string(),
+            offset: 0.0,
+            sample_start: None,
+            sample_length: None,
+            min_bpm: 150.0,
+            max_bpm: 150.0,
+            normalized_bpms: "150".to_string(),
+            music_length_seconds: 125.0,
+            first_second: 0.0,
+            total_length_seconds: 125,
+            precise_last_second_seconds: 125.0,
+            charts,
+        }
+    }
+
+    fn query(text: &str) -> SongQuery {
+        let mut query = SongQuery::default();
+        for span in song_query_token_spans(text) {
+            query.predicates.extend(
+                parse_song_query_token(&text[span]).expect("every token should be a predicate"),
+            );
+        }
+        query
+    }
+
+    #[test]
+    fn tokens_parse_ranges_comparisons_and_flags() {
+        assert_eq!(
+            parse_song_query_token("meter:11-13"),
+            Some(vec![
+                SongQueryPredicate::Stat {
+                    stat: SongQueryStat::Meter,
+                    cmp: SongQueryCmp::Ge,
+                    value: 11.0,
+                },
+                SongQueryPredicate::Stat {
+                    stat: SongQueryStat::Meter,
+                    cmp: SongQueryCmp::Le,
+                    value: 13.0,
+                },
+            ])
+        );
+        assert_eq!(
+            parse_song_query_token("length<2:30"),
+            Some(vec![SongQueryPredicate::Stat {
+                stat: SongQueryStat::Length,
+                cmp: SongQueryCmp::Lt,
+                value: 150.0,
+            }])
+        );
+        assert_eq!(
+            parse_song_query_token("grade<=A"),
+            Some(vec![SongQueryPredicate::Grade {
+                cmp: SongQueryCmp::Le,
+                tier: 9,
+            }])
+        );
+        assert_eq!(
+            parse_song_query_token("fc:no"),
+            Some(vec![SongQueryPredicate::FullCombo(false)])
+        );
+        assert_eq!(
+            parse_song_query_token("unplayed"),
+            Some(vec![SongQueryPredicate::Played(false)])
+        );
+        assert_eq!(
+            parse_song_query_token("artist:\"daft punk\""),
+            Some(vec![SongQueryPredicate::Artist("daft punk".to_string())])
+        );
+        assert_eq!(parse_song_query_token("re:mix"), None);
+        assert_eq!(parse_song_query_token("nps>fast"), None);
+        assert_eq!(parse_song_query_token("meter:"), None);
+    }
+
+    #[test]
+    fn token_spans_keep_quoted_values_together() {
+        let text = "foo artist:\"daft punk\"  nps>8";
+        let tokens: Vec<&str> = song_query_token_spans(text)
+            .into_iter()
+            .map(|span| &text[span])
+            .collect();
+        assert_eq!(tokens, ["foo", "artist:\"daft punk\"", "nps>8"]);
+    }
+
+    #[test]
+    fn chart_predicates_must_hold_on_one_chart() {
+        let mut hard = test_chart("hard", 11);
+        hard.max_nps = 6.0;
+        let mut expert = test_chart("expert", 13);
+        expert.max_nps = 9.5;
+        expert.tech_counts.crossovers = 30;
+        let song = test_song(vec![hard, expert]);
+        let player = SongQueryPlayerState::default();
+
+        assert!(query("meter:12-13 nps>8 crossovers>20").matches(
+            &song,
+            "Pack",
+            "dance-single",
+            &player
+        ));
+        assert!(!query("meter:11 nps>8").matches(&song, "Pack", "dance-single", &player));
+        assert!(query("artist:daft length<2:30").matches(&song, "Pack", "dance-single", &player));
+        assert!(!query("pack:other").matches(&song, "Pack", "dance-single", &player));
+        assert!(!query("meter:13").matches(&song, "Pack", "dance-double", &player));
+    }
+
+    #[test]
+    fn player_predicates_use_merged_scores_and_favorites() {
+        let song = test_song(vec![test_chart("hard", 11), test_chart("expert", 13)]);
+        let mut player = SongQueryPlayerState::default();
+        player.record_score("hard", 9, false);
+        player.record_score("hard", 12, true);
+        player.record_favorite("expert");
+
+        assert_eq!(
+            player.chart("hard"),
+            SongQueryChartState {
+                grade_tier: Some(9),
+                full_combo: true,
+                favorite: false,
+            }
+        );
+        assert!(query("grade<=A meter:11").matches(&song, "", "dance-single", &player));
+        assert!(!query("grade>=S meter:11").matches(&song, "", "dance-single", &player));
+        assert!(query("unplayed favorite").matches(&song, "", "dance-single", &player));
+        assert!(!query("unplayed meter:11").matches(&song, "", "dance-single", &player));
+        assert!(query("fc:no meter:13").matches(&song, "", "dance-single", &player));
+    }
+}
@@ -1,10 +1,12 @@
-use std::cmp::Ordering;
 use std::fmt::Write as _;
-use std::ops::Range;
 use std::sync::Arc;
 
 use deadsync_chart::SongData;
 
+use crate::song_query::{
+    SongQuery, SongQueryPlayerState, parse_song_query_token, song_query_token_spans,
+};
+
 #[derive(Clone, Debug)]
 pub struct SongSearchCandidate {
     pub pack_name: Arc<str>,
@@ -15,29 +17,12 @@ pub struct SongSearchCandidate {
     pub song: Arc<SongData>,
 }
 
-#[derive(Clone)]
-pub enum SongSearchCatalogEntry<'a> {
-    PackHeader(&'a str),
-    Song(&'a Arc<SongData>),
-}
-
 #[derive(Default)]
 struct SongSearchFilter {
     terms: String,
-    pack_term: Option<Range<usize>>,
-    song_term: Option<Range<usize>>,
     difficulty: Option<u8>,
     bpm_tier: Option<i32>,
-}
-
-impl SongSearchFilter {
-    fn pack_term(&self) -> Option<&str> {
-        self.terms.get(self.pack_term.as_ref()?.clone())
-    }
-
-    fn song_term(&self) -> Option<&str> {
-        self.terms.get(self.song_term.as_ref()?.clone())
-    }
+    query: SongQuery,
 }
 
 #[inline(always)]
@@ -93,44 +78,74 @@ fn parse_song_search_filter(input: &str) -> SongSearchFilter {
         }
         stripped.push(ch);
     }
-
-    let term_start = stripped.len() - stripped.trim_start().len();
-    let term_end = stripped.trim_end().len();
-    if term_start < term_end {
-        let terms = &stripped[term_start..term_end];
-        if let Some(slash) = terms.find('/') {
-            if slash > 0 {
-                filter.pack_term = Some(term_start..term_start + slash);
-            }
-            let song_start = term_start + slash + 1;
-            if song_start < term_end {
-                filter.song_term = Some(song_start..term_end);
-            }
-        } else {
-            filter.song_term = Some(term_start..term_end);
-        }
-    }
+    strip_song_query_tokens(&mut stripped, &mut filter.query);
     filter.terms = stripped;
     filter
 }
 
-/// Free text with `[###]` tokens stripped, plus the filters they produced.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+/// Moves query-language tokens (see [`crate::song_query`]) out of `text`,
+/// leaving the surrounding whitespace like the `[###]` tokens do.
+fn strip_song_query_tokens(text: &mut String, query: &mut SongQuery) {
+    let mut kept: Option<String> = None;
+    let mut last = 0;
+    for span in song_query_token_spans(text) {
+        let Some(predicates) = parse_song_query_token(&text[span.clone()]) else {
+            continue;
+        };
+        let kept = kept.get_or_insert_with(|| String::with_capacity(text.len()));
+        kept.push_str(&text[last..span.start]);
+        last = span.end;
+        query.predicates.extend(predicates);
+    }
+    if let Some(mut kept) = kept {
+        kept.push_str(&text[last..]);
+        *text = kept;
+    }
+}
+
+/// The query tokens in `input`, in their original spelling, so a completed
+/// query can carry them over verbatim.
+pub fn song_search_query_tokens(input: &str) -> impl Iterator<Item = &str> {
+    song_query_token_spans(input)
+        .into_iter()
+        .map(|span| &input[span])
+        .filter(|token| parse_song_query_token(token).is_some())
+}
+
+/// Free text with `[###]` and query tokens stripped, plus the filters they
+/// produced.
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct SongSearchLiveQuery {
     pub text: String,
     pub difficulty: Option<u8>,
     pub bpm_tier: Option<i32>,
+    pub query: SongQuery,
 }
 
 /// Parse a typeahead query, splitting `[###]` filters from the free text.
 ///
-/// The `pack/song` split is deliberately not applied: packs have their own scope.
+/// A `/` stays in the free text: packs have their own scope.
 pub fn parse_song_search_live(input: &str) -> SongSearchLiveQuery {
     let filter = parse_song_search_filter(input);
     SongSearchLiveQuery {
         text: filter.terms.trim().to_string(),
         difficulty: filter.difficulty,
         bpm_tier: filter.bpm_tier,
+        query: filter.query,
+    }
+}
+
+impl SongSearchLiveQuery {
+    /// [`song_passes_search_filters`] plus the query-language predicates.
+    pub fn song_passes(
+        &self,
+        song: &SongData,
+        pack_name: &str,
+        chart_type: &str,
+        player: &SongQueryPlayerState,
+    ) -> bool {
+        song_passes_search_filters(song, chart_type, self.difficulty, self.bpm_tier)
+            && self.query.matches(song, pack_name, chart_type, player)
     }
 }
 
@@ -181,164 +196,6 @@ pub fn song_passes_search_filters(
     true
 }
 
-#[inline]
-fn contains_ignore_ascii_case(haystack: &str, needle: &str) -> bool {
-    let needle = needle.as_bytes();
-    needle.is_empty()
-        || haystack
-            .as_bytes()
-            .windows(needle.len())
-            .any(|window| window.eq_ignore_ascii_case(needle))
-}
-
-#[inline]
-fn joined_contains_ignore_ascii_case(left: &str, right: &str, needle: &str) -> bool {
-    let right = (!right.trim().is_empty()).then_some(right);
-    let joined_len = left
-        .len()
-        .saturating_add(right.map_or(0, |value| value.len().saturating_add(1)));
-    let needle = needle.as_bytes();
-    if needle.is_empty() {
-        return true;
-    }
-    if needle.len() > joined_len {
-        return false;
-    }
-
-    let left = left.as_bytes();
-    let right = right.map(str::as_bytes).unwrap_or_default();
-    (0..=joined_len - needle.len()).any(|start| {
-        needle.iter().enumerate().all(|(offset, expected)| {
-            let index = start + offset;
-            let actual = if index < left.len() {
-                left[index]
-            } else if index == left.len() {
-                b' '
-            } else {
-                right[index - left.len() - 1]
-            };
-            actual.eq_ignore_ascii_case(expected)
-        })
-    })
-}
-
-#[inline]
-fn song_title_contains(song: &SongData, translit: bool, needle: &str) -> bool {
-    joined_contains_ignore_ascii_case(
-        song.display_title(translit),
-        song.display_subtitle(translit),
-        needle,
-    )
-}
-
-#[inline]
-fn lowercase_full_title_bytes(song: &SongData) -> impl Iterator<Item = u8> + '_ {
-    let subtitle = song.display_subtitle(false);
-    let has_subtitle = !subtitle.trim().is_empty();
-    song.display_title(false)
-        .bytes()
-        .chain(has_subtitle.then_some(b' '))
-        .chain(if has_subtitle { subtitle } else { "" }.bytes())
-        .map(|byte| byte.to_ascii_lowercase())
-}
-
-#[inline]
-fn display_full_title_cmp(left: &SongData, right: &SongData) -> Ordering {
-    lowercase_full_title_bytes(left).cmp(lowercase_full_title_bytes(right))
-}
-
-fn sort_song_search_candidates(candidates: &mut [SongSearchCandidate]) {
-    candidates.sort_by(|left, right| display_full_title_cmp(&left.song, &right.song));
-}
-
-pub fn build_song_search_candidates<'a>(
-    entries: impl IntoIterator<Item = SongSearchCatalogEntry<'a>>,
-    search_text: &str,
-    chart_type: &str,
-) -> Vec<SongSearchCandidate> {
-    let filter = parse_song_search_filter(search_text);
-    let entries = entries.into_iter();
-    let (entry_count, upper) = entries.size_hint();
-    let entry_count = upper.unwrap_or(entry_count);
-    let mut out = Vec::with_capacity(entry_count);
-    let mut current_pack_name: Option<&str> = None;
-    let mut current_pack_shared: Option<Arc<str>> = None;
-
-    for entry in entries {
-        match entry {
-            SongSearchCatalogEntry::PackHeader(name) => {
-                current_pack_name = Some(name);
-                current_pack_shared = None;
-            }
-            SongSearchCatalogEntry::Song(song) => {
-                if !song
-                    .charts
-                    .iter()
-                    .any(|c| c.chart_type.eq_ignore_ascii_case(chart_type))
-                {
-                    continue;
-                }
-
-                let pack_name = current_pack_name.unwrap_or_default();
-                if let Some(pack_term) = filter.pack_term()
-                    && !contains_ignore_ascii_case(pack_name, pack_term)
-                {
-                    continue;
-                }
-
-                if let Some(song_term) = filter.song_term()
-                    && !song_title_contains(song, false, song_term)
-                    && !song_title_contains(song, true, song_term)
-                {
-                    continue;
-                }
-
-                if let Some(diff) = filter.difficulty
-                    && !song.charts.iter().any(|c| {
-                        c.chart_type.eq_ignore_ascii_case(chart_type)
-                            && !c.difficulty.eq_ignore_ascii_case("edit")
-                            && c.meter == diff as u32
-                    })
-                {
-                    continue;
-                }
-
-                if let Some(want_tier) = filter.bpm_tier {
-                    let Some((bpm_lo, bpm_hi)) = song.display_bpm_range() else {
-                        continue;
-                    };
-                    let mut lo = song_search_bpm_tier(bpm_lo);
-                    let mut hi = song_search_bpm_tier(bpm_hi);
-                    if lo > hi {
-                        std::mem::swap(&mut lo, &mut hi);
-                    }
-                    if lo == hi {
-                        if want_tier != lo {
-                            continue;
-                        }
-                    } else if want_tier < lo || want_tier > hi {
-                        continue;
-                    }
-                }
-
-                let pack_name =
-                    Arc::clone(current_pack_shared.get_or_insert_with(|| Arc::from(pack_name)));
-                out.push(SongSearchCandidate {
-                    pack_name,
-                    title: Arc::from(song.display_title(false)),
-                    subtitle: Arc::from(song.display_subtitle(false)),
-                    bpm: Arc::from(song.formatted_chart_display_bpm(None)),
-                    difficulties: Arc::from(song_search_difficulties_text(song, chart_type)),
-                    song: Arc::clone(song),
-                });
-            }
-        }
-    }
-    sort_song_search_candidates(&mut out);
-
-    out
-}
-
 #[cfg(test)]
 mod tests {
     use std::{path::PathBuf, sync::Arc};
@@ -422,123 +279,32 @@ mod tests {
         }
     }
 
-    fn test_song_with_bpm(
-        title: &str,
-        display_bpm: &str,
-        min_bpm: f64,
-        max_bpm: f64,
-    ) -> Arc<SongData> {
-        let mut song = (*test_song(title, "")).clone();
-        song.display_bpm = display_bpm.to_string();
-        song.min_bpm = min_bpm;
-        song.max_bpm = max_bpm;
-        song.charts = vec![test_chart("dance-single"), test_chart("dance-double")];
-        Arc::new(song)
-    }
-
-    #[test]
-    fn bpm_filter_uses_display_bpm_range() {
-        let slow = test_song_with_bpm("Slow", "128", 128.0, 128.0);
-        let range = test_song_with_bpm("Range", "120:180", 120.0, 180.0);
-        let entries = [
-            SongSearchCatalogEntry::PackHeader("Pack"),
-            SongSearchCatalogEntry::Song(&slow),
-            SongSearchCatalogEntry::Song(&range),
-        ];
-
-        let candidates = build_song_search_candidates(entries, "[180]", "dance-single");
-
-        assert_eq!(candidates.len(), 1);
-        assert_eq!(candidates[0].song.title, "Range");
-    }
-
     #[test]
     fn search_filter_extracts_tokens_without_changing_term_text() {
-        let filter = parse_song_search_filter("  FINALs/[12]SoNG [180] Mix  ");
+        let live = parse_song_search_live("  FINALs/[12]SoNG [180] Mix  ");
 
-        assert_eq!(filter.pack_term(), Some("finals"));
-        assert_eq!(filter.song_term(), Some("song  mix"));
-        assert_eq!(filter.difficulty, Some(12));
-        assert_eq!(filter.bpm_tier, Some(180));
+        assert_eq!(live.text, "finals/song  mix");
+        assert_eq!(live.difficulty, Some(12));
+        assert_eq!(live.bpm_tier, Some(180));
 
-        let malformed = parse_song_search_filter("Pack/[x]ÄBC");
-        assert_eq!(malformed.pack_term(), Some("pack"));
-        assert_eq!(malformed.song_term(), Some("[x]Äbc"));
+        let malformed = parse_song_search_live("Pack/[x]ÄBC");
+        assert_eq!(malformed.text, "pack/[x]Äbc");
         assert_eq!(malformed.difficulty, None);
         assert_eq!(malformed.bpm_tier, None);
 
-        let saturated = parse_song_search_filter("[999999999999999999999999]Overflow");
+        let saturated = parse_song_search_live("[999999999999999999999999]Overflow");
         assert_eq!(saturated.bpm_tier, Some(i32::MAX));
     }
 
     #[test]
-    fn pack_and_song_terms_filter_candidates() {
-        let alpha = test_song_with_bpm("Alpha", "128", 128.0, 128.0);
-        let beta = test_song_with_bpm("Beta", "128", 128.0, 128.0);
-        let entries = [
-            SongSearchCatalogEntry::PackHeader("Warmups"),
-            SongSearchCatalogEntry::Song(&alpha),
-            SongSearchCatalogEntry::PackHeader("Finals"),
-            SongSearchCatalogEntry::Song(&beta),
-        ];
-
-        let candidates = build_song_search_candidates(entries, "warm/alpha", "dance-single");
-
-        assert_eq!(candidates.len(), 1);
-        assert_eq!(candidates[0].pack_name.as_ref(), "Warmups");
-        assert_eq!(candidates[0].song.title, "Alpha");
-    }
-
-    #[test]
-    fn candidates_prepare_display_text_and_share_pack_storage() {
-        let alpha = test_song_with_bpm("Alpha", "128", 128.0, 128.0);
-        let beta = test_song_with_bpm("Beta", "128", 128.0, 128.0);
-        let entries = [
-            SongSearchCatalogEntry::PackHeader("Warmups"),
-            SongSearchCatalogEntry::Song(&alpha),
-            SongSearchCatalogEntry::Song(&beta),
-        ];
-
-        let candidates = build_song_search_candidates(entries, "", "dance-single");
-
-        assert_eq!(candidates.len(), 2);
-        assert!(Arc::ptr_eq(
-            &candidates[0].pack_name,
-            &candidates[1].pack_name
-        ));
-        for candidate in &candidates {
-            assert_eq!(
-                candidate.title.as_ref(),
-                candidate.song.display_title(false)
-            );
-            assert_eq!(
-                candidate.subtitle.as_ref(),
-                candidate.song.display_subtitle(false)
-            );
-            assert_eq!(
-                candidate.bpm.as_ref(),
-                candidate.song.formatted_chart_display_bpm(None)
-            );
-            assert_eq!(candidate.difficulties.as_ref(), "12");
-        }
-    }
-
-    #[test]
-    fn difficulty_filter_ignores_edits() {
-        let mut chart = test_chart("dance-single");
-        chart.difficulty = "Edit".to_string();
-        chart.meter = 12;
-        let mut song = (*test_song("Edit Only", "")).clone();
-        song.charts = vec![chart];
-        let song = Arc::new(song);
-        let entries = [
-            SongSearchCatalogEntry::PackHeader("Pack"),
-            SongSearchCatalogEntry::Song(&song),
-        ];
-
-        let candidates = build_song_search_candidates(entries, "[12]", "dance-single");
-
-        assert!(candidates.is_empty());
+    fn query_tokens_leave_free_text() {
+        let live = parse_song_search_live("Alpha nps>8 artist:\"Daft Punk\"");
+        assert_eq!(live.text, "alpha");
+        assert_eq!(live.query.predicates.len(), 2);
+        assert_eq!(
+            song_search_query_tokens("Alpha nps>8 re:mix").collect::<Vec<_>>(),
+            ["nps>8"]
+        );
     }
 
     #[test]
@@ -560,64 +326,4 @@ mod tests {
         song.charts.clear();
         assert_eq!(song_search_difficulties_text(&song, "dance-single"), "-");
     }
-
-    #[test]
-    fn song_term_matches_ascii_case_and_title_subtitle_boundary() {
-        let song = test_song_with_bpm("Alpha", "128", 128.0, 128.0);
-        let mut with_subtitle = (*song).clone();
-        with_subtitle.subtitle = "Mix".to_string();
-        let with_subtitle = Arc::new(with_subtitle);
-        let entries = [
-            SongSearchCatalogEntry::PackHeader("WarmUps"),
-            SongSearchCatalogEntry::Song(&with_subtitle),
-        ];
-
-        let candidates = build_song_search_candidates(entries, "warmups/HA mI", "dance-single");
-
-        assert_eq!(candidates.len(), 1);
-        assert_eq!(candidates[0].song.title, "Alpha");
-    }
-
-    #[test]
-    fn candidates_sort_by_lowercase_full_title_and_keep_ties_stable() {
-        let beta = test_song_with_bpm("beta", "128", 128.0, 128.0);
-
-        let mut alpha_z = (*test_song_with_bpm("Alpha", "128", 128.0, 128.0)).clone();
-        alpha_z.subtitle = "Zoo".to_string();
-        let alpha_z = Arc::new(alpha_z);
-
-        let alpha_mix_title = test_song_with_bpm("ALPHA MIX", "128", 128.0, 128.0);
-        let mut alpha_mix_parts = (*test_song_with_bpm("Alpha", "128", 128.0, 128.0)).clone();
-        alpha_mix_parts.subtitle = "Mix".to_string();
-        let alpha_mix_parts = Arc::new(alpha_mix_parts);
-
-        let entries = [
-            SongSearchCatalogEntry::PackHeader("Pack"),
-            SongSearchCatalogEntry::Song(&beta),
-            SongSearchCatalogEntry::Song(&alpha_mix_title),
-            SongSearchCatalogEntry::Song(&alpha_z),
-            SongSearchCatalogEntry::Song(&alpha_mix_parts),
-        ];
-
-        let candidates = build_song_search_candidates(entries, "", "dance-single");
-        let titles = candidates
-            .iter()
-            .map(|candidate| {
-                (
-                    candidate.song.title.as_str(),
-                    candidate.song.subtitle.as_str(),
-                )
-            })
-            .collect::<Vec<_>>();
-
-        assert_eq!(
-            titles,
-            [
-                ("ALPHA MIX", ""),
-                ("Alpha", "Mix"),
-                ("Alpha", "Zoo"),
-                ("beta", ""),
-            ]
-        );
-    }
 }
@@ -1086,6 +1086,7 @@ fn dispatch_event(shared: &SmxShared, event: SmxEvent) {
                 id: pad_device_id(pad),
                 vendor_id: Some(SMX_USB_VENDOR_ID),
                 product_id: Some(SMX_USB_PRODUCT_ID),
+                uuid: *shared.uuid[pad].lock().unwrap(),
                 backend: PadBackend::Smx,
                 initial: false,
             };
@@ -0,0 +1,125 @@
+use crate::SongLuaCompileInfo;
+use std::path::PathBuf;
+
+/// Coarse outcome of a headless compile, ordered so `max` picks the worst.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SongLuaCompatibilityLevel {
+    Supported,
+    Degraded,
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SongLuaCompatibility {
+    Supported,
+    /// Compiled, but these constructs are dropped when the song plays.
+    Degraded(Vec<String>),
+    Failed(String),
+}
+
+impl SongLuaCompatibility {
+    pub fn from_compile_info(info: &SongLuaCompileInfo) -> Self {
+        let mut dropped = Vec::new();
+        push_dropped(
+            &mut dropped,
+            info.unsupported_perframes,
+            &info.unsupported_perframe_captures,
+            "perframe",
+        );
+        push_dropped(
+            &mut dropped,
+            info.unsupported_function_eases,
+            &info.unsupported_function_ease_captures,
+            "function ease",
+        );
+        push_dropped(
+            &mut dropped,
+            info.unsupported_function_actions,
+            &info.unsupported_function_action_captures,
+            "function action",
+        );
+        dropped.extend(info.skipped_message_command_captures.iter().cloned());
+        if dropped.is_empty() {
+            Self::Supported
+        } else {
+            Self::Degraded(dropped)
+        }
+    }
+
+    pub fn from_compile_result<T>(
+        result: &Result<T, String>,
+        info: impl FnOnce(&T) -> &SongLuaCompileInfo,
+    ) -> Self {
+        match result {
+            Ok(compiled) => Self::from_compile_info(info(compiled)),
+            Err(error) => Self::Failed(error.clone()),
+        }
+    }
+
+    #[inline(always)]
+    pub const fn level(&self) -> SongLuaCompatibilityLevel {
+        match self {
+            Self::Supported => SongLuaCompatibilityLevel::Supported,
+            Self::Degraded(_) => SongLuaCompatibilityLevel::Degraded,
+            Self::Failed(_) => SongLuaCompatibilityLevel::Failed,
+        }
+    }
+}
+
+/// Captures are deduplicated, so they only stand in for the count when at
+/// least one was recorded.
+fn push_dropped(out: &mut Vec<String>, count: usize, captures: &[String], label: &str) {
+    if captures.is_empty() {
+        if count > 0 {
+            out.push(format!("{count} unsupported {label}(s)"));
+        }
+    } else {
+        out.extend(captures.iter().cloned());
+    }
+}
+
+/// One song-lua entry point of a song: a foreground or background change.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SongLuaScanEntry {
+    pub path: PathBuf,
+    pub start_beat: f32,
+    pub compatibility: SongLuaCompatibility,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SongLuaScanSong {
+    pub simfile_path: PathBuf,
+    pub title: String,
+    pub entries: Vec<SongLuaScanEntry>,
+}
+
+impl SongLuaScanSong {
+    pub fn level(&self) -> SongLuaCompatibilityLevel {
+        self.entries
+            .iter()
+            .map(|entry| entry.compatibility.level())
+            .max()
+            .unwrap_or(SongLuaCompatibilityLevel::Supported)
+    }
+}
+
+/// Per-song compatibility for every song-lua chart in one pack.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SongLuaScanReport {
+    pub pack_dir: PathBuf,
+    pub pack_name: String,
+    pub songs_checked: usize,
+    /// Songs with at least one lua entry point, in pack order.
+    pub songs: Vec<SongLuaScanSong>,
+    /// Simfiles that could not be read or parsed.
+    pub failures: Vec<(PathBuf, String)>,
+}
+
+impl SongLuaScanReport {
+    pub fn count(&self, level: SongLuaCompatibilityLevel) -> usize {
+        self.songs
+            .iter()
+            .filter(|song| song.level() == level)
+            .count()
+    }
+}
@@ -2,11 +2,13 @@ use deadlib_present::actors::TextAttribute;
 use mlua::{Lua, Table};
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::{
     CompiledSongLua, SongLuaCompileContext, SongLuaCompileTimer, SongLuaHostState,
-    SongLuaNoteskinResolver, SongLuaOverlayActor, SongLuaOverlayKind, SongLuaOverlayModelLayer,
-    SongLuaOverlayState, SongLuaTimeUnit, SongLuaTrackedActorTarget as TrackedCompileActorTarget,
+    SongLuaLiveBaseline, SongLuaLiveRuntime, SongLuaNoteskinResolver, SongLuaOverlayActor,
+    SongLuaOverlayKind, SongLuaOverlayModelLayer, SongLuaOverlayState, SongLuaTimeUnit,
+    SongLuaTrackedActorTarget as TrackedCompileActorTarget,
     add_actor_child_from_path as add_host_actor_child_from_path,
     compile_multitap_update_overlays_for_actors, compile_perframes, compile_update_functions,
     create_dummy_actor as create_host_dummy_actor,
@@ -17,12 +19,14 @@ use crate::{
     push_unique_compile_detail, read_actor_model_layers, read_eases_for_overlay_actors,
     read_global_function_nested_tables, read_mod_windows, read_note_column_zoom_hides,
     read_noteskin_tap_actor_slots, read_overlay_compile_actor_actions, read_overlay_compile_actors,
-    read_runtime_mod_eases, read_song_lua_sound_paths, read_tracked_compile_actors,
-    read_update_function_nested_tables, read_update_function_overlay_compile_actor_actions,
-    read_update_function_tables, read_xero_runtime_mod_eases_for_overlay_actors,
-    register_loaded_easing_names, restore_compile_globals, run_actor_draw_functions,
-    run_actor_init_commands, run_actor_startup_commands, run_actor_update_functions,
+    read_perframe_entries, read_runtime_mod_eases, read_song_lua_sound_paths,
+    read_tracked_compile_actors, read_update_function_nested_tables,
+    read_update_function_overlay_compile_actor_actions, read_update_function_tables,
+    read_xero_runtime_mod_eases_for_overlay_actors, register_loaded_easing_names,
+    restore_compile_globals, run_actor_draw_functions, run_actor_init_commands,
+    run_actor_startup_commands, run_actor_update_functions,
     runtime_static_overlay_index_for_actors, snapshot_compile_globals, sort_compiled_song_lua,
+    tracked_player_tables, update_player_option_tables,
 };
 
 pub fn compile_song_lua_with_default_host<NoteskinSlot, ModelVertex, MultitapArrowVisualSpec>(
@@ -60,6 +64,48 @@ where
     )
 }
 
+/// [`compile_song_lua_with_default_host`] with a live runtime; see
+/// [`compile_live_song_lua_with_actors`].
+pub fn compile_live_song_lua_with_default_host<NoteskinSlot, ModelVertex, MultitapArrowVisualSpec>(
+    entry_path: &Path,
+    context: &SongLuaCompileContext,
+    noteskin_resolver: SongLuaNoteskinResolver,
+    read_model_slots: fn(&Path) -> Result<Arc<[NoteskinSlot]>, String>,
+    model_layer_from_slot: fn(&NoteskinSlot) -> Option<SongLuaOverlayModelLayer<ModelVertex>>,
+    multitap_arrow_visual_spec: MultitapArrowVisualSpec,
+    budget: Duration,
+) -> Result<
+    (
+        CompiledSongLua<
+            SongLuaOverlayActor<SongLuaOverlayKind<NoteskinSlot, ModelVertex, TextAttribute>>,
+        >,
+        Option<SongLuaLiveRuntime>,
+    ),
+    String,
+>
+where
+    MultitapArrowVisualSpec: FnMut(
+        &SongLuaCompileContext,
+        &str,
+    ) -> Option<(
+        SongLuaOverlayKind<NoteskinSlot, ModelVertex, TextAttribute>,
+        SongLuaOverlayState,
+    )>,
+{
+    compile_live_song_lua_with_actors(
+        entry_path,
+        context,
+        noteskin_resolver,
+        create_default_dummy_actor,
+        create_default_named_child_actor,
+        install_default_actor_methods,
+        read_model_slots,
+        model_layer_from_slot,
+        multitap_arrow_visual_spec,
+        budget,
+    )
+}
+
 fn create_default_named_child_actor(lua: &Lua, parent: &Table, name: &str) -> mlua::Result<Table> {
     create_host_named_child_actor(
         lua,
@@ -102,13 +148,103 @@ pub fn compile_song_lua_with_actors<NoteskinSlot, ModelVertex, MultitapArrowVisu
     install_actor_methods: fn(&Lua, &Table) -> mlua::Result<()>,
     read_model_slots: fn(&Path) -> Result<Arc<[NoteskinSlot]>, String>,
     model_layer_from_slot: fn(&NoteskinSlot) -> Option<SongLuaOverlayModelLayer<ModelVertex>>,
-    mut multitap_arrow_visual_spec: MultitapArrowVisualSpec,
+    multitap_arrow_visual_spec: MultitapArrowVisualSpec,
 ) -> Result<
     CompiledSongLua<
         SongLuaOverlayActor<SongLuaOverlayKind<NoteskinSlot, ModelVertex, TextAttribute>>,
     >,
     String,
 >
+where
+    MultitapArrowVisualSpec: FnMut(
+        &SongLuaCompileContext,
+        &str,
+    ) -> Option<(
+        SongLuaOverlayKind<NoteskinSlot, ModelVertex, TextAttribute>,
+        SongLuaOverlayState,
+    )>,
+{
+    compile_song_lua_parts(
+        entry_path,
+        context,
+        noteskin_resolver,
+        create_dummy_actor,
+        create_named_child_actor,
+        install_actor_methods,
+        read_model_slots,
+        model_layer_from_slot,
+        multitap_arrow_visual_spec,
+        None,
+    )
+    .map(|(compiled, _)| compiled)
+}
+
+/// Like [`compile_song_lua_with_actors`], but keeps the Lua state alive for
+/// callbacks compile-time sampling could not bake. The runtime is `None`
+/// when nothing needs live playback.
+pub fn compile_live_song_lua_with_actors<NoteskinSlot, ModelVertex, MultitapArrowVisualSpec>(
+    entry_path: &Path,
+    context: &SongLuaCompileContext,
+    noteskin_resolver: SongLuaNoteskinResolver,
+    create_dummy_actor: fn(&Lua, &'static str) -> mlua::Result<Table>,
+    create_named_child_actor: fn(&Lua, &Table, &str) -> mlua::Result<Table>,
+    install_actor_methods: fn(&Lua, &Table) -> mlua::Result<()>,
+    read_model_slots: fn(&Path) -> Result<Arc<[NoteskinSlot]>, String>,
+    model_layer_from_slot: fn(&NoteskinSlot) -> Option<SongLuaOverlayModelLayer<ModelVertex>>,
+    multitap_arrow_visual_spec: MultitapArrowVisualSpec,
+    budget: Duration,
+) -> Result<
+    (
+        CompiledSongLua<
+            SongLuaOverlayActor<SongLuaOverlayKind<NoteskinSlot, ModelVertex, TextAttribute>>,
+        >,
+        Option<SongLuaLiveRuntime>,
+    ),
+    String,
+>
+where
+    MultitapArrowVisualSpec: FnMut(
+        &SongLuaCompileContext,
+        &str,
+    ) -> Option<(
+        SongLuaOverlayKind<NoteskinSlot, ModelVertex, TextAttribute>,
+        SongLuaOverlayState,
+    )>,
+{
+    compile_song_lua_parts(
+        entry_path,
+        context,
+        noteskin_resolver,
+        create_dummy_actor,
+        create_named_child_actor,
+        install_actor_methods,
+        read_model_slots,
+        model_layer_from_slot,
+        multitap_arrow_visual_spec,
+        Some(budget),
+    )
+}
+
+fn compile_song_lua_parts<NoteskinSlot, ModelVertex, MultitapArrowVisualSpec>(
+    entry_path: &Path,
+    context: &SongLuaCompileContext,
+    noteskin_resolver: SongLuaNoteskinResolver,
+    create_dummy_actor: fn(&Lua, &'static str) -> mlua::Result<Table>,
+    create_named_child_actor: fn(&Lua, &Table, &str) -> mlua::Result<Table>,
+    install_actor_methods: fn(&Lua, &Table) -> mlua::Result<()>,
+    read_model_slots: fn(&Path) -> Result<Arc<[NoteskinSlot]>, String>,
+    model_layer_from_slot: fn(&NoteskinSlot) -> Option<SongLuaOverlayModelLayer<ModelVertex>>,
+    mut multitap_arrow_visual_spec: MultitapArrowVisualSpec,
+    live_budget: Option<Duration>,
+) -> Result<
+    (
+        CompiledSongLua<
+            SongLuaOverlayActor<SongLuaOverlayKind<NoteskinSlot, ModelVertex, TextAttribute>>,
+        >,
+        Option<SongLuaLiveRuntime>,
+    ),
+    String,
+>
 where
     MultitapArrowVisualSpec: FnMut(
         &SongLuaCompileContext,
@@ -192,6 +328,15 @@ where
             .get(player)
             .is_some_and(|tracked| !tracked.actor.initial_state.visible)
     });
+    let live_players = match live_budget {
+        Some(_) => {
+            let player_tables = tracked_player_tables(&tracked_actors);
+            let option_tables = update_player_option_tables(&lua)?;
+            let baseline = SongLuaLiveBaseline::capture(&player_tables, &option_tables)?;
+            Some((player_tables, option_tables, baseline))
+        }
+        None => None,
+    };
     let mut overlay_trigger_counter = 0usize;
     let prefix_perframes = globals
         .get::<Option<Table>>("prefix_globals")
@@ -325,12 +470,17 @@ where
     compile_timer.push_stage("update_actions");
     let (perframe_eases, perframe_overlay_eases, perframe_info) = compile_perframes(
         &lua,
-        prefix_perframes,
-        global_perframes,
+        prefix_perframes.clone(),
+        global_perframes.clone(),
         context,
         &mut overlays,
         &tracked_actors,
     )?;
+    let mut live_perframes = Vec::new();
+    if live_budget.is_some() && perframe_info.unsupported_perframes > 0 {
+        live_perframes = read_perframe_entries(prefix_perframes)?;
+        live_perframes.extend(read_perframe_entries(global_perframes)?);
+    }
     out.eases.extend(perframe_eases);
     out.overlay_eases.extend(perframe_overlay_eases);
     merge_compile_info(&mut out.info, perframe_info);
@@ -366,10 +516,21 @@ where
             .iter()
             .map(|overlay| overlay.actor.message_commands.as_slice()),
     );
+    let live_capture_tables = if live_players.is_some() {
+        overlays
+            .iter()
+            .map(|overlay| overlay.table.clone())
+            .chain(tracked_actors.iter().map(|tracked| tracked.table.clone()))
+            .enumerate()
+            .collect()
+    } else {
+        Vec::new()
+    };
     out.overlays = overlays.into_iter().map(|overlay| overlay.actor).collect();
     for tracked in tracked_actors {
         match tracked.target {
             TrackedCompileActorTarget::Player(player) => out.player_actors[player] = tracked.actor,
+            TrackedCompileActorTarget::SongBackground => out.song_background = tracked.actor,
             TrackedCompileActorTarget::SongForeground => out.song_foreground = tracked.actor,
         }
     }
@@ -379,5 +540,17 @@ where
     out.sound_paths = read_song_lua_sound_paths(&lua)?;
     compile_timer.push_stage("finalize");
     log_song_lua_compile_timing(&trace_entry_path, &compile_timer);
-    Ok(out)
+    let live = match (live_budget, live_players) {
+        (Some(budget), Some((player_tables, option_tables, baseline))) => SongLuaLiveRuntime::new(
+            lua,
+            live_perframes,
+            player_tables,
+            option_tables,
+            live_capture_tables,
+            baseline,
+            budget,
+        )?,
+        _ => None,
+    };
+    Ok((out, live))
 }
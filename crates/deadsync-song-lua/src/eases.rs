@@ -9,7 +9,7 @@ use crate::{
     SongLuaOverlayCompileActor, SongLuaOverlayEase, SongLuaSpanMode, SongLuaTimeUnit,
     capture_overlay_compile_actor_function_eases, compile_note_column_pos_function_ease,
     probe_function_ease_target, read_easing_name, read_f32, read_player, read_span_mode,
-    record_unsupported_function_ease_capture,
+    record_unsupported_function_ease_capture, stash_live_function_ease,
 };
 
 pub struct SongLuaReadEasesResult {
@@ -338,6 +338,9 @@ fn capture_function_ease<Kind>(
                     &probe_methods,
                 );
                 debug!("Unsupported song lua function ease capture: {detail}");
+                if let Err(err) = stash_live_function_ease(lua, &input) {
+                    debug!("Failed to keep song lua function ease for live playback: {err}");
+                }
                 Ok(SongLuaFunctionEaseResult {
                     decision: SongLuaFunctionEaseDecision::Skip,
                     stats,
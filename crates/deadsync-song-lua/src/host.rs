@@ -1,8 +1,10 @@
 use std::collections::HashMap;
 use std::ffi::c_void;
 use std::path::Path;
+use std::sync::Arc;
 
 use chrono::{Datelike, Local, Timelike};
+use deadlib_render_core::{ShaderParam, ShaderProgram, ShaderSources, register_shader_program};
 use mlua::{Function, Lua, MultiValue, Table, Value};
 
 use crate::{
@@ -111,6 +113,7 @@ fn install_compile_globals(
         top_screen.players.clone(),
     )?;
     install_sound_globals(lua, context.song_dir.as_path())?;
+    install_shader_globals(lua, context.song_dir.as_path())?;
     install_message_manager_globals(lua, broadcast_song_lua_message)?;
     install_late_globals(lua, context)?;
     Ok(())
@@ -1019,6 +1022,48 @@ pub fn install_sound_globals(lua: &Lua, song_dir: &Path) -> mlua::Result<()> {
     Ok(())
 }
 
+/// `RegisterShaderEffect(name, {Params={{"Amount", 1}}, Wgsl=path, Glsl=path})`
+/// adds a user fragment program that `SetShaderEffect(name, ...)` can then
+/// request. Sources resolve like script paths; backends that lack a source
+/// for their language draw the effect unchanged.
+pub fn install_shader_globals(lua: &Lua, song_dir: &Path) -> mlua::Result<()> {
+    let song_dir = song_dir.to_path_buf();
+    lua.globals().set(
+        "RegisterShaderEffect",
+        lua.create_function(move |lua, (name, desc): (String, Table)| {
+            let read_source = |key: &str| -> mlua::Result<Option<Arc<str>>> {
+                let Some(path) = desc.get::<Option<String>>(key)? else {
+                    return Ok(None);
+                };
+                let path = resolve_script_path(lua, song_dir.as_path(), &path)?;
+                std::fs::read_to_string(path)
+                    .map(|source| Some(source.into()))
+                    .map_err(mlua::Error::external)
+            };
+            let mut params = Vec::new();
+            if let Some(entries) = desc.get::<Option<Table>>("Params")? {
+                for entry in entries.sequence_values::<Table>() {
+                    let entry = entry?;
+                    params.push(ShaderParam {
+                        name: entry.get(1)?,
+                        default: entry.get::<Option<f32>>(2)?.unwrap_or(0.0),
+                    });
+                }
+            }
+            register_shader_program(ShaderProgram {
+                name: name.trim().to_owned(),
+                params,
+                sources: ShaderSources {
+                    wgsl: read_source("Wgsl")?,
+                    glsl: read_source("Glsl")?,
+                },
+            })
+            .map_err(mlua::Error::external)?;
+            Ok(())
+        })?,
+    )
+}
+
 pub struct SongLuaGameStateGlobals {
     pub current_sort_order: Table,
     pub current_song: Table,
@@ -4,10 +4,12 @@ use std::sync::Arc;
 
 use deadlib_present::actors::TextAlign;
 use deadlib_present::anim::{EffectClock, EffectMode};
+use deadlib_render_core::ShaderEffect;
 
 mod actions;
 mod cmd;
 mod compat;
+mod compatibility;
 mod compile;
 mod compile_timing;
 mod crypto;
@@ -15,6 +17,7 @@ mod eases;
 mod files;
 mod host;
 mod json;
+mod live;
 mod lua_util;
 mod mod_windows;
 mod multitap;
@@ -37,7 +40,14 @@ mod version;
 pub use actions::{SongLuaFunctionActionInput, read_actions_with_function_capture};
 pub use cmd::preprocess_lua_cmd_syntax;
 pub use compat::{SongLuaCompatCallbacks, install_default_stdlib_compat, install_stdlib_compat};
-pub use compile::{compile_song_lua_with_actors, compile_song_lua_with_default_host};
+pub use compatibility::{
+    SongLuaCompatibility, SongLuaCompatibilityLevel, SongLuaScanEntry, SongLuaScanReport,
+    SongLuaScanSong,
+};
+pub use compile::{
+    compile_live_song_lua_with_actors, compile_live_song_lua_with_default_host,
+    compile_song_lua_with_actors, compile_song_lua_with_default_host,
+};
 pub use compile_timing::{
     SongLuaCompileTimer, log_song_lua_compile_timing, song_lua_compile_stage_summary,
 };
@@ -69,6 +79,10 @@ pub use host::{
     restore_compile_globals, snapshot_compile_globals, song_lua_local_date_globals,
 };
 pub use json::{json_to_lua_value, lua_to_json_value};
+pub use live::{
+    SONG_LUA_LIVE_FRAME_BUDGET, SongLuaLiveBaseline, SongLuaLiveEaseFactor, SongLuaLiveRuntime,
+    SongLuaLiveStats, stash_live_function_ease,
+};
 pub use lua_util::{
     SongLuaActionCaptureScope, SongLuaCapturedMessageCommands, SongLuaFunctionActionCapture,
     SongLuaNoteColumnHandlerSnapshot, SongLuaNoteFieldColumnSnapshot, SongLuaNoteskinTapActorModel,
@@ -1475,6 +1489,7 @@ pub struct CompiledSongLua<OverlayActor> {
     pub overlays: Vec<OverlayActor>,
     pub overlay_eases: Vec<SongLuaOverlayEase>,
     pub player_actors: [SongLuaCapturedActor; LUA_PLAYERS],
+    pub song_background: SongLuaCapturedActor,
     pub song_foreground: SongLuaCapturedActor,
     pub hidden_players: [bool; LUA_PLAYERS],
     pub note_hides: Vec<SongLuaNoteHideWindow>,
@@ -1496,6 +1511,7 @@ impl<OverlayActor> Default for CompiledSongLua<OverlayActor> {
             overlays: Vec::new(),
             overlay_eases: Vec::new(),
             player_actors: std::array::from_fn(|_| SongLuaCapturedActor::default()),
+            song_background: SongLuaCapturedActor::default(),
             song_foreground: SongLuaCapturedActor::default(),
             hidden_players: [false; LUA_PLAYERS],
             note_hides: Vec::new(),
@@ -1662,16 +1678,24 @@ impl<Vertex> SongLuaOverlayModelLayer<Vertex> {
 pub enum SongLuaOverlayKind<NoteskinSlot, ModelVertex, TextAttribute> {
     Actor,
     ActorFrame,
-    ActorFrameTexture,
+    ActorFrameTexture {
+        /// Set by `EnablePreserveTexture`: each frame draws over the last one
+        /// instead of starting from a cleared texture.
+        preserve_texture: bool,
+    },
     ActorProxy {
         target: SongLuaProxyTarget,
     },
     AftSprite {
         capture_name: String,
+        /// Effect requested with `SetShaderEffect`.
+        shader_effect: Option<ShaderEffect>,
     },
     Sprite {
         texture_path: PathBuf,
         texture_key: Arc<str>,
+        /// Effect requested with `SetShaderEffect`.
+        shader_effect: Option<ShaderEffect>,
     },
     Sound {
         sound_path: PathBuf,
@@ -3548,6 +3572,7 @@ pub fn push_song_lua_video_paths<'a, NoteskinSlot, ModelVertex, TextAttribute>(
         let SongLuaOverlayKind::Sprite {
             texture_path,
             texture_key,
+            ..
         } = &overlay.kind
         else {
             continue;
@@ -3729,11 +3754,15 @@ pub struct SongLuaCapturedActor {
     pub message_commands: Vec<SongLuaOverlayMessageCommand>,
     /// The chart directly draws a Judgment or Combo child outside the Player draw.
     pub manual_hud_draw: bool,
+    /// Effect requested with `SetShaderEffect`. A player takes the one set
+    /// on its NoteField child.
+    pub shader_effect: Option<ShaderEffect>,
 }
 
 #[derive(Clone, Copy)]
 pub enum SongLuaTrackedActorTarget {
     Player(usize),
+    SongBackground,
     SongForeground,
 }
 
@@ -3952,23 +3981,24 @@ mod tests {
     use super::{
         CompiledSongLua, GRAPH_DISPLAY_VALUE_RESOLUTION, MultitapPhase, SONG_LUA_INITIAL_LIFE,
         SONG_LUA_RUNTIME_KEY, SONG_LUA_SPRITE_STATE_CLEAR, SONG_LUA_STARTUP_MESSAGE,
-        SongLuaColumnOffsetBuildParams, SongLuaColumnOffsetSample, SongLuaCompileContext,
-        SongLuaDifficulty, SongLuaEaseTarget, SongLuaEaseWindow, SongLuaMessageEvent,
+        SongLuaColumnOffsetBuildParams, SongLuaColumnOffsetSample, SongLuaCompatibility,
+        SongLuaCompatibilityLevel, SongLuaCompileContext, SongLuaCompileInfo, SongLuaDifficulty,
+        SongLuaEaseTarget, SongLuaEaseWindow, SongLuaLiveRuntime, SongLuaMessageEvent,
         SongLuaModWindow, SongLuaNoteHideWindow, SongLuaNoteskinResolver, SongLuaOverlayActor,
         SongLuaOverlayBlendMode, SongLuaOverlayCommandBlock, SongLuaOverlayCompileActor,
         SongLuaOverlayEase, SongLuaOverlayEaseBuildParams, SongLuaOverlayKind,
         SongLuaOverlayMessageCommand, SongLuaOverlayModelDraw, SongLuaOverlayModelLayer,
         SongLuaOverlayState, SongLuaOverlayStateDelta, SongLuaPlayerContext, SongLuaProxyTarget,
-        SongLuaSpanMode, SongLuaSpeedMod, SongLuaTextGlowMode, SongLuaTimeUnit,
-        THEME_RECEPTOR_Y_REV, THEME_RECEPTOR_Y_STD, TOP_SCREEN_THEME_CHILD_NAMES,
+        SongLuaScanEntry, SongLuaScanSong, SongLuaSpanMode, SongLuaSpeedMod, SongLuaTextGlowMode,
+        SongLuaTimeUnit, THEME_RECEPTOR_Y_REV, THEME_RECEPTOR_Y_STD, TOP_SCREEN_THEME_CHILD_NAMES,
         UNDERLAY_THEME_CHILD_NAMES, actor_indices_for_pointers, actor_overlay_initial_state,
         actor_pointers_touch_actor, add_actor_child_from_path as add_lua_actor_child_from_path,
         capture_actor_message_commands, capture_block_set_bool, capture_block_set_f32,
         capture_function_action_blocks, capture_indexed_actor_function_blocks,
         capture_overlay_function_eases, collect_indexed_actor_capture_blocks,
-        column_offset_windows_from_samples, compile_song_lua_with_actors,
-        compile_song_runtime_values, compiled_song_lua_sound_paths, create_debug_table,
-        create_dummy_actor as create_lua_dummy_actor,
+        column_offset_windows_from_samples, compile_live_song_lua_with_actors,
+        compile_song_lua_with_actors, compile_song_runtime_values, compiled_song_lua_sound_paths,
+        create_debug_table, create_dummy_actor as create_lua_dummy_actor,
         create_named_child_actor as create_lua_named_child_actor, create_song_runtime_table,
         custom_multi_modifier_key, easiest_steps_difficulty, ensure_overlay_arrow_visual,
         file_path_string, function_ease_actor_indices, function_named_upvalue_tables,
@@ -3999,6 +4029,7 @@ mod tests {
     use std::fs;
     use std::path::{Path, PathBuf};
     use std::sync::Arc;
+    use std::time::Duration;
 
     type TestOverlayKind = SongLuaOverlayKind<(), (), TextAttribute>;
     type TestOverlayActor = SongLuaOverlayActor<TestOverlayKind>;
@@ -4009,6 +4040,7 @@ mod tests {
             kind: TestOverlayKind::Sprite {
                 texture_key: Arc::from(path.to_string_lossy().into_owned()),
                 texture_path: path,
+                shader_effect: None,
             },
             name: None,
             parent_index: None,
@@ -4144,6 +4176,34 @@ mod tests {
         )
     }
 
+    fn test_compile_live_song_lua(
+        entry_path: &Path,
+        context: &SongLuaCompileContext,
+        budget: Duration,
+    ) -> Result<(TestCompiledSongLua, Option<SongLuaLiveRuntime>), String> {
+        compile_live_song_lua_with_actors(
+            entry_path,
+            context,
+            SongLuaNoteskinResolver::default(),
+            test_create_dummy_actor,
+            test_create_named_child_actor,
+            test_install_actor_methods,
+            test_read_model_slots,
+            test_model_layer_from_slot,
+            |_context, _noteskin| None,
+            budget,
+        )
+    }
+
+    fn test_linear_ease_factor(
+        _easing: Option<&str>,
+        t: f32,
+        _opt1: Option<f32>,
+        _opt2: Option<f32>,
+    ) -> f32 {
+        t
+    }
+
     fn test_read_model_slots(_: &Path) -> Result<Arc<[()]>, String> {
         Ok(Arc::from(Vec::<()>::new().into_boxed_slice()))
     }
@@ -4229,6 +4289,128 @@ mod tests {
         }
     }
 
+    #[test]
+    fn live_song_lua_runs_uncaptured_perframes_and_function_eases() {
+        let song_dir = test_dir("live-perframes");
+        let entry = song_dir.join("default.lua");
+        fs::write(
+            &entry,
+            r#"
+local po = GAMESTATE:GetPlayerState(PLAYER_1):GetPlayerOptions("ModsLevel_Song")
+local level = 0
+mods_ease = {
+    {2, 4, 0, 1, function(value) level = value end, "len"},
+}
+mod_perframes = {
+    {0, 8, function(beat) po:Drunk(level, 1) end},
+}
+return Def.ActorFrame{}
+"#,
+        )
+        .unwrap();
+
+        let (compiled, live) = test_compile_live_song_lua(
+            &entry,
+            &SongLuaCompileContext::new(&song_dir, "Live Song"),
+            Duration::from_secs(1),
+        )
+        .unwrap();
+        assert_eq!(compiled.info.unsupported_perframes, 1);
+        assert_eq!(compiled.info.unsupported_function_eases, 1);
+        let mut live = live.expect("uncaptured callbacks need a live runtime");
+        assert_eq!(live.perframe_count(), 1);
+        assert_eq!(live.function_ease_count(), 1);
+
+        live.step(1.0, 1.0, test_linear_ease_factor);
+        let windows = live.step(3.0, 3.0, test_linear_ease_factor);
+        assert_eq!(windows.len(), 1);
+        assert_eq!(
+            windows[0].target,
+            SongLuaEaseTarget::Mod("drunk".to_string())
+        );
+        assert_eq!(windows[0].player, Some(1));
+        assert!((windows[0].to - 25.0).abs() <= 1.0e-4);
+        assert_eq!(live.stats().frames, 2);
+        assert_eq!(live.fallback_reason(), None);
+    }
+
+    #[test]
+    fn live_song_lua_falls_back_when_a_frame_exceeds_its_budget() {
+        let song_dir = test_dir("live-budget");
+        let entry = song_dir.join("default.lua");
+        fs::write(
+            &entry,
+            r#"
+local po = GAMESTATE:GetPlayerState(PLAYER_1):GetPlayerOptions("ModsLevel_Song")
+mod_perframes = {
+    {0, 8, function(beat, dt)
+        po:Drunk(0.5, 1)
+        -- Compile-time sampling never steps this far, so only a live seek spins.
+        if dt > 2 then
+            while true do end
+        end
+    end},
+}
+return Def.ActorFrame{}
+"#,
+        )
+        .unwrap();
+
+        let (_, live) = test_compile_live_song_lua(
+            &entry,
+            &SongLuaCompileContext::new(&song_dir, "Live Budget"),
+            Duration::from_millis(50),
+        )
+        .unwrap();
+        let mut live = live.expect("uncaptured perframes need a live runtime");
+
+        assert_eq!(live.step(1.0, 1.0, test_linear_ease_factor).len(), 1);
+        assert!(live.step(5.0, 5.0, test_linear_ease_factor).is_empty());
+        assert!(
+            live.fallback_reason()
+                .is_some_and(|reason| reason.contains("budget"))
+        );
+        assert!(live.step(6.0, 6.0, test_linear_ease_factor).is_empty());
+    }
+
+    #[test]
+    fn live_song_lua_tolerates_over_budget_frames_until_they_repeat() {
+        let song_dir = test_dir("live-strikes");
+        let entry = song_dir.join("default.lua");
+        fs::write(
+            &entry,
+            r#"
+local po = GAMESTATE:GetPlayerState(PLAYER_1):GetPlayerOptions("ModsLevel_Song")
+mod_perframes = {
+    {0, 8, function(beat) po:Drunk(0.5, 1) end},
+}
+return Def.ActorFrame{}
+"#,
+        )
+        .unwrap();
+
+        // Every frame overruns a 1ns budget, but stays far below the hook's
+        // abort point, so each one is a strike rather than an error.
+        let (_, live) = test_compile_live_song_lua(
+            &entry,
+            &SongLuaCompileContext::new(&song_dir, "Live Strikes"),
+            Duration::from_nanos(1),
+        )
+        .unwrap();
+        let mut live = live.expect("uncaptured perframes need a live runtime");
+
+        assert_eq!(live.step(1.0, 1.0, test_linear_ease_factor).len(), 1);
+        assert_eq!(live.step(2.0, 2.0, test_linear_ease_factor).len(), 1);
+        assert_eq!(live.fallback_reason(), None);
+        assert_eq!(live.stats().over_budget_frames, 2);
+
+        assert!(live.step(3.0, 3.0, test_linear_ease_factor).is_empty());
+        assert!(
+            live.fallback_reason()
+                .is_some_and(|reason| reason.contains("budget"))
+        );
+    }
+
     #[test]
     fn compile_song_lua_reads_mod_tables() {
         let song_dir = test_dir("direct");
@@ -13569,7 +13751,7 @@ return Def.ActorFrame{
         assert_eq!(compiled.overlays.len(), 3);
         assert!(matches!(
             compiled.overlays[0].kind,
-            SongLuaOverlayKind::ActorFrameTexture
+            SongLuaOverlayKind::ActorFrameTexture { .. }
         ));
         assert!(matches!(
             compiled.overlays[1].kind,
@@ -13579,7 +13761,9 @@ return Def.ActorFrame{
         ));
         assert!(matches!(
             compiled.overlays[2].kind,
-            SongLuaOverlayKind::AftSprite { ref capture_name }
+            SongLuaOverlayKind::AftSprite {
+                ref capture_name, ..
+            }
                 if capture_name == "CaptureAFT"
         ));
         assert_eq!(
@@ -13643,7 +13827,7 @@ return Def.ActorFrame{
         assert_eq!(compiled.overlays.len(), 3);
         assert!(matches!(
             compiled.overlays[0].kind,
-            SongLuaOverlayKind::ActorFrameTexture
+            SongLuaOverlayKind::ActorFrameTexture { .. }
         ));
         assert!(matches!(
             compiled.overlays[1].kind,
@@ -13651,7 +13835,7 @@ return Def.ActorFrame{
                 target: SongLuaProxyTarget::Player { player_index: 0 }
             }
         ));
-        let SongLuaOverlayKind::AftSprite { capture_name } = &compiled.overlays[2].kind else {
+        let SongLuaOverlayKind::AftSprite { capture_name, .. } = &compiled.overlays[2].kind else {
             panic!("expected anonymous AFT output sprite");
         };
         assert!(capture_name.starts_with("ActorFrameTexture "));
@@ -13775,6 +13959,7 @@ return Def.ActorFrame{
             self:SetWidth(640)
             self:SetHeight(480)
             self:EnableAlphaBuffer(false)
+            self:EnablePreserveTexture(true)
             self:Create()
         end,
     },
@@ -13794,15 +13979,204 @@ return Def.ActorFrame{
         assert_eq!(compiled.overlays.len(), 2);
         assert!(matches!(
             compiled.overlays[0].kind,
-            SongLuaOverlayKind::ActorFrameTexture
+            SongLuaOverlayKind::ActorFrameTexture {
+                preserve_texture: true
+            }
         ));
         assert!(matches!(
             compiled.overlays[1].kind,
-            SongLuaOverlayKind::AftSprite { ref capture_name }
+            SongLuaOverlayKind::AftSprite {
+                ref capture_name, ..
+            }
                 if capture_name == "ScreenTex"
         ));
     }
 
+    #[test]
+    fn compile_song_lua_reads_sprite_notefield_and_background_shader_effects() {
+        let song_dir = test_dir("shader-effect-targets");
+        let entry = song_dir.join("default.lua");
+        let overlay_dir = song_dir.join("gfx");
+        fs::create_dir_all(&overlay_dir).unwrap();
+        fs::write(
+            overlay_dir.join("grid.png"),
+            b"not-an-image-but-good-enough-for-parser",
+        )
+        .unwrap();
+        fs::write(
+            &entry,
+            r#"
+return Def.ActorFrame{
+    OnCommand=function(self)
+        local screen = SCREENMAN:GetTopScreen()
+        screen:GetChild("PlayerP1"):GetChild("NoteField"):SetShaderEffect("ColorGrade", 0, 1.5)
+        screen:GetChild("PlayerP2"):SetShaderEffect("Blur", 2)
+        screen:GetChild("SongBackground"):SetShaderEffect("Blur", 6)
+    end,
+    Def.Sprite{
+        Texture="gfx/grid.png",
+        InitCommand=function(self)
+            self:SetShaderEffect("ChromaticAberration", 2, -1)
+        end,
+    },
+}
+"#,
+        )
+        .unwrap();
+
+        let compiled = test_compile_song_lua(
+            &entry,
+            &SongLuaCompileContext::new(&song_dir, "Shader Effect Targets"),
+        )
+        .unwrap();
+        assert!(compiled.overlays.iter().any(|overlay| matches!(
+            overlay.kind,
+            SongLuaOverlayKind::Sprite {
+                shader_effect: Some(ShaderEffect::ChromaticAberration {
+                    offset: [2.0, -1.0]
+                }),
+                ..
+            }
+        )));
+        assert_eq!(
+            compiled.player_actors[0].shader_effect,
+            Some(ShaderEffect::ColorGrade {
+                saturation: 0.0,
+                contrast: 1.5,
+                brightness: 0.0,
+            })
+        );
+        // Only the NoteField child's effect reaches the notefield.
+        assert_eq!(compiled.player_actors[1].shader_effect, None);
+        assert_eq!(
+            compiled.song_background.shader_effect,
+            Some(ShaderEffect::Blur { radius: 6.0 })
+        );
+        assert_eq!(compiled.song_foreground.shader_effect, None);
+    }
+
+    #[test]
+    fn compile_song_lua_reads_aft_sprite_shader_effect() {
+        let song_dir = test_dir("overlay-aft-shader-effect");
+        let entry = song_dir.join("default.lua");
+        fs::write(
+            &entry,
+            r#"
+return Def.ActorFrame{
+    Def.ActorFrameTexture{
+        InitCommand=function(self)
+            self:SetTextureName("ScreenTex")
+            self:Create()
+        end,
+    },
+    Def.Sprite{
+        Texture="ScreenTex",
+        InitCommand=function(self)
+            self:SetShaderEffect("Wave", 4, 64)
+        end,
+    },
+    Def.Sprite{
+        Texture="ScreenTex",
+        InitCommand=function(self)
+            self:SetShaderEffect("Sepia", 1):SetShaderEffect("Blur", 3):ClearShaderEffect()
+        end,
+    },
+}
+"#,
+        )
+        .unwrap();
+
+        let compiled = test_compile_song_lua(
+            &entry,
+            &SongLuaCompileContext::new(&song_dir, "AFT Shader Effect"),
+        )
+        .unwrap();
+        assert_eq!(compiled.overlays.len(), 3);
+        assert!(matches!(
+            compiled.overlays[1].kind,
+            SongLuaOverlayKind::AftSprite {
+                shader_effect: Some(ShaderEffect::Wave {
+                    amplitude: 4.0,
+                    wavelength: 64.0,
+                    phase: 0.0,
+                }),
+                ..
+            }
+        ));
+        assert!(matches!(
+            compiled.overlays[2].kind,
+            SongLuaOverlayKind::AftSprite {
+                shader_effect: None,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn compile_song_lua_registers_user_shader_effects() {
+        let song_dir = test_dir("overlay-user-shader-effect");
+        fs::create_dir_all(song_dir.join("fx")).unwrap();
+        fs::write(
+            song_dir.join("fx/sepia.frag"),
+            "vec4 effect(vec2 uv) { return tap(uv) * u_params.x; }",
+        )
+        .unwrap();
+        let entry = song_dir.join("default.lua");
+        fs::write(
+            &entry,
+            r#"
+RegisterShaderEffect("SongLuaSepia", {
+    Params = { {"Amount", 0.75}, {"Tone", 2} },
+    Glsl = "fx/sepia.frag",
+})
+return Def.ActorFrame{
+    Def.ActorFrameTexture{
+        InitCommand=function(self)
+            self:SetTextureName("ScreenTex")
+            self:Create()
+        end,
+    },
+    Def.Sprite{
+        Texture="ScreenTex",
+        InitCommand=function(self)
+            self:SetShaderEffect("songluasepia", 0.5)
+        end,
+    },
+}
+"#,
+        )
+        .unwrap();
+
+        let compiled = test_compile_song_lua(
+            &entry,
+            &SongLuaCompileContext::new(&song_dir, "User Shader Effect"),
+        )
+        .unwrap();
+        let program = ShaderEffect::from_name("SongLuaSepia", &[])
+            .expect("registered")
+            .program();
+        let registered = deadlib_render_core::shader_program(program).unwrap();
+        assert!(registered.sources.wgsl.is_none());
+        assert!(
+            registered
+                .sources
+                .glsl
+                .as_deref()
+                .unwrap()
+                .contains("u_params.x")
+        );
+        assert!(matches!(
+            compiled.overlays[1].kind,
+            SongLuaOverlayKind::AftSprite {
+                shader_effect: Some(ShaderEffect::Custom {
+                    program: id,
+                    params: [0.5, 2.0, 0.0, 0.0],
+                }),
+                ..
+            } if id == program
+        ));
+    }
+
     #[test]
     fn compile_song_lua_accepts_actorframetexture_draw_call() {
         let song_dir = test_dir("overlay-aft-draw");
@@ -13832,7 +14206,7 @@ return Def.ActorFrame{
         assert_eq!(compiled.overlays.len(), 1);
         assert!(matches!(
             compiled.overlays[0].kind,
-            SongLuaOverlayKind::ActorFrameTexture
+            SongLuaOverlayKind::ActorFrameTexture { .. }
         ));
         assert!(compiled.overlays[0].initial_state.visible);
     }
@@ -16493,6 +16867,7 @@ return Def.ActorFrame{
                 SongLuaOverlayKind::Sprite {
                     texture_path: PathBuf::from("arrow.png"),
                     texture_key: Arc::from("arrow.png"),
+                    shader_effect: None,
                 },
                 SongLuaOverlayState::default(),
             ))
@@ -17250,4 +17625,45 @@ return Def.ActorFrame{
             Value::Boolean(false)
         );
     }
+
+    #[test]
+    fn compile_info_classifies_dropped_constructs() {
+        assert_eq!(
+            SongLuaCompatibility::from_compile_info(&SongLuaCompileInfo::default()),
+            SongLuaCompatibility::Supported
+        );
+        let info = SongLuaCompileInfo {
+            unsupported_perframes: 2,
+            unsupported_function_eases: 3,
+            unsupported_function_ease_captures: vec!["function ease start=4.000".to_string()],
+            skipped_message_command_captures: vec!["BreakMeMessageCommand".to_string()],
+            ..SongLuaCompileInfo::default()
+        };
+        assert_eq!(
+            SongLuaCompatibility::from_compile_info(&info),
+            SongLuaCompatibility::Degraded(vec![
+                "2 unsupported perframe(s)".to_string(),
+                "function ease start=4.000".to_string(),
+                "BreakMeMessageCommand".to_string(),
+            ])
+        );
+        let failed: Result<SongLuaCompileInfo, String> = Err("syntax error".to_string());
+        let song = SongLuaScanSong {
+            simfile_path: PathBuf::from("Songs/Pack/Song/song.ssc"),
+            title: "Song".to_string(),
+            entries: vec![
+                SongLuaScanEntry {
+                    path: PathBuf::from("Songs/Pack/Song/fg.lua"),
+                    start_beat: 0.0,
+                    compatibility: SongLuaCompatibility::from_compile_info(&info),
+                },
+                SongLuaScanEntry {
+                    path: PathBuf::from("Songs/Pack/Song/bg.lua"),
+                    start_beat: 32.0,
+                    compatibility: SongLuaCompatibility::from_compile_result(&failed, |info| info),
+                },
+            ],
+        };
+        assert_eq!(song.level(), SongLuaCompatibilityLevel::Failed);
+    }
 }
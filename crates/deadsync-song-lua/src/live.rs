@@ -0,0 +1,485 @@
+use log::warn;
+use mlua::{Function, HookTriggers, Lua, Table, Value, VmState};
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::{
+    LUA_PLAYERS, SongLuaEaseWindow, SongLuaFunctionEaseInput, SongLuaPerframeEntry,
+    SongLuaPerframePlayerState, SongLuaSpanMode, SongLuaTimeUnit, SongLuaUpdateModState,
+    call_perframe_entry, current_perframe_player_states, current_update_mod_states,
+    push_perframe_player_targets, push_update_mod_targets, read_f32,
+    reset_indexed_actor_capture_tables, set_compile_song_runtime_delta_values,
+    set_compile_song_runtime_values,
+};
+
+/// Default Lua execution budget for one frame of live song-lua callbacks.
+pub const SONG_LUA_LIVE_FRAME_BUDGET: Duration = Duration::from_millis(2);
+const SONG_LUA_LIVE_FUNCTION_EASES_KEY: &str = "__songlua_live_function_eases";
+const SONG_LUA_LIVE_HOOK_INTERVAL: u32 = 1_000;
+// Over-budget frames tolerated inside the strike window before falling back.
+const SONG_LUA_LIVE_BUDGET_STRIKES: usize = 3;
+const SONG_LUA_LIVE_STRIKE_WINDOW_FRAMES: u64 = 240;
+// A single frame this many budgets deep is a runaway script, so the
+// instruction hook aborts it outright.
+const SONG_LUA_LIVE_ABORT_BUDGETS: u32 = 8;
+const PLAYER_STATE_PREFIX: &str = "__songlua_state_";
+const PLAYER_SPEEDMOD_PREFIX: &str = "__songlua_speedmod_";
+const PLAYER_OPTION_STATE_KEY: &str = "__songlua_player_option_state";
+
+/// Easing curve used for live function eases: `(easing, t, opt1, opt2)`.
+pub type SongLuaLiveEaseFactor = fn(Option<&str>, f32, Option<f32>, Option<f32>) -> f32;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SongLuaLiveStats {
+    pub frames: u64,
+    pub over_budget_frames: u64,
+    /// Lua execution time of the last frame; host-side bookkeeping is not
+    /// counted against the budget.
+    pub last_frame_ms: f64,
+    pub max_frame_ms: f64,
+}
+
+/// Time spent inside Lua calls during one live frame. The instruction hook
+/// reads it to abort runaway calls.
+#[derive(Default)]
+struct SongLuaLiveClock {
+    spent: Cell<Duration>,
+    call_started: Cell<Option<Instant>>,
+}
+
+impl SongLuaLiveClock {
+    fn elapsed(&self) -> Duration {
+        self.spent.get()
+            + self
+                .call_started
+                .get()
+                .map_or(Duration::ZERO, |started| started.elapsed())
+    }
+
+    fn in_call(&self) -> bool {
+        self.call_started.get().is_some()
+    }
+
+    fn time<T>(&self, call: impl FnOnce() -> T) -> T {
+        self.call_started.set(Some(Instant::now()));
+        let out = call();
+        if let Some(started) = self.call_started.take() {
+            self.spent.set(self.spent.get() + started.elapsed());
+        }
+        out
+    }
+}
+
+struct SongLuaLiveFunctionEase {
+    function: Function,
+    unit: SongLuaTimeUnit,
+    start: f32,
+    end: f32,
+    from: f32,
+    to: f32,
+    easing: Option<String>,
+    opt1: Option<f32>,
+    opt2: Option<f32>,
+    settled: bool,
+}
+
+type SongLuaLiveTableSnapshot = Vec<(String, Value)>;
+
+/// Player actor and option state captured before compile-time sampling runs
+/// the callbacks, so live playback starts from the script's initial state.
+pub struct SongLuaLiveBaseline {
+    players: [SongLuaPerframePlayerState; LUA_PLAYERS],
+    mods: [SongLuaUpdateModState; LUA_PLAYERS],
+    actors: Vec<(Table, SongLuaLiveTableSnapshot)>,
+    options: Vec<(Table, SongLuaLiveTableSnapshot, SongLuaLiveTableSnapshot)>,
+}
+
+impl SongLuaLiveBaseline {
+    pub fn capture(
+        player_tables: &[Option<Table>; LUA_PLAYERS],
+        option_tables: &[Table; LUA_PLAYERS],
+    ) -> Result<Self, String> {
+        let mut actors = Vec::new();
+        for actor in player_tables.iter().flatten() {
+            actors.push((
+                actor.clone(),
+                snapshot_prefixed(actor, PLAYER_STATE_PREFIX)?,
+            ));
+        }
+        let mut options = Vec::with_capacity(LUA_PLAYERS);
+        for table in option_tables {
+            let state = match table
+                .raw_get::<Option<Table>>(PLAYER_OPTION_STATE_KEY)
+                .map_err(|err| err.to_string())?
+            {
+                Some(state) => snapshot_prefixed(&state, "")?,
+                None => Vec::new(),
+            };
+            options.push((
+                table.clone(),
+                snapshot_prefixed(table, PLAYER_SPEEDMOD_PREFIX)?,
+                state,
+            ));
+        }
+        Ok(Self {
+            players: current_perframe_player_states(player_tables)?,
+            mods: current_update_mod_states(option_tables)?,
+            actors,
+            options,
+        })
+    }
+
+    fn restore(&self) -> Result<(), String> {
+        for (actor, snapshot) in &self.actors {
+            restore_prefixed(actor, PLAYER_STATE_PREFIX, snapshot)?;
+        }
+        for (table, speedmods, state) in &self.options {
+            restore_prefixed(table, PLAYER_SPEEDMOD_PREFIX, speedmods)?;
+            if let Some(current) = table
+                .raw_get::<Option<Table>>(PLAYER_OPTION_STATE_KEY)
+                .map_err(|err| err.to_string())?
+            {
+                restore_prefixed(&current, "", state)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn snapshot_prefixed(table: &Table, prefix: &str) -> Result<SongLuaLiveTableSnapshot, String> {
+    let mut out = Vec::new();
+    for pair in table.pairs::<Value, Value>() {
+        let (key, value) = pair.map_err(|err| err.to_string())?;
+        let Value::String(key) = key else {
+            continue;
+        };
+        let key = key.to_string_lossy();
+        if key.starts_with(prefix) {
+            out.push((key, value));
+        }
+    }
+    Ok(out)
+}
+
+fn restore_prefixed(
+    table: &Table,
+    prefix: &str,
+    snapshot: &SongLuaLiveTableSnapshot,
+) -> Result<(), String> {
+    for (key, _) in snapshot_prefixed(table, prefix)? {
+        table
+            .raw_set(key, Value::Nil)
+            .map_err(|err| err.to_string())?;
+    }
+    for (key, value) in snapshot {
+        table
+            .raw_set(key.as_str(), value.clone())
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+/// Records a function ease that compile-time capture could not bake, so a
+/// live runtime can call it every frame instead.
+pub fn stash_live_function_ease(lua: &Lua, input: &SongLuaFunctionEaseInput) -> mlua::Result<()> {
+    let stash = match lua.named_registry_value::<Option<Table>>(SONG_LUA_LIVE_FUNCTION_EASES_KEY)? {
+        Some(stash) => stash,
+        None => {
+            let stash = lua.create_table()?;
+            lua.set_named_registry_value(SONG_LUA_LIVE_FUNCTION_EASES_KEY, stash.clone())?;
+            stash
+        }
+    };
+    let end = match input.span_mode {
+        SongLuaSpanMode::Len => input.start + input.limit.max(0.0),
+        SongLuaSpanMode::End => input.limit,
+    };
+    let entry = lua.create_table()?;
+    entry.raw_set("function", input.function.clone())?;
+    entry.raw_set("seconds", matches!(input.unit, SongLuaTimeUnit::Second))?;
+    entry.raw_set("start", input.start)?;
+    entry.raw_set("end", end)?;
+    entry.raw_set("from", input.from)?;
+    entry.raw_set("to", input.to)?;
+    entry.raw_set("easing", input.easing.as_deref())?;
+    entry.raw_set("opt1", input.opt1)?;
+    entry.raw_set("opt2", input.opt2)?;
+    stash.raw_push(entry)
+}
+
+fn read_live_function_eases(lua: &Lua) -> Result<Vec<SongLuaLiveFunctionEase>, String> {
+    let Some(stash) = lua
+        .named_registry_value::<Option<Table>>(SONG_LUA_LIVE_FUNCTION_EASES_KEY)
+        .map_err(|err| err.to_string())?
+    else {
+        return Ok(Vec::new());
+    };
+    let mut out = Vec::new();
+    for entry in stash.sequence_values::<Table>() {
+        let entry = entry.map_err(|err| err.to_string())?;
+        let number = |key: &str| -> Result<Option<f32>, String> {
+            Ok(read_f32(
+                entry.raw_get::<Value>(key).map_err(|err| err.to_string())?,
+            ))
+        };
+        let (Some(start), Some(end), Some(from), Some(to)) = (
+            number("start")?,
+            number("end")?,
+            number("from")?,
+            number("to")?,
+        ) else {
+            continue;
+        };
+        out.push(SongLuaLiveFunctionEase {
+            function: entry
+                .raw_get::<Function>("function")
+                .map_err(|err| err.to_string())?,
+            unit: if entry
+                .raw_get::<bool>("seconds")
+                .map_err(|err| err.to_string())?
+            {
+                SongLuaTimeUnit::Second
+            } else {
+                SongLuaTimeUnit::Beat
+            },
+            start,
+            end,
+            from,
+            to,
+            easing: entry
+                .raw_get::<Option<String>>("easing")
+                .map_err(|err| err.to_string())?,
+            opt1: number("opt1")?,
+            opt2: number("opt2")?,
+            settled: false,
+        });
+    }
+    Ok(out)
+}
+
+/// Keeps a modfile's Lua state alive during gameplay and runs the callbacks
+/// compile-time sampling could not bake: unsupported perframes and function
+/// eases. Each frame's player transforms and option mods come back as
+/// constant ease windows (`from == to`).
+///
+/// Only time spent executing Lua counts against the budget. A frame that
+/// errors, or that runs so far past the budget the hook aborts it, stops the
+/// runtime for the rest of the song; so do repeated over-budget frames inside
+/// a short window. Callers then keep only the compiled eases.
+pub struct SongLuaLiveRuntime {
+    lua: Lua,
+    perframes: Vec<SongLuaPerframeEntry>,
+    function_eases: Vec<SongLuaLiveFunctionEase>,
+    player_tables: [Option<Table>; LUA_PLAYERS],
+    option_tables: [Table; LUA_PLAYERS],
+    capture_tables: Vec<(usize, Table)>,
+    baseline: SongLuaLiveBaseline,
+    budget: Duration,
+    clock: Rc<SongLuaLiveClock>,
+    strike_frames: VecDeque<u64>,
+    last_time: Option<(f32, f32)>,
+    windows: Vec<SongLuaEaseWindow>,
+    stats: SongLuaLiveStats,
+    fallback: Option<String>,
+}
+
+impl SongLuaLiveRuntime {
+    /// Returns `None` when the script left nothing that needs live playback.
+    pub fn new(
+        lua: Lua,
+        perframes: Vec<SongLuaPerframeEntry>,
+        player_tables: [Option<Table>; LUA_PLAYERS],
+        option_tables: [Table; LUA_PLAYERS],
+        capture_tables: Vec<(usize, Table)>,
+        baseline: SongLuaLiveBaseline,
+        budget: Duration,
+    ) -> Result<Option<Self>, String> {
+        let function_eases = read_live_function_eases(&lua)?;
+        if perframes.is_empty() && function_eases.is_empty() {
+            return Ok(None);
+        }
+        baseline.restore()?;
+        let clock = Rc::new(SongLuaLiveClock::default());
+        let hook_clock = Rc::clone(&clock);
+        let abort_after = budget.saturating_mul(SONG_LUA_LIVE_ABORT_BUDGETS);
+        lua.set_hook(
+            HookTriggers::new().every_nth_instruction(SONG_LUA_LIVE_HOOK_INTERVAL),
+            move |_, _| {
+                if hook_clock.in_call() && hook_clock.elapsed() > abort_after {
+                    return Err(mlua::Error::runtime(
+                        "live song lua frame exceeded its time budget",
+                    ));
+                }
+                Ok(VmState::Continue)
+            },
+        )
+        .map_err(|err| format!("failed to limit live song lua execution: {err}"))?;
+        Ok(Some(Self {
+            lua,
+            perframes,
+            function_eases,
+            player_tables,
+            option_tables,
+            capture_tables,
+            baseline,
+            budget,
+            clock,
+            strike_frames: VecDeque::with_capacity(SONG_LUA_LIVE_BUDGET_STRIKES),
+            last_time: None,
+            windows: Vec::new(),
+            stats: SongLuaLiveStats::default(),
+            fallback: None,
+        }))
+    }
+
+    #[inline(always)]
+    pub fn perframe_count(&self) -> usize {
+        self.perframes.len()
+    }
+
+    #[inline(always)]
+    pub fn function_ease_count(&self) -> usize {
+        self.function_eases.len()
+    }
+
+    #[inline(always)]
+    pub fn stats(&self) -> SongLuaLiveStats {
+        self.stats
+    }
+
+    /// Why the runtime stopped, once it has fallen back to compiled mode.
+    #[inline(always)]
+    pub fn fallback_reason(&self) -> Option<&str> {
+        self.fallback.as_deref()
+    }
+
+    /// Runs one frame at song `beat` / `seconds` and returns the resulting
+    /// constant windows. Empty after a fallback.
+    pub fn step(
+        &mut self,
+        beat: f32,
+        seconds: f32,
+        ease_factor: SongLuaLiveEaseFactor,
+    ) -> &[SongLuaEaseWindow] {
+        self.windows.clear();
+        if self.fallback.is_some() || !beat.is_finite() || !seconds.is_finite() {
+            return &self.windows;
+        }
+        self.clock.spent.set(Duration::ZERO);
+        let result = self.run_frame(beat, seconds, ease_factor);
+        let elapsed = self.clock.elapsed();
+        let frame_ms = elapsed.as_secs_f64() * 1000.0;
+        self.stats.frames += 1;
+        self.stats.last_frame_ms = frame_ms;
+        self.stats.max_frame_ms = self.stats.max_frame_ms.max(frame_ms);
+        let failure = match result {
+            Err(err) => Some(err),
+            Ok(()) if elapsed > self.budget => self.strike(frame_ms),
+            Ok(()) => None,
+        };
+        if let Some(reason) = failure {
+            warn!(
+                "Live song lua fell back to compiled mode at beat {beat:.3} after {} frames: {reason}",
+                self.stats.frames
+            );
+            self.fallback = Some(reason);
+            self.windows.clear();
+        }
+        &self.windows
+    }
+
+    /// Records an over-budget frame and returns the fallback reason once
+    /// too many land inside the strike window.
+    fn strike(&mut self, frame_ms: f64) -> Option<String> {
+        let frame = self.stats.frames;
+        self.stats.over_budget_frames += 1;
+        while self
+            .strike_frames
+            .front()
+            .is_some_and(|&struck| frame - struck >= SONG_LUA_LIVE_STRIKE_WINDOW_FRAMES)
+        {
+            self.strike_frames.pop_front();
+        }
+        self.strike_frames.push_back(frame);
+        (self.strike_frames.len() >= SONG_LUA_LIVE_BUDGET_STRIKES).then(|| {
+            format!(
+                "{} frames within {SONG_LUA_LIVE_STRIKE_WINDOW_FRAMES} ran past the {:.3}ms budget, the last taking {frame_ms:.3}ms",
+                self.strike_frames.len(),
+                self.budget.as_secs_f64() * 1000.0
+            )
+        })
+    }
+
+    fn run_frame(
+        &mut self,
+        beat: f32,
+        seconds: f32,
+        ease_factor: SongLuaLiveEaseFactor,
+    ) -> Result<(), String> {
+        let (delta_beats, delta_seconds) = match self.last_time {
+            Some((last_beat, last_seconds)) if beat >= last_beat => {
+                (beat - last_beat, (seconds - last_seconds).max(0.0))
+            }
+            _ => (0.0, 0.0),
+        };
+        self.last_time = Some((beat, seconds));
+        set_compile_song_runtime_values(&self.lua, beat, seconds).map_err(|err| err.to_string())?;
+        set_compile_song_runtime_delta_values(&self.lua, delta_beats, delta_seconds)
+            .map_err(|err| err.to_string())?;
+        reset_indexed_actor_capture_tables(&self.lua, &self.capture_tables)?;
+
+        for ease in &mut self.function_eases {
+            let time = match ease.unit {
+                SongLuaTimeUnit::Beat => beat,
+                SongLuaTimeUnit::Second => seconds,
+            };
+            if time < ease.start {
+                ease.settled = false;
+                continue;
+            }
+            let value = if time < ease.end && ease.end > ease.start {
+                let t = (time - ease.start) / (ease.end - ease.start);
+                let factor = ease_factor(ease.easing.as_deref(), t, ease.opt1, ease.opt2);
+                ease.from + (ease.to - ease.from) * factor
+            } else if ease.settled {
+                continue;
+            } else {
+                ease.settled = true;
+                ease.to
+            };
+            let function = &ease.function;
+            self.clock
+                .time(|| function.call::<Value>(value))
+                .map_err(|err| err.to_string())?;
+        }
+
+        for entry in &self.perframes {
+            if beat >= entry.start && beat < entry.end {
+                self.clock.time(|| {
+                    call_perframe_entry(&self.lua, entry, beat, delta_beats, delta_seconds)
+                })?;
+            }
+        }
+        let players = current_perframe_player_states(&self.player_tables)?;
+        let mods = current_update_mod_states(&self.option_tables)?;
+        push_perframe_player_targets(
+            &mut self.windows,
+            beat,
+            beat + 1.0,
+            &players,
+            &players,
+            &self.baseline.players,
+        );
+        push_update_mod_targets(
+            &mut self.windows,
+            beat,
+            beat + 1.0,
+            &mods,
+            &mods,
+            &self.baseline.mods,
+        );
+        Ok(())
+    }
+}
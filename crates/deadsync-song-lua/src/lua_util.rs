@@ -1,5 +1,6 @@
 use deadlib_present::actors::TextAttribute;
 use deadlib_present::anim::EffectClock;
+use deadlib_render_core::ShaderEffect;
 use image::image_dimensions;
 use mlua::{Function, Lua, MultiValue, Table, Value, ffi};
 use std::collections::{HashMap, HashSet};
@@ -371,19 +372,22 @@ pub fn read_tracked_compile_actors(
         .get::<Table>("__songlua_top_screen")
         .map_err(|err| err.to_string())?;
     let children = actor_children(lua, &top_screen).map_err(|err| err.to_string())?;
-    let song_foreground = if let Some(actor) = children
-        .get::<Option<Table>>("SongForeground")
-        .map_err(|err| err.to_string())?
-    {
-        actor
-    } else {
-        let actor = create_named_child_actor(lua, &top_screen, "SongForeground")
-            .map_err(|err| err.to_string())?;
+    let top_screen_child = |name: &str| -> Result<Table, String> {
+        if let Some(actor) = children
+            .get::<Option<Table>>(name)
+            .map_err(|err| err.to_string())?
+        {
+            return Ok(actor);
+        }
+        let actor =
+            create_named_child_actor(lua, &top_screen, name).map_err(|err| err.to_string())?;
         children
-            .set("SongForeground", actor.clone())
+            .set(name, actor.clone())
             .map_err(|err| err.to_string())?;
-        actor
+        Ok(actor)
     };
+    let song_background = top_screen_child("SongBackground")?;
+    let song_foreground = top_screen_child("SongForeground")?;
     Ok(vec![
         tracked_song_lua_actor(
             globals
@@ -397,6 +401,7 @@ pub fn read_tracked_compile_actors(
                 .map_err(|err| err.to_string())?,
             SongLuaTrackedActorTarget::Player(1),
         )?,
+        tracked_song_lua_actor(song_background, SongLuaTrackedActorTarget::SongBackground)?,
         tracked_song_lua_actor(song_foreground, SongLuaTrackedActorTarget::SongForeground)?,
     ])
 }
@@ -909,6 +914,8 @@ fn is_actor_mutable_state_key(key: &str) -> bool {
                 | "__songlua_stream_width"
                 | "__songlua_sprite_animation_length_seconds"
                 | "__songlua_sprite_effect_mode"
+                | "__songlua_shader_effect"
+                | "__songlua_shader_effect_params"
                 | "Text"
                 | "Texture"
                 | "File"
@@ -4949,6 +4956,37 @@ pub fn install_actor_sprite_animation_methods(lua: &Lua, actor: &Table) -> mlua:
             }
         })?,
     )?;
+    actor.set(
+        "SetShaderEffect",
+        lua.create_function({
+            let actor = actor.clone();
+            move |lua, args: MultiValue| {
+                let Some(name) = method_arg(&args, 0).cloned().and_then(read_string) else {
+                    return Ok(actor.clone());
+                };
+                let offset = method_arg_offset(&args);
+                let params = lua.create_sequence_from(
+                    args.iter()
+                        .skip(offset + 1)
+                        .filter_map(|value| read_f32(value.clone())),
+                )?;
+                actor.set("__songlua_shader_effect", name)?;
+                actor.set("__songlua_shader_effect_params", params)?;
+                Ok(actor.clone())
+            }
+        })?,
+    )?;
+    actor.set(
+        "ClearShaderEffect",
+        lua.create_function({
+            let actor = actor.clone();
+            move |_, _args: MultiValue| {
+                actor.set("__songlua_shader_effect", Value::Nil)?;
+                actor.set("__songlua_shader_effect_params", Value::Nil)?;
+                Ok(actor.clone())
+            }
+        })?,
+    )?;
     actor.set(
         "animate",
         lua.create_function({
@@ -5427,7 +5465,6 @@ pub fn install_actor_render_compat_methods(lua: &Lua, actor: &Table) -> mlua::Re
         "EnableAlphaBuffer",
         "EnableDepthBuffer",
         "EnableFloat",
-        "EnablePreserveTexture",
         "Create",
         "SetAmbientLightColor",
         "SetDiffuseLightColor",
@@ -5443,6 +5480,20 @@ pub fn install_actor_render_compat_methods(lua: &Lua, actor: &Table) -> mlua::Re
     ] {
         actor.set(name, make_actor_chain_method(lua, actor)?)?;
     }
+    actor.set(
+        "EnablePreserveTexture",
+        lua.create_function({
+            let actor = actor.clone();
+            move |_, args: MultiValue| {
+                let value = method_arg(&args, 0)
+                    .cloned()
+                    .and_then(read_boolish)
+                    .unwrap_or(false);
+                actor.set("__songlua_aft_preserve_texture", value)?;
+                Ok(actor.clone())
+            }
+        })?,
+    )?;
     actor.set(
         "Draw",
         lua.create_function({
@@ -9007,16 +9058,21 @@ pub fn tracked_song_lua_actor(
         Some(draw_state) => actor_overlay_initial_state(&draw_state)?,
         None => actor_overlay_initial_state(&table)?,
     };
-    let manual_hud_draw = if matches!(target, SongLuaTrackedActorTarget::Player(_)) {
-        manually_draws_player_hud(&table)?
+    let (manual_hud_draw, shader_effect) = if matches!(target, SongLuaTrackedActorTarget::Player(_))
+    {
+        (
+            manually_draws_player_hud(&table)?,
+            player_note_field_shader_effect(&table)?,
+        )
     } else {
-        false
+        (false, read_actor_shader_effect(&table)?)
     };
     Ok(SongLuaTrackedActor {
         actor: SongLuaCapturedActor {
             initial_state,
             message_commands: Vec::new(),
             manual_hud_draw,
+            shader_effect,
         },
         table,
         target,
@@ -9048,6 +9104,20 @@ fn manually_draws_player_hud(player: &Table) -> Result<bool, String> {
     Ok(false)
 }
 
+fn player_note_field_shader_effect(player: &Table) -> Result<Option<ShaderEffect>, String> {
+    let Some(note_field) = player
+        .get::<Option<Table>>("__songlua_children")
+        .map_err(|err| err.to_string())?
+        .map(|children| children.get::<Option<Table>>("NoteField"))
+        .transpose()
+        .map_err(|err| err.to_string())?
+        .flatten()
+    else {
+        return Ok(None);
+    };
+    read_actor_shader_effect(&note_field)
+}
+
 pub fn tracked_indices_for_actor_pointers(
     tracked_actors: &[SongLuaTrackedActor],
     actor_ptrs: &HashSet<usize>,
@@ -10207,6 +10277,26 @@ pub fn read_actor_multi_vertex_texture_path(
     Ok(resolve_actor_asset_path(actor, &texture).ok())
 }
 
+pub fn read_actor_shader_effect(actor: &Table) -> Result<Option<ShaderEffect>, String> {
+    let Some(name) = actor
+        .get::<Option<String>>("__songlua_shader_effect")
+        .map_err(|err| err.to_string())?
+    else {
+        return Ok(None);
+    };
+    let params = actor
+        .get::<Option<Table>>("__songlua_shader_effect_params")
+        .map_err(|err| err.to_string())?
+        .map(|params| {
+            params
+                .sequence_values::<Value>()
+                .filter_map(|value| value.ok().and_then(read_f32))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    Ok(ShaderEffect::from_name(name.trim(), &params))
+}
+
 pub fn read_actor_multi_vertex_mesh(
     actor: &Table,
 ) -> Result<Option<Arc<[SongLuaOverlayMeshVertex]>>, String> {
@@ -10545,7 +10635,12 @@ where
         SongLuaOverlayKind::ActorFrame
     } else if actor_type.eq_ignore_ascii_case("ActorFrameTexture") {
         name = actor_aft_capture_name(actor).map_err(|err| err.to_string())?;
-        SongLuaOverlayKind::ActorFrameTexture
+        SongLuaOverlayKind::ActorFrameTexture {
+            preserve_texture: actor
+                .get::<Option<bool>>("__songlua_aft_preserve_texture")
+                .map_err(|err| err.to_string())?
+                .unwrap_or(false),
+        }
     } else if actor_type.eq_ignore_ascii_case("ActorProxy") {
         let Some(target) = read_proxy_target_kind(actor)? else {
             return Ok(None);
@@ -10557,7 +10652,10 @@ where
             .map_err(|err| err.to_string())?
             .filter(|name| !name.trim().is_empty())
         {
-            SongLuaOverlayKind::AftSprite { capture_name }
+            SongLuaOverlayKind::AftSprite {
+                capture_name,
+                shader_effect: read_actor_shader_effect(actor)?,
+            }
         } else {
             let Some(texture) = actor
                 .get::<Option<String>>("Texture")
@@ -10568,6 +10666,7 @@ where
             if aft_capture_names.contains(&texture) {
                 SongLuaOverlayKind::AftSprite {
                     capture_name: texture,
+                    shader_effect: read_actor_shader_effect(actor)?,
                 }
             } else {
                 let Some(texture_path) = resolve_actor_asset_path(actor, &texture).ok() else {
@@ -10577,6 +10676,7 @@ where
                 SongLuaOverlayKind::Sprite {
                     texture_path,
                     texture_key,
+                    shader_effect: read_actor_shader_effect(actor)?,
                 }
             }
         }
@@ -11,7 +11,7 @@ use deadsync_config::prelude::{
     SelectMusicStepArtistBoxMode, SelectMusicWheelStyle, SmxPackName, SmxPadPreset, SrpgVariant,
     VersionOverlaySide, VisualStyle,
 };
-use deadsync_input::{InputBinding, KeyCode, VirtualAction};
+use deadsync_input::{ControllerClaim, InputBinding, KeyCode, VirtualAction};
 use deadsync_profile::{ActiveProfile, PlayMode, PlayStyle, PlayerSide};
 use deadsync_simfile::sync_offset::SongOffsetSyncChange;
 use deadsync_theme::{AudioRequest, GraphicsRequest, PlatformRequest};
@@ -120,6 +120,34 @@ pub enum SimplyLoveProfileRequest {
     DeleteLocalProfile {
         profile_id: String,
     },
+    /// Persist a layout edited in Practice to the side's `hud_layout.ini`.
+    SaveHudLayout {
+        side: PlayerSide,
+        layout: deadsync_theme::HudLayout,
+    },
+    /// Bring the profile's progress history up to date with its score files
+    /// off the render thread; answered by [`SimplyLoveProfileHistoryResult`].
+    LoadProfileHistory {
+        profile_id: String,
+    },
+    CancelProfileHistory,
+    /// Write the daily and weekly aggregates to `history.json` in the
+    /// profile directory.
+    ExportProfileHistory {
+        profile_id: String,
+        display_name: String,
+    },
+    /// Gather the per-play offsets of the profile and of every profile on
+    /// this machine; answered by [`SimplyLoveOffsetDriftResult`].
+    LoadOffsetDrift {
+        profile_id: String,
+    },
+    CancelOffsetDrift,
+    /// Read the profile's workout logs; answered by
+    /// [`SimplyLoveWorkoutSummaryResult`].
+    LoadWorkoutSummary {
+        profile_id: String,
+    },
     DiscoverItgProfiles,
     BrowseItgProfiles {
         title: String,
@@ -196,6 +224,35 @@ pub enum SimplyLoveLocalProfileEvent {
         result: Result<(), ()>,
         view: ManageLocalProfilesView,
     },
+    HistoryExported {
+        profile_id: String,
+        result: Result<PathBuf, ()>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct SimplyLoveProfileHistoryResult {
+    pub profile_id: String,
+    pub history: Result<deadsync_score::ProfileHistory, String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SimplyLoveOffsetDriftReports {
+    pub profile: deadsync_score::OffsetDriftReport,
+    pub machine: deadsync_score::OffsetDriftReport,
+}
+
+#[derive(Clone, Debug)]
+pub struct SimplyLoveOffsetDriftResult {
+    pub profile_id: String,
+    pub reports: Result<SimplyLoveOffsetDriftReports, String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SimplyLoveWorkoutSummaryResult {
+    pub profile_id: String,
+    /// Newest week first.
+    pub weeks: Result<Vec<deadsync_score::WorkoutWeek>, String>,
 }
 
 #[derive(Clone, Debug)]
@@ -257,6 +314,10 @@ pub enum SimplyLoveLobbyRequest {
     },
     Leave,
     SelectSong(deadsync_online::lobbies::LobbySongInfo),
+    SongVote {
+        side: PlayerSide,
+        vote: deadsync_online::song_vote::LobbySongVote,
+    },
     UpdateMachineState {
         screen_name: &'static str,
         ready: bool,
@@ -401,6 +462,20 @@ pub enum SimplyLoveSyncRequest {
     ApplySongOffsetBatch {
         changes: Vec<SongOffsetSyncChange>,
     },
+    /// Store a pack-wide bias in the pack's `PackSync.ini`.
+    SetPackSyncBias {
+        pack_dir: PathBuf,
+        bias_ms: f32,
+    },
+    /// Move the pack's bias into the `#OFFSET` of each listed simfile.
+    ApplyPackSyncBias {
+        pack_dir: PathBuf,
+        simfile_paths: Vec<PathBuf>,
+    },
+    /// Revert the last bias rewrite recorded in the pack's journal.
+    UndoPackOffsetRewrite {
+        pack_dir: PathBuf,
+    },
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -425,6 +500,8 @@ pub enum SimplyLoveSongSearchRequest {
         query: String,
         scope: crate::screens::components::select_music::select_music_menu::SongSearchScope,
         chart_type: &'static str,
+        /// Joined players' scores and favorites for the query's player predicates.
+        player: Arc<deadsync_simfile::song_query::SongQueryPlayerState>,
     },
 }
 
@@ -435,6 +512,77 @@ pub struct SimplyLoveSongSearchResult {
     pub matches: Vec<crate::screens::components::select_music::select_music_menu::SongSearchMatch>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimplyLovePatternAnalysisRequest {
+    /// Re-judge the stored replays of the profile on `side`; practice charts
+    /// are picked from `chart_type`.
+    Start {
+        side: PlayerSide,
+        chart_type: &'static str,
+    },
+    Cancel,
+}
+
+/// Workout sessions are tracked by the shell across songs, per side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimplyLoveWorkoutRequest {
+    Start {
+        side: PlayerSide,
+        goal: deadsync_score::WorkoutGoal,
+    },
+    End {
+        side: PlayerSide,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct SimplyLovePatternAnalysisResult {
+    pub side: PlayerSide,
+    pub report: Result<deadsync_score::PatternReport, String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimplyLovePackLintRequest {
+    Start { pack_dir: PathBuf },
+    Cancel,
+}
+
+#[derive(Clone, Debug)]
+pub struct SimplyLovePackLintResult {
+    pub pack_dir: PathBuf,
+    pub report: Result<deadsync_simfile::lint::PackLintReport, String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimplyLoveSongLuaScanRequest {
+    Start { pack_dir: PathBuf },
+    Cancel,
+}
+
+#[derive(Clone, Debug)]
+pub struct SimplyLoveSongLuaScanResult {
+    pub pack_dir: PathBuf,
+    pub report: Result<deadsync_song_lua::SongLuaScanReport, String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimplyLoveDuplicateSongsRequest {
+    Scan,
+    Cancel,
+    /// Saves the report's hide/prefer choices and refreshes the wheel if they
+    /// changed.
+    Save {
+        choices: deadsync_simfile::duplicates::DuplicateChoices,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct SimplyLoveDuplicateSongsResult {
+    pub report: Result<deadsync_simfile::duplicates::DuplicateReport, String>,
+    /// Saved choices, minus those for songs that no longer have a copy.
+    pub choices: deadsync_simfile::duplicates::DuplicateChoices,
+}
+
 #[derive(Clone, Debug)]
 pub struct SimplyLoveSyncTarget {
     pub song: Arc<deadsync_chart::SongData>,
@@ -843,11 +991,19 @@ pub enum SimplyLoveHardwareRequest {
     },
     SetSmxPlayerLights([Option<[u8; 3]>; 2]),
     ReenableSmxAutoLights,
+    /// Route every input of a controller to `side` for the rest of the session.
+    ClaimController {
+        side: PlayerSide,
+        claim: ControllerClaim,
+    },
+    ReleaseController(PlayerSide),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SimplyLoveDebugRequest {
     WriteFsrDump,
+    ToggleLatencyReport,
+    WriteLatencyReport,
 }
 
 /// Updater work requested by Simply Love and executed by the process shell.
@@ -887,6 +1043,14 @@ pub enum SimplyLoveRuntimeRequest {
     Updater(SimplyLoveUpdaterRequest),
     /// Rank the song search off the render thread.
     SongSearch(SimplyLoveSongSearchRequest),
+    /// Analyze a profile's replays off the render thread.
+    PatternAnalysis(SimplyLovePatternAnalysisRequest),
+    Workout(SimplyLoveWorkoutRequest),
+    /// Lint a pack's simfiles off the render thread.
+    PackLint(SimplyLovePackLintRequest),
+    /// Compile a pack's song-lua headlessly off the render thread.
+    SongLuaScan(SimplyLoveSongLuaScanRequest),
+    DuplicateSongs(SimplyLoveDuplicateSongsRequest),
 }
 
 pub type SimplyLoveEffect = deadsync_theme::ThemeEffect<SimplyLoveScreen, SimplyLoveRuntimeRequest>;
@@ -15,24 +15,28 @@ pub mod visual_styles;
 
 pub use effects::{
     SimplyLoveAdvancedConfigRequest, SimplyLoveConfigRequest, SimplyLoveContentRequest,
-    SimplyLoveCourseConfigRequest, SimplyLoveDebugRequest, SimplyLoveEffect,
-    SimplyLoveEffectRouteContext, SimplyLoveEffectRoutePlan, SimplyLoveGameplayConfigRequest,
-    SimplyLoveGameplayPadLights, SimplyLoveGraphOrientation, SimplyLoveGraphOrigin,
-    SimplyLoveHardwareRequest, SimplyLoveInputResult, SimplyLoveItgImportSummary,
-    SimplyLoveItgProfileCandidate, SimplyLoveLightsConfigRequest, SimplyLoveLightsDriver,
-    SimplyLoveLobbyRequest, SimplyLoveLocalProfileEvent, SimplyLoveMachineConfigRequest,
-    SimplyLoveMappingsConfigRequest, SimplyLoveMediaRequest, SimplyLoveNullOrDieConfigRequest,
-    SimplyLoveNullOrDieGraph, SimplyLoveOnlineConfigRequest, SimplyLoveOnlineRequest,
-    SimplyLoveOptionsConfigRequest, SimplyLoveProfileImportEvent, SimplyLoveProfileRequest,
+    SimplyLoveCourseConfigRequest, SimplyLoveDebugRequest, SimplyLoveDuplicateSongsRequest,
+    SimplyLoveDuplicateSongsResult, SimplyLoveEffect, SimplyLoveEffectRouteContext,
+    SimplyLoveEffectRoutePlan, SimplyLoveGameplayConfigRequest, SimplyLoveGameplayPadLights,
+    SimplyLoveGraphOrientation, SimplyLoveGraphOrigin, SimplyLoveHardwareRequest,
+    SimplyLoveInputResult, SimplyLoveItgImportSummary, SimplyLoveItgProfileCandidate,
+    SimplyLoveLightsConfigRequest, SimplyLoveLightsDriver, SimplyLoveLobbyRequest,
+    SimplyLoveLocalProfileEvent, SimplyLoveMachineConfigRequest, SimplyLoveMappingsConfigRequest,
+    SimplyLoveMediaRequest, SimplyLoveNullOrDieConfigRequest, SimplyLoveNullOrDieGraph,
+    SimplyLoveOffsetDriftReports, SimplyLoveOffsetDriftResult, SimplyLoveOnlineConfigRequest,
+    SimplyLoveOnlineRequest, SimplyLoveOptionsConfigRequest, SimplyLovePackLintRequest,
+    SimplyLovePackLintResult, SimplyLovePatternAnalysisRequest, SimplyLovePatternAnalysisResult,
+    SimplyLoveProfileHistoryResult, SimplyLoveProfileImportEvent, SimplyLoveProfileRequest,
     SimplyLoveQrLoginEvent, SimplyLoveQrLoginPolicy, SimplyLoveQrLoginRequest,
     SimplyLoveQrLoginService, SimplyLoveQrLoginSlot, SimplyLoveQrLoginSlotAvailability,
     SimplyLoveRuntimeRequest, SimplyLoveScoreImportEvent, SimplyLoveScoreImportProfile,
     SimplyLoveScoreImportProgress, SimplyLoveScoreImportRequest, SimplyLoveScoreImportSummary,
-    SimplyLoveSelectMusicConfigRequest, SimplyLoveSongSearchRequest, SimplyLoveSongSearchResult,
-    SimplyLoveSrpgShopFolder, SimplyLoveSyncEvent, SimplyLoveSyncKernel,
-    SimplyLoveSyncKernelTarget, SimplyLoveSyncOwner, SimplyLoveSyncPlotView, SimplyLoveSyncRequest,
-    SimplyLoveSyncResult, SimplyLoveSyncSongResult, SimplyLoveSyncStreamEvent,
-    SimplyLoveSyncTarget, SimplyLoveUpdaterRequest, resolve_effect_route,
+    SimplyLoveSelectMusicConfigRequest, SimplyLoveSongLuaScanRequest, SimplyLoveSongLuaScanResult,
+    SimplyLoveSongSearchRequest, SimplyLoveSongSearchResult, SimplyLoveSrpgShopFolder,
+    SimplyLoveSyncEvent, SimplyLoveSyncKernel, SimplyLoveSyncKernelTarget, SimplyLoveSyncOwner,
+    SimplyLoveSyncPlotView, SimplyLoveSyncRequest, SimplyLoveSyncResult, SimplyLoveSyncSongResult,
+    SimplyLoveSyncStreamEvent, SimplyLoveSyncTarget, SimplyLoveUpdaterRequest,
+    SimplyLoveWorkoutRequest, SimplyLoveWorkoutSummaryResult, resolve_effect_route,
 };
 
 pub use screens::components::select_music::select_music_menu::{
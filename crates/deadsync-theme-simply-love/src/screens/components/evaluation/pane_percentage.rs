@@ -2,9 +2,11 @@ use crate::act;
 use crate::assets::{FontRole, machine_font_key};
 use crate::config::MachineFont;
 use crate::screens::evaluation::{EvalPane, ScoreInfo};
+use crate::views::AltScoreView;
 use deadlib_present::actors::{Actor, SizeSpec, TextContent};
 use deadlib_present::color;
 use deadsync_profile as profile_data;
+use deadsync_rules::scoring::ScoringModelKind;
 
 use super::utils::{eval_style_alpha, pane_origin_x, pane3_origin_x};
 
@@ -23,6 +25,7 @@ pub(crate) struct PercentageText {
     score: TextContent,
     ex: TextContent,
     hard_ex: TextContent,
+    alt: Option<(TextContent, TextContent)>,
 }
 
 impl PercentageText {
@@ -31,10 +34,28 @@ impl PercentageText {
             score: percent_text(score.score_percent * 100.0),
             ex: percent_text(score.ex_score_percent.max(0.0)),
             hard_ex: percent_text(score.hard_ex_score_percent.max(0.0)),
+            alt: score.alt_score.as_ref().map(alt_text),
         }
     }
 }
 
+fn alt_text(alt: &AltScoreView) -> (TextContent, TextContent) {
+    let score = alt.score;
+    match alt.kind {
+        // DDR shows the raw money score rather than a percentage.
+        ScoringModelKind::DdrMoney => (
+            super::retained_str("DDR"),
+            super::retained_text(format_args!("{:.0}", score.points.max(0.0))),
+        ),
+        ScoringModelKind::Wife3 => (super::retained_str("WIFE3"), percent_text(score.percent())),
+        // Custom windows are labelled with the name their file gives them.
+        ScoringModelKind::Custom => (
+            super::retained_str(alt.custom_name.as_deref().unwrap_or("CUSTOM")),
+            percent_text(score.percent()),
+        ),
+    }
+}
+
 #[inline]
 fn percent_text(value: f64) -> TextContent {
     super::retained_text(format_args!("{value:.2}"))
@@ -232,10 +253,17 @@ pub(crate) fn build_pane_percentage_display(
             ));
         }
         EvalPane::Standard => {
+            // A chosen scoring model grows the box to fit a companion line,
+            // laid out like the H.EX line on the Hard EX pane.
+            let (bg_y, bg_h) = if text.alt.is_some() {
+                (14.0, 88.0)
+            } else {
+                (0.0, 60.0)
+            };
             children.push(act!(quad:
                 align(bg_align_x, 0.5):
-                xy(bg_x, 0.0):
-                setsize(158.5, 60.0):
+                xy(bg_x, bg_y):
+                setsize(158.5, bg_h):
                 diffuse(score_bg_color[0], score_bg_color[1], score_bg_color[2], score_bg_alpha)
             ));
             children.push(act!(text:
@@ -246,6 +274,29 @@ pub(crate) fn build_pane_percentage_display(
                 zoom(score_zoom):
                 horizalign(right)
             ));
+            if let Some((label, value)) = text.alt.as_ref() {
+                let bottom_value_x = if controller == profile_data::PlayerSide::P1 {
+                    0.0
+                } else {
+                    percent_x
+                };
+                children.push(act!(text:
+                    font(machine_font_key(machine_font, FontRole::Bold)):
+                    settext(label.clone()):
+                    align(1.0, 0.5):
+                    xy(bottom_value_x - 92.0, 40.0):
+                    zoom(0.5):
+                    horizalign(right)
+                ));
+                children.push(act!(text:
+                    font(machine_font_key(machine_font, FontRole::Headline)):
+                    settext(value.clone()):
+                    align(1.0, 0.5):
+                    xy(bottom_value_x, 40.0):
+                    zoom(companion_score_zoom):
+                    horizalign(right)
+                ));
+            }
         }
     }
 
@@ -274,6 +325,41 @@ mod tests {
         assert!(matches!(text, TextContent::Inline(_)));
     }
 
+    #[test]
+    fn alt_text_formats_ddr_money_as_points() {
+        let mut alt = AltScoreView {
+            kind: ScoringModelKind::DdrMoney,
+            custom_name: None,
+            score: deadsync_rules::scoring::AltScore {
+                points: 987_650.0,
+                possible: 1_000_000.0,
+            },
+        };
+        let (label, text) = alt_text(&alt);
+        assert_eq!(label.as_str(), "DDR");
+        assert_eq!(text.as_str(), "987650");
+
+        alt.kind = ScoringModelKind::Wife3;
+        let (label, text) = alt_text(&alt);
+        assert_eq!(label.as_str(), "WIFE3");
+        assert_eq!(text.as_str(), "98.77");
+    }
+
+    #[test]
+    fn alt_text_labels_custom_windows_with_their_name() {
+        let alt = AltScoreView {
+            kind: ScoringModelKind::Custom,
+            custom_name: Some(std::sync::Arc::from("Tight")),
+            score: deadsync_rules::scoring::AltScore {
+                points: 1.0,
+                possible: 2.0,
+            },
+        };
+        let (label, text) = alt_text(&alt);
+        assert_eq!(label.as_str(), "Tight");
+        assert_eq!(text.as_str(), "50.00");
+    }
+
     #[test]
     fn percentage_text_shares_oversized_fallback() {
         let text = percent_text(f64::MAX);
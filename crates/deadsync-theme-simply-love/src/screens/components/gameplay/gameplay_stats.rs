@@ -27,6 +27,7 @@ use deadsync_profile as profile_data;
 use deadsync_profile_gameplay::score_display_mode_from_profile;
 use deadsync_rules::judgment::{self, JudgeGrade};
 use deadsync_rules::timing::LiveTimingSnapshot;
+use deadsync_theme::{HudElement, HudElementLayout};
 use std::cell::RefCell;
 use std::sync::{Arc, LazyLock};
 
@@ -329,12 +330,17 @@ impl GameplayStatsTextPlan {
     }
 
     #[inline(always)]
-    fn judgment(&self, index: usize) -> Arc<str> {
+    pub(crate) fn judgment(&self, index: usize) -> Arc<str> {
         self.judgments
             .get(index)
             .map_or_else(|| Arc::clone(&EMPTY_STATS_TEXT), Arc::clone)
     }
 
+    #[inline(always)]
+    pub(crate) fn song_artist(&self) -> Arc<str> {
+        Arc::clone(&self.song_artist)
+    }
+
     #[inline(always)]
     fn description(&self, player: usize, elapsed: f32) -> Arc<str> {
         let Some(values) = self.descriptions.get(player) else {
@@ -1818,11 +1824,11 @@ pub fn push_step_stats(
 ) {
     let wide = is_wide();
     let mask = step_stats_mask(state, player_side);
-    if mask.is_empty() {
+    let player_idx = step_stats_player_idx(state, player_side);
+    if mask.is_empty() || step_stats_hud_layout(state, player_idx).hidden {
         return;
     }
     let layout = step_stats_pane_layout(state, playfield_center_x, player_side);
-    let player_idx = step_stats_player_idx(state, player_side);
     let extra_actor_count = state.step_stats_extra_resolved[player_idx].actor_count();
     actors.reserve((if wide { 48 } else { 1 }) + extra_actor_count);
     if mask.contains(profile_data::StepStatisticsMask::SONG_BANNER) {
@@ -1857,7 +1863,6 @@ pub fn push_step_stats(
         mask,
     );
     if mask.contains(profile_data::StepStatisticsMask::STEP_COUNTS) {
-        let player_idx = step_stats_player_idx(state, player_side);
         if state.profiles()[player_idx].display_scorebox {
             build_scorebox_pane(actors, state, layout, wide, player_side);
         } else {
@@ -1866,12 +1871,21 @@ pub fn push_step_stats(
     }
 }
 
+#[inline(always)]
+fn step_stats_hud_layout(state: &State, player_idx: usize) -> HudElementLayout {
+    state
+        .notefield_plan(player_idx)
+        .hud_layout()
+        .get(HudElement::StepStats)
+}
+
 fn step_stats_pane_layout(
     state: &State,
     playfield_center_x: f32,
     player_side: profile_data::PlayerSide,
 ) -> StepStatsPaneLayout {
-    step_stats_theme::pane_layout(StepStatsPaneParams {
+    let hud = step_stats_hud_layout(state, step_stats_player_idx(state, player_side));
+    let layout = step_stats_theme::pane_layout(StepStatsPaneParams {
         screen_w: screen_width(),
         screen_h: screen_height(),
         screen_center_x: screen_center_x(),
@@ -1881,7 +1895,37 @@ fn step_stats_pane_layout(
         num_players: state.num_players(),
         notefield_width: notefield_width(state),
         wide: is_wide(),
-    })
+    });
+    if hud.is_identity() {
+        return layout;
+    }
+    let [sidepane_center_x, sidepane_center_y] =
+        hud.apply([layout.sidepane_center_x, layout.sidepane_center_y]);
+    StepStatsPaneLayout {
+        sidepane_center_x,
+        sidepane_center_y,
+        sidepane_width: layout.sidepane_width * hud.zoom,
+        banner_data_zoom: layout.banner_data_zoom * hud.zoom,
+        ..layout
+    }
+}
+
+/// Pane center and width of the side step stats for `player_idx`, as the HUD
+/// layout editor frames them; `None` when that pane is not drawn.
+pub(crate) fn step_stats_anchor(state: &State, player_idx: usize) -> Option<([f32; 2], f32)> {
+    if !state.shows_side_step_stats() {
+        return None;
+    }
+    let side = gameplay_screen::runtime_profile_side(state, player_idx);
+    if step_stats_mask(state, side).is_empty() {
+        return None;
+    }
+    let playfield_center_x = state.notefield_hud_anchors(player_idx).playfield_center_x;
+    let layout = step_stats_pane_layout(state, playfield_center_x, side);
+    Some((
+        [layout.sidepane_center_x, layout.sidepane_center_y],
+        layout.sidepane_width,
+    ))
 }
 
 pub fn push_heart_rates(actors: &mut Vec<Actor>, state: &State, playfield_center_x: f32) {
@@ -23,7 +23,7 @@ use deadsync_notefield::{
 };
 use deadsync_notefield::{FieldPlacement, ProxyCaptureRequests, ViewOverride};
 use deadsync_profile as profile_data;
-use deadsync_theme::NotefieldStyle;
+use deadsync_theme::{HudLayout, HudLayoutLimits, NotefieldStyle};
 use std::array::from_fn;
 use std::cell::{Cell, RefCell};
 
@@ -427,6 +427,32 @@ pub(crate) struct GameplayNotefieldPlan {
     combo_font: Option<&'static str>,
 }
 
+impl GameplayNotefieldPlan {
+    #[inline(always)]
+    pub(crate) const fn hud_layout(&self) -> &HudLayout {
+        &self.options.hud_layout
+    }
+
+    #[inline(always)]
+    pub(crate) fn hud_layout_limits(&self) -> HudLayoutLimits {
+        HudLayoutLimits::from_style(&self.style)
+    }
+
+    /// Used by the layout editor; the next composed frame picks it up.
+    pub(crate) fn set_hud_layout(&mut self, layout: HudLayout) {
+        self.options.hud_layout = layout.validated(&self.hud_layout_limits()).0;
+    }
+}
+
+/// Hand-edited layout files may exceed what this theme's HUD can fit.
+pub(crate) fn validated_hud_layout(layout: &HudLayout, style: &NotefieldStyle) -> HudLayout {
+    let (layout, issues) = layout.validated(&HudLayoutLimits::from_style(style));
+    for issue in issues {
+        log::warn!("HUD layout: {issue}");
+    }
+    layout
+}
+
 pub(crate) fn gameplay_notefield_plan(
     profile: &profile_data::Profile,
     judgment_assets: &ResolvedJudgmentAssets,
@@ -548,6 +574,7 @@ pub(crate) fn gameplay_notefield_plan(
         mini_indicator_position,
         mini_indicator_zoom: zmod_mini_indicator_zoom(profile.mini_indicator_size),
         counter_left: profile.measure_counter_left,
+        hud_layout: validated_hud_layout(&profile.hud_layout, &style),
     };
     GameplayNotefieldPlan {
         style,
@@ -1033,6 +1060,7 @@ pub(crate) fn compose_frame(
         judgment_draw_range: hud_result.judgment_draw_range,
         combo_actors: hud_result.combo_actors,
         combo_draw_range: hud_result.combo_draw_range,
+        hud_anchors: hud_result.anchors,
     }
 }
 
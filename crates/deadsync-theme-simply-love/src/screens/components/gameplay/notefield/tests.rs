@@ -29,6 +29,10 @@ fn song_plan_preserves_profile_derived_notefield_behavior() {
     profile.combo_font = profile_data::ComboFont::SourceCode;
     profile.error_bar_active_mask =
         profile_data::ErrorBarMask::COLORFUL | profile_data::ErrorBarMask::TEXT;
+    profile
+        .hud_layout
+        .get_mut(deadsync_theme::HudElement::ErrorBar)
+        .zoom = 4.0;
     let judgment_assets = ResolvedJudgmentAssets::from_profile(&profile);
     let plan = gameplay_notefield_plan(&profile, &judgment_assets, 0.012_345);
 
@@ -50,6 +54,12 @@ fn song_plan_preserves_profile_derived_notefield_behavior() {
     assert!(plan.options.error_bar_modes.colorful);
     assert!(plan.options.frame_features.error_bar_text);
     assert_eq!(plan.options.blue_fantastic_window_s, 0.012_345);
+    assert_eq!(
+        plan.hud_layout()
+            .get(deadsync_theme::HudElement::ErrorBar)
+            .zoom,
+        deadsync_theme::HudLayoutLimits::from_style(&plan.style).error_bar_zoom_max
+    );
     assert_eq!(plan.small_combo_font, "combo_source_code");
     assert_eq!(plan.combo_font, Some("combo_source_code"));
     assert_eq!(
@@ -4,6 +4,7 @@ use crate::config::MachineFont;
 use crate::config::{
     DefaultSyncOffset, SelectMusicItlRankMode, SelectMusicItlWheelMode, SelectMusicSongSelectBgMode,
 };
+use crate::screens::components::select_music::select_music_menu::song_lua_level_rgba;
 use crate::screens::components::shared::banner as shared_banner;
 use crate::screens::select_music::MusicWheelEntry;
 use crate::views::{MUSIC_WHEEL_SLOT_COUNT, MusicWheelRuntimeView, MusicWheelSlotRuntimeRequest};
@@ -22,6 +23,7 @@ use deadsync_chart::{
 use deadsync_profile as profile_data;
 use deadsync_score as score_data;
 use deadsync_simfile::event_intro::is_srpg_event_song;
+use deadsync_song_lua::SongLuaCompatibilityLevel;
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -770,6 +772,8 @@ pub struct MusicWheelParams<'a> {
     pub song_box_color: Option<[f32; 4]>,
     pub song_text_color: Option<[f32; 4]>,
     pub song_text_color_overrides: Option<&'a HashMap<usize, [f32; 4]>>,
+    /// Song-lua scan levels by simfile path; tints the lua badge.
+    pub song_lua_levels: Option<&'a FxHashMap<PathBuf, SongLuaCompatibilityLevel>>,
     pub show_pack_sync: bool,
     pub show_music_wheel_grades: bool,
     pub show_music_wheel_lamps: bool,
@@ -1197,10 +1201,15 @@ pub fn push(actors: &mut Vec<Actor>, p: MusicWheelParams) {
                                 z(53)
                             ));
                         }
+                        let lua_rgba = p
+                            .song_lua_levels
+                            .and_then(|levels| levels.get(&info.simfile_path).copied())
+                            .map_or([1.0; 4], song_lua_level_rgba);
                         actors.push(act!(sprite("has_lua.png"):
                             align(1.0, 0.5):
                             xy(highlight_left_world + lua_x, y_center_item):
                             zoom(WHEEL_BADGE_ZOOM):
+                            diffuse(lua_rgba[0], lua_rgba[1], lua_rgba[2], lua_rgba[3]):
                             z(54)
                         ));
                     }
@@ -0,0 +1,274 @@
+use crate::act;
+use crate::assets::i18n::{tr, tr_fmt};
+use crate::assets::{FontRole, machine_font_key};
+use crate::config::MachineFont;
+use deadlib_present::actors::Actor;
+use deadlib_present::space::{screen_center_x, screen_center_y, screen_height, screen_width};
+use deadsync_input::{ControllerClaim, InputEvent, PadEvent, VirtualAction};
+use deadsync_profile::PlayerSide;
+
+pub const CONTROLLER_CLAIM_INPUT_LOCK_SECONDS: f32 = 0.15;
+
+const CLAIM_ROW_HEIGHT: f32 = 24.0;
+const CLAIM_PANE_WIDTH: f32 = 360.0;
+const CLAIM_DIM_ALPHA: f32 = 0.875;
+const CLAIM_Z: i16 = 1480;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ControllerClaimOverlayState {
+    Hidden,
+    Visible { side: PlayerSide, input_lock: f32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControllerClaimOutcome {
+    None,
+    Closed,
+    Claimed(ControllerClaim),
+    Released,
+}
+
+impl ControllerClaimOverlayState {
+    #[inline(always)]
+    pub const fn is_hidden(&self) -> bool {
+        matches!(self, Self::Hidden)
+    }
+
+    #[inline(always)]
+    pub const fn side(&self) -> Option<PlayerSide> {
+        match self {
+            Self::Hidden => None,
+            Self::Visible { side, .. } => Some(*side),
+        }
+    }
+
+    #[inline(always)]
+    const fn ready(&self) -> bool {
+        matches!(self, Self::Visible { input_lock, .. } if *input_lock <= 0.0)
+    }
+}
+
+pub fn begin_controller_claim_overlay(side: PlayerSide) -> ControllerClaimOverlayState {
+    ControllerClaimOverlayState::Visible {
+        side,
+        input_lock: CONTROLLER_CLAIM_INPUT_LOCK_SECONDS,
+    }
+}
+
+pub fn update_controller_claim_overlay(state: &mut ControllerClaimOverlayState, dt: f32) -> bool {
+    match state {
+        ControllerClaimOverlayState::Hidden => false,
+        ControllerClaimOverlayState::Visible { input_lock, .. } => {
+            *input_lock = (*input_lock - dt.max(0.0)).max(0.0);
+            true
+        }
+    }
+}
+
+/// The first raw button press from any controller claims it. Directions and
+/// axes are left alone so a pad's panels still reach the mapped inputs.
+pub fn handle_controller_claim_raw_pad(
+    state: &mut ControllerClaimOverlayState,
+    ev: &PadEvent,
+) -> ControllerClaimOutcome {
+    let PadEvent::RawButton {
+        id,
+        uuid,
+        pressed: true,
+        ..
+    } = *ev
+    else {
+        return ControllerClaimOutcome::None;
+    };
+    if !state.ready() {
+        return ControllerClaimOutcome::None;
+    }
+    *state = ControllerClaimOverlayState::Hidden;
+    ControllerClaimOutcome::Claimed(ControllerClaim { pad: id, uuid })
+}
+
+pub fn handle_controller_claim_input(
+    state: &mut ControllerClaimOverlayState,
+    ev: &InputEvent,
+) -> ControllerClaimOutcome {
+    if !ev.pressed || !state.ready() {
+        return ControllerClaimOutcome::None;
+    }
+    match ev.action {
+        VirtualAction::p1_select | VirtualAction::p2_select => {
+            *state = ControllerClaimOverlayState::Hidden;
+            ControllerClaimOutcome::Released
+        }
+        VirtualAction::p1_back | VirtualAction::p2_back => {
+            *state = ControllerClaimOverlayState::Hidden;
+            ControllerClaimOutcome::Closed
+        }
+        _ => ControllerClaimOutcome::None,
+    }
+}
+
+fn push_text(actors: &mut Vec<Actor>, text: String, x: f32, y: f32, zoom: f32, rgba: [f32; 4]) {
+    actors.push(act!(text:
+        font("miso"):
+        settext(text):
+        align(0.5, 0.5):
+        xy(x, y):
+        zoom(zoom):
+        maxwidth(CLAIM_PANE_WIDTH - 20.0):
+        diffuse(rgba[0], rgba[1], rgba[2], rgba[3]):
+        z(CLAIM_Z + 7):
+        horizalign(center)
+    ));
+}
+
+pub fn build_controller_claim_overlay(
+    state: &ControllerClaimOverlayState,
+    claim: Option<ControllerClaim>,
+    display_name: &str,
+    machine_font: MachineFont,
+) -> Option<Vec<Actor>> {
+    let ControllerClaimOverlayState::Visible { side, .. } = *state else {
+        return None;
+    };
+    // Header, prompt and current claim.
+    let pane_h = 3.0 * CLAIM_ROW_HEIGHT + 12.0;
+    let cx = screen_center_x();
+    let cy = screen_center_y() - 15.0;
+    let top = cy - pane_h * 0.5;
+    let mut actors = Vec::with_capacity(9);
+    actors.push(act!(quad:
+        align(0.0, 0.0): xy(0.0, 0.0):
+        zoomto(screen_width(), screen_height()):
+        diffuse(0.0, 0.0, 0.0, CLAIM_DIM_ALPHA):
+        z(CLAIM_Z)
+    ));
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(cx, cy):
+        zoomto(CLAIM_PANE_WIDTH + 2.0, pane_h + 2.0):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(CLAIM_Z + 2)
+    ));
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(cx, cy):
+        zoomto(CLAIM_PANE_WIDTH, pane_h):
+        diffuse(0.0, 0.0, 0.0, 1.0):
+        z(CLAIM_Z + 3)
+    ));
+    let header_y = top + CLAIM_ROW_HEIGHT * 0.5;
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(cx, header_y):
+        zoomto(CLAIM_PANE_WIDTH, CLAIM_ROW_HEIGHT):
+        diffuse(0.0, 0.0, 1.0, 1.0):
+        z(CLAIM_Z + 5)
+    ));
+    actors.push(act!(text:
+        font(machine_font_key(machine_font, FontRole::Header)):
+        settext(tr("SelectMusic", "ControllerClaimTitle")):
+        align(0.5, 0.5):
+        xy(cx, header_y):
+        zoom(0.5):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(CLAIM_Z + 6):
+        horizalign(center)
+    ));
+    push_text(
+        &mut actors,
+        display_name.to_string(),
+        cx,
+        top - 24.0,
+        0.8,
+        [1.0, 1.0, 1.0, 1.0],
+    );
+    let player = match side {
+        PlayerSide::P1 => "P1",
+        PlayerSide::P2 => "P2",
+    };
+    push_text(
+        &mut actors,
+        tr_fmt(
+            "SelectMusic",
+            "ControllerClaimPrompt",
+            &[("player", player)],
+        )
+        .to_string(),
+        cx,
+        top + CLAIM_ROW_HEIGHT * 1.5 + 3.0,
+        0.75,
+        [1.0, 1.0, 1.0, 1.0],
+    );
+    let current = claim.map_or_else(
+        || tr("SelectMusic", "ControllerClaimNone").to_string(),
+        |claim| {
+            tr_fmt(
+                "SelectMusic",
+                "ControllerClaimCurrent",
+                &[("pad", &claim.pad.0.to_string())],
+            )
+            .to_string()
+        },
+    );
+    push_text(
+        &mut actors,
+        current,
+        cx,
+        top + CLAIM_ROW_HEIGHT * 2.5 + 6.0,
+        0.75,
+        [0.78, 0.78, 0.78, 1.0],
+    );
+    actors.push(act!(text:
+        font("miso"):
+        settext(tr("SelectMusic", "ControllerClaimHelp")):
+        align(0.5, 0.5):
+        xy(screen_center_x(), screen_height() - 50.0):
+        zoom(1.1):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(CLAIM_Z + 8):
+        horizalign(center)
+    ));
+    Some(actors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deadsync_input::{PadCode, PadId};
+    use std::time::Instant;
+
+    fn button(pressed: bool) -> PadEvent {
+        PadEvent::RawButton {
+            id: PadId(2),
+            timestamp: Instant::now(),
+            host_nanos: 0,
+            code: PadCode(5),
+            uuid: [9; 16],
+            value: if pressed { 1.0 } else { 0.0 },
+            pressed,
+        }
+    }
+
+    #[test]
+    fn first_button_press_claims_the_controller() {
+        let mut state = begin_controller_claim_overlay(PlayerSide::P2);
+        assert_eq!(
+            handle_controller_claim_raw_pad(&mut state, &button(true)),
+            ControllerClaimOutcome::None,
+            "input lock swallows the press that opened the overlay"
+        );
+        update_controller_claim_overlay(&mut state, 1.0);
+        assert_eq!(
+            handle_controller_claim_raw_pad(&mut state, &button(false)),
+            ControllerClaimOutcome::None
+        );
+        assert_eq!(
+            handle_controller_claim_raw_pad(&mut state, &button(true)),
+            ControllerClaimOutcome::Claimed(ControllerClaim {
+                pad: PadId(2),
+                uuid: [9; 16],
+            })
+        );
+        assert!(state.is_hidden());
+    }
+}
@@ -0,0 +1,635 @@
+use crate::act;
+use crate::assets::i18n::{tr, tr_fmt};
+use crate::assets::{FontRole, machine_font_key};
+use crate::config::MachineFont;
+use deadlib_present::actors::Actor;
+use deadlib_present::space::{screen_center_x, screen_center_y, screen_height, screen_width};
+use deadsync_input::{InputEvent, VirtualAction};
+use deadsync_simfile::duplicates::{
+    DuplicateChoice, DuplicateChoices, DuplicateGroup, DuplicateReport,
+};
+
+pub const DUPLICATE_SONGS_INPUT_LOCK_SECONDS: f32 = 0.15;
+
+const DUPLICATE_SONGS_NUM_ROWS: usize = 13;
+const DUPLICATE_SONGS_ROW_HEIGHT: f32 = 22.0;
+const DUPLICATE_SONGS_PANE_HEIGHT: f32 = 360.0;
+const DUPLICATE_SONGS_PANE_WIDTH: f32 = 620.0;
+const DUPLICATE_SONGS_PANE_CENTER_Y: f32 = -15.0;
+const DUPLICATE_SONGS_DIM_ALPHA: f32 = 0.875;
+const DUPLICATE_SONGS_Z: i16 = 1480;
+const DUPLICATE_SONGS_TEXT_RGBA: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const DUPLICATE_SONGS_DIM_RGBA: [f32; 4] = [0.65, 0.65, 0.65, 1.0];
+const DUPLICATE_SONGS_PREFERRED_RGBA: [f32; 4] = [0.4, 1.0, 0.5, 1.0];
+const DUPLICATE_SONGS_ERROR_RGBA: [f32; 4] = [1.0, 0.35, 0.35, 1.0];
+const DUPLICATE_SONGS_CURSOR_RGBA: [f32; 4] = [0.2, 0.2, 0.45, 1.0];
+
+/// A report row: a song's group header or one of its copies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DuplicateSongsRow {
+    Group(usize),
+    Copy { group: usize, copy: usize },
+}
+
+#[derive(Clone, Debug)]
+pub struct DuplicateSongsOverlayStateData {
+    pub report: DuplicateReport,
+    pub choices: DuplicateChoices,
+    pub summary: String,
+    pub rows: Vec<DuplicateSongsRow>,
+    /// Row index of the selected copy.
+    pub cursor: usize,
+    pub scroll: usize,
+    pub input_lock: f32,
+}
+
+#[derive(Clone, Debug)]
+pub enum DuplicateSongsOverlayState {
+    Hidden,
+    /// Waiting on the shell worker.
+    Loading,
+    Visible(Box<DuplicateSongsOverlayStateData>),
+    Failed {
+        error: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DuplicateSongsInputOutcome {
+    None,
+    Moved,
+    /// A copy was hidden, shown or preferred.
+    Changed,
+    /// Closed while the worker was still running.
+    Cancelled,
+    /// Closed; carries the edited choices when a report was shown.
+    Closed(Option<DuplicateChoices>),
+}
+
+impl DuplicateSongsOverlayState {
+    #[inline(always)]
+    pub const fn is_hidden(&self) -> bool {
+        matches!(self, Self::Hidden)
+    }
+}
+
+fn report_rows(report: &DuplicateReport) -> Vec<DuplicateSongsRow> {
+    let mut rows = Vec::new();
+    for (group_ix, group) in report.groups.iter().enumerate() {
+        rows.push(DuplicateSongsRow::Group(group_ix));
+        rows.extend((0..group.copies.len()).map(|copy| DuplicateSongsRow::Copy {
+            group: group_ix,
+            copy,
+        }));
+    }
+    rows
+}
+
+pub fn begin_duplicate_songs_overlay(
+    report: Result<DuplicateReport, String>,
+    choices: DuplicateChoices,
+) -> DuplicateSongsOverlayState {
+    let report = match report {
+        Ok(report) => report,
+        Err(error) => return DuplicateSongsOverlayState::Failed { error },
+    };
+    let summary = tr_fmt(
+        "SelectMusic",
+        "DuplicateSongsSummary",
+        &[
+            ("songs", &report.songs_checked.to_string()),
+            ("groups", &report.groups.len().to_string()),
+        ],
+    )
+    .to_string();
+    let rows = report_rows(&report);
+    DuplicateSongsOverlayState::Visible(Box::new(DuplicateSongsOverlayStateData {
+        report,
+        choices,
+        summary,
+        cursor: rows
+            .iter()
+            .position(|row| matches!(row, DuplicateSongsRow::Copy { .. }))
+            .unwrap_or(0),
+        rows,
+        scroll: 0,
+        input_lock: DUPLICATE_SONGS_INPUT_LOCK_SECONDS,
+    }))
+}
+
+pub fn update_duplicate_songs_overlay(state: &mut DuplicateSongsOverlayState, dt: f32) -> bool {
+    match state {
+        DuplicateSongsOverlayState::Hidden => false,
+        DuplicateSongsOverlayState::Visible(overlay) => {
+            overlay.input_lock = (overlay.input_lock - dt.max(0.0)).max(0.0);
+            true
+        }
+        _ => true,
+    }
+}
+
+fn selected(overlay: &DuplicateSongsOverlayStateData) -> Option<(&DuplicateGroup, usize)> {
+    match overlay.rows.get(overlay.cursor)? {
+        DuplicateSongsRow::Copy { group, copy } => {
+            Some((overlay.report.groups.get(*group)?, *copy))
+        }
+        DuplicateSongsRow::Group(_) => None,
+    }
+}
+
+/// Moves to the next copy row in `delta`'s direction, keeping it and its
+/// group header on screen.
+fn move_cursor(overlay: &mut DuplicateSongsOverlayStateData, delta: isize) -> bool {
+    let mut row = overlay.cursor as isize;
+    loop {
+        row += delta;
+        if row < 0 {
+            return false;
+        }
+        match overlay.rows.get(row as usize) {
+            None => return false,
+            Some(DuplicateSongsRow::Copy { .. }) => break,
+            Some(DuplicateSongsRow::Group(_)) => {}
+        }
+    }
+    let row = row as usize;
+    overlay.cursor = row;
+    let top = if matches!(
+        overlay.rows.get(row.wrapping_sub(1)),
+        Some(DuplicateSongsRow::Group(_))
+    ) {
+        row - 1
+    } else {
+        row
+    };
+    if top < overlay.scroll {
+        overlay.scroll = top;
+    } else if row >= overlay.scroll + DUPLICATE_SONGS_NUM_ROWS {
+        overlay.scroll = row + 1 - DUPLICATE_SONGS_NUM_ROWS;
+    }
+    true
+}
+
+pub fn handle_duplicate_songs_input(
+    state: &mut DuplicateSongsOverlayState,
+    ev: &InputEvent,
+) -> DuplicateSongsInputOutcome {
+    if !ev.pressed {
+        return DuplicateSongsInputOutcome::None;
+    }
+    let closing = matches!(
+        ev.action,
+        VirtualAction::p1_back
+            | VirtualAction::p2_back
+            | VirtualAction::p1_select
+            | VirtualAction::p2_select
+    );
+    let overlay = match state {
+        DuplicateSongsOverlayState::Hidden => return DuplicateSongsInputOutcome::None,
+        DuplicateSongsOverlayState::Loading => {
+            if closing {
+                *state = DuplicateSongsOverlayState::Hidden;
+                return DuplicateSongsInputOutcome::Cancelled;
+            }
+            return DuplicateSongsInputOutcome::None;
+        }
+        DuplicateSongsOverlayState::Failed { .. } => {
+            if closing || matches!(ev.action, VirtualAction::p1_start | VirtualAction::p2_start) {
+                *state = DuplicateSongsOverlayState::Hidden;
+                return DuplicateSongsInputOutcome::Closed(None);
+            }
+            return DuplicateSongsInputOutcome::None;
+        }
+        DuplicateSongsOverlayState::Visible(overlay) => overlay,
+    };
+    if overlay.input_lock > 0.0 {
+        return DuplicateSongsInputOutcome::None;
+    }
+    if closing {
+        let choices = std::mem::take(&mut overlay.choices);
+        *state = DuplicateSongsOverlayState::Hidden;
+        return DuplicateSongsInputOutcome::Closed(Some(choices));
+    }
+    match ev.action {
+        VirtualAction::p1_up
+        | VirtualAction::p1_menu_up
+        | VirtualAction::p2_up
+        | VirtualAction::p2_menu_up => {
+            if move_cursor(overlay, -1) {
+                DuplicateSongsInputOutcome::Moved
+            } else {
+                DuplicateSongsInputOutcome::None
+            }
+        }
+        VirtualAction::p1_down
+        | VirtualAction::p1_menu_down
+        | VirtualAction::p2_down
+        | VirtualAction::p2_menu_down => {
+            if move_cursor(overlay, 1) {
+                DuplicateSongsInputOutcome::Moved
+            } else {
+                DuplicateSongsInputOutcome::None
+            }
+        }
+        VirtualAction::p1_left
+        | VirtualAction::p1_menu_left
+        | VirtualAction::p2_left
+        | VirtualAction::p2_menu_left
+        | VirtualAction::p1_right
+        | VirtualAction::p1_menu_right
+        | VirtualAction::p2_right
+        | VirtualAction::p2_menu_right => {
+            let Some((group, copy)) = selected(overlay) else {
+                return DuplicateSongsInputOutcome::None;
+            };
+            let path = group.copies[copy].simfile_path.clone();
+            overlay.choices.toggle_hidden(&path);
+            DuplicateSongsInputOutcome::Changed
+        }
+        VirtualAction::p1_start | VirtualAction::p2_start => {
+            let Some((group, copy)) = selected(overlay) else {
+                return DuplicateSongsInputOutcome::None;
+            };
+            let group = group.clone();
+            overlay.choices.toggle_preferred(&group, copy);
+            DuplicateSongsInputOutcome::Changed
+        }
+        _ => DuplicateSongsInputOutcome::None,
+    }
+}
+
+fn group_text(group: &DuplicateGroup) -> String {
+    let reason = match (group.same_charts, group.same_audio) {
+        (true, true) => tr("SelectMusic", "DuplicateSongsSameBoth"),
+        (true, false) => tr("SelectMusic", "DuplicateSongsSameCharts"),
+        _ => tr("SelectMusic", "DuplicateSongsSameAudio"),
+    };
+    let title = group.copies.first().map_or("", |copy| copy.title.as_str());
+    format!("{title} · {reason}")
+}
+
+fn choice_text(choice: Option<DuplicateChoice>) -> (String, [f32; 4]) {
+    match choice {
+        Some(DuplicateChoice::Preferred) => (
+            tr("SelectMusic", "DuplicateSongsPreferred").to_string(),
+            DUPLICATE_SONGS_PREFERRED_RGBA,
+        ),
+        Some(DuplicateChoice::Hidden) => (
+            tr("SelectMusic", "DuplicateSongsHidden").to_string(),
+            DUPLICATE_SONGS_DIM_RGBA,
+        ),
+        None => (
+            tr("SelectMusic", "DuplicateSongsShown").to_string(),
+            DUPLICATE_SONGS_TEXT_RGBA,
+        ),
+    }
+}
+
+fn push_pane(actors: &mut Vec<Actor>, pane_cx: f32, pane_cy: f32, title: &str, font: &'static str) {
+    actors.push(act!(quad:
+        align(0.0, 0.0): xy(0.0, 0.0):
+        zoomto(screen_width(), screen_height()):
+        diffuse(0.0, 0.0, 0.0, DUPLICATE_SONGS_DIM_ALPHA):
+        z(DUPLICATE_SONGS_Z)
+    ));
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(pane_cx, pane_cy):
+        zoomto(DUPLICATE_SONGS_PANE_WIDTH + 2.0, DUPLICATE_SONGS_PANE_HEIGHT + 2.0):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(DUPLICATE_SONGS_Z + 2)
+    ));
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(pane_cx, pane_cy):
+        zoomto(DUPLICATE_SONGS_PANE_WIDTH, DUPLICATE_SONGS_PANE_HEIGHT):
+        diffuse(0.0, 0.0, 0.0, 1.0):
+        z(DUPLICATE_SONGS_Z + 3)
+    ));
+    let header_y = pane_cy - DUPLICATE_SONGS_PANE_HEIGHT * 0.5 + DUPLICATE_SONGS_ROW_HEIGHT * 0.5;
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(pane_cx, header_y):
+        zoomto(DUPLICATE_SONGS_PANE_WIDTH + 2.0, DUPLICATE_SONGS_ROW_HEIGHT + 2.0):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(DUPLICATE_SONGS_Z + 4)
+    ));
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(pane_cx, header_y):
+        zoomto(DUPLICATE_SONGS_PANE_WIDTH, DUPLICATE_SONGS_ROW_HEIGHT):
+        diffuse(0.0, 0.0, 1.0, 1.0):
+        z(DUPLICATE_SONGS_Z + 5)
+    ));
+    actors.push(act!(text:
+        font(font):
+        settext(title.to_string()):
+        align(0.5, 0.5):
+        xy(pane_cx, header_y):
+        zoom(0.5):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(DUPLICATE_SONGS_Z + 6):
+        horizalign(center)
+    ));
+}
+
+fn push_footer(actors: &mut Vec<Actor>, text: String) {
+    actors.push(act!(text:
+        font("miso"):
+        settext(text):
+        align(0.5, 0.5):
+        xy(screen_center_x(), screen_height() - 50.0):
+        zoom(1.1):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(DUPLICATE_SONGS_Z + 8):
+        horizalign(center)
+    ));
+}
+
+fn push_cell(
+    actors: &mut Vec<Actor>,
+    text: String,
+    x: f32,
+    y: f32,
+    align_x: f32,
+    max_width: f32,
+    rgba: [f32; 4],
+) {
+    actors.push(act!(text:
+        font("miso"):
+        settext(text):
+        align(align_x, 0.5):
+        xy(x, y):
+        zoom(0.7):
+        maxwidth(max_width):
+        diffuse(rgba[0], rgba[1], rgba[2], rgba[3]):
+        z(DUPLICATE_SONGS_Z + 7)
+    ));
+}
+
+pub fn build_duplicate_songs_overlay(
+    state: &DuplicateSongsOverlayState,
+    machine_font: MachineFont,
+) -> Option<Vec<Actor>> {
+    if state.is_hidden() {
+        return None;
+    }
+    let mut actors = Vec::new();
+    let pane_cx = screen_center_x();
+    let pane_cy = screen_center_y() + DUPLICATE_SONGS_PANE_CENTER_Y;
+    push_pane(
+        &mut actors,
+        pane_cx,
+        pane_cy,
+        &tr("SelectMusic", "DuplicateSongsTitle"),
+        machine_font_key(machine_font, FontRole::Header),
+    );
+    let overlay = match state {
+        DuplicateSongsOverlayState::Hidden => return None,
+        DuplicateSongsOverlayState::Loading => {
+            push_cell(
+                &mut actors,
+                tr("SelectMusic", "DuplicateSongsLoading").to_string(),
+                pane_cx,
+                pane_cy,
+                0.5,
+                DUPLICATE_SONGS_PANE_WIDTH - 20.0,
+                DUPLICATE_SONGS_TEXT_RGBA,
+            );
+            push_footer(&mut actors, tr("SelectMusic", "SyncCancelHint").to_string());
+            return Some(actors);
+        }
+        DuplicateSongsOverlayState::Failed { error } => {
+            push_cell(
+                &mut actors,
+                tr_fmt("SelectMusic", "DuplicateSongsFailed", &[("error", error)]).to_string(),
+                pane_cx,
+                pane_cy,
+                0.5,
+                DUPLICATE_SONGS_PANE_WIDTH - 20.0,
+                DUPLICATE_SONGS_ERROR_RGBA,
+            );
+            push_footer(&mut actors, tr("SelectMusic", "SyncCloseHint").to_string());
+            return Some(actors);
+        }
+        DuplicateSongsOverlayState::Visible(overlay) => overlay,
+    };
+    let left = pane_cx - DUPLICATE_SONGS_PANE_WIDTH * 0.5 + 10.0;
+    let right = pane_cx + DUPLICATE_SONGS_PANE_WIDTH * 0.5 - 10.0;
+    let width = (DUPLICATE_SONGS_PANE_WIDTH - 20.0) / 0.7;
+    let row_y = |slot: usize| {
+        pane_cy - DUPLICATE_SONGS_PANE_HEIGHT * 0.5
+            + DUPLICATE_SONGS_ROW_HEIGHT * (slot as f32 + 1.5)
+    };
+    push_cell(
+        &mut actors,
+        overlay.summary.clone(),
+        left,
+        row_y(0),
+        0.0,
+        width,
+        DUPLICATE_SONGS_DIM_RGBA,
+    );
+    if overlay.rows.is_empty() {
+        push_cell(
+            &mut actors,
+            tr("SelectMusic", "DuplicateSongsNone").to_string(),
+            left,
+            row_y(1),
+            0.0,
+            width,
+            DUPLICATE_SONGS_TEXT_RGBA,
+        );
+    }
+    for (slot, (row_ix, row)) in overlay
+        .rows
+        .iter()
+        .enumerate()
+        .skip(overlay.scroll)
+        .take(DUPLICATE_SONGS_NUM_ROWS)
+        .enumerate()
+    {
+        let y = row_y(slot + 1);
+        match *row {
+            DuplicateSongsRow::Group(group) => push_cell(
+                &mut actors,
+                group_text(&overlay.report.groups[group]),
+                left,
+                y,
+                0.0,
+                width,
+                DUPLICATE_SONGS_TEXT_RGBA,
+            ),
+            DuplicateSongsRow::Copy { group, copy } => {
+                let copy = &overlay.report.groups[group].copies[copy];
+                if row_ix == overlay.cursor {
+                    actors.push(act!(quad:
+                        align(0.5, 0.5):
+                        xy(pane_cx, y):
+                        zoomto(DUPLICATE_SONGS_PANE_WIDTH - 4.0, DUPLICATE_SONGS_ROW_HEIGHT - 2.0):
+                        diffuse(
+                            DUPLICATE_SONGS_CURSOR_RGBA[0],
+                            DUPLICATE_SONGS_CURSOR_RGBA[1],
+                            DUPLICATE_SONGS_CURSOR_RGBA[2],
+                            DUPLICATE_SONGS_CURSOR_RGBA[3]
+                        ):
+                        z(DUPLICATE_SONGS_Z + 6)
+                    ));
+                }
+                let (status, rgba) = choice_text(overlay.choices.get(&copy.simfile_path));
+                push_cell(
+                    &mut actors,
+                    copy.pack_name.clone(),
+                    left + 16.0,
+                    y,
+                    0.0,
+                    width * 0.75,
+                    rgba,
+                );
+                push_cell(&mut actors, status, right, y, 1.0, width * 0.2, rgba);
+            }
+        }
+    }
+    if let Some((group, copy)) = selected(overlay) {
+        let path = group.copies[copy].simfile_path.as_path();
+        let dir = path.parent().unwrap_or(path).display().to_string();
+        push_cell(
+            &mut actors,
+            dir,
+            left,
+            row_y(DUPLICATE_SONGS_NUM_ROWS + 1),
+            0.0,
+            width,
+            DUPLICATE_SONGS_DIM_RGBA,
+        );
+    }
+    if overlay.rows.len() > DUPLICATE_SONGS_NUM_ROWS {
+        let position = tr_fmt(
+            "SelectMusic",
+            "DuplicateSongsPosition",
+            &[
+                ("first", &(overlay.scroll + 1).to_string()),
+                (
+                    "last",
+                    &(overlay.scroll + DUPLICATE_SONGS_NUM_ROWS)
+                        .min(overlay.rows.len())
+                        .to_string(),
+                ),
+                ("total", &overlay.rows.len().to_string()),
+            ],
+        );
+        push_cell(
+            &mut actors,
+            position.to_string(),
+            right,
+            row_y(0),
+            1.0,
+            200.0,
+            DUPLICATE_SONGS_DIM_RGBA,
+        );
+    }
+    push_footer(
+        &mut actors,
+        tr("SelectMusic", "DuplicateSongsHelp").to_string(),
+    );
+    Some(actors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deadsync_simfile::duplicates::DuplicateCopy;
+    use std::path::PathBuf;
+    use std::time::Instant;
+
+    fn press(action: VirtualAction) -> InputEvent {
+        let now = Instant::now();
+        InputEvent {
+            action,
+            input_slot: 0,
+            pressed: true,
+            source: deadsync_core::input::InputSource::Keyboard,
+            timestamp: now,
+            timestamp_host_nanos: 0,
+            stored_at: now,
+            emitted_at: now,
+        }
+    }
+
+    fn group(title: &str, packs: &[&str]) -> DuplicateGroup {
+        DuplicateGroup {
+            copies: packs
+                .iter()
+                .map(|pack| DuplicateCopy {
+                    simfile_path: PathBuf::from(format!("Songs/{pack}/{title}/song.ssc")),
+                    pack_dir: PathBuf::from(format!("Songs/{pack}")),
+                    pack_name: (*pack).to_string(),
+                    title: title.to_string(),
+                    music_path: None,
+                    music_seconds: 0.0,
+                    chart_hashes: Vec::new(),
+                })
+                .collect(),
+            same_charts: true,
+            same_audio: false,
+        }
+    }
+
+    fn visible() -> DuplicateSongsOverlayState {
+        let report = DuplicateReport {
+            songs_checked: 5,
+            groups: vec![group("One", &["A", "B"]), group("Two", &["A", "B", "C"])],
+        };
+        match begin_duplicate_songs_overlay(Ok(report), DuplicateChoices::default()) {
+            DuplicateSongsOverlayState::Visible(mut overlay) => {
+                overlay.input_lock = 0.0;
+                DuplicateSongsOverlayState::Visible(overlay)
+            }
+            _ => panic!("report not shown"),
+        }
+    }
+
+    fn cursor(state: &DuplicateSongsOverlayState) -> usize {
+        match state {
+            DuplicateSongsOverlayState::Visible(overlay) => overlay.cursor,
+            _ => panic!("overlay closed"),
+        }
+    }
+
+    #[test]
+    fn cursor_skips_group_headers() {
+        let mut state = visible();
+        assert_eq!(cursor(&state), 1);
+        assert_eq!(
+            handle_duplicate_songs_input(&mut state, &press(VirtualAction::p1_up)),
+            DuplicateSongsInputOutcome::None
+        );
+        handle_duplicate_songs_input(&mut state, &press(VirtualAction::p1_down));
+        handle_duplicate_songs_input(&mut state, &press(VirtualAction::p1_down));
+        assert_eq!(cursor(&state), 4);
+    }
+
+    #[test]
+    fn closing_returns_the_edited_choices() {
+        let mut state = visible();
+        handle_duplicate_songs_input(&mut state, &press(VirtualAction::p1_start));
+        handle_duplicate_songs_input(&mut state, &press(VirtualAction::p1_down));
+        handle_duplicate_songs_input(&mut state, &press(VirtualAction::p1_down));
+        handle_duplicate_songs_input(&mut state, &press(VirtualAction::p1_right));
+        let DuplicateSongsInputOutcome::Closed(Some(choices)) =
+            handle_duplicate_songs_input(&mut state, &press(VirtualAction::p1_back))
+        else {
+            panic!("closing should return the choices");
+        };
+        assert!(state.is_hidden());
+        let one = group("One", &["A", "B"]);
+        let two = group("Two", &["A", "B", "C"]);
+        assert_eq!(
+            choices.get(&one.copies[0].simfile_path),
+            Some(DuplicateChoice::Preferred)
+        );
+        assert!(choices.is_hidden(&one.copies[1].simfile_path));
+        assert!(choices.is_hidden(&two.copies[0].simfile_path));
+        assert_eq!(choices.get(&two.copies[1].simfile_path), None);
+    }
+}
@@ -1,19 +1,29 @@
+pub mod controller_claim;
 pub mod downloads;
+pub mod duplicate_songs;
 pub mod leaderboard;
 mod menu;
+pub mod pack_lint;
+pub mod pattern_analysis;
 pub mod replay;
 pub mod song_search;
 pub mod srpg_shop;
+pub mod workout;
 
+pub use controller_claim::*;
 pub use downloads::*;
+pub use duplicate_songs::*;
 pub use leaderboard::*;
 pub use menu::{
     CategoryItemLists as MenuLists, Entry, FOCUS_TWEEN_SECONDS, InputOutcome, RenderParams,
     VisibleState as MenuState, build_overlay, handle_input, move_selection, open,
 };
+pub use pack_lint::*;
+pub use pattern_analysis::*;
 pub use replay::*;
 pub use song_search::*;
 pub use srpg_shop::*;
+pub use workout::*;
 
 use deadlib_present::actors::Actor;
 use deadlib_present::actors::TextContent;
@@ -39,6 +49,12 @@ pub enum Action {
     SortByRecentP2,
     SortByTopGradesP1,
     SortByTopGradesP2,
+    SortByRecommendedP1,
+    SortByRecommendedP2,
+    PatternAnalysisP1,
+    PatternAnalysisP2,
+    WorkoutP1,
+    WorkoutP2,
     SortByPlaylist(String),
     /// Apply a pad-config preset/profile to a physical pad (quick recall).
     /// `preset` distinguishes a built-in preset name from a saved config name.
@@ -55,6 +71,8 @@ pub enum Action {
     SwitchToSingle,
     SwitchToDouble,
     TestInput,
+    ClaimControllerP1,
+    ClaimControllerP2,
     ConfigurePads,
     SongSearch,
     SwitchProfile,
@@ -65,10 +83,19 @@ pub enum Action {
     NullOrDiePack,
     SyncSong,
     SyncPack,
+    PackSyncBias,
+    ApplyPackSyncBias,
+    UndoPackOffsets,
+    LintPack,
+    ScanSongLua,
+    FindDuplicateSongs,
     PlayReplay,
     PracticeMode,
     ShowLeaderboard,
     ShowSetSummary,
+    NominateSong,
+    BanNomination,
+    SongRoulette,
 }
 
 #[derive(Clone, Debug)]
@@ -163,6 +190,36 @@ pub const ITEM_SORT_BY_TOP_GRADES_P2: Item = Item {
     bottom_label: TextContent::Static("P2 Clear Rank"),
     action: Action::SortByTopGradesP2,
 };
+pub const ITEM_SORT_BY_RECOMMENDED_P1: Item = Item {
+    top_label: TextContent::Static("Sort By"),
+    bottom_label: TextContent::Static("P1 Recommended"),
+    action: Action::SortByRecommendedP1,
+};
+pub const ITEM_SORT_BY_RECOMMENDED_P2: Item = Item {
+    top_label: TextContent::Static("Sort By"),
+    bottom_label: TextContent::Static("P2 Recommended"),
+    action: Action::SortByRecommendedP2,
+};
+pub const ITEM_PATTERN_ANALYSIS_P1: Item = Item {
+    top_label: TextContent::Static("Know Thyself"),
+    bottom_label: TextContent::Static("P1 Strengths"),
+    action: Action::PatternAnalysisP1,
+};
+pub const ITEM_PATTERN_ANALYSIS_P2: Item = Item {
+    top_label: TextContent::Static("Know Thyself"),
+    bottom_label: TextContent::Static("P2 Strengths"),
+    action: Action::PatternAnalysisP2,
+};
+pub const ITEM_WORKOUT_P1: Item = Item {
+    top_label: TextContent::Static("Workout Mode"),
+    bottom_label: TextContent::Static("P1 Goal"),
+    action: Action::WorkoutP1,
+};
+pub const ITEM_WORKOUT_P2: Item = Item {
+    top_label: TextContent::Static("Workout Mode"),
+    bottom_label: TextContent::Static("P2 Goal"),
+    action: Action::WorkoutP2,
+};
 pub const ITEM_SWITCH_TO_SINGLE: Item = Item {
     top_label: TextContent::Static("Change Style To"),
     bottom_label: TextContent::Static("Single"),
@@ -178,6 +235,16 @@ pub const ITEM_TEST_INPUT: Item = Item {
     bottom_label: TextContent::Static("Test Input"),
     action: Action::TestInput,
 };
+pub const ITEM_CLAIM_CONTROLLER_P1: Item = Item {
+    top_label: TextContent::Static("Bring Your Own"),
+    bottom_label: TextContent::Static("P1 Controller"),
+    action: Action::ClaimControllerP1,
+};
+pub const ITEM_CLAIM_CONTROLLER_P2: Item = Item {
+    top_label: TextContent::Static("Bring Your Own"),
+    bottom_label: TextContent::Static("P2 Controller"),
+    action: Action::ClaimControllerP2,
+};
 pub const ITEM_CONFIGURE_PADS: Item = Item {
     top_label: TextContent::Static("Dial It In"),
     bottom_label: TextContent::Static("Configure Pads"),
@@ -228,6 +295,36 @@ pub const ITEM_SYNC_PACK: Item = Item {
     bottom_label: TextContent::Static("SYNC PACK"),
     action: Action::SyncPack,
 };
+pub const ITEM_PACK_SYNC_BIAS: Item = Item {
+    top_label: TextContent::Static("Pack sync convention"),
+    bottom_label: TextContent::Static("PACK SYNC BIAS"),
+    action: Action::PackSyncBias,
+};
+pub const ITEM_APPLY_PACK_SYNC_BIAS: Item = Item {
+    top_label: TextContent::Static("Write pack bias into"),
+    bottom_label: TextContent::Static("PACK #OFFSETS"),
+    action: Action::ApplyPackSyncBias,
+};
+pub const ITEM_UNDO_PACK_OFFSETS: Item = Item {
+    top_label: TextContent::Static("Undo rewritten"),
+    bottom_label: TextContent::Static("PACK #OFFSETS"),
+    action: Action::UndoPackOffsets,
+};
+pub const ITEM_LINT_PACK: Item = Item {
+    top_label: TextContent::Static("Check charts in"),
+    bottom_label: TextContent::Static("LINT PACK"),
+    action: Action::LintPack,
+};
+pub const ITEM_SCAN_SONG_LUA: Item = Item {
+    top_label: TextContent::Static("Check song lua in"),
+    bottom_label: TextContent::Static("SCAN SONG LUA"),
+    action: Action::ScanSongLua,
+};
+pub const ITEM_FIND_DUPLICATE_SONGS: Item = Item {
+    top_label: TextContent::Static("Find copies across packs"),
+    bottom_label: TextContent::Static("DUPLICATE SONGS"),
+    action: Action::FindDuplicateSongs,
+};
 pub const ITEM_PLAY_REPLAY: Item = Item {
     top_label: TextContent::Static("Machine Data"),
     bottom_label: TextContent::Static("Play Replay"),
@@ -263,6 +360,21 @@ pub const ITEM_SET_SUMMARY: Item = Item {
     bottom_label: TextContent::Static("Set Summary"),
     action: Action::ShowSetSummary,
 };
+pub const ITEM_NOMINATE_SONG: Item = Item {
+    top_label: TextContent::Static("Can't Decide?"),
+    bottom_label: TextContent::Static("Nominate Song"),
+    action: Action::NominateSong,
+};
+pub const ITEM_BAN_NOMINATION: Item = Item {
+    top_label: TextContent::Static("Anything But This"),
+    bottom_label: TextContent::Static("Ban Nomination"),
+    action: Action::BanNomination,
+};
+pub const ITEM_SONG_ROULETTE: Item = Item {
+    top_label: TextContent::Static("Let Fate Decide"),
+    bottom_label: TextContent::Static("Song Roulette"),
+    action: Action::SongRoulette,
+};
 
 pub fn playlist_item(
     top_label: impl Into<String>,
@@ -0,0 +1,789 @@
+use crate::act;
+use crate::assets::i18n::{tr, tr_fmt};
+use crate::assets::{FontRole, machine_font_key};
+use crate::config::MachineFont;
+use deadlib_present::actors::Actor;
+use deadlib_present::space::{screen_center_x, screen_center_y, screen_height, screen_width};
+use deadsync_input::{InputEvent, VirtualAction};
+use deadsync_simfile::lint::{
+    LintFinding, LintKind, LintSeverity, PackLintReport, display_bpm_range_text,
+};
+use deadsync_song_lua::{
+    SongLuaCompatibility, SongLuaCompatibilityLevel, SongLuaScanReport, SongLuaScanSong,
+};
+use std::path::{Path, PathBuf};
+
+pub const PACK_LINT_INPUT_LOCK_SECONDS: f32 = 0.15;
+
+const PACK_LINT_NUM_ROWS: usize = 14;
+const PACK_LINT_ROW_HEIGHT: f32 = 22.0;
+const PACK_LINT_PANE_HEIGHT: f32 = 360.0;
+const PACK_LINT_PANE_WIDTH: f32 = 620.0;
+const PACK_LINT_PANE_CENTER_Y: f32 = -15.0;
+const PACK_LINT_DIM_ALPHA: f32 = 0.875;
+const PACK_LINT_Z: i16 = 1480;
+const PACK_LINT_ERROR_RGBA: [f32; 4] = [1.0, 0.35, 0.35, 1.0];
+const PACK_LINT_WARNING_RGBA: [f32; 4] = [1.0, 0.85, 0.4, 1.0];
+const PACK_LINT_TEXT_RGBA: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const PACK_LINT_DIM_RGBA: [f32; 4] = [0.65, 0.65, 0.65, 1.0];
+
+/// Which pack report the overlay shows; each comes from its own worker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PackReportKind {
+    Lint,
+    SongLua,
+}
+
+impl PackReportKind {
+    const fn key(self, lint: &'static str, song_lua: &'static str) -> &'static str {
+        match self {
+            Self::Lint => lint,
+            Self::SongLua => song_lua,
+        }
+    }
+}
+
+/// One row of the scrolling report: a song title or one of its findings.
+#[derive(Clone, Debug)]
+pub struct PackLintLine {
+    pub text: String,
+    pub indent: bool,
+    pub rgba: [f32; 4],
+}
+
+#[derive(Clone, Debug)]
+pub struct PackLintOverlayStateData {
+    pub kind: PackReportKind,
+    pub pack_name: String,
+    pub summary: String,
+    pub lines: Vec<PackLintLine>,
+    pub scroll: usize,
+    pub input_lock: f32,
+}
+
+#[derive(Clone, Debug)]
+pub enum PackLintOverlayState {
+    Hidden,
+    /// Waiting on the shell worker for `pack_dir`.
+    Loading {
+        kind: PackReportKind,
+        pack_dir: PathBuf,
+        pack_name: String,
+    },
+    Visible(PackLintOverlayStateData),
+    Failed {
+        kind: PackReportKind,
+        pack_name: String,
+        error: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PackLintInputOutcome {
+    None,
+    Scrolled,
+    /// Closed while the worker was still running.
+    Cancelled,
+    Closed,
+}
+
+impl PackLintOverlayState {
+    #[inline(always)]
+    pub const fn is_hidden(&self) -> bool {
+        matches!(self, Self::Hidden)
+    }
+
+    pub fn pack_name(&self) -> Option<&str> {
+        match self {
+            Self::Hidden => None,
+            Self::Loading { pack_name, .. } | Self::Failed { pack_name, .. } => {
+                Some(pack_name.as_str())
+            }
+            Self::Visible(overlay) => Some(overlay.pack_name.as_str()),
+        }
+    }
+
+    pub const fn kind(&self) -> Option<PackReportKind> {
+        match self {
+            Self::Hidden => None,
+            Self::Loading { kind, .. } | Self::Failed { kind, .. } => Some(*kind),
+            Self::Visible(overlay) => Some(overlay.kind),
+        }
+    }
+}
+
+fn beat_text(beat: f32) -> String {
+    format!("{beat:.3}")
+}
+
+pub fn pack_lint_kind_text(kind: &LintKind) -> String {
+    let text = match kind {
+        LintKind::MeterMismatch { meter, rating } => tr_fmt(
+            "SelectMusic",
+            "PackLintMeterMismatch",
+            &[
+                ("meter", &meter.to_string()),
+                ("rating", &format!("{rating:.1}")),
+            ],
+        ),
+        LintKind::MeterOrder {
+            meter,
+            nps,
+            easier_meter,
+            easier_nps,
+        } => tr_fmt(
+            "SelectMusic",
+            "PackLintMeterOrder",
+            &[
+                ("meter", &meter.to_string()),
+                ("nps", &format!("{nps:.1}")),
+                ("easier", &easier_meter.to_string()),
+                ("easier_nps", &format!("{easier_nps:.1}")),
+            ],
+        ),
+        LintKind::MissingMusic { tag } if tag.is_empty() => tr("SelectMusic", "PackLintNoMusic"),
+        LintKind::MissingMusic { tag } => {
+            tr_fmt("SelectMusic", "PackLintMissingMusic", &[("file", tag)])
+        }
+        LintKind::MusicTagMismatch { tag, resolved } => tr_fmt(
+            "SelectMusic",
+            "PackLintMusicMismatch",
+            &[("file", tag), ("resolved", resolved)],
+        ),
+        LintKind::MissingBanner { tag } if tag.is_empty() => tr("SelectMusic", "PackLintNoBanner"),
+        LintKind::MissingBanner { tag } => {
+            tr_fmt("SelectMusic", "PackLintMissingBanner", &[("file", tag)])
+        }
+        LintKind::BannerTagMismatch { tag, resolved } => tr_fmt(
+            "SelectMusic",
+            "PackLintBannerMismatch",
+            &[("file", tag), ("resolved", resolved)],
+        ),
+        LintKind::NotesInWarps { count, first_beat } => tr_fmt(
+            "SelectMusic",
+            "PackLintNotesInWarps",
+            &[
+                ("count", &count.to_string()),
+                ("beat", &beat_text(*first_beat)),
+            ],
+        ),
+        LintKind::NotesInFakes { count, first_beat } => tr_fmt(
+            "SelectMusic",
+            "PackLintNotesInFakes",
+            &[
+                ("count", &count.to_string()),
+                ("beat", &beat_text(*first_beat)),
+            ],
+        ),
+        LintKind::ShortStop { beat, seconds } => tr_fmt(
+            "SelectMusic",
+            "PackLintShortStop",
+            &[
+                ("beat", &beat_text(*beat)),
+                ("ms", &format!("{:.1}", seconds * 1000.0)),
+            ],
+        ),
+        LintKind::OverlappingHolds { count, first_beat } => tr_fmt(
+            "SelectMusic",
+            "PackLintOverlappingHolds",
+            &[
+                ("count", &count.to_string()),
+                ("beat", &beat_text(*first_beat)),
+            ],
+        ),
+        LintKind::DisplayBpmMismatch { display, actual } => tr_fmt(
+            "SelectMusic",
+            "PackLintDisplayBpm",
+            &[
+                ("display", &display_bpm_range_text(*display)),
+                ("actual", &display_bpm_range_text(*actual)),
+            ],
+        ),
+        LintKind::OffsetOutOfRange { offset } => tr_fmt(
+            "SelectMusic",
+            "PackLintOffset",
+            &[("offset", &format!("{offset:.3}"))],
+        ),
+        LintKind::DuplicateChart { other } => {
+            tr_fmt("SelectMusic", "PackLintDuplicate", &[("other", other)])
+        }
+    };
+    text.to_string()
+}
+
+fn finding_line(finding: &LintFinding) -> PackLintLine {
+    let text = pack_lint_kind_text(&finding.kind);
+    PackLintLine {
+        text: match &finding.chart {
+            Some(chart) => format!("{chart}: {text}"),
+            None => text,
+        },
+        indent: true,
+        rgba: match finding.severity() {
+            LintSeverity::Error => PACK_LINT_ERROR_RGBA,
+            LintSeverity::Warning => PACK_LINT_WARNING_RGBA,
+        },
+    }
+}
+
+fn file_name_text(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
+fn push_failure_lines(lines: &mut Vec<PackLintLine>, failures: &[(PathBuf, String)]) {
+    for (path, error) in failures {
+        let name = file_name_text(path);
+        lines.push(PackLintLine {
+            text: tr_fmt(
+                "SelectMusic",
+                "PackLintReadFailed",
+                &[("file", &name), ("error", error)],
+            )
+            .to_string(),
+            indent: false,
+            rgba: PACK_LINT_ERROR_RGBA,
+        });
+    }
+}
+
+fn report_lines(report: &PackLintReport) -> Vec<PackLintLine> {
+    let mut lines = Vec::new();
+    for song in &report.songs {
+        lines.push(PackLintLine {
+            text: song.title.clone(),
+            indent: false,
+            rgba: PACK_LINT_TEXT_RGBA,
+        });
+        lines.extend(song.findings.iter().map(finding_line));
+    }
+    push_failure_lines(&mut lines, &report.failures);
+    lines
+}
+
+/// Wheel badge tint for a scanned song-lua level; untinted when supported.
+pub const fn song_lua_level_rgba(level: SongLuaCompatibilityLevel) -> [f32; 4] {
+    match level {
+        SongLuaCompatibilityLevel::Supported => PACK_LINT_TEXT_RGBA,
+        SongLuaCompatibilityLevel::Degraded => PACK_LINT_WARNING_RGBA,
+        SongLuaCompatibilityLevel::Failed => PACK_LINT_ERROR_RGBA,
+    }
+}
+
+fn song_lua_level_text(level: SongLuaCompatibilityLevel) -> String {
+    let key = match level {
+        SongLuaCompatibilityLevel::Supported => "SongLuaScanSupported",
+        SongLuaCompatibilityLevel::Degraded => "SongLuaScanDegraded",
+        SongLuaCompatibilityLevel::Failed => "SongLuaScanFailedSong",
+    };
+    tr("SelectMusic", key).to_string()
+}
+
+fn song_lua_song_lines(song: &SongLuaScanSong, lines: &mut Vec<PackLintLine>) {
+    let level = song.level();
+    lines.push(PackLintLine {
+        text: format!("{}: {}", song.title, song_lua_level_text(level)),
+        indent: false,
+        rgba: song_lua_level_rgba(level),
+    });
+    for entry in &song.entries {
+        let file = file_name_text(&entry.path);
+        match &entry.compatibility {
+            SongLuaCompatibility::Supported => {}
+            SongLuaCompatibility::Degraded(dropped) => {
+                lines.extend(dropped.iter().map(|construct| {
+                    PackLintLine {
+                        text: tr_fmt(
+                            "SelectMusic",
+                            "SongLuaScanDropped",
+                            &[("file", &file), ("construct", construct)],
+                        )
+                        .to_string(),
+                        indent: true,
+                        rgba: PACK_LINT_WARNING_RGBA,
+                    }
+                }));
+            }
+            SongLuaCompatibility::Failed(error) => lines.push(PackLintLine {
+                text: format!("{file}: {error}"),
+                indent: true,
+                rgba: PACK_LINT_ERROR_RGBA,
+            }),
+        }
+    }
+}
+
+pub fn begin_song_lua_scan_overlay(
+    pack_name: String,
+    report: Result<SongLuaScanReport, String>,
+) -> PackLintOverlayState {
+    let kind = PackReportKind::SongLua;
+    let report = match report {
+        Ok(report) => report,
+        Err(error) => {
+            return PackLintOverlayState::Failed {
+                kind,
+                pack_name,
+                error,
+            };
+        }
+    };
+    let summary = tr_fmt(
+        "SelectMusic",
+        "SongLuaScanSummary",
+        &[
+            ("songs", &report.songs.len().to_string()),
+            (
+                "supported",
+                &report
+                    .count(SongLuaCompatibilityLevel::Supported)
+                    .to_string(),
+            ),
+            (
+                "degraded",
+                &report
+                    .count(SongLuaCompatibilityLevel::Degraded)
+                    .to_string(),
+            ),
+            (
+                "failed",
+                &report.count(SongLuaCompatibilityLevel::Failed).to_string(),
+            ),
+        ],
+    )
+    .to_string();
+    let mut lines = Vec::new();
+    for song in &report.songs {
+        song_lua_song_lines(song, &mut lines);
+    }
+    push_failure_lines(&mut lines, &report.failures);
+    PackLintOverlayState::Visible(PackLintOverlayStateData {
+        kind,
+        pack_name,
+        summary,
+        lines,
+        scroll: 0,
+        input_lock: PACK_LINT_INPUT_LOCK_SECONDS,
+    })
+}
+
+pub fn begin_pack_lint_overlay(
+    pack_name: String,
+    report: Result<PackLintReport, String>,
+) -> PackLintOverlayState {
+    let report = match report {
+        Ok(report) => report,
+        Err(error) => {
+            return PackLintOverlayState::Failed {
+                kind: PackReportKind::Lint,
+                pack_name,
+                error,
+            };
+        }
+    };
+    let summary = tr_fmt(
+        "SelectMusic",
+        "PackLintSummary",
+        &[
+            ("songs", &report.songs_checked.to_string()),
+            ("errors", &report.count(LintSeverity::Error).to_string()),
+            ("warnings", &report.count(LintSeverity::Warning).to_string()),
+        ],
+    )
+    .to_string();
+    PackLintOverlayState::Visible(PackLintOverlayStateData {
+        kind: PackReportKind::Lint,
+        pack_name,
+        summary,
+        lines: report_lines(&report),
+        scroll: 0,
+        input_lock: PACK_LINT_INPUT_LOCK_SECONDS,
+    })
+}
+
+pub fn update_pack_lint_overlay(state: &mut PackLintOverlayState, dt: f32) -> bool {
+    match state {
+        PackLintOverlayState::Hidden => false,
+        PackLintOverlayState::Visible(overlay) => {
+            overlay.input_lock = (overlay.input_lock - dt.max(0.0)).max(0.0);
+            true
+        }
+        _ => true,
+    }
+}
+
+#[inline(always)]
+fn max_scroll(overlay: &PackLintOverlayStateData) -> usize {
+    overlay.lines.len().saturating_sub(PACK_LINT_NUM_ROWS)
+}
+
+pub fn handle_pack_lint_input(
+    state: &mut PackLintOverlayState,
+    ev: &InputEvent,
+) -> PackLintInputOutcome {
+    if !ev.pressed {
+        return PackLintInputOutcome::None;
+    }
+    let closing = matches!(
+        ev.action,
+        VirtualAction::p1_back
+            | VirtualAction::p2_back
+            | VirtualAction::p1_select
+            | VirtualAction::p2_select
+    );
+    let overlay = match state {
+        PackLintOverlayState::Hidden => return PackLintInputOutcome::None,
+        PackLintOverlayState::Loading { .. } => {
+            if closing {
+                *state = PackLintOverlayState::Hidden;
+                return PackLintInputOutcome::Cancelled;
+            }
+            return PackLintInputOutcome::None;
+        }
+        PackLintOverlayState::Failed { .. } => {
+            if closing || matches!(ev.action, VirtualAction::p1_start | VirtualAction::p2_start) {
+                *state = PackLintOverlayState::Hidden;
+                return PackLintInputOutcome::Closed;
+            }
+            return PackLintInputOutcome::None;
+        }
+        PackLintOverlayState::Visible(overlay) => overlay,
+    };
+    if overlay.input_lock > 0.0 {
+        return PackLintInputOutcome::None;
+    }
+    let delta = match ev.action {
+        VirtualAction::p1_up
+        | VirtualAction::p1_menu_up
+        | VirtualAction::p2_up
+        | VirtualAction::p2_menu_up => -1,
+        VirtualAction::p1_down
+        | VirtualAction::p1_menu_down
+        | VirtualAction::p2_down
+        | VirtualAction::p2_menu_down => 1,
+        VirtualAction::p1_left
+        | VirtualAction::p1_menu_left
+        | VirtualAction::p2_left
+        | VirtualAction::p2_menu_left => -(PACK_LINT_NUM_ROWS as isize),
+        VirtualAction::p1_right
+        | VirtualAction::p1_menu_right
+        | VirtualAction::p2_right
+        | VirtualAction::p2_menu_right => PACK_LINT_NUM_ROWS as isize,
+        VirtualAction::p1_start | VirtualAction::p2_start => {
+            *state = PackLintOverlayState::Hidden;
+            return PackLintInputOutcome::Closed;
+        }
+        _ if closing => {
+            *state = PackLintOverlayState::Hidden;
+            return PackLintInputOutcome::Closed;
+        }
+        _ => return PackLintInputOutcome::None,
+    };
+    let scroll = (overlay.scroll as isize + delta).clamp(0, max_scroll(overlay) as isize) as usize;
+    if scroll == overlay.scroll {
+        return PackLintInputOutcome::None;
+    }
+    overlay.scroll = scroll;
+    PackLintInputOutcome::Scrolled
+}
+
+fn push_pane(actors: &mut Vec<Actor>, pane_cx: f32, pane_cy: f32, title: &str, font: &'static str) {
+    actors.push(act!(quad:
+        align(0.0, 0.0): xy(0.0, 0.0):
+        zoomto(screen_width(), screen_height()):
+        diffuse(0.0, 0.0, 0.0, PACK_LINT_DIM_ALPHA):
+        z(PACK_LINT_Z)
+    ));
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(pane_cx, pane_cy):
+        zoomto(PACK_LINT_PANE_WIDTH + 2.0, PACK_LINT_PANE_HEIGHT + 2.0):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(PACK_LINT_Z + 2)
+    ));
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(pane_cx, pane_cy):
+        zoomto(PACK_LINT_PANE_WIDTH, PACK_LINT_PANE_HEIGHT):
+        diffuse(0.0, 0.0, 0.0, 1.0):
+        z(PACK_LINT_Z + 3)
+    ));
+    let header_y = pane_cy - PACK_LINT_PANE_HEIGHT * 0.5 + PACK_LINT_ROW_HEIGHT * 0.5;
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(pane_cx, header_y):
+        zoomto(PACK_LINT_PANE_WIDTH + 2.0, PACK_LINT_ROW_HEIGHT + 2.0):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(PACK_LINT_Z + 4)
+    ));
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(pane_cx, header_y):
+        zoomto(PACK_LINT_PANE_WIDTH, PACK_LINT_ROW_HEIGHT):
+        diffuse(0.0, 0.0, 1.0, 1.0):
+        z(PACK_LINT_Z + 5)
+    ));
+    actors.push(act!(text:
+        font(font):
+        settext(title.to_string()):
+        align(0.5, 0.5):
+        xy(pane_cx, header_y):
+        zoom(0.5):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(PACK_LINT_Z + 6):
+        horizalign(center)
+    ));
+}
+
+fn push_footer(actors: &mut Vec<Actor>, text: String) {
+    actors.push(act!(text:
+        font("miso"):
+        settext(text):
+        align(0.5, 0.5):
+        xy(screen_center_x(), screen_height() - 50.0):
+        zoom(1.1):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(PACK_LINT_Z + 8):
+        horizalign(center)
+    ));
+}
+
+fn push_cell(
+    actors: &mut Vec<Actor>,
+    text: String,
+    x: f32,
+    y: f32,
+    align_x: f32,
+    max_width: f32,
+    rgba: [f32; 4],
+) {
+    actors.push(act!(text:
+        font("miso"):
+        settext(text):
+        align(align_x, 0.5):
+        xy(x, y):
+        zoom(0.7):
+        maxwidth(max_width):
+        diffuse(rgba[0], rgba[1], rgba[2], rgba[3]):
+        z(PACK_LINT_Z + 7)
+    ));
+}
+
+pub fn build_pack_lint_overlay(
+    state: &PackLintOverlayState,
+    machine_font: MachineFont,
+) -> Option<Vec<Actor>> {
+    let pack_name = state.pack_name()?;
+    let kind = state.kind()?;
+    let mut actors = Vec::new();
+    let pane_cx = screen_center_x();
+    let pane_cy = screen_center_y() + PACK_LINT_PANE_CENTER_Y;
+    push_pane(
+        &mut actors,
+        pane_cx,
+        pane_cy,
+        &tr("SelectMusic", kind.key("PackLintTitle", "SongLuaScanTitle")),
+        machine_font_key(machine_font, FontRole::Header),
+    );
+    actors.push(act!(text:
+        font("miso"):
+        settext(pack_name.to_string()):
+        align(0.5, 0.5):
+        xy(pane_cx, pane_cy - PACK_LINT_PANE_HEIGHT * 0.5 - 24.0):
+        zoom(0.8):
+        maxwidth(PACK_LINT_PANE_WIDTH / 0.8):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(PACK_LINT_Z + 6):
+        horizalign(center)
+    ));
+    let overlay = match state {
+        PackLintOverlayState::Hidden => return None,
+        PackLintOverlayState::Loading { .. } => {
+            push_cell(
+                &mut actors,
+                tr(
+                    "SelectMusic",
+                    kind.key("PackLintLoading", "SongLuaScanLoading"),
+                )
+                .to_string(),
+                pane_cx,
+                pane_cy,
+                0.5,
+                PACK_LINT_PANE_WIDTH - 20.0,
+                PACK_LINT_TEXT_RGBA,
+            );
+            push_footer(&mut actors, tr("SelectMusic", "SyncCancelHint").to_string());
+            return Some(actors);
+        }
+        PackLintOverlayState::Failed { error, .. } => {
+            push_cell(
+                &mut actors,
+                tr_fmt(
+                    "SelectMusic",
+                    kind.key("PackLintFailed", "SongLuaScanFailed"),
+                    &[("error", error)],
+                )
+                .to_string(),
+                pane_cx,
+                pane_cy,
+                0.5,
+                PACK_LINT_PANE_WIDTH - 20.0,
+                PACK_LINT_ERROR_RGBA,
+            );
+            push_footer(&mut actors, tr("SelectMusic", "SyncCloseHint").to_string());
+            return Some(actors);
+        }
+        PackLintOverlayState::Visible(overlay) => overlay,
+    };
+    let left = pane_cx - PACK_LINT_PANE_WIDTH * 0.5 + 10.0;
+    let width = (PACK_LINT_PANE_WIDTH - 20.0) / 0.7;
+    let row_y = |slot: usize| {
+        pane_cy - PACK_LINT_PANE_HEIGHT * 0.5 + PACK_LINT_ROW_HEIGHT * (slot as f32 + 1.5)
+    };
+    push_cell(
+        &mut actors,
+        overlay.summary.clone(),
+        left,
+        row_y(0),
+        0.0,
+        width,
+        PACK_LINT_DIM_RGBA,
+    );
+    if overlay.lines.is_empty() {
+        push_cell(
+            &mut actors,
+            tr("SelectMusic", kind.key("PackLintClean", "SongLuaScanClean")).to_string(),
+            left,
+            row_y(1),
+            0.0,
+            width,
+            PACK_LINT_TEXT_RGBA,
+        );
+    }
+    for (slot, line) in overlay
+        .lines
+        .iter()
+        .skip(overlay.scroll)
+        .take(PACK_LINT_NUM_ROWS)
+        .enumerate()
+    {
+        let indent = if line.indent { 16.0 } else { 0.0 };
+        push_cell(
+            &mut actors,
+            line.text.clone(),
+            left + indent,
+            row_y(slot + 1),
+            0.0,
+            width - indent / 0.7,
+            line.rgba,
+        );
+    }
+    if max_scroll(overlay) > 0 {
+        let position = tr_fmt(
+            "SelectMusic",
+            "PackLintPosition",
+            &[
+                ("first", &(overlay.scroll + 1).to_string()),
+                (
+                    "last",
+                    &(overlay.scroll + PACK_LINT_NUM_ROWS)
+                        .min(overlay.lines.len())
+                        .to_string(),
+                ),
+                ("total", &overlay.lines.len().to_string()),
+            ],
+        );
+        push_cell(
+            &mut actors,
+            position.to_string(),
+            pane_cx + PACK_LINT_PANE_WIDTH * 0.5 - 10.0,
+            row_y(0),
+            1.0,
+            200.0,
+            PACK_LINT_DIM_RGBA,
+        );
+    }
+    push_footer(&mut actors, tr("SelectMusic", "PackLintHelp").to_string());
+    Some(actors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn press(action: VirtualAction) -> InputEvent {
+        let now = Instant::now();
+        InputEvent {
+            action,
+            input_slot: 0,
+            pressed: true,
+            source: deadsync_core::input::InputSource::Keyboard,
+            timestamp: now,
+            timestamp_host_nanos: 0,
+            stored_at: now,
+            emitted_at: now,
+        }
+    }
+
+    fn visible(lines: usize) -> PackLintOverlayState {
+        PackLintOverlayState::Visible(PackLintOverlayStateData {
+            kind: PackReportKind::Lint,
+            pack_name: "Pack".to_string(),
+            summary: String::new(),
+            lines: (0..lines)
+                .map(|ix| PackLintLine {
+                    text: ix.to_string(),
+                    indent: false,
+                    rgba: PACK_LINT_TEXT_RGBA,
+                })
+                .collect(),
+            scroll: 0,
+            input_lock: 0.0,
+        })
+    }
+
+    fn scroll(state: &PackLintOverlayState) -> usize {
+        match state {
+            PackLintOverlayState::Visible(overlay) => overlay.scroll,
+            _ => panic!("overlay closed"),
+        }
+    }
+
+    #[test]
+    fn scrolling_stops_at_the_last_page() {
+        let mut state = visible(PACK_LINT_NUM_ROWS + 3);
+        assert_eq!(
+            handle_pack_lint_input(&mut state, &press(VirtualAction::p1_up)),
+            PackLintInputOutcome::None
+        );
+        assert_eq!(
+            handle_pack_lint_input(&mut state, &press(VirtualAction::p1_right)),
+            PackLintInputOutcome::Scrolled
+        );
+        assert_eq!(scroll(&state), 3);
+        handle_pack_lint_input(&mut state, &press(VirtualAction::p1_up));
+        assert_eq!(scroll(&state), 2);
+        assert_eq!(
+            handle_pack_lint_input(&mut state, &press(VirtualAction::p1_back)),
+            PackLintInputOutcome::Closed
+        );
+        assert!(state.is_hidden());
+    }
+
+    #[test]
+    fn closing_while_loading_cancels_the_worker() {
+        let mut state = PackLintOverlayState::Loading {
+            kind: PackReportKind::SongLua,
+            pack_dir: PathBuf::from("Songs/Pack"),
+            pack_name: "Pack".to_string(),
+        };
+        assert_eq!(
+            handle_pack_lint_input(&mut state, &press(VirtualAction::p2_select)),
+            PackLintInputOutcome::Cancelled
+        );
+        assert!(state.is_hidden());
+    }
+}
@@ -0,0 +1,625 @@
+use crate::act;
+use crate::assets::i18n::{tr, tr_fmt};
+use crate::assets::{FontRole, machine_font_key};
+use crate::config::MachineFont;
+use deadlib_present::actors::Actor;
+use deadlib_present::color;
+use deadlib_present::space::{screen_center_x, screen_center_y, screen_height, screen_width};
+use deadsync_input::{InputEvent, VirtualAction};
+use deadsync_profile::PlayerSide;
+use deadsync_score::{
+    PatternBpmBand, PatternCategory, PatternChartPick, PatternFocusKind, PatternReport,
+};
+use std::sync::Arc;
+
+pub const PATTERN_ANALYSIS_INPUT_LOCK_SECONDS: f32 = 0.15;
+
+const PATTERN_NUM_ROWS: usize = 13;
+const PATTERN_ROW_HEIGHT: f32 = 24.0;
+const PATTERN_PANE_HEIGHT: f32 = 360.0;
+const PATTERN_PANE_WIDTH: f32 = 620.0;
+const PATTERN_COLUMN_WIDTH: f32 = 300.0;
+const PATTERN_PANE_CENTER_Y: f32 = -15.0;
+const PATTERN_DIM_ALPHA: f32 = 0.875;
+const PATTERN_Z: i16 = 1480;
+/// Relative penalty tint thresholds for the focus table.
+const PATTERN_WEAK_RELATIVE: f32 = 1.15;
+const PATTERN_STRONG_RELATIVE: f32 = 0.85;
+
+/// One selectable practice chart, tagged with the focus it trains.
+#[derive(Clone, Debug)]
+pub struct PatternAnalysisPickRow {
+    pub kind: PatternFocusKind,
+    pub pick: PatternChartPick,
+}
+
+#[derive(Clone, Debug)]
+pub struct PatternAnalysisOverlayStateData {
+    pub side: PlayerSide,
+    pub report: Arc<PatternReport>,
+    pub picks: Vec<PatternAnalysisPickRow>,
+    pub selected_index: usize,
+    pub input_lock: f32,
+}
+
+#[derive(Clone, Debug)]
+pub enum PatternAnalysisOverlayState {
+    Hidden,
+    /// Waiting on the shell worker for `side`.
+    Loading(PlayerSide),
+    Visible(PatternAnalysisOverlayStateData),
+    Failed {
+        side: PlayerSide,
+        error: String,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub enum PatternAnalysisInputOutcome {
+    None,
+    ChangedSelection,
+    /// Closed while the worker was still running.
+    Cancelled,
+    Closed,
+    FocusChart(PatternChartPick),
+}
+
+impl PatternAnalysisOverlayState {
+    #[inline(always)]
+    pub const fn is_hidden(&self) -> bool {
+        matches!(self, Self::Hidden)
+    }
+
+    #[inline(always)]
+    pub const fn side(&self) -> Option<PlayerSide> {
+        match self {
+            Self::Hidden => None,
+            Self::Loading(side) | Self::Failed { side, .. } => Some(*side),
+            Self::Visible(overlay) => Some(overlay.side),
+        }
+    }
+}
+
+pub fn begin_pattern_analysis_overlay(
+    side: PlayerSide,
+    report: Result<PatternReport, String>,
+) -> PatternAnalysisOverlayState {
+    let report = match report {
+        Ok(report) => report,
+        Err(error) => return PatternAnalysisOverlayState::Failed { side, error },
+    };
+    let picks = report
+        .picks
+        .iter()
+        .flat_map(|(kind, picks)| {
+            picks.iter().map(|pick| PatternAnalysisPickRow {
+                kind: *kind,
+                pick: pick.clone(),
+            })
+        })
+        .collect();
+    PatternAnalysisOverlayState::Visible(PatternAnalysisOverlayStateData {
+        side,
+        report: Arc::new(report),
+        picks,
+        selected_index: 0,
+        input_lock: PATTERN_ANALYSIS_INPUT_LOCK_SECONDS,
+    })
+}
+
+pub fn update_pattern_analysis_overlay(state: &mut PatternAnalysisOverlayState, dt: f32) -> bool {
+    match state {
+        PatternAnalysisOverlayState::Hidden => false,
+        PatternAnalysisOverlayState::Visible(overlay) => {
+            overlay.input_lock = (overlay.input_lock - dt.max(0.0)).max(0.0);
+            true
+        }
+        _ => true,
+    }
+}
+
+pub fn pattern_category_label(category: PatternCategory) -> Arc<str> {
+    let key = match category {
+        PatternCategory::Stream => "PatternAnalysisStream",
+        PatternCategory::Break => "PatternAnalysisBreak",
+        PatternCategory::Jack => "PatternAnalysisJack",
+        PatternCategory::Footswitch => "PatternAnalysisFootswitch",
+        PatternCategory::Crossover => "PatternAnalysisCrossover",
+        PatternCategory::Candle => "PatternAnalysisCandle",
+        PatternCategory::Bracket => "PatternAnalysisBracket",
+    };
+    tr("SelectMusic", key)
+}
+
+pub fn pattern_bpm_band_label(band: PatternBpmBand) -> String {
+    match band.bpm_range() {
+        (low, Some(high)) if low <= 0.0 => tr_fmt(
+            "SelectMusic",
+            "PatternAnalysisBpmUnder",
+            &[("bpm", &format!("{high:.0}"))],
+        )
+        .to_string(),
+        (low, Some(high)) => tr_fmt(
+            "SelectMusic",
+            "PatternAnalysisBpmRange",
+            &[
+                ("low", &format!("{low:.0}")),
+                ("high", &format!("{:.0}", high - 1.0)),
+            ],
+        )
+        .to_string(),
+        (low, None) => tr_fmt(
+            "SelectMusic",
+            "PatternAnalysisBpmOver",
+            &[("bpm", &format!("{low:.0}"))],
+        )
+        .to_string(),
+    }
+}
+
+pub fn pattern_focus_label(kind: PatternFocusKind) -> String {
+    match kind {
+        PatternFocusKind::Category(category) => pattern_category_label(category).to_string(),
+        PatternFocusKind::BpmBand(band) => pattern_bpm_band_label(band),
+    }
+}
+
+#[inline(always)]
+fn pattern_total_items(overlay: &PatternAnalysisOverlayStateData) -> usize {
+    overlay.picks.len() + 1
+}
+
+pub fn handle_pattern_analysis_input(
+    state: &mut PatternAnalysisOverlayState,
+    ev: &InputEvent,
+) -> PatternAnalysisInputOutcome {
+    if !ev.pressed {
+        return PatternAnalysisInputOutcome::None;
+    }
+    let closing = matches!(
+        ev.action,
+        VirtualAction::p1_back
+            | VirtualAction::p2_back
+            | VirtualAction::p1_select
+            | VirtualAction::p2_select
+    );
+    let overlay = match state {
+        PatternAnalysisOverlayState::Hidden => return PatternAnalysisInputOutcome::None,
+        PatternAnalysisOverlayState::Loading(_) => {
+            if closing {
+                *state = PatternAnalysisOverlayState::Hidden;
+                return PatternAnalysisInputOutcome::Cancelled;
+            }
+            return PatternAnalysisInputOutcome::None;
+        }
+        PatternAnalysisOverlayState::Failed { .. } => {
+            if closing || matches!(ev.action, VirtualAction::p1_start | VirtualAction::p2_start) {
+                *state = PatternAnalysisOverlayState::Hidden;
+                return PatternAnalysisInputOutcome::Closed;
+            }
+            return PatternAnalysisInputOutcome::None;
+        }
+        PatternAnalysisOverlayState::Visible(overlay) => overlay,
+    };
+    if overlay.input_lock > 0.0 {
+        return PatternAnalysisInputOutcome::None;
+    }
+
+    let delta = match ev.action {
+        VirtualAction::p1_up
+        | VirtualAction::p1_menu_up
+        | VirtualAction::p1_left
+        | VirtualAction::p1_menu_left
+        | VirtualAction::p2_up
+        | VirtualAction::p2_menu_up
+        | VirtualAction::p2_left
+        | VirtualAction::p2_menu_left => -1,
+        VirtualAction::p1_down
+        | VirtualAction::p1_menu_down
+        | VirtualAction::p1_right
+        | VirtualAction::p1_menu_right
+        | VirtualAction::p2_down
+        | VirtualAction::p2_menu_down
+        | VirtualAction::p2_right
+        | VirtualAction::p2_menu_right => 1,
+        VirtualAction::p1_start | VirtualAction::p2_start => {
+            let pick = overlay
+                .picks
+                .get(overlay.selected_index)
+                .map(|row| row.pick.clone());
+            *state = PatternAnalysisOverlayState::Hidden;
+            return pick.map_or(
+                PatternAnalysisInputOutcome::Closed,
+                PatternAnalysisInputOutcome::FocusChart,
+            );
+        }
+        _ if closing => {
+            *state = PatternAnalysisOverlayState::Hidden;
+            return PatternAnalysisInputOutcome::Closed;
+        }
+        _ => return PatternAnalysisInputOutcome::None,
+    };
+    let len = pattern_total_items(overlay);
+    if len <= 1 {
+        return PatternAnalysisInputOutcome::None;
+    }
+    overlay.selected_index =
+        ((overlay.selected_index.min(len - 1) as isize + delta).rem_euclid(len as isize)) as usize;
+    PatternAnalysisInputOutcome::ChangedSelection
+}
+
+fn relative_color(relative: f32) -> [f32; 4] {
+    if relative >= PATTERN_WEAK_RELATIVE {
+        [1.0, 0.35, 0.35, 1.0]
+    } else if relative <= PATTERN_STRONG_RELATIVE {
+        [0.4, 1.0, 0.4, 1.0]
+    } else {
+        [1.0, 1.0, 1.0, 1.0]
+    }
+}
+
+fn push_pane(actors: &mut Vec<Actor>, pane_cx: f32, pane_cy: f32, title: &str, font: &'static str) {
+    actors.push(act!(quad:
+        align(0.0, 0.0): xy(0.0, 0.0):
+        zoomto(screen_width(), screen_height()):
+        diffuse(0.0, 0.0, 0.0, PATTERN_DIM_ALPHA):
+        z(PATTERN_Z)
+    ));
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(pane_cx, pane_cy):
+        zoomto(PATTERN_PANE_WIDTH + 2.0, PATTERN_PANE_HEIGHT + 2.0):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(PATTERN_Z + 2)
+    ));
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(pane_cx, pane_cy):
+        zoomto(PATTERN_PANE_WIDTH, PATTERN_PANE_HEIGHT):
+        diffuse(0.0, 0.0, 0.0, 1.0):
+        z(PATTERN_Z + 3)
+    ));
+    let header_y = pane_cy - PATTERN_PANE_HEIGHT * 0.5 + PATTERN_ROW_HEIGHT * 0.5;
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(pane_cx, header_y):
+        zoomto(PATTERN_PANE_WIDTH + 2.0, PATTERN_ROW_HEIGHT + 2.0):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(PATTERN_Z + 4)
+    ));
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(pane_cx, header_y):
+        zoomto(PATTERN_PANE_WIDTH, PATTERN_ROW_HEIGHT):
+        diffuse(0.0, 0.0, 1.0, 1.0):
+        z(PATTERN_Z + 5)
+    ));
+    actors.push(act!(text:
+        font(font):
+        settext(title.to_string()):
+        align(0.5, 0.5):
+        xy(pane_cx, header_y):
+        zoom(0.5):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(PATTERN_Z + 6):
+        horizalign(center)
+    ));
+}
+
+fn push_footer(actors: &mut Vec<Actor>, text: String) {
+    actors.push(act!(text:
+        font("miso"):
+        settext(text):
+        align(0.5, 0.5):
+        xy(screen_center_x(), screen_height() - 50.0):
+        zoom(1.1):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(PATTERN_Z + 8):
+        horizalign(center)
+    ));
+}
+
+fn push_cell(
+    actors: &mut Vec<Actor>,
+    text: String,
+    x: f32,
+    y: f32,
+    align_x: f32,
+    max_width: f32,
+    rgba: [f32; 4],
+) {
+    actors.push(act!(text:
+        font("miso"):
+        settext(text):
+        align(align_x, 0.5):
+        xy(x, y):
+        zoom(0.75):
+        maxwidth(max_width):
+        diffuse(rgba[0], rgba[1], rgba[2], rgba[3]):
+        z(PATTERN_Z + 7)
+    ));
+}
+
+pub fn build_pattern_analysis_overlay(
+    state: &PatternAnalysisOverlayState,
+    display_name: &str,
+    active_color_index: i32,
+    machine_font: MachineFont,
+) -> Option<Vec<Actor>> {
+    if state.is_hidden() {
+        return None;
+    }
+    let mut actors = Vec::new();
+    let pane_cx = screen_center_x();
+    let pane_cy = screen_center_y() + PATTERN_PANE_CENTER_Y;
+    let title = tr("SelectMusic", "PatternAnalysisTitle");
+    push_pane(
+        &mut actors,
+        pane_cx,
+        pane_cy,
+        &title,
+        machine_font_key(machine_font, FontRole::Header),
+    );
+    actors.push(act!(text:
+        font("miso"):
+        settext(display_name.to_string()):
+        align(0.5, 0.5):
+        xy(pane_cx, pane_cy - PATTERN_PANE_HEIGHT * 0.5 - 24.0):
+        zoom(0.8):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(PATTERN_Z + 6):
+        horizalign(center)
+    ));
+
+    let overlay = match state {
+        PatternAnalysisOverlayState::Hidden => return None,
+        PatternAnalysisOverlayState::Loading(_) => {
+            push_cell(
+                &mut actors,
+                tr("SelectMusic", "PatternAnalysisLoading").to_string(),
+                pane_cx,
+                pane_cy,
+                0.5,
+                PATTERN_PANE_WIDTH - 20.0,
+                [1.0, 1.0, 1.0, 1.0],
+            );
+            push_footer(&mut actors, tr("SelectMusic", "SyncCancelHint").to_string());
+            return Some(actors);
+        }
+        PatternAnalysisOverlayState::Failed { error, .. } => {
+            push_cell(
+                &mut actors,
+                tr_fmt("SelectMusic", "PatternAnalysisFailed", &[("error", error)]).to_string(),
+                pane_cx,
+                pane_cy,
+                0.5,
+                PATTERN_PANE_WIDTH - 20.0,
+                [1.0, 0.35, 0.35, 1.0],
+            );
+            push_footer(&mut actors, tr("SelectMusic", "SyncCloseHint").to_string());
+            return Some(actors);
+        }
+        PatternAnalysisOverlayState::Visible(overlay) => overlay,
+    };
+
+    let row_center = (PATTERN_NUM_ROWS as f32 + 1.0) * 0.5;
+    let row_y = |slot: usize| pane_cy + PATTERN_ROW_HEIGHT * ((slot + 1) as f32 - row_center);
+    let base = [1.0, 1.0, 1.0, 1.0];
+    let dim = [0.65, 0.65, 0.65, 1.0];
+
+    // Left column: every category and band with enough notes, weakest first.
+    let left = pane_cx - PATTERN_PANE_WIDTH * 0.5 + 8.0;
+    let notes_x = left + 170.0;
+    let miss_x = left + 215.0;
+    let error_x = left + 255.0;
+    let relative_x = left + PATTERN_COLUMN_WIDTH - 4.0;
+    let y = row_y(0);
+    push_cell(
+        &mut actors,
+        tr("SelectMusic", "PatternAnalysisColumnPattern").to_string(),
+        left,
+        y,
+        0.0,
+        120.0,
+        dim,
+    );
+    for (text, x) in [
+        ("PatternAnalysisColumnNotes", notes_x),
+        ("PatternAnalysisColumnMisses", miss_x),
+        ("PatternAnalysisColumnError", error_x),
+        ("PatternAnalysisColumnRelative", relative_x),
+    ] {
+        push_cell(
+            &mut actors,
+            tr("SelectMusic", text).to_string(),
+            x,
+            y,
+            1.0,
+            50.0,
+            dim,
+        );
+    }
+    if overlay.report.focuses.is_empty() {
+        push_cell(
+            &mut actors,
+            tr("SelectMusic", "PatternAnalysisNoWeakness").to_string(),
+            left,
+            row_y(1),
+            0.0,
+            PATTERN_COLUMN_WIDTH,
+            base,
+        );
+    }
+    for (slot, focus) in overlay
+        .report
+        .focuses
+        .iter()
+        .take(PATTERN_NUM_ROWS - 2)
+        .enumerate()
+    {
+        let y = row_y(slot + 1);
+        let stats = focus.stats;
+        push_cell(
+            &mut actors,
+            pattern_focus_label(focus.kind),
+            left,
+            y,
+            0.0,
+            150.0,
+            base,
+        );
+        push_cell(
+            &mut actors,
+            stats.notes.to_string(),
+            notes_x,
+            y,
+            1.0,
+            50.0,
+            base,
+        );
+        push_cell(
+            &mut actors,
+            format!("{:.1}%", stats.miss_rate() * 100.0),
+            miss_x,
+            y,
+            1.0,
+            45.0,
+            base,
+        );
+        push_cell(
+            &mut actors,
+            stats
+                .mean_abs_error_ms()
+                .map_or_else(String::new, |ms| format!("{ms:.1}ms")),
+            error_x,
+            y,
+            1.0,
+            45.0,
+            base,
+        );
+        push_cell(
+            &mut actors,
+            format!("x{:.2}", focus.relative),
+            relative_x,
+            y,
+            1.0,
+            45.0,
+            relative_color(focus.relative),
+        );
+    }
+    let mut summary = tr_fmt(
+        "SelectMusic",
+        "PatternAnalysisSummary",
+        &[("plays", &overlay.report.profile.plays.to_string())],
+    )
+    .to_string();
+    if overlay.report.missing_plays > 0 {
+        summary.push_str("  ");
+        summary.push_str(&tr_fmt(
+            "SelectMusic",
+            "PatternAnalysisSkipped",
+            &[("count", &overlay.report.missing_plays.to_string())],
+        ));
+    }
+    push_cell(
+        &mut actors,
+        summary,
+        left,
+        row_y(PATTERN_NUM_ROWS - 1),
+        0.0,
+        PATTERN_COLUMN_WIDTH,
+        dim,
+    );
+
+    // Right column: practice charts for the weakest focuses, then Exit.
+    let right = pane_cx + PATTERN_PANE_WIDTH * 0.5 - PATTERN_COLUMN_WIDTH;
+    let right_cx = right + PATTERN_COLUMN_WIDTH * 0.5;
+    push_cell(
+        &mut actors,
+        tr("SelectMusic", "PatternAnalysisPractice").to_string(),
+        right + 4.0,
+        row_y(0),
+        0.0,
+        PATTERN_COLUMN_WIDTH,
+        dim,
+    );
+    let selected_color = color::simply_love_rgba(active_color_index);
+    let total_items = pattern_total_items(overlay);
+    let visible_rows = PATTERN_NUM_ROWS - 1;
+    let window_start = if total_items <= visible_rows {
+        0
+    } else {
+        overlay
+            .selected_index
+            .saturating_sub(visible_rows / 2)
+            .min(total_items - visible_rows)
+    };
+    for row_slot in 0..visible_rows {
+        let row_idx = window_start + row_slot;
+        if row_idx >= total_items {
+            break;
+        }
+        let y = row_y(row_slot + 1);
+        let selected = row_idx == overlay.selected_index;
+        if selected {
+            actors.push(act!(quad:
+                align(0.5, 0.5):
+                xy(right_cx, y):
+                zoomto(PATTERN_COLUMN_WIDTH, PATTERN_ROW_HEIGHT):
+                diffuse(selected_color[0], selected_color[1], selected_color[2], 1.0):
+                z(PATTERN_Z + 5)
+            ));
+        }
+        let text_color = if selected { [0.0, 0.0, 0.0, 1.0] } else { base };
+        let Some(row) = overlay.picks.get(row_idx) else {
+            let exit_color = if selected {
+                [0.2, 0.0, 0.0, 1.0]
+            } else {
+                [1.0, 0.25, 0.25, 1.0]
+            };
+            push_cell(
+                &mut actors,
+                tr("SelectMusic", "PatternAnalysisExit").to_string(),
+                right_cx,
+                y,
+                0.5,
+                PATTERN_COLUMN_WIDTH,
+                exit_color,
+            );
+            continue;
+        };
+        push_cell(
+            &mut actors,
+            pattern_focus_label(row.kind),
+            right + 4.0,
+            y,
+            0.0,
+            80.0,
+            if selected { text_color } else { dim },
+        );
+        push_cell(
+            &mut actors,
+            row.pick.title.clone(),
+            right + 90.0,
+            y,
+            0.0,
+            170.0,
+            text_color,
+        );
+        push_cell(
+            &mut actors,
+            row.pick.meter.to_string(),
+            right + PATTERN_COLUMN_WIDTH - 6.0,
+            y,
+            1.0,
+            30.0,
+            text_color,
+        );
+    }
+
+    push_footer(
+        &mut actors,
+        tr("SelectMusic", "PatternAnalysisHelp").to_string(),
+    );
+    Some(actors)
+}
@@ -3,7 +3,9 @@
 //! A live typeahead: each keystroke re-ranks the catalog, offers a ghost
 //! completion, and Enter jumps to the pick. Tab accepts the completion and
 //! Shift+Tab switches songs/packs. Song mode still honors the `[###]`
-//! BPM/difficulty filter; the old `pack/song` split is gone.
+//! BPM/difficulty filter and the query tokens from
+//! [`deadsync_simfile::song_query`] (`meter:11-13`, `nps>8`, `grade<=A`, ...);
+//! the old `pack/song` split is gone.
 //!
 //! The opening shortcut is deliberately not named here: it is configurable, and
 //! a second built-in chord opens it too. Both live with the key handling in
@@ -19,9 +21,10 @@ use deadlib_present::actors::Actor;
 use deadlib_present::color;
 use deadlib_present::space::{screen_center_x, screen_center_y, screen_height, screen_width};
 use deadsync_chart::SongData;
+use deadsync_simfile::song_query::SongQueryPlayerState;
 use deadsync_simfile::song_search::{
-    SongSearchCandidate, parse_song_search_live, song_passes_search_filters,
-    song_search_difficulties_text,
+    SongSearchCandidate, parse_song_search_live, song_search_difficulties_text,
+    song_search_query_tokens,
 };
 use std::sync::Arc;
 
@@ -357,7 +360,7 @@ pub fn song_search_completion(open: &SongSearchOpen) -> Option<SongSearchComplet
 }
 
 /// Rebuild `query` so its free text becomes `label`, keeping the `[###]` filter
-/// tokens verbatim (a BPM token cannot be rebuilt from the parsed tier).
+/// and query tokens verbatim (a BPM token cannot be rebuilt from the parsed tier).
 pub fn song_search_query_completed_with(query: &str, label: &str) -> String {
     let mut out = String::new();
     let mut chars = query.chars().peekable();
@@ -384,6 +387,10 @@ pub fn song_search_query_completed_with(query: &str, label: &str) -> String {
             chars = tail;
         }
     }
+    for token in song_search_query_tokens(query) {
+        out.push_str(token);
+        out.push(' ');
+    }
     out.push_str(label);
     out.chars().take(SONG_SEARCH_MAX_LEN).collect()
 }
@@ -520,7 +527,8 @@ fn folded_key(text: &str) -> Arc<str> {
     }
 }
 
-/// Filter by `[###]`, then fuzzy-rank the cleaned titles.
+/// Filter by `[###]` and query tokens, then fuzzy-rank the cleaned titles.
+/// `player` backs the `grade`, `unplayed`, `favorite` and `fc` predicates.
 ///
 /// Only the rows that can be shown are materialized, so a large catalog does
 /// not build candidates it will discard.
@@ -528,6 +536,7 @@ pub fn build_song_matches(
     index: &SongSearchIndex,
     query: &str,
     chart_type: &str,
+    player: &SongQueryPlayerState,
 ) -> Vec<SongSearchMatch> {
     let parsed = parse_song_search_live(query);
     let q = fuzzy::prepare_query(&parsed.text);
@@ -536,8 +545,11 @@ pub fn build_song_matches(
     let mut ranked: Vec<(i32, usize)> = Vec::new();
 
     for (i, entry) in index.songs.iter().enumerate() {
-        if !song_passes_search_filters(&entry.song, chart_type, parsed.difficulty, parsed.bpm_tier)
-        {
+        let pack_name = index
+            .packs
+            .get(entry.pack)
+            .map_or("", |pack| pack.name.as_ref());
+        if !parsed.song_passes(&entry.song, pack_name, chart_type, player) {
             continue;
         }
 
@@ -974,7 +986,12 @@ mod tests {
             ("Pack A", test_song("Boaty McBoatface", 140.0)),
         ];
         let index = index_from(&songs);
-        let matches = build_song_matches(&index, "butter", "dance-single");
+        let matches = build_song_matches(
+            &index,
+            "butter",
+            "dance-single",
+            &SongQueryPlayerState::default(),
+        );
         assert!(!matches.is_empty());
         assert_eq!(matches[0].label(), "Butterfly");
     }
@@ -986,11 +1003,131 @@ mod tests {
             ("Pack A", test_song("Fast One", 200.0)),
         ];
         let index = index_from(&songs);
-        let matches = build_song_matches(&index, "[200]", "dance-single");
+        let matches = build_song_matches(
+            &index,
+            "[200]",
+            "dance-single",
+            &SongQueryPlayerState::default(),
+        );
         assert_eq!(matches.len(), 1);
         assert_eq!(matches[0].label(), "Fast One");
     }
 
+    #[test]
+    fn bpm_filter_uses_display_bpm_range() {
+        let mut range = (*test_song("Range", 120.0)).clone();
+        range.display_bpm = "120:180".to_string();
+        range.max_bpm = 180.0;
+        let songs = vec![
+            ("Pack A", test_song("Slow", 128.0)),
+            ("Pack A", Arc::new(range)),
+        ];
+        let index = index_from(&songs);
+        let matches = build_song_matches(
+            &index,
+            "[180]",
+            "dance-single",
+            &SongQueryPlayerState::default(),
+        );
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].label(), "Range");
+    }
+
+    #[test]
+    fn difficulty_filter_ignores_edits() {
+        let mut edit_only = (*test_song("Edit Only", 120.0)).clone();
+        edit_only.charts[0].difficulty = "Edit".to_string();
+        let songs = vec![("Pack A", Arc::new(edit_only))];
+        let index = index_from(&songs);
+        let matches = build_song_matches(
+            &index,
+            "[10]",
+            "dance-single",
+            &SongQueryPlayerState::default(),
+        );
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn query_tokens_filter_songs_and_leave_free_text_to_rank() {
+        let mut fast = (*test_song("Alpha Fast", 120.0)).clone();
+        fast.charts[0].max_nps = 9.0;
+        let songs = vec![
+            ("Pack A", Arc::new(fast)),
+            ("Pack A", test_song("Alpha Slow", 120.0)),
+        ];
+        let index = index_from(&songs);
+        let matches = build_song_matches(
+            &index,
+            "alpha nps>8",
+            "dance-single",
+            &SongQueryPlayerState::default(),
+        );
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].label(), "Alpha Fast");
+    }
+
+    #[test]
+    fn song_matches_prepare_display_text_and_share_pack_storage() {
+        let mut beta = (*test_song("Beta", 120.0)).clone();
+        beta.subtitle = "Mix".to_string();
+        let songs = vec![
+            ("Warmups", test_song("Alpha", 120.0)),
+            ("Warmups", Arc::new(beta)),
+        ];
+        let index = index_from(&songs);
+        let matches =
+            build_song_matches(&index, "", "dance-single", &SongQueryPlayerState::default());
+        let candidates: Vec<_> = matches
+            .iter()
+            .map(|m| match m {
+                SongSearchMatch::Song { candidate, .. } => candidate,
+                _ => panic!("expected a song match"),
+            })
+            .collect();
+        assert_eq!(candidates.len(), 2);
+        assert!(Arc::ptr_eq(
+            &candidates[0].pack_name,
+            &candidates[1].pack_name
+        ));
+        assert_eq!(candidates[0].pack_name.as_ref(), "Warmups");
+        for candidate in candidates {
+            assert_eq!(
+                candidate.title.as_ref(),
+                candidate.song.display_title(false)
+            );
+            assert_eq!(
+                candidate.subtitle.as_ref(),
+                candidate.song.display_subtitle(false)
+            );
+            assert_eq!(
+                candidate.bpm.as_ref(),
+                candidate.song.formatted_chart_display_bpm(None)
+            );
+            assert_eq!(candidate.difficulties.as_ref(), "10");
+        }
+    }
+
+    #[test]
+    fn query_tokens_use_player_state() {
+        let mut played = (*test_song("Played One", 120.0)).clone();
+        played.charts[0].short_hash = "played".to_string();
+        let mut fresh = (*test_song("Fresh One", 120.0)).clone();
+        fresh.charts[0].short_hash = "fresh".to_string();
+        let songs = vec![("Pack A", Arc::new(played)), ("Pack A", Arc::new(fresh))];
+        let index = index_from(&songs);
+        let mut player = SongQueryPlayerState::default();
+        player.record_score("played", 9, false);
+
+        let matches = build_song_matches(&index, "unplayed", "dance-single", &player);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].label(), "Fresh One");
+
+        let matches = build_song_matches(&index, "grade<=A meter:10 one", "dance-single", &player);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].label(), "Played One");
+    }
+
     #[test]
     fn pack_matches_rank_by_name() {
         let songs = vec![
@@ -1019,7 +1156,8 @@ mod tests {
             ("Pack A", test_song("Beta", 120.0)),
         ];
         let index = index_from(&songs);
-        let matches = build_song_matches(&index, "", "dance-single");
+        let matches =
+            build_song_matches(&index, "", "dance-single", &SongQueryPlayerState::default());
         assert_eq!(matches.len(), 2);
     }
 
@@ -1070,7 +1208,12 @@ mod tests {
     fn annotated_title_autocompletes_to_clean_name() {
         let songs = vec![("ITL Online 2026", test_song("[6998] [12] automate", 175.0))];
         let index = index_from(&songs);
-        let matches = build_song_matches(&index, "auto", "dance-single");
+        let matches = build_song_matches(
+            &index,
+            "auto",
+            "dance-single",
+            &SongQueryPlayerState::default(),
+        );
         assert_eq!(matches.len(), 1);
         assert_eq!(matches[0].label(), "automate");
     }
@@ -1102,14 +1245,24 @@ mod tests {
         // Every prefix has to match, or a live typeahead shows an empty list
         // partway through a word the user is spelling correctly.
         for query in ["d", "de", "dej", "deja", "dejav", "deja v", "deja vu"] {
-            let matches = build_song_matches(&index, query, "dance-single");
+            let matches = build_song_matches(
+                &index,
+                query,
+                "dance-single",
+                &SongQueryPlayerState::default(),
+            );
             assert!(
                 matches.iter().any(|m| m.label() == "Déjà Vu"),
                 "{query:?} did not reach the accented title"
             );
         }
         for (query, want) in [("senor", "Señorita"), ("uber", "Über Alles")] {
-            let matches = build_song_matches(&index, query, "dance-single");
+            let matches = build_song_matches(
+                &index,
+                query,
+                "dance-single",
+                &SongQueryPlayerState::default(),
+            );
             assert!(
                 matches.iter().any(|m| m.label() == want),
                 "{query:?} did not reach {want:?}"
@@ -1117,7 +1270,12 @@ mod tests {
         }
 
         // Typing the accent works too, and the shown label keeps it.
-        let matches = build_song_matches(&index, "déjà", "dance-single");
+        let matches = build_song_matches(
+            &index,
+            "déjà",
+            "dance-single",
+            &SongQueryPlayerState::default(),
+        );
         assert_eq!(matches[0].label(), "Déjà Vu");
     }
 
@@ -1134,7 +1292,13 @@ mod tests {
             let index = index_from(&songs);
             for query in ["cafe", "caf\u{e9}", "cafe\u{301}"] {
                 assert_eq!(
-                    build_song_matches(&index, query, "dance-single").len(),
+                    build_song_matches(
+                        &index,
+                        query,
+                        "dance-single",
+                        &SongQueryPlayerState::default()
+                    )
+                    .len(),
                     1,
                     "query {query:?} against title {title:?}"
                 );
@@ -1151,7 +1315,12 @@ mod tests {
             _ => unreachable!(),
         };
         open.query = "deja".to_string();
-        open.matches = build_song_matches(&index, "deja", "dance-single");
+        open.matches = build_song_matches(
+            &index,
+            "deja",
+            "dance-single",
+            &SongQueryPlayerState::default(),
+        );
         open.selected_index = 0;
 
         let completion = song_search_completion(&open).expect("ghost for an accented title");
@@ -1183,7 +1352,12 @@ mod tests {
             ("auto [10]", None),
         ] {
             open.query = query.to_string();
-            open.matches = build_song_matches(&index, query, "dance-single");
+            open.matches = build_song_matches(
+                &index,
+                query,
+                "dance-single",
+                &SongQueryPlayerState::default(),
+            );
             open.selected_index = 0;
             match (song_search_completion(&open), expected) {
                 (Some(completion), Some((display, accepted))) => {
@@ -0,0 +1,452 @@
+use crate::act;
+use crate::assets::i18n::{tr, tr_fmt};
+use crate::assets::{FontRole, machine_font_key};
+use crate::config::MachineFont;
+use deadlib_present::actors::Actor;
+use deadlib_present::color;
+use deadlib_present::space::{screen_center_x, screen_center_y, screen_height, screen_width};
+use deadsync_input::{InputEvent, VirtualAction};
+use deadsync_profile::PlayerSide;
+use deadsync_score::{WORKOUT_GOAL_PRESETS, WorkoutGoal, WorkoutGoalKind};
+
+pub const WORKOUT_INPUT_LOCK_SECONDS: f32 = 0.15;
+
+const WORKOUT_ROW_HEIGHT: f32 = 24.0;
+const WORKOUT_PANE_WIDTH: f32 = 360.0;
+const WORKOUT_DIM_ALPHA: f32 = 0.875;
+const WORKOUT_Z: i16 = 1480;
+
+/// A running session as the shell reports it; copied every Select Music
+/// frame, so it stays small and heap-free.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorkoutProgressView {
+    pub goal: WorkoutGoal,
+    /// Progress in the goal's unit.
+    pub amount: f32,
+    /// 0..=1.
+    pub progress: f32,
+    pub songs: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WorkoutOverlayState {
+    Hidden,
+    Visible {
+        side: PlayerSide,
+        /// Adds an End Workout row.
+        active: bool,
+        selected_index: usize,
+        input_lock: f32,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WorkoutInputOutcome {
+    None,
+    ChangedSelection,
+    Closed,
+    Start(WorkoutGoal),
+    End,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WorkoutRow {
+    Goal(WorkoutGoal),
+    End,
+    Exit,
+}
+
+impl WorkoutOverlayState {
+    #[inline(always)]
+    pub const fn is_hidden(&self) -> bool {
+        matches!(self, Self::Hidden)
+    }
+
+    #[inline(always)]
+    pub const fn side(&self) -> Option<PlayerSide> {
+        match self {
+            Self::Hidden => None,
+            Self::Visible { side, .. } => Some(*side),
+        }
+    }
+}
+
+fn workout_rows(active: bool) -> impl Iterator<Item = WorkoutRow> {
+    WORKOUT_GOAL_PRESETS
+        .into_iter()
+        .map(WorkoutRow::Goal)
+        .chain(active.then_some(WorkoutRow::End))
+        .chain(std::iter::once(WorkoutRow::Exit))
+}
+
+#[inline(always)]
+fn workout_row_count(active: bool) -> usize {
+    WORKOUT_GOAL_PRESETS.len() + usize::from(active) + 1
+}
+
+/// Opens on the running goal when there is one.
+pub fn begin_workout_overlay(
+    side: PlayerSide,
+    progress: Option<WorkoutProgressView>,
+) -> WorkoutOverlayState {
+    let selected_index = progress
+        .and_then(|view| {
+            WORKOUT_GOAL_PRESETS
+                .iter()
+                .position(|goal| *goal == view.goal)
+        })
+        .unwrap_or(0);
+    WorkoutOverlayState::Visible {
+        side,
+        active: progress.is_some(),
+        selected_index,
+        input_lock: WORKOUT_INPUT_LOCK_SECONDS,
+    }
+}
+
+pub fn update_workout_overlay(state: &mut WorkoutOverlayState, dt: f32) -> bool {
+    match state {
+        WorkoutOverlayState::Hidden => false,
+        WorkoutOverlayState::Visible { input_lock, .. } => {
+            *input_lock = (*input_lock - dt.max(0.0)).max(0.0);
+            true
+        }
+    }
+}
+
+pub fn workout_goal_label(goal: WorkoutGoal) -> String {
+    let key = match goal.kind {
+        WorkoutGoalKind::Calories => "WorkoutGoalCalories",
+        WorkoutGoalKind::Minutes => "WorkoutGoalMinutes",
+        WorkoutGoalKind::Steps => "WorkoutGoalSteps",
+    };
+    tr_fmt("SelectMusic", key, &[("target", &goal.target.to_string())]).to_string()
+}
+
+pub fn workout_progress_text(view: WorkoutProgressView) -> String {
+    let goal = workout_goal_label(view.goal);
+    if view.progress >= 1.0 {
+        return tr_fmt(
+            "SelectMusic",
+            "WorkoutProgressMet",
+            &[("goal", &goal), ("songs", &view.songs.to_string())],
+        )
+        .to_string();
+    }
+    tr_fmt(
+        "SelectMusic",
+        "WorkoutProgress",
+        &[
+            ("amount", &format!("{:.0}", view.amount.max(0.0).floor())),
+            ("goal", &goal),
+            ("percent", &format!("{:.0}", view.progress * 100.0)),
+        ],
+    )
+    .to_string()
+}
+
+pub fn handle_workout_input(
+    state: &mut WorkoutOverlayState,
+    ev: &InputEvent,
+) -> WorkoutInputOutcome {
+    if !ev.pressed {
+        return WorkoutInputOutcome::None;
+    }
+    let WorkoutOverlayState::Visible {
+        active,
+        selected_index,
+        input_lock,
+        ..
+    } = state
+    else {
+        return WorkoutInputOutcome::None;
+    };
+    if *input_lock > 0.0 {
+        return WorkoutInputOutcome::None;
+    }
+    let delta = match ev.action {
+        VirtualAction::p1_up
+        | VirtualAction::p1_menu_up
+        | VirtualAction::p1_left
+        | VirtualAction::p1_menu_left
+        | VirtualAction::p2_up
+        | VirtualAction::p2_menu_up
+        | VirtualAction::p2_left
+        | VirtualAction::p2_menu_left => -1,
+        VirtualAction::p1_down
+        | VirtualAction::p1_menu_down
+        | VirtualAction::p1_right
+        | VirtualAction::p1_menu_right
+        | VirtualAction::p2_down
+        | VirtualAction::p2_menu_down
+        | VirtualAction::p2_right
+        | VirtualAction::p2_menu_right => 1,
+        VirtualAction::p1_start | VirtualAction::p2_start => {
+            let row = workout_rows(*active).nth(*selected_index);
+            *state = WorkoutOverlayState::Hidden;
+            return match row {
+                Some(WorkoutRow::Goal(goal)) => WorkoutInputOutcome::Start(goal),
+                Some(WorkoutRow::End) => WorkoutInputOutcome::End,
+                Some(WorkoutRow::Exit) | None => WorkoutInputOutcome::Closed,
+            };
+        }
+        VirtualAction::p1_back
+        | VirtualAction::p2_back
+        | VirtualAction::p1_select
+        | VirtualAction::p2_select => {
+            *state = WorkoutOverlayState::Hidden;
+            return WorkoutInputOutcome::Closed;
+        }
+        _ => return WorkoutInputOutcome::None,
+    };
+    let len = workout_row_count(*active);
+    *selected_index =
+        ((((*selected_index).min(len - 1)) as isize + delta).rem_euclid(len as isize)) as usize;
+    WorkoutInputOutcome::ChangedSelection
+}
+
+fn push_text(actors: &mut Vec<Actor>, text: String, x: f32, y: f32, zoom: f32, rgba: [f32; 4]) {
+    actors.push(act!(text:
+        font("miso"):
+        settext(text):
+        align(0.5, 0.5):
+        xy(x, y):
+        zoom(zoom):
+        maxwidth(WORKOUT_PANE_WIDTH - 20.0):
+        diffuse(rgba[0], rgba[1], rgba[2], rgba[3]):
+        z(WORKOUT_Z + 7):
+        horizalign(center)
+    ));
+}
+
+pub fn build_workout_overlay(
+    state: &WorkoutOverlayState,
+    progress: Option<WorkoutProgressView>,
+    display_name: &str,
+    active_color_index: i32,
+    machine_font: MachineFont,
+) -> Option<Vec<Actor>> {
+    let WorkoutOverlayState::Visible {
+        active,
+        selected_index,
+        ..
+    } = *state
+    else {
+        return None;
+    };
+    let rows = workout_row_count(active);
+    // Header, progress line and one row per choice.
+    let pane_h = (rows + 2) as f32 * WORKOUT_ROW_HEIGHT + 12.0;
+    let cx = screen_center_x();
+    let cy = screen_center_y() - 15.0;
+    let top = cy - pane_h * 0.5;
+    let mut actors = Vec::with_capacity(rows + 10);
+    actors.push(act!(quad:
+        align(0.0, 0.0): xy(0.0, 0.0):
+        zoomto(screen_width(), screen_height()):
+        diffuse(0.0, 0.0, 0.0, WORKOUT_DIM_ALPHA):
+        z(WORKOUT_Z)
+    ));
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(cx, cy):
+        zoomto(WORKOUT_PANE_WIDTH + 2.0, pane_h + 2.0):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(WORKOUT_Z + 2)
+    ));
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(cx, cy):
+        zoomto(WORKOUT_PANE_WIDTH, pane_h):
+        diffuse(0.0, 0.0, 0.0, 1.0):
+        z(WORKOUT_Z + 3)
+    ));
+    let header_y = top + WORKOUT_ROW_HEIGHT * 0.5;
+    actors.push(act!(quad:
+        align(0.5, 0.5):
+        xy(cx, header_y):
+        zoomto(WORKOUT_PANE_WIDTH, WORKOUT_ROW_HEIGHT):
+        diffuse(0.0, 0.0, 1.0, 1.0):
+        z(WORKOUT_Z + 5)
+    ));
+    actors.push(act!(text:
+        font(machine_font_key(machine_font, FontRole::Header)):
+        settext(tr("SelectMusic", "WorkoutTitle")):
+        align(0.5, 0.5):
+        xy(cx, header_y):
+        zoom(0.5):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(WORKOUT_Z + 6):
+        horizalign(center)
+    ));
+    push_text(
+        &mut actors,
+        display_name.to_string(),
+        cx,
+        top - 24.0,
+        0.8,
+        [1.0, 1.0, 1.0, 1.0],
+    );
+    let status = progress.map_or_else(
+        || tr("SelectMusic", "WorkoutNoneActive").to_string(),
+        workout_progress_text,
+    );
+    push_text(
+        &mut actors,
+        status,
+        cx,
+        top + WORKOUT_ROW_HEIGHT * 1.5,
+        0.75,
+        [0.78, 0.78, 0.78, 1.0],
+    );
+
+    let selected_color = color::simply_love_rgba(active_color_index);
+    for (i, row) in workout_rows(active).enumerate() {
+        let y = top + WORKOUT_ROW_HEIGHT * (i as f32 + 2.5) + 6.0;
+        let selected = i == selected_index;
+        if selected {
+            actors.push(act!(quad:
+                align(0.5, 0.5):
+                xy(cx, y):
+                zoomto(WORKOUT_PANE_WIDTH - 12.0, WORKOUT_ROW_HEIGHT):
+                diffuse(selected_color[0], selected_color[1], selected_color[2], 1.0):
+                z(WORKOUT_Z + 5)
+            ));
+        }
+        let (text, rgba) = match row {
+            WorkoutRow::Goal(goal) => (workout_goal_label(goal), [1.0, 1.0, 1.0, 1.0]),
+            WorkoutRow::End => (
+                tr("SelectMusic", "WorkoutEnd").to_string(),
+                [1.0, 0.8, 0.3, 1.0],
+            ),
+            WorkoutRow::Exit => (
+                tr("SelectMusic", "WorkoutExit").to_string(),
+                [1.0, 0.25, 0.25, 1.0],
+            ),
+        };
+        let rgba = if selected { [0.0, 0.0, 0.0, 1.0] } else { rgba };
+        push_text(&mut actors, text, cx, y, 0.8, rgba);
+    }
+    actors.push(act!(text:
+        font("miso"):
+        settext(tr("SelectMusic", "WorkoutHelp")):
+        align(0.5, 0.5):
+        xy(screen_center_x(), screen_height() - 50.0):
+        zoom(1.1):
+        diffuse(1.0, 1.0, 1.0, 1.0):
+        z(WORKOUT_Z + 8):
+        horizalign(center)
+    ));
+    Some(actors)
+}
+
+/// The between-songs progress readout, tucked above the footer on the
+/// player's own side.
+pub fn build_workout_progress_line(side: PlayerSide, view: WorkoutProgressView) -> Actor {
+    let (x, halign) = match side {
+        PlayerSide::P1 => (10.0, 0.0),
+        PlayerSide::P2 => (screen_width() - 10.0, 1.0),
+    };
+    let rgba = if view.progress >= 1.0 {
+        [0.4, 1.0, 0.4, 1.0]
+    } else {
+        [1.0, 1.0, 1.0, 1.0]
+    };
+    act!(text:
+        font("miso"):
+        settext(workout_progress_text(view)):
+        align(halign, 1.0):
+        xy(x, screen_height() - 36.0):
+        zoom(0.7):
+        diffuse(rgba[0], rgba[1], rgba[2], rgba[3]):
+        z(120)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn press(state: &mut WorkoutOverlayState, action: VirtualAction) -> WorkoutInputOutcome {
+        let now = Instant::now();
+        handle_workout_input(
+            state,
+            &InputEvent {
+                action,
+                input_slot: 0,
+                pressed: true,
+                source: deadsync_core::input::InputSource::Keyboard,
+                timestamp: now,
+                timestamp_host_nanos: 0,
+                stored_at: now,
+                emitted_at: now,
+            },
+        )
+    }
+
+    #[test]
+    fn overlay_starts_the_chosen_goal_and_ends_a_running_one() {
+        let mut state = begin_workout_overlay(PlayerSide::P1, None);
+        assert!(matches!(
+            press(&mut state, VirtualAction::p1_start),
+            WorkoutInputOutcome::None
+        ));
+        update_workout_overlay(&mut state, 1.0);
+        press(&mut state, VirtualAction::p1_down);
+        assert_eq!(
+            press(&mut state, VirtualAction::p1_start),
+            WorkoutInputOutcome::Start(WORKOUT_GOAL_PRESETS[1])
+        );
+        assert!(state.is_hidden());
+
+        let running = WorkoutProgressView {
+            goal: WORKOUT_GOAL_PRESETS[4],
+            amount: 3.0,
+            progress: 0.2,
+            songs: 1,
+        };
+        let mut state = begin_workout_overlay(PlayerSide::P2, Some(running));
+        update_workout_overlay(&mut state, 1.0);
+        for _ in 4..WORKOUT_GOAL_PRESETS.len() {
+            press(&mut state, VirtualAction::p2_down);
+        }
+        assert_eq!(
+            press(&mut state, VirtualAction::p2_start),
+            WorkoutInputOutcome::End
+        );
+    }
+
+    #[test]
+    fn progress_text_switches_to_goal_met() {
+        let mut view = WorkoutProgressView {
+            goal: WorkoutGoal::steps(2_500),
+            amount: 1_250.4,
+            progress: 0.5,
+            songs: 3,
+        };
+        assert_eq!(
+            workout_progress_text(view),
+            tr_fmt(
+                "SelectMusic",
+                "WorkoutProgress",
+                &[
+                    ("amount", "1250"),
+                    ("goal", &workout_goal_label(view.goal)),
+                    ("percent", "50"),
+                ],
+            )
+            .to_string()
+        );
+        view.progress = 1.0;
+        assert_eq!(
+            workout_progress_text(view),
+            tr_fmt(
+                "SelectMusic",
+                "WorkoutProgressMet",
+                &[("goal", &workout_goal_label(view.goal)), ("songs", "3")],
+            )
+            .to_string()
+        );
+    }
+}
@@ -509,6 +509,7 @@ mod tests {
             judgments: None,
             score: None,
             ex_score: None,
+            song_vote: Default::default(),
         }
     }
 
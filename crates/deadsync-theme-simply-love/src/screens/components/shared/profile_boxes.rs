@@ -1099,7 +1099,10 @@ fn apply_zoom_to_actor(actor: &mut Actor, pivot: [f32; 2], zoom: f32) {
                 apply_zoom_to_actor(child, pivot, zoom);
             }
         }
-        Actor::CameraPush { .. } | Actor::CameraPop => {}
+        Actor::CameraPush { .. }
+        | Actor::CameraPop
+        | Actor::RenderTarget { .. }
+        | Actor::ShaderEffect { .. } => {}
         Actor::Shadow { len, child, .. } => {
             len[0] *= zoom;
             len[1] *= zoom;
@@ -1145,7 +1148,10 @@ fn apply_offset_to_actor(actor: &mut Actor, dx: f32, dy: f32) {
                 apply_offset_to_actor(child, dx, dy);
             }
         }
-        Actor::CameraPush { .. } | Actor::CameraPop => {}
+        Actor::CameraPush { .. }
+        | Actor::CameraPop
+        | Actor::RenderTarget { .. }
+        | Actor::ShaderEffect { .. } => {}
         Actor::Shadow { child, .. } => apply_offset_to_actor(child, dx, dy),
     }
 }
@@ -1160,10 +1166,12 @@ fn apply_z_offset(actor: &mut Actor, dz: i16) {
         | Actor::ReusableTexturedMesh { z, .. }
         | Actor::Frame { z, .. }
         | Actor::SharedFrame { z, .. }
-        | Actor::RetainedFrame { z, .. } => *z = z.saturating_add(dz),
+        | Actor::RetainedFrame { z, .. }
+        | Actor::ShaderEffect { z, .. } => *z = z.saturating_add(dz),
         Actor::Camera { .. }
         | Actor::CameraPush { .. }
         | Actor::CameraPop
+        | Actor::RenderTarget { .. }
         | Actor::Shadow { .. } => {}
     }
     match actor {
@@ -1213,7 +1221,9 @@ fn apply_clip_rect_to_actor(actor: &mut Actor, rect: [f32; 4]) {
         | Actor::ReusableTexturedMesh { .. }
         | Actor::RetainedFrame { .. }
         | Actor::CameraPush { .. }
-        | Actor::CameraPop => {}
+        | Actor::CameraPop
+        | Actor::RenderTarget { .. }
+        | Actor::ShaderEffect { .. } => {}
     }
 }
 
@@ -6,7 +6,7 @@ use deadlib_present::space::{screen_center_x, screen_center_y, screen_height, sc
 use deadsync_config::prelude::GameFlag;
 use deadsync_input::KeyCode;
 use deadsync_input::RawKeyboardEvent;
-use deadsync_input::{InputEvent, PadDir, PadEvent, VirtualAction, with_keymap};
+use deadsync_input::{InputEvent, PadDir, PadEvent, VirtualAction, with_active_keymap};
 use std::collections::{HashMap, VecDeque};
 use std::time::Instant;
 
@@ -365,7 +365,7 @@ pub fn apply_raw_pad_event(state: &mut State, pad_event: &PadEvent) {
         }
     };
 
-    let mapped = with_keymap(|km| km.pad_event_mapped(pad_event));
+    let mapped = with_active_keymap(|km| km.pad_event_mapped(pad_event));
     if mapped {
         return;
     }
@@ -384,7 +384,7 @@ pub fn apply_raw_key_event(state: &mut State, key_event: &RawKeyboardEvent) {
         return;
     }
     state.event_rate.record_key(key_event);
-    let mapped = with_keymap(|km| km.raw_key_event_mapped(key_event));
+    let mapped = with_active_keymap(|km| km.raw_key_event_mapped(key_event));
     if mapped {
         return;
     }
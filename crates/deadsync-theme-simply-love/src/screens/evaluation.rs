@@ -2942,6 +2942,7 @@ pub fn init(gameplay_results: Option<gameplay::State>, init_view: EvaluationInit
 
             // Quint comes from the achieved result, not whether FA+ is displayed.
             grade = score_data::promote_quint_grade(grade, ex_score_percent);
+            let alt_score = eval_alt_score(prof, notes, note_times, p.fail_time);
 
             let column_judgments = score_data::compute_column_judgments(
                 notes,
@@ -3003,6 +3004,7 @@ pub fn init(gameplay_results: Option<gameplay::State>, init_view: EvaluationInit
                 window_counts_10ms,
                 ex_score_percent,
                 hard_ex_score_percent,
+                alt_score,
                 calories_burned: p.calories_burned,
                 column_judgments,
                 noteskin,
@@ -4166,6 +4168,42 @@ fn eval_grade_for_result(
     }
 }
 
+/// Re-judge the stage under the player's `ScoringModel`. `Custom` without a
+/// loaded `scoring_windows.ini` shows nothing.
+fn eval_alt_score(
+    prof: &profile_data::Profile,
+    notes: &[deadsync_rules::note::Note],
+    note_times_ns: &[i64],
+    fail_time: Option<f32>,
+) -> Option<crate::views::AltScoreView> {
+    use deadsync_rules::scoring::{self, ScoringModelKind};
+
+    let kind = prof.scoring_model.kind()?;
+    let fail_time_ns = fail_time.map(deadsync_core::song_time::song_time_ns_from_seconds);
+    let (score, custom_name) = match kind {
+        ScoringModelKind::DdrMoney => (
+            scoring::ddr_money_score(notes, note_times_ns, fail_time_ns),
+            None,
+        ),
+        ScoringModelKind::Wife3 => (
+            scoring::wife3_score(notes, note_times_ns, fail_time_ns),
+            None,
+        ),
+        ScoringModelKind::Custom => {
+            let windows = prof.custom_scoring_windows.as_deref()?;
+            (
+                scoring::custom_windows_score(windows, notes, note_times_ns, fail_time_ns),
+                Some(Arc::from(windows.name.as_str())),
+            )
+        }
+    };
+    Some(crate::views::AltScoreView {
+        kind,
+        custom_name,
+        score,
+    })
+}
+
 pub fn all_joined_players_failed(state: &State) -> bool {
     let play_style = state.context.play_style;
     let mut found_player = false;
@@ -4485,7 +4523,11 @@ fn build_fail_label_text(label: Arc<str>, x: f32, y: f32) -> Actor {
 }
 
 #[inline(always)]
-fn life_record_lerp_at(life_history: &[(f32, f32)], record_start: f32, sample_time: f32) -> f32 {
+pub(crate) fn life_record_lerp_at(
+    life_history: &[(f32, f32)],
+    record_start: f32,
+    sample_time: f32,
+) -> f32 {
     let Some(&(_, start_life)) = life_history.first() else {
         return 0.0;
     };
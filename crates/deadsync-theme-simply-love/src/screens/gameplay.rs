@@ -37,15 +37,16 @@ use deadlib_present::space::{
     is_wide, screen_center_x, screen_center_y, screen_height, screen_width,
 };
 use deadlib_render_core::{
-    BlendMode, INVALID_TMESH_CACHE_KEY, MeshVertex, TMeshCacheKey, TexturedMeshVertex,
+    BlendMode, INVALID_TMESH_CACHE_KEY, MeshVertex, ShaderEffect, TMeshCacheKey,
+    TexturedMeshVertex, render_target_handle,
 };
 use deadsync_assets::noteskin::{self, Noteskin, SpriteSlot};
 use deadsync_assets::song_lua::{
-    CompiledSongLua, SongLuaCapturedActor, SongLuaOverlayActor, SongLuaOverlayBlendMode,
-    SongLuaOverlayCommandBlock, SongLuaOverlayKind, SongLuaOverlayMeshVertex,
-    SongLuaOverlayMessageCommand, SongLuaOverlayModelDraw, SongLuaOverlayModelLayer,
-    SongLuaOverlayState, SongLuaOverlayStateDelta, SongLuaProxyTarget, SongLuaTextGlowMode,
-    compile_song_lua,
+    CompiledSongLua, SONG_LUA_LIVE_FRAME_BUDGET, SongLuaCapturedActor, SongLuaLiveRuntime,
+    SongLuaOverlayActor, SongLuaOverlayBlendMode, SongLuaOverlayCommandBlock, SongLuaOverlayKind,
+    SongLuaOverlayMeshVertex, SongLuaOverlayMessageCommand, SongLuaOverlayModelDraw,
+    SongLuaOverlayModelLayer, SongLuaOverlayState, SongLuaOverlayStateDelta, SongLuaProxyTarget,
+    SongLuaTextGlowMode, compile_live_song_lua, compile_song_lua,
 };
 use deadsync_chart::{
     ChartData, GameplayChartData, SongBackgroundChange, SongBackgroundChangeTarget, SongData,
@@ -679,7 +680,7 @@ impl SongLuaOverlayTopologyIndex {
         let aft_sprite_targets = overlays
             .iter()
             .map(|overlay| match &overlay.kind {
-                SongLuaOverlayKind::AftSprite { capture_name } => SongLuaOverlayIndex::new(
+                SongLuaOverlayKind::AftSprite { capture_name, .. } => SongLuaOverlayIndex::new(
                     song_lua_overlay_capture_index_by_name(overlays, capture_name),
                 ),
                 _ => SongLuaOverlayIndex::default(),
@@ -708,7 +709,7 @@ impl SongLuaOverlayTopologyIndex {
         let dynamic_camera_scope = overlays.iter().any(|overlay| {
             matches!(
                 overlay.kind,
-                SongLuaOverlayKind::ActorFrame | SongLuaOverlayKind::ActorFrameTexture
+                SongLuaOverlayKind::ActorFrame | SongLuaOverlayKind::ActorFrameTexture { .. }
             ) && overlay
                 .message_commands
                 .iter()
@@ -717,7 +718,7 @@ impl SongLuaOverlayTopologyIndex {
         let mut aft_sprite_groups = vec![SongLuaOverlayIndex::default(); overlays.len()];
         let mut aft_peer_groups: Vec<Vec<usize>> = Vec::new();
         for (index, overlay) in overlays.iter().enumerate() {
-            let SongLuaOverlayKind::AftSprite { capture_name } = &overlay.kind else {
+            let SongLuaOverlayKind::AftSprite { capture_name, .. } = &overlay.kind else {
                 continue;
             };
             let group = overlays[..index]
@@ -726,6 +727,7 @@ impl SongLuaOverlayTopologyIndex {
                 .find_map(|(candidate_index, candidate)| {
                     let SongLuaOverlayKind::AftSprite {
                         capture_name: candidate_capture,
+                        ..
                     } = &candidate.kind
                     else {
                         return None;
@@ -773,6 +775,16 @@ impl SongLuaOverlayTopologyIndex {
             return None;
         }
         let capture_index = aft_sprite_targets.get(aft_index)?.get()?;
+        // A preserved texture accumulates across frames, which only a render
+        // target can hold.
+        if matches!(
+            overlays.get(capture_index)?.kind,
+            SongLuaOverlayKind::ActorFrameTexture {
+                preserve_texture: true
+            }
+        ) {
+            return None;
+        }
         let mut proxy_index = None;
         for (index, overlay) in overlays.iter().enumerate().filter(|(index, _)| {
             aft_ancestors
@@ -787,7 +799,7 @@ impl SongLuaOverlayTopologyIndex {
                 | SongLuaOverlayKind::Sound { .. } => {}
                 // A nested capture is a separate render boundary even when it
                 // does not currently contribute a visible sibling.
-                SongLuaOverlayKind::ActorFrameTexture => return None,
+                SongLuaOverlayKind::ActorFrameTexture { .. } => return None,
                 SongLuaOverlayKind::ActorProxy {
                     target:
                         SongLuaProxyTarget::Player { .. }
@@ -819,7 +831,7 @@ impl SongLuaOverlayTopologyIndex {
                 }
                 if matches!(
                     parent.kind,
-                    SongLuaOverlayKind::ActorFrame | SongLuaOverlayKind::ActorFrameTexture
+                    SongLuaOverlayKind::ActorFrame | SongLuaOverlayKind::ActorFrameTexture { .. }
                 ) && parent_state.fov.is_some()
                 {
                     Some(parent_index)
@@ -840,7 +852,7 @@ impl SongLuaOverlayTopologyIndex {
             overlays.get(ease.overlay_index).is_some_and(|overlay| {
                 matches!(
                     overlay.kind,
-                    SongLuaOverlayKind::ActorFrame | SongLuaOverlayKind::ActorFrameTexture
+                    SongLuaOverlayKind::ActorFrame | SongLuaOverlayKind::ActorFrameTexture { .. }
                 ) && (ease.from.delta.fov.is_some() || ease.to.delta.fov.is_some())
             })
         });
@@ -2178,6 +2190,8 @@ pub struct State {
     notefield_judgment_assets: [notefield::ResolvedJudgmentAssets; MAX_PLAYERS],
     notefield_combo_assets: notefield::ResolvedComboMilestoneAssets,
     notefield_plans: [notefield::GameplayNotefieldPlan; MAX_PLAYERS],
+    /// HUD anchors from the last composed frame, read by the layout editor.
+    notefield_hud_anchors: [Cell<deadsync_notefield::NotefieldHudAnchors>; MAX_PLAYERS],
     sync_overlay_text_cache: RefCell<SyncOverlayTextCache>,
     pub background_path_dirty: bool,
     pub background_changes: Vec<SongBackgroundChange>,
@@ -2216,6 +2230,11 @@ pub struct State {
     current_foreground_path: Option<PathBuf>,
     current_foreground_key: Option<Arc<str>>,
     song_lua_foreground_owner_index: SongLuaForegroundOwnerIndex,
+    /// Lua state of the primary song-lua script, kept only when the live
+    /// runtime is enabled and compile left perframes or function eases it
+    /// could not bake. The game thread steps it once per update; the first
+    /// frame over budget drops it and the compiled windows play on alone.
+    song_lua_live: Option<SongLuaLiveRuntime>,
     smx_sensor_views: [Option<SmxSensorPadView>; 2],
     pub heart_rate_view: HeartRateView,
     heart_rate_generation: (u64, u64),
@@ -2502,17 +2521,28 @@ impl State {
             &song_lua_visuals.overlays,
             &song_lua_proxy_request_index.topology,
         );
+        let background_layer_count = song_lua_visuals.background_visual_layers.len();
         let song_lua_background_aft_capture_scratch = song_lua_visuals
             .background_visual_layers
             .iter()
             .zip(song_lua_background_overlay_topology_indices.iter())
-            .map(|(layer, topology)| SongLuaAftCaptureScratch::new(&layer.overlays, topology))
+            .enumerate()
+            .map(|(layer_idx, (layer, topology))| {
+                SongLuaAftCaptureScratch::new(&layer.overlays, topology)
+                    .with_render_target_layer(u32::try_from(layer_idx + 1).unwrap_or(u32::MAX))
+            })
             .collect();
         let song_lua_foreground_aft_capture_scratch = song_lua_visuals
             .foreground_visual_layers
             .iter()
             .zip(song_lua_foreground_proxy_request_indices.iter())
-            .map(|(layer, index)| SongLuaAftCaptureScratch::new(&layer.overlays, &index.topology))
+            .enumerate()
+            .map(|(layer_idx, (layer, index))| {
+                SongLuaAftCaptureScratch::new(&layer.overlays, &index.topology)
+                    .with_render_target_layer(
+                        u32::try_from(background_layer_count + layer_idx + 1).unwrap_or(u32::MAX),
+                    )
+            })
             .collect();
         let mut proxy_pool_counts =
             song_lua_proxy_pool_counts(&song_lua_visuals.overlays, &song_lua_proxy_request_index);
@@ -2770,6 +2800,7 @@ impl State {
             notefield_judgment_assets,
             notefield_combo_assets,
             notefield_plans,
+            notefield_hud_anchors: Default::default(),
             sync_overlay_text_cache: RefCell::new(SyncOverlayTextCache::default()),
             background_path_dirty: true,
             background_changes,
@@ -2795,6 +2826,7 @@ impl State {
             current_foreground_path: None,
             current_foreground_key: None,
             song_lua_foreground_owner_index,
+            song_lua_live: None,
             smx_sensor_views: [None, None],
             heart_rate_view: HeartRateView::default(),
             heart_rate_generation: (u64::MAX, u64::MAX),
@@ -2826,6 +2858,30 @@ impl State {
         &self.notefield_plans[player_idx]
     }
 
+    /// Previews a HUD layout on the running field without touching the profile.
+    pub(crate) fn set_notefield_hud_layout(
+        &mut self,
+        player_idx: usize,
+        layout: deadsync_theme::HudLayout,
+    ) {
+        if let Some(plan) = self.notefield_plans.get_mut(player_idx) {
+            plan.set_hud_layout(layout);
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn shows_side_step_stats(&self) -> bool {
+        matches!(self.step_stats_mode, GameplayStepStatsMode::Side)
+    }
+
+    #[inline(always)]
+    pub(crate) fn notefield_hud_anchors(
+        &self,
+        player_idx: usize,
+    ) -> deadsync_notefield::NotefieldHudAnchors {
+        self.notefield_hud_anchors[player_idx].get()
+    }
+
     #[inline(always)]
     pub fn actor_resources(&self) -> &ActorResourceArena {
         &self.actor_resources
@@ -2856,7 +2912,7 @@ impl State {
     }
 
     #[inline(always)]
-    fn notefield_width(&self, player: usize) -> f32 {
+    pub(crate) fn notefield_width(&self, player: usize) -> f32 {
         self.notefield_widths
             .get(player)
             .copied()
@@ -3116,13 +3172,30 @@ fn compile_primary_song_lua(
     song_title: &str,
     path: &Path,
     context: &deadsync_assets::song_lua::SongLuaCompileContext,
-) -> Option<GameplayCompiledSongLua> {
+    live: bool,
+) -> Option<(GameplayCompiledSongLua, Option<SongLuaLiveRuntime>)> {
     let compile_started = Instant::now();
-    match compile_song_lua(path, context) {
-        Ok(compiled) => Some(GameplayCompiledSongLua {
-            compiled,
-            compile_ms: compile_started.elapsed().as_secs_f64() * 1000.0,
-        }),
+    let result = if live {
+        compile_live_song_lua(path, context, SONG_LUA_LIVE_FRAME_BUDGET)
+    } else {
+        compile_song_lua(path, context).map(|compiled| (compiled, None))
+    };
+    match result {
+        Ok((compiled, live)) => {
+            if let Some(live) = live.as_ref() {
+                log::info!(
+                    "Running gameplay lua for '{}' live: {} perframes, {} function eases",
+                    song_title,
+                    live.perframe_count(),
+                    live.function_ease_count(),
+                );
+            }
+            let compiled = GameplayCompiledSongLua {
+                compiled,
+                compile_ms: compile_started.elapsed().as_secs_f64() * 1000.0,
+            };
+            Some((compiled, live))
+        }
         Err(err) => {
             log::warn!(
                 "Failed to compile gameplay lua for '{}' from '{}': {}",
@@ -3169,7 +3242,7 @@ fn gameplay_song_lua_data(
     viewport: GameplayViewport,
     session: &GameplaySession,
     config: &GameplayConfig,
-) -> GameplaySongLuaData {
+) -> (GameplaySongLuaData, Option<SongLuaLiveRuntime>) {
     let primary_ix = song
         .foreground_lua_changes
         .iter()
@@ -3178,7 +3251,7 @@ fn gameplay_song_lua_data(
         && song.background_lua_changes.is_empty()
         && song.foreground_lua_changes.is_empty()
     {
-        return GameplaySongLuaData::default();
+        return (GameplaySongLuaData::default(), None);
     }
 
     let mut runtime_charts = [charts[0].clone(), charts[1].clone()];
@@ -3202,13 +3275,16 @@ fn gameplay_song_lua_data(
         session,
         config.center_1player_notefield,
     );
-    let primary = primary_ix.and_then(|ix| {
-        compile_primary_song_lua(
-            song.title.as_str(),
-            &song.foreground_lua_changes[ix].path,
-            &context,
-        )
-    });
+    let (primary, live) = primary_ix
+        .and_then(|ix| {
+            compile_primary_song_lua(
+                song.title.as_str(),
+                &song.foreground_lua_changes[ix].path,
+                &context,
+                config.song_lua_live_runtime,
+            )
+        })
+        .map_or((None, None), |(primary, live)| (Some(primary), live));
     let primary_key = primary_ix.map(|ix| {
         let change = &song.foreground_lua_changes[ix];
         (change.start_beat.to_bits(), change.path.clone())
@@ -3246,11 +3322,12 @@ fn gameplay_song_lua_data(
         })
         .collect();
 
-    GameplaySongLuaData {
+    let data = GameplaySongLuaData {
         primary,
         background_layers,
         foreground_layers,
-    }
+    };
+    (data, live)
 }
 
 fn song_lua_sound_paths(data: &GameplaySongLuaData) -> Vec<PathBuf> {
@@ -3632,10 +3709,12 @@ fn build_song_lua_runtime_windows_for_data(
             },
             message_commands: Vec::new(),
             manual_hud_draw: false,
+            shader_effect: None,
         }
     });
     let mut player_events: [Vec<SongLuaOverlayMessageRuntime>; MAX_PLAYERS] =
         std::array::from_fn(|_| Vec::new());
+    let mut song_background = SongLuaCapturedActor::default();
     let mut song_foreground = SongLuaCapturedActor::default();
     let mut song_foreground_events = Vec::new();
     let mut hidden_players = [false; MAX_PLAYERS];
@@ -3660,6 +3739,7 @@ fn build_song_lua_runtime_windows_for_data(
                 foreground_visual_layers,
                 player_actors,
                 player_events,
+                song_background,
                 song_foreground,
                 song_foreground_events,
                 hidden_players,
@@ -3713,6 +3793,7 @@ fn build_song_lua_runtime_windows_for_data(
                 )
             },
         );
+        song_background = compiled.song_background.clone();
         song_foreground = compiled.song_foreground.clone();
         song_foreground_events =
             deadsync_profile_gameplay::build_song_lua_actor_message_events_for_commands(
@@ -3837,6 +3918,7 @@ fn build_song_lua_runtime_windows_for_data(
             foreground_visual_layers,
             player_actors,
             player_events,
+            song_background,
             song_foreground,
             song_foreground_events,
             hidden_players,
@@ -3888,7 +3970,7 @@ pub fn init(
         gameplay_noteskin_assets(cols_per_player, num_players, &runtime_profile_data);
     let noteskin_data =
         noteskin_assets.gameplay_data(cols_per_player, num_players, &runtime_profile_data);
-    let song_lua_data = gameplay_song_lua_data(
+    let (song_lua_data, song_lua_live) = gameplay_song_lua_data(
         &song,
         &charts,
         &player_profiles,
@@ -3908,7 +3990,8 @@ pub fn init(
     let pack_group = pack_data.pack_group;
     let pack_banner_path = pack_data.pack_banner_path;
     let pack_sync_pref = pack_data.sync_pref;
-    State::from_gameplay_with_screen_data(
+    let pack_sync_bias_ms = pack_data.sync_bias_ms;
+    let mut state = State::from_gameplay_with_screen_data(
         deadsync_gameplay::init_gameplay_runtime(
             song,
             charts,
@@ -3917,6 +4000,7 @@ pub fn init(
             session,
             config,
             pack_sync_pref,
+            pack_sync_bias_ms,
             scores.mini_indicator,
             noteskin_data,
             song_lua_data,
@@ -3949,7 +4033,9 @@ pub fn init(
         scores.rival_score_types,
         runtime,
         hud,
-    )
+    );
+    state.song_lua_live = song_lua_live;
+    state
 }
 
 #[inline(always)]
@@ -4769,6 +4855,7 @@ pub fn update(
     fallback_host_nanos: impl FnOnce() -> u64,
     effects: &mut Vec<ThemeEffect>,
 ) {
+    step_song_lua_live(state);
     let action = update_core(state, delta_time, audio_snapshot, fallback_host_nanos);
     update_life_meter_visuals(state, delta_time);
     match action {
@@ -4781,6 +4868,25 @@ pub fn update(
     }
 }
 
+/// Runs one live song-lua frame at the last displayed beat, so the windows it
+/// leaves land in this update's attack refresh.
+fn step_song_lua_live(state: &mut State) {
+    let Some(live) = state.song_lua_live.as_mut() else {
+        return;
+    };
+    let beat = state.gameplay.current_beat_display();
+    let seconds = state.gameplay.current_music_time_display();
+    let windows = live.step(beat, seconds, song_lua_ease_factor);
+    deadsync_profile_gameplay::song_lua_live_ease_windows(
+        windows,
+        state.gameplay.num_players(),
+        &mut state.gameplay.mods.attacks.song_lua_live_windows,
+    );
+    if live.fallback_reason().is_some() {
+        state.song_lua_live = None;
+    }
+}
+
 fn update_life_meter_visuals(state: &mut State, delta_time: f32) {
     for player in 0..state.gameplay.num_players().min(MAX_PLAYERS) {
         let runtime = &state.gameplay.players()[player];
@@ -7297,7 +7403,7 @@ fn song_lua_overlay_parent_uses_center_origin(
         parent_kind,
         SongLuaOverlayKind::Actor
             | SongLuaOverlayKind::ActorFrame
-            | SongLuaOverlayKind::ActorFrameTexture
+            | SongLuaOverlayKind::ActorFrameTexture { .. }
     ) && (parent_axis - 0.5 * overlay_space_axis).abs() <= 0.01
 }
 
@@ -7315,7 +7421,7 @@ fn song_lua_overlay_compose_state(
         parent_kind,
         SongLuaOverlayKind::Actor
             | SongLuaOverlayKind::ActorFrame
-            | SongLuaOverlayKind::ActorFrameTexture
+            | SongLuaOverlayKind::ActorFrameTexture { .. }
     ) && song_lua_overlay_parent_uses_center_origin(
         parent_kind,
         parent.x,
@@ -7330,7 +7436,7 @@ fn song_lua_overlay_compose_state(
         parent_kind,
         SongLuaOverlayKind::Actor
             | SongLuaOverlayKind::ActorFrame
-            | SongLuaOverlayKind::ActorFrameTexture
+            | SongLuaOverlayKind::ActorFrameTexture { .. }
     ) && song_lua_overlay_parent_uses_center_origin(
         parent_kind,
         parent.y,
@@ -7716,7 +7822,7 @@ fn song_lua_capture_replaces_player(
                         if *target_player == player_index
                 ) && song_lua_proxy_target_has_source(target, proxy_sources)
             }
-            SongLuaOverlayKind::AftSprite { capture_name } => {
+            SongLuaOverlayKind::AftSprite { capture_name, .. } => {
                 song_lua_overlay_capture_index_by_name(overlays, capture_name).is_some_and(
                     |nested_capture| {
                         song_lua_capture_replaces_player(
@@ -7747,7 +7853,7 @@ fn song_lua_replacement_active_players(
         if !overlay_state.visible || overlay_state.diffuse[3] <= f32::EPSILON {
             continue;
         }
-        let SongLuaOverlayKind::AftSprite { capture_name } = &overlay.kind else {
+        let SongLuaOverlayKind::AftSprite { capture_name, .. } = &overlay.kind else {
             continue;
         };
         let Some(capture_index) = song_lua_overlay_capture_index_by_name(overlays, capture_name)
@@ -7870,7 +7976,7 @@ fn song_lua_overlay_aft_ancestor(
 ) -> Option<usize> {
     while let Some(parent_index) = overlays.get(index).and_then(|overlay| overlay.parent_index) {
         match overlays.get(parent_index).map(|overlay| &overlay.kind) {
-            Some(SongLuaOverlayKind::ActorFrameTexture) => return Some(parent_index),
+            Some(SongLuaOverlayKind::ActorFrameTexture { .. }) => return Some(parent_index),
             Some(_) => index = parent_index,
             None => return None,
         }
@@ -7886,7 +7992,7 @@ fn song_lua_overlay_camera_ancestor(
         let overlay = overlays.get(current)?;
         if matches!(
             overlay.kind,
-            SongLuaOverlayKind::ActorFrame | SongLuaOverlayKind::ActorFrameTexture
+            SongLuaOverlayKind::ActorFrame | SongLuaOverlayKind::ActorFrameTexture { .. }
         ) {
             return Some(current);
         }
@@ -7900,7 +8006,7 @@ fn song_lua_overlay_capture_index_by_name(
     capture_name: &str,
 ) -> Option<usize> {
     overlays.iter().position(|overlay| {
-        matches!(overlay.kind, SongLuaOverlayKind::ActorFrameTexture)
+        matches!(overlay.kind, SongLuaOverlayKind::ActorFrameTexture { .. })
             && overlay
                 .name
                 .as_deref()
@@ -8125,6 +8231,15 @@ const SONG_LUA_SCREEN_CAPTURE_CAPACITY: usize = 32;
 const SONG_LUA_PROXY_FRAME_BANKS: usize = 2;
 const SONG_LUA_PROXY_SEGMENTS_PER_ACTOR: usize = 5;
 const SONG_LUA_AFT_FRAME_BANKS: usize = 2;
+// Render-target slots reserved per song-lua layer; each AFT overlay index
+// within a layer maps to one slot in the lower half, and an AFT that shows its
+// own texture keeps last frame's copy at the same index in the upper half.
+const SONG_LUA_AFT_TARGET_SLOTS: u32 = 1 << 16;
+const SONG_LUA_AFT_FEEDBACK_SLOT_OFFSET: u32 = SONG_LUA_AFT_TARGET_SLOTS / 2;
+// The last block holds the song background's effect target, then one per
+// player notefield.
+const SONG_LUA_EFFECT_TARGET_BASE: u32 = u32::MAX - SONG_LUA_AFT_TARGET_SLOTS + 1;
+const SONG_LUA_BACKGROUND_EFFECT_SLOT: u32 = SONG_LUA_EFFECT_TARGET_BASE;
 const SONG_LUA_PLAYER_PROXY_SOURCE_COUNT: usize = 4;
 const SONG_LUA_FIELD_PROXY_SOURCE: usize = 0;
 const SONG_LUA_JUDGMENT_PROXY_SOURCE: usize = 1;
@@ -8468,7 +8583,10 @@ fn song_lua_normalize_proxy_segment(
 #[derive(Default)]
 struct SongLuaAftCaptureScratch {
     slots: Vec<Option<[SharedActorFrameScratch; SONG_LUA_AFT_FRAME_BANKS]>>,
+    target_modes: Vec<SongLuaAftTargetMode>,
     active_bank: usize,
+    render_target_base: u32,
+    render_targets: bool,
 }
 
 impl SongLuaAftCaptureScratch {
@@ -8483,12 +8601,46 @@ impl SongLuaAftCaptureScratch {
                 })
             })
             .collect();
+        let target_modes = (0..overlays.len())
+            .map(|index| SongLuaAftTargetMode::new(overlays, topology, index))
+            .collect();
         Self {
             slots,
+            target_modes,
             active_bank: SONG_LUA_AFT_FRAME_BANKS - 1,
+            render_target_base: 0,
+            render_targets: false,
         }
     }
 
+    /// Places this layer's captures in render-target block `layer`.
+    fn with_render_target_layer(mut self, layer: u32) -> Self {
+        self.render_target_base = layer.saturating_mul(SONG_LUA_AFT_TARGET_SLOTS);
+        self
+    }
+
+    /// Render-target slot for the AFT or shader-effect overlay at `index`, or
+    /// `None` when captures must re-render their children in place.
+    fn render_target_slot(&self, index: usize) -> Option<u32> {
+        let index = u32::try_from(index).ok()?;
+        (self.render_targets && index < SONG_LUA_AFT_FEEDBACK_SLOT_OFFSET)
+            .then(|| self.render_target_base.saturating_add(index))
+    }
+
+    /// Render-target pass for the AFT sprite at `index`, following how its
+    /// captured AFT keeps the texture between frames.
+    fn aft_target(&self, index: usize) -> Option<SongLuaAftTarget> {
+        let slot = self.render_target_slot(index)?;
+        let mode = self.target_modes.get(index).copied().unwrap_or_default();
+        Some(SongLuaAftTarget {
+            slot,
+            clear: !mode.preserve,
+            feedback_slot: mode
+                .feedback
+                .then(|| slot.saturating_add(SONG_LUA_AFT_FEEDBACK_SLOT_OFFSET)),
+        })
+    }
+
     fn begin_frame(&mut self) {
         self.active_bank = (self.active_bank + 1) % SONG_LUA_AFT_FRAME_BANKS;
     }
@@ -8501,11 +8653,81 @@ impl SongLuaAftCaptureScratch {
     }
 }
 
+/// How an AFT sprite's render target carries over between frames.
+#[derive(Clone, Copy, Default)]
+struct SongLuaAftTargetMode {
+    /// The captured AFT preserves its texture, so each pass draws over the
+    /// last one.
+    preserve: bool,
+    /// The capture shows its own texture, which it samples from last frame's
+    /// copy.
+    feedback: bool,
+}
+
+impl SongLuaAftTargetMode {
+    fn new(
+        overlays: &[SongLuaOverlayActor],
+        topology: &SongLuaOverlayTopologyIndex,
+        index: usize,
+    ) -> Self {
+        let Some(capture_index) = topology
+            .aft_sprite_targets
+            .get(index)
+            .copied()
+            .and_then(SongLuaOverlayIndex::get)
+        else {
+            return Self::default();
+        };
+        let preserve = matches!(
+            overlays.get(capture_index).map(|overlay| &overlay.kind),
+            Some(SongLuaOverlayKind::ActorFrameTexture {
+                preserve_texture: true
+            })
+        );
+        let feedback = (0..overlays.len()).any(|child| {
+            topology
+                .aft_ancestors
+                .get(child)
+                .copied()
+                .and_then(SongLuaOverlayIndex::get)
+                == Some(capture_index)
+                && topology
+                    .aft_sprite_targets
+                    .get(child)
+                    .copied()
+                    .and_then(SongLuaOverlayIndex::get)
+                    == Some(capture_index)
+        });
+        Self { preserve, feedback }
+    }
+}
+
+/// One frame's offscreen pass for an AFT capture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct SongLuaAftTarget {
+    slot: u32,
+    clear: bool,
+    /// Slot that receives a copy of `slot` before the pass, so sprites inside
+    /// the capture that show its texture read the previous frame instead of
+    /// the target being drawn.
+    feedback_slot: Option<u32>,
+}
+
+impl SongLuaAftTarget {
+    const fn cleared(slot: u32) -> Self {
+        Self {
+            slot,
+            clear: true,
+            feedback_slot: None,
+        }
+    }
+}
+
 fn song_lua_aft_actor_capacity(kind: &SongLuaOverlayKind) -> usize {
     match kind {
         SongLuaOverlayKind::Actor
         | SongLuaOverlayKind::ActorFrame
-        | SongLuaOverlayKind::ActorFrameTexture
+        | SongLuaOverlayKind::ActorFrameTexture { .. }
         | SongLuaOverlayKind::AftSprite { .. }
         | SongLuaOverlayKind::Sound { .. } => 0,
         SongLuaOverlayKind::ActorProxy { .. } => 1,
@@ -9005,7 +9227,7 @@ fn song_lua_collect_capture_requests(
             SongLuaOverlayKind::ActorProxy { target } => {
                 song_lua_mark_proxy_target(requests, target);
             }
-            SongLuaOverlayKind::AftSprite { capture_name } => {
+            SongLuaOverlayKind::AftSprite { capture_name, .. } => {
                 if let Some(nested_capture) =
                     song_lua_overlay_capture_index_by_name(overlays, capture_name)
                 {
@@ -9045,7 +9267,7 @@ fn song_lua_proxy_requests(
             SongLuaOverlayKind::ActorProxy { target } => {
                 song_lua_mark_proxy_target(&mut requests, target);
             }
-            SongLuaOverlayKind::AftSprite { capture_name } => {
+            SongLuaOverlayKind::AftSprite { capture_name, .. } => {
                 if let Some(capture_index) =
                     song_lua_overlay_capture_index_by_name(overlays, capture_name)
                 {
@@ -9664,7 +9886,8 @@ fn song_lua_proxy_actor_has_z(actor: &Actor) -> bool {
         }
         Actor::Camera { children, .. } => children.iter().any(song_lua_proxy_actor_has_z),
         Actor::Shadow { child, .. } => song_lua_proxy_actor_has_z(child),
-        Actor::CameraPush { .. } | Actor::CameraPop => false,
+        Actor::ShaderEffect { z, .. } => *z != 0,
+        Actor::CameraPush { .. } | Actor::CameraPop | Actor::RenderTarget { .. } => false,
     }
 }
 
@@ -9678,9 +9901,13 @@ fn song_lua_proxy_actor_z(actor: &Actor) -> i16 {
         | Actor::ReusableTexturedMesh { z, .. }
         | Actor::Frame { z, .. }
         | Actor::SharedFrame { z, .. }
-        | Actor::RetainedFrame { z, .. } => *z,
+        | Actor::RetainedFrame { z, .. }
+        | Actor::ShaderEffect { z, .. } => *z,
         Actor::Shadow { child, .. } => song_lua_proxy_actor_z(child),
-        Actor::Camera { .. } | Actor::CameraPush { .. } | Actor::CameraPop => 0,
+        Actor::Camera { .. }
+        | Actor::CameraPush { .. }
+        | Actor::CameraPop
+        | Actor::RenderTarget { .. } => 0,
     }
 }
 
@@ -9782,7 +10009,8 @@ fn song_lua_proxy_zero_local_z(actor: &mut Actor) {
         | Actor::ReusableMesh { z, .. }
         | Actor::TexturedMesh { z, .. }
         | Actor::ReusableTexturedMesh { z, .. }
-        | Actor::RetainedFrame { z, .. } => *z = 0,
+        | Actor::RetainedFrame { z, .. }
+        | Actor::ShaderEffect { z, .. } => *z = 0,
         Actor::Frame { z, children, .. } => {
             *z = 0;
             song_lua_proxy_local_children_in_place(children);
@@ -9793,7 +10021,7 @@ fn song_lua_proxy_zero_local_z(actor: &mut Actor) {
         }
         Actor::Camera { children, .. } => song_lua_proxy_local_children_in_place(children),
         Actor::Shadow { child, .. } => song_lua_proxy_zero_local_z(child),
-        Actor::CameraPush { .. } | Actor::CameraPop => {}
+        Actor::CameraPush { .. } | Actor::CameraPop | Actor::RenderTarget { .. } => {}
     }
 }
 
@@ -9986,7 +10214,7 @@ fn song_lua_capture_overlay_child_states(
             overlay_space_width,
             overlay_space_height,
         );
-        if !matches!(overlay.kind, SongLuaOverlayKind::ActorFrameTexture) {
+        if !matches!(overlay.kind, SongLuaOverlayKind::ActorFrameTexture { .. }) {
             song_lua_capture_overlay_child_states(
                 overlays,
                 local_overlay_states,
@@ -10061,7 +10289,7 @@ fn song_lua_append_local_proxy_target(
                 );
             }
         }
-        SongLuaOverlayKind::ActorFrameTexture
+        SongLuaOverlayKind::ActorFrameTexture { .. }
         | SongLuaOverlayKind::ActorProxy { .. }
         | SongLuaOverlayKind::AftSprite { .. }
         | SongLuaOverlayKind::Sound { .. } => {}
@@ -10191,6 +10419,7 @@ fn song_lua_capture_children_into(
     topology_index: &SongLuaOverlayTopologyIndex,
     asset_manager: &AssetManager,
     capture_index: usize,
+    feedback_slot: Option<u32>,
     proxy_sources: &SongLuaScreenProxySources<'_>,
     mut proxy_actor_scratch: Option<&mut SongLuaProxyActorScratch>,
     overlay_space_width: f32,
@@ -10237,7 +10466,7 @@ fn song_lua_capture_children_into(
             overlay.kind,
             SongLuaOverlayKind::Actor
                 | SongLuaOverlayKind::ActorFrame
-                | SongLuaOverlayKind::ActorFrameTexture
+                | SongLuaOverlayKind::ActorFrameTexture { .. }
         ) {
             continue;
         }
@@ -10281,6 +10510,26 @@ fn song_lua_capture_children_into(
                     out.push(actor);
                 }
             }
+            SongLuaOverlayKind::AftSprite { .. }
+                if topology_index
+                    .aft_sprite_targets
+                    .get(idx)
+                    .copied()
+                    .and_then(SongLuaOverlayIndex::get)
+                    == Some(capture_index) =>
+            {
+                if let Some(feedback_slot) = feedback_slot {
+                    song_lua_push_capture_feedback(
+                        out,
+                        overlay,
+                        overlay_state,
+                        draw_idx.min(i16::MAX as usize) as i16,
+                        feedback_slot,
+                        overlay_space_width,
+                        overlay_space_height,
+                    );
+                }
+            }
             _ => {
                 let z = draw_idx.min(i16::MAX as usize) as i16;
                 if append_song_lua_multi_actor_overlay(
@@ -10344,6 +10593,7 @@ fn song_lua_capture_children(
         topology_index,
         asset_manager,
         capture_index,
+        None,
         proxy_sources,
         None,
         overlay_space_width,
@@ -11167,6 +11417,20 @@ fn song_lua_style_capture_actor(
         },
         Actor::CameraPush { view_proj } => Actor::CameraPush { view_proj },
         Actor::CameraPop => Actor::CameraPop,
+        target @ Actor::RenderTarget { .. } => target,
+        Actor::ShaderEffect {
+            slot,
+            effect,
+            tint,
+            blend: actor_blend,
+            z,
+        } => Actor::ShaderEffect {
+            slot,
+            effect,
+            tint: song_lua_capture_tint(tint, capture_tint),
+            blend: blend.unwrap_or(actor_blend),
+            z: song_lua_add_z(z, z_shift),
+        },
         Actor::Shadow {
             len,
             color,
@@ -11264,7 +11528,7 @@ fn song_lua_rgb_aft_group_for(
     index: usize,
 ) -> Option<(usize, [usize; 3])> {
     let overlay = overlays.get(index)?;
-    let SongLuaOverlayKind::AftSprite { capture_name } = &overlay.kind else {
+    let SongLuaOverlayKind::AftSprite { capture_name, .. } = &overlay.kind else {
         return None;
     };
     let peers = overlays
@@ -11273,6 +11537,7 @@ fn song_lua_rgb_aft_group_for(
         .filter_map(|(candidate_index, candidate)| {
             let SongLuaOverlayKind::AftSprite {
                 capture_name: candidate_capture,
+                ..
             } = &candidate.kind
             else {
                 return None;
@@ -11355,7 +11620,8 @@ fn song_lua_shift_capture_z(actor: &mut Actor, z_shift: i16) {
         | Actor::TexturedMesh { z, .. }
         | Actor::ReusableTexturedMesh { z, .. }
         | Actor::SharedFrame { z, .. }
-        | Actor::RetainedFrame { z, .. } => *z = song_lua_add_z(*z, z_shift),
+        | Actor::RetainedFrame { z, .. }
+        | Actor::ShaderEffect { z, .. } => *z = song_lua_add_z(*z, z_shift),
         Actor::Frame { children, z, .. } => {
             *z = song_lua_add_z(*z, z_shift);
             for child in children {
@@ -11368,7 +11634,7 @@ fn song_lua_shift_capture_z(actor: &mut Actor, z_shift: i16) {
             }
         }
         Actor::Shadow { child, .. } => song_lua_shift_capture_z(child, z_shift),
-        Actor::CameraPush { .. } | Actor::CameraPop => {}
+        Actor::CameraPush { .. } | Actor::CameraPop | Actor::RenderTarget { .. } => {}
     }
 }
 
@@ -11409,6 +11675,7 @@ fn song_lua_build_shared_capture(
     z: i16,
     overlay_space_width: f32,
     overlay_space_height: f32,
+    render_target: Option<SongLuaAftTarget>,
     scratch: &mut SharedActorFrameScratch,
     fill: impl FnOnce(&mut Vec<Actor>),
 ) -> Option<Actor> {
@@ -11440,6 +11707,19 @@ fn song_lua_build_shared_capture(
             out.push(Actor::CameraPop);
         }
     })?;
+    if let Some(target) = render_target {
+        let effect = match &overlay.kind {
+            SongLuaOverlayKind::AftSprite { shader_effect, .. } => *shader_effect,
+            _ => None,
+        };
+        return Some(song_lua_render_target_capture(
+            target,
+            children,
+            state.diffuse,
+            blend,
+            effect,
+        ));
+    }
     Some(Actor::SharedFrame {
         align: [0.0, 0.0],
         offset: [0.0, 0.0],
@@ -11452,6 +11732,176 @@ fn song_lua_build_shared_capture(
     })
 }
 
+// Renders a capture once into an offscreen target, then composites it as a
+// single full-screen sprite so the diffuse and blend apply to the flattened
+// result instead of to every captured child. A requested shader effect
+// replaces the sprite with the effect composite. A feedback slot is filled
+// with last frame's target first, ahead of the pass that redraws it.
+fn song_lua_render_target_capture(
+    target: SongLuaAftTarget,
+    children: Arc<[Actor]>,
+    tint: [f32; 4],
+    blend: Option<BlendMode>,
+    effect: Option<ShaderEffect>,
+) -> Actor {
+    let size = [
+        screen_width().round().max(1.0) as u32,
+        screen_height().round().max(1.0) as u32,
+    ];
+    let blend = blend.unwrap_or(BlendMode::Alpha);
+    let mut frame_children = Vec::with_capacity(3);
+    if let Some(feedback_slot) = target.feedback_slot {
+        frame_children.push(Actor::RenderTarget {
+            slot: feedback_slot,
+            size,
+            clear: true,
+            children: Arc::from([song_lua_render_target_sprite(
+                target.slot,
+                [0.0, 0.0],
+                [1.0; 4],
+                BlendMode::Alpha,
+                0,
+            )]),
+        });
+    }
+    frame_children.push(Actor::RenderTarget {
+        slot: target.slot,
+        size,
+        clear: target.clear,
+        children,
+    });
+    frame_children.push(match effect {
+        Some(effect) => Actor::ShaderEffect {
+            slot: target.slot,
+            effect,
+            tint,
+            blend,
+            z: 0,
+        },
+        None => song_lua_render_target_sprite(target.slot, [0.0, 0.0], tint, blend, 0),
+    });
+    Actor::Frame {
+        align: [0.0, 0.0],
+        offset: [0.0, 0.0],
+        size: [SizeSpec::Fill, SizeSpec::Fill],
+        children: frame_children,
+        background: None,
+        z: 0,
+    }
+}
+
+// A full-screen sprite showing render-target `slot`.
+fn song_lua_render_target_sprite(
+    slot: u32,
+    offset: [f32; 2],
+    tint: [f32; 4],
+    blend: BlendMode,
+    z: i16,
+) -> Actor {
+    Actor::Sprite {
+        align: [0.0, 0.0],
+        offset,
+        world_z: 0.0,
+        size: [SizeSpec::Fill, SizeSpec::Fill],
+        source: SpriteSource::TextureStaticHandle {
+            key: "__render_target",
+            handle: render_target_handle(slot),
+            generation: 0,
+        },
+        tint,
+        glow: [1.0, 1.0, 1.0, 0.0],
+        z,
+        cell: None,
+        grid: None,
+        uv_rect: Some([0.0, 0.0, 1.0, 1.0]),
+        visible: true,
+        flip_x: false,
+        flip_y: false,
+        cropleft: 0.0,
+        cropright: 0.0,
+        croptop: 0.0,
+        cropbottom: 0.0,
+        fadeleft: 0.0,
+        faderight: 0.0,
+        fadetop: 0.0,
+        fadebottom: 0.0,
+        blend,
+        mask_source: false,
+        mask_dest: false,
+        rot_x_deg: 0.0,
+        rot_y_deg: 0.0,
+        rot_z_deg: 0.0,
+        local_offset: [0.0, 0.0],
+        local_offset_rot_sin_cos: [0.0, 1.0],
+        texcoordvelocity: None,
+        animate: false,
+        state_delay: 0.1,
+        scale: [1.0, 1.0],
+        shadow_len: [0.0, 0.0],
+        shadow_color: [0.0, 0.0, 0.0, 0.5],
+        effect: EffectState::default(),
+    }
+}
+
+// An AFT sprite inside the capture it shows: draws last frame's copy from
+// `feedback_slot`, placed the way the sprite places a capture.
+fn song_lua_push_capture_feedback(
+    out: &mut Vec<Actor>,
+    overlay: &SongLuaOverlayActor,
+    state: SongLuaOverlayState,
+    z: i16,
+    feedback_slot: u32,
+    overlay_space_width: f32,
+    overlay_space_height: f32,
+) {
+    if !state.visible || state.diffuse[3] <= f32::EPSILON {
+        return;
+    }
+    let (offset, view_proj) =
+        song_lua_capture_placement(overlay, state, overlay_space_width, overlay_space_height);
+    if let Some(view_proj) = view_proj {
+        out.push(Actor::CameraPush { view_proj });
+    }
+    out.push(song_lua_render_target_sprite(
+        feedback_slot,
+        offset,
+        state.diffuse,
+        song_lua_overlay_blend(state.blend),
+        z,
+    ));
+    if view_proj.is_some() {
+        out.push(Actor::CameraPop);
+    }
+}
+
+fn song_lua_sprite_shader_effect(overlay: &SongLuaOverlayActor) -> Option<ShaderEffect> {
+    match &overlay.kind {
+        SongLuaOverlayKind::Sprite { shader_effect, .. } => *shader_effect,
+        _ => None,
+    }
+}
+
+// Draws `children` offscreen and composites them through `effect` at draw
+// depth `z`. Sprites, the notefield and the song background share it.
+fn song_lua_shader_effect_actor(
+    slot: u32,
+    children: Arc<[Actor]>,
+    effect: ShaderEffect,
+    z: i16,
+) -> Actor {
+    let mut actor = song_lua_render_target_capture(
+        SongLuaAftTarget::cleared(slot),
+        children,
+        [1.0; 4],
+        None,
+        Some(effect),
+    );
+    if let Actor::Frame { z: frame_z, .. } = &mut actor {
+        *frame_z = z;
+    }
+    actor
+}
+
 #[cfg(test)]
 fn song_lua_build_capture_actor(
     overlay: &SongLuaOverlayActor,
@@ -11851,7 +12301,7 @@ fn song_lua_overlay_camera_state(
         let state = overlay_states.get(current).copied()?;
         if matches!(
             overlay.kind,
-            SongLuaOverlayKind::ActorFrame | SongLuaOverlayKind::ActorFrameTexture
+            SongLuaOverlayKind::ActorFrame | SongLuaOverlayKind::ActorFrameTexture { .. }
         ) && state.fov.is_some()
         {
             return Some(state);
@@ -13081,6 +13531,7 @@ impl SongLuaAftCaptureBenchmark {
         let overlay = SongLuaOverlayActor {
             kind: SongLuaOverlayKind::AftSprite {
                 capture_name: "bench".to_string(),
+                shader_effect: None,
             },
             name: None,
             parent_index: None,
@@ -13123,6 +13574,7 @@ impl SongLuaAftCaptureBenchmark {
             100,
             screen_width(),
             screen_height(),
+            None,
             &mut self.banks[self.active_bank],
             |out| out.extend(source.iter().cloned()),
         )
@@ -13575,7 +14027,7 @@ fn build_song_lua_overlay_actor_with_scratch(
     match &overlay.kind {
         SongLuaOverlayKind::Actor => None,
         SongLuaOverlayKind::ActorFrame => None,
-        SongLuaOverlayKind::ActorFrameTexture => None,
+        SongLuaOverlayKind::ActorFrameTexture { .. } => None,
         SongLuaOverlayKind::ActorProxy { .. } => None,
         SongLuaOverlayKind::AftSprite { .. } => None,
         SongLuaOverlayKind::Sound { .. } => None,
@@ -16276,6 +16728,7 @@ fn push_song_lua_layer_actors(
                     .get(idx)
                     .copied()
                     .and_then(SongLuaOverlayIndex::get);
+                let render_target = aft_capture_scratch.aft_target(idx);
                 if let (Some(capture_index), Some(capture_scratch)) =
                     (capture_index, aft_capture_scratch.overlay(idx))
                 {
@@ -16285,6 +16738,7 @@ fn push_song_lua_layer_actors(
                         z,
                         space_width,
                         space_height,
+                        render_target,
                         capture_scratch,
                         |source| {
                             song_lua_capture_children_into(
@@ -16296,6 +16750,7 @@ fn push_song_lua_layer_actors(
                                 topology_index,
                                 asset_manager,
                                 capture_index,
+                                render_target.and_then(|target| target.feedback_slot),
                                 proxy_sources,
                                 proxy_actor_scratch.as_deref_mut(),
                                 space_width,
@@ -16342,7 +16797,18 @@ fn push_song_lua_layer_actors(
                         projected_mesh_scratch.get_mut(idx),
                     )
                 {
-                    out.extend(actors);
+                    match (
+                        song_lua_sprite_shader_effect(overlay),
+                        aft_capture_scratch.render_target_slot(idx),
+                    ) {
+                        (Some(effect), Some(slot)) => out.push(song_lua_shader_effect_actor(
+                            slot,
+                            actors.into_iter().collect(),
+                            effect,
+                            z,
+                        )),
+                        _ => out.extend(actors),
+                    }
                 }
             }
         }
@@ -16411,6 +16877,12 @@ pub fn push_actors(
     if let Some(scratch) = song_lua_proxy_actor_scratch.as_mut() {
         scratch.begin_frame();
     }
+    for scratch in std::iter::once(&mut *song_lua_aft_capture_scratch)
+        .chain(song_lua_background_aft_capture_scratch.iter_mut())
+        .chain(song_lua_foreground_aft_capture_scratch.iter_mut())
+    {
+        scratch.render_targets = visual_policy.render_targets;
+    }
     song_lua_direct_proxies.clear();
 
     let notefield_view = view.notefield;
@@ -16504,6 +16976,21 @@ pub fn push_actors(
         );
     }
     let proxy_requests = proxy_analysis.all;
+    // A Song Lua NoteField effect wins over the one the noteskin requests.
+    let field_shader_effects: [Option<ShaderEffect>; MAX_PLAYERS] = std::array::from_fn(|player| {
+        if !visual_policy.render_targets {
+            return None;
+        }
+        song_lua_visuals
+            .player_actors
+            .get(player)
+            .and_then(|actor| actor.shader_effect)
+            .or_else(|| {
+                state.noteskin_assets.noteskin[player]
+                    .as_deref()
+                    .and_then(|ns| ns.note_display_metrics.field_shader_effect)
+            })
+    });
     let direct_player_candidates: [bool; MAX_PLAYERS] = std::array::from_fn(|player| {
         (proxy_analysis.root_players[player] != 0
             || proxy_analysis.direct_aft.players[player].player)
@@ -16518,6 +17005,7 @@ pub fn push_actors(
             || proxy_analysis.direct_aft.players[player].note_field)
             && !proxy_analysis.captured.players[player].note_field
             && (!proxy_requests.players[player].player || direct_player_candidates[player])
+            && field_shader_effects[player].is_none()
     });
     let direct_judgment_candidates: [bool; MAX_PLAYERS] = std::array::from_fn(|player| {
         (proxy_analysis.root_judgments[player] != 0
@@ -16544,6 +17032,18 @@ pub fn push_actors(
         policy.background_brightness,
         policy.background_color,
     );
+    if visual_policy.render_targets
+        && let Some(effect) = song_lua_visuals.song_background.shader_effect
+        && actors.len() > underlay_start
+    {
+        let children: Arc<[Actor]> = actors.drain(underlay_start..).collect();
+        actors.push(song_lua_shader_effect_actor(
+            SONG_LUA_BACKGROUND_EFFECT_SLOT,
+            children,
+            effect,
+            -101,
+        ));
+    }
     for &layer_idx in song_lua_background_active_layers {
         let layer = &song_lua_visuals.background_visual_layers[layer_idx];
         let local_states = &song_lua_background_layer_local_state_scratch[layer_idx];
@@ -16861,6 +17361,7 @@ pub fn push_actors(
                 judgment_draw_range,
                 combo_actors,
                 combo_draw_range,
+                hud_anchors,
             } = notefield::compose_frame(
                 state,
                 state.notefield_judgment_assets(player_idx),
@@ -16885,8 +17386,10 @@ pub fn push_actors(
                     player: requests.player && !direct_player_candidates[player_idx],
                     // Whole-player captures consume the same field source, so
                     // materialize compact notes into that cold actor capture.
+                    // Field effects composite that same shared capture.
                     note_field: (requests.note_field && !direct_note_field_candidates[player_idx])
-                        || (requests.player && !direct_player_candidates[player_idx]),
+                        || (requests.player && !direct_player_candidates[player_idx])
+                        || field_shader_effects[player_idx].is_some(),
                     direct_note_field: requests.note_field
                         && direct_note_field_candidates[player_idx],
                     judgment: requests.judgment && !direct_judgment_candidates[player_idx],
@@ -16902,6 +17405,18 @@ pub fn push_actors(
                 hud_scratch,
                 hud_flat_draw_scratch,
             );
+            state.notefield_hud_anchors[player_idx].set(hud_anchors);
+            if let (Some(effect), Some([shared])) =
+                (field_shader_effects[player_idx], field_actors.as_ref())
+                && let Some(field @ Actor::SharedFrame { .. }) = field_scratch.last_mut()
+            {
+                *field = song_lua_shader_effect_actor(
+                    SONG_LUA_EFFECT_TARGET_BASE + 1 + player_idx as u32,
+                    Arc::clone(shared),
+                    effect,
+                    0,
+                );
+            }
             let player_actor = &song_lua_visuals.player_actors[player_idx];
             let player_state = song_lua_player_render_state(
                 state,
@@ -19209,6 +19724,7 @@ mod tests {
         SongLuaOverlayKind::Sprite {
             texture_path: std::path::PathBuf::from(key),
             texture_key: Arc::from(key),
+            shader_effect: None,
         }
     }
 
@@ -19217,6 +19733,7 @@ mod tests {
         SongLuaOverlayKind::Sprite {
             texture_path: path,
             texture_key,
+            shader_effect: None,
         }
     }
 
@@ -19381,6 +19898,7 @@ mod tests {
             },
             message_commands: Vec::new(),
             manual_hud_draw: false,
+            shader_effect: None,
         };
         let mut expected_cache = SongLuaMessageStateCache::default();
         let mut fast_cache = SongLuaMessageStateCache::default();
@@ -20754,7 +21272,9 @@ mod tests {
 
     fn test_capture_overlay(name: &str) -> SongLuaOverlayActor {
         SongLuaOverlayActor {
-            kind: SongLuaOverlayKind::ActorFrameTexture,
+            kind: SongLuaOverlayKind::ActorFrameTexture {
+                preserve_texture: false,
+            },
             name: Some(name.to_string()),
             parent_index: None,
             initial_state: SongLuaOverlayState::default(),
@@ -20779,6 +21299,7 @@ mod tests {
         SongLuaOverlayActor {
             kind: SongLuaOverlayKind::AftSprite {
                 capture_name: capture_name.to_string(),
+                shader_effect: None,
             },
             name: None,
             parent_index: None,
@@ -20903,6 +21424,7 @@ mod tests {
             judgments: None,
             score: None,
             ex_score: None,
+            song_vote: Default::default(),
         }
     }
 
@@ -21189,6 +21711,23 @@ mod tests {
         assert!(analysis.direct_aft.players[0].note_field);
     }
 
+    #[test]
+    fn song_lua_direct_aft_leaves_preserved_textures_to_render_targets() {
+        let mut capture = test_capture_overlay("cap");
+        capture.kind = SongLuaOverlayKind::ActorFrameTexture {
+            preserve_texture: true,
+        };
+        let overlays = vec![
+            capture,
+            test_capture_proxy_child(0, SongLuaProxyTarget::NoteField { player_index: 0 }),
+            test_aft_overlay("cap", true),
+        ];
+
+        let topology = SongLuaOverlayTopologyIndex::new(&overlays);
+
+        assert_eq!(topology.direct_aft_proxies[2], None);
+    }
+
     #[test]
     fn song_lua_direct_proxy_capacity_counts_every_root_destination() {
         let root = |target| SongLuaOverlayActor {
@@ -21665,7 +22204,7 @@ mod tests {
                 "prepared camera state diverged for overlay {overlay_index}",
             );
             let expected_target = match &overlay.kind {
-                SongLuaOverlayKind::AftSprite { capture_name } => {
+                SongLuaOverlayKind::AftSprite { capture_name, .. } => {
                     song_lua_overlay_capture_index_by_name(&overlays, capture_name)
                 }
                 _ => None,
@@ -22574,6 +23113,7 @@ mod tests {
             7,
             screen_width(),
             screen_height(),
+            None,
             &mut scratch,
             |children| children.push(source),
         )
@@ -22651,6 +23191,278 @@ mod tests {
         assert_eq!(scratch.stats().growths, 0);
     }
 
+    #[test]
+    fn render_target_aft_capture_samples_its_own_slot() {
+        let overlay = test_aft_overlay("CaptureAFT", true);
+        let state = SongLuaOverlayState {
+            x: 0.5 * screen_width(),
+            y: 0.5 * screen_height(),
+            diffuse: [1.0, 1.0, 1.0, 0.5],
+            blend: SongLuaOverlayBlendMode::Add,
+            ..SongLuaOverlayState::default()
+        };
+        let source = Actor::Frame {
+            align: [0.0, 0.0],
+            offset: [0.0, 0.0],
+            size: [SizeSpec::Fill, SizeSpec::Fill],
+            children: Vec::new(),
+            background: None,
+            z: 0,
+        };
+        let mut aft_scratch =
+            SongLuaAftCaptureScratch::new(&[], &SongLuaOverlayTopologyIndex::default())
+                .with_render_target_layer(2);
+        assert_eq!(aft_scratch.render_target_slot(3), None);
+        aft_scratch.render_targets = true;
+        let slot = aft_scratch
+            .render_target_slot(3)
+            .expect("render-target slot");
+        assert_eq!(slot, 2 * SONG_LUA_AFT_TARGET_SLOTS + 3);
+
+        let mut scratch = SharedActorFrameScratch::with_capacity(1);
+        let actor = song_lua_build_shared_capture(
+            &overlay,
+            state,
+            7,
+            screen_width(),
+            screen_height(),
+            Some(SongLuaAftTarget::cleared(slot)),
+            &mut scratch,
+            |children| children.push(source),
+        )
+        .expect("render-target AFT capture");
+
+        let Actor::Frame { children, .. } = actor else {
+            panic!("expected render-target capture frame");
+        };
+        let [
+            Actor::RenderTarget {
+                slot: target_slot,
+                clear: true,
+                children: target_children,
+                ..
+            },
+            Actor::Sprite {
+                source: SpriteSource::TextureStaticHandle { handle, .. },
+                tint,
+                blend,
+                ..
+            },
+        ] = children.as_slice()
+        else {
+            panic!("expected render target followed by its sprite");
+        };
+        assert_eq!(*target_slot, slot);
+        assert_eq!(target_children.len(), 1);
+        assert_eq!(*handle, render_target_handle(slot));
+        assert_eq!(*tint, state.diffuse);
+        assert_eq!(*blend, BlendMode::Add);
+    }
+
+    #[test]
+    fn render_target_aft_capture_composites_requested_shader_effect() {
+        let effect = ShaderEffect::Blur { radius: 3.0 };
+        let mut overlay = test_aft_overlay("CaptureAFT", true);
+        overlay.kind = SongLuaOverlayKind::AftSprite {
+            capture_name: "CaptureAFT".to_string(),
+            shader_effect: Some(effect),
+        };
+        let state = SongLuaOverlayState {
+            x: 0.5 * screen_width(),
+            y: 0.5 * screen_height(),
+            diffuse: [1.0, 0.5, 1.0, 0.75],
+            ..SongLuaOverlayState::default()
+        };
+        let source = Actor::Frame {
+            align: [0.0, 0.0],
+            offset: [0.0, 0.0],
+            size: [SizeSpec::Fill, SizeSpec::Fill],
+            children: Vec::new(),
+            background: None,
+            z: 0,
+        };
+
+        let mut scratch = SharedActorFrameScratch::with_capacity(1);
+        let actor = song_lua_build_shared_capture(
+            &overlay,
+            state,
+            0,
+            screen_width(),
+            screen_height(),
+            Some(SongLuaAftTarget::cleared(4)),
+            &mut scratch,
+            |children| children.push(source),
+        )
+        .expect("render-target AFT capture");
+
+        let Actor::Frame { children, .. } = actor else {
+            panic!("expected render-target capture frame");
+        };
+        let [
+            Actor::RenderTarget { slot: 4, .. },
+            Actor::ShaderEffect {
+                slot: 4,
+                effect: composited,
+                tint,
+                blend: BlendMode::Alpha,
+                ..
+            },
+        ] = children.as_slice()
+        else {
+            panic!("expected render target followed by its effect composite");
+        };
+        assert_eq!(*composited, effect);
+        assert_eq!(*tint, state.diffuse);
+    }
+
+    #[test]
+    fn preserved_feedback_aft_copies_last_frame_before_drawing_over_it() {
+        let mut capture = test_capture_overlay("TrailAFT");
+        capture.kind = SongLuaOverlayKind::ActorFrameTexture {
+            preserve_texture: true,
+        };
+        let mut echo = test_direct_aft_overlay("TrailAFT");
+        echo.parent_index = Some(0);
+        let overlays = vec![
+            capture,
+            echo,
+            test_capture_overlay("PlainAFT"),
+            test_direct_aft_overlay("TrailAFT"),
+            test_direct_aft_overlay("PlainAFT"),
+        ];
+        let topology = SongLuaOverlayTopologyIndex::new(&overlays);
+        let mut aft_scratch = SongLuaAftCaptureScratch::new(&overlays, &topology);
+        assert_eq!(aft_scratch.aft_target(3), None);
+        aft_scratch.render_targets = true;
+        assert_eq!(
+            aft_scratch.aft_target(4),
+            Some(SongLuaAftTarget::cleared(4))
+        );
+        let target = aft_scratch.aft_target(3).expect("trail render target");
+        let feedback_slot = 3 + SONG_LUA_AFT_FEEDBACK_SLOT_OFFSET;
+        assert_eq!(
+            target,
+            SongLuaAftTarget {
+                slot: 3,
+                clear: false,
+                feedback_slot: Some(feedback_slot),
+            }
+        );
+
+        let mut scratch = SharedActorFrameScratch::with_capacity(2);
+        let actor = song_lua_build_shared_capture(
+            &overlays[3],
+            overlays[3].initial_state,
+            0,
+            screen_width(),
+            screen_height(),
+            Some(target),
+            &mut scratch,
+            |children| {
+                song_lua_push_capture_feedback(
+                    children,
+                    &overlays[1],
+                    overlays[1].initial_state,
+                    0,
+                    feedback_slot,
+                    screen_width(),
+                    screen_height(),
+                );
+            },
+        )
+        .expect("trail capture");
+
+        let Actor::Frame { children, .. } = actor else {
+            panic!("expected render-target capture frame");
+        };
+        let [
+            Actor::RenderTarget {
+                slot: copy_slot,
+                clear: true,
+                children: copy,
+                ..
+            },
+            Actor::RenderTarget {
+                slot: 3,
+                clear: false,
+                children: drawn,
+                ..
+            },
+            Actor::Sprite { .. },
+        ] = children.as_slice()
+        else {
+            panic!("expected the feedback copy, the preserved target and its sprite");
+        };
+        assert_eq!(*copy_slot, feedback_slot);
+        let [
+            Actor::Sprite {
+                source: SpriteSource::TextureStaticHandle { handle, .. },
+                ..
+            },
+        ] = &copy[..]
+        else {
+            panic!("expected the copy to show last frame's target");
+        };
+        assert_eq!(*handle, render_target_handle(3));
+        let [
+            Actor::Frame {
+                children: drawn, ..
+            },
+        ] = &drawn[..]
+        else {
+            panic!("expected the shared capture frame");
+        };
+        let [
+            Actor::Sprite {
+                source: SpriteSource::TextureStaticHandle { handle, .. },
+                ..
+            },
+        ] = drawn.as_slice()
+        else {
+            panic!("expected the echo sprite to sample the feedback copy");
+        };
+        assert_eq!(*handle, render_target_handle(feedback_slot));
+    }
+
+    #[test]
+    fn shader_effect_actor_composites_children_at_requested_depth() {
+        let effect = ShaderEffect::ColorGrade {
+            saturation: 0.0,
+            contrast: 1.0,
+            brightness: 0.0,
+        };
+        let children: Arc<[Actor]> = Arc::from(vec![Actor::Frame {
+            align: [0.0, 0.0],
+            offset: [0.0, 0.0],
+            size: [SizeSpec::Fill, SizeSpec::Fill],
+            children: Vec::new(),
+            background: None,
+            z: 0,
+        }]);
+
+        let actor =
+            song_lua_shader_effect_actor(SONG_LUA_BACKGROUND_EFFECT_SLOT, children, effect, -101);
+
+        let Actor::Frame { children, z, .. } = actor else {
+            panic!("expected render-target capture frame");
+        };
+        assert_eq!(z, -101);
+        let [
+            Actor::RenderTarget { slot, .. },
+            Actor::ShaderEffect {
+                slot: composite_slot,
+                effect: composited,
+                ..
+            },
+        ] = children.as_slice()
+        else {
+            panic!("expected render target followed by its effect composite");
+        };
+        assert_eq!(*slot, SONG_LUA_BACKGROUND_EFFECT_SLOT);
+        assert_eq!(*composite_slot, SONG_LUA_BACKGROUND_EFFECT_SLOT);
+        assert_eq!(*composited, effect);
+    }
+
     #[test]
     fn aft_capture_scratch_prewarms_both_frame_banks() {
         let overlays = vec![
@@ -23546,6 +24358,7 @@ mod tests {
             321,
             screen_width(),
             screen_height(),
+            None,
             &mut aft_scratch,
             |out| out.push(player_actor),
         )
@@ -23696,6 +24509,7 @@ mod tests {
                 321,
                 screen_width(),
                 screen_height(),
+                None,
                 &mut capture_scratch,
                 |out| out.push(proxy_actor),
             )
@@ -26704,6 +27518,7 @@ mod tests {
             foreground_visual_layers: vec![layer(10.0, overlay())],
             player_actors: std::array::from_fn(|_| SongLuaCapturedActor::default()),
             player_events: std::array::from_fn(|_| Vec::new()),
+            song_background: SongLuaCapturedActor::default(),
             song_foreground: SongLuaCapturedActor::default(),
             song_foreground_events: Vec::new(),
             hidden_players: [false; MAX_PLAYERS],
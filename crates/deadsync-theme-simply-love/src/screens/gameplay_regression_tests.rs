@@ -250,6 +250,7 @@ mod tests {
             session,
             config,
             pack_sync_pref,
+            0.0,
             mini_indicator_data,
             noteskin_data,
             song_lua_data,
@@ -3338,7 +3339,7 @@ return Def.ActorFrame{
                         assert!(visuals.overlays.iter().any(|overlay| {
                             matches!(
                                 &overlay.kind,
-                                deadsync_assets::song_lua::SongLuaOverlayKind::ActorFrameTexture
+                                deadsync_assets::song_lua::SongLuaOverlayKind::ActorFrameTexture { .. }
                             )
                         }));
                         assert!(visuals.overlays.iter().any(|overlay| {
@@ -3357,6 +3358,7 @@ return Def.ActorFrame{
                             .find_map(|overlay| match &overlay.kind {
                                 deadsync_assets::song_lua::SongLuaOverlayKind::AftSprite {
                                     capture_name,
+                                    ..
                                 } => Some(capture_name),
                                 _ => None,
                             })
@@ -3365,7 +3367,7 @@ return Def.ActorFrame{
                         assert!(visuals.overlays.iter().any(|overlay| {
                             matches!(
                                 overlay.kind,
-                                deadsync_assets::song_lua::SongLuaOverlayKind::ActorFrameTexture
+                                deadsync_assets::song_lua::SongLuaOverlayKind::ActorFrameTexture { .. }
                             ) && overlay.name.as_deref() == Some(capture_name)
                         }));
 
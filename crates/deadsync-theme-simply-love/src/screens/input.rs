@@ -5,7 +5,7 @@ use deadlib_present::color;
 use deadsync_config::prelude::GameFlag;
 use deadsync_core::input::InputSource;
 use deadsync_input::RawKeyboardEvent;
-use deadsync_input::{InputEvent, PadEvent, VirtualAction, with_keymap};
+use deadsync_input::{InputEvent, PadEvent, VirtualAction, with_active_keymap};
 use deadsync_profile::PlayerSide;
 use std::time::{Duration, Instant};
 
@@ -323,7 +323,7 @@ pub fn handle_raw_key_event(state: &mut State, key_event: &RawKeyboardEvent) ->
     if key_event.pressed && key_event.repeat {
         return ThemeEffect::None;
     }
-    let is_back = with_keymap(|km| {
+    let is_back = with_active_keymap(|km| {
         km.raw_key_event_has_action(key_event, |action| {
             matches!(action, VirtualAction::p1_back | VirtualAction::p2_back)
         })
@@ -22,6 +22,13 @@ use deadsync_profile::favorites_view::unicode_case_insensitive_cmp;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+#[path = "manage_local_profiles/progress.rs"]
+mod progress;
+#[path = "manage_local_profiles/timing.rs"]
+mod timing;
+#[path = "manage_local_profiles/workouts.rs"]
+mod workouts;
+
 /* ---------------------------- transitions ---------------------------- */
 const TRANSITION_IN_DURATION: f32 = 0.4;
 const TRANSITION_OUT_DURATION: f32 = 0.4;
@@ -124,6 +131,9 @@ enum ProfileMenuAction {
     SetP2,
     LinkArrowCloud,
     LinkGrooveStats,
+    ViewProgress,
+    ViewTiming,
+    ViewWorkouts,
     Rename,
     Delete,
 }
@@ -134,16 +144,22 @@ fn profile_menu_action_label(action: ProfileMenuAction) -> Arc<str> {
         ProfileMenuAction::SetP2 => tr("Profiles", "SetP2"),
         ProfileMenuAction::LinkArrowCloud => tr("Profiles", "LinkArrowCloud"),
         ProfileMenuAction::LinkGrooveStats => tr("Profiles", "LinkGrooveStats"),
+        ProfileMenuAction::ViewProgress => tr("Profiles", "ViewProgress"),
+        ProfileMenuAction::ViewTiming => tr("Profiles", "ViewTiming"),
+        ProfileMenuAction::ViewWorkouts => tr("Profiles", "ViewWorkouts"),
         ProfileMenuAction::Rename => tr("Profiles", "Rename"),
         ProfileMenuAction::Delete => tr("Profiles", "Delete"),
     }
 }
 
-const PROFILE_MENU_ACTIONS: [ProfileMenuAction; 6] = [
+const PROFILE_MENU_ACTIONS: [ProfileMenuAction; 9] = [
     ProfileMenuAction::SetP1,
     ProfileMenuAction::SetP2,
     ProfileMenuAction::LinkArrowCloud,
     ProfileMenuAction::LinkGrooveStats,
+    ProfileMenuAction::ViewProgress,
+    ProfileMenuAction::ViewTiming,
+    ProfileMenuAction::ViewWorkouts,
     ProfileMenuAction::Rename,
     ProfileMenuAction::Delete,
 ];
@@ -299,6 +315,9 @@ pub struct State {
     import_job: Option<ImportJob>,
     import_browse_pending: bool,
     import_message: Option<ImportMessageState>,
+    progress: Option<progress::ProgressState>,
+    timing: Option<timing::TimingState>,
+    workouts: Option<workouts::WorkoutsState>,
     pending_effects: Vec<ThemeEffect>,
     menu_lr_chord: screen_input::MenuLrChordTracker,
     menu_lr_undo: i8,
@@ -328,6 +347,9 @@ pub fn init(view: ManageLocalProfilesView) -> State {
         import_job: None,
         import_browse_pending: false,
         import_message: None,
+        progress: None,
+        timing: None,
+        workouts: None,
         pending_effects: Vec::new(),
         menu_lr_chord: screen_input::MenuLrChordTracker::default(),
         menu_lr_undo: 0,
@@ -658,6 +680,13 @@ fn confirm_profile_menu(state: &mut State) -> ThemeEffect {
                 )),
             )
         }
+        ProfileMenuAction::ViewProgress => {
+            progress::begin_progress(state, &menu.id, &menu.display_name)
+        }
+        ProfileMenuAction::ViewTiming => timing::begin_timing(state, &menu.id, &menu.display_name),
+        ProfileMenuAction::ViewWorkouts => {
+            workouts::begin_workouts(state, &menu.id, &menu.display_name)
+        }
         ProfileMenuAction::Rename => {
             state.profile_menu = None;
             begin_name_entry_rename(state, &menu.id, &menu.display_name);
@@ -749,9 +778,30 @@ pub fn apply_local_profile_event(state: &mut State, event: crate::SimplyLoveLoca
                 }
             }
         },
+        crate::SimplyLoveLocalProfileEvent::HistoryExported { profile_id, result } => {
+            progress::apply_export(state, &profile_id, result);
+        }
     }
 }
 
+pub fn apply_profile_history_result(
+    state: &mut State,
+    result: crate::SimplyLoveProfileHistoryResult,
+) {
+    progress::apply_history(state, &result.profile_id, result.history);
+}
+
+pub fn apply_offset_drift_result(state: &mut State, result: crate::SimplyLoveOffsetDriftResult) {
+    timing::apply_reports(state, &result.profile_id, result.reports);
+}
+
+pub fn apply_workout_summary_result(
+    state: &mut State,
+    result: crate::SimplyLoveWorkoutSummaryResult,
+) {
+    workouts::apply_weeks(state, &result.profile_id, result.weeks);
+}
+
 fn set_name_entry_error(state: &mut State, key: &str) {
     if let Some(entry) = state.name_entry.as_mut() {
         entry.error = Some(tr("Profiles", key));
@@ -1329,6 +1379,15 @@ pub fn handle_input(state: &mut State, ev: &InputEvent) -> ThemeEffect {
             _ => {}
         }
         if let Some((_, nav)) = three_key_action {
+            if state.progress.is_some() {
+                return progress::handle_progress_three_key(state, nav);
+            }
+            if state.timing.is_some() {
+                return timing::handle_timing_three_key(state, nav);
+            }
+            if state.workouts.is_some() {
+                return workouts::handle_workouts_three_key(state, nav);
+            }
             if state.import_picker.is_some() {
                 return match nav {
                     screen_input::ThreeKeyMenuAction::Prev => {
@@ -1417,6 +1476,15 @@ pub fn handle_input(state: &mut State, ev: &InputEvent) -> ThemeEffect {
             };
         }
     }
+    if state.progress.is_some() {
+        return progress::handle_progress_input(state, ev);
+    }
+    if state.timing.is_some() {
+        return timing::handle_timing_input(state, ev);
+    }
+    if state.workouts.is_some() {
+        return workouts::handle_workouts_input(state, ev);
+    }
     if state.import_picker.is_some() {
         return handle_import_picker_input(state, ev);
     }
@@ -2816,6 +2884,9 @@ pub fn push_actors(
     push_import_picker_overlay(actors, state, asset_manager, header_font);
     push_import_progress_overlay(actors, state, header_font);
     push_import_message_overlay(actors, state, asset_manager, header_font);
+    progress::push_progress_overlay(actors, state, header_font);
+    timing::push_timing_overlay(actors, state, header_font);
+    workouts::push_workouts_overlay(actors, state, header_font);
 
     for actor in &mut actors[ui_start..] {
         actor.mul_alpha(alpha_multiplier);
@@ -3168,6 +3239,135 @@ mod tests {
         assert!(state.profile_menu.is_none());
     }
 
+    #[test]
+    fn progress_history_applies_only_to_the_waiting_profile() {
+        let mut state = state_with_profile_row();
+        begin_profile_menu(&mut state, "test-profile", "Test Profile");
+        state.profile_menu.as_mut().unwrap().selected_action = PROFILE_MENU_ACTIONS
+            .iter()
+            .position(|action| *action == ProfileMenuAction::ViewProgress)
+            .unwrap();
+
+        let ThemeEffect::Batch(effects) = confirm_profile_menu(&mut state) else {
+            panic!("expected audio and history request batch");
+        };
+        assert!(matches!(
+            &effects[1],
+            ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Profile(
+                crate::SimplyLoveProfileRequest::LoadProfileHistory { profile_id }
+            )) if profile_id == "test-profile"
+        ));
+        assert!(state.profile_menu.is_none());
+
+        let history = |profile_id: &str| crate::SimplyLoveProfileHistoryResult {
+            profile_id: profile_id.to_owned(),
+            history: Ok(deadsync_score::ProfileHistory::default()),
+        };
+        apply_profile_history_result(&mut state, history("other-profile"));
+        assert!(matches!(
+            state.progress.as_ref().map(|progress| &progress.view),
+            Some(progress::ProgressView::Loading)
+        ));
+        apply_profile_history_result(&mut state, history("test-profile"));
+        assert!(matches!(
+            state.progress.as_ref().map(|progress| &progress.view),
+            Some(progress::ProgressView::Ready { plays: 0, .. })
+        ));
+
+        assert!(matches!(
+            press(&mut state, VirtualAction::p1_back),
+            ThemeEffect::None
+        ));
+        assert!(state.progress.is_none());
+    }
+
+    #[test]
+    fn timing_reports_apply_only_to_the_waiting_profile() {
+        let mut state = state_with_profile_row();
+        begin_profile_menu(&mut state, "test-profile", "Test Profile");
+        state.profile_menu.as_mut().unwrap().selected_action = PROFILE_MENU_ACTIONS
+            .iter()
+            .position(|action| *action == ProfileMenuAction::ViewTiming)
+            .unwrap();
+
+        let ThemeEffect::Batch(effects) = confirm_profile_menu(&mut state) else {
+            panic!("expected audio and offset drift request batch");
+        };
+        assert!(matches!(
+            &effects[1],
+            ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Profile(
+                crate::SimplyLoveProfileRequest::LoadOffsetDrift { profile_id }
+            )) if profile_id == "test-profile"
+        ));
+
+        let reports = |profile_id: &str| crate::SimplyLoveOffsetDriftResult {
+            profile_id: profile_id.to_owned(),
+            reports: Ok(crate::SimplyLoveOffsetDriftReports::default()),
+        };
+        apply_offset_drift_result(&mut state, reports("other-profile"));
+        assert!(matches!(
+            state.timing.as_ref().map(|timing| &timing.view),
+            Some(timing::TimingView::Loading)
+        ));
+        apply_offset_drift_result(&mut state, reports("test-profile"));
+        assert!(matches!(
+            state.timing.as_ref().map(|timing| &timing.view),
+            Some(timing::TimingView::Ready(_))
+        ));
+
+        press(&mut state, VirtualAction::p1_right);
+        assert_eq!(
+            state.timing.as_ref().map(|timing| timing.scope),
+            Some(timing::TimingScope::Machine)
+        );
+        assert!(matches!(
+            press(&mut state, VirtualAction::p1_back),
+            ThemeEffect::None
+        ));
+        assert!(state.timing.is_none());
+    }
+
+    #[test]
+    fn workout_summary_applies_only_to_the_waiting_profile() {
+        let mut state = state_with_profile_row();
+        begin_profile_menu(&mut state, "test-profile", "Test Profile");
+        state.profile_menu.as_mut().unwrap().selected_action = PROFILE_MENU_ACTIONS
+            .iter()
+            .position(|action| *action == ProfileMenuAction::ViewWorkouts)
+            .unwrap();
+
+        let ThemeEffect::Batch(effects) = confirm_profile_menu(&mut state) else {
+            panic!("expected audio and workout summary request batch");
+        };
+        assert!(matches!(
+            &effects[1],
+            ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Profile(
+                crate::SimplyLoveProfileRequest::LoadWorkoutSummary { profile_id }
+            )) if profile_id == "test-profile"
+        ));
+
+        let summary = |profile_id: &str| crate::SimplyLoveWorkoutSummaryResult {
+            profile_id: profile_id.to_owned(),
+            weeks: Ok(Vec::new()),
+        };
+        apply_workout_summary_result(&mut state, summary("other-profile"));
+        assert!(matches!(
+            state.workouts.as_ref().map(|workouts| &workouts.view),
+            Some(workouts::WorkoutsView::Loading)
+        ));
+        apply_workout_summary_result(&mut state, summary("test-profile"));
+        assert!(matches!(
+            state.workouts.as_ref().map(|workouts| &workouts.view),
+            Some(workouts::WorkoutsView::Ready(_))
+        ));
+
+        assert!(matches!(
+            press(&mut state, VirtualAction::p1_back),
+            ThemeEffect::None
+        ));
+        assert!(state.workouts.is_none());
+    }
+
     #[test]
     fn browse_requests_shell_picker_and_keeps_modal_open() {
         let mut state = init(ManageLocalProfilesView::default());
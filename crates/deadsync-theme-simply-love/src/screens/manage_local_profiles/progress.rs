@@ -0,0 +1,625 @@
+use super::*;
+use deadsync_score::{HistoryBucket, HistoryPeriod, ProfileHistory, profile_history_buckets};
+use std::path::PathBuf;
+
+/// Buckets drawn in each graph, newest on the right.
+const PROGRESS_GRAPH_BUCKETS: usize = 12;
+const PROGRESS_BOX_W: f32 = 760.0;
+const PROGRESS_BOX_H: f32 = 430.0;
+const PROGRESS_HEADER_H: f32 = 58.0;
+const PROGRESS_GRAPH_H: f32 = 110.0;
+const PROGRESS_GAP: f32 = 20.0;
+const PROGRESS_MAX_METER_ROWS: usize = 6;
+/// EX bars start here so day-to-day changes stay visible.
+const PROGRESS_EX_FLOOR: f32 = 50.0;
+/// Smallest half-range of the offset graph, in ms.
+const PROGRESS_OFFSET_MIN_RANGE_MS: f32 = 10.0;
+
+pub(super) struct ProgressState {
+    pub(super) profile_id: String,
+    pub(super) display_name: String,
+    pub(super) period: HistoryPeriod,
+    pub(super) view: ProgressView,
+    /// Result of the last export, shown above the footer.
+    pub(super) notice: Option<Arc<str>>,
+}
+
+pub(super) enum ProgressView {
+    Loading,
+    Ready {
+        plays: usize,
+        daily: Vec<HistoryBucket>,
+        weekly: Vec<HistoryBucket>,
+    },
+    Failed(String),
+}
+
+impl ProgressState {
+    fn buckets(&self) -> &[HistoryBucket] {
+        match &self.view {
+            ProgressView::Ready { daily, weekly, .. } => match self.period {
+                HistoryPeriod::Day => daily,
+                HistoryPeriod::Week => weekly,
+            },
+            _ => &[],
+        }
+    }
+
+    fn window(&self) -> &[HistoryBucket] {
+        let buckets = self.buckets();
+        &buckets[buckets.len().saturating_sub(PROGRESS_GRAPH_BUCKETS)..]
+    }
+}
+
+pub(super) fn begin_progress(state: &mut State, id: &str, display_name: &str) -> ThemeEffect {
+    reset_nav_hold(state);
+    state.profile_menu = None;
+    state.progress = Some(ProgressState {
+        profile_id: id.to_string(),
+        display_name: display_name.to_string(),
+        period: HistoryPeriod::Day,
+        view: ProgressView::Loading,
+        notice: None,
+    });
+    crate::effects::sfx_then(
+        "assets/sounds/start.ogg",
+        ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Profile(
+            crate::SimplyLoveProfileRequest::LoadProfileHistory {
+                profile_id: id.to_string(),
+            },
+        )),
+    )
+}
+
+pub(super) fn apply_history(
+    state: &mut State,
+    profile_id: &str,
+    history: Result<ProfileHistory, String>,
+) {
+    let Some(progress) = state.progress.as_mut() else {
+        return;
+    };
+    if progress.profile_id != profile_id || !matches!(progress.view, ProgressView::Loading) {
+        return;
+    }
+    progress.view = match history {
+        Ok(history) => ProgressView::Ready {
+            plays: history.plays.len(),
+            daily: profile_history_buckets(&history, HistoryPeriod::Day, &chrono::Local),
+            weekly: profile_history_buckets(&history, HistoryPeriod::Week, &chrono::Local),
+        },
+        Err(error) => ProgressView::Failed(error),
+    };
+}
+
+pub(super) fn apply_export(state: &mut State, profile_id: &str, result: Result<PathBuf, ()>) {
+    let Some(progress) = state.progress.as_mut() else {
+        return;
+    };
+    if progress.profile_id != profile_id {
+        return;
+    }
+    progress.notice = Some(match result {
+        Ok(path) => tr_fmt(
+            "Profiles",
+            "ProgressExported",
+            &[("path", &path.display().to_string())],
+        ),
+        Err(()) => tr("Profiles", "ProgressExportFailed"),
+    });
+}
+
+fn close_progress(state: &mut State) -> ThemeEffect {
+    let loading = state
+        .progress
+        .take()
+        .is_some_and(|progress| matches!(progress.view, ProgressView::Loading));
+    reset_nav_hold(state);
+    if loading {
+        ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Profile(
+            crate::SimplyLoveProfileRequest::CancelProfileHistory,
+        ))
+    } else {
+        ThemeEffect::None
+    }
+}
+
+fn toggle_period(state: &mut State) -> ThemeEffect {
+    let Some(progress) = state.progress.as_mut() else {
+        return ThemeEffect::None;
+    };
+    progress.period = match progress.period {
+        HistoryPeriod::Day => HistoryPeriod::Week,
+        HistoryPeriod::Week => HistoryPeriod::Day,
+    };
+    crate::effects::sfx("assets/sounds/change.ogg")
+}
+
+fn export_progress(state: &mut State) -> ThemeEffect {
+    let Some(progress) = state.progress.as_mut() else {
+        return ThemeEffect::None;
+    };
+    if !matches!(progress.view, ProgressView::Ready { .. }) {
+        return ThemeEffect::None;
+    }
+    progress.notice = None;
+    crate::effects::sfx_then(
+        "assets/sounds/start.ogg",
+        ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Profile(
+            crate::SimplyLoveProfileRequest::ExportProfileHistory {
+                profile_id: progress.profile_id.clone(),
+                display_name: progress.display_name.clone(),
+            },
+        )),
+    )
+}
+
+pub(super) fn handle_progress_three_key(
+    state: &mut State,
+    nav: screen_input::ThreeKeyMenuAction,
+) -> ThemeEffect {
+    match nav {
+        screen_input::ThreeKeyMenuAction::Prev | screen_input::ThreeKeyMenuAction::Next => {
+            toggle_period(state)
+        }
+        screen_input::ThreeKeyMenuAction::Confirm => export_progress(state),
+        screen_input::ThreeKeyMenuAction::Cancel => close_progress(state),
+    }
+}
+
+pub(super) fn handle_progress_input(state: &mut State, ev: &InputEvent) -> ThemeEffect {
+    if !ev.pressed {
+        return ThemeEffect::None;
+    }
+    match ev.action {
+        VirtualAction::p1_back | VirtualAction::p2_back => close_progress(state),
+        VirtualAction::p1_left
+        | VirtualAction::p1_menu_left
+        | VirtualAction::p2_left
+        | VirtualAction::p2_menu_left
+        | VirtualAction::p1_right
+        | VirtualAction::p1_menu_right
+        | VirtualAction::p2_right
+        | VirtualAction::p2_menu_right => toggle_period(state),
+        VirtualAction::p1_start | VirtualAction::p2_start => export_progress(state),
+        _ => ThemeEffect::None,
+    }
+}
+
+/// Mean EX per meter over `buckets`, highest meter first.
+fn window_ex_by_meter(buckets: &[HistoryBucket]) -> Vec<(u32, u32, f64)> {
+    let mut sums: Vec<(u32, u32, f64)> = Vec::new();
+    for meter in buckets.iter().flat_map(|bucket| &bucket.ex_by_meter) {
+        let weighted = meter.mean_ex_percent * f64::from(meter.plays);
+        match sums.iter_mut().find(|(m, _, _)| *m == meter.meter) {
+            Some((_, plays, ex_sum)) => {
+                *plays += meter.plays;
+                *ex_sum += weighted;
+            }
+            None => sums.push((meter.meter, meter.plays, weighted)),
+        }
+    }
+    sums.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+    sums.into_iter()
+        .map(|(meter, plays, ex_sum)| (meter, plays, ex_sum / f64::from(plays.max(1))))
+        .collect()
+}
+
+fn format_play_time(seconds: f64) -> String {
+    let minutes = (seconds / 60.0).round() as u64;
+    if minutes < 60 {
+        format!("{minutes}m")
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+fn window_summary(buckets: &[HistoryBucket]) -> String {
+    let plays: u32 = buckets.iter().map(|bucket| bucket.plays).sum();
+    let steps: u64 = buckets.iter().map(|bucket| bucket.steps_hit).sum();
+    let seconds: f64 = buckets.iter().map(|bucket| bucket.play_seconds).sum();
+    let calories: f64 = buckets.iter().map(|bucket| bucket.calories).sum();
+    let pbs: u32 = buckets.iter().map(|bucket| bucket.personal_bests).sum();
+    tr_fmt(
+        "Profiles",
+        "ProgressSummary",
+        &[
+            ("plays", &fmt_count(plays as usize)),
+            ("steps", &fmt_count(steps as usize)),
+            ("time", &format_play_time(seconds)),
+            ("calories", &format!("{calories:.0}")),
+            ("pbs", &pbs.to_string()),
+        ],
+    )
+    .to_string()
+}
+
+fn push_graph_frame(ui: &mut Vec<Actor>, x: f32, y: f32, w: f32, h: f32, title: Arc<str>) {
+    ui.push(act!(quad:
+        align(0.0, 0.0):
+        xy(x, y):
+        zoomto(w, h):
+        diffuse(1.0, 1.0, 1.0, 0.06):
+        z(1002)
+    ));
+    ui.push(act!(text:
+        align(0.0, 1.0):
+        xy(x, y - 2.0):
+        font("miso"):
+        zoom(0.7):
+        maxwidth(w):
+        settext(title):
+        diffuse(0.78, 0.78, 0.78, 1.0):
+        z(1003):
+        horizalign(left)
+    ));
+}
+
+fn push_date_labels(ui: &mut Vec<Actor>, buckets: &[HistoryBucket], x: f32, y: f32, w: f32) {
+    let (Some(first), Some(last)) = (buckets.first(), buckets.last()) else {
+        return;
+    };
+    for (text, ax, px) in [
+        (first.start.format("%m-%d").to_string(), 0.0, x),
+        (last.start.format("%m-%d").to_string(), 1.0, x + w),
+    ] {
+        ui.push(act!(text:
+            align(ax, 0.0):
+            xy(px, y + 2.0):
+            font("miso"):
+            zoom(0.6):
+            settext(text):
+            diffuse(0.62, 0.62, 0.62, 1.0):
+            z(1003)
+        ));
+    }
+}
+
+/// Bars scaled between `floor` and `top`, one slot per bucket.
+#[allow(clippy::too_many_arguments)]
+fn push_bars(
+    ui: &mut Vec<Actor>,
+    values: &[f32],
+    floor: f32,
+    top: f32,
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+    rgba: [f32; 4],
+) {
+    let slot_w = w / PROGRESS_GRAPH_BUCKETS as f32;
+    let range = (top - floor).max(f32::EPSILON);
+    let first_slot = PROGRESS_GRAPH_BUCKETS.saturating_sub(values.len());
+    for (i, value) in values.iter().enumerate() {
+        let bar_h = ((value - floor) / range).clamp(0.0, 1.0) * h;
+        if bar_h <= 0.0 {
+            continue;
+        }
+        let bar_x = ((first_slot + i) as f32).mul_add(slot_w, x + slot_w * 0.15);
+        ui.push(act!(quad:
+            align(0.0, 1.0):
+            xy(bar_x, y + h):
+            zoomto(slot_w * 0.7, bar_h):
+            diffuse(rgba[0], rgba[1], rgba[2], rgba[3]):
+            z(1003)
+        ));
+    }
+}
+
+/// Mean offset markers with a ±1 standard deviation bar around a zero line.
+fn push_offsets(ui: &mut Vec<Actor>, buckets: &[HistoryBucket], x: f32, y: f32, w: f32, h: f32) {
+    let range = buckets
+        .iter()
+        .filter_map(|bucket| {
+            let mean = bucket.offset_mean_ms?;
+            Some(mean.abs() + bucket.offset_stddev_ms.unwrap_or(0.0))
+        })
+        .fold(PROGRESS_OFFSET_MIN_RANGE_MS, f32::max);
+    let mid_y = y + h * 0.5;
+    let px_per_ms = h * 0.5 / range;
+    ui.push(act!(quad:
+        align(0.0, 0.5):
+        xy(x, mid_y):
+        zoomto(w, 1.0):
+        diffuse(1.0, 1.0, 1.0, 0.3):
+        z(1003)
+    ));
+    let slot_w = w / PROGRESS_GRAPH_BUCKETS as f32;
+    let first_slot = PROGRESS_GRAPH_BUCKETS.saturating_sub(buckets.len());
+    for (i, bucket) in buckets.iter().enumerate() {
+        let Some(mean) = bucket.offset_mean_ms else {
+            continue;
+        };
+        let cx = ((first_slot + i) as f32 + 0.5).mul_add(slot_w, x);
+        // Late offsets are positive and drawn below the line, like the
+        // evaluation scatter plot.
+        let cy = mean.mul_add(px_per_ms, mid_y);
+        let spread = bucket.offset_stddev_ms.unwrap_or(0.0) * px_per_ms;
+        ui.push(act!(quad:
+            align(0.5, 0.5):
+            xy(cx, cy):
+            zoomto(2.0, (spread * 2.0).max(1.0)):
+            diffuse(0.55, 0.75, 1.0, 0.6):
+            z(1003)
+        ));
+        ui.push(act!(quad:
+            align(0.5, 0.5):
+            xy(cx, cy):
+            zoomto(slot_w * 0.5, 3.0):
+            diffuse(0.55, 0.75, 1.0, 1.0):
+            z(1004)
+        ));
+    }
+    for (text, ay, py) in [
+        (format!("-{range:.0}ms"), 0.0, y),
+        (format!("+{range:.0}ms"), 1.0, y + h),
+    ] {
+        ui.push(act!(text:
+            align(1.0, ay):
+            xy(x - 4.0, py):
+            font("miso"):
+            zoom(0.55):
+            settext(text):
+            diffuse(0.62, 0.62, 0.62, 1.0):
+            z(1003)
+        ));
+    }
+}
+
+fn push_meter_rows(ui: &mut Vec<Actor>, buckets: &[HistoryBucket], x: f32, y: f32, w: f32) {
+    for (i, (meter, plays, ex)) in window_ex_by_meter(buckets)
+        .into_iter()
+        .take(PROGRESS_MAX_METER_ROWS)
+        .enumerate()
+    {
+        let text = tr_fmt(
+            "Profiles",
+            "ProgressMeterRow",
+            &[
+                ("meter", &meter.to_string()),
+                ("ex", &format!("{ex:.2}")),
+                ("plays", &plays.to_string()),
+            ],
+        );
+        ui.push(act!(text:
+            align(0.0, 0.0):
+            xy(x + 8.0, (i as f32).mul_add(18.0, y + 6.0)):
+            font("miso"):
+            zoom(0.75):
+            maxwidth(w - 16.0):
+            settext(text):
+            diffuse(0.96, 0.96, 0.96, 1.0):
+            z(1003):
+            horizalign(left)
+        ));
+    }
+}
+
+pub(super) fn push_progress_overlay(ui: &mut Vec<Actor>, state: &State, header_font: &'static str) {
+    let Some(progress) = &state.progress else {
+        return;
+    };
+
+    let w = screen_width();
+    let h = screen_height();
+    let box_w = PROGRESS_BOX_W.min(w * 0.94);
+    let box_h = PROGRESS_BOX_H.min(h * 0.9);
+    let cx = w * 0.5;
+    let cy = h * 0.5;
+    let top = cy - box_h * 0.5;
+    let left = cx - box_w * 0.5;
+
+    push_popup_backdrop(ui, w, h);
+    push_popup_box(ui, cx, cy, box_w, box_h);
+    push_popup_title(
+        ui,
+        tr_fmt(
+            "Profiles",
+            "ProgressTitle",
+            &[("name", progress.display_name.as_str())],
+        )
+        .to_string(),
+        cx,
+        top,
+        box_w - 40.0,
+        header_font,
+    );
+
+    let body = match &progress.view {
+        ProgressView::Loading => Some(tr("Profiles", "ProgressLoading").to_string()),
+        ProgressView::Failed(error) => {
+            Some(tr_fmt("Profiles", "ProgressFailed", &[("error", error.as_str())]).to_string())
+        }
+        ProgressView::Ready { plays: 0, .. } => Some(tr("Profiles", "ProgressEmpty").to_string()),
+        ProgressView::Ready { .. } => None,
+    };
+    if let Some(body) = body {
+        ui.push(act!(text:
+            align(0.5, 0.5):
+            xy(cx, cy):
+            font("miso"):
+            zoom(0.9):
+            maxwidth(box_w - 48.0):
+            settext(body):
+            diffuse(1.0, 1.0, 1.0, 1.0):
+            z(1002):
+            horizalign(center)
+        ));
+        push_popup_footer(
+            ui,
+            tr("Profiles", "PressBackCancel").to_string(),
+            cx,
+            cy + box_h * 0.5 - 12.0,
+        );
+        return;
+    }
+
+    let window = progress.window();
+    let period_key = match progress.period {
+        HistoryPeriod::Day => "ProgressDaily",
+        HistoryPeriod::Week => "ProgressWeekly",
+    };
+    ui.push(act!(text:
+        align(0.5, 0.0):
+        xy(cx, top + PROGRESS_HEADER_H - 14.0):
+        font("miso"):
+        zoom(0.8):
+        maxwidth(box_w - 40.0):
+        settext(format!("{}    {}", tr("Profiles", period_key), window_summary(window))):
+        diffuse(0.96, 0.96, 0.96, 1.0):
+        z(1002):
+        horizalign(center)
+    ));
+
+    let graph_w = (box_w - 3.0 * PROGRESS_GAP - 24.0) * 0.5;
+    let col_x = [
+        left + PROGRESS_GAP + 24.0,
+        left + 2.0f32.mul_add(PROGRESS_GAP, graph_w) + 24.0,
+    ];
+    let row_y = [
+        top + PROGRESS_HEADER_H + 30.0,
+        top + PROGRESS_HEADER_H + 30.0 + PROGRESS_GRAPH_H + 40.0,
+    ];
+
+    let plays: Vec<f32> = window.iter().map(|bucket| bucket.plays as f32).collect();
+    let max_plays = plays.iter().copied().fold(1.0, f32::max);
+    push_graph_frame(
+        ui,
+        col_x[0],
+        row_y[0],
+        graph_w,
+        PROGRESS_GRAPH_H,
+        tr_fmt(
+            "Profiles",
+            "ProgressGraphPlays",
+            &[("max", &format!("{max_plays:.0}"))],
+        ),
+    );
+    push_bars(
+        ui,
+        &plays,
+        0.0,
+        max_plays,
+        col_x[0],
+        row_y[0],
+        graph_w,
+        PROGRESS_GRAPH_H,
+        [0.55, 0.92, 0.55, 0.9],
+    );
+    push_date_labels(ui, window, col_x[0], row_y[0] + PROGRESS_GRAPH_H, graph_w);
+
+    let ex: Vec<f32> = window
+        .iter()
+        .map(|bucket| bucket.mean_ex_percent as f32)
+        .collect();
+    push_graph_frame(
+        ui,
+        col_x[1],
+        row_y[0],
+        graph_w,
+        PROGRESS_GRAPH_H,
+        tr("Profiles", "ProgressGraphEx"),
+    );
+    push_bars(
+        ui,
+        &ex,
+        PROGRESS_EX_FLOOR,
+        100.0,
+        col_x[1],
+        row_y[0],
+        graph_w,
+        PROGRESS_GRAPH_H,
+        [0.96, 0.78, 0.36, 0.9],
+    );
+    push_date_labels(ui, window, col_x[1], row_y[0] + PROGRESS_GRAPH_H, graph_w);
+
+    push_graph_frame(
+        ui,
+        col_x[0],
+        row_y[1],
+        graph_w,
+        PROGRESS_GRAPH_H,
+        tr("Profiles", "ProgressGraphOffset"),
+    );
+    push_offsets(ui, window, col_x[0], row_y[1], graph_w, PROGRESS_GRAPH_H);
+    push_date_labels(ui, window, col_x[0], row_y[1] + PROGRESS_GRAPH_H, graph_w);
+
+    push_graph_frame(
+        ui,
+        col_x[1],
+        row_y[1],
+        graph_w,
+        PROGRESS_GRAPH_H,
+        tr("Profiles", "ProgressGraphMeters"),
+    );
+    push_meter_rows(ui, window, col_x[1], row_y[1], graph_w);
+
+    if let Some(notice) = &progress.notice {
+        ui.push(act!(text:
+            align(0.5, 1.0):
+            xy(cx, cy + box_h * 0.5 - 34.0):
+            font("miso"):
+            zoom(0.7):
+            maxwidth(box_w - 40.0):
+            settext(notice.clone()):
+            diffuse(0.78, 0.78, 0.78, 1.0):
+            z(1003):
+            horizalign(center)
+        ));
+    }
+    push_popup_footer(
+        ui,
+        tr("Profiles", "ProgressPrompt").to_string(),
+        cx,
+        cy + box_h * 0.5 - 12.0,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use deadsync_score::HistoryMeterEx;
+
+    fn bucket(day: u32, meters: &[(u32, u32, f64)]) -> HistoryBucket {
+        HistoryBucket {
+            start: NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+            plays: 1,
+            steps_hit: 100,
+            play_seconds: 90.0,
+            calories: 10.0,
+            mean_ex_percent: 80.0,
+            ex_by_meter: meters
+                .iter()
+                .map(|&(meter, plays, mean_ex_percent)| HistoryMeterEx {
+                    meter,
+                    plays,
+                    mean_ex_percent,
+                })
+                .collect(),
+            personal_bests: 0,
+            offset_mean_ms: None,
+            offset_stddev_ms: None,
+        }
+    }
+
+    #[test]
+    fn progress_meter_rows_weight_by_plays_and_sort_high_first() {
+        let buckets = [
+            bucket(1, &[(9, 1, 90.0), (10, 3, 80.0)]),
+            bucket(2, &[(10, 1, 84.0)]),
+        ];
+        let rows = window_ex_by_meter(&buckets);
+        assert_eq!(rows.len(), 2);
+        assert_eq!((rows[0].0, rows[0].1), (10, 4));
+        assert!((rows[0].2 - 81.0).abs() < 1e-9);
+        assert_eq!(rows[1].0, 9);
+    }
+
+    #[test]
+    fn progress_play_time_switches_to_hours() {
+        assert_eq!(format_play_time(59.0 * 60.0), "59m");
+        assert_eq!(format_play_time(125.0 * 60.0), "2h 05m");
+    }
+}
@@ -0,0 +1,431 @@
+use super::*;
+use crate::SimplyLoveOffsetDriftReports;
+use deadsync_score::OffsetDriftReport;
+
+/// Plays drawn in the graph, newest on the right.
+const TIMING_GRAPH_PLAYS: usize = 60;
+const TIMING_MAX_PACK_ROWS: usize = 5;
+const TIMING_BOX_W: f32 = 760.0;
+const TIMING_BOX_H: f32 = 430.0;
+const TIMING_HEADER_H: f32 = 58.0;
+const TIMING_GRAPH_H: f32 = 150.0;
+const TIMING_GAP: f32 = 20.0;
+/// Smallest half-range of the graph, in ms.
+const TIMING_MIN_RANGE_MS: f32 = 10.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum TimingScope {
+    Profile,
+    Machine,
+}
+
+pub(super) struct TimingState {
+    pub(super) profile_id: String,
+    pub(super) display_name: String,
+    pub(super) scope: TimingScope,
+    pub(super) view: TimingView,
+}
+
+pub(super) enum TimingView {
+    Loading,
+    Ready(SimplyLoveOffsetDriftReports),
+    Failed(String),
+}
+
+impl TimingState {
+    fn report(&self) -> Option<&OffsetDriftReport> {
+        let TimingView::Ready(reports) = &self.view else {
+            return None;
+        };
+        Some(match self.scope {
+            TimingScope::Profile => &reports.profile,
+            TimingScope::Machine => &reports.machine,
+        })
+    }
+}
+
+pub(super) fn begin_timing(state: &mut State, id: &str, display_name: &str) -> ThemeEffect {
+    reset_nav_hold(state);
+    state.profile_menu = None;
+    state.timing = Some(TimingState {
+        profile_id: id.to_string(),
+        display_name: display_name.to_string(),
+        scope: TimingScope::Profile,
+        view: TimingView::Loading,
+    });
+    crate::effects::sfx_then(
+        "assets/sounds/start.ogg",
+        ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Profile(
+            crate::SimplyLoveProfileRequest::LoadOffsetDrift {
+                profile_id: id.to_string(),
+            },
+        )),
+    )
+}
+
+pub(super) fn apply_reports(
+    state: &mut State,
+    profile_id: &str,
+    reports: Result<SimplyLoveOffsetDriftReports, String>,
+) {
+    let Some(timing) = state.timing.as_mut() else {
+        return;
+    };
+    if timing.profile_id != profile_id || !matches!(timing.view, TimingView::Loading) {
+        return;
+    }
+    timing.view = match reports {
+        Ok(reports) => TimingView::Ready(reports),
+        Err(error) => TimingView::Failed(error),
+    };
+}
+
+fn close_timing(state: &mut State) -> ThemeEffect {
+    let loading = state
+        .timing
+        .take()
+        .is_some_and(|timing| matches!(timing.view, TimingView::Loading));
+    reset_nav_hold(state);
+    if loading {
+        ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Profile(
+            crate::SimplyLoveProfileRequest::CancelOffsetDrift,
+        ))
+    } else {
+        ThemeEffect::None
+    }
+}
+
+fn toggle_scope(state: &mut State) -> ThemeEffect {
+    let Some(timing) = state.timing.as_mut() else {
+        return ThemeEffect::None;
+    };
+    timing.scope = match timing.scope {
+        TimingScope::Profile => TimingScope::Machine,
+        TimingScope::Machine => TimingScope::Profile,
+    };
+    crate::effects::sfx("assets/sounds/change.ogg")
+}
+
+pub(super) fn handle_timing_three_key(
+    state: &mut State,
+    nav: screen_input::ThreeKeyMenuAction,
+) -> ThemeEffect {
+    match nav {
+        screen_input::ThreeKeyMenuAction::Prev | screen_input::ThreeKeyMenuAction::Next => {
+            toggle_scope(state)
+        }
+        screen_input::ThreeKeyMenuAction::Confirm => ThemeEffect::None,
+        screen_input::ThreeKeyMenuAction::Cancel => close_timing(state),
+    }
+}
+
+pub(super) fn handle_timing_input(state: &mut State, ev: &InputEvent) -> ThemeEffect {
+    if !ev.pressed {
+        return ThemeEffect::None;
+    }
+    match ev.action {
+        VirtualAction::p1_back | VirtualAction::p2_back => close_timing(state),
+        VirtualAction::p1_left
+        | VirtualAction::p1_menu_left
+        | VirtualAction::p2_left
+        | VirtualAction::p2_menu_left
+        | VirtualAction::p1_right
+        | VirtualAction::p1_menu_right
+        | VirtualAction::p2_right
+        | VirtualAction::p2_menu_right => toggle_scope(state),
+        _ => ThemeEffect::None,
+    }
+}
+
+fn format_ms(ms: Option<f32>) -> String {
+    ms.map_or_else(|| "--".to_string(), |ms| format!("{ms:+.1}ms"))
+}
+
+fn proposal_text(report: &OffsetDriftReport) -> Arc<str> {
+    match report.global_offset_change_ms {
+        Some(change) => tr_fmt(
+            "Profiles",
+            if change < 0.0 {
+                "TimingProposalLate"
+            } else {
+                "TimingProposalEarly"
+            },
+            &[("change", &format!("{change:+.1}"))],
+        ),
+        None => tr("Profiles", "TimingNoDrift"),
+    }
+}
+
+/// Play medians as dots around a zero line, with the baseline marked.
+fn push_play_medians(
+    ui: &mut Vec<Actor>,
+    report: &OffsetDriftReport,
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+) {
+    let plays = &report.plays[report.plays.len().saturating_sub(TIMING_GRAPH_PLAYS)..];
+    let range = plays
+        .iter()
+        .map(|play| play.median_ms.abs())
+        .fold(TIMING_MIN_RANGE_MS, f32::max);
+    let mid_y = y + h * 0.5;
+    let px_per_ms = h * 0.5 / range;
+    ui.push(act!(quad:
+        align(0.0, 0.5):
+        xy(x, mid_y):
+        zoomto(w, 1.0):
+        diffuse(1.0, 1.0, 1.0, 0.3):
+        z(1003)
+    ));
+    if let Some(baseline) = report.baseline_ms {
+        ui.push(act!(quad:
+            align(0.0, 0.5):
+            xy(x, baseline.mul_add(px_per_ms, mid_y)):
+            zoomto(w, 1.0):
+            diffuse(0.96, 0.78, 0.36, 0.7):
+            z(1003)
+        ));
+    }
+    let slot_w = w / TIMING_GRAPH_PLAYS as f32;
+    let first_slot = TIMING_GRAPH_PLAYS.saturating_sub(plays.len());
+    for (i, play) in plays.iter().enumerate() {
+        let cx = ((first_slot + i) as f32 + 0.5).mul_add(slot_w, x);
+        // Late offsets are positive and drawn below the line, like the
+        // progress graph.
+        let cy = play.median_ms.mul_add(px_per_ms, mid_y);
+        ui.push(act!(quad:
+            align(0.5, 0.5):
+            xy(cx, cy):
+            zoomto(slot_w.clamp(2.0, 6.0), 3.0):
+            diffuse(0.55, 0.75, 1.0, 1.0):
+            z(1004)
+        ));
+    }
+    for (text, ay, py) in [
+        (format!("-{range:.0}ms"), 0.0, y),
+        (format!("+{range:.0}ms"), 1.0, y + h),
+    ] {
+        ui.push(act!(text:
+            align(1.0, ay):
+            xy(x - 4.0, py):
+            font("miso"):
+            zoom(0.55):
+            settext(text):
+            diffuse(0.62, 0.62, 0.62, 1.0):
+            z(1003)
+        ));
+    }
+}
+
+fn push_text_line(ui: &mut Vec<Actor>, text: Arc<str>, x: f32, y: f32, w: f32, zoom: f32) {
+    ui.push(act!(text:
+        align(0.0, 0.0):
+        xy(x, y):
+        font("miso"):
+        zoom(zoom):
+        maxwidth(w):
+        settext(text):
+        diffuse(0.96, 0.96, 0.96, 1.0):
+        z(1003):
+        horizalign(left)
+    ));
+}
+
+pub(super) fn push_timing_overlay(ui: &mut Vec<Actor>, state: &State, header_font: &'static str) {
+    let Some(timing) = &state.timing else {
+        return;
+    };
+
+    let w = screen_width();
+    let h = screen_height();
+    let box_w = TIMING_BOX_W.min(w * 0.94);
+    let box_h = TIMING_BOX_H.min(h * 0.9);
+    let cx = w * 0.5;
+    let cy = h * 0.5;
+    let top = cy - box_h * 0.5;
+    let left = cx - box_w * 0.5;
+
+    push_popup_backdrop(ui, w, h);
+    push_popup_box(ui, cx, cy, box_w, box_h);
+    push_popup_title(
+        ui,
+        tr_fmt(
+            "Profiles",
+            "TimingTitle",
+            &[("name", timing.display_name.as_str())],
+        )
+        .to_string(),
+        cx,
+        top,
+        box_w - 40.0,
+        header_font,
+    );
+
+    let report = timing.report();
+    let body = match (&timing.view, report) {
+        (TimingView::Loading, _) => Some(tr("Profiles", "TimingLoading").to_string()),
+        (TimingView::Failed(error), _) => {
+            Some(tr_fmt("Profiles", "TimingFailed", &[("error", error.as_str())]).to_string())
+        }
+        (_, Some(report)) if report.plays.is_empty() => {
+            Some(tr("Profiles", "TimingEmpty").to_string())
+        }
+        _ => None,
+    };
+    let scope_key = match timing.scope {
+        TimingScope::Profile => "TimingProfile",
+        TimingScope::Machine => "TimingMachine",
+    };
+    let footer_y = cy + box_h * 0.5 - 12.0;
+    if let Some(body) = body {
+        if matches!(timing.view, TimingView::Ready(_)) {
+            ui.push(act!(text:
+                align(0.5, 0.0):
+                xy(cx, top + TIMING_HEADER_H - 14.0):
+                font("miso"):
+                zoom(0.8):
+                settext(tr("Profiles", scope_key)):
+                diffuse(0.96, 0.96, 0.96, 1.0):
+                z(1002):
+                horizalign(center)
+            ));
+        }
+        ui.push(act!(text:
+            align(0.5, 0.5):
+            xy(cx, cy):
+            font("miso"):
+            zoom(0.9):
+            maxwidth(box_w - 48.0):
+            settext(body):
+            diffuse(1.0, 1.0, 1.0, 1.0):
+            z(1002):
+            horizalign(center)
+        ));
+        let footer_key = if matches!(timing.view, TimingView::Ready(_)) {
+            "TimingPrompt"
+        } else {
+            "PressBackCancel"
+        };
+        push_popup_footer(ui, tr("Profiles", footer_key).to_string(), cx, footer_y);
+        return;
+    }
+    let Some(report) = report else {
+        return;
+    };
+
+    ui.push(act!(text:
+        align(0.5, 0.0):
+        xy(cx, top + TIMING_HEADER_H - 14.0):
+        font("miso"):
+        zoom(0.8):
+        maxwidth(box_w - 40.0):
+        settext(format!(
+            "{}    {}",
+            tr("Profiles", scope_key),
+            tr_fmt(
+                "Profiles",
+                "TimingSummary",
+                &[
+                    ("plays", &fmt_count(report.plays.len())),
+                    ("baseline", &format_ms(report.baseline_ms)),
+                    ("recent", &format_ms(report.recent_ms)),
+                ],
+            )
+        )):
+        diffuse(0.96, 0.96, 0.96, 1.0):
+        z(1002):
+        horizalign(center)
+    ));
+
+    let graph_x = left + TIMING_GAP + 24.0;
+    let graph_w = box_w - 2.0 * TIMING_GAP - 24.0;
+    let graph_y = top + TIMING_HEADER_H + 30.0;
+    ui.push(act!(quad:
+        align(0.0, 0.0):
+        xy(graph_x, graph_y):
+        zoomto(graph_w, TIMING_GRAPH_H):
+        diffuse(1.0, 1.0, 1.0, 0.06):
+        z(1002)
+    ));
+    ui.push(act!(text:
+        align(0.0, 1.0):
+        xy(graph_x, graph_y - 2.0):
+        font("miso"):
+        zoom(0.7):
+        maxwidth(graph_w):
+        settext(tr("Profiles", "TimingGraph")):
+        diffuse(0.78, 0.78, 0.78, 1.0):
+        z(1003):
+        horizalign(left)
+    ));
+    push_play_medians(ui, report, graph_x, graph_y, graph_w, TIMING_GRAPH_H);
+
+    let mut line_y = graph_y + TIMING_GRAPH_H + 12.0;
+    push_text_line(ui, proposal_text(report), graph_x, line_y, graph_w, 0.8);
+    line_y += 26.0;
+    push_text_line(
+        ui,
+        tr("Profiles", "TimingPacks"),
+        graph_x,
+        line_y,
+        graph_w,
+        0.75,
+    );
+    line_y += 20.0;
+    if report.off_sync_packs.is_empty() {
+        push_text_line(
+            ui,
+            tr("Profiles", "TimingNoPacks"),
+            graph_x + 8.0,
+            line_y,
+            graph_w,
+            0.7,
+        );
+    }
+    for (i, pack) in report
+        .off_sync_packs
+        .iter()
+        .take(TIMING_MAX_PACK_ROWS)
+        .enumerate()
+    {
+        let text = tr_fmt(
+            "Profiles",
+            "TimingPackRow",
+            &[
+                ("pack", pack.pack.as_str()),
+                ("delta", &format!("{:+.1}", pack.delta_ms)),
+                ("plays", &pack.plays.to_string()),
+            ],
+        );
+        push_text_line(
+            ui,
+            text,
+            graph_x + 8.0,
+            (i as f32).mul_add(18.0, line_y),
+            graph_w - 8.0,
+            0.7,
+        );
+    }
+
+    push_popup_footer(ui, tr("Profiles", "TimingPrompt").to_string(), cx, footer_y);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proposal_names_the_direction_players_are_hitting() {
+        let mut report = OffsetDriftReport::default();
+        assert_eq!(proposal_text(&report), tr("Profiles", "TimingNoDrift"));
+        report.global_offset_change_ms = Some(-7.5);
+        assert_eq!(
+            proposal_text(&report),
+            tr_fmt("Profiles", "TimingProposalLate", &[("change", "-7.5")])
+        );
+        assert_eq!(format_ms(None), "--");
+        assert_eq!(format_ms(Some(3.0)), "+3.0ms");
+    }
+}
@@ -0,0 +1,259 @@
+use super::*;
+use deadsync_score::WorkoutWeek;
+
+const WORKOUTS_MAX_WEEKS: usize = 8;
+const WORKOUTS_BOX_W: f32 = 760.0;
+const WORKOUTS_BOX_H: f32 = 360.0;
+const WORKOUTS_HEADER_H: f32 = 58.0;
+const WORKOUTS_ROW_H: f32 = 28.0;
+
+pub(super) struct WorkoutsState {
+    pub(super) profile_id: String,
+    pub(super) display_name: String,
+    pub(super) view: WorkoutsView,
+}
+
+pub(super) enum WorkoutsView {
+    Loading,
+    Ready(Vec<WorkoutWeek>),
+    Failed(String),
+}
+
+pub(super) fn begin_workouts(state: &mut State, id: &str, display_name: &str) -> ThemeEffect {
+    reset_nav_hold(state);
+    state.profile_menu = None;
+    state.workouts = Some(WorkoutsState {
+        profile_id: id.to_string(),
+        display_name: display_name.to_string(),
+        view: WorkoutsView::Loading,
+    });
+    crate::effects::sfx_then(
+        "assets/sounds/start.ogg",
+        ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Profile(
+            crate::SimplyLoveProfileRequest::LoadWorkoutSummary {
+                profile_id: id.to_string(),
+            },
+        )),
+    )
+}
+
+pub(super) fn apply_weeks(
+    state: &mut State,
+    profile_id: &str,
+    weeks: Result<Vec<WorkoutWeek>, String>,
+) {
+    let Some(workouts) = state.workouts.as_mut() else {
+        return;
+    };
+    if workouts.profile_id != profile_id || !matches!(workouts.view, WorkoutsView::Loading) {
+        return;
+    }
+    workouts.view = match weeks {
+        Ok(weeks) => WorkoutsView::Ready(weeks),
+        Err(error) => WorkoutsView::Failed(error),
+    };
+}
+
+fn close_workouts(state: &mut State) -> ThemeEffect {
+    state.workouts = None;
+    reset_nav_hold(state);
+    ThemeEffect::None
+}
+
+pub(super) fn handle_workouts_three_key(
+    state: &mut State,
+    nav: screen_input::ThreeKeyMenuAction,
+) -> ThemeEffect {
+    match nav {
+        screen_input::ThreeKeyMenuAction::Cancel => close_workouts(state),
+        _ => ThemeEffect::None,
+    }
+}
+
+pub(super) fn handle_workouts_input(state: &mut State, ev: &InputEvent) -> ThemeEffect {
+    if !ev.pressed {
+        return ThemeEffect::None;
+    }
+    match ev.action {
+        VirtualAction::p1_back
+        | VirtualAction::p2_back
+        | VirtualAction::p1_start
+        | VirtualAction::p2_start => close_workouts(state),
+        _ => ThemeEffect::None,
+    }
+}
+
+fn format_minutes(seconds: f32) -> String {
+    format!("{:.0}", (seconds.max(0.0) / 60.0).floor())
+}
+
+fn format_bpm(bpm: Option<u16>) -> String {
+    bpm.map_or_else(|| "--".to_string(), |bpm| bpm.to_string())
+}
+
+fn week_row_text(week: &WorkoutWeek) -> Arc<str> {
+    tr_fmt(
+        "Profiles",
+        "WorkoutsWeekRow",
+        &[
+            ("week", &week.start.format("%Y-%m-%d").to_string()),
+            ("sessions", &week.sessions.to_string()),
+            ("met", &week.goals_met.to_string()),
+            ("songs", &week.songs.to_string()),
+            ("minutes", &format_minutes(week.active_seconds)),
+            ("calories", &format!("{:.0}", week.calories.max(0.0))),
+            ("steps", &week.steps.to_string()),
+            ("avg", &format_bpm(week.average_bpm)),
+            ("peak", &format_bpm(week.peak_bpm)),
+        ],
+    )
+}
+
+pub(super) fn push_workouts_overlay(ui: &mut Vec<Actor>, state: &State, header_font: &'static str) {
+    let Some(workouts) = &state.workouts else {
+        return;
+    };
+
+    let w = screen_width();
+    let h = screen_height();
+    let box_w = WORKOUTS_BOX_W.min(w * 0.94);
+    let box_h = WORKOUTS_BOX_H.min(h * 0.9);
+    let cx = w * 0.5;
+    let cy = h * 0.5;
+    let top = cy - box_h * 0.5;
+    let left = cx - box_w * 0.5;
+
+    push_popup_backdrop(ui, w, h);
+    push_popup_box(ui, cx, cy, box_w, box_h);
+    push_popup_title(
+        ui,
+        tr_fmt(
+            "Profiles",
+            "WorkoutsTitle",
+            &[("name", workouts.display_name.as_str())],
+        )
+        .to_string(),
+        cx,
+        top,
+        box_w - 40.0,
+        header_font,
+    );
+    let footer_y = cy + box_h * 0.5 - 12.0;
+
+    let body = match &workouts.view {
+        WorkoutsView::Loading => Some(tr("Profiles", "WorkoutsLoading")),
+        WorkoutsView::Failed(error) => Some(tr_fmt(
+            "Profiles",
+            "WorkoutsFailed",
+            &[("error", error.as_str())],
+        )),
+        WorkoutsView::Ready(weeks) if weeks.is_empty() => Some(tr("Profiles", "WorkoutsEmpty")),
+        WorkoutsView::Ready(_) => None,
+    };
+    if let Some(body) = body {
+        ui.push(act!(text:
+            align(0.5, 0.5):
+            xy(cx, cy):
+            font("miso"):
+            zoom(0.9):
+            maxwidth(box_w - 48.0):
+            settext(body):
+            diffuse(1.0, 1.0, 1.0, 1.0):
+            z(1002):
+            horizalign(center)
+        ));
+        push_popup_footer(
+            ui,
+            tr("Profiles", "PressBackCancel").to_string(),
+            cx,
+            footer_y,
+        );
+        return;
+    }
+    let WorkoutsView::Ready(weeks) = &workouts.view else {
+        return;
+    };
+
+    let row_x = left + 24.0;
+    let row_w = box_w - 48.0;
+    ui.push(act!(text:
+        align(0.0, 0.0):
+        xy(row_x, top + WORKOUTS_HEADER_H - 14.0):
+        font("miso"):
+        zoom(0.75):
+        maxwidth(row_w):
+        settext(tr("Profiles", "WorkoutsWeeks")):
+        diffuse(0.78, 0.78, 0.78, 1.0):
+        z(1002):
+        horizalign(left)
+    ));
+    for (i, week) in weeks.iter().take(WORKOUTS_MAX_WEEKS).enumerate() {
+        let y = (i as f32).mul_add(WORKOUTS_ROW_H, top + WORKOUTS_HEADER_H + 12.0);
+        if i % 2 == 0 {
+            ui.push(act!(quad:
+                align(0.0, 0.0):
+                xy(row_x - 6.0, y - 4.0):
+                zoomto(row_w + 12.0, WORKOUTS_ROW_H):
+                diffuse(1.0, 1.0, 1.0, 0.05):
+                z(1002)
+            ));
+        }
+        ui.push(act!(text:
+            align(0.0, 0.0):
+            xy(row_x, y):
+            font("miso"):
+            zoom(0.75):
+            maxwidth(row_w):
+            settext(week_row_text(week)):
+            diffuse(0.96, 0.96, 0.96, 1.0):
+            z(1003):
+            horizalign(left)
+        ));
+    }
+
+    push_popup_footer(
+        ui,
+        tr("Profiles", "WorkoutsPrompt").to_string(),
+        cx,
+        footer_y,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn week_row_shows_dashes_without_heart_rate() {
+        let week = WorkoutWeek {
+            start: chrono::NaiveDate::from_ymd_opt(2026, 10, 12).unwrap(),
+            sessions: 2,
+            goals_met: 1,
+            songs: 9,
+            calories: 180.6,
+            active_seconds: 1_259.0,
+            steps: 3_400,
+            average_bpm: None,
+            peak_bpm: None,
+        };
+        let text = week_row_text(&week);
+        assert_eq!(
+            text,
+            tr_fmt(
+                "Profiles",
+                "WorkoutsWeekRow",
+                &[
+                    ("week", "2026-10-12"),
+                    ("sessions", "2"),
+                    ("met", "1"),
+                    ("songs", "9"),
+                    ("minutes", "20"),
+                    ("calories", "181"),
+                    ("steps", "3400"),
+                    ("avg", "--"),
+                    ("peak", "--"),
+                ],
+            )
+        );
+    }
+}
@@ -18,7 +18,7 @@ use deadsync_core::input::InputSource;
 use deadsync_input::KeyCode;
 use deadsync_input::RawKeyboardEvent;
 use deadsync_input::{
-    GamepadCodeBinding, InputBinding, InputEvent, Keymap, PadEvent, VirtualAction,
+    AxisDirection, GamepadCodeBinding, InputBinding, InputEvent, Keymap, PadEvent, VirtualAction,
     clamp_input_debounce_seconds, cleared_keymap, editable_key_binding_slot_indices,
     protected_default_key_for_action, updated_keymap_unique_gamepad,
     updated_keymap_unique_keyboard,
@@ -1197,6 +1197,18 @@ fn format_binding_for_display(binding: InputBinding) -> String {
             }
             label
         }
+        InputBinding::PadAxis(binding) => {
+            let dev = binding.device.unwrap_or(0);
+            let mut hex = format!("{:08X}", binding.code_u32);
+            while hex.len() > 1 && hex.starts_with('0') {
+                hex.remove(0);
+            }
+            let sign = match binding.direction {
+                AxisDirection::Positive => '+',
+                AxisDirection::Negative => '-',
+            };
+            format!("Pad {dev} Axis 0x{hex}{sign}")
+        }
     }
 }
 
@@ -34,6 +34,7 @@ pub mod select_profile;
 pub mod select_style;
 mod select_style_flow;
 pub mod smx_assign;
+pub mod spectator;
 pub mod test_lights;
 
 pub use crate::SimplyLoveEffect as ThemeEffect;
@@ -111,11 +111,11 @@ pub(super) const NO_TAP_EXPLOSION_LABEL: &str = "NoTapExplosionLabel";
 
 use deadsync_profile::{
     AttackMode, ColumnFlashBrightness, ColumnFlashSize, ComboColors, ComboFont, ComboMode,
-    ErrorBarTrim, HideLightType, LifeMeterType, MeasureCounter, MeasureLines, MiniIndicator,
-    MiniIndicatorColor, MiniIndicatorPosition, MiniIndicatorScoreType, MiniIndicatorSize,
-    MiniIndicatorSubtractiveDisplay, NoCmodAlternative, Perspective, ScatterplotMaxWindow,
-    ScoreDisplayMode, ScorePosition, TargetScoreMissPolicy, TargetScoreSetting,
-    TimingWindowsOption, TurnOption,
+    ErrorBarTrim, HideLightType, LifeMeterType, LifeType, MeasureCounter, MeasureLines,
+    MiniIndicator, MiniIndicatorColor, MiniIndicatorPosition, MiniIndicatorScoreType,
+    MiniIndicatorSize, MiniIndicatorSubtractiveDisplay, NoCmodAlternative, Perspective,
+    ScatterplotMaxWindow, ScoreDisplayMode, ScorePosition, ScoringModel, TargetScoreMissPolicy,
+    TargetScoreSetting, TimingWindowsOption, TurnOption,
 };
 
 /// `NoCmodAlternative` variants in row-choice order (index ↔ enum).
@@ -233,6 +233,15 @@ pub(super) const LIFE_METER_TYPE_VARIANTS: [LifeMeterType; 3] = [
     LifeMeterType::Vertical,
 ];
 
+pub(super) const SCORING_MODEL_VARIANTS: [ScoringModel; 4] = [
+    ScoringModel::Off,
+    ScoringModel::DdrMoney,
+    ScoringModel::Wife3,
+    ScoringModel::Custom,
+];
+
+pub(super) const LIFE_TYPE_VARIANTS: [LifeType; 2] = [LifeType::Bar, LifeType::Battery];
+
 pub(super) const ERROR_BAR_TRIM_VARIANTS: [ErrorBarTrim; 4] = [
     ErrorBarTrim::Off,
     ErrorBarTrim::Fantastic,
@@ -18,11 +18,11 @@ use super::super::state::{
 use super::*;
 use deadsync_profile::{
     ColumnFlashBrightness, ColumnFlashSize, ComboColors, ComboMode, ErrorBarMask, ErrorBarTrim,
-    LifeMeterType, MeasureCounter, MeasureLines, MiniIndicator, MiniIndicatorColor,
+    LifeMeterType, LifeType, MeasureCounter, MeasureLines, MiniIndicator, MiniIndicatorColor,
     MiniIndicatorPosition, MiniIndicatorScoreType, MiniIndicatorSize,
     MiniIndicatorSubtractiveDisplay, PlayerOptionsData, ScatterplotMaxWindow, ScoreDisplayMode,
-    ScorePosition, StepStatsExtra, TargetScoreMissPolicy, TargetScoreSetting, TimingWindowsOption,
-    TurnOption,
+    ScorePosition, ScoringModel, StepStatsExtra, TargetScoreMissPolicy, TargetScoreSetting,
+    TimingWindowOverrides, TimingWindowsOption, TurnOption,
 };
 
 // =============================== Bindings ===============================
@@ -55,6 +55,62 @@ const LIFE_METER_TYPE: ChoiceBinding<usize> = index_binding!(
         }
     })
 );
+const LIFE_TYPE: ChoiceBinding<usize> = index_binding!(
+    LIFE_TYPE_VARIANTS,
+    LifeType::Bar,
+    life_type,
+    true,
+    Some(CycleInit {
+        from_profile: |p| {
+            LIFE_TYPE_VARIANTS
+                .iter()
+                .position(|&v| v == p.life_type)
+                .unwrap_or(0)
+        }
+    })
+);
+const BATTERY_LIVES: NumericBinding = NumericBinding {
+    parse: parse_i32,
+    apply: |p, v| {
+        p.battery_lives = v.clamp(
+            i32::from(deadsync_profile::BATTERY_LIVES_MIN),
+            i32::from(deadsync_profile::BATTERY_LIVES_MAX),
+        ) as u8;
+        Outcome::persisted()
+    },
+    init: Some(NumericInit {
+        from_profile: |p| i32::from(p.battery_lives),
+        format: |v| format!("{v}"),
+    }),
+};
+const BATTERY_REWARD_LIVES: NumericBinding = NumericBinding {
+    parse: parse_i32,
+    apply: |p, v| {
+        p.battery_reward_lives = v.clamp(
+            i32::from(deadsync_profile::BATTERY_REWARD_LIVES_MIN),
+            i32::from(deadsync_profile::BATTERY_REWARD_LIVES_MAX),
+        ) as u8;
+        Outcome::persisted()
+    },
+    init: Some(NumericInit {
+        from_profile: |p| i32::from(p.battery_reward_lives),
+        format: |v| format!("{v}"),
+    }),
+};
+const TIMING_WINDOW_SCALE: NumericBinding = NumericBinding {
+    parse: parse_i32_percent,
+    apply: |p, v| {
+        p.timing_window_scale = v.clamp(
+            i32::from(deadsync_profile::TIMING_WINDOW_SCALE_MIN),
+            i32::from(deadsync_profile::TIMING_WINDOW_SCALE_MAX),
+        ) as u8;
+        Outcome::persisted()
+    },
+    init: Some(NumericInit {
+        from_profile: |p| i32::from(p.timing_window_scale),
+        format: |v| format!("{v}%"),
+    }),
+};
 const SCATTERPLOT_MAX_WINDOW: ChoiceBinding<usize> = index_binding!(
     SCATTERPLOT_MAX_WINDOW_VARIANTS,
     ScatterplotMaxWindow::Off,
@@ -83,6 +139,20 @@ const SCORE_POSITION: ChoiceBinding<usize> = index_binding!(
         }
     })
 );
+const SCORING_MODEL: ChoiceBinding<usize> = index_binding!(
+    SCORING_MODEL_VARIANTS,
+    ScoringModel::Off,
+    scoring_model,
+    false,
+    Some(CycleInit {
+        from_profile: |p| {
+            SCORING_MODEL_VARIANTS
+                .iter()
+                .position(|&v| v == p.scoring_model)
+                .unwrap_or(0)
+        }
+    })
+);
 const SCORE_DISPLAY_MODE: ChoiceBinding<usize> = index_binding!(
     SCORE_DISPLAY_MODE_VARIANTS,
     ScoreDisplayMode::Normal,
@@ -1191,6 +1261,46 @@ const CUSTOM_BLUE_FANTASTIC_WINDOW_MS: CustomBinding = CustomBinding {
     },
 };
 
+/// Rows for `TimingWindowOverrides`, in its W1..W5, Mine slot order.
+pub(super) const TIMING_WINDOW_OVERRIDE_ROWS: [RowId; TimingWindowOverrides::SLOTS] = [
+    RowId::TimingWindowOverrideW1,
+    RowId::TimingWindowOverrideW2,
+    RowId::TimingWindowOverrideW3,
+    RowId::TimingWindowOverrideW4,
+    RowId::TimingWindowOverrideW5,
+    RowId::TimingWindowOverrideMine,
+];
+
+const TIMING_WINDOW_OVERRIDE: CustomBinding = CustomBinding {
+    apply: |state, player_idx, row_id, delta, wrap| {
+        let Some(slot) = TIMING_WINDOW_OVERRIDE_ROWS
+            .iter()
+            .position(|&id| id == row_id)
+        else {
+            return Outcome::NONE;
+        };
+        let Some(new_index) = choice::cycle_choice_index(state, player_idx, row_id, delta, wrap)
+        else {
+            return Outcome::NONE;
+        };
+        let Some(choice) = state
+            .pane()
+            .row_map
+            .get(row_id)
+            .and_then(|r| r.choices.get(new_index))
+            .cloned()
+        else {
+            return Outcome::NONE;
+        };
+        // Index 0 is "Off"; every other choice is "<ms>ms".
+        let ms = choice.trim_end_matches("ms").parse::<u8>().ok();
+        state.player_options[player_idx]
+            .timing_window_overrides
+            .set_slot(slot, ms);
+        Outcome::persisted()
+    },
+};
+
 const CROSSOVER_CUE_DURATION: CustomBinding = CustomBinding {
     apply: |state, player_idx, row_id, delta, wrap| {
         let Some(new_index) = choice::cycle_choice_index(state, player_idx, row_id, delta, wrap)
@@ -1340,6 +1450,34 @@ pub(super) fn build_advanced_rows(return_screen: Screen, scorebox_available: boo
             tr("PlayerOptions", "LifeMeterTypeVertical").to_string(),
         ],
     ));
+    b.push(Row::cycle(
+        RowId::LifeType,
+        lookup_key("PlayerOptions", "LifeType"),
+        lookup_key("PlayerOptionsHelp", "LifeTypeHelp"),
+        CycleBinding::Index(LIFE_TYPE),
+        vec![
+            tr("PlayerOptions", "LifeTypeBar").to_string(),
+            tr("PlayerOptions", "LifeTypeBattery").to_string(),
+        ],
+    ));
+    b.push(Row::numeric(
+        RowId::BatteryLives,
+        lookup_key("PlayerOptions", "BatteryLives"),
+        lookup_key("PlayerOptionsHelp", "BatteryLivesHelp"),
+        BATTERY_LIVES,
+        (deadsync_profile::BATTERY_LIVES_MIN..=deadsync_profile::BATTERY_LIVES_MAX)
+            .map(|v| v.to_string())
+            .collect(),
+    ));
+    b.push(Row::numeric(
+        RowId::BatteryRewardLives,
+        lookup_key("PlayerOptions", "BatteryRewardLives"),
+        lookup_key("PlayerOptionsHelp", "BatteryRewardLivesHelp"),
+        BATTERY_REWARD_LIVES,
+        (deadsync_profile::BATTERY_REWARD_LIVES_MIN..=deadsync_profile::BATTERY_REWARD_LIVES_MAX)
+            .map(|v| v.to_string())
+            .collect(),
+    ));
     b.push(Row::bitmask(
         RowId::LifeBarOptions,
         lookup_key("PlayerOptions", "LifeBarOptions"),
@@ -1406,6 +1544,18 @@ pub(super) fn build_advanced_rows(return_screen: Screen, scorebox_available: boo
             tr("PlayerOptions", "ScorePositionStepStatistics").to_string(),
         ],
     ));
+    b.push(Row::cycle(
+        RowId::ScoringModel,
+        lookup_key("PlayerOptions", "ScoringModel"),
+        lookup_key("PlayerOptionsHelp", "ScoringModelHelp"),
+        CycleBinding::Index(SCORING_MODEL),
+        vec![
+            tr("PlayerOptions", "ScoringModelOff").to_string(),
+            tr("PlayerOptions", "ScoringModelDdrMoney").to_string(),
+            tr("PlayerOptions", "ScoringModelWife3").to_string(),
+            tr("PlayerOptions", "ScoringModelCustom").to_string(),
+        ],
+    ));
     b.push(Row::cycle(
         RowId::ActionOnMissedTarget,
         lookup_key("PlayerOptions", "TargetScoreMissPolicy"),
@@ -1930,6 +2080,32 @@ pub(super) fn build_advanced_rows(return_screen: Screen, scorebox_available: boo
             tr("PlayerOptions", "TimingWindowsFantasticsAndExcellents").to_string(),
         ],
     ));
+    b.push(Row::numeric(
+        RowId::TimingWindowScale,
+        lookup_key("PlayerOptions", "TimingWindowScale"),
+        lookup_key("PlayerOptionsHelp", "TimingWindowScaleHelp"),
+        TIMING_WINDOW_SCALE,
+        (deadsync_profile::TIMING_WINDOW_SCALE_MIN..=deadsync_profile::TIMING_WINDOW_SCALE_MAX)
+            .step_by(usize::from(deadsync_profile::TIMING_WINDOW_SCALE_STEP))
+            .map(|v| format!("{v}%"))
+            .collect(),
+    ));
+    for (id, name) in TIMING_WINDOW_OVERRIDE_ROWS.into_iter().zip([
+        "TimingWindowOverrideW1",
+        "TimingWindowOverrideW2",
+        "TimingWindowOverrideW3",
+        "TimingWindowOverrideW4",
+        "TimingWindowOverrideW5",
+        "TimingWindowOverrideMine",
+    ]) {
+        b.push(Row::custom(
+            id,
+            lookup_key("PlayerOptions", name),
+            lookup_key("PlayerOptionsHelp", "TimingWindowOverrideHelp"),
+            TIMING_WINDOW_OVERRIDE,
+            timing_window_override_choices(),
+        ));
+    }
     b.push(Row::bitmask(
         RowId::FAPlusOptions,
         lookup_key("PlayerOptions", "FAPlusOptions"),
@@ -382,6 +382,21 @@ pub(super) fn apply_profile_defaults(
             row.selected_choice_index[player_idx] = idx;
         }
     }
+    for (slot, &id) in TIMING_WINDOW_OVERRIDE_ROWS.iter().enumerate() {
+        let Some(row) = row_map.get_mut(id) else {
+            continue;
+        };
+        row.selected_choice_index[player_idx] = match profile.timing_window_overrides.slot(slot) {
+            Some(ms) => {
+                let target = format!("{ms}ms");
+                row.choices
+                    .iter()
+                    .position(|c| c.as_ref() == target)
+                    .unwrap_or(0)
+            }
+            None => 0,
+        };
+    }
     if let Some(row) = row_map.get_mut(RowId::CrossoverCueDuration) {
         let ms =
             deadsync_profile::clamp_crossover_cue_duration_ms(profile.crossover_cue_duration_ms);
@@ -355,6 +355,16 @@ pub(super) fn custom_fantastic_window_choices() -> Vec<String> {
     out
 }
 
+pub(super) fn timing_window_override_choices() -> Vec<String> {
+    let hi = deadsync_profile::TIMING_WINDOW_OVERRIDE_MAX_MS;
+    let mut out = Vec::with_capacity(usize::from(hi) + 1);
+    out.push(tr("Common", "Off").to_string());
+    for ms in 1..=hi {
+        out.push(format!("{ms}ms"));
+    }
+    out
+}
+
 pub(super) fn crossover_cue_duration_choices() -> Vec<String> {
     let lo = deadsync_profile::CROSSOVER_CUE_DURATION_MIN_MS;
     let hi = deadsync_profile::CROSSOVER_CUE_DURATION_MAX_MS;
@@ -41,11 +41,15 @@ pub enum RowId {
     Scroll,
     Hide,
     LifeMeterType,
+    LifeType,
+    BatteryLives,
+    BatteryRewardLives,
     LifeBarOptions,
     DataVisualizations,
     StepStatsExtra,
     ScoreDisplay,
     ScorePosition,
+    ScoringModel,
     DensityGraphBackground,
     ActionOnMissedTarget,
     MiniIndicator,
@@ -98,6 +102,13 @@ pub enum RowId {
     ResultsExtras,
     ScatterplotMaxWindow,
     TimingWindows,
+    TimingWindowScale,
+    TimingWindowOverrideW1,
+    TimingWindowOverrideW2,
+    TimingWindowOverrideW3,
+    TimingWindowOverrideW4,
+    TimingWindowOverrideW5,
+    TimingWindowOverrideMine,
     FAPlusOptions,
     FAPlusWindowOptions,
     CustomBlueFantasticWindow,
@@ -1109,10 +1120,12 @@ pub(super) fn row_shows_all_choices_inline(id: RowId) -> bool {
         || id == RowId::Scroll
         || id == RowId::Hide
         || id == RowId::LifeMeterType
+        || id == RowId::LifeType
         || id == RowId::LifeBarOptions
         || id == RowId::DataVisualizations
         || id == RowId::ScoreDisplay
         || id == RowId::ScorePosition
+        || id == RowId::ScoringModel
         || id == RowId::DensityGraphBackground
         || id == RowId::ComboColors
         || id == RowId::ComboColorMode
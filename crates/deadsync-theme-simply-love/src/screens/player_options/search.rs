@@ -119,6 +119,18 @@ fn row_aliases(id: RowId) -> &'static [&'static str] {
         RowId::ErrorBar => &["error bar", "timing", "offset"],
         RowId::MeasureCounter => &["measure", "counter", "stream"],
         RowId::LifeMeterType => &["life", "health", "bar"],
+        RowId::LifeType | RowId::BatteryLives | RowId::BatteryRewardLives => {
+            &["battery", "lives", "life"]
+        }
+        RowId::TimingWindows
+        | RowId::TimingWindowScale
+        | RowId::TimingWindowOverrideW1
+        | RowId::TimingWindowOverrideW2
+        | RowId::TimingWindowOverrideW3
+        | RowId::TimingWindowOverrideW4
+        | RowId::TimingWindowOverrideW5
+        | RowId::TimingWindowOverrideMine => &["timing", "windows", "judge"],
+        RowId::ScoringModel => &["ddr", "money", "wife", "etterna", "scoring"],
         RowId::JudgmentFont => &["judgment", "judgement", "font"],
         RowId::ComboFont => &["combo", "font"],
         RowId::HeartRateMonitor => &["heart rate", "hr", "bpm"],
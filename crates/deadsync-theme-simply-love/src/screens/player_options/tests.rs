@@ -4130,6 +4130,10 @@ pub(super) mod tests {
         let p = &mut state.player_options[P1];
         p.turn_option = super::TURN_OPTION_VARIANTS[1];
         p.lifemeter_type = super::LIFE_METER_TYPE_VARIANTS[1];
+        p.life_type = super::LIFE_TYPE_VARIANTS[1];
+        p.battery_lives = 7;
+        p.battery_reward_lives = 2;
+        p.scoring_model = super::SCORING_MODEL_VARIANTS[2];
         p.step_statistics = StepStatisticsMask::SONG_BANNER | StepStatisticsMask::STEP_COUNTS;
         p.score_position = super::SCORE_POSITION_VARIANTS[1];
         p.score_display_mode = super::SCORE_DISPLAY_MODE_VARIANTS[1];
@@ -4147,6 +4151,8 @@ pub(super) mod tests {
         p.measure_counter = super::MEASURE_COUNTER_VARIANTS[1];
         p.measure_lines = super::MEASURE_LINES_VARIANTS[1];
         p.timing_windows = super::TIMING_WINDOWS_VARIANTS[1];
+        p.timing_window_scale = 75;
+        p.timing_window_overrides.windows_ms[0] = Some(15);
         p.transparent_density_graph_bg = true;
         p.carry_combo_between_songs = true;
         p.judgment_tilt = true;
@@ -4197,6 +4203,20 @@ pub(super) mod tests {
             &super::LIFE_METER_TYPE_VARIANTS,
             profile.lifemeter_type,
         );
+        assert_variant_at_cursor(
+            &row_map,
+            RowId::LifeType,
+            &super::LIFE_TYPE_VARIANTS,
+            profile.life_type,
+        );
+        assert_choice_at_cursor(&row_map, RowId::BatteryLives, "7");
+        assert_choice_at_cursor(&row_map, RowId::BatteryRewardLives, "2");
+        assert_variant_at_cursor(
+            &row_map,
+            RowId::ScoringModel,
+            &super::SCORING_MODEL_VARIANTS,
+            profile.scoring_model,
+        );
         assert_eq!(masks.step_statistics, profile.step_statistics);
         assert_eq!(
             row_map
@@ -4302,6 +4322,9 @@ pub(super) mod tests {
             &super::TIMING_WINDOWS_VARIANTS,
             profile.timing_windows,
         );
+        assert_choice_at_cursor(&row_map, RowId::TimingWindowScale, "75%");
+        assert_choice_at_cursor(&row_map, RowId::TimingWindowOverrideW1, "15ms");
+        assert_choice_at_cursor(&row_map, RowId::TimingWindowOverrideW2, "Off");
 
         for id in [
             RowId::DensityGraphBackground,
@@ -37,6 +37,7 @@ pub(super) struct RowVisibility {
     pub(super) show_smx_bg_pack: bool,
     pub(super) show_smx_judge_pack: bool,
     pub(super) show_max_heart_rate: bool,
+    pub(super) show_battery_lives: bool,
 }
 
 #[inline(always)]
@@ -168,6 +169,9 @@ pub(super) fn row_visible_with_flags(id: RowId, visibility: RowVisibility) -> bo
     if id == RowId::MaxHeartRate {
         return visibility.show_max_heart_rate;
     }
+    if id == RowId::BatteryLives || id == RowId::BatteryRewardLives {
+        return visibility.show_battery_lives;
+    }
     true
 }
 
@@ -258,6 +262,9 @@ pub(super) fn conditional_row_parent(id: RowId) -> Option<RowId> {
     if id == RowId::MaxHeartRate {
         return Some(RowId::HeartRateMonitor);
     }
+    if id == RowId::BatteryLives || id == RowId::BatteryRewardLives {
+        return Some(RowId::LifeType);
+    }
     None
 }
 
@@ -731,6 +738,16 @@ pub(super) fn max_heart_rate_visible(row_map: &RowMap, active: [bool; PLAYER_SLO
         .any(|player_idx| selected_choice(row_map, RowId::HeartRateMonitor, player_idx) != Some(0))
 }
 
+/// Battery Lives and Battery Life Gain only apply when some active player
+/// picked the Battery life type.
+pub(super) fn battery_lives_visible(row_map: &RowMap, active: [bool; PLAYER_SLOTS]) -> bool {
+    if row_map.get(RowId::LifeType).is_none() {
+        return false;
+    }
+    active_player_indices(active)
+        .any(|player_idx| selected_choice(row_map, RowId::LifeType, player_idx) == Some(1))
+}
+
 #[inline(always)]
 pub(super) fn row_visibility(
     row_map: &RowMap,
@@ -782,6 +799,7 @@ pub(super) fn row_visibility(
         show_smx_bg_pack: policy.smx_input && policy.smx_panel_lights,
         show_smx_judge_pack: policy.smx_input && policy.smx_panel_lights,
         show_max_heart_rate: max_heart_rate_visible(row_map, active),
+        show_battery_lives: battery_lives_visible(row_map, active),
     }
 }
 
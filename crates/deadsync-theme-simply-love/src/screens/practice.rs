@@ -24,6 +24,9 @@ use deadsync_rules::timing::{SpeedSegment, SpeedUnit, TimingSegments};
 use std::path::Path;
 use std::sync::Arc;
 
+#[path = "practice/hud_editor.rs"]
+mod hud_editor;
+
 const LEAD_IN_SECONDS: f32 = 1.0;
 const LOOP_AFTER_SECONDS: f32 = 1.0;
 const BEATS_PER_MEASURE: f32 = 4.0;
@@ -359,6 +362,7 @@ pub struct State {
     flash: Option<(Arc<str>, f32)>,
     pending_sfx: Vec<&'static str>,
     pending_profile: Vec<crate::SimplyLoveProfileRequest>,
+    hud_editor: Option<hud_editor::HudEditor>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -423,6 +427,10 @@ static MAIN_MENU: MenuDef = MenuDef {
             label: lookup_key("Practice", "MenuPracticeModeOptions"),
             action: Some(action_editor_options),
         },
+        MenuRow {
+            label: lookup_key("Practice", "MenuHudLayout"),
+            action: Some(action_hud_layout),
+        },
         MenuRow {
             label: lookup_key("Practice", "MenuExit"),
             action: Some(action_exit_practice),
@@ -555,6 +563,7 @@ pub fn init(mut gameplay: gameplay_screen::State, runtime: PracticeRuntimeView)
         flash: None,
         pending_sfx: Vec::with_capacity(8),
         pending_profile: Vec::with_capacity(4),
+        hud_editor: None,
     };
     set_cursor(&mut state, MIN_CURSOR_BEAT);
     snap_display_to_cursor(&mut state);
@@ -676,6 +685,7 @@ fn update_inner(
     } = state.mode
     else {
         state.gameplay.advance_screen_elapsed(delta_time);
+        hud_editor::update(state, delta_time);
         update_cursor_hold(state, delta_time);
         update_page_hold(state, delta_time);
         update_display_scroll(state, delta_time);
@@ -716,6 +726,9 @@ fn handle_input_inner(
     ev: &InputEvent,
     snap_music_start: MusicStartSnap,
 ) -> ThemeEffect {
+    if state.hud_editor.is_some() {
+        return hud_editor::handle_input(state, ev);
+    }
     if state.menu.is_some() {
         return handle_menu_input(state, ev, snap_music_start);
     }
@@ -859,6 +872,13 @@ fn handle_raw_key_event_inner(
         return (true, ThemeEffect::None);
     }
 
+    if state.hud_editor.is_some() {
+        return (
+            hud_editor::handle_raw_key(state, raw_key),
+            ThemeEffect::None,
+        );
+    }
+
     if matches!(state.mode, Mode::Playing { .. }) {
         return match raw_key.code {
             KeyCode::Escape | KeyCode::Enter => {
@@ -1065,7 +1085,9 @@ pub fn push_actors(
         arrow_effect_time_s,
         visual_policy,
     );
-    if matches!(state.mode, Mode::Editing) {
+    if state.hud_editor.is_some() {
+        hud_editor::append_overlay(state, actors);
+    } else if matches!(state.mode, Mode::Editing) {
         append_edit_markers(state, actors);
         append_edit_overlay(state, actors);
     }
@@ -1079,6 +1101,9 @@ pub fn push_actors(
 }
 
 fn practice_view(state: &State) -> gameplay_screen::ActorViewOverride {
+    if state.hud_editor.is_some() {
+        return hud_editor::view();
+    }
     let mut notefield = if matches!(state.mode, Mode::Editing) {
         practice_notefield_view(state)
     } else {
@@ -1244,6 +1269,11 @@ fn action_editor_options(_state: &mut State, _snap_music_start: MusicStartSnap)
     ThemeEffect::Navigate(Screen::PlayerOptions)
 }
 
+fn action_hud_layout(state: &mut State, _snap_music_start: MusicStartSnap) -> ThemeEffect {
+    hud_editor::open(state);
+    ThemeEffect::None
+}
+
 fn action_exit_practice(_state: &mut State, _snap_music_start: MusicStartSnap) -> ThemeEffect {
     ThemeEffect::Navigate(Screen::SelectMusic)
 }
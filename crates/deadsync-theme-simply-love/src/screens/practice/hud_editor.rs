@@ -0,0 +1,512 @@
+//! Practice-mode editor for the per-profile HUD layout.
+//!
+//! The field switches to its normal gameplay geometry while the editor is
+//! open, so offsets are judged against what the player will see. Edits go
+//! straight to the running notefield plan; Start writes `hud_layout.ini` for
+//! every player whose layout changed and Back puts the opening layouts back.
+
+use super::{FLASH_DURATION_SECS, State, practice_player_color, queue_sfx};
+use crate::act;
+use crate::assets::i18n;
+use crate::screens::ThemeEffect;
+use crate::screens::components::gameplay::gameplay_stats;
+use crate::screens::gameplay as gameplay_screen;
+use deadlib_present::actors::Actor;
+use deadlib_present::space::{screen_center_x, screen_height, screen_width};
+use deadsync_core::input::MAX_PLAYERS;
+use deadsync_input::{InputEvent, KeyCode, PadDir, RawKeyboardEvent, VirtualAction};
+use deadsync_theme::{HudElement, HudElementLayout, HudLayout};
+use std::sync::Arc;
+
+const HUD_EDITOR_Z: f32 = 2990.0;
+const NUDGE_UNITS: f32 = 1.0;
+const DRAG_UNITS_PER_SECOND: f32 = 120.0;
+const DRAG_DELAY_SECONDS: f32 = 0.25;
+const FAST_MULTIPLIER: f32 = 4.0;
+const ZOOM_STEP: f32 = 0.05;
+const OUTLINE_THICKNESS: f32 = 1.5;
+const STEP_STATS_BOX_HEIGHT: f32 = 220.0;
+const SAVE_SOUND: &str = "assets/sounds/start.ogg";
+const CHANGE_SOUND: &str = "assets/sounds/change.ogg";
+
+/// Outline sizes at zoom 1; the real sprites vary by skin, so these only
+/// need to frame the anchor recognisably.
+const fn element_box(element: HudElement) -> [f32; 2] {
+    match element {
+        HudElement::Judgment => [180.0, 56.0],
+        HudElement::ErrorBar => [240.0, 18.0],
+        HudElement::MeasureCounter => [90.0, 28.0],
+        HudElement::MiniIndicator => [90.0, 28.0],
+        HudElement::StepStats => [0.0, STEP_STATS_BOX_HEIGHT],
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct StatusKey {
+    player_idx: usize,
+    element: HudElement,
+    x: i32,
+    y: i32,
+    zoom_pct: i32,
+    hidden: bool,
+}
+
+pub(super) struct HudEditor {
+    player_idx: usize,
+    selected: HudElement,
+    /// Layouts in effect when the editor opened; Back restores them.
+    saved: [HudLayout; MAX_PLAYERS],
+    /// Seconds each direction has been held, indexed like [`dir_index`].
+    held: [Option<f32>; 4],
+    names: [Arc<str>; HudElement::ALL.len()],
+    help: Arc<str>,
+    status: Option<(StatusKey, Arc<str>)>,
+}
+
+pub(super) fn open(state: &mut State) {
+    let saved = std::array::from_fn(|idx| {
+        if idx < state.gameplay.num_players() {
+            *state.gameplay.notefield_plan(idx).hud_layout()
+        } else {
+            HudLayout::IDENTITY
+        }
+    });
+    let names = HudElement::ALL
+        .map(|element| i18n::tr("Practice", &format!("HudElement{}", element.name())));
+    state.hud_editor = Some(HudEditor {
+        player_idx: 0,
+        selected: HudElement::Judgment,
+        saved,
+        held: [None; 4],
+        names,
+        help: i18n::tr("Practice", "HudEditorHelp"),
+        status: None,
+    });
+}
+
+pub(super) fn view() -> gameplay_screen::ActorViewOverride {
+    gameplay_screen::ActorViewOverride {
+        notefield: gameplay_screen::NotefieldViewOverride {
+            hide_display_mods: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+pub(super) fn handle_input(state: &mut State, ev: &InputEvent) -> ThemeEffect {
+    let action = ev.action.primary_from_menu_alias().unwrap_or(ev.action);
+    if let Some(dir) = deadsync_input::pad_dir_from_action(action) {
+        let player_idx = player_for_action(state, action);
+        let Some(editor) = state.hud_editor.as_mut() else {
+            return ThemeEffect::None;
+        };
+        if !ev.pressed {
+            editor.held[dir_index(dir)] = None;
+            return ThemeEffect::None;
+        }
+        editor.player_idx = player_idx;
+        editor.held[dir_index(dir)] = Some(0.0);
+        let units = nudge_units(state.shift_held);
+        edit_selected(state, |layout| nudge(layout, dir, units));
+        return ThemeEffect::None;
+    }
+    if !ev.pressed {
+        return ThemeEffect::None;
+    }
+    match action {
+        VirtualAction::p1_select | VirtualAction::p2_select => {
+            let player_idx = player_for_action(state, action);
+            if let Some(editor) = state.hud_editor.as_mut() {
+                editor.player_idx = player_idx;
+            }
+            cycle_element(state, 1);
+        }
+        VirtualAction::p1_start | VirtualAction::p2_start => save(state),
+        VirtualAction::p1_back | VirtualAction::p2_back => cancel(state),
+        _ => {}
+    }
+    ThemeEffect::None
+}
+
+/// Keyboard-only edits; arrows fall through to the mapped virtual actions.
+pub(super) fn handle_raw_key(state: &mut State, raw_key: &RawKeyboardEvent) -> bool {
+    if !raw_key.pressed {
+        return false;
+    }
+    match raw_key.code {
+        KeyCode::Minus | KeyCode::NumpadSubtract => {
+            edit_selected(state, |layout| layout.zoom = stepped_zoom(layout.zoom, -1));
+        }
+        KeyCode::Equal | KeyCode::NumpadAdd => {
+            edit_selected(state, |layout| layout.zoom = stepped_zoom(layout.zoom, 1));
+        }
+        KeyCode::KeyH if !raw_key.repeat => {
+            edit_selected(state, |layout| layout.hidden = !layout.hidden);
+            queue_sfx(state, CHANGE_SOUND);
+        }
+        KeyCode::Backspace if !raw_key.repeat => {
+            edit_selected(state, |layout| *layout = HudElementLayout::IDENTITY);
+            queue_sfx(state, CHANGE_SOUND);
+        }
+        KeyCode::PageUp => cycle_element(state, -1),
+        KeyCode::PageDown => cycle_element(state, 1),
+        KeyCode::Enter if !raw_key.repeat => save(state),
+        KeyCode::Escape if !raw_key.repeat => cancel(state),
+        KeyCode::Minus
+        | KeyCode::NumpadSubtract
+        | KeyCode::Equal
+        | KeyCode::NumpadAdd
+        | KeyCode::KeyH
+        | KeyCode::Backspace
+        | KeyCode::Enter
+        | KeyCode::Escape => {}
+        _ => return false,
+    }
+    true
+}
+
+pub(super) fn update(state: &mut State, delta_time: f32) {
+    let Some(editor) = state.hud_editor.as_mut() else {
+        return;
+    };
+    let mut delta = [0.0; 2];
+    for (idx, held) in editor.held.iter_mut().enumerate() {
+        let Some(seconds) = held.as_mut() else {
+            continue;
+        };
+        let before = *seconds;
+        *seconds += delta_time;
+        let moving = (*seconds - before.max(DRAG_DELAY_SECONDS)).max(0.0);
+        let [dx, dy] = dir_delta(DIRS[idx]);
+        delta[0] += dx * moving;
+        delta[1] += dy * moving;
+    }
+    if delta == [0.0; 2] {
+        return;
+    }
+    let speed = DRAG_UNITS_PER_SECOND * nudge_units(state.shift_held);
+    edit_selected(state, |layout| {
+        layout.offset[0] += delta[0] * speed;
+        layout.offset[1] += delta[1] * speed;
+    });
+}
+
+pub(super) fn append_overlay(state: &mut State, actors: &mut Vec<Actor>) {
+    let Some(editor) = state.hud_editor.as_ref() else {
+        return;
+    };
+    let selected_color = practice_player_color(state);
+    for player_idx in 0..state.gameplay.num_players() {
+        let layout = *state.gameplay.notefield_plan(player_idx).hud_layout();
+        for element in HudElement::ALL {
+            let Some((center, size)) = element_frame(state, player_idx, element, &layout) else {
+                continue;
+            };
+            let selected = player_idx == editor.player_idx && element == editor.selected;
+            let mut color = if selected {
+                selected_color
+            } else {
+                [1.0, 1.0, 1.0, 0.6]
+            };
+            if layout.get(element).hidden {
+                color[3] *= 0.35;
+            }
+            append_outline(actors, center, size, color);
+            let name = editor.names[element.index()].clone();
+            actors.push(act!(text:
+                font("miso"):
+                settext(name):
+                align(0.5, 1.0):
+                xy(center[0], center[1] - size[1] * 0.5 - 2.0):
+                zoom(0.6):
+                diffuse(color[0], color[1], color[2], color[3].max(0.5)):
+                shadowlength(1.0):
+                z(HUD_EDITOR_Z + 1.0)
+            ));
+        }
+    }
+    let status = sync_status(state);
+    let Some(editor) = state.hud_editor.as_ref() else {
+        return;
+    };
+    let help = editor.help.clone();
+    actors.push(act!(quad:
+        align(0.5, 1.0):
+        xy(screen_center_x(), screen_height()):
+        zoomto(screen_width(), 44.0):
+        diffuse(0.0, 0.0, 0.0, 0.7):
+        z(HUD_EDITOR_Z)
+    ));
+    actors.push(act!(text:
+        font("miso"):
+        settext(status):
+        align(0.5, 1.0):
+        xy(screen_center_x(), screen_height() - 24.0):
+        zoom(0.8):
+        diffuse(selected_color[0], selected_color[1], selected_color[2], 1.0):
+        shadowlength(1.0):
+        z(HUD_EDITOR_Z + 1.0)
+    ));
+    actors.push(act!(text:
+        font("miso"):
+        settext(help):
+        align(0.5, 1.0):
+        xy(screen_center_x(), screen_height() - 6.0):
+        zoom(0.6):
+        maxwidth(screen_width() / 0.6 - 20.0):
+        shadowlength(1.0):
+        z(HUD_EDITOR_Z + 1.0)
+    ));
+}
+
+fn element_frame(
+    state: &State,
+    player_idx: usize,
+    element: HudElement,
+    layout: &HudLayout,
+) -> Option<([f32; 2], [f32; 2])> {
+    let anchors = state.gameplay.notefield_hud_anchors(player_idx);
+    let zoom = layout.get(element).zoom;
+    let [w, h] = element_box(element);
+    let center = match element {
+        HudElement::Judgment => anchors.judgment,
+        HudElement::ErrorBar => anchors.error_bar,
+        HudElement::MeasureCounter => anchors.measure_counter?,
+        HudElement::MiniIndicator => anchors.mini_indicator,
+        HudElement::StepStats => {
+            // The pane width already carries the element zoom.
+            let (center, width) = gameplay_stats::step_stats_anchor(&state.gameplay, player_idx)?;
+            return Some((center, [width, h * zoom]));
+        }
+    };
+    Some((center, [w * zoom, h * zoom]))
+}
+
+fn append_outline(actors: &mut Vec<Actor>, center: [f32; 2], size: [f32; 2], color: [f32; 4]) {
+    let [x, y] = center;
+    let [w, h] = size;
+    let t = OUTLINE_THICKNESS;
+    for (ex, ey, ew, eh) in [
+        (x, y - h * 0.5, w, t),
+        (x, y + h * 0.5, w, t),
+        (x - w * 0.5, y, t, h),
+        (x + w * 0.5, y, t, h),
+    ] {
+        actors.push(act!(quad:
+            align(0.5, 0.5):
+            xy(ex, ey):
+            zoomto(ew, eh):
+            diffuse(color[0], color[1], color[2], color[3]):
+            z(HUD_EDITOR_Z)
+        ));
+    }
+}
+
+fn sync_status(state: &mut State) -> Arc<str> {
+    let Some(editor) = state.hud_editor.as_ref() else {
+        return Arc::from("");
+    };
+    let element = state
+        .gameplay
+        .notefield_plan(editor.player_idx)
+        .hud_layout()
+        .get(editor.selected);
+    let key = StatusKey {
+        player_idx: editor.player_idx,
+        element: editor.selected,
+        x: element.offset[0].round() as i32,
+        y: element.offset[1].round() as i32,
+        zoom_pct: (element.zoom * 100.0).round() as i32,
+        hidden: element.hidden,
+    };
+    if let Some((cached, text)) = editor.status.as_ref()
+        && *cached == key
+    {
+        return text.clone();
+    }
+    let player = (key.player_idx + 1).to_string();
+    let x = format!("{:+}", key.x);
+    let y = format!("{:+}", key.y);
+    let zoom = format!("{:.2}", element.zoom);
+    let status_key = if key.hidden {
+        "HudEditorStatusHidden"
+    } else {
+        "HudEditorStatus"
+    };
+    let text = i18n::tr_fmt(
+        "Practice",
+        status_key,
+        &[
+            ("player", &player),
+            ("element", &editor.names[key.element.index()]),
+            ("x", &x),
+            ("y", &y),
+            ("zoom", &zoom),
+        ],
+    );
+    if let Some(editor) = state.hud_editor.as_mut() {
+        editor.status = Some((key, text.clone()));
+    }
+    text
+}
+
+fn edit_selected(state: &mut State, edit: impl FnOnce(&mut HudElementLayout)) {
+    let Some(editor) = state.hud_editor.as_ref() else {
+        return;
+    };
+    let (player_idx, element) = (editor.player_idx, editor.selected);
+    let mut layout = *state.gameplay.notefield_plan(player_idx).hud_layout();
+    edit(layout.get_mut(element));
+    state.gameplay.set_notefield_hud_layout(player_idx, layout);
+}
+
+fn cycle_element(state: &mut State, delta: isize) {
+    let Some(editor) = state.hud_editor.as_mut() else {
+        return;
+    };
+    let len = HudElement::ALL.len() as isize;
+    let next = (editor.selected.index() as isize + delta).rem_euclid(len) as usize;
+    editor.selected = HudElement::ALL[next];
+    editor.held = [None; 4];
+    queue_sfx(state, CHANGE_SOUND);
+}
+
+fn save(state: &mut State) {
+    let Some(editor) = state.hud_editor.take() else {
+        return;
+    };
+    let mut changed = false;
+    for player_idx in 0..state.gameplay.num_players() {
+        let layout = *state.gameplay.notefield_plan(player_idx).hud_layout();
+        if layout == editor.saved[player_idx] {
+            continue;
+        }
+        changed = true;
+        state
+            .pending_profile
+            .push(crate::SimplyLoveProfileRequest::SaveHudLayout {
+                side: gameplay_screen::runtime_profile_side(&state.gameplay, player_idx),
+                layout,
+            });
+    }
+    queue_sfx(state, SAVE_SOUND);
+    if changed {
+        state.flash = Some((
+            i18n::tr("Practice", "FlashHudLayoutSaved"),
+            FLASH_DURATION_SECS,
+        ));
+    }
+}
+
+fn cancel(state: &mut State) {
+    let Some(editor) = state.hud_editor.take() else {
+        return;
+    };
+    for player_idx in 0..state.gameplay.num_players() {
+        state
+            .gameplay
+            .set_notefield_hud_layout(player_idx, editor.saved[player_idx]);
+    }
+    queue_sfx(state, SAVE_SOUND);
+}
+
+/// P2 buttons edit the second field in versus; a lone player owns every
+/// button.
+fn player_for_action(state: &State, action: VirtualAction) -> usize {
+    let current = state
+        .hud_editor
+        .as_ref()
+        .map_or(0, |editor| editor.player_idx);
+    if state.gameplay.num_players() < 2 {
+        return current;
+    }
+    let side = if is_p2_action(action) {
+        deadsync_profile::PlayerSide::P2
+    } else {
+        deadsync_profile::PlayerSide::P1
+    };
+    (0..state.gameplay.num_players())
+        .find(|&idx| gameplay_screen::runtime_profile_side(&state.gameplay, idx) == side)
+        .unwrap_or(current)
+}
+
+const fn is_p2_action(action: VirtualAction) -> bool {
+    matches!(
+        action,
+        VirtualAction::p2_up
+            | VirtualAction::p2_down
+            | VirtualAction::p2_left
+            | VirtualAction::p2_right
+            | VirtualAction::p2_start
+            | VirtualAction::p2_back
+            | VirtualAction::p2_select
+    )
+}
+
+const DIRS: [PadDir; 4] = [PadDir::Left, PadDir::Right, PadDir::Up, PadDir::Down];
+
+const fn dir_index(dir: PadDir) -> usize {
+    match dir {
+        PadDir::Left => 0,
+        PadDir::Right => 1,
+        PadDir::Up => 2,
+        PadDir::Down => 3,
+    }
+}
+
+const fn dir_delta(dir: PadDir) -> [f32; 2] {
+    match dir {
+        PadDir::Left => [-1.0, 0.0],
+        PadDir::Right => [1.0, 0.0],
+        PadDir::Up => [0.0, -1.0],
+        PadDir::Down => [0.0, 1.0],
+    }
+}
+
+const fn nudge_units(fast: bool) -> f32 {
+    if fast {
+        NUDGE_UNITS * FAST_MULTIPLIER
+    } else {
+        NUDGE_UNITS
+    }
+}
+
+fn nudge(layout: &mut HudElementLayout, dir: PadDir, units: f32) {
+    let [dx, dy] = dir_delta(dir);
+    layout.offset[0] = (layout.offset[0] + dx * units).round();
+    layout.offset[1] = (layout.offset[1] + dy * units).round();
+}
+
+/// Snaps to the zoom grid so repeated presses land on tidy values.
+fn stepped_zoom(zoom: f32, steps: i32) -> f32 {
+    ((zoom / ZOOM_STEP).round() + steps as f32) * ZOOM_STEP
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DIRS, dir_index, nudge, stepped_zoom};
+    use deadsync_input::PadDir;
+    use deadsync_theme::HudElementLayout;
+
+    #[test]
+    fn nudges_snap_to_whole_units() {
+        let mut layout = HudElementLayout {
+            offset: [2.4, -0.6],
+            ..HudElementLayout::IDENTITY
+        };
+        nudge(&mut layout, PadDir::Up, 1.0);
+        assert_eq!(layout.offset, [2.0, -2.0]);
+        nudge(&mut layout, PadDir::Right, 4.0);
+        assert_eq!(layout.offset, [6.0, -2.0]);
+        for (idx, dir) in DIRS.into_iter().enumerate() {
+            assert_eq!(dir_index(dir), idx);
+        }
+    }
+
+    #[test]
+    fn zoom_steps_land_on_grid() {
+        assert!((stepped_zoom(1.0, 1) - 1.05).abs() < 1e-5);
+        assert!((stepped_zoom(0.93, -1) - 0.9).abs() < 1e-5);
+    }
+}
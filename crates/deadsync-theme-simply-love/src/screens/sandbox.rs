@@ -21,15 +21,41 @@ const INPUT_LOG_FADE_DURATION: f32 = 5.0; // seconds to fade out
 const TRANSITION_IN_DURATION: f32 = 0.4;
 const TRANSITION_OUT_DURATION: f32 = 0.4;
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LatencyTailView {
+    pub samples: u32,
+    pub p50_us: u32,
+    pub p95_us: u32,
+    pub p99_us: u32,
+    pub max_us: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LatencyDeviceView {
+    pub device: String,
+    pub judgment: LatencyTailView,
+    pub photon: LatencyTailView,
+    pub audio: LatencyTailView,
+}
+
+/// Per-device input latency percentiles, refreshed by the shell.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LatencyReportView {
+    pub enabled: bool,
+    pub devices: Vec<LatencyDeviceView>,
+}
+
 pub struct State {
     pub elapsed: f32,
     pub last_inputs: VecDeque<(String, Instant)>,
+    pub latency: LatencyReportView,
 }
 
 pub fn init() -> State {
     State {
         elapsed: 0.0,
         last_inputs: VecDeque::with_capacity(INPUT_LOG_MAX_ITEMS),
+        latency: LatencyReportView::default(),
     }
 }
 
@@ -57,6 +83,16 @@ pub fn handle_raw_key_event(state: &mut State, key_event: &RawKeyboardEvent) ->
         if matches!(code, KeyCode::F4 | KeyCode::Escape) {
             return ThemeEffect::Navigate(Screen::Menu);
         }
+        if code == KeyCode::F6 {
+            return ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Debug(
+                crate::SimplyLoveDebugRequest::ToggleLatencyReport,
+            ));
+        }
+        if code == KeyCode::F7 {
+            return ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Debug(
+                crate::SimplyLoveDebugRequest::WriteLatencyReport,
+            ));
+        }
         let key_str = format!("Keyboard: KeyCode::{code:?}");
         state.last_inputs.push_front((key_str, Instant::now()));
         if state.last_inputs.len() > INPUT_LOG_MAX_ITEMS {
@@ -98,8 +134,12 @@ pub fn handle_raw_pad_event(state: &mut State, pad_event: &PadEvent) {
                 let dev = usize::from(*id);
                 let code_u32 = code.into_u32();
                 let uuid_hex: String = uuid.iter().map(|b| format!("{b:02X}")).collect();
+                let midi = code
+                    .as_midi_note()
+                    .map(|(channel, note)| format!(" MIDI ch{} note {note}", channel + 1))
+                    .unwrap_or_default();
                 format!(
-                    "Gamepad {dev} [uuid={uuid_hex}]: RAW BTN {{ PadCode[0x{code_u32:08X}], value: {value:.3}, pressed: {pressed} }}",
+                    "Gamepad {dev} [uuid={uuid_hex}]: RAW BTN {{ PadCode[0x{code_u32:08X}],{midi} value: {value:.3}, pressed: {pressed} }}",
                 )
             }
             PadEvent::RawAxis {
@@ -165,7 +205,7 @@ pub fn push_actors(
     state: &State,
     visual_policy: crate::views::SimplyLoveVisualPolicyView,
 ) {
-    actors.reserve(10 + INPUT_LOG_MAX_ITEMS);
+    actors.reserve(12 + INPUT_LOG_MAX_ITEMS + state.latency.devices.len());
 
     actors.push(act!(text:
         align(0.5, 0.0): xy(screen_center_x(), 20.0):
@@ -194,10 +234,65 @@ pub fn push_actors(
             z(200)
         ));
     }
+
+    push_latency_actors(actors, &state.latency);
+}
+
+#[inline(always)]
+fn ms(us: u32) -> f32 {
+    us as f32 / 1000.0
+}
+
+fn latency_tail_text(label: &str, tail: LatencyTailView) -> String {
+    format!(
+        "{label} {:.1}/{:.1}/{:.1}/{:.1}",
+        ms(tail.p50_us),
+        ms(tail.p95_us),
+        ms(tail.p99_us),
+        ms(tail.max_us),
+    )
+}
+
+fn push_latency_actors(actors: &mut Vec<Actor>, latency: &LatencyReportView) {
+    let header = if latency.enabled {
+        "Latency report: RECORDING (F6 stop, F7 write JSON) - ms p50/p95/p99/max"
+    } else {
+        "Latency report: off (F6 start, F7 write JSON) - ms p50/p95/p99/max"
+    };
+    let start_y = 330.0;
+    let line_height = 18.0;
+    actors.push(act!(text:
+        font("miso"):
+        settext(header):
+        align(0.5, 0.0):
+        xy(screen_center_x(), start_y):
+        zoom(0.8):
+        horizalign(center):
+        z(200)
+    ));
+    for (i, device) in latency.devices.iter().enumerate() {
+        let text = format!(
+            "{} ({}): {} | {} | {}",
+            device.device,
+            device.judgment.samples,
+            latency_tail_text("judge", device.judgment),
+            latency_tail_text("photon", device.photon),
+            latency_tail_text("audio", device.audio),
+        );
+        actors.push(act!(text:
+            font("miso"):
+            settext(text):
+            align(0.5, 0.0):
+            xy(screen_center_x(), ((i + 1) as f32).mul_add(line_height, start_y)):
+            zoom(0.7):
+            horizalign(center):
+            z(200)
+        ));
+    }
 }
 
 pub fn get_actors(state: &State) -> Vec<Actor> {
-    let mut actors = Vec::with_capacity(10 + INPUT_LOG_MAX_ITEMS);
+    let mut actors = Vec::with_capacity(12 + INPUT_LOG_MAX_ITEMS + state.latency.devices.len());
     push_actors(&mut actors, state, Default::default());
     actors
 }
@@ -2672,6 +2672,7 @@ pub fn push_actors(
             song_box_color: None,
             song_text_color: Some(COURSE_WHEEL_SONG_TEXT_COLOR),
             song_text_color_overrides: Some(&state.course_text_color_overrides),
+            song_lua_levels: None,
             show_pack_sync: false,
             show_music_wheel_grades: true,
             show_music_wheel_lamps: true,
@@ -3378,6 +3379,8 @@ mod song_lookup_tests {
             folder_series: String::new(),
             year: 0,
             sync_pref: SyncPref::Default,
+            sync_bias_ms: 0.0,
+            sync_bias_written_ms: Vec::new(),
             directory: PathBuf::from("Songs").join(name),
             banner_path: None,
             songs,
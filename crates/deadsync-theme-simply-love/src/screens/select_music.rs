@@ -54,9 +54,13 @@ use deadsync_chart::{
 use deadsync_config::prelude::GameFlag;
 use deadsync_core::input::InputSource;
 use deadsync_input::{
-    InputEvent, KeyCode, Keymap, PadDir, PadEvent, RawKeyboardEvent, VirtualAction, with_keymap,
+    InputEvent, KeyCode, Keymap, PadDir, PadEvent, RawKeyboardEvent, VirtualAction,
+    with_active_keymap,
 };
 use deadsync_online::lobbies as lobby_data;
+use deadsync_online::song_vote::{
+    LobbySongVote, SongVote, SongVoteError, song_vote_key, song_voter_id,
+};
 use deadsync_profile as profile_data;
 use deadsync_profile::favorites_view::ascii_case_insensitive_cmp;
 use deadsync_profile::pad_config_sync::{AppliedPadConfig, PadConfigIntent};
@@ -66,12 +70,14 @@ use deadsync_simfile::playlist::{
     PlaylistEntry, PlaylistSongLookup, PlaylistSongSource,
     normalize_song_path as normalize_lobby_song_path, song_pack_and_dir_name,
 };
+use deadsync_simfile::song_query::SongQueryPlayerState;
 use deadsync_simfile::song_sort::{
     GroupedSongs, SongSortGroup, alpha_group_char, artist_grouped_songs, bpm_grouped_songs,
     genre_grouped_songs, length_grouped_songs, meter_grouped_songs, song_title_sort_key,
     title_grouped_songs,
 };
 use deadsync_simfile::sync_offset::SongOffsetSyncChange;
+use deadsync_song_lua::SongLuaCompatibilityLevel;
 use deadsync_theme::views::{AudioPlaybackView, SmxAssignmentPadView, SmxAssignmentView};
 use deadsync_theme::{AudioCut, AudioRequest};
 use image::{Rgba, RgbaImage};
@@ -1147,6 +1153,12 @@ enum ManualSyncTarget {
         pack_name: String,
         simfile_paths: Vec<PathBuf>,
     },
+    /// Adjusts the pack's `PackSync.ini` bias instead of its simfiles.
+    PackBias {
+        pack_name: String,
+        pack_dir: PathBuf,
+        bias_ms: f32,
+    },
 }
 
 struct ManualSyncOverlayData {
@@ -1181,6 +1193,7 @@ enum WheelSortMode {
     TopGradesP1,
     TopGradesP2,
     Favorites(profile_data::PlayerSide),
+    Recommended(profile_data::PlayerSide),
     Playlist,
 }
 
@@ -1671,9 +1684,20 @@ pub struct State {
     song_search_generation: u64,
     /// Ranking requests queued for the shell's background search worker.
     pending_search: Vec<crate::SimplyLoveSongSearchRequest>,
+    /// Joined players' scores and favorites for `grade:`/`played`/`fc` tokens,
+    /// snapshotted when the search overlay opens.
+    song_search_player: Arc<SongQueryPlayerState>,
     song_search_ignore_next_back_select: bool,
     song_search_ignore_next_text: bool,
     replay_overlay: select_music_menu::ReplayOverlayState,
+    pattern_analysis_overlay: select_music_menu::PatternAnalysisOverlayState,
+    workout_overlay: select_music_menu::WorkoutOverlayState,
+    controller_claim_overlay: select_music_menu::ControllerClaimOverlayState,
+    pack_lint_overlay: select_music_menu::PackLintOverlayState,
+    /// Worst song-lua level per simfile from pack scans, for the wheel badge.
+    song_lua_scan_levels: FxHashMap<PathBuf, SongLuaCompatibilityLevel>,
+    duplicate_songs_overlay: select_music_menu::DuplicateSongsOverlayState,
+    workout_view: [Option<select_music_menu::WorkoutProgressView>; 2],
     lobby_overlay: lobby_overlay::OverlayState,
     sync_overlay: SyncOverlayState,
     pack_sync_overlay: crate::screens::pack_sync::OverlayState,
@@ -1728,6 +1752,8 @@ pub struct State {
     recent_p2_entries: Arc<[MusicWheelEntry]>,
     top_grades_p1_entries: Arc<[MusicWheelEntry]>,
     top_grades_p2_entries: Arc<[MusicWheelEntry]>,
+    /// Entries the active Recommended sort was built from.
+    recommended_source: Arc<[MusicWheelEntry]>,
     /// Union of the joined players' favorites, used only to decide whether the
     /// Favorites menu item is available. The active wheel is built per-player.
     joined_favorites_entries: Arc<[MusicWheelEntry]>,
@@ -1838,6 +1864,9 @@ pub struct State {
     lobby_last_failed_remote_song: Option<FailedLobbySong>,
     lobby_notice_text: Option<String>,
     lobby_notice_time_left: f32,
+    /// Local nominations and bans per side. In a lobby they are published on
+    /// the machine state; otherwise they are the whole P1 vs P2 vote.
+    song_votes: [LobbySongVote; 2],
     lobby_disconnect_hold_p1: Option<Instant>,
     lobby_disconnect_hold_p2: Option<Instant>,
 
@@ -1880,6 +1909,14 @@ fn history_score(
         .map(|index| history.cached_scores[index].1)
 }
 
+fn history_ex_score(history: &SelectMusicHistorySideView, chart_hash: &str) -> Option<f64> {
+    history
+        .local_ex_scores
+        .binary_search_by(|(hash, _)| hash.as_str().cmp(chart_hash))
+        .ok()
+        .map(|index| history.local_ex_scores[index].1)
+}
+
 fn song_has_cached_score(
     song: &SongData,
     history: &SelectMusicHistoryView,
@@ -3115,6 +3152,81 @@ fn build_top_grades_grouped_entries_for_side(
     entries
 }
 
+/// Rates the player against the whole library, then recommends from
+/// `source_entries` only.
+fn build_recommended_entries_for_side(
+    library_entries: &[MusicWheelEntry],
+    source_entries: &[MusicWheelEntry],
+    chart_type: &str,
+    history: &SelectMusicHistorySideView,
+) -> Vec<MusicWheelEntry> {
+    let rating = score_data::skill_rating(
+        songs_from_entries(library_entries).iter().map(Arc::as_ref),
+        chart_type,
+        &history.recent_play_ex,
+    );
+    let Some(rating) = rating else {
+        return single_header_song_entries(
+            tr("SelectMusic", "RecommendedNoRating").to_string(),
+            std::iter::empty(),
+        );
+    };
+    let rating_text = format!("{:.1}", rating.rating);
+    single_header_song_entries(
+        tr_fmt(
+            "SelectMusic",
+            "RecommendedHeader",
+            &[("rating", &rating_text)],
+        )
+        .to_string(),
+        score_data::recommended_songs(
+            songs_from_entries(source_entries),
+            chart_type,
+            rating.rating,
+            |chart_hash| history_ex_score(history, chart_hash),
+        ),
+    )
+}
+
+/// What Recommended draws from: the pack or series under the cursor when a
+/// header is selected, otherwise everything the current sort shows.
+fn recommended_source_entries(state: &State) -> Arc<[MusicWheelEntry]> {
+    let Some(key) = state
+        .entries
+        .get(state.selected_index)
+        .and_then(MusicWheelEntry::section_key)
+    else {
+        return Arc::clone(&state.all_entries);
+    };
+    let mut in_section = false;
+    let mut songs = Vec::new();
+    for entry in state.all_entries.iter() {
+        match entry {
+            MusicWheelEntry::PackHeader { parent_series, .. } => {
+                let starts_section = entry.section_key() == Some(key);
+                let inside_series =
+                    !entry.is_series_header() && parent_series.as_deref() == Some(key);
+                in_section = starts_section || inside_series;
+            }
+            MusicWheelEntry::Song(song) if in_section => songs.push(Arc::clone(song)),
+            MusicWheelEntry::Song(_) => {}
+        }
+    }
+    single_header_song_entries(key.to_string(), songs).into()
+}
+
+fn refresh_recommended_entries(state: &mut State, side: profile_data::PlayerSide) {
+    let history = &state.history.sides[profile_data::player_side_index(side)];
+    state.all_entries = build_recommended_entries_for_side(
+        &state.group_entries,
+        &state.recommended_source,
+        state.session.play_style.chart_type(),
+        history,
+    )
+    .into();
+    state.expanded_pack_name = first_header_name(&state.all_entries);
+}
+
 fn build_favorites_view_entries(
     grouped_entries: &[MusicWheelEntry],
     series_entries: &[MusicWheelEntry],
@@ -3531,6 +3643,14 @@ fn apply_wheel_sort(state: &mut State, sort_mode: WheelSortMode) {
                 .and_then(|song| group_name_for_song(&state.all_entries, song))
                 .or_else(|| first_header_name(&state.all_entries));
         }
+        WheelSortMode::Recommended(side) => {
+            // Switching players keeps the view the first Recommended was
+            // opened from instead of narrowing to the previous list.
+            if !matches!(state.sort_mode, WheelSortMode::Recommended(_)) {
+                state.recommended_source = recommended_source_entries(state);
+            }
+            refresh_recommended_entries(state, side);
+        }
         WheelSortMode::Playlist => {
             if let Some(playlist_entries) = state
                 .active_playlist_id
@@ -3868,6 +3988,7 @@ pub fn init(init_view: SelectMusicInitView) -> State {
         recent_p2_entries,
         top_grades_p1_entries,
         top_grades_p2_entries,
+        recommended_source: Arc::from([]),
         joined_favorites_entries,
         playlist_entries: Arc::from([]),
         playlist_library,
@@ -3891,9 +4012,17 @@ pub fn init(init_view: SelectMusicInitView) -> State {
         song_search: select_music_menu::SongSearchState::Hidden,
         song_search_generation: 0,
         pending_search: Vec::new(),
+        song_search_player: Arc::new(SongQueryPlayerState::default()),
         song_search_ignore_next_back_select: false,
         song_search_ignore_next_text: false,
         replay_overlay: select_music_menu::ReplayOverlayState::Hidden,
+        pattern_analysis_overlay: select_music_menu::PatternAnalysisOverlayState::Hidden,
+        workout_overlay: select_music_menu::WorkoutOverlayState::Hidden,
+        controller_claim_overlay: select_music_menu::ControllerClaimOverlayState::Hidden,
+        pack_lint_overlay: select_music_menu::PackLintOverlayState::Hidden,
+        song_lua_scan_levels: FxHashMap::default(),
+        duplicate_songs_overlay: select_music_menu::DuplicateSongsOverlayState::Hidden,
+        workout_view: [None; 2],
         lobby_overlay: lobby_overlay::OverlayState::Hidden,
         sync_overlay: SyncOverlayState::Hidden,
         pack_sync_overlay: crate::screens::pack_sync::OverlayState::Hidden,
@@ -4018,6 +4147,7 @@ pub fn init(init_view: SelectMusicInitView) -> State {
         lobby_last_failed_remote_song: None,
         lobby_notice_text: None,
         lobby_notice_time_left: 0.0,
+        song_votes: Default::default(),
         lobby_disconnect_hold_p1: None,
         lobby_disconnect_hold_p2: None,
         step_artist_cycle_base: 0.0,
@@ -4123,6 +4253,7 @@ pub fn init_placeholder() -> State {
         recent_p2_entries: Arc::from([]),
         top_grades_p1_entries: Arc::from([]),
         top_grades_p2_entries: Arc::from([]),
+        recommended_source: Arc::from([]),
         joined_favorites_entries: Arc::from([]),
         playlist_entries: Arc::from([]),
         playlist_library: Vec::new(),
@@ -4146,9 +4277,17 @@ pub fn init_placeholder() -> State {
         song_search: select_music_menu::SongSearchState::Hidden,
         song_search_generation: 0,
         pending_search: Vec::new(),
+        song_search_player: Arc::new(SongQueryPlayerState::default()),
         song_search_ignore_next_back_select: false,
         song_search_ignore_next_text: false,
         replay_overlay: select_music_menu::ReplayOverlayState::Hidden,
+        pattern_analysis_overlay: select_music_menu::PatternAnalysisOverlayState::Hidden,
+        workout_overlay: select_music_menu::WorkoutOverlayState::Hidden,
+        controller_claim_overlay: select_music_menu::ControllerClaimOverlayState::Hidden,
+        pack_lint_overlay: select_music_menu::PackLintOverlayState::Hidden,
+        song_lua_scan_levels: FxHashMap::default(),
+        duplicate_songs_overlay: select_music_menu::DuplicateSongsOverlayState::Hidden,
+        workout_view: [None; 2],
         lobby_overlay: lobby_overlay::OverlayState::Hidden,
         sync_overlay: SyncOverlayState::Hidden,
         pack_sync_overlay: crate::screens::pack_sync::OverlayState::Hidden,
@@ -4269,6 +4408,7 @@ pub fn init_placeholder() -> State {
         lobby_last_failed_remote_song: None,
         lobby_notice_text: None,
         lobby_notice_time_left: 0.0,
+        song_votes: Default::default(),
         lobby_disconnect_hold_p1: None,
         lobby_disconnect_hold_p2: None,
         step_artist_cycle_base: 0.0,
@@ -5126,6 +5266,21 @@ fn build_select_music_menu(state: &State) -> select_music_menu::MenuLists {
         standalone.push(select_music_menu::ITEM_PRACTICE_MODE);
         standalone.push(select_music_menu::ITEM_SHOW_LEADERBOARD);
         standalone.push(select_music_menu::ITEM_TOGGLE_FAVORITE);
+        if song_vote_available(state) {
+            standalone.push(select_music_menu::ITEM_NOMINATE_SONG);
+            let candidates = current_song_vote(state).candidates();
+            if candidates.len() > 1 {
+                let selected_key =
+                    build_local_lobby_song_info(state).map(|song_info| song_vote_key(&song_info));
+                if candidates
+                    .iter()
+                    .any(|candidate| selected_key.as_ref() == Some(&candidate.key))
+                {
+                    standalone.push(select_music_menu::ITEM_BAN_NOMINATION);
+                }
+                standalone.push(select_music_menu::ITEM_SONG_ROULETTE);
+            }
+        }
     } else if has_pack_selected || has_series_selected {
         standalone.push(select_music_menu::ITEM_TOGGLE_FAVORITE);
     }
@@ -5148,16 +5303,22 @@ fn build_select_music_menu(state: &State) -> select_music_menu::MenuLists {
             .local_profile_id(profile_data::PlayerSide::P2)
             .is_some();
     let profile_items = if p1_has_profile || p2_has_profile {
-        let mut items = Vec::with_capacity(8);
+        let mut items = Vec::with_capacity(12);
         if p1_has_profile {
             items.push(select_music_menu::ITEM_SORT_BY_POPULARITY_P1);
             items.push(select_music_menu::ITEM_SORT_BY_RECENT_P1);
             items.push(select_music_menu::ITEM_SORT_BY_TOP_GRADES_P1);
+            items.push(select_music_menu::ITEM_SORT_BY_RECOMMENDED_P1);
+            items.push(select_music_menu::ITEM_PATTERN_ANALYSIS_P1);
+            items.push(select_music_menu::ITEM_WORKOUT_P1);
         }
         if p2_has_profile {
             items.push(select_music_menu::ITEM_SORT_BY_POPULARITY_P2);
             items.push(select_music_menu::ITEM_SORT_BY_RECENT_P2);
             items.push(select_music_menu::ITEM_SORT_BY_TOP_GRADES_P2);
+            items.push(select_music_menu::ITEM_SORT_BY_RECOMMENDED_P2);
+            items.push(select_music_menu::ITEM_PATTERN_ANALYSIS_P2);
+            items.push(select_music_menu::ITEM_WORKOUT_P2);
         }
         // Favorites sort (if any player has favorites)
         let any_has_favorites = state.joined_favorites_entries.len() > 1;
@@ -5169,8 +5330,14 @@ fn build_select_music_menu(state: &State) -> select_music_menu::MenuLists {
         None
     };
 
-    let mut advanced = Vec::with_capacity(10);
+    let mut advanced = Vec::with_capacity(12);
     advanced.push(select_music_menu::ITEM_TEST_INPUT);
+    if p1_joined {
+        advanced.push(select_music_menu::ITEM_CLAIM_CONTROLLER_P1);
+    }
+    if p2_joined {
+        advanced.push(select_music_menu::ITEM_CLAIM_CONTROLLER_P2);
+    }
     if state.policy.fsr_profiles {
         advanced.push(select_music_menu::ITEM_CONFIGURE_PADS);
     }
@@ -5183,9 +5350,21 @@ fn build_select_music_menu(state: &State) -> select_music_menu::MenuLists {
         advanced.push(select_music_menu::ITEM_SRPG_SHOP);
     }
     advanced.push(select_music_menu::ITEM_SET_SUMMARY);
+    advanced.push(select_music_menu::ITEM_FIND_DUPLICATE_SONGS);
     if has_pack_selected {
         advanced.push(select_music_menu::ITEM_NULL_OR_DIE_PACK);
         advanced.push(select_music_menu::ITEM_SYNC_PACK);
+        advanced.push(select_music_menu::ITEM_PACK_SYNC_BIAS);
+        if let Some(pack) = selected_song_pack(state) {
+            if pack.sync_bias_ms != 0.0 {
+                advanced.push(select_music_menu::ITEM_APPLY_PACK_SYNC_BIAS);
+            }
+            if deadsync_simfile::pack_sync::pack_offset_journal_exists(&pack.directory) {
+                advanced.push(select_music_menu::ITEM_UNDO_PACK_OFFSETS);
+            }
+        }
+        advanced.push(select_music_menu::ITEM_LINT_PACK);
+        advanced.push(select_music_menu::ITEM_SCAN_SONG_LUA);
     }
     if has_song_selected {
         advanced.push(select_music_menu::ITEM_SYNC_SONG);
@@ -5256,6 +5435,11 @@ fn show_test_input_overlay(state: &mut State) {
     state.downloads_overlay = select_music_menu::DownloadsOverlayState::Hidden;
     state.srpg_shop_overlay = select_music_menu::SrpgShopOverlayState::Hidden;
     state.replay_overlay = select_music_menu::ReplayOverlayState::Hidden;
+    state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Hidden;
+    state.workout_overlay = select_music_menu::WorkoutOverlayState::Hidden;
+    state.controller_claim_overlay = select_music_menu::ControllerClaimOverlayState::Hidden;
+    state.pack_lint_overlay = select_music_menu::PackLintOverlayState::Hidden;
+    state.duplicate_songs_overlay = select_music_menu::DuplicateSongsOverlayState::Hidden;
     state.lobby_overlay = lobby_overlay::OverlayState::Hidden;
     state.sync_overlay = SyncOverlayState::Hidden;
     pack_sync::hide_overlay(state);
@@ -5279,6 +5463,11 @@ fn show_pad_config_overlay(state: &mut State) {
     state.downloads_overlay = select_music_menu::DownloadsOverlayState::Hidden;
     state.srpg_shop_overlay = select_music_menu::SrpgShopOverlayState::Hidden;
     state.replay_overlay = select_music_menu::ReplayOverlayState::Hidden;
+    state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Hidden;
+    state.workout_overlay = select_music_menu::WorkoutOverlayState::Hidden;
+    state.controller_claim_overlay = select_music_menu::ControllerClaimOverlayState::Hidden;
+    state.pack_lint_overlay = select_music_menu::PackLintOverlayState::Hidden;
+    state.duplicate_songs_overlay = select_music_menu::DuplicateSongsOverlayState::Hidden;
     state.lobby_overlay = lobby_overlay::OverlayState::Hidden;
     state.sync_overlay = SyncOverlayState::Hidden;
     pack_sync::hide_overlay(state);
@@ -5327,6 +5516,11 @@ fn show_lobby_overlay(state: &mut State) {
     state.downloads_overlay = select_music_menu::DownloadsOverlayState::Hidden;
     state.srpg_shop_overlay = select_music_menu::SrpgShopOverlayState::Hidden;
     state.replay_overlay = select_music_menu::ReplayOverlayState::Hidden;
+    state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Hidden;
+    state.workout_overlay = select_music_menu::WorkoutOverlayState::Hidden;
+    state.controller_claim_overlay = select_music_menu::ControllerClaimOverlayState::Hidden;
+    state.pack_lint_overlay = select_music_menu::PackLintOverlayState::Hidden;
+    state.duplicate_songs_overlay = select_music_menu::DuplicateSongsOverlayState::Hidden;
     state.sync_overlay = SyncOverlayState::Hidden;
     pack_sync::hide_overlay(state);
     state.profile_switch_overlay = None;
@@ -5349,6 +5543,11 @@ fn open_song_search(state: &mut State) {
     state.downloads_overlay = select_music_menu::DownloadsOverlayState::Hidden;
     state.srpg_shop_overlay = select_music_menu::SrpgShopOverlayState::Hidden;
     state.replay_overlay = select_music_menu::ReplayOverlayState::Hidden;
+    state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Hidden;
+    state.workout_overlay = select_music_menu::WorkoutOverlayState::Hidden;
+    state.controller_claim_overlay = select_music_menu::ControllerClaimOverlayState::Hidden;
+    state.pack_lint_overlay = select_music_menu::PackLintOverlayState::Hidden;
+    state.duplicate_songs_overlay = select_music_menu::DuplicateSongsOverlayState::Hidden;
     state.lobby_overlay = lobby_overlay::OverlayState::Hidden;
     state.sync_overlay = SyncOverlayState::Hidden;
     pack_sync::hide_overlay(state);
@@ -5361,10 +5560,36 @@ fn open_song_search(state: &mut State) {
     if let select_music_menu::SongSearchState::Open(open) = &mut state.song_search {
         open.chart_type = state.session.play_style.chart_type();
     }
+    state.song_search_player = Arc::new(song_search_player_state(state));
     rebuild_song_search_matches(state);
     state.song_search_ignore_next_text = false;
 }
 
+/// Best grade, full-combo lamp and favorite per chart across joined sides.
+fn song_search_player_state(state: &State) -> SongQueryPlayerState {
+    let mut player = SongQueryPlayerState::default();
+    for side in [profile_data::PlayerSide::P1, profile_data::PlayerSide::P2] {
+        if !state.session.side_joined(side) {
+            continue;
+        }
+        let ix = profile_data::player_side_index(side);
+        for (hash, score) in &state.history.sides[ix].cached_scores {
+            if !cached_score_exists(*score) {
+                continue;
+            }
+            player.record_score(
+                hash,
+                score.grade.to_sprite_state() as u8,
+                score.lamp_index.is_some_and(|lamp| lamp <= 4),
+            );
+        }
+        for hash in &state.favorites.chart_hashes[ix] {
+            player.record_favorite(hash);
+        }
+    }
+    player
+}
+
 /// Queue an off-thread re-rank. The overlay keeps its previous matches until
 /// [`apply_song_search_result`] lands, but the highlight resets now so it can
 /// never point at a stale row.
@@ -5391,6 +5616,7 @@ fn rebuild_song_search_matches(state: &mut State) {
             query,
             scope,
             chart_type,
+            player: Arc::clone(&state.song_search_player),
         });
 }
 
@@ -5480,6 +5706,11 @@ fn show_profile_switch_overlay(state: &mut State) {
     state.downloads_overlay = select_music_menu::DownloadsOverlayState::Hidden;
     state.srpg_shop_overlay = select_music_menu::SrpgShopOverlayState::Hidden;
     state.replay_overlay = select_music_menu::ReplayOverlayState::Hidden;
+    state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Hidden;
+    state.workout_overlay = select_music_menu::WorkoutOverlayState::Hidden;
+    state.controller_claim_overlay = select_music_menu::ControllerClaimOverlayState::Hidden;
+    state.pack_lint_overlay = select_music_menu::PackLintOverlayState::Hidden;
+    state.duplicate_songs_overlay = select_music_menu::DuplicateSongsOverlayState::Hidden;
     state.lobby_overlay = lobby_overlay::OverlayState::Hidden;
     state.sync_overlay = SyncOverlayState::Hidden;
     pack_sync::hide_overlay(state);
@@ -5524,6 +5755,11 @@ pub fn open_late_join_profile_overlay(
     state.downloads_overlay = select_music_menu::DownloadsOverlayState::Hidden;
     state.srpg_shop_overlay = select_music_menu::SrpgShopOverlayState::Hidden;
     state.replay_overlay = select_music_menu::ReplayOverlayState::Hidden;
+    state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Hidden;
+    state.workout_overlay = select_music_menu::WorkoutOverlayState::Hidden;
+    state.controller_claim_overlay = select_music_menu::ControllerClaimOverlayState::Hidden;
+    state.pack_lint_overlay = select_music_menu::PackLintOverlayState::Hidden;
+    state.duplicate_songs_overlay = select_music_menu::DuplicateSongsOverlayState::Hidden;
     state.lobby_overlay = lobby_overlay::OverlayState::Hidden;
     state.sync_overlay = SyncOverlayState::Hidden;
     pack_sync::hide_overlay(state);
@@ -5672,6 +5908,11 @@ fn begin_reload_ui(state: &mut State) -> bool {
     state.select_music_menu = select_music_menu::State::Hidden;
     state.leaderboard = select_music_menu::LeaderboardOverlayState::Hidden;
     state.replay_overlay = select_music_menu::ReplayOverlayState::Hidden;
+    state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Hidden;
+    state.workout_overlay = select_music_menu::WorkoutOverlayState::Hidden;
+    state.controller_claim_overlay = select_music_menu::ControllerClaimOverlayState::Hidden;
+    state.pack_lint_overlay = select_music_menu::PackLintOverlayState::Hidden;
+    state.duplicate_songs_overlay = select_music_menu::DuplicateSongsOverlayState::Hidden;
     state.sync_overlay = SyncOverlayState::Hidden;
     pack_sync::hide_overlay(state);
     state.profile_switch_overlay = None;
@@ -7053,6 +7294,14 @@ pub fn adopt_song_search_generation(state: &mut State, previous: &State) {
     state.song_search_generation = previous.song_search_generation;
 }
 
+/// Pack scans are too slow to repeat on every visit, so their badge levels
+/// carry over to the rebuilt screen.
+pub fn adopt_song_lua_scan_levels(state: &mut State, previous: &State) {
+    state
+        .song_lua_scan_levels
+        .clone_from(&previous.song_lua_scan_levels);
+}
+
 fn refresh_after_reload(state: &mut State, song_packs: Vec<SongPack>) {
     let selected_song = selected_song_arc(state);
     let selected_simfile_path = selected_song.as_ref().map(|song| song.simfile_path.clone());
@@ -7487,6 +7736,11 @@ fn show_leaderboard_overlay(state: &mut State) {
         state.scoreboxes.clone(),
     ) {
         state.replay_overlay = select_music_menu::ReplayOverlayState::Hidden;
+        state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Hidden;
+        state.workout_overlay = select_music_menu::WorkoutOverlayState::Hidden;
+        state.controller_claim_overlay = select_music_menu::ControllerClaimOverlayState::Hidden;
+        state.pack_lint_overlay = select_music_menu::PackLintOverlayState::Hidden;
+        state.duplicate_songs_overlay = select_music_menu::DuplicateSongsOverlayState::Hidden;
         state.downloads_overlay = select_music_menu::DownloadsOverlayState::Hidden;
         state.srpg_shop_overlay = select_music_menu::SrpgShopOverlayState::Hidden;
         state.lobby_overlay = lobby_overlay::OverlayState::Hidden;
@@ -7502,6 +7756,11 @@ fn show_leaderboard_overlay(state: &mut State) {
 fn show_downloads_overlay(state: &mut State) {
     state.leaderboard = select_music_menu::LeaderboardOverlayState::Hidden;
     state.replay_overlay = select_music_menu::ReplayOverlayState::Hidden;
+    state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Hidden;
+    state.workout_overlay = select_music_menu::WorkoutOverlayState::Hidden;
+    state.controller_claim_overlay = select_music_menu::ControllerClaimOverlayState::Hidden;
+    state.pack_lint_overlay = select_music_menu::PackLintOverlayState::Hidden;
+    state.duplicate_songs_overlay = select_music_menu::DuplicateSongsOverlayState::Hidden;
     state.lobby_overlay = lobby_overlay::OverlayState::Hidden;
     state.sync_overlay = SyncOverlayState::Hidden;
     pack_sync::hide_overlay(state);
@@ -7515,6 +7774,11 @@ fn show_downloads_overlay(state: &mut State) {
 fn show_srpg_shop_overlay(state: &mut State, side: profile_data::PlayerSide) {
     state.leaderboard = select_music_menu::LeaderboardOverlayState::Hidden;
     state.replay_overlay = select_music_menu::ReplayOverlayState::Hidden;
+    state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Hidden;
+    state.workout_overlay = select_music_menu::WorkoutOverlayState::Hidden;
+    state.controller_claim_overlay = select_music_menu::ControllerClaimOverlayState::Hidden;
+    state.pack_lint_overlay = select_music_menu::PackLintOverlayState::Hidden;
+    state.duplicate_songs_overlay = select_music_menu::DuplicateSongsOverlayState::Hidden;
     state.downloads_overlay = select_music_menu::DownloadsOverlayState::Hidden;
     state.lobby_overlay = lobby_overlay::OverlayState::Hidden;
     state.sync_overlay = SyncOverlayState::Hidden;
@@ -7546,6 +7810,11 @@ fn show_replay_overlay(state: &mut State) {
     }
     state.leaderboard = select_music_menu::LeaderboardOverlayState::Hidden;
     state.replay_overlay = select_music_menu::ReplayOverlayState::Hidden;
+    state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Hidden;
+    state.workout_overlay = select_music_menu::WorkoutOverlayState::Hidden;
+    state.controller_claim_overlay = select_music_menu::ControllerClaimOverlayState::Hidden;
+    state.pack_lint_overlay = select_music_menu::PackLintOverlayState::Hidden;
+    state.duplicate_songs_overlay = select_music_menu::DuplicateSongsOverlayState::Hidden;
     state.downloads_overlay = select_music_menu::DownloadsOverlayState::Hidden;
     state.srpg_shop_overlay = select_music_menu::SrpgShopOverlayState::Hidden;
     state.lobby_overlay = lobby_overlay::OverlayState::Hidden;
@@ -7567,6 +7836,77 @@ pub fn sync_machine_replays(state: &mut State, entries: Vec<score_data::MachineR
     state.replay_overlay = select_music_menu::begin_replay_overlay(entries);
 }
 
+fn show_pattern_analysis_overlay(state: &mut State, side: profile_data::PlayerSide) -> ThemeEffect {
+    if state.profiles.local_profile_id(side).is_none() {
+        return ThemeEffect::None;
+    }
+    state.leaderboard = select_music_menu::LeaderboardOverlayState::Hidden;
+    state.replay_overlay = select_music_menu::ReplayOverlayState::Hidden;
+    state.downloads_overlay = select_music_menu::DownloadsOverlayState::Hidden;
+    state.srpg_shop_overlay = select_music_menu::SrpgShopOverlayState::Hidden;
+    state.lobby_overlay = lobby_overlay::OverlayState::Hidden;
+    state.sync_overlay = SyncOverlayState::Hidden;
+    pack_sync::hide_overlay(state);
+    state.profile_switch_overlay = None;
+    hide_test_input_overlay(state);
+    state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Loading(side);
+    clear_preview(state);
+    ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::PatternAnalysis(
+        crate::SimplyLovePatternAnalysisRequest::Start {
+            side,
+            chart_type: state.session.play_style.chart_type(),
+        },
+    ))
+}
+
+fn show_controller_claim_overlay(state: &mut State, side: profile_data::PlayerSide) {
+    state.controller_claim_overlay = select_music_menu::begin_controller_claim_overlay(side);
+}
+
+fn show_workout_overlay(state: &mut State, side: profile_data::PlayerSide) {
+    let idx = profile_data::player_side_index(side);
+    state.workout_overlay = select_music_menu::begin_workout_overlay(side, state.workout_view[idx]);
+}
+
+/// Mirrors the shell's active workout sessions, one slot per player side.
+pub fn set_workout_view(
+    state: &mut State,
+    view: [Option<select_music_menu::WorkoutProgressView>; 2],
+) {
+    state.workout_view = view;
+}
+
+/// Show a finished analysis, unless the overlay was closed or reopened for
+/// the other player while it ran.
+pub fn apply_pattern_analysis_result(
+    state: &mut State,
+    result: crate::SimplyLovePatternAnalysisResult,
+) {
+    if matches!(
+        state.pattern_analysis_overlay,
+        select_music_menu::PatternAnalysisOverlayState::Loading(side) if side == result.side
+    ) {
+        state.pattern_analysis_overlay =
+            select_music_menu::begin_pattern_analysis_overlay(result.side, result.report);
+    }
+}
+
+fn focus_pattern_pick(state: &mut State, pick: &score_data::PatternChartPick) {
+    let Some(song) = state.group_entries.iter().find_map(|entry| match entry {
+        MusicWheelEntry::Song(song) if song.simfile_path == pick.simfile_path => {
+            Some(Arc::clone(song))
+        }
+        _ => None,
+    }) else {
+        return;
+    };
+    focus_song_from_search(state, &song);
+    let chart_type = state.session.play_style.chart_type();
+    if let Some(index) = song.steps_index_for_chart_hash(chart_type, &pick.chart_hash) {
+        set_selected_steps_index_for_sync(state, index);
+    }
+}
+
 fn handle_lobby_overlay_input(state: &mut State, ev: &InputEvent) -> ThemeEffect {
     if modal_blocks_arrow(state, ev.action) {
         return ThemeEffect::None;
@@ -8127,6 +8467,230 @@ fn publish_lobby_confirmed_song_selection(state: &mut State) {
     );
 }
 
+/// The joined lobby when it has remote players to vote with.
+fn song_vote_lobby(state: &State) -> Option<&lobby_data::JoinedLobby> {
+    let snapshot = &state.lobby_view.snapshot;
+    let joined = snapshot.joined_lobby.as_ref()?;
+    (matches!(snapshot.connection, lobby_data::ConnectionState::Connected)
+        && joined.players.len() > local_lobby_player_count(state.session))
+    .then_some(joined)
+}
+
+fn song_vote_available(state: &State) -> bool {
+    song_vote_lobby(state).is_some()
+        || (state.session.side_joined(profile_data::PlayerSide::P1)
+            && state.session.side_joined(profile_data::PlayerSide::P2))
+}
+
+fn song_vote_side_active(state: &State, side: profile_data::PlayerSide) -> bool {
+    if song_vote_lobby(state).is_some() {
+        local_lobby_side_is_active(state.session, side)
+    } else {
+        state.session.side_joined(side)
+    }
+}
+
+/// Voters are keyed by their local profile id, which survives renames and
+/// shared names. Guests fall back to their lobby label, or the fixed side id
+/// in local versus.
+fn song_vote_player(state: &State, side: profile_data::PlayerSide) -> String {
+    if let Some(id) = state.profiles.local_profile_id(side) {
+        id.to_string()
+    } else if song_vote_lobby(state).is_some() {
+        state.profiles.display_name(side).to_string()
+    } else {
+        lobby_data::lobby_player_id(side).to_string()
+    }
+}
+
+/// The vote as this machine sees it. Lobby order decides ban turns, and local
+/// sides use their latest entry before the lobby echoes it back.
+fn current_song_vote(state: &State) -> SongVote {
+    let local: Vec<(String, &LobbySongVote)> =
+        [profile_data::PlayerSide::P1, profile_data::PlayerSide::P2]
+            .into_iter()
+            .filter(|&side| song_vote_side_active(state, side))
+            .map(|side| {
+                (
+                    song_vote_player(state, side),
+                    &state.song_votes[profile_data::player_side_index(side)],
+                )
+            })
+            .collect();
+    let Some(joined) = song_vote_lobby(state) else {
+        return SongVote::from_players(local.iter().map(|(player, vote)| (player.as_str(), *vote)));
+    };
+    let mut entries: Vec<(&str, &LobbySongVote)> = joined
+        .players
+        .iter()
+        .map(|player| {
+            let voter = song_voter_id(&player.label, &player.song_vote);
+            let vote = local
+                .iter()
+                .find(|(id, _)| id == voter)
+                .map_or(&player.song_vote, |(_, vote)| *vote);
+            (voter, vote)
+        })
+        .collect();
+    for (player, vote) in &local {
+        if !joined
+            .players
+            .iter()
+            .any(|remote| song_voter_id(&remote.label, &remote.song_vote) == player)
+        {
+            entries.push((player.as_str(), *vote));
+        }
+    }
+    SongVote::from_players(entries)
+}
+
+fn song_vote_voter_count(state: &State) -> usize {
+    song_vote_lobby(state).map_or(2, |joined| joined.players.len())
+}
+
+fn publish_song_vote(state: &mut State, side: profile_data::PlayerSide) {
+    if song_vote_lobby(state).is_none() {
+        return;
+    }
+    let mut vote = state.song_votes[profile_data::player_side_index(side)].clone();
+    vote.voter = song_vote_player(state, side);
+    queue_online(
+        state,
+        crate::SimplyLoveOnlineRequest::Lobby(crate::SimplyLoveLobbyRequest::SongVote {
+            side,
+            vote,
+        }),
+    );
+}
+
+fn reset_local_song_votes(state: &mut State) {
+    for side in [profile_data::PlayerSide::P1, profile_data::PlayerSide::P2] {
+        let ix = profile_data::player_side_index(side);
+        if state.song_votes[ix].is_empty() {
+            continue;
+        }
+        state.song_votes[ix] = LobbySongVote::default();
+        publish_song_vote(state, side);
+    }
+}
+
+fn song_vote_title(song_info: &lobby_data::LobbySongInfo) -> &str {
+    song_info
+        .title
+        .as_deref()
+        .unwrap_or(song_info.song_path.as_str())
+}
+
+fn song_vote_decided_notice(song_info: &lobby_data::LobbySongInfo) -> String {
+    tr_fmt(
+        "Lobby",
+        "SongVoteDecidedFormat",
+        &[("title", song_vote_title(song_info))],
+    )
+    .to_string()
+}
+
+/// Focuses the winning chart, shares it with the lobby and starts a new round.
+fn finish_song_vote(state: &mut State, song_info: lobby_data::LobbySongInfo, notice: String) {
+    apply_remote_lobby_song_selection(state, &song_info);
+    reset_local_song_votes(state);
+    if song_vote_lobby(state).is_some() {
+        queue_online(
+            state,
+            crate::SimplyLoveOnlineRequest::Lobby(crate::SimplyLoveLobbyRequest::SelectSong(
+                song_info,
+            )),
+        );
+    }
+    set_lobby_notice(state, notice);
+}
+
+fn nominate_selected_song(state: &mut State, side: profile_data::PlayerSide) {
+    let Some(song_info) = build_local_lobby_song_info(state) else {
+        return;
+    };
+    state.song_votes[profile_data::player_side_index(side)].nomination = Some(song_info.clone());
+    publish_song_vote(state, side);
+
+    // A shared nomination only settles the vote once everyone has nominated.
+    let vote = current_song_vote(state);
+    if vote.nominations().len() >= song_vote_voter_count(state)
+        && let Some(winner) = vote.decided()
+    {
+        let notice = song_vote_decided_notice(&winner);
+        finish_song_vote(state, winner, notice);
+        return;
+    }
+    let player = song_vote_player(state, side);
+    let notice = tr_fmt(
+        "Lobby",
+        "SongVoteNominatedFormat",
+        &[
+            ("player", player.as_str()),
+            ("title", song_vote_title(&song_info)),
+        ],
+    )
+    .to_string();
+    set_lobby_notice(state, notice);
+}
+
+fn ban_selected_nomination(state: &mut State, side: profile_data::PlayerSide) {
+    let Some(song_info) = build_local_lobby_song_info(state) else {
+        return;
+    };
+    let key = song_vote_key(&song_info);
+    let player = song_vote_player(state, side);
+    let mut vote = current_song_vote(state);
+    if let Err(err) = vote.ban(player.as_str(), key.as_str()) {
+        let text_key = match err {
+            SongVoteError::NotYourTurn => "SongVoteNotYourTurn",
+            SongVoteError::NotACandidate => "SongVoteNotACandidate",
+            SongVoteError::LastCandidate => "SongVoteLastCandidate",
+        };
+        set_lobby_notice(state, tr("Lobby", text_key).to_string());
+        return;
+    }
+    state.song_votes[profile_data::player_side_index(side)]
+        .bans
+        .push(key);
+    publish_song_vote(state, side);
+
+    if let Some(winner) = vote.decided() {
+        let notice = song_vote_decided_notice(&winner);
+        finish_song_vote(state, winner, notice);
+        return;
+    }
+    let notice = tr_fmt(
+        "Lobby",
+        "SongVoteBannedFormat",
+        &[
+            ("player", player.as_str()),
+            ("title", song_vote_title(&song_info)),
+        ],
+    )
+    .to_string();
+    set_lobby_notice(state, notice);
+}
+
+fn spin_song_roulette(state: &mut State) {
+    let vote = current_song_vote(state);
+    // Lobby spins are salted with the room code; local spins with the clock.
+    let salt = song_vote_lobby(state).map_or_else(
+        || state.session_elapsed.to_bits().to_string(),
+        |joined| joined.code.clone(),
+    );
+    let Some(winner) = vote.roulette(vote.seed(salt.as_str())) else {
+        return;
+    };
+    let notice = tr_fmt(
+        "Lobby",
+        "SongVoteRouletteFormat",
+        &[("title", song_vote_title(&winner))],
+    )
+    .to_string();
+    finish_song_vote(state, winner, notice);
+}
+
 fn sync_lobby_select_music(state: &mut State) {
     let snapshot = std::sync::Arc::clone(&state.lobby_view.snapshot);
     sync_lobby_select_music_with(state, &snapshot);
@@ -8175,6 +8739,8 @@ fn sync_lobby_select_music_with(state: &mut State, snapshot: &lobby_data::Snapsh
             if apply_remote_lobby_song_selection(state, song_info) {
                 state.lobby_last_applied_remote_song = Some(LobbySongKey::new(song_info));
                 state.lobby_last_failed_remote_song = None;
+                // Any lobby-wide pick ends the current nomination round.
+                reset_local_song_votes(state);
             } else {
                 let matched_path = find_song_by_lobby_path(state, song_info.song_path.as_str())
                     .and_then(|song| lobby_song_path(song.as_ref(), &state.song_scan_roots));
@@ -8391,6 +8957,7 @@ pub fn benchmark_lobby_reconcile_fixture() -> LobbyReconcileBench {
                 judgments: None,
                 score: None,
                 ex_score: None,
+                song_vote: Default::default(),
             }],
             song_info: Some(song_info),
         }),
@@ -8905,6 +9472,7 @@ fn sync_direction(delta: f32) -> &'static str {
 fn sync_overlay_title(overlay: &ManualSyncOverlayData) -> std::sync::Arc<str> {
     match overlay.target {
         ManualSyncTarget::Pack { .. } => tr("SelectMusic", "ManualSyncPackTitle"),
+        ManualSyncTarget::PackBias { .. } => tr("SelectMusic", "PackSyncBiasTitle"),
     }
 }
 
@@ -8922,6 +9490,14 @@ fn sync_overlay_subtitle(overlay: &ManualSyncOverlayData) -> String {
             ],
         )
         .to_string(),
+        ManualSyncTarget::PackBias {
+            pack_name, bias_ms, ..
+        } => tr_fmt(
+            "SelectMusic",
+            "PackSyncBiasSubtitle",
+            &[("pack", pack_name), ("bias", &format!("{bias_ms:+.0}"))],
+        )
+        .to_string(),
     }
 }
 
@@ -8935,6 +9511,10 @@ fn sync_overlay_change_line(overlay: &ManualSyncOverlayData) -> Option<String> {
         ManualSyncTarget::Pack { .. } => Some(format!(
             "Pack offsets move by {delta:+.3} (notes {direction})"
         )),
+        ManualSyncTarget::PackBias { bias_ms, .. } => Some(format!(
+            "Pack sync bias moves from {bias_ms:+.0}ms to {:+.0}ms (notes {direction})",
+            pack_sync_bias_after(*bias_ms, delta)
+        )),
     }
 }
 
@@ -8958,6 +9538,12 @@ fn sync_overlay_prompt_text(overlay: &ManualSyncOverlayData) -> String {
             prompt.push_str(pack_name);
             prompt.push_str(":\n\n");
         }
+        ManualSyncTarget::PackBias { pack_name, .. } => {
+            prompt.push_str(&tr("SelectMusic", "PackSyncBiasLine"));
+            prompt.push('\n');
+            prompt.push_str(pack_name);
+            prompt.push_str(":\n\n");
+        }
     }
     prompt.push_str(&change_line);
     prompt.push_str("\n\n");
@@ -8967,6 +9553,11 @@ fn sync_overlay_prompt_text(overlay: &ManualSyncOverlayData) -> String {
     prompt
 }
 
+#[inline(always)]
+fn pack_sync_bias_after(bias_ms: f32, delta_seconds: f32) -> f32 {
+    deadsync_simfile::pack_sync::clamp_pack_sync_bias_ms(bias_ms + delta_seconds * 1000.0)
+}
+
 fn unique_sync_paths(paths: &[PathBuf]) -> Vec<PathBuf> {
     let mut unique = Vec::with_capacity(paths.len());
     for path in paths {
@@ -9005,6 +9596,11 @@ fn prepare_sync_overlay(state: &mut State) {
     state.downloads_overlay = select_music_menu::DownloadsOverlayState::Hidden;
     state.srpg_shop_overlay = select_music_menu::SrpgShopOverlayState::Hidden;
     state.replay_overlay = select_music_menu::ReplayOverlayState::Hidden;
+    state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Hidden;
+    state.workout_overlay = select_music_menu::WorkoutOverlayState::Hidden;
+    state.controller_claim_overlay = select_music_menu::ControllerClaimOverlayState::Hidden;
+    state.pack_lint_overlay = select_music_menu::PackLintOverlayState::Hidden;
+    state.duplicate_songs_overlay = select_music_menu::DuplicateSongsOverlayState::Hidden;
     pack_sync::hide_overlay(state);
     state.profile_switch_overlay = None;
     hide_test_input_overlay(state);
@@ -9319,43 +9915,250 @@ fn show_sync_pack_overlay(state: &mut State) {
     });
 }
 
-fn sync_overlay_apply_action(overlay: &ManualSyncOverlayData) -> Option<ThemeEffect> {
-    if !overlay.yes_selected || !manual_sync_has_change(overlay) {
-        return None;
-    }
-    let delta_seconds = sync_quantized_offset(overlay.delta_seconds);
-    match &overlay.target {
-        ManualSyncTarget::Pack { simfile_paths, .. } => {
-            let changes = unique_sync_paths(simfile_paths)
-                .into_iter()
-                .map(|simfile_path| SongOffsetSyncChange {
-                    simfile_path,
-                    delta_seconds,
-                })
-                .collect::<Vec<_>>();
-            (!changes.is_empty()).then_some(ThemeEffect::Runtime(
-                crate::SimplyLoveRuntimeRequest::Sync(
-                    crate::SimplyLoveSyncRequest::ApplySongOffsetBatch { changes },
-                ),
-            ))
+/// The loaded pack behind the selected pack header, if any.
+fn selected_song_pack(state: &State) -> Option<&SongPack> {
+    let pack_key = state.entries.get(state.selected_index)?.pack_key()?;
+    state
+        .song_packs
+        .iter()
+        .find(|pack| pack.group_name == pack_key)
+}
+
+fn pack_report_effect(
+    kind: select_music_menu::PackReportKind,
+    start: Option<PathBuf>,
+) -> ThemeEffect {
+    let request = match (kind, start) {
+        (select_music_menu::PackReportKind::Lint, Some(pack_dir)) => {
+            crate::SimplyLoveRuntimeRequest::PackLint(crate::SimplyLovePackLintRequest::Start {
+                pack_dir,
+            })
         }
-    }
+        (select_music_menu::PackReportKind::Lint, None) => {
+            crate::SimplyLoveRuntimeRequest::PackLint(crate::SimplyLovePackLintRequest::Cancel)
+        }
+        (select_music_menu::PackReportKind::SongLua, Some(pack_dir)) => {
+            crate::SimplyLoveRuntimeRequest::SongLuaScan(
+                crate::SimplyLoveSongLuaScanRequest::Start { pack_dir },
+            )
+        }
+        (select_music_menu::PackReportKind::SongLua, None) => {
+            crate::SimplyLoveRuntimeRequest::SongLuaScan(
+                crate::SimplyLoveSongLuaScanRequest::Cancel,
+            )
+        }
+    };
+    ThemeEffect::Runtime(request)
 }
 
-fn handle_manual_sync_overlay_input(state: &mut State, ev: &InputEvent) -> ThemeEffect {
-    if !ev.pressed {
+fn show_pack_lint_overlay(
+    state: &mut State,
+    kind: select_music_menu::PackReportKind,
+) -> ThemeEffect {
+    let Some((pack_dir, pack_name)) =
+        selected_song_pack(state).map(|pack| (pack.directory.clone(), pack.name.clone()))
+    else {
         return ThemeEffect::None;
-    }
-
-    let mut close_overlay = false;
-    let mut apply_action = None;
-    let mut play_change = false;
-    let mut play_start = false;
-
-    {
-        let SyncOverlayState::Manual(overlay) = &mut state.sync_overlay else {
-            return ThemeEffect::None;
-        };
+    };
+    state.leaderboard = select_music_menu::LeaderboardOverlayState::Hidden;
+    state.replay_overlay = select_music_menu::ReplayOverlayState::Hidden;
+    state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Hidden;
+    state.workout_overlay = select_music_menu::WorkoutOverlayState::Hidden;
+    state.controller_claim_overlay = select_music_menu::ControllerClaimOverlayState::Hidden;
+    state.downloads_overlay = select_music_menu::DownloadsOverlayState::Hidden;
+    state.srpg_shop_overlay = select_music_menu::SrpgShopOverlayState::Hidden;
+    state.lobby_overlay = lobby_overlay::OverlayState::Hidden;
+    state.sync_overlay = SyncOverlayState::Hidden;
+    pack_sync::hide_overlay(state);
+    state.profile_switch_overlay = None;
+    hide_test_input_overlay(state);
+    state.duplicate_songs_overlay = select_music_menu::DuplicateSongsOverlayState::Hidden;
+    state.pack_lint_overlay = select_music_menu::PackLintOverlayState::Loading {
+        kind,
+        pack_dir: pack_dir.clone(),
+        pack_name,
+    };
+    pack_report_effect(kind, Some(pack_dir))
+}
+
+/// Takes the pack name of a report still waiting on `kind` for `result_dir`.
+fn take_waiting_pack_name(
+    state: &mut State,
+    kind: select_music_menu::PackReportKind,
+    result_dir: &Path,
+) -> Option<String> {
+    let select_music_menu::PackLintOverlayState::Loading {
+        kind: waiting,
+        pack_dir,
+        pack_name,
+    } = &mut state.pack_lint_overlay
+    else {
+        return None;
+    };
+    if *waiting != kind || pack_dir.as_path() != result_dir {
+        return None;
+    }
+    Some(std::mem::take(pack_name))
+}
+
+/// Show a finished report, unless the overlay was closed or reopened for
+/// another pack while it ran.
+pub fn apply_pack_lint_result(state: &mut State, result: crate::SimplyLovePackLintResult) {
+    let Some(pack_name) = take_waiting_pack_name(
+        state,
+        select_music_menu::PackReportKind::Lint,
+        &result.pack_dir,
+    ) else {
+        return;
+    };
+    state.pack_lint_overlay = select_music_menu::begin_pack_lint_overlay(pack_name, result.report);
+}
+
+/// Records each scanned song's level for the wheel's lua badge, then shows
+/// the report like [`apply_pack_lint_result`].
+pub fn apply_song_lua_scan_result(state: &mut State, result: crate::SimplyLoveSongLuaScanResult) {
+    if let Ok(report) = result.report.as_ref() {
+        for song in &report.songs {
+            state
+                .song_lua_scan_levels
+                .insert(song.simfile_path.clone(), song.level());
+        }
+    }
+    let Some(pack_name) = take_waiting_pack_name(
+        state,
+        select_music_menu::PackReportKind::SongLua,
+        &result.pack_dir,
+    ) else {
+        return;
+    };
+    state.pack_lint_overlay =
+        select_music_menu::begin_song_lua_scan_overlay(pack_name, result.report);
+}
+
+fn show_duplicate_songs_overlay(state: &mut State) -> ThemeEffect {
+    state.leaderboard = select_music_menu::LeaderboardOverlayState::Hidden;
+    state.replay_overlay = select_music_menu::ReplayOverlayState::Hidden;
+    state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Hidden;
+    state.workout_overlay = select_music_menu::WorkoutOverlayState::Hidden;
+    state.controller_claim_overlay = select_music_menu::ControllerClaimOverlayState::Hidden;
+    state.pack_lint_overlay = select_music_menu::PackLintOverlayState::Hidden;
+    state.downloads_overlay = select_music_menu::DownloadsOverlayState::Hidden;
+    state.srpg_shop_overlay = select_music_menu::SrpgShopOverlayState::Hidden;
+    state.lobby_overlay = lobby_overlay::OverlayState::Hidden;
+    state.sync_overlay = SyncOverlayState::Hidden;
+    pack_sync::hide_overlay(state);
+    state.profile_switch_overlay = None;
+    hide_test_input_overlay(state);
+    state.duplicate_songs_overlay = select_music_menu::DuplicateSongsOverlayState::Loading;
+    ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::DuplicateSongs(
+        crate::SimplyLoveDuplicateSongsRequest::Scan,
+    ))
+}
+
+/// Show a finished scan, unless the overlay was closed while it ran.
+pub fn apply_duplicate_songs_result(
+    state: &mut State,
+    result: crate::SimplyLoveDuplicateSongsResult,
+) {
+    if !matches!(
+        state.duplicate_songs_overlay,
+        select_music_menu::DuplicateSongsOverlayState::Loading
+    ) {
+        return;
+    }
+    state.duplicate_songs_overlay =
+        select_music_menu::begin_duplicate_songs_overlay(result.report, result.choices);
+}
+
+fn show_pack_sync_bias_overlay(state: &mut State) {
+    let Some(pack) = selected_song_pack(state) else {
+        return;
+    };
+    let target = ManualSyncTarget::PackBias {
+        pack_name: pack.name.clone(),
+        pack_dir: pack.directory.clone(),
+        bias_ms: pack.sync_bias_ms,
+    };
+    prepare_sync_overlay(state);
+    state.sync_overlay = SyncOverlayState::Manual(ManualSyncOverlayData {
+        target,
+        delta_seconds: 0.0,
+        yes_selected: true,
+    });
+}
+
+fn apply_pack_sync_bias_effect(state: &State) -> ThemeEffect {
+    let Some(pack) = selected_song_pack(state) else {
+        return ThemeEffect::None;
+    };
+    let simfile_paths = selected_pack_sync_paths(state, &pack.group_name);
+    if pack.sync_bias_ms == 0.0 || simfile_paths.is_empty() {
+        return ThemeEffect::None;
+    }
+    ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Sync(
+        crate::SimplyLoveSyncRequest::ApplyPackSyncBias {
+            pack_dir: pack.directory.clone(),
+            simfile_paths,
+        },
+    ))
+}
+
+fn undo_pack_offsets_effect(state: &State) -> ThemeEffect {
+    let Some(pack) = selected_song_pack(state) else {
+        return ThemeEffect::None;
+    };
+    ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Sync(
+        crate::SimplyLoveSyncRequest::UndoPackOffsetRewrite {
+            pack_dir: pack.directory.clone(),
+        },
+    ))
+}
+
+fn sync_overlay_apply_action(overlay: &ManualSyncOverlayData) -> Option<ThemeEffect> {
+    if !overlay.yes_selected || !manual_sync_has_change(overlay) {
+        return None;
+    }
+    let delta_seconds = sync_quantized_offset(overlay.delta_seconds);
+    match &overlay.target {
+        ManualSyncTarget::Pack { simfile_paths, .. } => {
+            let changes = unique_sync_paths(simfile_paths)
+                .into_iter()
+                .map(|simfile_path| SongOffsetSyncChange {
+                    simfile_path,
+                    delta_seconds,
+                })
+                .collect::<Vec<_>>();
+            (!changes.is_empty()).then_some(ThemeEffect::Runtime(
+                crate::SimplyLoveRuntimeRequest::Sync(
+                    crate::SimplyLoveSyncRequest::ApplySongOffsetBatch { changes },
+                ),
+            ))
+        }
+        ManualSyncTarget::PackBias {
+            pack_dir, bias_ms, ..
+        } => Some(ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Sync(
+            crate::SimplyLoveSyncRequest::SetPackSyncBias {
+                pack_dir: pack_dir.clone(),
+                bias_ms: pack_sync_bias_after(*bias_ms, delta_seconds),
+            },
+        ))),
+    }
+}
+
+fn handle_manual_sync_overlay_input(state: &mut State, ev: &InputEvent) -> ThemeEffect {
+    if !ev.pressed {
+        return ThemeEffect::None;
+    }
+
+    let mut close_overlay = false;
+    let mut apply_action = None;
+    let mut play_change = false;
+    let mut play_start = false;
+
+    {
+        let SyncOverlayState::Manual(overlay) = &mut state.sync_overlay else {
+            return ThemeEffect::None;
+        };
         match ev.action {
             VirtualAction::p1_left
             | VirtualAction::p1_menu_left
@@ -9811,6 +10614,158 @@ fn handle_replay_overlay_input(state: &mut State, ev: &InputEvent) -> ThemeEffec
     }
 }
 
+fn handle_pattern_analysis_overlay_input(state: &mut State, ev: &InputEvent) -> ThemeEffect {
+    if modal_blocks_arrow(state, ev.action) {
+        return ThemeEffect::None;
+    }
+
+    match select_music_menu::handle_pattern_analysis_input(&mut state.pattern_analysis_overlay, ev)
+    {
+        select_music_menu::PatternAnalysisInputOutcome::ChangedSelection => {
+            queue_sfx(state, "assets/sounds/change.ogg");
+            ThemeEffect::None
+        }
+        select_music_menu::PatternAnalysisInputOutcome::Cancelled => {
+            queue_sfx(state, "assets/sounds/start.ogg");
+            ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::PatternAnalysis(
+                crate::SimplyLovePatternAnalysisRequest::Cancel,
+            ))
+        }
+        select_music_menu::PatternAnalysisInputOutcome::Closed => {
+            queue_sfx(state, "assets/sounds/start.ogg");
+            ThemeEffect::None
+        }
+        select_music_menu::PatternAnalysisInputOutcome::FocusChart(pick) => {
+            focus_pattern_pick(state, &pick);
+            queue_sfx(state, "assets/sounds/start.ogg");
+            ThemeEffect::None
+        }
+        select_music_menu::PatternAnalysisInputOutcome::None => ThemeEffect::None,
+    }
+}
+
+fn handle_workout_overlay_input(state: &mut State, ev: &InputEvent) -> ThemeEffect {
+    if modal_blocks_arrow(state, ev.action) {
+        return ThemeEffect::None;
+    }
+
+    let Some(side) = state.workout_overlay.side() else {
+        return ThemeEffect::None;
+    };
+    match select_music_menu::handle_workout_input(&mut state.workout_overlay, ev) {
+        select_music_menu::WorkoutInputOutcome::ChangedSelection => {
+            queue_sfx(state, "assets/sounds/change.ogg");
+            ThemeEffect::None
+        }
+        select_music_menu::WorkoutInputOutcome::Start(goal) => {
+            queue_sfx(state, "assets/sounds/start.ogg");
+            ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Workout(
+                crate::SimplyLoveWorkoutRequest::Start { side, goal },
+            ))
+        }
+        select_music_menu::WorkoutInputOutcome::End => {
+            queue_sfx(state, "assets/sounds/start.ogg");
+            ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Workout(
+                crate::SimplyLoveWorkoutRequest::End { side },
+            ))
+        }
+        select_music_menu::WorkoutInputOutcome::Closed => {
+            queue_sfx(state, "assets/sounds/start.ogg");
+            ThemeEffect::None
+        }
+        select_music_menu::WorkoutInputOutcome::None => ThemeEffect::None,
+    }
+}
+
+fn controller_claim_effect(
+    state: &mut State,
+    side: profile_data::PlayerSide,
+    outcome: select_music_menu::ControllerClaimOutcome,
+) -> ThemeEffect {
+    let request = match outcome {
+        select_music_menu::ControllerClaimOutcome::None => return ThemeEffect::None,
+        select_music_menu::ControllerClaimOutcome::Closed => {
+            queue_sfx(state, "assets/sounds/start.ogg");
+            return ThemeEffect::None;
+        }
+        select_music_menu::ControllerClaimOutcome::Claimed(claim) => {
+            crate::SimplyLoveHardwareRequest::ClaimController { side, claim }
+        }
+        select_music_menu::ControllerClaimOutcome::Released => {
+            crate::SimplyLoveHardwareRequest::ReleaseController(side)
+        }
+    };
+    queue_sfx(state, "assets/sounds/start.ogg");
+    ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Hardware(request))
+}
+
+fn handle_controller_claim_overlay_input(state: &mut State, ev: &InputEvent) -> ThemeEffect {
+    let Some(side) = state.controller_claim_overlay.side() else {
+        return ThemeEffect::None;
+    };
+    let outcome =
+        select_music_menu::handle_controller_claim_input(&mut state.controller_claim_overlay, ev);
+    controller_claim_effect(state, side, outcome)
+}
+
+fn handle_pack_lint_overlay_input(state: &mut State, ev: &InputEvent) -> ThemeEffect {
+    if modal_blocks_arrow(state, ev.action) {
+        return ThemeEffect::None;
+    }
+    let Some(kind) = state.pack_lint_overlay.kind() else {
+        return ThemeEffect::None;
+    };
+    match select_music_menu::handle_pack_lint_input(&mut state.pack_lint_overlay, ev) {
+        select_music_menu::PackLintInputOutcome::Scrolled => {
+            queue_sfx(state, "assets/sounds/change.ogg");
+            ThemeEffect::None
+        }
+        select_music_menu::PackLintInputOutcome::Cancelled => {
+            queue_sfx(state, "assets/sounds/start.ogg");
+            pack_report_effect(kind, None)
+        }
+        select_music_menu::PackLintInputOutcome::Closed => {
+            queue_sfx(state, "assets/sounds/start.ogg");
+            ThemeEffect::None
+        }
+        select_music_menu::PackLintInputOutcome::None => ThemeEffect::None,
+    }
+}
+
+fn handle_duplicate_songs_overlay_input(state: &mut State, ev: &InputEvent) -> ThemeEffect {
+    if modal_blocks_arrow(state, ev.action) {
+        return ThemeEffect::None;
+    }
+    match select_music_menu::handle_duplicate_songs_input(&mut state.duplicate_songs_overlay, ev) {
+        select_music_menu::DuplicateSongsInputOutcome::Moved => {
+            queue_sfx(state, "assets/sounds/change.ogg");
+            ThemeEffect::None
+        }
+        select_music_menu::DuplicateSongsInputOutcome::Changed => {
+            queue_sfx(state, "assets/sounds/start.ogg");
+            ThemeEffect::None
+        }
+        select_music_menu::DuplicateSongsInputOutcome::Cancelled => {
+            queue_sfx(state, "assets/sounds/start.ogg");
+            ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::DuplicateSongs(
+                crate::SimplyLoveDuplicateSongsRequest::Cancel,
+            ))
+        }
+        select_music_menu::DuplicateSongsInputOutcome::Closed(choices) => {
+            queue_sfx(state, "assets/sounds/start.ogg");
+            match choices {
+                Some(choices) => {
+                    ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::DuplicateSongs(
+                        crate::SimplyLoveDuplicateSongsRequest::Save { choices },
+                    ))
+                }
+                None => ThemeEffect::None,
+            }
+        }
+        select_music_menu::DuplicateSongsInputOutcome::None => ThemeEffect::None,
+    }
+}
+
 fn handle_profile_switch_overlay_input(
     state: &mut State,
     ev: &InputEvent,
@@ -10345,6 +11300,40 @@ fn dispatch_menu_action(
             hide_select_music_menu(state);
             ThemeEffect::None
         }
+        select_music_menu::Action::SortByRecommendedP1 => {
+            apply_wheel_sort(
+                state,
+                WheelSortMode::Recommended(profile_data::PlayerSide::P1),
+            );
+            hide_select_music_menu(state);
+            ThemeEffect::None
+        }
+        select_music_menu::Action::SortByRecommendedP2 => {
+            apply_wheel_sort(
+                state,
+                WheelSortMode::Recommended(profile_data::PlayerSide::P2),
+            );
+            hide_select_music_menu(state);
+            ThemeEffect::None
+        }
+        select_music_menu::Action::PatternAnalysisP1 => {
+            hide_select_music_menu(state);
+            show_pattern_analysis_overlay(state, profile_data::PlayerSide::P1)
+        }
+        select_music_menu::Action::PatternAnalysisP2 => {
+            hide_select_music_menu(state);
+            show_pattern_analysis_overlay(state, profile_data::PlayerSide::P2)
+        }
+        select_music_menu::Action::WorkoutP1 => {
+            hide_select_music_menu(state);
+            show_workout_overlay(state, profile_data::PlayerSide::P1);
+            ThemeEffect::None
+        }
+        select_music_menu::Action::WorkoutP2 => {
+            hide_select_music_menu(state);
+            show_workout_overlay(state, profile_data::PlayerSide::P2);
+            ThemeEffect::None
+        }
         select_music_menu::Action::SortByPlaylist(id) => {
             state.active_playlist_id = Some(id);
             if state.sort_mode == WheelSortMode::Playlist {
@@ -10377,6 +11366,16 @@ fn dispatch_menu_action(
             show_test_input_overlay(state);
             ThemeEffect::None
         }
+        select_music_menu::Action::ClaimControllerP1 => {
+            hide_select_music_menu(state);
+            show_controller_claim_overlay(state, profile_data::PlayerSide::P1);
+            ThemeEffect::None
+        }
+        select_music_menu::Action::ClaimControllerP2 => {
+            hide_select_music_menu(state);
+            show_controller_claim_overlay(state, profile_data::PlayerSide::P2);
+            ThemeEffect::None
+        }
         select_music_menu::Action::ConfigurePads => {
             hide_select_music_menu(state);
             show_pad_config_overlay(state);
@@ -10402,6 +11401,21 @@ fn dispatch_menu_action(
             hide_select_music_menu(state);
             start_reload_songs_and_courses(state)
         }
+        select_music_menu::Action::NominateSong => {
+            hide_select_music_menu(state);
+            nominate_selected_song(state, side);
+            ThemeEffect::None
+        }
+        select_music_menu::Action::BanNomination => {
+            hide_select_music_menu(state);
+            ban_selected_nomination(state, side);
+            ThemeEffect::None
+        }
+        select_music_menu::Action::SongRoulette => {
+            hide_select_music_menu(state);
+            spin_song_roulette(state);
+            ThemeEffect::None
+        }
         select_music_menu::Action::ShowLobbies => {
             hide_select_music_menu(state);
             show_lobby_overlay(state);
@@ -10432,6 +11446,31 @@ fn dispatch_menu_action(
             show_sync_pack_overlay(state);
             ThemeEffect::None
         }
+        select_music_menu::Action::PackSyncBias => {
+            hide_select_music_menu(state);
+            show_pack_sync_bias_overlay(state);
+            ThemeEffect::None
+        }
+        select_music_menu::Action::ApplyPackSyncBias => {
+            hide_select_music_menu(state);
+            apply_pack_sync_bias_effect(state)
+        }
+        select_music_menu::Action::UndoPackOffsets => {
+            hide_select_music_menu(state);
+            undo_pack_offsets_effect(state)
+        }
+        select_music_menu::Action::LintPack => {
+            hide_select_music_menu(state);
+            show_pack_lint_overlay(state, select_music_menu::PackReportKind::Lint)
+        }
+        select_music_menu::Action::ScanSongLua => {
+            hide_select_music_menu(state);
+            show_pack_lint_overlay(state, select_music_menu::PackReportKind::SongLua)
+        }
+        select_music_menu::Action::FindDuplicateSongs => {
+            hide_select_music_menu(state);
+            show_duplicate_songs_overlay(state)
+        }
         select_music_menu::Action::PlayReplay => {
             hide_select_music_menu(state);
             show_replay_overlay(state);
@@ -11094,7 +12133,7 @@ pub fn handle_player_options_mute_hotkey(
 
 #[inline(always)]
 fn key_bound_to_player_input(key: &RawKeyboardEvent) -> bool {
-    with_keymap(|km| keymap_has_player_input(km, key))
+    with_active_keymap(|km| keymap_has_player_input(km, key))
 }
 
 #[inline(always)]
@@ -11330,6 +12369,75 @@ fn handle_raw_key_event_impl(
         }
         return ThemeInputResult::ignored();
     }
+    if !state.pattern_analysis_overlay.is_hidden() {
+        if key.is_some_and(|key| key.pressed && key.code == KeyCode::Escape) {
+            // Consumed so the same edge never reaches the back handler.
+            let loading = matches!(
+                state.pattern_analysis_overlay,
+                select_music_menu::PatternAnalysisOverlayState::Loading(_)
+            );
+            state.pattern_analysis_overlay = select_music_menu::PatternAnalysisOverlayState::Hidden;
+            if !loading {
+                return ThemeInputResult::consumed(ThemeEffect::None);
+            }
+            return ThemeInputResult::consumed(ThemeEffect::Runtime(
+                crate::SimplyLoveRuntimeRequest::PatternAnalysis(
+                    crate::SimplyLovePatternAnalysisRequest::Cancel,
+                ),
+            ));
+        }
+        return ThemeInputResult::ignored();
+    }
+    if !state.workout_overlay.is_hidden() {
+        if key.is_some_and(|key| key.pressed && key.code == KeyCode::Escape) {
+            state.workout_overlay = select_music_menu::WorkoutOverlayState::Hidden;
+            return ThemeInputResult::consumed(ThemeEffect::None);
+        }
+        return ThemeInputResult::ignored();
+    }
+    if !state.controller_claim_overlay.is_hidden() {
+        if key.is_some_and(|key| key.pressed && key.code == KeyCode::Escape) {
+            state.controller_claim_overlay = select_music_menu::ControllerClaimOverlayState::Hidden;
+            return ThemeInputResult::consumed(ThemeEffect::None);
+        }
+        return ThemeInputResult::ignored();
+    }
+    if !state.pack_lint_overlay.is_hidden() {
+        if key.is_some_and(|key| key.pressed && key.code == KeyCode::Escape) {
+            let previous = std::mem::replace(
+                &mut state.pack_lint_overlay,
+                select_music_menu::PackLintOverlayState::Hidden,
+            );
+            let select_music_menu::PackLintOverlayState::Loading { kind, .. } = previous else {
+                return ThemeInputResult::consumed(ThemeEffect::None);
+            };
+            return ThemeInputResult::consumed(pack_report_effect(kind, None));
+        }
+        return ThemeInputResult::ignored();
+    }
+    if !state.duplicate_songs_overlay.is_hidden() {
+        if key.is_some_and(|key| key.pressed && key.code == KeyCode::Escape) {
+            let previous = std::mem::replace(
+                &mut state.duplicate_songs_overlay,
+                select_music_menu::DuplicateSongsOverlayState::Hidden,
+            );
+            let request = match previous {
+                select_music_menu::DuplicateSongsOverlayState::Loading => {
+                    crate::SimplyLoveDuplicateSongsRequest::Cancel
+                }
+                select_music_menu::DuplicateSongsOverlayState::Visible(overlay) => {
+                    crate::SimplyLoveDuplicateSongsRequest::Save {
+                        choices: overlay.choices,
+                    }
+                }
+                _ => return ThemeInputResult::consumed(ThemeEffect::None),
+            };
+            return ThemeInputResult::consumed(ThemeEffect::Runtime(
+                crate::SimplyLoveRuntimeRequest::DuplicateSongs(request),
+            ));
+        }
+        return ThemeInputResult::ignored();
+    }
     if state.pad_config_overlay_visible {
         // While the save name box is open, raw keys type the name (and keyboard
         // Enter/Esc confirm/cancel). Otherwise editing is virtual-action driven.
@@ -11499,11 +12607,20 @@ fn handle_raw_key_event_impl(
     ThemeInputResult::ignored()
 }
 
-pub fn handle_raw_pad_event(state: &mut State, pad_event: &PadEvent) {
-    if !state.test_input_overlay_visible {
-        return;
+/// Returns whether the event was consumed, and the effect it produced.
+pub fn handle_raw_pad_event(state: &mut State, pad_event: &PadEvent) -> (bool, ThemeEffect) {
+    if let Some(side) = state.controller_claim_overlay.side() {
+        let outcome = select_music_menu::handle_controller_claim_raw_pad(
+            &mut state.controller_claim_overlay,
+            pad_event,
+        );
+        let consumed = outcome != select_music_menu::ControllerClaimOutcome::None;
+        return (consumed, controller_claim_effect(state, side, outcome));
+    }
+    if state.test_input_overlay_visible {
+        test_input::apply_raw_pad_event(&mut state.test_input_overlay, pad_event);
     }
-    test_input::apply_raw_pad_event(&mut state.test_input_overlay, pad_event);
+    (false, ThemeEffect::None)
 }
 
 pub fn handle_input(
@@ -11616,6 +12733,21 @@ fn handle_input_impl(
     ) {
         return handle_replay_overlay_input(state, ev);
     }
+    if !state.pattern_analysis_overlay.is_hidden() {
+        return handle_pattern_analysis_overlay_input(state, ev);
+    }
+    if !state.workout_overlay.is_hidden() {
+        return handle_workout_overlay_input(state, ev);
+    }
+    if !state.controller_claim_overlay.is_hidden() {
+        return handle_controller_claim_overlay_input(state, ev);
+    }
+    if !state.pack_lint_overlay.is_hidden() {
+        return handle_pack_lint_overlay_input(state, ev);
+    }
+    if !state.duplicate_songs_overlay.is_hidden() {
+        return handle_duplicate_songs_overlay_input(state, ev);
+    }
     if state.test_input_overlay_visible {
         return handle_test_input_overlay_input(state, ev);
     }
@@ -11981,6 +13113,21 @@ fn update_impl(state: &mut State, dt: f32, smx: &SmxAssignmentView) -> ThemeEffe
     if select_music_menu::update_replay_overlay(&mut state.replay_overlay, dt) {
         return ThemeEffect::None;
     }
+    if select_music_menu::update_pattern_analysis_overlay(&mut state.pattern_analysis_overlay, dt) {
+        return ThemeEffect::None;
+    }
+    if select_music_menu::update_workout_overlay(&mut state.workout_overlay, dt) {
+        return ThemeEffect::None;
+    }
+    if select_music_menu::update_controller_claim_overlay(&mut state.controller_claim_overlay, dt) {
+        return ThemeEffect::None;
+    }
+    if select_music_menu::update_pack_lint_overlay(&mut state.pack_lint_overlay, dt) {
+        return ThemeEffect::None;
+    }
+    if select_music_menu::update_duplicate_songs_overlay(&mut state.duplicate_songs_overlay, dt) {
+        return ThemeEffect::None;
+    }
     if let Some(overlay) = state.profile_switch_overlay.as_mut() {
         profile_boxes::update(overlay, dt);
         return ThemeEffect::None;
@@ -12455,6 +13602,13 @@ pub fn reset_preview_after_gameplay(state: &mut State, history: SelectMusicHisto
     } else if was_popularity_sort {
         state.sort_mode = WheelSortMode::Group;
         apply_wheel_sort(state, WheelSortMode::Popularity);
+    } else if let WheelSortMode::Recommended(side) = state.sort_mode {
+        // The chart just played may now be mastered or have moved the rating.
+        refresh_recommended_entries(state, side);
+        rebuild_displayed_entries(state);
+        state.selected_index = state
+            .selected_index
+            .min(state.entries.len().saturating_sub(1));
     }
     state.currently_playing_preview_song = None;
     state.currently_playing_preview_start_sec = None;
@@ -12506,6 +13660,11 @@ pub fn allows_late_join(state: &State) -> bool {
             state.replay_overlay,
             select_music_menu::ReplayOverlayState::Hidden
         )
+        && state.pattern_analysis_overlay.is_hidden()
+        && state.workout_overlay.is_hidden()
+        && state.controller_claim_overlay.is_hidden()
+        && state.pack_lint_overlay.is_hidden()
+        && state.duplicate_songs_overlay.is_hidden()
         && matches!(
             state.leaderboard,
             select_music_menu::LeaderboardOverlayState::Hidden
@@ -12614,6 +13773,11 @@ fn delayed_selection_updates_blocked(state: &State) -> bool {
             state.replay_overlay,
             select_music_menu::ReplayOverlayState::Hidden
         )
+        || !state.pattern_analysis_overlay.is_hidden()
+        || !state.workout_overlay.is_hidden()
+        || !state.controller_claim_overlay.is_hidden()
+        || !state.pack_lint_overlay.is_hidden()
+        || !state.duplicate_songs_overlay.is_hidden()
         || state.profile_switch_overlay.is_some()
         || state.test_input_overlay_visible
         || state.pad_config_overlay_visible
@@ -13507,6 +14671,11 @@ pub fn push_actors(
             state.policy.machine_font,
         ));
     }
+    for side in [profile_data::PlayerSide::P1, profile_data::PlayerSide::P2] {
+        if let Some(view) = state.workout_view[profile_data::player_side_index(side)] {
+            actors.push(select_music_menu::build_workout_progress_line(side, view));
+        }
+    }
 
     // Pads
     {
@@ -14518,6 +15687,7 @@ pub fn push_actors(
             song_box_color: None,
             song_text_color: None,
             song_text_color_overrides: None,
+            song_lua_levels: Some(&state.song_lua_scan_levels),
             show_pack_sync: presentation.pack_ini_offsets,
             show_music_wheel_grades: state.policy.wheel.show_grades,
             show_music_wheel_lamps: state.policy.wheel.show_lamps,
@@ -14768,6 +15938,54 @@ pub fn push_actors(
         actors.extend(replay_overlay);
         return;
     }
+    if let Some(side) = state.pattern_analysis_overlay.side()
+        && let Some(pattern_overlay) = select_music_menu::build_pattern_analysis_overlay(
+            &state.pattern_analysis_overlay,
+            state.profiles.display_name(side),
+            state.active_color_index,
+            state.policy.machine_font,
+        )
+    {
+        actors.extend(pattern_overlay);
+        return;
+    }
+    if let Some(side) = state.workout_overlay.side()
+        && let Some(workout_overlay) = select_music_menu::build_workout_overlay(
+            &state.workout_overlay,
+            state.workout_view[profile_data::player_side_index(side)],
+            state.profiles.display_name(side),
+            state.active_color_index,
+            state.policy.machine_font,
+        )
+    {
+        actors.extend(workout_overlay);
+        return;
+    }
+    if let Some(side) = state.controller_claim_overlay.side()
+        && let Some(claim_overlay) = select_music_menu::build_controller_claim_overlay(
+            &state.controller_claim_overlay,
+            deadsync_input::controller_claims()[profile_data::player_side_index(side)],
+            state.profiles.display_name(side),
+            state.policy.machine_font,
+        )
+    {
+        actors.extend(claim_overlay);
+        return;
+    }
+    if let Some(pack_lint_overlay) = select_music_menu::build_pack_lint_overlay(
+        &state.pack_lint_overlay,
+        state.policy.machine_font,
+    ) {
+        actors.extend(pack_lint_overlay);
+        return;
+    }
+    if let Some(duplicate_songs_overlay) = select_music_menu::build_duplicate_songs_overlay(
+        &state.duplicate_songs_overlay,
+        state.policy.machine_font,
+    ) {
+        actors.extend(duplicate_songs_overlay);
+        return;
+    }
     if let Some(pack_sync_overlay) = pack_sync::build_overlay(
         &state.pack_sync_overlay,
         state.active_color_index,
@@ -15329,14 +16547,14 @@ mod tests {
         PREVIEW_DELAY_SECONDS, SyncGraphCols, WheelSortMode, append_pending_runtime,
         banner_texture_key, build_displayed_entries, build_local_lobby_song_info,
         build_playlist_entries_from_text, build_playlist_song_lookup, build_sync_heat_image,
-        current_preview_path, delayed_selection_updates_blocked, first_song_entry_index,
-        handle_downloads_overlay_raw_key, handle_profile_switch_overlay_input, init_placeholder,
-        keymap_has_player_input, maybe_prewarm_replaygain_for_pack,
-        maybe_refresh_select_music_leaderboard, prepend_pending_effect, profile_boxes,
-        reset_preview_after_gameplay, select_music_lobby_lock_text,
-        select_music_lobby_lock_text_for, solo_runtime_side, steps_index_for_side,
-        sync_beat_axis_rows, sync_beat_marker_rows, sync_beat_row_y, sync_bias_axis_pos,
-        sync_graph_cols, sync_lobby_select_music, sync_low_confidence_warning,
+        current_preview_path, delayed_selection_updates_blocked, dispatch_menu_action,
+        first_song_entry_index, handle_downloads_overlay_raw_key,
+        handle_profile_switch_overlay_input, init_placeholder, keymap_has_player_input,
+        maybe_prewarm_replaygain_for_pack, maybe_refresh_select_music_leaderboard,
+        prepend_pending_effect, profile_boxes, reset_preview_after_gameplay,
+        select_music_lobby_lock_text, select_music_lobby_lock_text_for, solo_runtime_side,
+        steps_index_for_side, sync_beat_axis_rows, sync_beat_marker_rows, sync_beat_row_y,
+        sync_bias_axis_pos, sync_graph_cols, sync_lobby_select_music, sync_low_confidence_warning,
         sync_overlay_graph_size,
     };
     use crate::config::{
@@ -17618,6 +18836,8 @@ mod tests {
             folder_series: String::new(),
             year: 0,
             sync_pref: SyncPref::Default,
+            sync_bias_ms: 0.0,
+            sync_bias_written_ms: Vec::new(),
             directory: PathBuf::from(name),
             banner_path: None,
             songs: Vec::new(),
@@ -17723,6 +18943,63 @@ mod tests {
         assert_eq!(state.p2_selected_steps_index, 3);
     }
 
+    #[test]
+    fn recommended_sort_draws_from_the_selected_pack_or_the_whole_view() {
+        let song_with_meters = |index, meters| {
+            let mut song = (*super::test_folder_stats_song(index)).clone();
+            for (chart, meter) in song.charts.iter_mut().zip(meters) {
+                chart.meter = meter;
+            }
+            Arc::new(song)
+        };
+        let song_a = song_with_meters(0, [2, 5, 8, 10, 12]);
+        let song_b = song_with_meters(1, [3, 8, 9, 11, 13]);
+        let song_c = song_with_meters(2, [4, 6, 9, 11, 13]);
+        let group_entries = vec![
+            header("Pack A", 0, 2, Some("Pack A")),
+            super::MusicWheelEntry::Song(song_a.clone()),
+            super::MusicWheelEntry::Song(song_b.clone()),
+            header("Pack B", 1, 1, Some("Pack B")),
+            super::MusicWheelEntry::Song(song_c.clone()),
+        ];
+        let mut state = init_placeholder();
+        let p1 = &mut state.history.sides[0];
+        p1.available = true;
+        // One par score on a 10 rates the player at 10.
+        p1.recent_chart_hashes = vec![song_a.charts[3].short_hash.clone()];
+        p1.recent_play_ex = vec![(song_a.charts[3].short_hash.clone(), 85.0)];
+        p1.local_ex_scores = vec![(song_a.charts[3].short_hash.clone(), 85.0)];
+        state.group_entries = group_entries.clone().into();
+        state.all_entries = group_entries.clone().into();
+        state.entries = group_entries;
+        state.selected_index = 3;
+
+        super::apply_wheel_sort(
+            &mut state,
+            WheelSortMode::Recommended(profile_data::PlayerSide::P1),
+        );
+        let recommended: Vec<_> = super::songs_from_entries(&state.all_entries);
+        assert_eq!(recommended.len(), 1);
+        assert!(Arc::ptr_eq(&recommended[0], &song_c));
+
+        super::apply_wheel_sort(&mut state, WheelSortMode::Group);
+        state.selected_index = state
+            .entries
+            .iter()
+            .position(|entry| matches!(entry, super::MusicWheelEntry::Song(_)))
+            .expect("the open pack shows its songs");
+        super::apply_wheel_sort(
+            &mut state,
+            WheelSortMode::Recommended(profile_data::PlayerSide::P1),
+        );
+        // Unplayed 11s come before the 10 already scored below mastery.
+        let recommended = super::songs_from_entries(&state.all_entries);
+        assert_eq!(recommended.len(), 3);
+        assert!(Arc::ptr_eq(&recommended[0], &song_b));
+        assert!(Arc::ptr_eq(&recommended[1], &song_c));
+        assert!(Arc::ptr_eq(&recommended[2], &song_a));
+    }
+
     fn song_titles(entries: &[super::MusicWheelEntry]) -> Vec<&str> {
         entries
             .iter()
@@ -17942,6 +19219,7 @@ mod tests {
             judgments: None,
             score: None,
             ex_score: None,
+            song_vote: Default::default(),
         }
     }
 
@@ -18016,6 +19294,53 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn matching_lobby_nomination_settles_song_vote() {
+        let mut state = init_placeholder();
+        let entry = super::MusicWheelEntry::Song(super::test_folder_stats_song(0));
+        state.group_entries = Arc::from([entry.clone()]);
+        state.entries = vec![entry];
+        let song_info = build_local_lobby_song_info(&state).expect("fixture should select a chart");
+        let side = state.session.player_side;
+        let mut local = test_lobby_player("ScreenSelectMusic");
+        local.label = state.profiles.display_name(side).to_string();
+        let mut remote = test_lobby_player("ScreenSelectMusic");
+        remote.song_vote.nomination = Some(song_info.clone());
+        state.lobby_view.snapshot = Arc::new(lobby_data::Snapshot {
+            connection: lobby_data::ConnectionState::Connected,
+            joined_lobby: Some(test_joined_lobby(vec![local, remote], None)),
+            ..Default::default()
+        });
+
+        let _ = dispatch_menu_action(
+            &mut state,
+            super::select_music_menu::Action::NominateSong,
+            side,
+        );
+
+        assert!(state.song_votes.iter().all(|vote| vote.is_empty()));
+        match state.pending_online.as_slice() {
+            [
+                crate::SimplyLoveOnlineRequest::Lobby(crate::SimplyLoveLobbyRequest::SongVote {
+                    vote: nominated,
+                    ..
+                }),
+                crate::SimplyLoveOnlineRequest::Lobby(crate::SimplyLoveLobbyRequest::SongVote {
+                    vote: cleared,
+                    ..
+                }),
+                crate::SimplyLoveOnlineRequest::Lobby(crate::SimplyLoveLobbyRequest::SelectSong(
+                    picked,
+                )),
+            ] => {
+                assert_eq!(nominated.nomination.as_ref(), Some(&song_info));
+                assert!(cleared.is_empty());
+                assert_eq!(picked, &song_info);
+            }
+            other => panic!("unexpected lobby requests: {other:?}"),
+        }
+    }
+
     #[test]
     fn failed_remote_selection_retries_only_after_content_replacement() {
         let mut state = init_placeholder();
@@ -18248,6 +19573,29 @@ mod tests {
         assert!(state.song_packs.is_empty());
     }
 
+    #[test]
+    fn pack_sync_bias_overlay_saves_clamped_bias_for_selected_pack() {
+        let mut state = init_placeholder();
+        state.entries = test_entries();
+        state.selected_index = 0;
+        let mut pack = test_pack("Pack A", "");
+        pack.sync_bias_ms = 95.0;
+        state.song_packs = vec![pack];
+
+        super::show_pack_sync_bias_overlay(&mut state);
+        let super::SyncOverlayState::Manual(overlay) = &mut state.sync_overlay else {
+            panic!("expected the manual sync overlay");
+        };
+        overlay.delta_seconds = 0.02;
+
+        assert!(matches!(
+            super::sync_overlay_apply_action(overlay),
+            Some(ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::Sync(
+                crate::SimplyLoveSyncRequest::SetPackSyncBias { pack_dir, bias_ms }
+            ))) if pack_dir == PathBuf::from("Pack A") && bias_ms == 100.0
+        ));
+    }
+
     #[test]
     fn opposite_direction_press_steps_once_then_stops_hold() {
         let mut state = init_placeholder();
@@ -18960,6 +20308,13 @@ mod tests {
         let completed =
             super::select_music_menu::song_search_query_completed_with("[180] sun", "Sunlight");
         assert_eq!(completed, "[180] Sunlight");
+
+        // Query-language tokens survive the same way.
+        let completed = super::select_music_menu::song_search_query_completed_with(
+            "meter:11-13 grade<=A butt",
+            "Butterfly",
+        );
+        assert_eq!(completed, "meter:11-13 grade<=A Butterfly");
     }
 
     #[test]
@@ -19105,6 +20460,160 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn pattern_analysis_result_applies_only_to_waiting_side() {
+        let mut state = init_placeholder();
+        let result = |side| crate::SimplyLovePatternAnalysisResult {
+            side,
+            report: Ok(deadsync_score::PatternReport::default()),
+        };
+
+        // Closed before the worker finished: nothing reappears.
+        super::apply_pattern_analysis_result(&mut state, result(profile_data::PlayerSide::P1));
+        assert!(state.pattern_analysis_overlay.is_hidden());
+
+        state.pattern_analysis_overlay =
+            super::select_music_menu::PatternAnalysisOverlayState::Loading(
+                profile_data::PlayerSide::P2,
+            );
+        super::apply_pattern_analysis_result(&mut state, result(profile_data::PlayerSide::P1));
+        assert!(matches!(
+            state.pattern_analysis_overlay,
+            super::select_music_menu::PatternAnalysisOverlayState::Loading(_)
+        ));
+
+        super::apply_pattern_analysis_result(&mut state, result(profile_data::PlayerSide::P2));
+        assert!(matches!(
+            state.pattern_analysis_overlay,
+            super::select_music_menu::PatternAnalysisOverlayState::Visible(_)
+        ));
+    }
+
+    #[test]
+    fn pack_lint_result_applies_only_to_waiting_pack() {
+        let mut state = init_placeholder();
+        let result = |dir: &str| crate::SimplyLovePackLintResult {
+            pack_dir: std::path::PathBuf::from(dir),
+            report: Ok(deadsync_simfile::lint::PackLintReport::default()),
+        };
+
+        super::apply_pack_lint_result(&mut state, result("Songs/A"));
+        assert!(state.pack_lint_overlay.is_hidden());
+
+        state.pack_lint_overlay = super::select_music_menu::PackLintOverlayState::Loading {
+            kind: super::select_music_menu::PackReportKind::Lint,
+            pack_dir: std::path::PathBuf::from("Songs/B"),
+            pack_name: "B".to_string(),
+        };
+        super::apply_pack_lint_result(&mut state, result("Songs/A"));
+        assert!(matches!(
+            state.pack_lint_overlay,
+            super::select_music_menu::PackLintOverlayState::Loading { .. }
+        ));
+
+        super::apply_pack_lint_result(&mut state, result("Songs/B"));
+        assert_eq!(state.pack_lint_overlay.pack_name(), Some("B"));
+        assert!(matches!(
+            state.pack_lint_overlay,
+            super::select_music_menu::PackLintOverlayState::Visible(_)
+        ));
+    }
+
+    #[test]
+    fn song_lua_scan_records_levels_without_taking_a_lint_overlay() {
+        use deadsync_song_lua::{
+            SongLuaCompatibility, SongLuaCompatibilityLevel, SongLuaScanEntry, SongLuaScanReport,
+            SongLuaScanSong,
+        };
+        let mut state = init_placeholder();
+        state.pack_lint_overlay = super::select_music_menu::PackLintOverlayState::Loading {
+            kind: super::select_music_menu::PackReportKind::Lint,
+            pack_dir: std::path::PathBuf::from("Songs/A"),
+            pack_name: "A".to_string(),
+        };
+        let simfile = std::path::PathBuf::from("Songs/A/Song/song.ssc");
+        let result = crate::SimplyLoveSongLuaScanResult {
+            pack_dir: std::path::PathBuf::from("Songs/A"),
+            report: Ok(SongLuaScanReport {
+                songs: vec![SongLuaScanSong {
+                    simfile_path: simfile.clone(),
+                    title: "Song".to_string(),
+                    entries: vec![SongLuaScanEntry {
+                        path: std::path::PathBuf::from("Songs/A/Song/default.lua"),
+                        start_beat: 0.0,
+                        compatibility: SongLuaCompatibility::Degraded(vec![
+                            "perframe start=0.000 end=4.000".to_string(),
+                        ]),
+                    }],
+                }],
+                ..SongLuaScanReport::default()
+            }),
+        };
+
+        super::apply_song_lua_scan_result(&mut state, result);
+        assert_eq!(
+            state.song_lua_scan_levels.get(&simfile),
+            Some(&SongLuaCompatibilityLevel::Degraded)
+        );
+        assert!(matches!(
+            state.pack_lint_overlay,
+            super::select_music_menu::PackLintOverlayState::Loading { .. }
+        ));
+    }
+
+    #[test]
+    fn duplicate_songs_result_applies_only_while_scanning() {
+        let mut state = init_placeholder();
+        let result = || crate::SimplyLoveDuplicateSongsResult {
+            report: Ok(deadsync_simfile::duplicates::DuplicateReport::default()),
+            choices: deadsync_simfile::duplicates::DuplicateChoices::default(),
+        };
+
+        super::apply_duplicate_songs_result(&mut state, result());
+        assert!(state.duplicate_songs_overlay.is_hidden());
+
+        assert!(matches!(
+            super::show_duplicate_songs_overlay(&mut state),
+            ThemeEffect::Runtime(crate::SimplyLoveRuntimeRequest::DuplicateSongs(
+                crate::SimplyLoveDuplicateSongsRequest::Scan
+            ))
+        ));
+        super::apply_duplicate_songs_result(&mut state, result());
+        assert!(matches!(
+            state.duplicate_songs_overlay,
+            super::select_music_menu::DuplicateSongsOverlayState::Visible(_)
+        ));
+    }
+
+    #[test]
+    fn workout_overlay_offers_end_only_for_running_side() {
+        let mut state = init_placeholder();
+        super::set_workout_view(
+            &mut state,
+            [
+                None,
+                Some(super::select_music_menu::WorkoutProgressView {
+                    goal: deadsync_score::WorkoutGoal::minutes(30),
+                    amount: 12.0,
+                    progress: 0.4,
+                    songs: 4,
+                }),
+            ],
+        );
+
+        super::show_workout_overlay(&mut state, profile_data::PlayerSide::P1);
+        assert!(matches!(
+            state.workout_overlay,
+            super::select_music_menu::WorkoutOverlayState::Visible { active: false, .. }
+        ));
+
+        super::show_workout_overlay(&mut state, profile_data::PlayerSide::P2);
+        assert!(matches!(
+            state.workout_overlay,
+            super::select_music_menu::WorkoutOverlayState::Visible { active: true, .. }
+        ));
+    }
+
     #[test]
     fn music_select_shortcuts_defer_to_bound_player_keys() {
         let search_key = raw_key(KeyCode::KeyS, true, false);
@@ -0,0 +1,367 @@
+//! Stream/tournament layout for the spectator window.
+//!
+//! The spectator window draws its own screen from the same per-frame
+//! gameplay state: a song header, each player's notefield cropped out of the
+//! main window's frame, and live judgment counts, score and life graph beside
+//! it. The main frame arrives as render target [`GAMEPLAY_TARGET_SLOT`].
+
+use crate::act;
+use crate::assets::i18n::{LookupKey, lookup_key};
+use crate::screens::evaluation::life_record_lerp_at;
+use crate::screens::gameplay::{self, State};
+use deadlib_present::actors::{Actor, SizeSpec, SpriteSource, TextContent};
+use deadlib_present::anim::EffectState;
+use deadlib_present::color;
+use deadlib_present::density::build_graph_line_mesh;
+use deadlib_present::space::logical_height;
+use deadlib_render_core::{BlendMode, render_target_handle};
+use deadsync_core::song_time::song_time_ns_to_seconds;
+use deadsync_profile as profile_data;
+use std::sync::Arc;
+
+/// Render-target slot the main window's frame is drawn into. Song-lua
+/// ActorFrameTextures count up from zero, so the top slot never collides.
+pub const GAMEPLAY_TARGET_SLOT: u32 = u32::MAX;
+
+const HEADER_HEIGHT: f32 = 52.0;
+const PANEL_MARGIN: f32 = 12.0;
+const NOTEFIELD_CROP_SCALE: f32 = 1.2;
+const JUDGMENT_ROW_HEIGHT: f32 = 22.0;
+const LIFE_GRAPH_HEIGHT: f32 = 80.0;
+const LIFE_GRAPH_SAMPLES: usize = 100;
+const PANEL_RGBA: [f32; 4] = [16.0 / 255.0, 21.0 / 255.0, 25.0 / 255.0, 0.9];
+
+const WAITING_TEXT: LookupKey = lookup_key("Gameplay", "SpectatorWaiting");
+const LIFE_TEXT: LookupKey = lookup_key("Gameplay", "SpectatorLife");
+
+/// Where the spectator screen is drawn and what it samples.
+#[derive(Clone, Copy, Debug)]
+pub struct SpectatorView {
+    /// Logical width of the spectator window; the height is always 480.
+    pub width: f32,
+    /// Logical width of the main window whose frame fills the target slot.
+    pub gameplay_width: f32,
+}
+
+#[inline(always)]
+fn text(args: std::fmt::Arguments<'_>) -> TextContent {
+    TextContent::inline_format(args).unwrap_or_else(|| TextContent::Owned(args.to_string()))
+}
+
+/// Between songs the spectator window only shows a holding card.
+pub fn push_idle_actors(actors: &mut Vec<Actor>, view: SpectatorView) {
+    actors.push(act!(text:
+        font("miso"): settext(WAITING_TEXT.get()):
+        align(0.5, 0.5): xy(0.5 * view.width, 0.5 * logical_height()):
+        zoom(0.9): diffuse(1.0, 1.0, 1.0, 0.7):
+        z(1)
+    ));
+}
+
+pub fn push_actors(actors: &mut Vec<Actor>, state: &State, view: SpectatorView) {
+    let song = state.song();
+    let song_first = song.precise_first_second();
+    let song_last = song.precise_last_second().max(song_first + 0.001);
+    let now = song_time_ns_to_seconds(state.current_music_time_ns());
+    push_header(actors, state, view, (now / song_last).clamp(0.0, 1.0));
+
+    let players = state.num_players().clamp(1, 2);
+    let column_width = view.width / players as f32;
+    for player in 0..players {
+        push_player_column(
+            actors,
+            state,
+            view,
+            player,
+            column_width * player as f32,
+            column_width,
+            [song_first, song_last, now],
+        );
+    }
+}
+
+fn push_header(actors: &mut Vec<Actor>, state: &State, view: SpectatorView, progress: f32) {
+    let center_x = 0.5 * view.width;
+    actors.push(act!(quad:
+        align(0.0, 0.0): xy(0.0, 0.0):
+        zoomto(view.width, HEADER_HEIGHT):
+        diffuse(0.0, 0.0, 0.0, 0.85):
+        z(0)
+    ));
+    actors.push(act!(text:
+        font("miso"): settext(Arc::clone(&state.song_full_title)):
+        align(0.5, 0.5): xy(center_x, 16.0):
+        maxwidth((view.width - 32.0) / 0.9):
+        zoom(0.9): z(1)
+    ));
+    actors.push(act!(text:
+        font("miso"): settext(text(format_args!(
+            "{}  ·  {}",
+            state.gameplay_stats_text.song_artist(),
+            state.pack_group
+        ))):
+        align(0.5, 0.5): xy(center_x, 34.0):
+        maxwidth((view.width - 32.0) / 0.6):
+        zoom(0.6): diffuse(1.0, 1.0, 1.0, 0.75):
+        z(1)
+    ));
+    actors.push(act!(quad:
+        align(0.0, 0.5): xy(0.0, HEADER_HEIGHT - 2.0):
+        zoomto(view.width, 4.0):
+        diffuse(1.0, 1.0, 1.0, 0.15):
+        z(1)
+    ));
+    actors.push(act!(quad:
+        align(0.0, 0.5): xy(0.0, HEADER_HEIGHT - 2.0):
+        zoomto(view.width * progress, 4.0):
+        diffuse(1.0, 1.0, 1.0, 0.8):
+        z(2)
+    ));
+}
+
+fn push_player_column(
+    actors: &mut Vec<Actor>,
+    state: &State,
+    view: SpectatorView,
+    player: usize,
+    left: f32,
+    width: f32,
+    [song_first, song_last, now]: [f32; 3],
+) {
+    let top = HEADER_HEIGHT + PANEL_MARGIN;
+    let bottom = logical_height() - PANEL_MARGIN;
+
+    // Crop the notefield out of the main frame and scale it to the panel,
+    // keeping its aspect so arrows stay square.
+    let gameplay_width = view.gameplay_width.max(1.0);
+    let crop_width =
+        (state.notefield_width(player) * NOTEFIELD_CROP_SCALE).clamp(1.0, gameplay_width);
+    let crop_left = (state.notefield_hud_anchors(player).playfield_center_x - 0.5 * crop_width)
+        .clamp(0.0, gameplay_width - crop_width);
+    let scale = ((bottom - top) / logical_height()).min(0.5 * width / crop_width);
+    let field_size = [crop_width * scale, logical_height() * scale];
+    let field_x = left + PANEL_MARGIN;
+    actors.push(act!(quad:
+        align(0.0, 0.0): xy(field_x, top):
+        zoomto(field_size[0], field_size[1]):
+        diffuse(0.0, 0.0, 0.0, 1.0):
+        z(0)
+    ));
+    actors.push(notefield_sprite(
+        [field_x, top],
+        field_size,
+        [
+            crop_left / gameplay_width,
+            0.0,
+            (crop_left + crop_width) / gameplay_width,
+            1.0,
+        ],
+    ));
+
+    let stats_x = field_x + field_size[0] + 2.0 * PANEL_MARGIN;
+    let stats_width = (left + width - PANEL_MARGIN - stats_x).max(1.0);
+    let Some(runtime) = state.players().get(player) else {
+        return;
+    };
+    let side = gameplay::runtime_profile_side(state, player);
+    let name = state
+        .profile(player)
+        .map(|profile| profile.display_name.trim())
+        .filter(|name| !name.is_empty());
+    actors.push(act!(text:
+        font("miso"): settext(match name {
+            Some(name) => text(format_args!("{name}")),
+            None => text(format_args!("P{}", profile_data::player_side_number(side))),
+        }):
+        align(0.0, 0.5): xy(stats_x, top + 10.0):
+        maxwidth(stats_width / 0.9):
+        zoom(0.9): z(1)
+    ));
+    if let Some(chart) = state.chart(player) {
+        let rgba = color::difficulty_rgba(&chart.difficulty, state.active_color_index());
+        actors.push(act!(text:
+            font("miso"): settext(text(format_args!(
+                "{} {}",
+                color::difficulty_display_name(&chart.difficulty, false),
+                chart.meter
+            ))):
+            align(0.0, 0.5): xy(stats_x, top + 32.0):
+            maxwidth(stats_width / 0.7):
+            zoom(0.7): diffuse(rgba[0], rgba[1], rgba[2], 1.0):
+            z(1)
+        ));
+    }
+    actors.push(act!(text:
+        font("miso"): settext(text(format_args!(
+            "{:.2}%",
+            (state.display_itg_score_percent(player) * 10_000.0).floor() / 100.0
+        ))):
+        align(0.0, 0.5): xy(stats_x, top + 64.0):
+        zoom(1.6): z(1)
+    ));
+
+    let rows_top = top + 100.0;
+    for (index, (&count, rgba)) in runtime
+        .judgment_counts
+        .iter()
+        .zip(color::JUDGMENT_RGBA)
+        .enumerate()
+    {
+        let y = rows_top + JUDGMENT_ROW_HEIGHT * index as f32;
+        actors.push(act!(text:
+            font("miso"): settext(state.gameplay_stats_text.judgment(index)):
+            align(0.0, 0.5): xy(stats_x, y):
+            zoom(0.75): diffuse(rgba[0], rgba[1], rgba[2], rgba[3]):
+            z(1)
+        ));
+        actors.push(act!(text:
+            font("miso"): settext(text(format_args!("{count}"))):
+            align(1.0, 0.5): xy(stats_x + stats_width, y):
+            zoom(0.75): z(1)
+        ));
+    }
+
+    let life_top = rows_top + JUDGMENT_ROW_HEIGHT * 6.0 + 4.0;
+    actors.push(act!(text:
+        font("miso"): settext(LIFE_TEXT.get()):
+        align(0.0, 0.5): xy(stats_x, life_top):
+        zoom(0.6): diffuse(1.0, 1.0, 1.0, 0.75):
+        z(1)
+    ));
+    actors.push(act!(text:
+        font("miso"): settext(text(format_args!(
+            "{:.0}%",
+            runtime.life.clamp(0.0, 1.0) * 100.0
+        ))):
+        align(1.0, 0.5): xy(stats_x + stats_width, life_top):
+        zoom(0.6): z(1)
+    ));
+    let graph_height = LIFE_GRAPH_HEIGHT.min(bottom - life_top - 12.0).max(1.0);
+    let graph_end = runtime.fail_time.unwrap_or(now);
+    actors.push(Actor::Frame {
+        align: [0.0, 0.0],
+        offset: [stats_x, life_top + 12.0],
+        size: [SizeSpec::Px(stats_width), SizeSpec::Px(graph_height)],
+        children: life_graph(
+            &runtime.life_history,
+            [song_first, song_last, graph_end],
+            [stats_width, graph_height],
+        ),
+        background: None,
+        z: 1,
+    });
+}
+
+/// The lifeline so far, sampled across the whole song like the evaluation
+/// graph but stopping at `now`.
+fn life_graph(
+    life_history: &[(f32, f32)],
+    [song_first, song_last, now]: [f32; 3],
+    [width, height]: [f32; 2],
+) -> Vec<Actor> {
+    let mut children = vec![act!(quad:
+        align(0.0, 0.0): xy(0.0, 0.0):
+        setsize(width, height):
+        diffuse(PANEL_RGBA[0], PANEL_RGBA[1], PANEL_RGBA[2], PANEL_RGBA[3]):
+        z(0)
+    )];
+    if life_history.is_empty() || now <= song_first {
+        return children;
+    }
+    let step = (song_last - song_first) / (LIFE_GRAPH_SAMPLES - 1) as f32;
+    let mut points = [[0.0_f32; 2]; LIFE_GRAPH_SAMPLES];
+    let mut len = 0;
+    for (index, point) in points.iter_mut().enumerate() {
+        let t = (song_first + step * index as f32).min(now);
+        let life = life_record_lerp_at(life_history, song_first, t);
+        *point = [
+            (t - song_first) / (song_last - song_first) * width,
+            (1.0 - life) * height,
+        ];
+        len = index + 1;
+        if t >= now {
+            break;
+        }
+    }
+    let mesh = build_graph_line_mesh(&points[..len], 2.0, [1.0, 1.0, 1.0, 1.0]);
+    if !mesh.is_empty() {
+        children.push(Actor::Mesh {
+            align: [0.0, 0.0],
+            offset: [0.0, 0.0],
+            size: [SizeSpec::Px(width), SizeSpec::Px(height)],
+            tint: [1.0; 4],
+            vertices: mesh,
+            visible: true,
+            blend: BlendMode::Alpha,
+            z: 1,
+        });
+    }
+    children
+}
+
+fn notefield_sprite(offset: [f32; 2], size: [f32; 2], uv_rect: [f32; 4]) -> Actor {
+    Actor::Sprite {
+        align: [0.0, 0.0],
+        offset,
+        world_z: 0.0,
+        size: [SizeSpec::Px(size[0]), SizeSpec::Px(size[1])],
+        source: SpriteSource::TextureStaticHandle {
+            key: "__render_target",
+            handle: render_target_handle(GAMEPLAY_TARGET_SLOT),
+            generation: 0,
+        },
+        tint: [1.0; 4],
+        glow: [1.0, 1.0, 1.0, 0.0],
+        z: 1,
+        cell: None,
+        grid: None,
+        uv_rect: Some(uv_rect),
+        visible: true,
+        flip_x: false,
+        flip_y: false,
+        cropleft: 0.0,
+        cropright: 0.0,
+        croptop: 0.0,
+        cropbottom: 0.0,
+        fadeleft: 0.0,
+        faderight: 0.0,
+        fadetop: 0.0,
+        fadebottom: 0.0,
+        blend: BlendMode::Alpha,
+        mask_source: false,
+        mask_dest: false,
+        rot_x_deg: 0.0,
+        rot_y_deg: 0.0,
+        rot_z_deg: 0.0,
+        local_offset: [0.0, 0.0],
+        local_offset_rot_sin_cos: [0.0, 1.0],
+        texcoordvelocity: None,
+        animate: false,
+        state_delay: 0.1,
+        scale: [1.0, 1.0],
+        shadow_len: [0.0, 0.0],
+        shadow_color: [0.0, 0.0, 0.0, 0.5],
+        effect: EffectState::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn life_graph_stops_at_the_current_time() {
+        let history = [(0.0, 0.5), (50.0, 1.0), (100.0, 0.0)];
+        let children = life_graph(&history, [0.0, 100.0, 0.0], [200.0, 80.0]);
+        assert_eq!(children.len(), 1);
+        let children = life_graph(&history, [0.0, 100.0, 50.0], [200.0, 80.0]);
+        let Some(Actor::Mesh { vertices, .. }) = children.get(1) else {
+            panic!("expected a lifeline mesh");
+        };
+        let max_x = vertices
+            .iter()
+            .map(|vertex| vertex.pos[0])
+            .fold(f32::MIN, f32::max);
+        assert!(max_x <= 100.0 + 2.0, "lifeline ran past now: {max_x}");
+    }
+}
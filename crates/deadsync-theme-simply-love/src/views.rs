@@ -7,9 +7,9 @@ use std::sync::Arc;
 
 pub use deadsync_config::frame_pacing::VisibleStutterSample;
 pub use deadsync_theme::views::{
-    AudioTimingView, CourseGraphStageView, CourseStageView, CourseTypeView, DensityGraphView,
-    EvaluationView, FrameStatsSample, FrameStatsSummary, OverlayAnchor, OverlayStyle,
-    SelectedCourseView, TimingHealthView,
+    AltScoreView, AudioTimingView, CourseGraphStageView, CourseStageView, CourseTypeView,
+    DensityGraphView, EvaluationView, FrameStatsSample, FrameStatsSummary, OverlayAnchor,
+    OverlayStyle, SelectedCourseView, TimingHealthView,
 };
 
 /// Concrete evaluation view used by the Simply Love screens.
@@ -65,6 +65,9 @@ pub struct SimplyLoveVisualPolicyView {
     pub title_logo_texture_key: Option<&'static str>,
     pub srpg10_tint: bool,
     pub screen_bar: ScreenBarBackgroundView,
+    /// The active renderer draws offscreen targets, so song-lua
+    /// `ActorFrameTexture` captures can composite through a real texture.
+    pub render_targets: bool,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
@@ -92,6 +95,7 @@ impl Default for SimplyLoveVisualPolicyView {
             title_logo_texture_key: None,
             srpg10_tint: false,
             screen_bar: ScreenBarBackgroundView::Default,
+            render_targets: false,
         }
     }
 }
@@ -1102,8 +1106,12 @@ pub struct SelectMusicHistorySideView {
     pub available: bool,
     pub played_chart_counts: Vec<(String, u32)>,
     pub recent_chart_hashes: Vec<String>,
+    /// Local EX percent of the most recent plays, newest first, fails included.
+    pub recent_play_ex: Vec<(String, f64)>,
     /// Merged local/online best scores sorted by chart hash.
     pub cached_scores: Vec<(String, deadsync_score::CachedScore)>,
+    /// Best passing local EX percent sorted by chart hash.
+    pub local_ex_scores: Vec<(String, f64)>,
 }
 
 /// Cache-derived play history used by Simply Love's popularity and recent
@@ -0,0 +1,436 @@
+//! Per-profile overrides for where gameplay HUD elements are drawn.
+//!
+//! A layout only nudges what the theme already places: each element keeps
+//! its theme anchor and gets an extra offset, a zoom and a hidden flag. The
+//! file is ini-shaped, one section per element:
+//!
+//! ```ini
+//! [ErrorBar]
+//! X=0
+//! Y=-12
+//! Zoom=0.8
+//! Hidden=0
+//! ```
+
+use crate::{CounterHudStyle, ErrorBarStyle, NotefieldStyle};
+
+/// Smallest zoom any element can be shrunk to.
+pub const HUD_LAYOUT_ZOOM_MIN: f32 = 0.25;
+/// Largest zoom for elements whose style does not impose a tighter cap.
+pub const HUD_LAYOUT_ZOOM_MAX: f32 = 2.0;
+/// Vertical offsets stay within half of the 480-unit theme height.
+pub const HUD_LAYOUT_OFFSET_Y_MAX: f32 = 240.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HudElement {
+    Judgment,
+    ErrorBar,
+    MeasureCounter,
+    MiniIndicator,
+    StepStats,
+}
+
+impl HudElement {
+    pub const ALL: [Self; 5] = [
+        Self::Judgment,
+        Self::ErrorBar,
+        Self::MeasureCounter,
+        Self::MiniIndicator,
+        Self::StepStats,
+    ];
+
+    #[inline(always)]
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Section name used in the layout file.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Judgment => "Judgment",
+            Self::ErrorBar => "ErrorBar",
+            Self::MeasureCounter => "MeasureCounter",
+            Self::MiniIndicator => "MiniIndicator",
+            Self::StepStats => "StepStats",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|element| element.name().eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HudElementLayout {
+    /// Extra theme-space offset from the element's usual anchor.
+    pub offset: [f32; 2],
+    pub zoom: f32,
+    pub hidden: bool,
+}
+
+impl HudElementLayout {
+    pub const IDENTITY: Self = Self {
+        offset: [0.0; 2],
+        zoom: 1.0,
+        hidden: false,
+    };
+
+    #[inline(always)]
+    pub fn is_identity(self) -> bool {
+        self == Self::IDENTITY
+    }
+
+    #[inline(always)]
+    pub fn apply(self, xy: [f32; 2]) -> [f32; 2] {
+        [xy[0] + self.offset[0], xy[1] + self.offset[1]]
+    }
+}
+
+impl Default for HudElementLayout {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HudLayout {
+    elements: [HudElementLayout; HudElement::ALL.len()],
+}
+
+impl HudLayout {
+    pub const IDENTITY: Self = Self {
+        elements: [HudElementLayout::IDENTITY; HudElement::ALL.len()],
+    };
+
+    #[inline(always)]
+    pub const fn get(&self, element: HudElement) -> HudElementLayout {
+        self.elements[element.index()]
+    }
+
+    #[inline(always)]
+    pub fn get_mut(&mut self, element: HudElement) -> &mut HudElementLayout {
+        &mut self.elements[element.index()]
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.elements.iter().all(|element| element.is_identity())
+    }
+
+    /// Writes only elements that differ from the theme default, so an
+    /// untouched layout serializes to an empty file.
+    pub fn to_ini(&self) -> String {
+        let mut out = String::new();
+        for element in HudElement::ALL {
+            let layout = self.get(element);
+            if layout.is_identity() {
+                continue;
+            }
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&format!(
+                "[{}]\nX={}\nY={}\nZoom={}\nHidden={}\n",
+                element.name(),
+                layout.offset[0],
+                layout.offset[1],
+                layout.zoom,
+                u8::from(layout.hidden),
+            ));
+        }
+        out
+    }
+
+    /// Clamps every element into `limits`, reporting each value that moved.
+    pub fn validated(&self, limits: &HudLayoutLimits) -> (Self, Vec<HudLayoutIssue>) {
+        let mut out = *self;
+        let mut issues = Vec::new();
+        for element in HudElement::ALL {
+            let layout = out.get_mut(element);
+            let max_zoom = limits.max_zoom(element);
+            let zoom = layout.zoom.clamp(HUD_LAYOUT_ZOOM_MIN, max_zoom);
+            if zoom != layout.zoom {
+                issues.push(HudLayoutIssue::Zoom {
+                    element,
+                    requested: layout.zoom,
+                    clamped: zoom,
+                });
+                layout.zoom = zoom;
+            }
+            let offset = [
+                layout.offset[0].clamp(-limits.offset_x_max, limits.offset_x_max),
+                layout.offset[1].clamp(-HUD_LAYOUT_OFFSET_Y_MAX, HUD_LAYOUT_OFFSET_Y_MAX),
+            ];
+            if offset != layout.offset {
+                issues.push(HudLayoutIssue::Offset {
+                    element,
+                    requested: layout.offset,
+                    clamped: offset,
+                });
+                layout.offset = offset;
+            }
+        }
+        (out, issues)
+    }
+}
+
+/// Bounds a layout must respect, derived from the theme's notefield style.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HudLayoutLimits {
+    pub offset_x_max: f32,
+    pub error_bar_zoom_max: f32,
+    pub counter_zoom_max: f32,
+}
+
+impl HudLayoutLimits {
+    /// Elements may travel half of the widest layout. The error bar must
+    /// still fit in that half at its widest mode, and the counter text must
+    /// not grow past unit zoom.
+    pub fn from_style(style: &NotefieldStyle) -> Self {
+        let half_width = style.layout_width_max * 0.5;
+        let bar = &style.error_bar;
+        let widest_bar = bar
+            .colorful_width
+            .max(bar.average_width)
+            .max(bar.monochrome_width);
+        let cap = |limit: f32| {
+            if limit.is_finite() && limit > 0.0 {
+                limit.clamp(HUD_LAYOUT_ZOOM_MIN, HUD_LAYOUT_ZOOM_MAX)
+            } else {
+                HUD_LAYOUT_ZOOM_MAX
+            }
+        };
+        Self {
+            offset_x_max: half_width,
+            error_bar_zoom_max: cap(half_width / widest_bar),
+            counter_zoom_max: cap(1.0 / style.counter_hud.base_zoom),
+        }
+    }
+
+    pub const fn max_zoom(&self, element: HudElement) -> f32 {
+        match element {
+            HudElement::ErrorBar => self.error_bar_zoom_max,
+            HudElement::MeasureCounter => self.counter_zoom_max,
+            HudElement::Judgment | HudElement::MiniIndicator | HudElement::StepStats => {
+                HUD_LAYOUT_ZOOM_MAX
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HudLayoutIssue {
+    Zoom {
+        element: HudElement,
+        requested: f32,
+        clamped: f32,
+    },
+    Offset {
+        element: HudElement,
+        requested: [f32; 2],
+        clamped: [f32; 2],
+    },
+}
+
+impl std::fmt::Display for HudLayoutIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Zoom {
+                element,
+                requested,
+                clamped,
+            } => write!(
+                f,
+                "{} zoom {requested} is out of range, using {clamped}",
+                element.name()
+            ),
+            Self::Offset {
+                element,
+                requested,
+                clamped,
+            } => write!(
+                f,
+                "{} offset {},{} is out of range, using {},{}",
+                element.name(),
+                requested[0],
+                requested[1],
+                clamped[0],
+                clamped[1]
+            ),
+        }
+    }
+}
+
+/// Parses a layout file. Unknown sections or keys are errors so a typo does
+/// not silently leave an element at its default.
+pub fn parse_hud_layout(text: &str) -> Result<HudLayout, String> {
+    let mut out = HudLayout::IDENTITY;
+    let mut current = None;
+    let number = |key: &str, value: &str, line: usize| {
+        value
+            .parse::<f32>()
+            .ok()
+            .filter(|v| v.is_finite())
+            .ok_or_else(|| format!("line {line}: '{value}' is not a valid {key}"))
+    };
+    for (idx, raw) in text.lines().enumerate() {
+        let line = idx + 1;
+        let raw = raw.trim();
+        if raw.is_empty() || raw.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(section) = raw.strip_prefix('[') {
+            let Some(name) = section.strip_suffix(']') else {
+                return Err(format!("line {line}: unterminated section"));
+            };
+            let Some(element) = HudElement::from_name(name.trim()) else {
+                return Err(format!(
+                    "line {line}: unknown HUD element '{}'",
+                    name.trim()
+                ));
+            };
+            current = Some(element);
+            continue;
+        }
+        let Some(element) = current else {
+            return Err(format!("line {line}: expected [Element] before values"));
+        };
+        let Some((key, value)) = raw.split_once('=') else {
+            return Err(format!("line {line}: expected Key=Value"));
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let layout = out.get_mut(element);
+        match key.to_ascii_lowercase().as_str() {
+            "x" => layout.offset[0] = number("X", value, line)?,
+            "y" => layout.offset[1] = number("Y", value, line)?,
+            "zoom" => {
+                layout.zoom = number("Zoom", value, line)?;
+                if layout.zoom <= 0.0 {
+                    return Err(format!("line {line}: Zoom must be positive"));
+                }
+            }
+            "hidden" => {
+                layout.hidden = match value {
+                    "1" => true,
+                    "0" => false,
+                    _ if value.eq_ignore_ascii_case("true") => true,
+                    _ if value.eq_ignore_ascii_case("false") => false,
+                    _ => return Err(format!("line {line}: Hidden expects 0 or 1")),
+                };
+            }
+            _ => return Err(format!("line {line}: unknown key '{key}'")),
+        }
+    }
+    Ok(out)
+}
+
+impl CounterHudStyle {
+    /// Scales every size and spacing metric, leaving colors and layers.
+    pub fn scaled(self, zoom: f32) -> Self {
+        Self {
+            base_zoom: self.base_zoom * zoom,
+            lookahead_zoom_step: self.lookahead_zoom_step * zoom,
+            vertical_step_y: self.vertical_step_y * zoom,
+            horizontal_span: self.horizontal_span * zoom,
+            broken_y_offset: self.broken_y_offset * zoom,
+            broken_vertical_y_offset: self.broken_vertical_y_offset * zoom,
+            ..self
+        }
+    }
+}
+
+impl ErrorBarStyle {
+    /// Scales bar, tick and text geometry, leaving timings, colors and
+    /// layers.
+    pub fn scaled(self, zoom: f32) -> Self {
+        Self {
+            colorful_width: self.colorful_width * zoom,
+            colorful_height: self.colorful_height * zoom,
+            colorful_border_size: self.colorful_border_size * zoom,
+            average_width: self.average_width * zoom,
+            average_height: self.average_height * zoom,
+            average_tick_padding: self.average_tick_padding * zoom,
+            monochrome_width: self.monochrome_width * zoom,
+            monochrome_border_size: self.monochrome_border_size * zoom,
+            monochrome_center_width: self.monochrome_center_width * zoom,
+            monochrome_line_width: self.monochrome_line_width * zoom,
+            tick_width: self.tick_width * zoom,
+            average_tick_extra_height: self.average_tick_extra_height * zoom,
+            label_zoom: self.label_zoom * zoom,
+            center_tick_width: self.center_tick_width * zoom,
+            long_average_tick_extra_height: self.long_average_tick_extra_height * zoom,
+            long_average_tick_width: self.long_average_tick_width * zoom,
+            text_x_offset: self.text_x_offset * zoom,
+            text_zoom: self.text_zoom * zoom,
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: HudLayoutLimits = HudLayoutLimits {
+        offset_x_max: 427.0,
+        error_bar_zoom_max: 427.0 / 325.0,
+        counter_zoom_max: HUD_LAYOUT_ZOOM_MAX,
+    };
+
+    #[test]
+    fn parsed_layout_round_trips_through_ini() {
+        let layout = parse_hud_layout(
+            "# moved for a cabinet\n[errorbar]\nX=12\nY=-8.5\nZoom=0.75\n\n[StepStats]\nHidden=1\n",
+        )
+        .unwrap();
+        let bar = layout.get(HudElement::ErrorBar);
+        assert_eq!(bar.offset, [12.0, -8.5]);
+        assert_eq!(bar.zoom, 0.75);
+        assert!(layout.get(HudElement::StepStats).hidden);
+        assert!(layout.get(HudElement::Judgment).is_identity());
+        assert_eq!(parse_hud_layout(&layout.to_ini()), Ok(layout));
+        assert_eq!(HudLayout::IDENTITY.to_ini(), "");
+    }
+
+    #[test]
+    fn parse_rejects_unknown_names_and_bad_values() {
+        assert_eq!(
+            parse_hud_layout("[Lifebar]\nX=1"),
+            Err("line 1: unknown HUD element 'Lifebar'".to_string())
+        );
+        assert_eq!(
+            parse_hud_layout("X=1"),
+            Err("line 1: expected [Element] before values".to_string())
+        );
+        assert_eq!(
+            parse_hud_layout("[Judgment]\nZoom=0"),
+            Err("line 2: Zoom must be positive".to_string())
+        );
+        assert_eq!(
+            parse_hud_layout("[Judgment]\nSpin=1"),
+            Err("line 2: unknown key 'Spin'".to_string())
+        );
+    }
+
+    #[test]
+    fn validation_clamps_to_style_limits() {
+        let mut layout = HudLayout::IDENTITY;
+        layout.get_mut(HudElement::ErrorBar).zoom = 2.0;
+        layout.get_mut(HudElement::MiniIndicator).offset = [-900.0, 10.0];
+        let (clamped, issues) = layout.validated(&LIMITS);
+        assert_eq!(
+            clamped.get(HudElement::ErrorBar).zoom,
+            LIMITS.error_bar_zoom_max
+        );
+        assert_eq!(
+            clamped.get(HudElement::MiniIndicator).offset,
+            [-427.0, 10.0]
+        );
+        assert_eq!(issues.len(), 2);
+        assert!(
+            HudLayout::IDENTITY.validated(&LIMITS).1.is_empty(),
+            "theme defaults are always valid"
+        );
+    }
+}
@@ -1,10 +1,15 @@
 mod effect;
+mod hud_layout;
 mod runtime;
 mod screen;
 pub mod views;
 
 pub use deadlib_assets::{FontAssetSpec, TextureAssetSpec, texture_asset};
 pub use effect::{ThemeEffect, ThemeFlowEvent, ThemeInputResult};
+pub use hud_layout::{
+    HUD_LAYOUT_OFFSET_Y_MAX, HUD_LAYOUT_ZOOM_MAX, HUD_LAYOUT_ZOOM_MIN, HudElement,
+    HudElementLayout, HudLayout, HudLayoutIssue, HudLayoutLimits, parse_hud_layout,
+};
 pub use runtime::{
     AudioCut, AudioOutputModeChoice, AudioRequest, AudioVolumeTarget, DisplayModeChoice,
     FullscreenChoice, GraphicsRequest, PlatformRequest, PresentPolicyChoice, RendererChoice,
@@ -305,8 +310,9 @@ pub enum FontRole {
 mod tests {
     use super::{
         ColumnCueStyle, ColumnFlashLayoutStyle, ColumnFlashStyle, ComboFeedbackStyle,
-        CounterHudStyle, ErrorBarLayers, ErrorBarPalette, ErrorBarStyle, JudgmentFeedbackStyle,
-        MiniIndicatorStyle, NotefieldActorStyle, NotefieldStyle, ReceptorStyle,
+        CounterHudStyle, ErrorBarLayers, ErrorBarPalette, ErrorBarStyle, HudLayoutLimits,
+        JudgmentFeedbackStyle, MiniIndicatorStyle, NotefieldActorStyle, NotefieldStyle,
+        ReceptorStyle,
     };
 
     #[test]
@@ -538,5 +544,13 @@ mod tests {
         assert_eq!(style.error_bar_offset_y, 25.0);
         assert_eq!(style.actors.tap_explosion_z, 150);
         assert_eq!(style.actors.mine_core_size_ratio, 0.45);
+
+        let limits = HudLayoutLimits::from_style(&style);
+        assert_eq!(limits.offset_x_max, 427.0);
+        assert_eq!(
+            limits.error_bar_zoom_max,
+            427.0 / style.error_bar.average_width
+        );
+        assert_eq!(limits.counter_zoom_max, super::HUD_LAYOUT_ZOOM_MAX);
     }
 }
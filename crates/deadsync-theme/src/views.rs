@@ -3,6 +3,7 @@
 use crate::{DisplayModeChoice, FullscreenChoice, PresentPolicyChoice, RendererChoice};
 use deadsync_chart::{ChartData, SongData};
 use deadsync_rules::judgment::{self, JudgeGrade};
+use deadsync_rules::scoring::{AltScore, ScoringModelKind};
 use deadsync_rules::scroll::ScrollSpeedSetting;
 use deadsync_rules::timing::{
     ArrowTimingStats, HistogramMs, ScatterPoint, TimingStats, WindowCounts,
@@ -251,6 +252,15 @@ pub struct CourseGraphStageView {
     pub song_last_second: f32,
 }
 
+/// Companion score and the model that produced it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AltScoreView {
+    pub kind: ScoringModelKind,
+    /// `Name=` from the profile's custom windows; `None` for built-in models.
+    pub custom_name: Option<Arc<str>>,
+    pub score: AltScore,
+}
+
 /// Final score snapshot consumed by evaluation screens and course summaries.
 ///
 /// The concrete noteskin handle and player-side identity remain generic so
@@ -300,6 +310,8 @@ pub struct EvaluationView<N, S> {
     pub window_counts_10ms: WindowCounts,
     pub ex_score_percent: f64,
     pub hard_ex_score_percent: f64,
+    /// Companion score under the player's chosen scoring model, if any.
+    pub alt_score: Option<AltScoreView>,
     pub calories_burned: f32,
     pub column_judgments: Vec<ColumnJudgments>,
     pub noteskin: Option<N>,
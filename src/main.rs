@@ -180,6 +180,32 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::process::exit(code);
     }
 
+    let lint_packs =
+        deadsync_shell::pack_lint::cli_pack_dirs(&cli.remaining).map_err(std::io::Error::other)?;
+    if !lint_packs.is_empty() {
+        let code = deadsync_shell::pack_lint::run_cli(&lint_packs);
+        log::logger().flush();
+        std::process::exit(code);
+    }
+
+    let song_lua_packs = deadsync_shell::song_lua_scan::cli_pack_dirs(&cli.remaining)
+        .map_err(std::io::Error::other)?;
+    if !song_lua_packs.is_empty() {
+        let code = deadsync_shell::song_lua_scan::run_cli(&song_lua_packs);
+        log::logger().flush();
+        std::process::exit(code);
+    }
+
+    if let Some(replay) = deadsync_shell::frame_capture::cli_replay_options(&cli.remaining)
+        .map_err(std::io::Error::other)?
+    {
+        let code = deadsync_shell::frame_capture::run_replay_cli(&replay);
+        log::logger().flush();
+        std::process::exit(code);
+    }
+    let frame_capture = deadsync_shell::frame_capture::cli_capture_request(&cli.remaining)
+        .map_err(std::io::Error::other)?;
+
     config::load();
     let cfg = config::get();
     if let Some(case) = live_case.as_ref() {
@@ -292,5 +318,5 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         };
 
-    app::run(audio, music_clock, live_case)
+    app::run(audio, music_clock, live_case, frame_capture)
 }
@@ -295,7 +295,7 @@ fn compile_song_lua_supports_rgb_aft_fixture() {
         compiled
             .overlays
             .iter()
-            .any(|overlay| matches!(overlay.kind, SongLuaOverlayKind::ActorFrameTexture))
+            .any(|overlay| matches!(overlay.kind, SongLuaOverlayKind::ActorFrameTexture { .. }))
     );
     assert!(
         compiled